@@ -0,0 +1,333 @@
+//! Daily feed digest
+//!
+//! Once a day, at the configured hour, the new items of the configured
+//! RSS/Atom feeds are summarized by the loaded model into a "Digest" note
+//! conversation, and a desktop notification says it is ready. Items already
+//! seen are remembered in `{data_dir}/digest.json`; a feed met for the first
+//! time only contributes its items of the last day.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use thiserror::Error;
+
+use crate::agent::tools::feeds::{fetch_feed, FeedItem};
+use crate::inference::{collect_text, EngineError, InferenceBackend, SharedEngine};
+use crate::storage::conversations::{save_conversation, Conversation};
+use crate::storage::dataset::strip_thinking;
+use crate::storage::settings::load_settings;
+use crate::storage::{get_data_dir, StorageError};
+use crate::system::notifications::notify;
+use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+
+/// New items taken from one feed per digest
+const MAX_ITEMS_PER_FEED: usize = 10;
+/// Item ids remembered per feed
+const MAX_SEEN_PER_FEED: usize = 500;
+/// Wait after a failed digest before trying again
+const RETRY_AFTER: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+#[derive(Error, Debug)]
+pub enum DigestError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid digest state: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("Inference error: {0}")]
+    Engine(#[from] EngineError),
+    #[error("No model loaded")]
+    NoModel,
+    #[error("No feed could be read")]
+    NoFeed,
+    #[error("Generation failed: {0}")]
+    Generation(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigestSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Feed URLs
+    #[serde(default)]
+    pub feeds: Vec<String>,
+    /// Local hour from which the day's digest is written
+    #[serde(default = "default_hour")]
+    pub hour: u32,
+    /// Persona whose instructions become the system prompt
+    #[serde(default)]
+    pub persona: Option<String>,
+}
+
+fn default_hour() -> u32 {
+    8
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            feeds: Vec::new(),
+            hour: default_hour(),
+            persona: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DigestState {
+    #[serde(default)]
+    last_run: Option<NaiveDate>,
+    /// Item ids per feed URL, oldest first
+    #[serde(default)]
+    seen: HashMap<String, Vec<String>>,
+}
+
+fn state_path() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("digest.json"))
+}
+
+fn load_state() -> DigestState {
+    state_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &DigestState) -> Result<(), DigestError> {
+    std::fs::write(state_path()?, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Day of the last digest written
+pub fn last_run() -> Option<NaiveDate> {
+    load_state().last_run
+}
+
+/// Whether the day's digest should be written now
+pub fn is_due(now: DateTime<Local>, settings: &DigestSettings, last_run: Option<NaiveDate>) -> bool {
+    settings.enabled
+        && !settings.feeds.is_empty()
+        && now.hour() >= settings.hour
+        && last_run != Some(now.date_naive())
+}
+
+/// Items of a feed not seen yet; for a feed never seen, those of the last day
+pub fn new_items(items: &[FeedItem], seen: Option<&Vec<String>>, now: DateTime<Utc>) -> Vec<FeedItem> {
+    let fresh: Vec<FeedItem> = match seen {
+        Some(seen) => {
+            let seen: HashSet<&String> = seen.iter().collect();
+            items.iter().filter(|item| !seen.contains(&item.id)).cloned().collect()
+        }
+        None => items
+            .iter()
+            .filter(|item| item.published.is_some_and(|date| now - date < Duration::days(1)))
+            .cloned()
+            .collect(),
+    };
+    fresh.into_iter().take(MAX_ITEMS_PER_FEED).collect()
+}
+
+/// Request to the model, with every item as (feed title, item)
+pub fn digest_prompt(items: &[(String, FeedItem)]) -> String {
+    let mut prompt = String::from(
+        "Voici les nouveaux articles de mes flux RSS. Rédige un digest concis : regroupe-les par thème, résume chaque point en une ou deux phrases et garde le lien source de chacun en Markdown. Réponds dans la langue de la majorité des articles.\n",
+    );
+    for (feed, item) in items {
+        prompt.push_str(&format!("\n### {} — {}\n{}\n", feed, item.title, item.link));
+        if !item.summary.is_empty() {
+            prompt.push_str(&format!("{}\n", item.summary));
+        }
+    }
+    prompt
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static LAST_FAILURE: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+static CONVERSATIONS_ADDED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::Relaxed)
+}
+
+/// Whether a digest conversation was saved since the last call
+pub fn take_conversations_added() -> bool {
+    CONVERSATIONS_ADDED.swap(false, Ordering::Relaxed)
+}
+
+/// Write the digest if it is due and didn't just fail; called every minute
+pub async fn tick(engine: SharedEngine, settings: DigestSettings) {
+    let retry_wait = LAST_FAILURE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some_and(|failed| failed.elapsed() < RETRY_AFTER);
+    if retry_wait || !is_due(Local::now(), &settings, last_run()) {
+        return;
+    }
+    run_now(engine, settings).await;
+}
+
+/// Write the digest now, whatever the schedule, and notify the outcome
+pub async fn run_now(engine: SharedEngine, settings: DigestSettings) {
+    if RUNNING.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let result = run(&engine, &settings).await;
+    RUNNING.store(false, Ordering::Relaxed);
+
+    let is_en = load_settings().language == "en";
+    let mut last_failure = LAST_FAILURE.lock().unwrap_or_else(|e| e.into_inner());
+    match result {
+        Ok(0) => {
+            *last_failure = None;
+            tracing::info!("Digest: no new items");
+        }
+        Ok(count) => {
+            *last_failure = None;
+            tracing::info!("Digest written with {} item(s)", count);
+            let (title, body) = if is_en {
+                ("ClawRS: digest ready", format!("{} new items", count))
+            } else {
+                ("ClawRS : digest pret", format!("{} nouveaux articles", count))
+            };
+            notify(title, &body);
+        }
+        Err(e) => {
+            *last_failure = Some(Instant::now());
+            tracing::warn!("Digest failed: {}", e);
+            let title = if is_en { "ClawRS: digest failed" } else { "ClawRS : echec du digest" };
+            notify(title, &e.to_string());
+        }
+    }
+}
+
+/// Fetch the feeds, summarize the new items and save the note; returns the
+/// number of items summarized
+async fn run(engine: &SharedEngine, settings: &DigestSettings) -> Result<usize, DigestError> {
+    let mut state = load_state();
+    let now = Utc::now();
+    let mut items: Vec<(String, FeedItem)> = Vec::new();
+    let mut read_any = false;
+    for url in &settings.feeds {
+        let feed = match fetch_feed(url).await {
+            Ok(feed) => feed,
+            Err(e) => {
+                tracing::warn!("Digest: skipped {}: {}", url, e);
+                continue;
+            }
+        };
+        read_any = true;
+        let title = if feed.title.is_empty() { url.clone() } else { feed.title.clone() };
+        items.extend(
+            new_items(&feed.items, state.seen.get(url), now)
+                .into_iter()
+                .map(|item| (title.clone(), item)),
+        );
+        // Everything listed now counts as seen, summarized or not
+        let seen = state.seen.entry(url.clone()).or_default();
+        for item in feed.items.iter().rev() {
+            if !seen.contains(&item.id) {
+                seen.push(item.id.clone());
+            }
+        }
+        let excess = seen.len().saturating_sub(MAX_SEEN_PER_FEED);
+        seen.drain(..excess);
+    }
+    if !read_any {
+        return Err(DigestError::NoFeed);
+    }
+
+    if !items.is_empty() {
+        let app_settings = load_settings();
+        let mut messages = Vec::new();
+        if let Some(persona) = settings.persona.as_deref().and_then(|id| app_settings.persona(id)) {
+            messages.push(ChatMessage::new(
+                ChatRole::System,
+                persona.instructions(app_settings.language == "en"),
+            ));
+        }
+        let prompt = digest_prompt(&items);
+        messages.push(ChatMessage::new(ChatRole::User, prompt.clone()));
+
+        // The lock is only held to start the generation, like the chat loop
+        let rx = {
+            let engine = engine.lock().await;
+            if !engine.is_model_loaded() {
+                return Err(DigestError::NoModel);
+            }
+            engine.generate_stream_messages(messages, app_settings.generation_params())?.0
+        };
+        let output = tokio::task::spawn_blocking(move || collect_text(rx))
+            .await
+            .map_err(|e| DigestError::Generation(e.to_string()))?
+            .map_err(DigestError::Generation)?;
+
+        let mut conversation = Conversation::new(Some(ChatMessage::new(ChatRole::User, prompt)));
+        conversation.add_message(ChatMessage::new(
+            ChatRole::Assistant,
+            strip_thinking(&output).trim().to_string(),
+        ));
+        conversation.title = format!("Digest {}", Local::now().format("%Y-%m-%d"));
+        conversation.persona = settings.persona.clone();
+        save_conversation(&conversation)?;
+        CONVERSATIONS_ADDED.store(true, Ordering::Relaxed);
+    }
+
+    state.last_run = Some(Local::now().date_naive());
+    save_state(&state)?;
+    Ok(items.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn item(id: &str, hours_ago: Option<i64>, now: DateTime<Utc>) -> FeedItem {
+        FeedItem {
+            id: id.to_string(),
+            title: id.to_string(),
+            link: format!("https://example.com/{}", id),
+            published: hours_ago.map(|h| now - Duration::hours(h)),
+            summary: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let settings = DigestSettings {
+            enabled: true,
+            feeds: vec!["https://example.com/feed".to_string()],
+            ..Default::default()
+        };
+        let morning = Local.with_ymd_and_hms(2026, 10, 15, 9, 0, 0).unwrap();
+        let dawn = Local.with_ymd_and_hms(2026, 10, 15, 6, 0, 0).unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2026, 10, 14);
+        assert!(is_due(morning, &settings, yesterday));
+        assert!(is_due(morning, &settings, None));
+        assert!(!is_due(dawn, &settings, yesterday));
+        assert!(!is_due(morning, &settings, morning.date_naive().into()));
+        let disabled = DigestSettings { enabled: false, ..settings };
+        assert!(!is_due(morning, &disabled, yesterday));
+    }
+
+    #[test]
+    fn test_new_items() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 15, 8, 0, 0).unwrap();
+        let items = vec![item("a", Some(2), now), item("b", Some(30), now), item("c", None, now)];
+        // First time: only the last day's dated items
+        let first: Vec<String> = new_items(&items, None, now).into_iter().map(|i| i.id).collect();
+        assert_eq!(first, vec!["a"]);
+        let seen = vec!["a".to_string(), "b".to_string()];
+        let next: Vec<String> = new_items(&items, Some(&seen), now).into_iter().map(|i| i.id).collect();
+        assert_eq!(next, vec!["c"]);
+        assert!(digest_prompt(&[("Blog".to_string(), items[0].clone())]).contains("### Blog — a\nhttps://example.com/a\n"));
+    }
+}
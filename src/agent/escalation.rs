@@ -0,0 +1,363 @@
+//! Cloud escalation ("phone a friend")
+//!
+//! Decides when the local agent may hand a question to a remote model via
+//! `ai_consult`, which provider and model to use, and what the consent
+//! dialog shows before any data leaves the machine. Spending is tracked in
+//! `storage::escalation_log`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Remote provider used for escalation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationProvider {
+    OpenRouter,
+    OpenAi,
+}
+
+impl EscalationProvider {
+    /// Display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OpenRouter => "OpenRouter",
+            Self::OpenAi => "OpenAI",
+        }
+    }
+
+    /// Chat completions endpoint
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            Self::OpenRouter => "https://openrouter.ai/api/v1/chat/completions",
+            Self::OpenAi => "https://api.openai.com/v1/chat/completions",
+        }
+    }
+
+    /// Environment variable holding the API key
+    pub fn api_key_env(&self) -> &'static str {
+        match self {
+            Self::OpenRouter => "OPENROUTER_API_KEY",
+            Self::OpenAi => "OPENAI_API_KEY",
+        }
+    }
+
+    /// Map a configured model id onto one this provider accepts
+    pub fn resolve_model(&self, model: &str) -> String {
+        match self {
+            Self::OpenRouter => model.to_string(),
+            Self::OpenAi => match model.strip_prefix("openai/") {
+                Some(name) => name.to_string(),
+                // OpenRouter-style ids from another vendor can't be served
+                None if model.contains('/') || model.is_empty() => "gpt-4o-mini".to_string(),
+                None => model.to_string(),
+            },
+        }
+    }
+
+    /// Models offered in the settings picker
+    pub fn popular_models(&self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            Self::OpenRouter => crate::agent::tools::openrouter::get_popular_models(),
+            Self::OpenAi => vec![
+                ("gpt-4o-mini", "GPT-4o Mini"),
+                ("gpt-4o", "GPT-4o"),
+                ("o3-mini", "o3-mini"),
+            ],
+        }
+    }
+}
+
+/// Why an escalation is allowed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationReason {
+    /// The user explicitly asked for an external model
+    UserRequest,
+    /// The agent failed this many iterations in a row
+    RepeatedFailures(usize),
+    /// The request matched a configured task type
+    TaskType(String),
+    /// The local model decided on its own
+    ModelInitiated,
+}
+
+impl EscalationReason {
+    /// Short bilingual label for the consent dialog and logs
+    pub fn label(&self, is_en: bool) -> String {
+        match (self, is_en) {
+            (Self::UserRequest, true) => "requested by you".to_string(),
+            (Self::UserRequest, false) => "demande par vous".to_string(),
+            (Self::RepeatedFailures(n), true) => format!("{} failed iterations", n),
+            (Self::RepeatedFailures(n), false) => format!("{} iterations echouees", n),
+            (Self::TaskType(t), true) => format!("task type: {}", t),
+            (Self::TaskType(t), false) => format!("type de tache : {}", t),
+            (Self::ModelInitiated, true) => "local model's own decision".to_string(),
+            (Self::ModelInitiated, false) => "decision du modele local".to_string(),
+        }
+    }
+}
+
+/// Escalation policy
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EscalationPolicy {
+    /// Master switch; when off `ai_consult` is always refused
+    pub enabled: bool,
+    /// Remote provider
+    pub provider: EscalationProvider,
+    /// Allow escalation when the user asks for it
+    pub on_user_request: bool,
+    /// Suggest escalation after this many consecutive failures (0 = never)
+    pub after_failed_iterations: usize,
+    /// Keywords that mark a task type as worth escalating (e.g. "proof", "legal")
+    pub task_types: Vec<String>,
+    /// Let the local model call `ai_consult` without any trigger
+    pub allow_model_initiated: bool,
+    /// Monthly spending limit in USD (0 = unlimited)
+    pub monthly_budget_usd: f64,
+}
+
+impl Default for EscalationPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            provider: EscalationProvider::OpenRouter,
+            on_user_request: true,
+            after_failed_iterations: 3,
+            task_types: Vec::new(),
+            allow_model_initiated: true,
+            monthly_budget_usd: 0.0,
+        }
+    }
+}
+
+/// Phrases that count as the user asking for an external model
+const USER_REQUEST_MARKERS: &[&str] = &[
+    "ai_consult",
+    "phone a friend",
+    "ask a stronger model",
+    "ask a bigger model",
+    "ask a more powerful",
+    "ask an external model",
+    "use openrouter",
+    "modele plus puissant",
+    "modèle plus puissant",
+    "modele externe",
+    "modèle externe",
+    "demande a un autre modele",
+    "demande à un autre modèle",
+];
+
+impl EscalationPolicy {
+    /// Trigger found in the user's message when a run starts
+    pub fn trigger_for_request(&self, user_message: &str) -> Option<EscalationReason> {
+        if !self.enabled {
+            return None;
+        }
+        let lower = user_message.to_lowercase();
+
+        if self.on_user_request && USER_REQUEST_MARKERS.iter().any(|m| lower.contains(m)) {
+            return Some(EscalationReason::UserRequest);
+        }
+
+        self.task_types
+            .iter()
+            .map(|t| t.trim())
+            .find(|t| !t.is_empty() && lower.contains(&t.to_lowercase()))
+            .map(|t| EscalationReason::TaskType(t.to_string()))
+    }
+
+    /// Trigger reached after `consecutive_errors` failures
+    pub fn trigger_for_failures(&self, consecutive_errors: usize) -> Option<EscalationReason> {
+        (self.enabled
+            && self.after_failed_iterations > 0
+            && consecutive_errors >= self.after_failed_iterations)
+            .then_some(EscalationReason::RepeatedFailures(consecutive_errors))
+    }
+
+    /// Decide whether an `ai_consult` call may go ahead
+    pub fn authorize(
+        &self,
+        active: Option<&EscalationReason>,
+        month_spend_usd: f64,
+    ) -> Result<EscalationReason, EscalationDenied> {
+        if !self.enabled {
+            return Err(EscalationDenied::Disabled);
+        }
+        if self.monthly_budget_usd > 0.0 && month_spend_usd >= self.monthly_budget_usd {
+            return Err(EscalationDenied::BudgetExhausted {
+                spent: month_spend_usd,
+                budget: self.monthly_budget_usd,
+            });
+        }
+        match active {
+            Some(reason) => Ok(reason.clone()),
+            None if self.allow_model_initiated => Ok(EscalationReason::ModelInitiated),
+            None => Err(EscalationDenied::NoTrigger),
+        }
+    }
+}
+
+/// Why an `ai_consult` call was refused before reaching the user
+#[derive(Clone, Debug, PartialEq)]
+pub enum EscalationDenied {
+    Disabled,
+    NoTrigger,
+    BudgetExhausted { spent: f64, budget: f64 },
+}
+
+impl EscalationDenied {
+    /// Explanation injected for the local model
+    pub fn system_note(&self) -> String {
+        match self {
+            Self::Disabled => {
+                "L'escalade vers un modèle externe (ai_consult) est désactivée. Continue avec les outils locaux.".to_string()
+            }
+            Self::NoTrigger => {
+                "ai_consult n'est autorisé que sur demande de l'utilisateur ou après plusieurs échecs. Continue avec les outils locaux.".to_string()
+            }
+            Self::BudgetExhausted { spent, budget } => format!(
+                "Budget mensuel d'escalade atteint ({:.2} $ / {:.2} $). ai_consult est indisponible, continue localement.",
+                spent, budget
+            ),
+        }
+    }
+}
+
+/// Everything the consent dialog shows before data leaves the machine
+#[derive(Clone, Debug, PartialEq)]
+pub struct EscalationPreview {
+    pub provider: EscalationProvider,
+    pub model: String,
+    pub reason: EscalationReason,
+    /// Exact text sent to the provider
+    pub outgoing_text: String,
+    pub estimated_tokens: usize,
+    /// `None` when the model's price is unknown
+    pub estimated_cost_usd: Option<f64>,
+}
+
+impl EscalationPreview {
+    /// Build the preview for an `ai_consult` call
+    pub fn new(provider: EscalationProvider, model: &str, reason: EscalationReason, params: &Value) -> Self {
+        let question = params.get("question").and_then(|v| v.as_str()).unwrap_or_default();
+        let outgoing_text = match params.get("context").and_then(|v| v.as_str()) {
+            Some(ctx) if !ctx.trim().is_empty() => format!("Question: {}\n\nContext:\n{}", question, ctx),
+            _ => question.to_string(),
+        };
+        let max_tokens = params.get("max_tokens").and_then(|v| v.as_u64()).unwrap_or(1024) as usize;
+        let estimated_tokens = estimate_tokens(&outgoing_text);
+        let estimated_cost_usd = model_pricing(model)
+            .map(|(input, output)| price(input, output, estimated_tokens, max_tokens));
+
+        Self {
+            provider,
+            model: model.to_string(),
+            reason,
+            outgoing_text,
+            estimated_tokens,
+            estimated_cost_usd,
+        }
+    }
+}
+
+/// Rough token count (~4 characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// USD per million (input, output) tokens for known models
+pub fn model_pricing(model: &str) -> Option<(f64, f64)> {
+    if model.ends_with(":free") || model == crate::agent::tools::openrouter::DEFAULT_MODEL {
+        return Some((0.0, 0.0));
+    }
+    let name = model.rsplit('/').next().unwrap_or(model);
+    match name {
+        "gpt-4o" => Some((2.5, 10.0)),
+        "gpt-4o-mini" => Some((0.15, 0.6)),
+        "o3-mini" => Some((1.1, 4.4)),
+        "claude-3.5-sonnet" => Some((3.0, 15.0)),
+        "claude-3-opus" => Some((15.0, 75.0)),
+        "deepseek-chat" => Some((0.27, 1.1)),
+        _ => None,
+    }
+}
+
+/// Cost in USD for a call with the given token counts
+pub fn price(input_per_m: f64, output_per_m: f64, prompt_tokens: usize, completion_tokens: usize) -> f64 {
+    (prompt_tokens as f64 * input_per_m + completion_tokens as f64 * output_per_m) / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_triggers() {
+        let mut policy = EscalationPolicy {
+            task_types: vec!["preuve".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.trigger_for_request("Peux-tu demander à un modèle plus puissant ?"),
+            Some(EscalationReason::UserRequest)
+        );
+        assert_eq!(
+            policy.trigger_for_request("Écris une PREUVE du théorème"),
+            Some(EscalationReason::TaskType("preuve".to_string()))
+        );
+        assert_eq!(policy.trigger_for_request("Liste mes fichiers"), None);
+        assert_eq!(policy.trigger_for_failures(2), None);
+        assert_eq!(policy.trigger_for_failures(3), Some(EscalationReason::RepeatedFailures(3)));
+
+        policy.enabled = false;
+        assert_eq!(policy.trigger_for_request("phone a friend"), None);
+        assert_eq!(policy.trigger_for_failures(10), None);
+    }
+
+    #[test]
+    fn test_authorize() {
+        let mut policy = EscalationPolicy::default();
+        assert_eq!(policy.authorize(None, 0.0), Ok(EscalationReason::ModelInitiated));
+
+        policy.allow_model_initiated = false;
+        assert_eq!(policy.authorize(None, 0.0), Err(EscalationDenied::NoTrigger));
+        let reason = EscalationReason::UserRequest;
+        assert_eq!(policy.authorize(Some(&reason), 0.0), Ok(reason.clone()));
+
+        policy.monthly_budget_usd = 1.0;
+        assert!(matches!(
+            policy.authorize(Some(&reason), 1.2),
+            Err(EscalationDenied::BudgetExhausted { .. })
+        ));
+
+        policy.enabled = false;
+        assert_eq!(policy.authorize(Some(&reason), 0.0), Err(EscalationDenied::Disabled));
+    }
+
+    #[test]
+    fn test_preview_cost() {
+        let params = json!({"question": "abcd", "context": "efgh", "max_tokens": 1000});
+        let preview = EscalationPreview::new(
+            EscalationProvider::OpenAi,
+            "gpt-4o-mini",
+            EscalationReason::UserRequest,
+            &params,
+        );
+        assert!(preview.outgoing_text.contains("efgh"));
+        assert_eq!(preview.estimated_tokens, estimate_tokens(&preview.outgoing_text));
+        assert!(preview.estimated_cost_usd.unwrap() > 0.0);
+
+        let free = EscalationPreview::new(
+            EscalationProvider::OpenRouter,
+            "google/gemma-2-9b-it:free",
+            EscalationReason::UserRequest,
+            &params,
+        );
+        assert_eq!(free.estimated_cost_usd, Some(0.0));
+        assert_eq!(model_pricing("unknown/model"), None);
+        assert_eq!(EscalationProvider::OpenAi.resolve_model("openai/gpt-4o"), "gpt-4o");
+        assert_eq!(EscalationProvider::OpenAi.resolve_model("openrouter/pony-alpha"), "gpt-4o-mini");
+    }
+}
@@ -0,0 +1,81 @@
+//! Focus mode
+//!
+//! For a set time (an exam, offline work), network tools disappear from the
+//! tool registry, so the model neither sees nor runs them, and desktop
+//! notifications are queued until it ends (see
+//! [`notifications::flush_held`](crate::system::notifications::flush_held)).
+//! The end time is kept here for the registry and in the settings so a
+//! restart doesn't end it early.
+
+use crate::agent::get_tool_permission;
+use crate::agent::permissions::PermissionLevel;
+use crate::agent::skills::{self, SkillPermission};
+use crate::types::message::unix_now;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Durations offered in the header, in minutes
+pub const FOCUS_DURATIONS: [u64; 4] = [25, 50, 90, 180];
+
+/// End of focus mode in Unix seconds; 0 when off
+static FOCUS_UNTIL: AtomicU64 = AtomicU64::new(0);
+
+/// Set or clear the end of focus mode (Unix seconds)
+pub fn set_focus_until(until: Option<u64>) {
+    FOCUS_UNTIL.store(until.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Seconds of focus mode left; `None` when off or over
+pub fn focus_remaining() -> Option<u64> {
+    remaining_at(FOCUS_UNTIL.load(Ordering::Relaxed), unix_now())
+}
+
+fn remaining_at(until: u64, now: u64) -> Option<u64> {
+    (until > now).then(|| until - now)
+}
+
+pub fn is_focus_active() -> bool {
+    focus_remaining().is_some()
+}
+
+/// Whether focus mode hides this tool: anything reaching the network,
+/// including skills that declare network access among other permissions
+pub fn blocks_tool(name: &str) -> bool {
+    blocks_tool_at(name, FOCUS_UNTIL.load(Ordering::Relaxed), unix_now())
+}
+
+fn blocks_tool_at(name: &str, until: u64, now: u64) -> bool {
+    remaining_at(until, now).is_some()
+        && (get_tool_permission(name) == PermissionLevel::Network
+            || skills::declares(name, SkillPermission::Network))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The clock and end time are passed in: the global end time is read by
+    // registry tests running in parallel
+    #[test]
+    fn test_focus_blocks_network_tools() {
+        let now = 1_000;
+        let until = now + 60;
+        assert_eq!(remaining_at(until, now), Some(60));
+        assert!(blocks_tool_at("web_search", until, now));
+        assert!(blocks_tool_at("mcp_github_list_repos", until, now));
+        assert!(!blocks_tool_at("file_read", until, now));
+
+        // Over: nothing blocked, without anyone turning it off
+        assert_eq!(remaining_at(now - 1, now), None);
+        assert!(!blocks_tool_at("web_search", now - 1, now));
+        assert_eq!(remaining_at(0, now), None);
+    }
+
+    #[test]
+    fn test_focus_blocks_network_skills() {
+        let content = "---\nname: focus_probe\npermissions: [network, shell]\n---\n";
+        let skill = skills::parse_skill(content, "/nonexistent/focus_probe".into()).unwrap();
+        skills::record_permissions(&skill);
+        assert!(blocks_tool_at("skill_focus_probe", 1_060, 1_000));
+        skills::forget_permissions("skill_focus_probe");
+    }
+}
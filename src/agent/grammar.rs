@@ -0,0 +1,86 @@
+//! GBNF grammar constraining a reply to text or a valid tool call
+//!
+//! Used on iterations where the model is expected to call a tool (after a
+//! reflection prompt, or a malformed call). A reply starting with `{` or a
+//! code fence must be a well-formed `{"tool": ..., "params": {...}}` object
+//! naming a registered tool (fenced or bare); anything else is free text.
+//! Generated from the tool list so the names never drift from the registry.
+
+use crate::agent::tools::ToolInfo;
+
+/// Rule the grammar starts from
+pub const GRAMMAR_ROOT: &str = "root";
+
+/// Generic JSON, adapted from llama.cpp's `json.gbnf`
+const JSON_RULES: &str = r#"value ::= object | array | string | number | ("true" | "false" | "null") ws
+object ::= "{" ws ( string ":" ws value ( "," ws string ":" ws value )* )? "}" ws
+array ::= "[" ws ( value ( "," ws value )* )? "]" ws
+string ::= "\"" ( [^"\\\x7F\x00-\x1F] | "\\" ( ["\\/bfnrt] | "u" [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] ) )* "\"" ws
+number ::= "-"? ( [0-9] | [1-9] [0-9]* ) ( "." [0-9]+ )? ( [eE] [-+]? [0-9]+ )? ws
+ws ::= ( [ \t\n] ws )?
+"#;
+
+/// `s` as a GBNF string literal matching the JSON string `"s"`
+fn json_string_literal(s: &str) -> String {
+    let json = serde_json::to_string(s).unwrap_or_default();
+    let mut literal = String::from("\"");
+    for c in json.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Grammar accepting free text or a call to one of `tools`
+pub fn tool_call_grammar(tools: &[ToolInfo]) -> String {
+    let mut names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    names.sort_unstable();
+    names.dedup();
+    if names.is_empty() {
+        // No tool to call: text only
+        return format!("{} ::= text\ntext ::= [^\\x00]*\n", GRAMMAR_ROOT);
+    }
+    let tool_names = names.iter().map(|name| json_string_literal(name)).collect::<Vec<_>>().join(" | ");
+
+    format!(
+        r#"{root} ::= ws ( call | "```" [a-z]* ws call "```" | text )
+call ::= "{{" ws "\"tool\"" ws ":" ws tool-name ws "," ws "\"params\"" ws ":" ws object "}}" ws
+tool-name ::= {tool_names}
+text ::= ( [^{{` \t\n\r] | "`" [^`] | "``" [^`] ) [^\x00]*
+{JSON_RULES}"#,
+        root = GRAMMAR_ROOT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool(name: &str) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: String::new(),
+            description_fr: None,
+            parameters_schema: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_grammar_lists_registered_tools() {
+        let grammar = tool_call_grammar(&[tool("web_search"), tool("file_read"), tool("web_search")]);
+        assert!(grammar.starts_with("root ::= "));
+        assert!(grammar.contains(r#"tool-name ::= "\"file_read\"" | "\"web_search\"""#));
+        assert!(grammar.contains("object ::= "));
+        assert_eq!(tool_call_grammar(&[]), "root ::= text\ntext ::= [^\\x00]*\n");
+    }
+
+    #[test]
+    fn test_json_string_literal_escapes() {
+        assert_eq!(json_string_literal("a\"b"), r#""\"a\\\"b\"""#);
+    }
+}
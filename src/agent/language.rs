@@ -0,0 +1,70 @@
+//! Response language
+//!
+//! A conversation can pin the language the agent answers in, whatever the
+//! UI language or the language of the user's messages. The same choice
+//! drives the loop's own status lines (tool in use, permission prompts,
+//! errors), which exist in French and English only.
+
+/// Languages offered per conversation, as (code, name in that language)
+pub const RESPONSE_LANGUAGES: &[(&str, &str)] = &[
+    ("fr", "Français"),
+    ("en", "English"),
+    ("de", "Deutsch"),
+    ("es", "Español"),
+    ("it", "Italiano"),
+    ("pt", "Português"),
+    ("nl", "Nederlands"),
+    ("pl", "Polski"),
+    ("ja", "日本語"),
+    ("zh", "中文"),
+];
+
+/// Name of a language code, in that language
+pub fn language_name(code: &str) -> Option<&'static str> {
+    RESPONSE_LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+/// System prompt section pinning the response language; `None` for auto
+pub fn language_directive(code: Option<&str>) -> Option<String> {
+    let name = language_name(code?)?;
+    Some(format!(
+        "## Langue de réponse\nRéponds toujours en {} ({}), même si l'utilisateur, les documents ou les résultats d'outils utilisent une autre langue. Les noms d'outils, le code et les citations restent tels quels.",
+        name,
+        code?
+    ))
+}
+
+/// Whether the loop's status lines are in English: the conversation's
+/// language when pinned (English for any language but French), otherwise
+/// the UI language
+pub fn status_in_english(conversation: Option<&str>, ui_language: &str) -> bool {
+    match conversation.filter(|code| language_name(code).is_some()) {
+        Some(code) => code != "fr",
+        None => ui_language == "en",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_directive() {
+        assert_eq!(language_directive(None), None);
+        assert_eq!(language_directive(Some("xx")), None);
+        let directive = language_directive(Some("de")).unwrap();
+        assert!(directive.contains("Deutsch (de)"));
+    }
+
+    #[test]
+    fn test_status_in_english() {
+        assert!(status_in_english(None, "en"));
+        assert!(!status_in_english(None, "fr"));
+        assert!(!status_in_english(Some("fr"), "en"));
+        assert!(status_in_english(Some("de"), "fr"));
+        assert!(!status_in_english(Some("xx"), "fr"));
+    }
+}
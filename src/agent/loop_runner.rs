@@ -8,6 +8,7 @@
 //! - Dynamic planning with TODO lists
 //! - Configurable iteration limits
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
@@ -18,6 +19,8 @@ use uuid::Uuid;
 use crate::agent::tools::{ToolRegistry, ToolResult, ToolError};
 use crate::agent::planning::{TaskPlan, TaskStatus, PlanManager};
 use crate::agent::runner::{ToolCall, extract_tool_call};
+use crate::agent::permissions::PermissionLevel;
+use crate::agent::get_tool_permission;
 
 /// Agent loop configuration
 #[derive(Clone, Debug)]
@@ -38,6 +41,8 @@ pub struct AgentLoopConfig {
     pub enable_retry: bool,
     /// Maximum retries per tool call
     pub max_retries: usize,
+    /// When and how often reflection prompts are injected
+    pub reflection: ReflectionPolicy,
 }
 
 impl Default for AgentLoopConfig {
@@ -51,6 +56,67 @@ impl Default for AgentLoopConfig {
             min_iteration_delay_ms: 100,
             enable_retry: true,
             max_retries: 2,
+            reflection: ReflectionPolicy::default(),
+        }
+    }
+}
+
+/// When a reflection prompt is injected after a tool call
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReflectionTrigger {
+    /// Never reflect; errors are passed back to the model as-is
+    Never,
+    /// Reflect after failed tool calls
+    OnFailure,
+    /// Reflect after every tool call
+    Always,
+}
+
+/// Reflection policy for the agent loop
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReflectionPolicy {
+    /// When to inject the reflection prompt
+    pub trigger: ReflectionTrigger,
+    /// Consecutive tool failures the agent may recover from before it must conclude
+    pub max_recoveries: usize,
+    /// Also reflect after successful tool calls once the task runs long
+    pub reflect_on_long_tasks: bool,
+    /// Iteration from which a task counts as long
+    pub long_task_iterations: usize,
+}
+
+impl Default for ReflectionPolicy {
+    fn default() -> Self {
+        Self {
+            trigger: ReflectionTrigger::OnFailure,
+            max_recoveries: 3,
+            reflect_on_long_tasks: false,
+            long_task_iterations: 8,
+        }
+    }
+}
+
+impl ReflectionPolicy {
+    /// Whether the agent may try again after `consecutive_errors` failures
+    pub fn can_recover(&self, consecutive_errors: usize) -> bool {
+        consecutive_errors <= self.max_recoveries
+    }
+
+    /// Whether a failed tool call gets a reflection prompt
+    pub fn reflect_after_failure(&self, consecutive_errors: usize) -> bool {
+        self.trigger != ReflectionTrigger::Never && self.can_recover(consecutive_errors)
+    }
+
+    /// Whether a successful tool call at `iteration` gets a reflection prompt
+    pub fn reflect_after_success(&self, iteration: usize) -> bool {
+        match self.trigger {
+            ReflectionTrigger::Never => false,
+            ReflectionTrigger::Always => true,
+            ReflectionTrigger::OnFailure => {
+                self.reflect_on_long_tasks && iteration >= self.long_task_iterations
+            }
         }
     }
 }
@@ -143,6 +209,8 @@ pub struct AgentContext {
     pub last_response: Option<String>,
     /// Detected patterns (for loop detection)
     pub detected_patterns: Vec<String>,
+    /// Results of read-only tool calls this run, keyed by `tool_call_key`
+    pub result_cache: HashMap<u64, ToolResult>,
 }
 
 impl AgentContext {
@@ -158,19 +226,45 @@ impl AgentContext {
             thinking_log: Vec::new(),
             last_response: None,
             detected_patterns: Vec::new(),
+            result_cache: HashMap::new(),
         }
     }
     
+    /// Previous result of an identical read-only tool call in this run
+    pub fn cached_result(&self, tool: &str, params: &Value) -> Option<&ToolResult> {
+        if !is_cacheable_tool(tool) {
+            return None;
+        }
+        self.result_cache.get(&tool_call_key(tool, params))
+    }
+
+    /// Remember a tool result so identical calls can be short-circuited.
+    ///
+    /// Tools with side effects invalidate the whole cache, since a file
+    /// read before a write may no longer be accurate.
+    pub fn record_result(&mut self, tool: &str, params: &Value, result: &ToolResult) {
+        if is_cacheable_tool(tool) {
+            if result.success {
+                self.result_cache.insert(tool_call_key(tool, params), result.clone());
+            }
+        } else if !matches!(
+            get_tool_permission(tool),
+            PermissionLevel::ReadOnly | PermissionLevel::Network
+        ) {
+            self.result_cache.clear();
+        }
+    }
+
     /// Check if we're stuck in a loop (repeated tool calls, text patterns, or no progress)
     pub fn is_stuck(&self) -> bool {
         // Check last 3 tool calls for repetition
         if self.tool_history.len() >= 3 {
             let last_three: Vec<_> = self.tool_history.iter().rev().take(3).collect();
-            let first = &last_three[0];
-            if last_three.iter().all(|entry| {
-                entry.tool_name == first.tool_name && 
-                entry.params.to_string() == first.params.to_string()
-            }) {
+            let first = tool_call_key(&last_three[0].tool_name, &last_three[0].params);
+            if last_three
+                .iter()
+                .all(|entry| tool_call_key(&entry.tool_name, &entry.params) == first)
+            {
                 tracing::warn!("Stuck: repeated tool calls detected");
                 return true;
             }
@@ -226,6 +320,40 @@ impl AgentContext {
     }
 }
 
+/// Hash identifying a tool call by name and parameters
+///
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is the same in every build,
+/// since the persistent tool cache names its files after it.
+pub fn tool_call_key(tool: &str, params: &Value) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    // serde_json objects are sorted maps, so key order doesn't matter
+    let params = params.to_string();
+    let bytes = tool.bytes().chain([0]).chain(params.bytes());
+    bytes.fold(OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+/// Whether repeated calls to a tool can reuse the earlier result.
+///
+/// Only tools without side effects qualify; polling tools and the
+/// scratchpad tools are expected to be called repeatedly. Skills other than
+/// `skill_list` may run a script or fetch something new on every call.
+pub fn is_cacheable_tool(tool: &str) -> bool {
+    if matches!(
+        tool,
+        "think" | "todo_write" | "deep_research_check" | "process_list" | "system_info"
+    ) {
+        return false;
+    }
+    if tool.starts_with("skill_") && tool != "skill_list" {
+        return false;
+    }
+    matches!(
+        get_tool_permission(tool),
+        PermissionLevel::ReadOnly | PermissionLevel::Network
+    )
+}
+
 /// Entry in tool call history
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ToolHistoryEntry {
@@ -338,6 +466,7 @@ impl AgentLoop {
     ) -> Result<ToolResult, ToolError> {
         let tool = self.tool_registry.get(&tool_call.tool)
             .ok_or_else(|| ToolError::NotFound(tool_call.tool.clone()))?;
+        let params = self.tool_registry.validate_params(&tool_call.tool, &tool_call.params)?;
         
         let mut retry_count = 0;
         let max_retries = if self.config.enable_retry { self.config.max_retries } else { 0 };
@@ -347,17 +476,17 @@ impl AgentLoop {
             
             let _ = event_tx.send(AgentEvent::ToolCallStarted {
                 tool: tool_call.tool.clone(),
-                params: tool_call.params.clone(),
+                params: params.clone(),
             }).await;
             
-            match tool.execute(tool_call.params.clone()).await {
+            match tool.execute(params.clone()).await {
                 Ok(result) => {
                     let duration_ms = start.elapsed().as_millis() as u64;
                     
                     // Record in history
                     ctx.tool_history.push(ToolHistoryEntry {
                         tool_name: tool_call.tool.clone(),
-                        params: tool_call.params.clone(),
+                        params: params.clone(),
                         result: Some(result.clone()),
                         error: None,
                         timestamp: std::time::SystemTime::now()
@@ -387,7 +516,7 @@ impl AgentLoop {
                         // Record failure in history
                         ctx.tool_history.push(ToolHistoryEntry {
                             tool_name: tool_call.tool.clone(),
-                            params: tool_call.params.clone(),
+                            params: params.clone(),
                             result: None,
                             error: Some(e.to_string()),
                             timestamp: std::time::SystemTime::now()
@@ -546,4 +675,74 @@ mod tests {
         
         assert!(ctx.is_stuck());
     }
+
+    #[test]
+    fn test_tool_call_key_is_stable() {
+        // Persisted cache file names depend on this exact value
+        let key = tool_call_key("web_search", &serde_json::json!({ "query": "rust" }));
+        assert_eq!(key, 0x8486_0cd3_5190_dd0e);
+        assert_ne!(key, tool_call_key("web_searc", &serde_json::json!({ "hquery": "rust" })));
+    }
+
+    #[test]
+    fn test_duplicate_call_cache() {
+        let mut ctx = AgentContext::new();
+        let params = serde_json::json!({"path": "a.txt", "limit": 10});
+        let reordered = serde_json::json!({"limit": 10, "path": "a.txt"});
+        let result = ToolResult {
+            success: true,
+            data: Value::Null,
+            message: "contenu".to_string(),
+        };
+
+        ctx.record_result("file_read", &params, &result);
+        assert!(ctx.cached_result("file_read", &reordered).is_some());
+        assert!(ctx.cached_result("file_read", &serde_json::json!({"path": "b.txt"})).is_none());
+
+        // Scratchpad tools are never short-circuited
+        ctx.record_result("think", &params, &result);
+        assert!(ctx.cached_result("think", &params).is_none());
+
+        // A write invalidates earlier reads
+        ctx.record_result("file_write", &params, &result);
+        assert!(ctx.cached_result("file_read", &params).is_none());
+
+        // Skills are never short-circuited; one not loaded counts as unsafe
+        ctx.record_result("file_read", &params, &result);
+        ctx.record_result("skill_weather", &params, &result);
+        assert!(ctx.cached_result("skill_weather", &params).is_none());
+        assert!(ctx.cached_result("file_read", &params).is_none());
+    }
+
+    #[test]
+    fn test_reflection_policy() {
+        let policy = ReflectionPolicy::default();
+        assert!(policy.reflect_after_failure(1));
+        assert!(policy.reflect_after_failure(3));
+        assert!(!policy.reflect_after_failure(4));
+        assert!(!policy.reflect_after_success(20));
+
+        let long_tasks = ReflectionPolicy {
+            reflect_on_long_tasks: true,
+            long_task_iterations: 5,
+            ..Default::default()
+        };
+        assert!(!long_tasks.reflect_after_success(4));
+        assert!(long_tasks.reflect_after_success(5));
+
+        let never = ReflectionPolicy {
+            trigger: ReflectionTrigger::Never,
+            reflect_on_long_tasks: true,
+            ..Default::default()
+        };
+        assert!(!never.reflect_after_failure(1));
+        assert!(never.can_recover(1));
+        assert!(!never.reflect_after_success(50));
+
+        let always = ReflectionPolicy {
+            trigger: ReflectionTrigger::Always,
+            ..Default::default()
+        };
+        assert!(always.reflect_after_success(1));
+    }
 }
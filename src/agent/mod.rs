@@ -15,6 +15,22 @@ pub mod loop_runner;
 pub mod planning;
 pub mod prompts;
 pub mod mcp_config;
+pub mod digest;
+pub mod grammar;
+pub mod escalation;
+pub mod focus;
+pub mod injection;
+pub mod language;
+pub mod personas;
+pub mod quality;
+pub mod plugins;
+pub mod research;
+pub mod routing;
+pub mod tagging;
+pub mod run_progress;
+pub mod sandbox;
+pub mod watch_folders;
+pub mod workspace_index;
 
 use std::sync::Arc;
 use skills::{SkillRegistry, loader::SkillLoader};
@@ -27,8 +43,8 @@ pub use tools::{Tool, ToolRegistry, ToolResult, ToolError, ToolInfo};
 pub use tools::exa::{ExaSearchTool, ExaSearchConfig, create_exa_tools};
 pub use tools::mcp_client::{McpServerConfig, McpTransport, McpServerManager};
 pub use tools::mcp_presets::{McpPreset, McpCategory, get_all_presets};
-pub use runner::{ToolCall, extract_tool_call, build_tool_instructions, format_tool_result_for_system};
-pub use loop_runner::{AgentLoop, AgentLoopConfig, AgentState, AgentContext, AgentEvent, IterationResult};
+pub use runner::{ToolCall, extract_tool_call, extract_tool_calls, build_tool_instructions, format_tool_result_for_system};
+pub use loop_runner::{AgentLoop, AgentLoopConfig, AgentState, AgentContext, AgentEvent, IterationResult, ReflectionPolicy, ReflectionTrigger};
 pub use planning::{TaskPlan, Task, TaskStatus, TaskPriority, PlanManager};
 pub use prompts::{build_agent_system_prompt, build_tool_instructions_advanced, build_context_compression_prompt};
 
@@ -152,13 +168,25 @@ impl Agent {
         // ============================================================
         if self.config.enable_web_search {
             let exa_config = ExaSearchConfig::default();
-            let exa_tools = create_exa_tools(exa_config);
-            for tool in exa_tools {
-                self.tool_registry.register(tool).await;
+            match create_exa_tools(exa_config) {
+                Ok(exa_tools) => {
+                    for tool in exa_tools {
+                        self.tool_registry.register(tool).await;
+                    }
+                    tracing::info!("Exa search tools registered (web_search, code_search, company_research, deep_research, web_crawl)");
+                }
+                Err(e) => tracing::warn!("Exa search tools not registered: {}", e),
             }
-            tracing::info!("Exa search tools registered (web_search, code_search, company_research, deep_research, web_crawl)");
         }
         
+        // ============================================================
+        // Feeds (RSS/Atom)
+        // ============================================================
+        if self.config.enable_web_fetch {
+            self.tool_registry.register(Arc::new(tools::feeds::FeedReadTool)).await;
+            tracing::info!("Feed tool registered (feed_read)");
+        }
+
         // ============================================================
         // File system tools (read-only)
         // ============================================================
@@ -169,7 +197,10 @@ impl Agent {
             self.tool_registry.register(Arc::new(builtins::GlobTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileInfoTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileSearchContentTool)).await;
-            tracing::info!("Filesystem read tools registered (file_read, file_list, grep, glob, file_info, file_search)");
+            self.tool_registry.register(Arc::new(tools::csv::CsvReadTool)).await;
+            self.tool_registry.register(Arc::new(tools::repo_map::RepoMapTool)).await;
+            self.tool_registry.register(Arc::new(tools::workspace_search::WorkspaceSearchTool)).await;
+            tracing::info!("Filesystem read tools registered (file_read, file_list, grep, glob, file_info, file_search, csv_read, repo_map, workspace_search)");
         }
         
         // ============================================================
@@ -285,7 +316,8 @@ impl Agent {
         self.tool_registry.register(Arc::new(pdf::PdfCreateTool)).await;
         self.tool_registry.register(Arc::new(pdf::PdfAddPageTool)).await;
         self.tool_registry.register(Arc::new(pdf::PdfMergeTool)).await;
-        tracing::info!("PDF tools registered (pdf_read, pdf_create, pdf_add_page, pdf_merge)");
+        self.tool_registry.register(Arc::new(tools::document::DocumentRenderTool)).await;
+        tracing::info!("PDF tools registered (pdf_read, pdf_create, pdf_add_page, pdf_merge, document_render)");
         
         // ============================================================
         // OpenRouter AI consultation tool
@@ -305,6 +337,17 @@ impl Agent {
         }
         self.skill_registry.register_as_tools(&self.tool_registry).await;
         tracing::info!("{} skills loaded and registered as tools", skill_count);
+
+        // ============================================================
+        // Native plugins (loaded from {data_dir}/plugins)
+        // ============================================================
+        if let Some(plugins_dir) = plugins::get_plugins_dir() {
+            let loaded = plugins::load_plugins_from(&plugins_dir);
+            if !loaded.is_empty() {
+                let plugin_count = plugins::register_plugins(&self.tool_registry, &loaded).await;
+                tracing::info!("{} plugin tool(s) registered from {} plugin(s)", plugin_count, loaded.len());
+            }
+        }
         
         let total = self.tool_registry.count();
         tracing::info!("Agent initialized with {} total tools", total);
@@ -343,7 +386,7 @@ pub fn get_tool_permission(tool_name: &str) -> PermissionLevel {
         | "file_info" | "file_search" | "diff" | "wc" | "tree"
         | "process_list" | "environment" | "system_info" | "which"
         | "git_status" | "git_diff" | "git_log" | "git_branch"
-        | "pdf_read"
+        | "pdf_read" | "csv_read" | "repo_map" | "workspace_search"
         | "skill_list" | "skill_invoke" 
         | "mcp_list_servers" => {
             PermissionLevel::ReadOnly
@@ -351,14 +394,14 @@ pub fn get_tool_permission(tool_name: &str) -> PermissionLevel {
         // Network tools (external requests)
         "web_search" | "code_search" | "company_research" 
         | "deep_research_start" | "deep_research_check" | "web_crawl"
-        | "web_fetch" | "web_download" | "ai_consult" => {
+        | "web_fetch" | "web_download" | "feed_read" | "ai_consult" => {
             PermissionLevel::Network
         }
         // Write tools (file modifications)
         "file_write" | "file_edit" | "file_create" | "file_delete" 
         | "file_move" | "file_copy" | "directory_create"
         | "find_replace" | "patch"
-        | "pdf_create" | "pdf_add_page" | "pdf_merge"
+        | "pdf_create" | "pdf_add_page" | "pdf_merge" | "document_render"
         | "skill_create" 
         | "mcp_add_server" | "mcp_remove_server" => {
             PermissionLevel::WriteFile
@@ -371,8 +414,13 @@ pub fn get_tool_permission(tool_name: &str) -> PermissionLevel {
         }
         // MCP tools (from external servers)
         name if name.starts_with("mcp_") => PermissionLevel::Network,
-        // Default to read-only
-        _ => PermissionLevel::ReadOnly,
+        // Skills require the level of their declared permissions; one that
+        // is not loaded cannot be vouched for
+        name if name.starts_with("skill_") => {
+            skills::declared_level(name).unwrap_or(PermissionLevel::ExecuteUnsafe)
+        }
+        // Plugin tools declare their level; anything else is read-only
+        name => plugins::declared_level(name).unwrap_or(PermissionLevel::ReadOnly),
     }
 }
 
@@ -417,6 +465,7 @@ mod tests {
         // Skill tools
         assert_eq!(get_tool_permission("skill_invoke"), PermissionLevel::ReadOnly);
         assert_eq!(get_tool_permission("skill_list"), PermissionLevel::ReadOnly);
+        assert_eq!(get_tool_permission("skill_not_loaded"), PermissionLevel::ExecuteUnsafe);
         // MCP
         assert_eq!(get_tool_permission("mcp_github_list_repos"), PermissionLevel::Network);
     }
@@ -3,8 +3,9 @@
 //! Provides permission levels, request tracking, and UI notification signals
 //! for approval workflows.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, Utc};
@@ -46,7 +47,7 @@ impl std::fmt::Display for PermissionLevel {
 }
 
 impl PermissionLevel {
-    fn rank(self) -> u8 {
+    pub(crate) fn rank(self) -> u8 {
         match self {
             PermissionLevel::ReadOnly => 0,
             PermissionLevel::WriteFile => 1,
@@ -57,6 +58,13 @@ impl PermissionLevel {
         }
     }
     
+    /// Levels that can't change files, reach the network or run anything;
+    /// "approve all safe" only covers these. `ExecuteSafe` is left out: only
+    /// plugins and skills hold it, and they run their own programs.
+    pub fn is_safe(self) -> bool {
+        matches!(self, PermissionLevel::ReadOnly)
+    }
+
     /// Human-readable label for UI
     pub fn label(&self) -> &'static str {
         match self {
@@ -82,6 +90,15 @@ impl PermissionLevel {
     }
 }
 
+/// Whether the app window has focus; permission timeouts only run while it
+/// does, so stepping away doesn't deny anything
+static WINDOW_FOCUSED: AtomicBool = AtomicBool::new(true);
+
+/// Called by the window event handler
+pub fn set_window_focused(focused: bool) {
+    WINDOW_FOCUSED.store(focused, Ordering::Relaxed);
+}
+
 /// Request for a permission decision.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PermissionRequest {
@@ -148,6 +165,8 @@ pub struct PermissionManager {
     pending: Arc<Mutex<Vec<PermissionRequest>>>,
     approved: Arc<Mutex<HashSet<Uuid>>>,
     denied: Arc<Mutex<HashSet<Uuid>>>,
+    /// Time each waiting request has counted towards its timeout
+    waited: Arc<Mutex<HashMap<Uuid, Duration>>>,
     default_level: PermissionLevel,
     signals: PermissionSignals,
 }
@@ -160,6 +179,7 @@ impl PermissionManager {
             pending: Arc::new(Mutex::new(Vec::new())),
             approved: Arc::new(Mutex::new(HashSet::new())),
             denied: Arc::new(Mutex::new(HashSet::new())),
+            waited: Arc::new(Mutex::new(HashMap::new())),
             default_level,
             signals: PermissionSignals {
                 pending_requests: pending,
@@ -186,6 +206,17 @@ impl PermissionManager {
         PermissionResult::Pending
     }
 
+    /// Queues a request for the user even if its level is allowed by default,
+    /// for actions that always need explicit consent.
+    pub async fn request_consent(&self, request: PermissionRequest) -> PermissionResult {
+        self.pending
+            .lock()
+            .expect("pending mutex poisoned")
+            .push(request);
+        self.sync_pending_signal();
+        PermissionResult::Pending
+    }
+
     /// Approves a pending permission request.
     pub async fn approve(&self, request_id: Uuid) -> Result<(), PermissionError> {
         self.ensure_not_decided(request_id)?;
@@ -216,6 +247,52 @@ impl PermissionManager {
         Ok(())
     }
 
+    /// Denies every pending request, e.g. when the user stops the run that
+    /// asked for them. Returns how many were denied.
+    pub fn deny_all_pending(&self) -> usize {
+        let pending: Vec<PermissionRequest> = self
+            .pending
+            .lock()
+            .expect("pending mutex poisoned")
+            .drain(..)
+            .collect();
+        if pending.is_empty() {
+            return 0;
+        }
+        {
+            let mut denied = self.denied.lock().expect("denied mutex poisoned");
+            denied.extend(pending.iter().map(|request| request.id));
+        }
+        self.sync_pending_signal();
+        for request in &pending {
+            self.emit_decision(request.id, PermissionDecision::Denied);
+        }
+        pending.len()
+    }
+
+    /// Approves every pending request matching `filter`, e.g. the safe ones.
+    /// Returns how many were approved.
+    pub fn approve_where(&self, filter: impl Fn(&PermissionRequest) -> bool) -> usize {
+        let approved: Vec<Uuid> = {
+            let mut pending = self.pending.lock().expect("pending mutex poisoned");
+            let (approved, kept): (Vec<_>, Vec<_>) = pending.drain(..).partition(|request| filter(request));
+            *pending = kept;
+            approved.into_iter().map(|request| request.id).collect()
+        };
+        if approved.is_empty() {
+            return 0;
+        }
+        self.approved
+            .lock()
+            .expect("approved mutex poisoned")
+            .extend(approved.iter().copied());
+        self.sync_pending_signal();
+        for id in &approved {
+            self.emit_decision(*id, PermissionDecision::Approved);
+        }
+        approved.len()
+    }
+
     /// Checks whether a permission level is allowed by default.
     pub fn check_permission(&self, _tool: &str, level: PermissionLevel) -> bool {
         level.rank() <= self.default_level.rank()
@@ -236,24 +313,51 @@ impl PermissionManager {
         None
     }
 
-    /// Waits for a permission decision or times out.
+    /// Waits for a permission decision or times out; a request that times
+    /// out is denied so it leaves the queue. Only time spent with the window
+    /// focused counts, and `None` waits until the user decides.
     pub async fn wait_for_decision(
         &self,
         request_id: Uuid,
-        timeout: Duration,
+        timeout: Option<Duration>,
     ) -> Option<PermissionDecision> {
-        let start = Instant::now();
-        loop {
+        let mut last = Instant::now();
+        let decision = loop {
             if let Some(decision) = self.decision_for(request_id) {
-                return Some(decision);
+                break Some(decision);
             }
 
-            if start.elapsed() >= timeout {
-                return None;
+            let waited = {
+                let mut waited = self.waited.lock().expect("waited mutex poisoned");
+                let entry = waited.entry(request_id).or_default();
+                if WINDOW_FOCUSED.load(Ordering::Relaxed) {
+                    *entry += last.elapsed();
+                }
+                *entry
+            };
+            last = Instant::now();
+            if timeout.is_some_and(|timeout| waited >= timeout) {
+                break match self.deny(request_id).await {
+                    Ok(()) => None,
+                    // Decided just now
+                    Err(_) => self.decision_for(request_id),
+                };
             }
 
             sleep(Duration::from_millis(200)).await;
-        }
+        };
+        self.waited.lock().expect("waited mutex poisoned").remove(&request_id);
+        decision
+    }
+
+    /// Time a waiting request has counted towards its timeout
+    pub fn waited(&self, request_id: Uuid) -> Duration {
+        self.waited
+            .lock()
+            .expect("waited mutex poisoned")
+            .get(&request_id)
+            .copied()
+            .unwrap_or_default()
     }
 
     /// Returns a snapshot of pending permission requests.
@@ -307,3 +411,52 @@ impl PermissionManager {
         signal.set(Some(notification));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus::prelude::{ScopeId, VNode, VirtualDom};
+
+    fn request(tool: &str, level: PermissionLevel) -> PermissionRequest {
+        PermissionRequest {
+            id: Uuid::new_v4(),
+            tool_name: tool.to_string(),
+            operation: "execute".to_string(),
+            target: String::new(),
+            level,
+            params: Value::Null,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_approve_all_safe() {
+        // The manager's signals need a Dioxus runtime
+        let dom = VirtualDom::new(VNode::empty);
+        dom.in_runtime(|| {
+            ScopeId::ROOT.in_runtime(|| {
+                let manager = PermissionManager::new(PermissionLevel::ReadOnly);
+                let safe = request("file_list", PermissionLevel::ReadOnly);
+                let plugin = request("plugin_tool", PermissionLevel::ExecuteSafe);
+                let write = request("file_write", PermissionLevel::WriteFile);
+                let net = request("web_fetch", PermissionLevel::Network);
+                let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+                runtime.block_on(async {
+                    for r in [&safe, &plugin, &write, &net] {
+                        assert_eq!(manager.request_consent(r.clone()).await, PermissionResult::Pending);
+                    }
+
+                    assert_eq!(manager.approve_where(|r| r.level.is_safe()), 1);
+                    assert_eq!(manager.decision_for(safe.id), Some(PermissionDecision::Approved));
+                    assert_eq!(manager.get_pending_requests().len(), 3);
+
+                    // A request that times out is denied and leaves the queue
+                    assert_eq!(manager.wait_for_decision(write.id, Some(Duration::ZERO)).await, None);
+                    assert_eq!(manager.decision_for(write.id), Some(PermissionDecision::Denied));
+                    assert_eq!(manager.deny_all_pending(), 2);
+                    assert!(manager.get_pending_requests().is_empty());
+                });
+            })
+        });
+    }
+}
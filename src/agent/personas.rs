@@ -0,0 +1,208 @@
+//! Agent personas
+//!
+//! A persona bundles instructions, a tool subset, loop limits and an
+//! optional preferred model, and is selected per conversation. Tools outside
+//! the subset are left out of the prompt and refused if called anyway, which
+//! shrinks the prompt and keeps risky tools out of reach: the Writer has no
+//! shell or git at all.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::tools::ToolInfo;
+
+/// A named agent configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Persona {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: String,
+    /// Instructions appended to the system prompt
+    #[serde(default)]
+    pub prompt: String,
+    /// Tool name patterns the persona may use (`git_*`); empty allows all
+    #[serde(default)]
+    pub allow_tools: Vec<String>,
+    /// Patterns removed even when allowed
+    #[serde(default)]
+    pub deny_tools: Vec<String>,
+    /// Overrides the agent loop's maximum iterations
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    /// Path of the model to load when the persona is selected
+    #[serde(default)]
+    pub preferred_model: Option<String>,
+}
+
+fn patterns(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+/// Shell, git and process tools
+const EXECUTION_TOOLS: [&str; 5] = ["bash*", "command", "git_*", "process_list", "environment"];
+
+impl Persona {
+    /// Personas shipped with the app
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self {
+                id: "coder".into(),
+                name: "Coder".into(),
+                icon: "💻".into(),
+                prompt: String::new(),
+                allow_tools: Vec::new(),
+                deny_tools: patterns(&[
+                    "pdf_create",
+                    "pdf_add_page",
+                    "pdf_merge",
+                    "document_render",
+                    "company_research",
+                    "deep_research_*",
+                ]),
+                max_iterations: Some(40),
+                preferred_model: None,
+            },
+            Self {
+                id: "researcher".into(),
+                name: "Researcher".into(),
+                icon: "🔎".into(),
+                prompt: String::new(),
+                allow_tools: patterns(&[
+                    "web_*",
+                    "feed_read",
+                    "code_search",
+                    "company_research",
+                    "deep_research_*",
+                    "ai_consult",
+                    "file_read",
+                    "file_list",
+                    "file_info",
+                    "file_search",
+                    "grep",
+                    "glob",
+                    "pdf_read",
+                    "csv_read",
+                    "repo_map",
+                    "workspace_search",
+                    "file_create",
+                    "file_write",
+                    "think",
+                    "todo_write",
+                    "skill_*",
+                ]),
+                deny_tools: patterns(&EXECUTION_TOOLS),
+                max_iterations: Some(30),
+                preferred_model: None,
+            },
+            Self {
+                id: "writer".into(),
+                name: "Writer".into(),
+                icon: "✍️".into(),
+                prompt: String::new(),
+                allow_tools: patterns(&[
+                    "file_read",
+                    "file_list",
+                    "file_info",
+                    "file_write",
+                    "file_edit",
+                    "file_create",
+                    "glob",
+                    "grep",
+                    "find_replace",
+                    "diff",
+                    "wc",
+                    "pdf_*",
+                    "document_render",
+                    "csv_read",
+                    "web_search",
+                    "web_fetch",
+                    "think",
+                    "todo_write",
+                ]),
+                deny_tools: patterns(&EXECUTION_TOOLS),
+                max_iterations: Some(15),
+                preferred_model: None,
+            },
+        ]
+    }
+
+    pub fn is_builtin(id: &str) -> bool {
+        matches!(id, "coder" | "researcher" | "writer")
+    }
+
+    /// Display name; built-in personas are translated
+    pub fn label(&self, is_en: bool) -> String {
+        let name = match (self.id.as_str(), is_en) {
+            ("coder", false) => "Developpeur",
+            ("researcher", false) => "Chercheur",
+            ("writer", false) => "Redacteur",
+            _ => self.name.as_str(),
+        };
+        format!("{} {}", self.icon, name).trim().to_string()
+    }
+
+    /// Instructions for the system prompt, in the agent's language
+    pub fn instructions(&self, is_en: bool) -> String {
+        let builtin = match (self.id.as_str(), is_en) {
+            ("coder", true) => "You are acting as a software engineer. Read the relevant code before changing it, keep changes minimal and consistent with the project's style, and verify them (build, tests) when you can.",
+            ("coder", false) => "Tu agis en tant qu'ingénieur logiciel. Lis le code concerné avant de le modifier, fais des changements minimaux et cohérents avec le style du projet, et vérifie-les (build, tests) quand c'est possible.",
+            ("researcher", true) => "You are acting as a researcher. Cross-check information across several sources, cite them, separate facts from assumptions and say when the evidence is thin.",
+            ("researcher", false) => "Tu agis en tant que chercheur. Recoupe les informations entre plusieurs sources, cite-les, distingue les faits des hypothèses et signale quand les preuves sont minces.",
+            ("writer", true) => "You are acting as a writer and editor. Focus on clear structure, tone and concision; produce documents (Markdown, PDF) when asked and don't run commands.",
+            ("writer", false) => "Tu agis en tant que rédacteur et correcteur. Soigne la structure, le ton et la concision ; produis des documents (Markdown, PDF) quand on te le demande et n'exécute pas de commandes.",
+            _ => "",
+        };
+        if builtin.is_empty() {
+            self.prompt.clone()
+        } else {
+            builtin.to_string()
+        }
+    }
+
+    /// Whether the persona may use a tool
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|p| glob::Pattern::new(p).map_or(p == tool, |pattern| pattern.matches(tool)))
+        };
+        (self.allow_tools.is_empty() || matches(&self.allow_tools)) && !matches(&self.deny_tools)
+    }
+
+    /// Keep only the tools the persona may use
+    pub fn filter_tools(&self, tools: Vec<ToolInfo>) -> Vec<ToolInfo> {
+        tools
+            .into_iter()
+            .filter(|t| self.allows_tool(&t.name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin(id: &str) -> Persona {
+        Persona::builtin().into_iter().find(|p| p.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_tool_subsets() {
+        let writer = builtin("writer");
+        assert!(writer.allows_tool("pdf_create"));
+        assert!(writer.allows_tool("document_render"));
+        assert!(!writer.allows_tool("bash"));
+        assert!(!writer.allows_tool("bash_background"));
+        assert!(!writer.allows_tool("git_commit"));
+
+        let coder = builtin("coder");
+        assert!(coder.allows_tool("bash"));
+        assert!(coder.allows_tool("mcp_github_search"));
+        assert!(!coder.allows_tool("deep_research_start"));
+
+        let researcher = builtin("researcher");
+        assert!(researcher.allows_tool("web_fetch"));
+        assert!(!researcher.allows_tool("git_status"));
+        assert!(!researcher.allows_tool("file_delete"));
+    }
+}
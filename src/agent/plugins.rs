@@ -0,0 +1,378 @@
+//! Native tool plugins
+//!
+//! Shared libraries placed in `{data_dir}/plugins/` (`.dll`, `.so` or
+//! `.dylib`) are loaded at startup and their tools registered into the
+//! `ToolRegistry`. Only the global directory is scanned: a project folder
+//! must never be able to run native code just by being opened.
+//!
+//! # Plugin ABI (version 1)
+//!
+//! The interface is plain C so plugins don't have to match the compiler
+//! version ClawRS was built with. Data crosses the boundary as UTF-8,
+//! NUL-terminated JSON strings. A plugin exports:
+//!
+//! - `uint32_t clawrs_plugin_abi_version(void)` returning [`PLUGIN_ABI_VERSION`]
+//! - `const char *clawrs_plugin_manifest(void)`: a [`PluginManifest`] as
+//!   JSON, in static memory owned by the plugin
+//! - `char *clawrs_plugin_call(const char *tool, const char *params)`: runs
+//!   a tool and returns `{"success": bool, "message": str, "data": any}` or
+//!   `{"error": str}`, allocated by the plugin
+//! - `void clawrs_plugin_free(char *result)`: releases a string returned by
+//!   `clawrs_plugin_call`
+//!
+//! Calls run on a blocking thread and may be concurrent. Each tool declares
+//! its permission level (`ReadOnly`, `WriteFile`, `ReadWrite`, `ExecuteSafe`,
+//! `ExecuteUnsafe` or `Network`), which drives the approval dialog like for
+//! built-in tools. A tool whose name is already taken is skipped.
+//!
+//! A minimal plugin in Rust (`crate-type = ["cdylib"]`):
+//!
+//! ```ignore
+//! use std::ffi::{c_char, CStr, CString};
+//!
+//! #[no_mangle]
+//! pub extern "C" fn clawrs_plugin_abi_version() -> u32 { 1 }
+//!
+//! #[no_mangle]
+//! pub extern "C" fn clawrs_plugin_manifest() -> *const c_char {
+//!     c"{\"name\":\"hello\",\"version\":\"0.1.0\",\"tools\":[{\"name\":\"hello\",\
+//!        \"description\":\"Greets someone\",\"permission\":\"ReadOnly\",\
+//!        \"parameters\":{\"type\":\"object\",\"properties\":{\"name\":{\"type\":\"string\"}}}}]}"
+//!         .as_ptr()
+//! }
+//!
+//! #[no_mangle]
+//! pub extern "C" fn clawrs_plugin_call(_tool: *const c_char, params: *const c_char) -> *mut c_char {
+//!     // A real plugin would parse the JSON, e.g. with serde_json
+//!     let params = unsafe { CStr::from_ptr(params) }.to_string_lossy();
+//!     let result = format!("{{\"success\":true,\"message\":\"Hello!\",\"data\":{}}}", params);
+//!     CString::new(result).unwrap().into_raw()
+//! }
+//!
+//! #[no_mangle]
+//! pub extern "C" fn clawrs_plugin_free(result: *mut c_char) {
+//!     if !result.is_null() {
+//!         unsafe { drop(CString::from_raw(result)) };
+//!     }
+//! }
+//! ```
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use libloading::Library;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::ffi::{c_char, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::agent::permissions::PermissionLevel;
+use crate::agent::tools::{Tool, ToolError, ToolRegistry, ToolResult};
+use crate::storage::get_data_dir;
+
+/// Version of the plugin ABI implemented by this build
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *const c_char;
+type CallFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// Permission levels declared by loaded plugin tools
+static DECLARED_LEVELS: Lazy<DashMap<String, PermissionLevel>> = Lazy::new(DashMap::new);
+
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("Failed to load plugin library: {0}")]
+    Load(#[from] libloading::Error),
+    #[error("Plugin ABI version {found} is not supported (expected {expected})")]
+    AbiMismatch { found: u32, expected: u32 },
+    #[error("Invalid plugin manifest: {0}")]
+    InvalidManifest(String),
+}
+
+/// What a plugin declares about itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub tools: Vec<PluginToolSpec>,
+}
+
+/// A tool provided by a plugin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginToolSpec {
+    pub name: String,
+    pub description: String,
+    pub permission: PermissionLevel,
+    /// JSON schema of the parameters
+    #[serde(default = "default_parameters")]
+    pub parameters: Value,
+}
+
+fn default_parameters() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+/// Parse and check a manifest returned by a plugin
+pub fn parse_manifest(raw: &str) -> Result<PluginManifest, PluginError> {
+    let manifest: PluginManifest =
+        serde_json::from_str(raw).map_err(|e| PluginError::InvalidManifest(e.to_string()))?;
+    if manifest.name.trim().is_empty() {
+        return Err(PluginError::InvalidManifest("empty plugin name".into()));
+    }
+    if let Some(tool) = manifest.tools.iter().find(|t| {
+        t.name.is_empty()
+            || !t
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }) {
+        return Err(PluginError::InvalidManifest(format!(
+            "invalid tool name `{}`",
+            tool.name
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Convert the JSON returned by `clawrs_plugin_call`
+pub fn parse_call_output(raw: &str) -> Result<ToolResult, ToolError> {
+    let value: Value = serde_json::from_str(raw)
+        .map_err(|e| ToolError::ExecutionFailed(format!("Plugin returned invalid JSON: {}", e)))?;
+    if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+        return Err(ToolError::ExecutionFailed(error.to_string()));
+    }
+    Ok(ToolResult {
+        success: value
+            .get("success")
+            .and_then(|s| s.as_bool())
+            .unwrap_or(true),
+        message: value
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        data: value.get("data").cloned().unwrap_or(Value::Null),
+    })
+}
+
+/// A loaded plugin library
+pub struct Plugin {
+    pub path: PathBuf,
+    pub manifest: PluginManifest,
+    call: CallFn,
+    free: FreeFn,
+    // Keeps the code behind `call` and `free` mapped; dropped last
+    _library: Library,
+}
+
+impl Plugin {
+    /// Load a plugin library and read its manifest
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        // SAFETY: loading runs the library's initializers; plugins are native
+        // code the user installed, trusted like the app itself
+        let library = unsafe { Library::new(path)? };
+        // SAFETY: the symbol types are those of the documented ABI
+        let (abi_version, manifest_fn, call, free) = unsafe {
+            (
+                *library.get::<AbiVersionFn>(b"clawrs_plugin_abi_version\0")?,
+                *library.get::<ManifestFn>(b"clawrs_plugin_manifest\0")?,
+                *library.get::<CallFn>(b"clawrs_plugin_call\0")?,
+                *library.get::<FreeFn>(b"clawrs_plugin_free\0")?,
+            )
+        };
+
+        // SAFETY: ABI functions without arguments
+        let found = unsafe { abi_version() };
+        if found != PLUGIN_ABI_VERSION {
+            return Err(PluginError::AbiMismatch {
+                found,
+                expected: PLUGIN_ABI_VERSION,
+            });
+        }
+        let raw = unsafe { manifest_fn() };
+        if raw.is_null() {
+            return Err(PluginError::InvalidManifest("null manifest".into()));
+        }
+        // SAFETY: the manifest is a NUL-terminated string in static memory
+        let manifest = parse_manifest(&unsafe { CStr::from_ptr(raw) }.to_string_lossy())?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            manifest,
+            call,
+            free,
+            _library: library,
+        })
+    }
+
+    /// Run one of the plugin's tools (blocking)
+    fn call_tool(&self, tool: &str, params: &Value) -> Result<ToolResult, ToolError> {
+        let to_cstring = |s: String| {
+            CString::new(s).map_err(|_| ToolError::InvalidParameters("NUL byte in input".into()))
+        };
+        let tool = to_cstring(tool.to_string())?;
+        let params = to_cstring(params.to_string())?;
+
+        // SAFETY: both arguments are valid NUL-terminated strings that outlive the call
+        let raw = unsafe { (self.call)(tool.as_ptr(), params.as_ptr()) };
+        if raw.is_null() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Plugin `{}` returned no result",
+                self.manifest.name
+            )));
+        }
+        // SAFETY: a non-null result is a NUL-terminated string owned by the
+        // plugin, released with its own `free`
+        let output = unsafe { CStr::from_ptr(raw) }.to_string_lossy().to_string();
+        unsafe { (self.free)(raw) };
+        parse_call_output(&output)
+    }
+}
+
+/// Registry entry for a plugin tool
+pub struct PluginTool {
+    spec: PluginToolSpec,
+    plugin: Arc<Plugin>,
+}
+
+#[async_trait]
+impl Tool for PluginTool {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn description(&self) -> &str {
+        &self.spec.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.spec.parameters.clone()
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let plugin = self.plugin.clone();
+        let tool = self.spec.name.clone();
+        tokio::task::spawn_blocking(move || plugin.call_tool(&tool, &params))
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Plugin call panicked: {}", e)))?
+    }
+}
+
+/// Directory scanned for plugins, inside the active profile's data directory
+pub fn get_plugins_dir() -> Option<PathBuf> {
+    get_data_dir().ok().map(|dir| dir.join("plugins"))
+}
+
+fn is_library(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+}
+
+/// Load every plugin in `dir`, logging the ones that fail
+pub fn load_plugins_from(dir: &Path) -> Vec<Arc<Plugin>> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_library(path))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| match Plugin::load(&path) {
+            Ok(plugin) => {
+                tracing::info!(
+                    "Loaded plugin {} v{} ({} tool(s)) from {}",
+                    plugin.manifest.name,
+                    plugin.manifest.version,
+                    plugin.manifest.tools.len(),
+                    path.display()
+                );
+                Some(Arc::new(plugin))
+            }
+            Err(e) => {
+                tracing::warn!("Skipping plugin {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Register the tools of `plugins`, skipping names already in use
+pub async fn register_plugins(registry: &ToolRegistry, plugins: &[Arc<Plugin>]) -> usize {
+    let mut count = 0;
+    for plugin in plugins {
+        for spec in &plugin.manifest.tools {
+            if registry.get(&spec.name).is_some() {
+                tracing::warn!(
+                    "Plugin {}: tool `{}` already exists, skipped",
+                    plugin.manifest.name,
+                    spec.name
+                );
+                continue;
+            }
+            DECLARED_LEVELS.insert(spec.name.clone(), spec.permission);
+            registry
+                .register(Arc::new(PluginTool {
+                    spec: spec.clone(),
+                    plugin: plugin.clone(),
+                }))
+                .await;
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Permission level declared by a plugin tool
+pub fn declared_level(tool: &str) -> Option<PermissionLevel> {
+    DECLARED_LEVELS.get(tool).map(|level| *level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(
+            r#"{"name":"weather","version":"1.0.0","tools":[
+                {"name":"forecast","description":"Weather forecast","permission":"Network"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.tools[0].permission, PermissionLevel::Network);
+        assert_eq!(manifest.tools[0].parameters, default_parameters());
+
+        assert!(parse_manifest(
+            r#"{"name":"x","version":"1","tools":[{"name":"bad name","description":"","permission":"ReadOnly"}]}"#
+        )
+        .is_err());
+        assert!(parse_manifest(
+            r#"{"name":"x","version":"1","tools":[{"name":"t","description":"","permission":"Root"}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_call_output() {
+        let result =
+            parse_call_output(r#"{"success":true,"message":"Sunny","data":{"temp":21}}"#).unwrap();
+        assert_eq!(result.message, "Sunny");
+        assert_eq!(result.data, json!({"temp": 21}));
+        assert!(matches!(
+            parse_call_output(r#"{"error":"city not found"}"#),
+            Err(ToolError::ExecutionFailed(e)) if e == "city not found"
+        ));
+        assert!(parse_call_output("not json").is_err());
+    }
+}
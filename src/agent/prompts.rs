@@ -22,8 +22,8 @@ pub fn build_agent_system_prompt(
         prompt.push_str("\n\n");
     }
 
-    // Agent identity and capabilities
-    prompt.push_str(AGENT_IDENTITY);
+    // Agent identity and what the registered tools let it do
+    prompt.push_str(&build_identity(tools));
     prompt.push('\n');
 
     // Thinking instructions
@@ -38,6 +38,9 @@ pub fn build_agent_system_prompt(
 
     // Planning instructions
     prompt.push_str(PLANNING_INSTRUCTIONS);
+    if tools.iter().any(|t| t.name == "todo_write") {
+        prompt.push_str("\nKeep the plan up to date with the todo_write tool.\n");
+    }
     prompt.push('\n');
 
     // Context injection if available
@@ -55,23 +58,53 @@ pub fn build_agent_system_prompt(
     prompt
 }
 
-/// Agent identity prompt
-const AGENT_IDENTITY: &str = r#"## Identity
-You are an advanced AI assistant with autonomous agent capabilities, similar to Claude Code or OpenCode. You can:
-- Think and plan before acting
-- Read, create, edit, delete and move files
-- Execute full shell commands (bash/powershell)
-- Perform Git operations (status, diff, log, commit, branch, stash)
-- Search code and the web
-- Fetch web pages and API content
-- Compare files, do multi-file find-and-replace
-- Inspect the system (processes, environment, system info)
-- Connect to external MCP servers (GitHub, Brave Search, databases, etc.)
-- Iterate and improve your responses
-
-You work autonomously but ask for confirmation for dangerous actions.
-You prefer editing existing files (file_edit) over full rewrites (file_write).
-"#;
+/// Capabilities claimed in the identity, each with the tools that provide it
+const CAPABILITIES: &[(&str, &[&str])] = &[
+    ("Read and search files", &["file_read", "file_list", "grep", "glob", "file_search"]),
+    ("Create, edit, delete and move files", &["file_write", "file_edit", "file_create", "file_delete", "file_move"]),
+    ("Execute shell commands", &["bash", "command"]),
+    ("Perform Git operations", &["git_status", "git_diff", "git_log", "git_commit"]),
+    ("Search the web and code", &["web_search", "code_search"]),
+    ("Fetch web pages and API content", &["web_fetch", "web_crawl"]),
+    ("Follow RSS and Atom feeds", &["feed_read"]),
+    ("Compare files and find-and-replace across files", &["diff", "find_replace", "patch"]),
+    ("Inspect the system (processes, environment, system info)", &["process_list", "environment", "system_info"]),
+];
+
+/// Agent identity, listing only what the registered `tools` allow
+fn build_identity(tools: &[ToolInfo]) -> String {
+    let has = |name: &str| tools.iter().any(|t| t.name == name);
+    let mut out = String::from(
+        "## Identity\nYou are an AI assistant with autonomous agent capabilities. You can:\n- Think and plan before acting\n",
+    );
+    for (capability, names) in CAPABILITIES {
+        if names.iter().any(|name| has(name)) {
+            out.push_str(&format!("- {}\n", capability));
+        }
+    }
+    if tools.iter().any(|t| t.name.starts_with("mcp_") && !t.name.ends_with("_server") && t.name != "mcp_list_servers") {
+        out.push_str("- Use tools from connected MCP servers\n");
+    }
+    out.push_str("- Iterate and improve your responses\n\n");
+    if tools.is_empty() {
+        out.push_str("No tools are available in this conversation: answer from your own knowledge and never pretend to run one.\n");
+    } else {
+        out.push_str("Only the tools listed under Available Tools exist; never invent one.\n");
+        out.push_str("You work autonomously but ask for confirmation for dangerous actions.\n");
+    }
+    if has("file_edit") && has("file_write") {
+        out.push_str("You prefer editing existing files (file_edit) over full rewrites (file_write).\n");
+    }
+    out
+}
+
+/// Context window and limits of the loaded model, appended to the base prompt
+pub fn build_model_capabilities(context_size: u32, max_tokens: u32) -> String {
+    format!(
+        "## Model\n- Context window: {} tokens, shared by this prompt, the conversation and tool results. Keep tool calls focused: long outputs get truncated.\n- Replies are cut after {} tokens; split long deliverables across files or messages.",
+        context_size, max_tokens
+    )
+}
 
 /// Instructions for thinking/reasoning mode
 const THINKING_INSTRUCTIONS: &str = r#"## Thinking Mode
@@ -123,8 +156,6 @@ For complex tasks, create a structured plan:
 3. Execute each task one by one
 4. Verify results and adjust if necessary
 5. Summarize results at the end
-
-You can update your plan with the todo_write tool if available.
 "#;
 
 /// Build advanced tool instructions with examples
@@ -360,6 +391,10 @@ Line 3</param>
         "wc" => Some(r#"{"tool": "wc", "params": {"path": "src/main.rs"}}"#),
         // System tools
         "tree" => Some(r#"{"tool": "tree", "params": {"path": ".", "max_depth": 3}}"#),
+        "repo_map" => Some(r#"{"tool": "repo_map", "params": {"path": ".", "max_tokens": 1500}}"#),
+        "workspace_search" => Some(
+            r#"{"tool": "workspace_search", "params": {"query": "retry logic for HTTP requests", "mode": "semantic"}}"#,
+        ),
         "which" => Some(r#"{"tool": "which", "params": {"command": "cargo"}}"#),
         "system_info" => Some(r#"{"tool": "system_info", "params": {}}"#),
         "process_list" => Some(r#"{"tool": "process_list", "params": {"filter": "node"}}"#),
@@ -580,6 +615,7 @@ mod tests {
         let tools = vec![ToolInfo {
             name: "web_search".to_string(),
             description: "Search the web".to_string(),
+            description_fr: None,
             parameters_schema: json!({
                 "type": "object",
                 "properties": {
@@ -592,4 +628,24 @@ mod tests {
         assert!(instructions.contains("web_search"));
         assert!(instructions.contains("Search the web"));
     }
+
+    #[test]
+    fn test_identity_follows_registered_tools() {
+        let tool = |name: &str| ToolInfo {
+            name: name.to_string(),
+            description: String::new(),
+            description_fr: None,
+            parameters_schema: json!({"type": "object"}),
+        };
+        let identity = build_identity(&[tool("file_read"), tool("web_search")]);
+        assert!(identity.contains("Read and search files"));
+        assert!(identity.contains("Search the web"));
+        assert!(!identity.contains("shell"));
+        assert!(!identity.contains("Git"));
+        assert!(!identity.contains("file_edit"));
+
+        let prompt = build_agent_system_prompt("", &[], None, None);
+        assert!(prompt.contains("No tools are available"));
+        assert!(!prompt.contains("todo_write"));
+    }
 }
@@ -0,0 +1,169 @@
+//! Output quality checks: garbage detection and word metrics
+//!
+//! Both depend on the writing system of the text. Chinese, Japanese and
+//! Thai are written without spaces between words, so a whitespace-based
+//! word count sees a whole paragraph as one "word"; the script is detected
+//! first and the heuristics that assume spaces are skipped for them.
+
+/// Writing system of a text, as far as the heuristics care
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// Latin, Cyrillic, Greek, Arabic... anything separating words with spaces
+    Spaced,
+    Chinese,
+    Japanese,
+    /// Hangul, written with spaces between words
+    Korean,
+    Thai,
+}
+
+/// Letters looked at when detecting the script
+const DETECT_SAMPLE: usize = 2000;
+
+fn is_han(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}')
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}')
+}
+
+fn is_thai(c: char) -> bool {
+    matches!(c, '\u{0E00}'..='\u{0E7F}')
+}
+
+/// Whether `c` is a character of a script written without spaces
+fn is_unspaced(c: char) -> bool {
+    is_han(c) || is_kana(c) || is_thai(c)
+}
+
+impl Script {
+    /// Main script of `text`: a script written without spaces wins as soon
+    /// as it makes up a third of the letters, since code and names mixed in
+    /// are usually Latin
+    pub fn detect(text: &str) -> Self {
+        let (mut letters, mut han, mut kana, mut hangul, mut thai) = (0usize, 0, 0, 0, 0);
+        for c in text.chars().filter(|c| c.is_alphabetic()).take(DETECT_SAMPLE) {
+            letters += 1;
+            match c {
+                c if is_han(c) => han += 1,
+                c if is_kana(c) => kana += 1,
+                c if is_hangul(c) => hangul += 1,
+                c if is_thai(c) => thai += 1,
+                _ => {}
+            }
+        }
+        let significant = |count: usize| count > 0 && count * 3 >= letters;
+        if significant(han + kana) {
+            // Japanese mixes kanji with kana; Chinese has no kana
+            if kana * 10 >= han + kana { Script::Japanese } else { Script::Chinese }
+        } else if significant(thai) {
+            Script::Thai
+        } else if significant(hangul) {
+            Script::Korean
+        } else {
+            Script::Spaced
+        }
+    }
+
+    pub fn uses_spaces(self) -> bool {
+        matches!(self, Script::Spaced | Script::Korean)
+    }
+}
+
+/// Number of words in `text`; each character of a script written without
+/// spaces counts as one (close to the word count for Chinese and Japanese)
+pub fn word_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_unspaced(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            count += 1;
+            in_word = true;
+        }
+    }
+    count
+}
+
+/// Detect if generated text is garbage/corrupted (model hallucinating)
+pub fn is_garbage_text(content: &str) -> bool {
+    let lower = content.to_lowercase();
+    let script = Script::detect(content);
+
+    // Patterns that indicate model is generating fake tool outputs
+    let garbage_patterns = [
+        "assistantcommentary",
+        "userresponse",
+        "toolresult:",
+        "✅ pdf_read:",
+        "✅ file_read:",
+        "contenu du pdf:",
+    ];
+
+    for pattern in garbage_patterns {
+        if lower.matches(pattern).count() > 3 {
+            tracing::warn!("Garbage detected: pattern '{}' repeated", pattern);
+            return true;
+        }
+    }
+
+    // Check for abnormal word/char ratio (text stuck together without
+    // spaces), meaningless for scripts that don't use them
+    let chars = content.chars().count();
+    let words = content.split_whitespace().count();
+    if script.uses_spaces() && chars > 300 && words > 0 {
+        let avg_word_len = chars / words;
+        if avg_word_len > 25 {
+            tracing::warn!("Garbage detected: abnormal word length ratio {} ({:?})", avg_word_len, script);
+            return true;
+        }
+    }
+
+    // Check for excessive repetition of any 20-char sequence
+    if chars > 200 {
+        let text: Vec<char> = content.chars().collect();
+        let chunks: Vec<&[char]> = text.chunks(20).collect();
+        if chunks.len() > 5 {
+            let first = chunks[0];
+            let repeat_count = chunks.iter().filter(|c| **c == first).count();
+            if repeat_count > 3 {
+                tracing::warn!("Garbage detected: repeated chunk pattern ({:?})", script);
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_word_count() {
+        assert_eq!(Script::detect("Bonjour, voici le fichier main.rs"), Script::Spaced);
+        assert_eq!(Script::detect("这是一个测试，使用 main.rs 文件"), Script::Chinese);
+        assert_eq!(Script::detect("これはテストです。ファイルを読みます"), Script::Japanese);
+        assert_eq!(Script::detect("안녕하세요 파일을 읽겠습니다"), Script::Korean);
+        assert_eq!(word_count("hello world"), 2);
+        assert_eq!(word_count("读取 main.rs 文件"), 5);
+    }
+
+    #[test]
+    fn test_garbage_not_triggered_by_cjk() {
+        let chinese = "这是一个关于文件系统的详细解释，包括目录结构、权限以及读取方式。".repeat(10);
+        assert!(!is_garbage_text(&chinese));
+        let stuck = "abcdefghijklmnopqrstuvwxyz0123456789".repeat(12);
+        assert!(is_garbage_text(&stuck));
+    }
+}
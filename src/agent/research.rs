@@ -0,0 +1,351 @@
+//! Managed deep research
+//!
+//! `deep_research_start` hands the Exa task to a background poller instead of
+//! leaving the agent to call `deep_research_check` in a loop. The poller
+//! records each new interim finding, keeps a Markdown research document in
+//! `{data_dir}/research/{id}.md` up to date, and writes a structured final
+//! report when the task completes. The chat shows running tasks in a
+//! progress card; `deep_research_check` reads the same state.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::agent::tools::exa::{check_deep_research, ExaMcpClient};
+use crate::agent::tools::CancelToken;
+use crate::storage::get_data_dir;
+
+/// Delay between two polls of a running task
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// Give up on a task still running after this long
+const MAX_DURATION: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResearchStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl ResearchStatus {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Running => "in_progress",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Content reported by the provider while the task runs
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub at: DateTime<Utc>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResearchTask {
+    /// Provider task id
+    pub id: String,
+    pub query: String,
+    pub status: ResearchStatus,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub findings: Vec<Finding>,
+    /// Final report, once completed
+    pub report: Option<String>,
+    pub error: Option<String>,
+    /// Research document kept up to date while the task runs
+    pub document: Option<PathBuf>,
+    /// Hidden from the progress card
+    pub dismissed: bool,
+    cancel: CancelToken,
+}
+
+impl ResearchTask {
+    pub fn is_running(&self) -> bool {
+        self.status == ResearchStatus::Running
+    }
+
+    pub fn elapsed_secs(&self) -> i64 {
+        let end = if self.is_running() {
+            Utc::now()
+        } else {
+            self.updated_at
+        };
+        (end - self.started_at).num_seconds().max(0)
+    }
+}
+
+static TASKS: Lazy<Mutex<HashMap<String, ResearchTask>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Bumped on every change, for the UI to poll cheaply
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+static URL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"https?://[^\s<>()\[\]"'`]+[^\s<>()\[\]"'`.,;:!?]"#).unwrap());
+
+fn update(id: &str, f: impl FnOnce(&mut ResearchTask)) -> Option<ResearchTask> {
+    let mut tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    let task = tasks.get_mut(id)?;
+    f(task);
+    task.updated_at = Utc::now();
+    REVISION.fetch_add(1, Ordering::Relaxed);
+    Some(task.clone())
+}
+
+pub fn revision() -> u64 {
+    REVISION.load(Ordering::Relaxed)
+}
+
+pub fn get(id: &str) -> Option<ResearchTask> {
+    TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(id)
+        .cloned()
+}
+
+/// Tasks started in this session, most recent first
+pub fn list() -> Vec<ResearchTask> {
+    let mut tasks: Vec<ResearchTask> = TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .collect();
+    tasks.sort_by_key(|t| std::cmp::Reverse(t.started_at));
+    tasks
+}
+
+/// Stop polling a running task
+pub fn cancel(id: &str) {
+    if let Some(task) = get(id) {
+        task.cancel.cancel();
+    }
+}
+
+/// Hide a task from the progress card
+pub fn dismiss(id: &str) {
+    update(id, |t| t.dismissed = true);
+}
+
+/// Distinct URLs cited in `text`, in order of appearance
+fn extract_sources(text: &str) -> Vec<String> {
+    let mut sources: Vec<String> = Vec::new();
+    for m in URL_RE.find_iter(text) {
+        let url = m.as_str().to_string();
+        if !sources.contains(&url) {
+            sources.push(url);
+        }
+    }
+    sources
+}
+
+/// The research document: query, status, final report and interim findings
+fn render_document(task: &ResearchTask) -> String {
+    let mut doc = format!(
+        "# Recherche approfondie\n\n**Question :** {}\n\n**Statut :** {} — démarrée le {}, {} s\n",
+        task.query,
+        task.status.id(),
+        task.started_at.format("%Y-%m-%d %H:%M UTC"),
+        task.elapsed_secs()
+    );
+    if let Some(error) = &task.error {
+        doc.push_str(&format!("\n**Erreur :** {}\n", error));
+    }
+    if let Some(report) = &task.report {
+        doc.push_str(&format!("\n## Rapport\n\n{}\n", report.trim()));
+        let sources = extract_sources(report);
+        if !sources.is_empty() {
+            doc.push_str("\n## Sources\n\n");
+            for source in sources {
+                doc.push_str(&format!("- {}\n", source));
+            }
+        }
+    }
+    if !task.findings.is_empty() {
+        doc.push_str("\n## Étapes intermédiaires\n");
+        for finding in &task.findings {
+            doc.push_str(&format!(
+                "\n### {}\n\n{}\n",
+                finding.at.format("%H:%M:%S"),
+                finding.text.trim()
+            ));
+        }
+    }
+    doc
+}
+
+fn write_document(task: &ResearchTask) {
+    let Some(path) = &task.document else { return };
+    if let Err(e) = std::fs::write(path, render_document(task)) {
+        tracing::warn!("Failed to write research document: {}", e);
+    }
+}
+
+fn document_path(id: &str) -> Option<PathBuf> {
+    let dir = get_data_dir().ok()?.join("research");
+    std::fs::create_dir_all(&dir).ok()?;
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Some(dir.join(format!("{}.md", name)))
+}
+
+/// Register a started task and poll it in the background
+pub fn start(client: Arc<ExaMcpClient>, id: String, query: String) -> ResearchTask {
+    let now = Utc::now();
+    let task = ResearchTask {
+        id: id.clone(),
+        query,
+        status: ResearchStatus::Running,
+        started_at: now,
+        updated_at: now,
+        findings: Vec::new(),
+        report: None,
+        error: None,
+        document: document_path(&id),
+        dismissed: false,
+        cancel: CancelToken::new(),
+    };
+    write_document(&task);
+    let cancel = task.cancel.clone();
+    TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(id.clone(), task.clone());
+    REVISION.fetch_add(1, Ordering::Relaxed);
+    tokio::spawn(poll(client, id, cancel));
+    task
+}
+
+async fn poll(client: Arc<ExaMcpClient>, id: String, cancel: CancelToken) {
+    let started = std::time::Instant::now();
+    let mut last_text = String::new();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            _ = cancel.cancelled() => {
+                if let Some(task) = update(&id, |t| t.status = ResearchStatus::Cancelled) {
+                    write_document(&task);
+                }
+                return;
+            }
+        }
+
+        let checked = tokio::select! {
+            checked = check_deep_research(&client, &id) => checked,
+            _ = cancel.cancelled() => continue,
+        };
+        let task = match checked {
+            Ok(("in_progress", text)) => {
+                if started.elapsed() > MAX_DURATION {
+                    update(&id, |t| {
+                        t.status = ResearchStatus::Failed;
+                        t.error = Some("délai dépassé".to_string());
+                    })
+                } else if !text.trim().is_empty() && text != last_text {
+                    last_text = text.clone();
+                    update(&id, |t| {
+                        t.findings.push(Finding {
+                            at: Utc::now(),
+                            text,
+                        })
+                    })
+                } else {
+                    // Refresh the elapsed time shown in the card
+                    update(&id, |_| {});
+                    continue;
+                }
+            }
+            Ok(("failed", text)) => update(&id, |t| {
+                t.status = ResearchStatus::Failed;
+                t.error = Some(text);
+            }),
+            Ok((_, text)) => update(&id, |t| {
+                t.status = ResearchStatus::Completed;
+                t.report = Some(text);
+            }),
+            Err(e) => {
+                // Transient errors are already retried by the client; keep
+                // polling until the deadline
+                tracing::warn!("Deep research check failed: {}", e);
+                if started.elapsed() <= MAX_DURATION {
+                    continue;
+                }
+                update(&id, |t| {
+                    t.status = ResearchStatus::Failed;
+                    t.error = Some(e.to_string());
+                })
+            }
+        };
+        let Some(task) = task else { return };
+        write_document(&task);
+        if !task.is_running() {
+            tracing::info!("Deep research {} finished: {}", id, task.status.id());
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> ResearchTask {
+        let now = Utc::now();
+        ResearchTask {
+            id: "r1".to_string(),
+            query: "Etat de l'art des batteries sodium-ion".to_string(),
+            status: ResearchStatus::Completed,
+            started_at: now,
+            updated_at: now,
+            findings: vec![Finding {
+                at: now,
+                text: "Lecture de 12 sources".to_string(),
+            }],
+            report: Some(
+                "Voir https://example.com/a et (https://example.org/b). Encore https://example.com/a."
+                    .to_string(),
+            ),
+            error: None,
+            document: None,
+            dismissed: false,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    #[test]
+    fn test_extract_sources() {
+        let sources = extract_sources(task().report.as_deref().unwrap());
+        assert_eq!(
+            sources,
+            vec!["https://example.com/a", "https://example.org/b"]
+        );
+    }
+
+    #[test]
+    fn test_render_document() {
+        let doc = render_document(&task());
+        assert!(doc.contains("**Question :** Etat de l'art"));
+        assert!(doc.contains("## Rapport"));
+        assert!(doc.contains("## Sources\n\n- https://example.com/a\n- https://example.org/b\n"));
+        assert!(doc.contains("Lecture de 12 sources"));
+        assert!(doc.find("## Rapport") < doc.find("## Étapes intermédiaires"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Model routing rules
+//!
+//! Each outgoing message is classified with cheap heuristics (chit-chat,
+//! code, anything else) and the first enabled rule matching its kind and
+//! keywords picks the model and/or persona that answers it, e.g. a small
+//! model for chit-chat and a big one for code.
+
+use crate::agent::quality::word_count;
+use serde::{Deserialize, Serialize};
+
+/// Kind of request, as guessed from the message text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    /// Short small talk: greetings, thanks, one-liners
+    Chat,
+    /// Programming: code blocks, errors, languages, files
+    Code,
+    /// Everything else
+    General,
+}
+
+impl RequestKind {
+    pub const ALL: [RequestKind; 3] = [RequestKind::Chat, RequestKind::Code, RequestKind::General];
+
+    pub fn label(self, is_en: bool) -> &'static str {
+        match (self, is_en) {
+            (RequestKind::Chat, true) => "Chit-chat",
+            (RequestKind::Chat, false) => "Discussion",
+            (RequestKind::Code, _) => "Code",
+            (RequestKind::General, true) => "Other",
+            (RequestKind::General, false) => "Autre",
+        }
+    }
+}
+
+/// Words that point at a programming request
+const CODE_WORDS: &[&str] = &[
+    "code", "function", "fonction", "bug", "debug", "compile", "compiler", "refactor", "stack trace",
+    "stacktrace", "exception", "regex", "script", "api", "sql", "rust", "python", "javascript",
+    "typescript", "java", "c++", "golang", "bash", "cargo", "npm", "git", "unit test", "test unitaire",
+];
+/// Snippets that only appear in code
+const CODE_MARKERS: &[&str] = &["```", "fn ", "def ", "=>", "();", "::", "#include"];
+/// File extensions of source files
+const CODE_EXTENSIONS: &[&str] = &[".rs", ".py", ".js", ".ts", ".tsx", ".go", ".java", ".cpp", ".c", ".h", ".sh", ".toml", ".json", ".yaml"];
+/// Messages of at most this many words are chit-chat unless they look like code
+const CHAT_MAX_WORDS: usize = 8;
+
+fn has_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Guess the kind of a message
+pub fn classify(text: &str) -> RequestKind {
+    let lower = text.to_lowercase();
+    let code = CODE_MARKERS.iter().any(|marker| text.contains(marker))
+        || CODE_WORDS.iter().any(|word| has_word(&lower, word))
+        || lower
+            .split_whitespace()
+            .any(|word| CODE_EXTENSIONS.iter().any(|ext| word.trim_end_matches(['.', ',', '?', '!', ')']).ends_with(ext) && word.len() > ext.len()));
+    if code {
+        RequestKind::Code
+    } else if word_count(&lower) <= CHAT_MAX_WORDS {
+        RequestKind::Chat
+    } else {
+        RequestKind::General
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteRule {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Kind of request matched; `None` matches any kind
+    #[serde(default)]
+    pub kind: Option<RequestKind>,
+    /// Matches only when the message contains one of these (case-insensitive)
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Model file that answers; `None` keeps the loaded one
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Persona used for this message instead of the conversation's
+    #[serde(default)]
+    pub persona: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RouteRule {
+    pub fn new(name: impl Into<String>, kind: Option<RequestKind>) -> Self {
+        Self {
+            name: name.into(),
+            enabled: true,
+            kind,
+            keywords: Vec::new(),
+            model: None,
+            persona: None,
+        }
+    }
+
+    pub fn matches(&self, kind: RequestKind, text: &str) -> bool {
+        if !self.enabled || self.kind.is_some_and(|k| k != kind) {
+            return false;
+        }
+        let lower = text.to_lowercase();
+        self.keywords.is_empty()
+            || self
+                .keywords
+                .iter()
+                .any(|keyword| !keyword.trim().is_empty() && lower.contains(&keyword.trim().to_lowercase()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RoutingSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Tried in order; the first match wins
+    #[serde(default)]
+    pub rules: Vec<RouteRule>,
+}
+
+/// Outcome of routing one message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub kind: RequestKind,
+    pub rule: RouteRule,
+}
+
+impl RoutingSettings {
+    /// Rule that applies to `text`, if routing is on and one matches
+    pub fn route(&self, text: &str) -> Option<Route> {
+        if !self.enabled {
+            return None;
+        }
+        let kind = classify(text);
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(kind, text))
+            .map(|rule| Route { kind, rule: rule.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("Salut, ça va ?"), RequestKind::Chat);
+        assert_eq!(classify("thanks!"), RequestKind::Chat);
+        assert_eq!(classify("Why does my Rust build fail?"), RequestKind::Code);
+        assert_eq!(classify("```\nlet x = 1;\n```"), RequestKind::Code);
+        assert_eq!(classify("look at main.rs"), RequestKind::Code);
+        // "api" inside a word is not code
+        assert_eq!(
+            classify("Can you suggest a few rapid ideas for a birthday party this weekend please"),
+            RequestKind::General
+        );
+    }
+
+    #[test]
+    fn test_route() {
+        let mut small = RouteRule::new("small", Some(RequestKind::Chat));
+        small.model = Some("/models/small.gguf".to_string());
+        let mut sql = RouteRule::new("sql", Some(RequestKind::Code));
+        sql.keywords = vec!["SQL".to_string()];
+        sql.persona = Some("data".to_string());
+        let big = RouteRule::new("big", Some(RequestKind::Code));
+        let mut settings = RoutingSettings { enabled: false, rules: vec![small, sql, big] };
+        assert_eq!(settings.route("hello"), None);
+
+        settings.enabled = true;
+        assert_eq!(settings.route("hello").unwrap().rule.name, "small");
+        assert_eq!(settings.route("fix this sql query").unwrap().rule.name, "sql");
+        assert_eq!(settings.route("fix this python script").unwrap().rule.name, "big");
+        assert_eq!(settings.route("Write me a long story about dragons and a lonely knight"), None);
+
+        settings.rules[0].enabled = false;
+        assert_eq!(settings.route("hello"), None);
+    }
+}
@@ -0,0 +1,215 @@
+//! Progress of the running agent, shown on the generating indicator
+//!
+//! Built from [`AgentEvent`]s: a live timer, the current iteration and tool,
+//! and staged hints as the run gets long, up to the point where the user may
+//! want the agent to wrap up before the runtime limit stops it.
+
+use crate::agent::AgentEvent;
+use std::time::{Duration, Instant};
+
+/// Hard runtime limit of a run; the hints count down to it
+pub const MAX_RUNTIME: Duration = Duration::from_secs(300);
+
+/// No hint for the first seconds of a run
+const QUIET: Duration = Duration::from_secs(20);
+/// From here on the hint mentions the iteration
+const LONG: Duration = Duration::from_secs(90);
+/// From here on the hint suggests wrapping up
+const NEAR_LIMIT: Duration = Duration::from_secs(210);
+
+/// Instruction injected when the user asks the agent to wrap up, in the
+/// language of the loop's status lines
+pub fn wrap_up_instruction(en: bool) -> &'static str {
+    if en {
+        "The user asks you to wrap up now. Don't call any more tools: write your final answer right away from what you have already found, pointing out what remains incomplete."
+    } else {
+        "L'utilisateur demande de conclure maintenant. N'appelle plus aucun outil : rédige immédiatement ta réponse finale à partir de ce que tu as déjà trouvé, en signalant ce qui reste incomplet."
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunProgress {
+    pub started_at: Instant,
+    pub iteration: usize,
+    pub max_iterations: usize,
+    /// Tool currently executing
+    pub tool: Option<String>,
+    /// The user asked to wrap up; the next reply is the final one
+    pub wrapping_up: bool,
+}
+
+impl RunProgress {
+    pub fn new(max_iterations: usize) -> Self {
+        Self {
+            started_at: Instant::now(),
+            iteration: 0,
+            max_iterations,
+            tool: None,
+            wrapping_up: false,
+        }
+    }
+
+    /// Update from an event of the run
+    pub fn apply(&mut self, event: &AgentEvent) {
+        match event {
+            AgentEvent::Progress {
+                iteration,
+                max_iterations,
+                ..
+            } => {
+                self.iteration = *iteration;
+                self.max_iterations = *max_iterations;
+            }
+            AgentEvent::ToolCallStarted { tool, .. } => self.tool = Some(tool.clone()),
+            AgentEvent::ToolCallCompleted { .. } | AgentEvent::ToolCallFailed { .. } => {
+                self.tool = None
+            }
+            _ => {}
+        }
+    }
+
+    /// Timer and hint for the indicator, e.g. "2m 05s · iteration 6/10";
+    /// `None` while the run is still short
+    pub fn hint(&self, elapsed: Duration, is_en: bool) -> Option<String> {
+        if elapsed < QUIET {
+            return None;
+        }
+        let mut hint = format_elapsed(elapsed);
+        if self.wrapping_up {
+            hint.push_str(if is_en {
+                " · wrapping up…"
+            } else {
+                " · conclusion en cours…"
+            });
+            return Some(hint);
+        }
+        if let Some(tool) = &self.tool {
+            hint.push_str(&format!(" · {}", tool));
+        }
+        if elapsed >= LONG && self.max_iterations > 0 {
+            hint.push_str(&if is_en {
+                format!(
+                    " · the agent is on iteration {}/{}",
+                    self.iteration, self.max_iterations
+                )
+            } else {
+                format!(
+                    " · l'agent en est a l'iteration {}/{}",
+                    self.iteration, self.max_iterations
+                )
+            });
+        }
+        if elapsed >= NEAR_LIMIT {
+            let left = MAX_RUNTIME.saturating_sub(elapsed);
+            hint.push_str(&if is_en {
+                format!(" · stops in {}", format_elapsed(left))
+            } else {
+                format!(" · arret dans {}", format_elapsed(left))
+            });
+        }
+        Some(hint)
+    }
+
+    /// Whether to emphasize the "wrap up now" button
+    pub fn near_limit(&self, elapsed: Duration) -> bool {
+        !self.wrapping_up && elapsed >= NEAR_LIMIT
+    }
+}
+
+/// "45s", "2m 05s"
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::runner::ToolCall;
+    use crate::agent::tools::dev::DiffTool;
+    use crate::agent::tools::ToolRegistry;
+    use crate::agent::{AgentContext, AgentLoop, AgentLoopConfig};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Events `AgentLoop` sends while running a `diff` call with `params`
+    async fn tool_call_events(params: serde_json::Value) -> Vec<AgentEvent> {
+        let registry = ToolRegistry::new();
+        registry.register_sync(Arc::new(DiffTool));
+        let config = AgentLoopConfig {
+            enable_retry: false,
+            ..Default::default()
+        };
+        let agent_loop = AgentLoop::new(config, Arc::new(registry));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let call = ToolCall {
+            tool: "diff".to_string(),
+            params,
+        };
+        let _ = agent_loop
+            .execute_tool_with_retry(&call, &mut AgentContext::new(), &tx)
+            .await;
+        drop(tx);
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn test_apply_loop_events() {
+        let mut progress = RunProgress::new(10);
+        // As sent by the chat at the start of each iteration
+        progress.apply(&AgentEvent::Progress {
+            iteration: 6,
+            max_iterations: 12,
+            message: String::new(),
+        });
+        assert_eq!((progress.iteration, progress.max_iterations), (6, 12));
+
+        let events = tool_call_events(json!({ "text_a": "a\n", "text_b": "b\n" })).await;
+        assert_eq!(events.len(), 2);
+        progress.apply(&events[0]);
+        assert_eq!(progress.tool.as_deref(), Some("diff"));
+        progress.apply(&events[1]);
+        assert_eq!(progress.tool, None);
+
+        // Missing text_b: the call fails
+        let events = tool_call_events(json!({ "text_a": "a" })).await;
+        assert!(matches!(events.last(), Some(AgentEvent::ToolCallFailed { .. })));
+        for event in &events {
+            progress.apply(event);
+        }
+        assert_eq!(progress.tool, None);
+    }
+
+    #[test]
+    fn test_staged_hints() {
+        let mut progress = RunProgress::new(10);
+        progress.iteration = 6;
+        assert_eq!(progress.hint(Duration::from_secs(5), true), None);
+        assert_eq!(
+            progress.hint(Duration::from_secs(45), true).as_deref(),
+            Some("45s")
+        );
+        assert_eq!(
+            progress.hint(Duration::from_secs(125), true).as_deref(),
+            Some("2m 05s · the agent is on iteration 6/10")
+        );
+        let late = progress.hint(Duration::from_secs(240), true).unwrap();
+        assert!(late.ends_with("stops in 1m 00s"));
+        assert!(progress.near_limit(Duration::from_secs(240)));
+
+        progress.wrapping_up = true;
+        assert_eq!(
+            progress.hint(Duration::from_secs(240), true).as_deref(),
+            Some("4m 00s · wrapping up…")
+        );
+        assert!(!progress.near_limit(Duration::from_secs(240)));
+    }
+}
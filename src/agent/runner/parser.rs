@@ -0,0 +1,544 @@
+//! Tool call parser
+//!
+//! Extracts tool calls from model output in every format we've seen local
+//! models produce:
+//! - XML: `<use_tool name="x"><param name="p">...</param></use_tool>`
+//! - bare JSON: `{"tool": "x", "params": {...}}` (or `"arguments"`)
+//! - the same JSON inside a ```json fence or surrounded by prose
+//! - a JSON array of calls, or several calls in one message
+//!
+//! Truncated or slightly malformed calls (unclosed tags, missing closing
+//! braces, trailing commas) are repaired when the intent is unambiguous.
+
+use regex::Regex;
+use serde_json::Value;
+
+use super::ToolCall;
+
+/// Extract the first tool call from a model response
+pub fn extract_tool_call(text: &str) -> Option<ToolCall> {
+    extract_tool_calls(text).into_iter().next()
+}
+
+/// Extract every tool call from a model response, in order of appearance
+pub fn extract_tool_calls(text: &str) -> Vec<ToolCall> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+
+    // XML takes precedence: its params may legitimately contain JSON
+    let xml_calls = extract_xml_tool_calls(trimmed);
+    if !xml_calls.is_empty() {
+        return xml_calls;
+    }
+
+    let calls = extract_json_tool_calls(trimmed);
+    if !calls.is_empty() {
+        return calls;
+    }
+
+    heuristic_tool_call(trimmed).into_iter().collect()
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+fn extract_json_tool_calls(text: &str) -> Vec<ToolCall> {
+    // Whole message is a single call or an array of calls
+    if let Ok(value) = serde_json::from_str::<Value>(text) {
+        let calls = calls_from_value(&value);
+        if !calls.is_empty() {
+            return calls;
+        }
+    }
+
+    let scan = scan_json_objects(text);
+    let mut calls = Vec::new();
+    for candidate in &scan.complete {
+        let value = serde_json::from_str::<Value>(candidate)
+            .ok()
+            .or_else(|| repair_json(candidate));
+        if let Some(value) = value {
+            for call in calls_from_value(&value) {
+                push_unique(&mut calls, call);
+            }
+        }
+    }
+
+    // A call cut off mid-generation (e.g. max tokens reached)
+    if let Some(tail) = scan.unclosed {
+        if tail.contains("\"tool\"") {
+            if let Some(value) = repair_json(tail) {
+                for call in calls_from_value(&value) {
+                    tracing::warn!("Recovered truncated tool call: {}", call.tool);
+                    push_unique(&mut calls, call);
+                }
+            }
+        }
+    }
+
+    calls
+}
+
+fn calls_from_value(value: &Value) -> Vec<ToolCall> {
+    match value {
+        Value::Array(items) => items.iter().filter_map(call_from_object).collect(),
+        other => call_from_object(other).into_iter().collect(),
+    }
+}
+
+fn call_from_object(value: &Value) -> Option<ToolCall> {
+    let obj = value.as_object()?;
+    let tool = obj.get("tool").and_then(|v| v.as_str())?.trim().to_string();
+    if tool.is_empty() {
+        return None;
+    }
+    let params = obj
+        .get("params")
+        .cloned()
+        .or_else(|| obj.get("arguments").cloned())
+        .unwrap_or(Value::Null);
+
+    Some(ToolCall { tool, params })
+}
+
+fn push_unique(calls: &mut Vec<ToolCall>, call: ToolCall) {
+    if !calls
+        .iter()
+        .any(|c| c.tool == call.tool && c.params == call.params)
+    {
+        calls.push(call);
+    }
+}
+
+/// Top-level `{...}` spans in a text, plus an unterminated trailing one
+struct JsonScan<'a> {
+    complete: Vec<&'a str>,
+    unclosed: Option<&'a str>,
+}
+
+fn scan_json_objects(text: &str) -> JsonScan<'_> {
+    let mut complete = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escape = false;
+    let mut start: Option<usize> = None;
+
+    for (i, ch) in text.char_indices() {
+        if in_string {
+            if escape {
+                escape = false;
+            } else if ch == '\\' {
+                escape = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match ch {
+            // Quotes only matter inside an object; prose may contain stray ones
+            '"' if depth > 0 => in_string = true,
+            '{' => {
+                if depth == 0 {
+                    start = Some(i);
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    if let Some(start_idx) = start.take() {
+                        complete.push(&text[start_idx..=i]);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let unclosed = if depth > 0 { start.map(|s| &text[s..]) } else { None };
+    JsonScan { complete, unclosed }
+}
+
+/// Best-effort repair of almost-JSON: drops trailing commas, strips a
+/// closing code fence, and closes any open string, array or object.
+pub fn repair_json(input: &str) -> Option<Value> {
+    let input = input.trim().trim_end_matches("```").trim_end();
+    let mut out = String::with_capacity(input.len() + 8);
+    let mut closers: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escape = false;
+
+    for ch in input.chars() {
+        if in_string {
+            out.push(ch);
+            if escape {
+                escape = false;
+            } else if ch == '\\' {
+                escape = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match ch {
+            '"' => {
+                in_string = true;
+                out.push(ch);
+            }
+            '{' => {
+                closers.push('}');
+                out.push(ch);
+            }
+            '[' => {
+                closers.push(']');
+                out.push(ch);
+            }
+            '}' | ']' => {
+                if closers.last() != Some(&ch) {
+                    return None;
+                }
+                closers.pop();
+                trim_trailing_comma(&mut out);
+                out.push(ch);
+            }
+            _ => out.push(ch),
+        }
+    }
+
+    if in_string {
+        if escape {
+            out.pop();
+        }
+        out.push('"');
+    }
+    trim_trailing_comma(&mut out);
+    if out.ends_with(':') {
+        out.push_str("null");
+    }
+    while let Some(closer) = closers.pop() {
+        trim_trailing_comma(&mut out);
+        out.push(closer);
+    }
+
+    serde_json::from_str(&out).ok()
+}
+
+fn trim_trailing_comma(out: &mut String) {
+    let trimmed_len = out.trim_end().len();
+    out.truncate(trimmed_len);
+    if out.ends_with(',') {
+        out.pop();
+    }
+}
+
+/// Bare params without the `{"tool": ...}` wrapper for tools whose
+/// params are distinctive enough to recognise
+fn heuristic_tool_call(text: &str) -> Option<ToolCall> {
+    for candidate in scan_json_objects(text).complete {
+        let Ok(value) = serde_json::from_str::<Value>(candidate) else {
+            continue;
+        };
+        let Some(obj) = value.as_object() else {
+            continue;
+        };
+
+        if obj.contains_key("name") && obj.contains_key("description") && obj.contains_key("content") {
+            tracing::warn!("Heuristic: detected potential skill_create params without wrapper");
+            return Some(ToolCall {
+                tool: "skill_create".to_string(),
+                params: value,
+            });
+        }
+
+        if obj.contains_key("thought") && obj.len() == 1 {
+            tracing::warn!("Heuristic: detected potential think params without wrapper");
+            return Some(ToolCall {
+                tool: "think".to_string(),
+                params: value,
+            });
+        }
+    }
+    None
+}
+
+// ============================================================================
+// XML
+// ============================================================================
+
+fn extract_xml_tool_calls(text: &str) -> Vec<ToolCall> {
+    let Ok(use_tool_open) = Regex::new(r#"<use_tool\s+name\s*=\s*['"]?([^'"\s>]+)['"]?\s*>"#) else {
+        return Vec::new();
+    };
+    let opens: Vec<_> = use_tool_open.captures_iter(text).collect();
+    let mut calls = Vec::new();
+
+    for (idx, captures) in opens.iter().enumerate() {
+        let (Some(whole), Some(name)) = (captures.get(0), captures.get(1)) else {
+            continue;
+        };
+        let body_start = whole.end();
+        let next_open = opens
+            .get(idx + 1)
+            .and_then(|c| c.get(0))
+            .map(|m| m.start())
+            .unwrap_or(text.len());
+
+        // Missing </use_tool> (truncated output): the block runs to the next call
+        let body_end = text[body_start..next_open]
+            .find("</use_tool>")
+            .map(|pos| body_start + pos)
+            .unwrap_or(next_open);
+
+        calls.push(ToolCall {
+            tool: name.as_str().to_string(),
+            params: parse_xml_params(&text[body_start..body_end]),
+        });
+    }
+
+    calls
+}
+
+fn parse_xml_params(body: &str) -> Value {
+    let Ok(param_open) = Regex::new(r#"<param\s+name\s*=\s*['"]?([^'"\s>]+)['"]?\s*>"#) else {
+        return Value::Object(serde_json::Map::new());
+    };
+    let opens: Vec<_> = param_open.captures_iter(body).collect();
+    let mut params = serde_json::Map::new();
+
+    for (idx, captures) in opens.iter().enumerate() {
+        let (Some(whole), Some(name)) = (captures.get(0), captures.get(1)) else {
+            continue;
+        };
+        let value_start = whole.end();
+        let next_open = opens
+            .get(idx + 1)
+            .and_then(|c| c.get(0))
+            .map(|m| m.start())
+            .unwrap_or(body.len());
+
+        let value_end = body[value_start..]
+            .find("</param>")
+            .map(|pos| value_start + pos)
+            .filter(|&end| end <= next_open)
+            .unwrap_or(next_open);
+
+        params.insert(
+            name.as_str().to_string(),
+            parse_xml_value(&body[value_start..value_end]),
+        );
+    }
+
+    Value::Object(params)
+}
+
+/// Typed value for a `<param>` body; multi-line strings keep their
+/// inner formatting (only the newline after the opening tag and the
+/// indentation before the closing tag are dropped)
+fn parse_xml_value(raw: &str) -> Value {
+    let raw = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|r| r.trim_end().strip_suffix("]]>"))
+        .unwrap_or(raw);
+    let value = raw.trim();
+
+    match value {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        "null" => return Value::Null,
+        _ => {}
+    }
+
+    if let Ok(int_val) = value.parse::<i64>() {
+        return Value::Number(int_val.into());
+    }
+    if value.contains('.') {
+        if let Some(number) = value.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+            return Value::Number(number);
+        }
+    }
+    if (value.starts_with('{') && value.ends_with('}'))
+        || (value.starts_with('[') && value.ends_with(']'))
+    {
+        if let Ok(parsed) = serde_json::from_str(value) {
+            return parsed;
+        }
+    }
+
+    if !value.contains('\n') {
+        return Value::String(value.to_string());
+    }
+
+    let without_lead = raw
+        .strip_prefix("\r\n")
+        .or_else(|| raw.strip_prefix('\n'))
+        .unwrap_or(raw);
+    let without_tail = match without_lead.rfind('\n') {
+        Some(pos) if without_lead[pos + 1..].trim().is_empty() => &without_lead[..pos],
+        _ => without_lead,
+    };
+    Value::String(without_tail.trim_end_matches('\r').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn single(text: &str) -> ToolCall {
+        let calls = extract_tool_calls(text);
+        assert_eq!(calls.len(), 1, "expected one call in {:?}, got {:?}", text, calls);
+        calls.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_plain_json() {
+        let call = single(r#"{"tool": "file_read", "params": {"path": "a.txt"}}"#);
+        assert_eq!(call.tool, "file_read");
+        assert_eq!(call.params, json!({"path": "a.txt"}));
+    }
+
+    #[test]
+    fn test_arguments_alias_and_missing_params() {
+        let call = single(r#"{"tool": "web_search", "arguments": {"query": "rust"}}"#);
+        assert_eq!(call.params, json!({"query": "rust"}));
+        let call = single(r#"{"tool": "system_info"}"#);
+        assert_eq!(call.params, Value::Null);
+    }
+
+    #[test]
+    fn test_fenced_json_with_prose() {
+        let text = "Je vais lire le fichier.\n```json\n{\"tool\": \"file_read\", \"params\": {\"path\": \"src/main.rs\"}}\n```\n";
+        let call = single(text);
+        assert_eq!(call.tool, "file_read");
+        assert_eq!(call.params["path"], "src/main.rs");
+    }
+
+    #[test]
+    fn test_braces_inside_strings() {
+        let text = r#"{"tool": "file_write", "params": {"path": "a.rs", "content": "fn main() { println!(\"}\"); }"}}"#;
+        let call = single(text);
+        assert_eq!(call.params["content"], "fn main() { println!(\"}\"); }");
+    }
+
+    #[test]
+    fn test_prose_quotes_do_not_break_scan() {
+        let text = r#"Il a dit "bonjour" puis: {"tool": "think", "params": {"thought": "ok"}}"#;
+        assert_eq!(single(text).tool, "think");
+    }
+
+    #[test]
+    fn test_json_without_tool_is_not_a_call() {
+        assert!(extract_tool_calls(r#"Voici la config: {"port": 8080}"#).is_empty());
+        assert!(extract_tool_calls("Bonjour, comment puis-je aider ?").is_empty());
+        assert!(extract_tool_calls("").is_empty());
+    }
+
+    #[test]
+    fn test_multiple_json_calls() {
+        let text = "```json\n{\"tool\": \"file_read\", \"params\": {\"path\": \"a\"}}\n```\npuis\n```json\n{\"tool\": \"file_read\", \"params\": {\"path\": \"b\"}}\n```";
+        let calls = extract_tool_calls(text);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].params["path"], "a");
+        assert_eq!(calls[1].params["path"], "b");
+    }
+
+    #[test]
+    fn test_json_array_of_calls() {
+        let text = r#"[{"tool": "git_status", "params": {}}, {"tool": "git_diff", "params": {}}]"#;
+        let calls = extract_tool_calls(text);
+        let names: Vec<&str> = calls.iter().map(|c| c.tool.as_str()).collect();
+        assert_eq!(names, vec!["git_status", "git_diff"]);
+    }
+
+    #[test]
+    fn test_duplicate_calls_are_merged() {
+        let text = r#"{"tool": "git_status", "params": {}} {"tool": "git_status", "params": {}}"#;
+        assert_eq!(extract_tool_calls(text).len(), 1);
+    }
+
+    #[test]
+    fn test_recovers_truncated_json() {
+        let call = single(r##"{"tool": "file_write", "params": {"path": "notes.md", "content": "# Titre\nligne"##);
+        assert_eq!(call.tool, "file_write");
+        assert_eq!(call.params["content"], "# Titre\nligne");
+
+        let call = single("```json\n{\"tool\": \"web_search\", \"params\": {\"query\": \"rust\"\n```");
+        assert_eq!(call.params["query"], "rust");
+    }
+
+    #[test]
+    fn test_recovers_trailing_commas() {
+        let call = single(r#"{"tool": "grep", "params": {"pattern": "fn", "path": "src",},}"#);
+        assert_eq!(call.params, json!({"pattern": "fn", "path": "src"}));
+    }
+
+    #[test]
+    fn test_repair_json_rejects_mismatched_brackets() {
+        assert!(repair_json(r#"{"a": [1, 2}"#).is_none());
+        assert_eq!(repair_json(r#"{"a": "#), Some(json!({"a": null})));
+    }
+
+    #[test]
+    fn test_xml_single_call_with_types() {
+        let text = r#"<use_tool name="file_list"><param name="path">/tmp</param><param name="recursive">true</param><param name="depth">2</param></use_tool>"#;
+        let call = single(text);
+        assert_eq!(call.tool, "file_list");
+        assert_eq!(call.params, json!({"path": "/tmp", "recursive": true, "depth": 2}));
+    }
+
+    #[test]
+    fn test_xml_multiline_content_preserved() {
+        let text = "<use_tool name=\"file_write\">\n<param name=\"path\">app.py</param>\n<param name=\"content\">\ndef main():\n    if x > 1 and y < 2:\n        print(\"{}\")\n\nmain()\n</param>\n</use_tool>";
+        let call = single(text);
+        assert_eq!(
+            call.params["content"],
+            "def main():\n    if x > 1 and y < 2:\n        print(\"{}\")\n\nmain()"
+        );
+    }
+
+    #[test]
+    fn test_xml_json_param_and_cdata() {
+        let text = r#"<use_tool name='todo_write'><param name='todos'>[{"task": "a"}]</param><param name='note'><![CDATA[<b>gras</b>]]></param></use_tool>"#;
+        let call = single(text);
+        assert_eq!(call.params["todos"], json!([{"task": "a"}]));
+        assert_eq!(call.params["note"], "<b>gras</b>");
+    }
+
+    #[test]
+    fn test_xml_multiple_calls() {
+        let text = r#"D'abord <use_tool name="git_status"></use_tool> puis <use_tool name="git_log"><param name="limit">5</param></use_tool>"#;
+        let calls = extract_tool_calls(text);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].tool, "git_status");
+        assert_eq!(calls[1].params["limit"], 5);
+    }
+
+    #[test]
+    fn test_xml_recovers_unclosed_tags() {
+        let text = "<use_tool name=\"file_write\">\n<param name=\"path\">a.txt</param>\n<param name=\"content\">bonjour";
+        let call = single(text);
+        assert_eq!(call.params, json!({"path": "a.txt", "content": "bonjour"}));
+
+        let text = r#"<use_tool name="bash"><param name="command">ls<param name="working_dir">/tmp</param></use_tool>"#;
+        let call = single(text);
+        assert_eq!(call.params, json!({"command": "ls", "working_dir": "/tmp"}));
+    }
+
+    #[test]
+    fn test_xml_wins_over_embedded_json() {
+        let text = r#"<use_tool name="file_write"><param name="content">{"tool": "fake", "params": {}}</param></use_tool>"#;
+        let call = single(text);
+        assert_eq!(call.tool, "file_write");
+    }
+
+    #[test]
+    fn test_heuristic_bare_params() {
+        let call = single(r#"{"thought": "Je dois d'abord lister les fichiers"}"#);
+        assert_eq!(call.tool, "think");
+    }
+}
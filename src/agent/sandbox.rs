@@ -0,0 +1,354 @@
+//! WASI sandbox for untrusted code
+//!
+//! Runs a WebAssembly program (a WASI preview 1 module or a preview 2
+//! command component) inside wasmtime. The guest only sees what it is
+//! granted: preopened directories, read-only or writable, and the network
+//! when allowed. There is no process spawning in WASI, so a sandboxed tool
+//! sits between read-only tools and full shell access.
+//!
+//! Preview 1 modules have no sockets; only components can use a network
+//! grant.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use thiserror::Error;
+use wasmtime::component::{Component, Linker as ComponentLinker, ResourceTable};
+use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtx, WasiCtxBuilder, WasiView};
+
+/// Captured output is truncated past this size
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("Failed to read WebAssembly file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a WebAssembly module or component: {0}")]
+    NotWasm(PathBuf),
+    #[error("Cannot grant access to {path}: {reason}")]
+    Grant { path: PathBuf, reason: String },
+    #[error("Sandboxed program timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("WebAssembly error: {0}")]
+    Wasm(String),
+}
+
+impl From<wasmtime::Error> for SandboxError {
+    fn from(e: wasmtime::Error) -> Self {
+        Self::Wasm(format!("{:#}", e))
+    }
+}
+
+/// A host directory visible to the guest
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirGrant {
+    pub host: PathBuf,
+    /// Path the guest opens it with, e.g. `/workspace`
+    pub guest: String,
+    pub writable: bool,
+}
+
+/// What a sandboxed program may access
+#[derive(Debug, Clone, PartialEq)]
+pub struct SandboxGrants {
+    pub dirs: Vec<DirGrant>,
+    /// Outgoing TCP/UDP and DNS (components only)
+    pub network: bool,
+    pub env: Vec<(String, String)>,
+    pub timeout: Duration,
+    pub max_memory_bytes: usize,
+}
+
+impl Default for SandboxGrants {
+    fn default() -> Self {
+        Self {
+            dirs: Vec::new(),
+            network: false,
+            env: Vec::new(),
+            timeout: Duration::from_secs(60),
+            max_memory_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+impl SandboxGrants {
+    pub fn read_dir(mut self, host: impl Into<PathBuf>, guest: impl Into<String>) -> Self {
+        self.dirs.push(DirGrant {
+            host: host.into(),
+            guest: guest.into(),
+            writable: false,
+        });
+        self
+    }
+
+    pub fn write_dir(mut self, host: impl Into<PathBuf>, guest: impl Into<String>) -> Self {
+        self.dirs.push(DirGrant {
+            host: host.into(),
+            guest: guest.into(),
+            writable: true,
+        });
+        self
+    }
+
+    pub fn with_network(mut self, network: bool) -> Self {
+        self.network = network;
+        self
+    }
+}
+
+/// Result of a sandboxed run
+#[derive(Debug, Clone)]
+pub struct SandboxOutput {
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+struct ModuleState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+struct ComponentState {
+    ctx: WasiCtx,
+    table: ResourceTable,
+    limits: StoreLimits,
+}
+
+impl WasiView for ComponentState {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.ctx
+    }
+}
+
+/// Whether the bytes are a component rather than a core module
+fn is_component(bytes: &[u8]) -> Option<bool> {
+    if bytes.len() < 8 || &bytes[..4] != b"\0asm" {
+        return None;
+    }
+    // Core modules are version 1; components use a different version/layer
+    Some(bytes[4..8] != [1, 0, 0, 0])
+}
+
+fn wasi_builder(
+    grants: &SandboxGrants,
+    args: &[String],
+    stdin: Vec<u8>,
+    stdout: &MemoryOutputPipe,
+    stderr: &MemoryOutputPipe,
+) -> Result<WasiCtxBuilder, SandboxError> {
+    let mut builder = WasiCtxBuilder::new();
+    builder
+        .args(args)
+        .envs(&grants.env)
+        .stdin(MemoryInputPipe::new(stdin))
+        .stdout(stdout.clone())
+        .stderr(stderr.clone());
+    for dir in &grants.dirs {
+        let (dir_perms, file_perms) = if dir.writable {
+            (DirPerms::all(), FilePerms::all())
+        } else {
+            (DirPerms::READ, FilePerms::READ)
+        };
+        builder
+            .preopened_dir(&dir.host, &dir.guest, dir_perms, file_perms)
+            .map_err(|e| SandboxError::Grant {
+                path: dir.host.clone(),
+                reason: e.to_string(),
+            })?;
+    }
+    if grants.network {
+        builder.inherit_network().allow_ip_name_lookup(true);
+    }
+    Ok(builder)
+}
+
+/// Exit code of a finished run, treating `proc_exit` as a normal exit
+fn exit_code(result: wasmtime::Result<()>, timeout: Duration) -> Result<i32, SandboxError> {
+    match result {
+        Ok(()) => Ok(0),
+        Err(e) => {
+            if let Some(exit) = e.downcast_ref::<I32Exit>() {
+                return Ok(exit.0);
+            }
+            if e.downcast_ref::<wasmtime::Trap>() == Some(&wasmtime::Trap::Interrupt) {
+                return Err(SandboxError::Timeout(timeout));
+            }
+            Err(e.into())
+        }
+    }
+}
+
+fn output_text(pipe: &MemoryOutputPipe) -> String {
+    String::from_utf8_lossy(&pipe.contents()).to_string()
+}
+
+/// Run a WebAssembly program with `args` (argv[0] included) and `stdin`
+///
+/// Blocks until the program exits or its timeout expires.
+pub fn run_wasm(
+    path: &Path,
+    args: &[String],
+    stdin: Vec<u8>,
+    grants: &SandboxGrants,
+) -> Result<SandboxOutput, SandboxError> {
+    let bytes = std::fs::read(path)?;
+    let component =
+        is_component(&bytes).ok_or_else(|| SandboxError::NotWasm(path.to_path_buf()))?;
+
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config)?;
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(grants.max_memory_bytes)
+        .build();
+    let stdout = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+    let stderr = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+    let mut builder = wasi_builder(grants, args, stdin, &stdout, &stderr)?;
+
+    // Interrupts the guest when the timeout expires; returns early once the
+    // run is over and `_done` is dropped
+    let (_done, done_rx) = mpsc::channel::<()>();
+    let timer_engine = engine.clone();
+    let timeout = grants.timeout;
+    std::thread::spawn(move || {
+        if done_rx.recv_timeout(timeout) == Err(mpsc::RecvTimeoutError::Timeout) {
+            timer_engine.increment_epoch();
+        }
+    });
+
+    let result = if component {
+        let component = Component::new(&engine, &bytes)?;
+        let mut linker = ComponentLinker::<ComponentState>::new(&engine);
+        wasmtime_wasi::add_to_linker_sync(&mut linker)?;
+        let mut store = Store::new(
+            &engine,
+            ComponentState {
+                ctx: builder.build(),
+                table: ResourceTable::new(),
+                limits,
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(1);
+        let command =
+            wasmtime_wasi::bindings::sync::Command::instantiate(&mut store, &component, &linker)?;
+        match command.wasi_cli_run().call_run(&mut store) {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(())) => Err(I32Exit(1).into()),
+            Err(e) => Err(e),
+        }
+    } else {
+        let module = Module::new(&engine, &bytes)?;
+        let mut linker = Linker::<ModuleState>::new(&engine);
+        preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi)?;
+        let mut store = Store::new(
+            &engine,
+            ModuleState {
+                wasi: builder.build_p1(),
+                limits,
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(1);
+        let instance = linker.instantiate(&mut store, &module)?;
+        let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+        start.call(&mut store, ())
+    };
+
+    Ok(SandboxOutput {
+        exit_code: exit_code(result, timeout)?,
+        stdout: output_text(&stdout),
+        stderr: output_text(&stderr),
+    })
+}
+
+/// Async wrapper running [`run_wasm`] on a blocking thread
+pub async fn run_wasm_async(
+    path: PathBuf,
+    args: Vec<String>,
+    stdin: Vec<u8>,
+    grants: SandboxGrants,
+) -> Result<SandboxOutput, SandboxError> {
+    tokio::task::spawn_blocking(move || run_wasm(&path, &args, stdin, &grants))
+        .await
+        .map_err(|e| SandboxError::Wasm(format!("sandbox thread failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes "hi\n" to stdout, then tries to open `secret.txt` in the first
+    /// preopened directory (fd 3) and exits with the resulting errno
+    const PROBE: &str = r#"(module
+        (import "wasi_snapshot_preview1" "fd_write"
+            (func $fd_write (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "path_open"
+            (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 8) "hi\n")
+        (data (i32.const 32) "secret.txt")
+        (func (export "_start")
+            (i32.store (i32.const 0) (i32.const 8))
+            (i32.store (i32.const 4) (i32.const 3))
+            (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 16)))
+            (call $proc_exit
+                (call $path_open (i32.const 3) (i32.const 0) (i32.const 32) (i32.const 10)
+                    (i32.const 0) (i64.const 2) (i64.const 0) (i32.const 0) (i32.const 48)))))"#;
+
+    const SPIN: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "_start") (loop $l (br $l))))"#;
+
+    fn write_module(dir: &Path, name: &str, wat: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_grants_limit_file_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = write_module(dir.path(), "probe.wasm", PROBE);
+        let data = tempfile::tempdir().unwrap();
+        std::fs::write(data.path().join("secret.txt"), "42").unwrap();
+        let args = vec!["probe".to_string()];
+
+        // Without a grant fd 3 doesn't exist: EBADF (8)
+        let denied = run_wasm(&module, &args, Vec::new(), &SandboxGrants::default()).unwrap();
+        assert_eq!(denied.stdout, "hi\n");
+        assert_eq!(denied.exit_code, 8);
+
+        let granted = SandboxGrants::default().read_dir(data.path(), "/data");
+        let allowed = run_wasm(&module, &args, Vec::new(), &granted).unwrap();
+        assert_eq!(allowed.exit_code, 0);
+    }
+
+    #[test]
+    fn test_timeout_interrupts_guest() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = write_module(dir.path(), "spin.wasm", SPIN);
+        let grants = SandboxGrants {
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        assert!(matches!(
+            run_wasm(&module, &[], Vec::new(), &grants),
+            Err(SandboxError::Timeout(_))
+        ));
+        assert!(matches!(
+            run_wasm(&dir.path().join("missing.wasm"), &[], Vec::new(), &grants),
+            Err(SandboxError::Io(_))
+        ));
+    }
+}
@@ -1,15 +1,113 @@
 use async_trait::async_trait;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::PathBuf;
+use crate::agent::permissions::PermissionLevel;
 use crate::agent::tools::{Tool, ToolResult, ToolError};
-use tokio::process::Command;
 
 pub mod loader;
 pub mod registry;
+pub mod runtime;
 
 pub use registry::SkillRegistry;
 
+/// Scripts run when a skill is executed, in order of precedence
+const ENTRYPOINTS: [&str; 7] = [
+    "main.py", "index.js", "run.sh", "run.py", "main.ts", "run.ps1", "main.wasm",
+];
+
+/// Effective permissions of each registered skill tool, read by
+/// `get_tool_permission` and focus mode
+static DECLARED_PERMISSIONS: Lazy<DashMap<String, Vec<SkillPermission>>> =
+    Lazy::new(DashMap::new);
+
+/// Capability a skill declares in its front-matter (`permissions: network, write`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillPermission {
+    /// Makes network requests
+    Network,
+    /// Creates or modifies files outside its own folder
+    Write,
+    /// Runs scripts or shell commands
+    Shell,
+    /// Runs WebAssembly in the sandbox; implied for `.wasm` skills that
+    /// declare nothing else
+    Sandbox,
+}
+
+impl SkillPermission {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "network" | "net" => Some(Self::Network),
+            "write" | "file_write" => Some(Self::Write),
+            "shell" | "exec" | "execute" => Some(Self::Shell),
+            "sandbox" | "wasm" => Some(Self::Sandbox),
+            _ => None,
+        }
+    }
+
+    /// Permission level checked by the `PermissionManager`
+    pub fn level(&self) -> PermissionLevel {
+        match self {
+            Self::Network => PermissionLevel::Network,
+            Self::Write => PermissionLevel::WriteFile,
+            Self::Shell => PermissionLevel::ExecuteUnsafe,
+            Self::Sandbox => PermissionLevel::ExecuteSafe,
+        }
+    }
+
+    pub fn label(&self, is_en: bool) -> &'static str {
+        match (self, is_en) {
+            (Self::Network, true) => "Network",
+            (Self::Network, false) => "Reseau",
+            (Self::Write, true) => "File write",
+            (Self::Write, false) => "Ecriture",
+            (Self::Shell, _) => "Shell",
+            (Self::Sandbox, true) => "Sandbox",
+            (Self::Sandbox, false) => "Bac a sable",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        self.level().icon()
+    }
+}
+
+/// Most sensitive level among permissions, `None` when there are none
+pub fn required_level(permissions: &[SkillPermission]) -> Option<PermissionLevel> {
+    permissions
+        .iter()
+        .map(SkillPermission::level)
+        .max_by_key(|level| level.rank())
+}
+
+/// Permission level a registered skill tool requires; `None` for a skill
+/// that is not loaded
+pub fn declared_level(tool: &str) -> Option<PermissionLevel> {
+    DECLARED_PERMISSIONS
+        .get(tool)
+        .map(|permissions| required_level(&permissions).unwrap_or(PermissionLevel::ReadOnly))
+}
+
+/// Whether a registered skill tool has `permission` among its effective ones
+pub fn declares(tool: &str, permission: SkillPermission) -> bool {
+    DECLARED_PERMISSIONS
+        .get(tool)
+        .is_some_and(|permissions| permissions.contains(&permission))
+}
+
+/// Record a skill's effective permissions (none means read-only)
+pub(crate) fn record_permissions(skill: &Skill) {
+    DECLARED_PERMISSIONS.insert(skill.name.clone(), skill.effective_permissions());
+}
+
+pub(crate) fn forget_permissions(tool: &str) {
+    DECLARED_PERMISSIONS.remove(tool);
+}
+
 /// Represents a loaded skill
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
@@ -18,9 +116,65 @@ pub struct Skill {
     pub content: String,
     pub disable_auto_invoke: bool,
     pub allowed_tools: Vec<String>,
+    /// Permissions declared in the front-matter
+    #[serde(default)]
+    pub permissions: Vec<SkillPermission>,
     pub path: PathBuf,
 }
 
+impl Skill {
+    /// Script run by the skill, if any
+    pub fn entrypoint(&self) -> Option<PathBuf> {
+        ENTRYPOINTS
+            .iter()
+            .map(|exe| self.path.join(exe))
+            .find(|path| path.exists())
+    }
+
+    /// Declared permissions, plus `Shell` for a skill that runs a script
+    /// without declaring it. WebAssembly entrypoints are sandboxed: they
+    /// don't count as scripts, but still run code, so one declaring nothing
+    /// gets `Sandbox` and is never approved as read-only.
+    pub fn effective_permissions(&self) -> Vec<SkillPermission> {
+        let mut permissions = self.permissions.clone();
+        let Some(entrypoint) = self.entrypoint() else {
+            return permissions;
+        };
+        let is_wasm = entrypoint.extension().is_some_and(|ext| ext == "wasm");
+        if is_wasm {
+            if permissions.is_empty() {
+                permissions.push(SkillPermission::Sandbox);
+            }
+        } else if !permissions.contains(&SkillPermission::Shell) {
+            permissions.push(SkillPermission::Shell);
+        }
+        permissions
+    }
+}
+
+/// Permissions a skill tool call requires, `None` when it needs none
+///
+/// For `skill_create` these are the permissions the new skill declares, shown
+/// in the review dialog; for invocations, those of the invoked skill.
+pub fn skill_call_permissions(
+    registry: &SkillRegistry,
+    tool: &str,
+    params: &Value,
+) -> Option<Vec<SkillPermission>> {
+    let permissions = match tool {
+        "skill_list" => return None,
+        "skill_create" => params["permissions"]
+            .as_array()?
+            .iter()
+            .filter_map(|p| p.as_str().and_then(SkillPermission::parse))
+            .collect(),
+        "skill_invoke" => registry.find(params["name"].as_str()?)?.effective_permissions(),
+        name if name.starts_with("skill_") => registry.get(name)?.effective_permissions(),
+        _ => return None,
+    };
+    Some(permissions).filter(|p| !p.is_empty())
+}
+
 /// A tool that wraps a Skill
 pub struct SkillTool {
     pub skill: Skill,
@@ -43,146 +197,85 @@ impl Tool for SkillTool {
     }
 
     fn parameters_schema(&self) -> Value {
-        serde_json::json!({
-            "type": "object",
-            "properties": {},
-            "description": "This skill takes no parameters. Invoking it activates the skill's knowledge."
-        })
+        if self.skill.entrypoint().is_some() {
+            serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "additionalProperties": true,
+                "description": "Parameters are passed to the skill's script as JSON (first argument and stdin). See the skill instructions for the expected fields."
+            })
+        } else {
+            serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "description": "This skill takes no parameters. Invoking it activates the skill's knowledge."
+            })
+        }
     }
 
-    async fn execute(&self, _params: Value) -> Result<ToolResult, ToolError> {
-        // Check for executable files in the skill directory
-        let executables = ["main.py", "index.js", "run.sh", "run.py", "main.ts"];
-        
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
         tracing::info!("Skill '{}' path: {}", self.skill.name, self.skill.path.display());
-        
-        for exe in executables {
-            // skill.path is now the skill directory directly (absolute path)
-            let exe_path = self.skill.path.join(exe);
-            if exe_path.exists() {
-                tracing::info!("Executing skill script: {}", exe_path.display());
-                
-                let mut cmd = if exe.ends_with(".py") {
-                    // Try to find a working Python interpreter
-                    // On Windows, 'python' may be a Windows Store stub that fails
-                    // We try multiple options and use the first that works
-                    let python_variants = if cfg!(windows) {
-                        vec!["python", "python3", "py"]
-                    } else {
-                        vec!["python3", "python"]
-                    };
-                    
-                    let mut working_cmd = None;
-                    for py in &python_variants {
-                        // Quick test: try to run python --version
-                        let test_result = std::process::Command::new(py)
-                            .arg("--version")
-                            .output();
-                        
-                        if let Ok(output) = test_result {
-                            if output.status.success() {
-                                tracing::debug!("Found working Python: {}", py);
-                                let mut c = Command::new(*py);
-                                c.arg(&exe_path);
-                                working_cmd = Some(c);
-                                break;
-                            }
-                        }
-                    }
-                    
-                    working_cmd.unwrap_or_else(|| {
-                        tracing::warn!("No working Python found, falling back to 'python'");
-                        let mut c = Command::new("python");
-                        c.arg(&exe_path);
-                        c
-                    })
-                } else if exe.ends_with(".js") {
-                    let mut c = Command::new("node");
-                    c.arg(&exe_path);
-                    c
-                } else if exe.ends_with(".ts") {
-                    let mut c = Command::new("ts-node");
-                    c.arg(&exe_path);
-                    c
-                } else {
-                    let mut c = Command::new("bash");
-                    c.arg(&exe_path);
-                    c
-                };
-                
-                // Set working directory to skill folder
-                if let Some(parent) = exe_path.parent() {
-                    cmd.current_dir(parent);
-                }
-                
-                match cmd.output().await {
-                    Ok(output) => {
-                        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                        let success = output.status.success();
-                        let exit_code = output.status.code();
-                        
-                        // Debug logging
-                        tracing::debug!(
-                            "Skill '{}' finished: success={}, exit_code={:?}, stdout_len={}, stderr_len={}",
-                            self.skill.name, success, exit_code, stdout.len(), stderr.len()
-                        );
-                        if !stderr.is_empty() {
-                            tracing::warn!("Skill '{}' stderr: {}", self.skill.name, stderr);
-                        }
-                        tracing::info!("Skill '{}' stdout: {}", self.skill.name, stdout.trim());
-                        
-                        // Clear, structured output format for AI consumption
-                        let result_message = if success {
-                            if stderr.is_empty() {
-                                format!(
-                                    "✅ SKILL '{}' EXECUTED SUCCESSFULLY\n\n=== OUTPUT ===\n{}\n=== END OUTPUT ===",
-                                    self.skill.name,
-                                    stdout.trim()
-                                )
-                            } else {
-                                format!(
-                                    "✅ SKILL '{}' EXECUTED (with warnings)\n\n=== OUTPUT ===\n{}\n=== WARNINGS ===\n{}\n=== END ===",
-                                    self.skill.name,
-                                    stdout.trim(),
-                                    stderr.trim()
-                                )
-                            }
-                        } else {
-                            format!(
-                                "❌ SKILL '{}' FAILED\n\n=== ERROR ===\n{}\n=== OUTPUT (partial) ===\n{}\n=== END ===",
-                                self.skill.name,
-                                stderr.trim(),
-                                stdout.trim()
-                            )
-                        };
-                        
-                        return Ok(ToolResult {
-                            success,
-                            data: serde_json::json!({
-                                "skill_name": self.skill.name,
-                                "stdout": stdout,
-                                "stderr": stderr,
-                                "exit_code": output.status.code()
-                            }),
-                            message: result_message,
-                        });
-                    },
-                    Err(e) => {
-                        return Err(ToolError::ExecutionFailed(format!("Failed to execute skill script: {}", e)));
-                    }
-                }
-            }
+
+        // No script: the skill only provides instructions
+        let Some(entrypoint) = self.skill.entrypoint() else {
+            return Ok(ToolResult {
+                success: true,
+                data: serde_json::json!({
+                    "skill_name": self.skill.name,
+                    "content": self.skill.content
+                }),
+                message: format!("Skill '{}' active. Instructions:\n{}", self.skill.name, self.skill.content),
+            });
+        };
+
+        tracing::info!("Executing skill script: {}", entrypoint.display());
+        let run = runtime::run_skill_script(&self.skill.path, &entrypoint, &params, &self.skill.permissions)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        let success = run.success();
+        let stdout = run.stdout.trim();
+        let stderr = run.stderr.trim();
+
+        tracing::debug!(
+            "Skill '{}' finished: runtime={}, success={}, exit_code={:?}, stdout_len={}, stderr_len={}",
+            self.skill.name, run.runtime.label(), success, run.exit_code, stdout.len(), stderr.len()
+        );
+        if !stderr.is_empty() {
+            tracing::warn!("Skill '{}' stderr: {}", self.skill.name, stderr);
         }
-        
-        // Fallback: just return instructions if no executable found
+
+        // Clear, structured output format for AI consumption
+        let result_message = if success {
+            if stderr.is_empty() {
+                format!(
+                    "✅ SKILL '{}' EXECUTED SUCCESSFULLY\n\n=== OUTPUT ===\n{}\n=== END OUTPUT ===",
+                    self.skill.name, stdout
+                )
+            } else {
+                format!(
+                    "✅ SKILL '{}' EXECUTED (with warnings)\n\n=== OUTPUT ===\n{}\n=== WARNINGS ===\n{}\n=== END ===",
+                    self.skill.name, stdout, stderr
+                )
+            }
+        } else {
+            format!(
+                "❌ SKILL '{}' FAILED\n\n=== ERROR ===\n{}\n=== OUTPUT (partial) ===\n{}\n=== END ===",
+                self.skill.name, stderr, stdout
+            )
+        };
+
         Ok(ToolResult {
-            success: true,
+            success,
             data: serde_json::json!({
                 "skill_name": self.skill.name,
-                "content": self.skill.content
+                "runtime": run.runtime.label(),
+                "interpreter": run.interpreter,
+                "stdout": run.stdout,
+                "stderr": run.stderr,
+                "exit_code": run.exit_code,
+                "output": run.output
             }),
-            message: format!("Skill '{}' active. Instructions:\n{}", self.skill.name, self.skill.content),
+            message: result_message,
         })
     }
 }
@@ -217,6 +310,7 @@ pub fn parse_skill(content: &str, path: PathBuf) -> Result<Skill, SkillError> {
     let mut description = String::new();
     let mut disable_auto_invoke = false;
     let mut allowed_tools = Vec::new();
+    let mut permissions = Vec::new();
 
     for line in frontmatter_str.lines() {
         let line = line.trim();
@@ -241,6 +335,19 @@ pub fn parse_skill(content: &str, path: PathBuf) -> Result<Skill, SkillError> {
                         .filter(|s| !s.is_empty())
                         .collect();
                 }
+                "permissions" => {
+                    // Same inline list syntax; unknown values are rejected rather
+                    // than silently granting less than the skill expects
+                    let clean_value = value.trim_matches(|c| c == '[' || c == ']');
+                    for item in clean_value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                        let permission = SkillPermission::parse(item).ok_or_else(|| {
+                            SkillError::InvalidFrontmatter(format!("Unknown permission '{}'", item))
+                        })?;
+                        if !permissions.contains(&permission) {
+                            permissions.push(permission);
+                        }
+                    }
+                }
                 _ => {} // Ignore unknown keys
             }
         }
@@ -271,6 +378,50 @@ pub fn parse_skill(content: &str, path: PathBuf) -> Result<Skill, SkillError> {
         content: markdown_content,
         disable_auto_invoke,
         allowed_tools,
+        permissions,
         path,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skill_permissions() {
+        let content = "---\nname: weather\ndescription: Forecasts\npermissions: [network, shell]\n---\nRun main.py";
+        let skill = parse_skill(content, PathBuf::from("/nonexistent/weather")).unwrap();
+        assert_eq!(skill.name, "skill_weather");
+        assert_eq!(skill.permissions, vec![SkillPermission::Network, SkillPermission::Shell]);
+        assert_eq!(required_level(&skill.permissions), Some(PermissionLevel::Network));
+        assert_eq!(required_level(&[SkillPermission::Write]), Some(PermissionLevel::WriteFile));
+
+        // A WebAssembly skill declaring nothing still needs approval
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.wasm"), b"\0asm").unwrap();
+        let wasm = parse_skill("---\nname: wasm\n---\n", dir.path().to_path_buf()).unwrap();
+        assert_eq!(wasm.effective_permissions(), vec![SkillPermission::Sandbox]);
+        assert_eq!(
+            required_level(&wasm.effective_permissions()),
+            Some(PermissionLevel::ExecuteSafe)
+        );
+
+        let invalid = "---\nname: x\npermissions: network, root\n---\n";
+        assert!(matches!(
+            parse_skill(invalid, PathBuf::from("x")),
+            Err(SkillError::InvalidFrontmatter(_))
+        ));
+    }
+
+    #[test]
+    fn test_declared_level() {
+        let content = "---\nname: level_probe\npermissions: [network]\n---\n";
+        let skill = parse_skill(content, PathBuf::from("/nonexistent/level_probe")).unwrap();
+        record_permissions(&skill);
+        assert_eq!(declared_level("skill_level_probe"), Some(PermissionLevel::Network));
+        assert!(declares("skill_level_probe", SkillPermission::Network));
+        assert!(!declares("skill_level_probe", SkillPermission::Shell));
+        forget_permissions("skill_level_probe");
+        assert_eq!(declared_level("skill_level_probe"), None);
+    }
+}
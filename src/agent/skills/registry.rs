@@ -1,7 +1,7 @@
 use std::sync::Arc;
 use dashmap::DashMap;
 use crate::agent::tools::ToolRegistry;
-use crate::agent::skills::{Skill, SkillTool};
+use crate::agent::skills::{forget_permissions, record_permissions, Skill, SkillTool};
 
 /// Registry for managing available skills
 pub struct SkillRegistry {
@@ -17,6 +17,7 @@ impl SkillRegistry {
 
     /// Add a skill to the registry
     pub async fn register(&self, skill: Skill) {
+        record_permissions(&skill);
         self.skills.insert(skill.name.clone(), skill);
     }
 
@@ -25,8 +26,24 @@ impl SkillRegistry {
         self.skills.get(name).map(|r| r.value().clone())
     }
 
+    /// Find a skill by the name a model or user typed: `skill_git_master`,
+    /// `git_master` or `git-master`
+    pub fn find(&self, name: &str) -> Option<Skill> {
+        let target = name.trim().to_lowercase().replace('-', "_");
+        let target = if target.starts_with("skill_") {
+            target
+        } else {
+            format!("skill_{}", target)
+        };
+        self.skills
+            .iter()
+            .find(|r| r.key().to_lowercase() == target)
+            .map(|r| r.value().clone())
+    }
+
     /// Remove a skill from the registry
     pub fn remove(&self, name: &str) {
+        forget_permissions(name);
         self.skills.remove(name);
     }
     
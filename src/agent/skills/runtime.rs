@@ -0,0 +1,312 @@
+//! Skill script runtime
+//!
+//! Runs a skill's entrypoint with the interpreter matching its extension,
+//! after checking that interpreter is installed. The script runs in the skill
+//! folder and receives its parameters as JSON, both as the first argument and
+//! on stdin. A JSON object or array printed on stdout (alone, or as the last
+//! line) is returned as structured output.
+//!
+//! `.wasm` entrypoints need no interpreter: they run in the WASI sandbox with
+//! only what the skill's declared permissions grant.
+
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::agent::sandbox::{run_wasm_async, SandboxError, SandboxGrants};
+use crate::agent::skills::SkillPermission;
+use std::process::Stdio;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Language a skill entrypoint is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillRuntime {
+    Python,
+    Node,
+    TypeScript,
+    Shell,
+    PowerShell,
+    /// WebAssembly (WASI), run in the sandbox
+    Wasm,
+}
+
+impl SkillRuntime {
+    /// Runtime for a script, from its extension
+    pub fn detect(entrypoint: &Path) -> Option<Self> {
+        match entrypoint.extension()?.to_str()?.to_lowercase().as_str() {
+            "py" => Some(Self::Python),
+            "js" | "mjs" | "cjs" => Some(Self::Node),
+            "ts" => Some(Self::TypeScript),
+            "sh" => Some(Self::Shell),
+            "ps1" => Some(Self::PowerShell),
+            "wasm" => Some(Self::Wasm),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Python => "Python",
+            Self::Node => "Node.js",
+            Self::TypeScript => "TypeScript",
+            Self::Shell => "shell",
+            Self::PowerShell => "PowerShell",
+            Self::Wasm => "WebAssembly",
+        }
+    }
+
+    /// Interpreters to try, in order
+    fn interpreters(&self) -> &'static [&'static str] {
+        match self {
+            // On Windows `python` may be the Microsoft Store stub
+            Self::Python if cfg!(windows) => &["python", "python3", "py"],
+            Self::Python => &["python3", "python"],
+            Self::Node => &["node"],
+            Self::TypeScript => &["tsx", "ts-node"],
+            Self::Shell => &["bash", "sh"],
+            Self::PowerShell => &["pwsh", "powershell"],
+            Self::Wasm => &[],
+        }
+    }
+
+    /// Arguments placed before the script path
+    fn script_args(&self) -> &'static [&'static str] {
+        match self {
+            Self::PowerShell => &["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RuntimeError {
+    #[error("Unsupported skill entrypoint: {0}")]
+    Unsupported(PathBuf),
+    #[error("No {runtime} interpreter found in PATH (tried {tried})")]
+    MissingInterpreter {
+        runtime: &'static str,
+        tried: String,
+    },
+    #[error("Failed to run skill script: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Sandbox(#[from] SandboxError),
+}
+
+/// Full path of a program found in PATH, via `which` (`where` on Windows)
+pub async fn locate(program: &str) -> Option<PathBuf> {
+    let finder = if cfg!(windows) { "where" } else { "which" };
+    let output = Command::new(finder).arg(program).output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| PathBuf::from(line.trim()))
+}
+
+/// First installed interpreter for a runtime
+pub async fn find_interpreter(runtime: SkillRuntime) -> Result<PathBuf, RuntimeError> {
+    for program in runtime.interpreters() {
+        let Some(path) = locate(program).await else {
+            continue;
+        };
+        // Python stubs exist in PATH but fail to run
+        if runtime == SkillRuntime::Python {
+            let works = Command::new(&path)
+                .arg("--version")
+                .output()
+                .await
+                .is_ok_and(|o| o.status.success());
+            if !works {
+                continue;
+            }
+        }
+        return Ok(path);
+    }
+    Err(RuntimeError::MissingInterpreter {
+        runtime: runtime.label(),
+        tried: runtime.interpreters().join(", "),
+    })
+}
+
+/// JSON printed by a script: the whole stdout, else its last line
+pub fn parse_structured_output(stdout: &str) -> Option<Value> {
+    let is_structured = |v: &Value| v.is_object() || v.is_array();
+    let trimmed = stdout.trim();
+    if let Some(value) = serde_json::from_str(trimmed).ok().filter(is_structured) {
+        return Some(value);
+    }
+    let last = trimmed.lines().next_back()?.trim();
+    serde_json::from_str(last).ok().filter(is_structured)
+}
+
+/// Result of a skill script run
+#[derive(Debug, Clone)]
+pub struct SkillOutput {
+    pub runtime: SkillRuntime,
+    pub interpreter: PathBuf,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// JSON the script printed, if any
+    pub output: Option<Value>,
+}
+
+impl SkillOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Workspace folder from the settings, mapped into WebAssembly skills
+static WORKSPACE: Lazy<RwLock<Option<PathBuf>>> = Lazy::new(|| RwLock::new(None));
+
+/// Set the workspace WebAssembly skills see at `/workspace`; `None` maps none
+pub fn set_skill_workspace(workspace: Option<PathBuf>) {
+    *WORKSPACE.write().unwrap_or_else(|e| e.into_inner()) = workspace;
+}
+
+fn skill_workspace() -> Option<PathBuf> {
+    WORKSPACE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .filter(|dir| dir.is_dir())
+}
+
+/// Sandbox grants for a WebAssembly skill
+///
+/// The skill folder is readable at `/skill` and the configured workspace, if
+/// any, at `/workspace`, writable only with `write`; the network needs
+/// `network`.
+pub fn wasm_grants(
+    skill_dir: &Path,
+    permissions: &[SkillPermission],
+    workspace: Option<&Path>,
+) -> SandboxGrants {
+    let grants = SandboxGrants::default().read_dir(skill_dir, "/skill");
+    let grants = match workspace {
+        Some(dir) if permissions.contains(&SkillPermission::Write) => {
+            grants.write_dir(dir, "/workspace")
+        }
+        Some(dir) => grants.read_dir(dir, "/workspace"),
+        None => grants,
+    };
+    grants.with_network(permissions.contains(&SkillPermission::Network))
+}
+
+/// Run a skill entrypoint in `skill_dir` with `params` as JSON
+///
+/// `permissions` are the skill's declared permissions; they only restrict
+/// WebAssembly skills, other scripts run with the user's rights.
+pub async fn run_skill_script(
+    skill_dir: &Path,
+    entrypoint: &Path,
+    params: &Value,
+    permissions: &[SkillPermission],
+) -> Result<SkillOutput, RuntimeError> {
+    let runtime = SkillRuntime::detect(entrypoint)
+        .ok_or_else(|| RuntimeError::Unsupported(entrypoint.to_path_buf()))?;
+    let params_json = params.to_string();
+
+    if runtime == SkillRuntime::Wasm {
+        let output = run_wasm_async(
+            entrypoint.to_path_buf(),
+            vec!["skill".to_string(), params_json.clone()],
+            params_json.into_bytes(),
+            wasm_grants(skill_dir, permissions, skill_workspace().as_deref()),
+        )
+        .await?;
+        return Ok(SkillOutput {
+            runtime,
+            interpreter: PathBuf::from("wasmtime"),
+            exit_code: Some(output.exit_code),
+            output: parse_structured_output(&output.stdout),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        });
+    }
+
+    let interpreter = find_interpreter(runtime).await?;
+
+    let mut child = Command::new(&interpreter)
+        .args(runtime.script_args())
+        .arg(entrypoint)
+        .arg(&params_json)
+        .current_dir(skill_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Scripts that don't read stdin close it early; that's not an error
+        let _ = stdin.write_all(params_json.as_bytes()).await;
+    }
+
+    let output = child.wait_with_output().await?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    Ok(SkillOutput {
+        runtime,
+        interpreter,
+        exit_code: output.status.code(),
+        output: parse_structured_output(&stdout),
+        stdout,
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_and_parse_output() {
+        assert_eq!(
+            SkillRuntime::detect(Path::new("skill/main.py")),
+            Some(SkillRuntime::Python)
+        );
+        assert_eq!(
+            SkillRuntime::detect(Path::new("run.PS1")),
+            Some(SkillRuntime::PowerShell)
+        );
+        assert_eq!(SkillRuntime::detect(Path::new("README.md")), None);
+
+        assert_eq!(
+            parse_structured_output("{\"temp\": 21}\n"),
+            Some(json!({"temp": 21}))
+        );
+        assert_eq!(
+            parse_structured_output("Fetching...\ndone\n[1, 2]"),
+            Some(json!([1, 2]))
+        );
+        assert_eq!(parse_structured_output("42"), None);
+        assert_eq!(parse_structured_output("plain text"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_shell_skill() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        std::fs::write(
+            &script,
+            "read input\necho \"log line\"\necho \"{\\\"arg\\\": $1, \\\"stdin\\\": $input}\"\n",
+        )
+        .unwrap();
+
+        let result = run_skill_script(dir.path(), &script, &json!({"city": "Lyon"}), &[])
+            .await
+            .unwrap();
+        assert!(result.success(), "{}", result.stderr);
+        assert_eq!(
+            result.output,
+            Some(json!({"arg": {"city": "Lyon"}, "stdin": {"city": "Lyon"}}))
+        );
+    }
+}
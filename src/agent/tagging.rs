@@ -0,0 +1,199 @@
+//! Automatic conversation tags
+//!
+//! After each reply the user's messages are matched against tag rules (the
+//! kind of request guessed by the routing heuristics and/or keywords), and
+//! the workspace the agent worked in adds a `workspace:<folder>` tag. Each
+//! tag is added once: one removed by hand is not put back.
+
+use crate::agent::routing::{classify, RequestKind};
+use crate::storage::conversations::Conversation;
+use crate::types::message::{Message, Role};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Prefix of the tag naming the workspace folder
+pub const WORKSPACE_TAG_PREFIX: &str = "workspace:";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagRule {
+    /// Tag added when the rule matches, lowercase
+    pub tag: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Matches when one of the user's messages is of this kind
+    #[serde(default)]
+    pub kind: Option<RequestKind>,
+    /// Matches when one of the user's messages contains one of these
+    /// (case-insensitive)
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl TagRule {
+    pub fn new(tag: impl Into<String>, kind: Option<RequestKind>, keywords: &[&str]) -> Self {
+        Self {
+            tag: tag.into(),
+            enabled: true,
+            kind,
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Whether a user message of `kind` and lowercase text `lower` matches;
+    /// a rule with neither kind nor keywords never does
+    fn matches(&self, kind: RequestKind, lower: &str) -> bool {
+        self.enabled
+            && (self.kind == Some(kind)
+                || self
+                    .keywords
+                    .iter()
+                    .any(|keyword| !keyword.trim().is_empty() && lower.contains(&keyword.trim().to_lowercase())))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoTagSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Tag conversations with the workspace folder the agent worked in
+    #[serde(default = "default_enabled")]
+    pub workspace: bool,
+    #[serde(default = "default_rules")]
+    pub rules: Vec<TagRule>,
+}
+
+impl Default for AutoTagSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            workspace: true,
+            rules: default_rules(),
+        }
+    }
+}
+
+/// Built-in rules: coding, writing and research
+pub fn default_rules() -> Vec<TagRule> {
+    vec![
+        TagRule::new("coding", Some(RequestKind::Code), &[]),
+        TagRule::new(
+            "writing",
+            None,
+            &[
+                "write an email", "draft", "rewrite", "proofread", "essay", "blog post", "cover letter",
+                "rediger", "rédiger", "reformule", "relis", "lettre de motivation",
+            ],
+        ),
+        TagRule::new(
+            "research",
+            None,
+            &[
+                "research", "find sources", "compare", "summarize", "summary of", "what is the difference",
+                "recherche", "resume", "résume", "quelle est la difference", "quelle est la différence",
+            ],
+        ),
+    ]
+}
+
+/// Tag naming a workspace folder, e.g. `workspace:clawrs`
+pub fn workspace_tag(workspace: &Path) -> Option<String> {
+    let name = workspace.file_name()?.to_string_lossy().trim().to_lowercase();
+    (!name.is_empty()).then(|| format!("{WORKSPACE_TAG_PREFIX}{name}"))
+}
+
+impl AutoTagSettings {
+    /// Tags the conversation calls for: matching rules in order, then the
+    /// workspace one
+    pub fn suggest(&self, messages: &[Message], workspace: Option<&Path>) -> Vec<String> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let user_messages: Vec<(RequestKind, String)> = messages
+            .iter()
+            .filter(|m| m.role == Role::User)
+            .map(|m| (classify(&m.content), m.content.to_lowercase()))
+            .collect();
+        let mut tags: Vec<String> = Vec::new();
+        for rule in &self.rules {
+            let tag = rule.tag.trim().to_lowercase();
+            if tag.is_empty() || tags.contains(&tag) {
+                continue;
+            }
+            if user_messages.iter().any(|(kind, lower)| rule.matches(*kind, lower)) {
+                tags.push(tag);
+            }
+        }
+        if self.workspace {
+            if let Some(tag) = workspace.and_then(workspace_tag) {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+        tags
+    }
+}
+
+/// Add the suggested tags that were never added automatically before;
+/// returns whether the conversation's tags changed
+pub fn apply_auto_tags(conversation: &mut Conversation, suggested: Vec<String>) -> bool {
+    let mut changed = false;
+    for tag in suggested {
+        if conversation.auto_tags.contains(&tag) {
+            continue;
+        }
+        if !conversation.tags.contains(&tag) {
+            conversation.tags.push(tag.clone());
+            changed = true;
+        }
+        conversation.auto_tags.push(tag);
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_suggest() {
+        let settings = AutoTagSettings::default();
+        let messages = vec![
+            Message::new(Role::User, "Why does my Rust build fail?"),
+            Message::new(Role::Assistant, "Please draft an essay about it"),
+        ];
+        let workspace = PathBuf::from("/home/me/ClawRS");
+        assert_eq!(
+            settings.suggest(&messages, Some(&workspace)),
+            vec!["coding".to_string(), "workspace:clawrs".to_string()]
+        );
+
+        let messages = vec![Message::new(Role::User, "Can you proofread this cover letter for me, it is for a bakery job")];
+        assert_eq!(settings.suggest(&messages, None), vec!["writing".to_string()]);
+
+        let off = AutoTagSettings { enabled: false, ..AutoTagSettings::default() };
+        assert!(off.suggest(&messages, Some(&workspace)).is_empty());
+    }
+
+    #[test]
+    fn test_apply_auto_tags_keeps_removals() {
+        let mut conversation = Conversation::new(None);
+        assert!(apply_auto_tags(&mut conversation, vec!["coding".to_string()]));
+        assert_eq!(conversation.tags, vec!["coding".to_string()]);
+
+        // Removed by hand: not added back
+        conversation.tags.clear();
+        assert!(!apply_auto_tags(&mut conversation, vec!["coding".to_string()]));
+        assert!(conversation.tags.is_empty());
+
+        // Already set by hand: recorded, not duplicated
+        conversation.tags.push("research".to_string());
+        assert!(!apply_auto_tags(&mut conversation, vec!["research".to_string()]));
+        assert_eq!(conversation.tags, vec!["research".to_string()]);
+    }
+}
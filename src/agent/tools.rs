@@ -1,9 +1,12 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use dashmap::DashMap;
+use tokio::sync::Notify;
 use thiserror::Error;
+use crate::agent::focus;
 
 /// Compute a short hash (2 chars) for a line of content
 /// This is used for Hashline - see https://github.com/0xZKnw/oh-my-pi
@@ -19,13 +22,92 @@ fn compute_line_hash(line: &str) -> String {
     format!("{:02x}", hash & 0xFFF)
 }
 
+/// Progress notification emitted by a running tool
+#[derive(Clone, Debug, PartialEq)]
+pub enum ToolProgress {
+    /// Short status update (e.g. "45% downloaded")
+    Status(String),
+    /// Chunk of partial output (e.g. a line of stdout)
+    Output(String),
+}
+
+/// Channel used by tools to report progress while executing
+pub type ToolProgressSender = tokio::sync::mpsc::UnboundedSender<ToolProgress>;
+
+/// Cancellation flag shared between the UI and a running tool
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation and wake every waiter
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once `cancel` has been called
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            // Register before checking the flag so a concurrent cancel isn't missed
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Per-execution handles passed to `Tool::execute_with_context`
+#[derive(Clone)]
+pub struct ToolContext {
+    /// Progress notifications for the UI
+    pub progress: ToolProgressSender,
+    /// Set when the user cancels this execution
+    pub cancel: CancelToken,
+}
+
 /// Tool trait - all tools must implement this
 #[async_trait]
 pub trait Tool: Send + Sync {
     fn name(&self) -> &str;
+    /// English description, the one models understand best
     fn description(&self) -> &str;
+    /// French description, when the tool has one
+    fn description_fr(&self) -> Option<&str> {
+        descriptions::french(self.name())
+    }
     fn parameters_schema(&self) -> Value;
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError>;
+
+    /// Execute while reporting progress and honouring cancellation.
+    ///
+    /// Long-running tools override this to stream output and clean up on
+    /// cancel (kill child processes, ...). The default runs `execute` and
+    /// drops it if cancelled, which also aborts in-flight HTTP requests.
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        tokio::select! {
+            result = self.execute(params) => result,
+            _ = ctx.cancel.cancelled() => Err(ToolError::Cancelled),
+        }
+    }
 }
 
 /// Tool execution result
@@ -55,16 +137,41 @@ pub enum ToolError {
     NotFound(String),
     #[error("Timeout")]
     Timeout,
+    #[error("Cancelled by user")]
+    Cancelled,
+    #[error("{0}")]
+    Validation(#[from] validation::ValidationError),
+    #[error("{0}")]
+    DiskSpace(#[from] crate::system::disk::InsufficientSpace),
 }
 
 /// Tool information for listing
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ToolInfo {
     pub name: String,
+    /// Prompt-facing description: English unless `in_language` picked another
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_fr: Option<String>,
     pub parameters_schema: Value,
 }
 
+impl ToolInfo {
+    /// Description in `language` ("en" or "fr"), English when missing
+    pub fn description_for(&self, language: &str) -> &str {
+        match (language, &self.description_fr) {
+            ("fr", Some(french)) => french,
+            _ => &self.description,
+        }
+    }
+
+    /// Same tool with the prompt-facing description in `language`
+    pub fn in_language(mut self, language: &str) -> Self {
+        self.description = self.description_for(language).to_string();
+        self
+    }
+}
+
 /// Tool registry - singleton pattern
 pub struct ToolRegistry {
     tools: DashMap<String, Arc<dyn Tool>>,
@@ -89,20 +196,40 @@ impl ToolRegistry {
         self.tools.remove(name);
     }
     
+    /// Registered tool, unless focus mode hides it
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        if focus::blocks_tool(name) {
+            return None;
+        }
         self.tools.get(name).map(|t| t.clone())
     }
+
+    /// Validate params against the tool's schema, returning normalized params
+    pub fn validate_params(&self, name: &str, params: &Value) -> Result<Value, ToolError> {
+        let tool = self.get(name).ok_or_else(|| ToolError::NotFound(name.to_string()))?;
+        Ok(validation::validate_params(name, &tool.parameters_schema(), params)?)
+    }
     
     pub fn list_tools(&self) -> Vec<ToolInfo> {
         self.tools
             .iter()
+            .filter(|entry| !focus::blocks_tool(entry.key()))
             .map(|entry| ToolInfo {
                 name: entry.name().to_string(),
                 description: entry.description().to_string(),
+                description_fr: entry.description_fr().map(str::to_string),
                 parameters_schema: entry.parameters_schema(),
             })
             .collect()
     }
+
+    /// Tools with their descriptions in `language`, for the prompt
+    pub fn list_tools_in(&self, language: &str) -> Vec<ToolInfo> {
+        self.list_tools()
+            .into_iter()
+            .map(|tool| tool.in_language(language))
+            .collect()
+    }
     
     pub fn count(&self) -> usize {
         self.tools.len()
@@ -130,15 +257,42 @@ pub mod git;
 /// Web tools (fetch, download)
 pub mod web;
 
+/// RSS/Atom feed reading
+pub mod feeds;
+
+/// Rate limiting, retries and circuit breaking for web providers
+pub mod rate_limit;
+
 /// Developer tools (diff, find-replace, patch, wc)
 pub mod dev;
 
+/// Condensed workspace map (languages, key files, entry points, tree)
+pub mod repo_map;
+
+/// Symbol and semantic search over the workspace index
+pub mod workspace_search;
+
 /// System tools (process list, environment, system info, which, tree)
 pub mod system;
 
 /// PDF tools (read, create, add page, merge)
 pub mod pdf;
 
+/// Markdown to PDF/HTML document rendering
+pub mod document;
+
+/// CSV/TSV analysis (schema, samples, column statistics)
+pub mod csv;
+
+/// Parameter validation against tool schemas
+pub mod validation;
+
+/// French descriptions of the built-in tools
+pub mod descriptions;
+
+/// Tool results replayed from a recording
+pub mod replay;
+
 /// OpenRouter AI consultation tool
 pub mod openrouter;
 
@@ -1047,6 +1201,8 @@ pub mod builtins {
             
             let mut cmd = Command::new(shell);
             cmd.arg(shell_arg).arg(command_str);
+            // Dropped on cancellation: don't leave the process running
+            cmd.kill_on_drop(true);
             
             if let Some(dir) = working_dir {
                 cmd.current_dir(dir);
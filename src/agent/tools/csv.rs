@@ -0,0 +1,485 @@
+//! CSV/TSV analysis tool
+//!
+//! `csv_read` summarizes a delimited file instead of dumping it into the
+//! context: it infers a type for each column and returns the schema, row
+//! count, head/tail samples and per-column statistics.
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+
+/// Files larger than this are refused
+const MAX_FILE_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Distinct values tracked per column before giving up on counting
+const MAX_DISTINCT: usize = 1000;
+
+/// Longest cell shown in samples, in bytes
+const MAX_SAMPLE_CELL: usize = 80;
+
+/// Inferred column type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    /// Every value is empty
+    Empty,
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    Text,
+}
+
+impl ColumnType {
+    /// Type of a single non-empty value
+    pub fn infer(value: &str) -> Self {
+        let v = value.trim();
+        if v.is_empty() {
+            return Self::Empty;
+        }
+        let numeric_chars = v
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'));
+        if numeric_chars && v.chars().any(|c| c.is_ascii_digit()) {
+            if v.parse::<i64>().is_ok() {
+                return Self::Integer;
+            }
+            if v.parse::<f64>().is_ok() {
+                return Self::Float;
+            }
+        }
+        if matches!(
+            v.to_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "oui" | "non"
+        ) {
+            return Self::Boolean;
+        }
+        let is_date = ["%Y-%m-%d", "%d/%m/%Y", "%Y/%m/%d"]
+            .iter()
+            .any(|fmt| chrono::NaiveDate::parse_from_str(v, fmt).is_ok())
+            || chrono::DateTime::parse_from_rfc3339(v).is_ok()
+            || chrono::NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S").is_ok();
+        if is_date {
+            return Self::Date;
+        }
+        Self::Text
+    }
+
+    /// Widen a column type to also cover `other`
+    pub fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Empty, t) | (t, Self::Empty) => t,
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Schema and statistics of one column
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnSummary {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+    pub nulls: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean: Option<f64>,
+    /// Number of distinct values, absent when above `MAX_DISTINCT`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distinct: Option<usize>,
+}
+
+/// Running statistics for a column
+struct ColumnAccumulator {
+    column_type: ColumnType,
+    nulls: usize,
+    count: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+    distinct: Option<HashSet<String>>,
+}
+
+impl ColumnAccumulator {
+    fn new() -> Self {
+        Self {
+            column_type: ColumnType::Empty,
+            nulls: 0,
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            distinct: Some(HashSet::new()),
+        }
+    }
+
+    fn add(&mut self, value: &str) {
+        let v = value.trim();
+        if v.is_empty() {
+            self.nulls += 1;
+            return;
+        }
+        let value_type = ColumnType::infer(v);
+        self.column_type = self.column_type.merge(value_type);
+        if matches!(value_type, ColumnType::Integer | ColumnType::Float) {
+            if let Ok(n) = v.parse::<f64>() {
+                self.count += 1;
+                self.sum += n;
+                self.min = self.min.min(n);
+                self.max = self.max.max(n);
+            }
+        }
+        if let Some(set) = &mut self.distinct {
+            set.insert(v.to_string());
+            if set.len() > MAX_DISTINCT {
+                self.distinct = None;
+            }
+        }
+    }
+
+    fn finish(self, name: String) -> ColumnSummary {
+        let numeric =
+            matches!(self.column_type, ColumnType::Integer | ColumnType::Float) && self.count > 0;
+        ColumnSummary {
+            name,
+            column_type: self.column_type,
+            nulls: self.nulls,
+            min: numeric.then_some(self.min),
+            max: numeric.then_some(self.max),
+            mean: numeric.then(|| self.sum / self.count as f64),
+            distinct: self.distinct.map(|set| set.len()),
+        }
+    }
+}
+
+/// Split delimited text into records, honouring RFC 4180 quoting
+/// (quoted delimiters, newlines and `""` escapes)
+pub fn parse_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if !(record.len() == 1 && record[0].is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Pick the delimiter from an explicit name, the extension, or the header
+pub fn detect_delimiter(explicit: Option<&str>, path: &str, text: &str) -> char {
+    match explicit {
+        Some("\\t") | Some("tab") | Some("\t") => return '\t',
+        Some(d) if d.chars().count() == 1 => return d.chars().next().unwrap_or(','),
+        _ => {}
+    }
+    if path.to_lowercase().ends_with(".tsv") {
+        return '\t';
+    }
+    let first_line = text.lines().next().unwrap_or_default();
+    [',', ';', '\t', '|']
+        .into_iter()
+        .map(|d| {
+            (
+                d,
+                parse_records(first_line, d).first().map_or(0, |r| r.len()),
+            )
+        })
+        .max_by_key(|&(d, n)| (n, d == ','))
+        .filter(|&(_, n)| n > 1)
+        .map_or(',', |(d, _)| d)
+}
+
+/// Result of analyzing a delimited file
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvSummary {
+    pub rows: usize,
+    pub columns: Vec<ColumnSummary>,
+    pub head: Vec<Vec<String>>,
+    pub tail: Vec<Vec<String>>,
+    /// Rows whose cell count differs from the header
+    pub ragged_rows: usize,
+}
+
+/// Summarize delimited text: column summaries, row count, head and tail
+pub fn analyze(
+    text: &str,
+    delimiter: char,
+    has_header: bool,
+    head: usize,
+    tail: usize,
+) -> CsvSummary {
+    let mut records = parse_records(text, delimiter).into_iter();
+    let width_hint = if has_header { records.next() } else { None };
+
+    let mut columns: Vec<ColumnAccumulator> = Vec::new();
+    let mut head_rows = Vec::new();
+    let mut tail_rows: VecDeque<Vec<String>> = VecDeque::new();
+    let mut rows = 0;
+    let mut ragged = 0;
+    let expected = width_hint.as_ref().map(|h| h.len());
+
+    for record in records {
+        if expected.is_some_and(|n| n != record.len()) {
+            ragged += 1;
+        }
+        while columns.len() < record.len() {
+            columns.push(ColumnAccumulator::new());
+        }
+        for (column, value) in columns.iter_mut().zip(&record) {
+            column.add(value);
+        }
+        // Missing trailing cells count as nulls
+        for column in columns.iter_mut().skip(record.len()) {
+            column.nulls += 1;
+        }
+
+        let sample: Vec<String> = record
+            .iter()
+            .map(|v| crate::truncate_str(v, MAX_SAMPLE_CELL).to_string())
+            .collect();
+        if head_rows.len() < head {
+            head_rows.push(sample);
+        } else if tail > 0 {
+            if tail_rows.len() == tail {
+                tail_rows.pop_front();
+            }
+            tail_rows.push_back(sample);
+        }
+        rows += 1;
+    }
+
+    let header = width_hint.unwrap_or_default();
+    while columns.len() < header.len() {
+        columns.push(ColumnAccumulator::new());
+    }
+    let summaries = columns
+        .into_iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let name = header
+                .get(i)
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty())
+                .unwrap_or_else(|| format!("column_{}", i + 1));
+            column.finish(name)
+        })
+        .collect();
+
+    CsvSummary {
+        rows,
+        columns: summaries,
+        head: head_rows,
+        tail: tail_rows.into(),
+        ragged_rows: ragged,
+    }
+}
+
+// ============================================================================
+// CsvReadTool
+// ============================================================================
+
+pub struct CsvReadTool;
+
+#[async_trait]
+impl Tool for CsvReadTool {
+    fn name(&self) -> &str {
+        "csv_read"
+    }
+
+    fn description(&self) -> &str {
+        "Analyze a CSV/TSV file without loading it whole: schema (inferred types), row count, first/last rows and per-column statistics (min/max/mean)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path of the CSV or TSV file"
+                },
+                "delimiter": {
+                    "type": "string",
+                    "description": "Delimiter (',', ';', 'tab', '|'). Default: detected automatically"
+                },
+                "has_header": {
+                    "type": "boolean",
+                    "description": "The first row holds the column names (default: true)"
+                },
+                "head": {
+                    "type": "integer",
+                    "description": "Number of first rows to show (default: 5, max: 50)"
+                },
+                "tail": {
+                    "type": "integer",
+                    "description": "Number of last rows to show (default: 5, max: 50)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let has_header = params["has_header"].as_bool().unwrap_or(true);
+        let head = params["head"].as_u64().unwrap_or(5).min(50) as usize;
+        let tail = params["tail"].as_u64().unwrap_or(5).min(50) as usize;
+
+        let size = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur lecture '{}': {}", path, e)))?
+            .len();
+        if size > MAX_FILE_BYTES {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Fichier trop volumineux ({} Mo, max {} Mo)",
+                size / (1024 * 1024),
+                MAX_FILE_BYTES / (1024 * 1024)
+            )));
+        }
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur lecture '{}': {}", path, e)))?;
+        let text = String::from_utf8_lossy(&bytes);
+
+        let delimiter = detect_delimiter(params["delimiter"].as_str(), path, &text);
+        let summary = analyze(&text, delimiter, has_header, head, tail);
+
+        let delimiter_label = match delimiter {
+            '\t' => "tab".to_string(),
+            d => d.to_string(),
+        };
+        let message = format!(
+            "{}: {} lignes, {} colonnes",
+            path,
+            summary.rows,
+            summary.columns.len()
+        );
+        let mut data = serde_json::to_value(&summary)
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        data["path"] = Value::from(path);
+        data["delimiter"] = Value::from(delimiter_label);
+
+        Ok(ToolResult {
+            success: true,
+            data,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_records_quoting() {
+        let text = "name,note\r\n\"Dupont, Jean\",\"dit \"\"bonjour\"\"\"\n\"multi\nligne\",x\n";
+        let records = parse_records(text, ',');
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1], vec!["Dupont, Jean", "dit \"bonjour\""]);
+        assert_eq!(records[2], vec!["multi\nligne", "x"]);
+    }
+
+    #[test]
+    fn test_type_inference() {
+        assert_eq!(ColumnType::infer("42"), ColumnType::Integer);
+        assert_eq!(ColumnType::infer("-3.5e2"), ColumnType::Float);
+        assert_eq!(ColumnType::infer("Oui"), ColumnType::Boolean);
+        assert_eq!(ColumnType::infer("2024-02-29"), ColumnType::Date);
+        assert_eq!(ColumnType::infer("e"), ColumnType::Text);
+        assert_eq!(
+            ColumnType::Integer.merge(ColumnType::Float),
+            ColumnType::Float
+        );
+        assert_eq!(ColumnType::Empty.merge(ColumnType::Date), ColumnType::Date);
+        assert_eq!(
+            ColumnType::Integer.merge(ColumnType::Text),
+            ColumnType::Text
+        );
+    }
+
+    #[test]
+    fn test_analyze() {
+        let mut text = String::from("id;price;city\n");
+        for i in 1..=20 {
+            let price = if i == 7 {
+                String::new()
+            } else {
+                format!("{}.5", i)
+            };
+            text.push_str(&format!(
+                "{};{};{}\n",
+                i,
+                price,
+                if i % 2 == 0 { "Paris" } else { "Lyon" }
+            ));
+        }
+        let delimiter = detect_delimiter(None, "data.csv", &text);
+        assert_eq!(delimiter, ';');
+
+        let summary = analyze(&text, delimiter, true, 3, 2);
+        assert_eq!(
+            (
+                summary.rows,
+                summary.head.len(),
+                summary.tail.len(),
+                summary.ragged_rows
+            ),
+            (20, 3, 2, 0)
+        );
+        assert_eq!(summary.tail[1][0], "20");
+
+        let columns = &summary.columns;
+
+        assert_eq!(columns[0].column_type, ColumnType::Integer);
+        assert_eq!(
+            (columns[0].min, columns[0].max, columns[0].mean),
+            (Some(1.0), Some(20.0), Some(10.5))
+        );
+        assert_eq!(columns[1].column_type, ColumnType::Float);
+        assert_eq!(columns[1].nulls, 1);
+        assert_eq!(columns[2].column_type, ColumnType::Text);
+        assert_eq!(columns[2].distinct, Some(2));
+        assert!(columns[2].mean.is_none());
+    }
+}
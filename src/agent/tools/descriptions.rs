@@ -0,0 +1,86 @@
+//! French descriptions of the built-in tools
+//!
+//! `Tool::description` is the English text models understand best. The
+//! French one is shown in the French UI and, when chosen in Settings, given
+//! to the model instead. Plugins and MCP tools only have their own text.
+
+/// French description of a built-in tool
+pub fn french(name: &str) -> Option<&'static str> {
+    let text = match name {
+        "file_read" => "Lire le contenu d'un fichier. Peut lire seulement certaines plages de lignes.",
+        "file_write" => "Écrire du contenu dans un fichier, créé s'il n'existe pas. NÉCESSITE UNE APPROBATION.",
+        "file_list" => "Lister les fichiers d'un dossier avec leurs informations détaillées.",
+        "grep" => "Chercher un motif (regex) dans des fichiers. Renvoie les lignes trouvées avec leur contexte.",
+        "glob" => "Trouver les fichiers correspondant à un motif glob (ex. '**/*.rs', 'src/**/*.py').",
+        "think" => "Noter ton raisonnement pour avancer pas à pas sur un problème complexe.",
+        "todo_write" => "Créer ou mettre à jour une liste de tâches pour planifier le travail. Pour les tâches complexes en plusieurs étapes.",
+        "command" => "Exécuter une commande shell (approbation requise). Seules les commandes sûres en lecture seule sont permises.",
+        "file_edit" => "Modifier un fichier en remplaçant une chaîne exacte, ou une ligne repérée par son numéro et son hash (sortie de file_read). NÉCESSITE UNE APPROBATION.",
+        "file_create" => "Créer un nouveau fichier avec du contenu. Échoue si le fichier existe déjà ; les dossiers parents sont créés. NÉCESSITE UNE APPROBATION.",
+        "file_delete" => "Supprimer un fichier ou un dossier vide. Un dossier non vide demande recursive=true. NÉCESSITE UNE APPROBATION.",
+        "file_move" => "Déplacer ou renommer un fichier ou un dossier ; les dossiers de destination sont créés. NÉCESSITE UNE APPROBATION.",
+        "file_info" => "Informations détaillées sur un fichier ou un dossier (taille, permissions, dates, type).",
+        "directory_create" => "Créer un dossier et ses parents s'ils n'existent pas (comme mkdir -p). NÉCESSITE UNE APPROBATION.",
+        "file_copy" => "Copier un fichier ; les dossiers de destination sont créés. NÉCESSITE UNE APPROBATION.",
+        "file_search" => "Chercher du texte dans les fichiers d'un dossier. Renvoie les fichiers trouvés avec numéros de ligne et contexte.",
+        "bash" => "Exécuter une commande shell avec un accès complet : compilation, installation de paquets, git, scripts, commandes système. NÉCESSITE UNE APPROBATION. PowerShell sous Windows, bash sous Unix.",
+        "bash_background" => "Lancer une commande longue en arrière-plan (serveur de dev, watcher). Rend la main aussitôt avec un identifiant de processus. NÉCESSITE UNE APPROBATION.",
+        "git_status" => "État git actuel : fichiers modifiés, changements indexés, branche, fichiers non suivis.",
+        "git_diff" => "Afficher un diff git : changements non indexés, indexés, ou entre commits/branches.",
+        "git_log" => "Historique des commits git avec messages, auteurs et dates.",
+        "git_commit" => "Indexer des fichiers et créer un commit git, pour certains fichiers ou tous les changements. NÉCESSITE UNE APPROBATION.",
+        "git_branch" => "Lister, créer, changer ou supprimer des branches git.",
+        "git_stash" => "Mettre de côté ou restaurer les changements non commités. Actions : save, pop, list, drop.",
+        "web_fetch" => "Récupérer le contenu d'une URL sous forme de texte : pages HTML (converties en texte lisible), API JSON, texte brut.",
+        "web_download" => "Télécharger un fichier depuis une URL et l'enregistrer sur le disque. NÉCESSITE UNE APPROBATION.",
+        "web_search" => "Chercher sur le web. Renvoie le contenu résumé des meilleurs résultats : informations récentes, actualités, faits.",
+        "code_search" => "Trouver exemples de code, documentation et solutions de programmation sur GitHub, Stack Overflow et les docs officielles.",
+        "company_research" => "Se renseigner sur une entreprise : produits et services, actualités récentes, position dans son secteur.",
+        "deep_research_start" => "Lancer une recherche approfondie en arrière-plan : recherche web, lecture de nombreuses sources et document de recherche tenu à jour. Renvoie un task_id à consulter avec deep_research_check.",
+        "deep_research_check" => "Consulter l'état et les résultats d'une recherche lancée avec deep_research_start.",
+        "web_crawl" => "Récupérer le contenu complet d'une page web à partir de son URL exacte.",
+        "feed_read" => "Lire un flux RSS ou Atom et lister ses derniers éléments (titre, lien, date, résumé), du plus récent au plus ancien.",
+        "diff" => "Comparer deux fichiers ou textes et montrer les différences ligne par ligne.",
+        "find_replace" => "Chercher et remplacer du texte dans plusieurs fichiers d'un dossier, avec filtre sur les noms. NÉCESSITE UNE APPROBATION.",
+        "patch" => "Appliquer un patch au format diff unifié à un fichier. NÉCESSITE UNE APPROBATION.",
+        "wc" => "Compter les lignes, mots et caractères d'un fichier (comme wc).",
+        "repo_map" => "Carte condensée d'un projet : langages, fichiers clés, points d'entrée et arborescence avec tailles. À utiliser d'abord pour s'orienter dans un code inconnu.",
+        "workspace_search" => "Chercher dans l'espace de travail indexé : 'symbols' trouve fonctions, types et classes par nom ; 'semantic' trouve des passages par le sens. Renvoie chemins et numéros de ligne.",
+        "process_list" => "Lister les processus en cours, avec filtre par nom.",
+        "environment" => "Lire les variables d'environnement : toutes, ou une seule.",
+        "system_info" => "Informations système : OS, architecture, nom d'hôte, dossier courant, espace disque.",
+        "which" => "Trouver le chemin complet d'un exécutable (comme 'which' sous Unix ou 'where' sous Windows).",
+        "tree" => "Afficher l'arborescence d'un dossier pour comprendre l'organisation d'un projet.",
+        "pdf_read" => "Lire un fichier PDF et en extraire le texte.",
+        "pdf_create" => "Créer un nouveau fichier PDF avec du texte. Les lignes longues sont renvoyées à la ligne et les pages ajoutées automatiquement.",
+        "pdf_add_page" => "Ajouter une page de texte à un PDF existant.",
+        "pdf_merge" => "Fusionner plusieurs fichiers PDF en un seul.",
+        "document_render" => "Générer un document PDF (multi-pages, retour à la ligne, tableaux, code) ou HTML à partir de Markdown. À préférer à pdf_create pour tout contenu long ou structuré.",
+        "csv_read" => "Analyser un fichier CSV/TSV sans le charger en entier : schéma (types déduits), nombre de lignes, premières/dernières lignes et statistiques par colonne.",
+        "ai_consult" => "Consulter un modèle externe plus puissant (via le fournisseur cloud configuré) pour un raisonnement complexe ou un second avis.",
+        "skill_create" => "Créer un nouveau skill : génère un fichier SKILL.md avec le contenu et les métadonnées fournis.",
+        "skill_invoke" => "Invoquer un skill par son nom ; ses instructions sont ajoutées au contexte.",
+        "skill_list" => "Lister les skills disponibles avec leur description.",
+        "mcp_add_server" => "Ajouter la configuration d'un serveur MCP au fichier mcp.json global.",
+        "mcp_list_servers" => "Lister les serveurs MCP effectifs (préréglages et configuration locale).",
+        "mcp_remove_server" => "Retirer un serveur MCP de la configuration locale.",
+        _ => return None,
+    };
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::tools::{builtins, pdf, Tool};
+
+    #[test]
+    fn test_french() {
+        let tools: [&dyn Tool; 3] = [&builtins::FileReadTool, &builtins::GrepTool, &pdf::PdfReadTool];
+        for tool in tools {
+            let french = french(tool.name()).unwrap();
+            assert_ne!(french, tool.description());
+        }
+        assert_eq!(french("mcp_github_create_issue"), None);
+    }
+}
@@ -6,6 +6,7 @@ use async_trait::async_trait;
 use serde_json::Value;
 use std::path::PathBuf;
 
+use crate::agent::quality::word_count;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
 
 // ============================================================================
@@ -406,7 +407,7 @@ impl Tool for CountLinesTool {
             .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire: {}", e)))?;
 
         let lines = content.lines().count();
-        let words = content.split_whitespace().count();
+        let words = word_count(&content);
         let chars = content.chars().count();
         let bytes = content.len();
 
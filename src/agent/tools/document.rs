@@ -0,0 +1,1728 @@
+//! Document rendering tool
+//!
+//! `document_render` turns Markdown (headings, lists, tables, code, quotes)
+//! into a typeset multi-page PDF or a styled HTML page. PDF output goes
+//! through a small layout pipeline: Markdown is parsed into blocks, blocks
+//! are laid out into positioned draw operations (word wrapping, pagination,
+//! table rows, page numbers), and the pages are then emitted with printpdf.
+//! HTML output is rendered into a built-in or user-supplied template.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::system::disk::ensure_free_space;
+
+// ============================================================================
+// Markdown model
+// ============================================================================
+
+/// Inline text style
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpanStyle {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Code,
+}
+
+/// A run of text with one style
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: SpanStyle,
+    pub link: Option<String>,
+}
+
+impl Span {
+    fn plain(text: &str, style: SpanStyle) -> Self {
+        Self {
+            text: text.to_string(),
+            style,
+            link: None,
+        }
+    }
+}
+
+/// A block-level Markdown element
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    Heading(u8, Vec<Span>),
+    Paragraph(Vec<Span>),
+    Code {
+        lang: String,
+        text: String,
+    },
+    List {
+        ordered: bool,
+        items: Vec<Vec<Span>>,
+    },
+    Quote(Vec<Span>),
+    Table {
+        header: Vec<Vec<Span>>,
+        rows: Vec<Vec<Vec<Span>>>,
+    },
+    Rule,
+    PageBreak,
+}
+
+/// Parse Markdown into blocks
+pub fn parse_markdown(markdown: &str) -> Vec<Block> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+
+        if trimmed.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        if trimmed == "<!-- pagebreak -->" || trimmed == "\\pagebreak" {
+            blocks.push(Block::PageBreak);
+            i += 1;
+            continue;
+        }
+
+        if let Some(fence) = trimmed.strip_prefix("```") {
+            let lang = fence.trim().to_string();
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with("```") {
+                code.push(lines[i].trim_end());
+                i += 1;
+            }
+            blocks.push(Block::Code {
+                lang,
+                text: code.join("\n"),
+            });
+            i += 1;
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            blocks.push(Block::Rule);
+            i += 1;
+            continue;
+        }
+
+        let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            let text = trimmed[hashes..].trim().trim_end_matches('#').trim();
+            blocks.push(Block::Heading(hashes as u8, parse_inline(text)));
+            i += 1;
+            continue;
+        }
+
+        if trimmed.starts_with('>') {
+            let mut quote = Vec::new();
+            while i < lines.len() && lines[i].trim().starts_with('>') {
+                quote.push(lines[i].trim().trim_start_matches('>').trim());
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_inline(&quote.join(" "))));
+            continue;
+        }
+
+        if trimmed.starts_with('|') {
+            let mut table_lines = Vec::new();
+            while i < lines.len() && lines[i].trim().starts_with('|') {
+                table_lines.push(lines[i].trim());
+                i += 1;
+            }
+            let mut rows: Vec<Vec<Vec<Span>>> = table_lines
+                .iter()
+                .filter(|l| !is_table_separator(l))
+                .map(|l| split_table_row(l).iter().map(|c| parse_inline(c)).collect())
+                .collect();
+            if !rows.is_empty() {
+                let header = rows.remove(0);
+                blocks.push(Block::Table { header, rows });
+            }
+            continue;
+        }
+
+        if let Some(ordered) = list_marker(trimmed).map(|(ordered, _)| ordered) {
+            let mut items: Vec<String> = Vec::new();
+            while i < lines.len() {
+                let l = lines[i].trim();
+                match list_marker(l) {
+                    Some((o, rest)) if o == ordered => items.push(rest.to_string()),
+                    Some(_) => break,
+                    None if l.is_empty() || starts_block(l) => break,
+                    // Continuation of the previous item
+                    None => {
+                        if let Some(last) = items.last_mut() {
+                            last.push(' ');
+                            last.push_str(l);
+                        }
+                    }
+                }
+                i += 1;
+            }
+            blocks.push(Block::List {
+                ordered,
+                items: items.iter().map(|item| parse_inline(item)).collect(),
+            });
+            continue;
+        }
+
+        let mut paragraph = Vec::new();
+        while i < lines.len() {
+            let l = lines[i].trim();
+            if l.is_empty() || (!paragraph.is_empty() && starts_block(l)) {
+                break;
+            }
+            paragraph.push(l);
+            i += 1;
+        }
+        blocks.push(Block::Paragraph(parse_inline(&paragraph.join(" "))));
+    }
+
+    blocks
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ["-", "*", "_"]
+            .iter()
+            .any(|m| compact.chars().all(|c| c.to_string() == *m))
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let inner = line.trim().trim_matches('|');
+    inner.contains('-') && inner.chars().all(|c| matches!(c, '-' | ':' | '|' | ' '))
+}
+
+fn split_table_row(line: &str) -> Vec<String> {
+    line.trim()
+        .trim_matches('|')
+        .split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+/// `(ordered, item text)` if the line starts a list item
+fn list_marker(line: &str) -> Option<(bool, &str)> {
+    for bullet in ["- ", "* ", "+ ", "• "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            return Some((false, rest.trim()));
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if let Some(item) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some((true, item.trim()));
+        }
+    }
+    None
+}
+
+fn starts_block(line: &str) -> bool {
+    line.starts_with('#')
+        || line.starts_with("```")
+        || line.starts_with('>')
+        || line.starts_with('|')
+        || is_rule(line)
+        || list_marker(line).is_some()
+}
+
+/// For each position, the index of the first match at or after it
+/// (`chars.len()` when there is none)
+fn next_match(chars: &[char], is_match: impl Fn(usize) -> bool) -> Vec<usize> {
+    let mut next = vec![chars.len(); chars.len() + 1];
+    for i in (0..chars.len()).rev() {
+        next[i] = if is_match(i) { i } else { next[i + 1] };
+    }
+    next
+}
+
+/// Start of the last run of at least 1, 2 and 3 `marker` characters
+fn last_runs(chars: &[char], marker: char) -> [Option<usize>; 3] {
+    let mut last = [None; 3];
+    let mut run = 0;
+    for i in (0..chars.len()).rev() {
+        run = if chars[i] == marker { run + 1 } else { 0 };
+        for (k, slot) in last.iter_mut().enumerate() {
+            if run > k && slot.is_none() {
+                *slot = Some(i);
+            }
+        }
+    }
+    last
+}
+
+/// Parse inline Markdown: `**bold**`, `*italic*`, `` `code` ``, `[text](url)`
+///
+/// One pass over the text: where the closing backtick, link parts and
+/// emphasis markers are is looked up in tables built beforehand.
+pub fn parse_inline(text: &str) -> Vec<Span> {
+    let chars: Vec<char> = text.chars().collect();
+    let n = chars.len();
+    let next_tick = next_match(&chars, |i| chars[i] == '`');
+    let next_link_mid = next_match(&chars, |i| {
+        chars[i] == ']' && chars.get(i + 1) == Some(&'(')
+    });
+    let next_paren = next_match(&chars, |i| chars[i] == ')');
+    let last_stars = last_runs(&chars, '*');
+    let last_underscores = last_runs(&chars, '_');
+
+    let mut spans: Vec<Span> = Vec::new();
+    let mut buf = String::new();
+    let (mut bold, mut italic) = (false, false);
+    let mut i = 0;
+
+    let style = |bold: bool, italic: bool| match (bold, italic) {
+        (true, true) => SpanStyle::BoldItalic,
+        (true, false) => SpanStyle::Bold,
+        (false, true) => SpanStyle::Italic,
+        (false, false) => SpanStyle::Regular,
+    };
+    let flush = |buf: &mut String, spans: &mut Vec<Span>, s: SpanStyle| {
+        if !buf.is_empty() {
+            spans.push(Span::plain(buf, s));
+            buf.clear();
+        }
+    };
+
+    while i < n {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < n => {
+                buf.push(chars[i + 1]);
+                i += 2;
+            }
+            '`' if next_tick[i + 1] < n => {
+                flush(&mut buf, &mut spans, style(bold, italic));
+                let end = next_tick[i + 1];
+                let code: String = chars[i + 1..end].iter().collect();
+                spans.push(Span::plain(&code, SpanStyle::Code));
+                i = end + 1;
+            }
+            '[' => {
+                // `](` always leaves room for the URL's start
+                let mid = next_link_mid[i + 1];
+                let end = if mid < n { next_paren[mid + 2] } else { n };
+                if end < n {
+                    flush(&mut buf, &mut spans, style(bold, italic));
+                    spans.push(Span {
+                        text: chars[i + 1..mid].iter().collect(),
+                        style: style(bold, italic),
+                        link: Some(chars[mid + 2..end].iter().collect()),
+                    });
+                    i = end + 1;
+                } else {
+                    buf.push(c);
+                    i += 1;
+                }
+            }
+            '*' | '_' => {
+                let run = chars[i..].iter().take_while(|&&ch| ch == c).count().min(3);
+                let last_runs = if c == '*' {
+                    &last_stars
+                } else {
+                    &last_underscores
+                };
+                let prev_alnum = i > 0 && chars[i - 1].is_alphanumeric();
+                let next_alnum = chars.get(i + run).is_some_and(|ch| ch.is_alphanumeric());
+                // snake_case words and lone markers stay literal
+                let intraword = c == '_' && prev_alnum && next_alnum;
+                let closing = (run >= 2 && bold) || (run % 2 == 1 && italic);
+                let closed_later = last_runs[run - 1].is_some_and(|start| start >= i + run);
+                if intraword || (!closing && !closed_later) {
+                    buf.extend(std::iter::repeat_n(c, run));
+                } else {
+                    flush(&mut buf, &mut spans, style(bold, italic));
+                    if run >= 2 {
+                        bold = !bold;
+                    }
+                    if run % 2 == 1 {
+                        italic = !italic;
+                    }
+                }
+                i += run;
+            }
+            _ => {
+                buf.push(c);
+                i += 1;
+            }
+        }
+    }
+    flush(&mut buf, &mut spans, style(bold, italic));
+    spans
+}
+
+fn spans_text(spans: &[Span]) -> String {
+    spans.iter().map(|s| s.text.as_str()).collect()
+}
+
+// ============================================================================
+// PDF layout
+// ============================================================================
+
+const PT_TO_MM: f32 = 0.352_778;
+
+/// Built-in PDF fonts used by the layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontKind {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Mono,
+}
+
+impl From<SpanStyle> for FontKind {
+    fn from(style: SpanStyle) -> Self {
+        match style {
+            SpanStyle::Regular => Self::Regular,
+            SpanStyle::Bold => Self::Bold,
+            SpanStyle::Italic => Self::Italic,
+            SpanStyle::BoldItalic => Self::BoldItalic,
+            SpanStyle::Code => Self::Mono,
+        }
+    }
+}
+
+/// Helvetica advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+fn char_width(c: char, font: FontKind) -> f32 {
+    let table = match font {
+        FontKind::Mono => return 600.0,
+        FontKind::Bold | FontKind::BoldItalic => &HELVETICA_BOLD_WIDTHS,
+        FontKind::Regular | FontKind::Italic => &HELVETICA_WIDTHS,
+    };
+    match c as u32 {
+        code @ 32..=126 => f32::from(table[(code - 32) as usize]),
+        _ if c.is_uppercase() => 722.0,
+        _ => 556.0,
+    }
+}
+
+/// Width of `text` in mm at `size` pt
+pub fn text_width(text: &str, font: FontKind, size: f32) -> f32 {
+    text.chars().map(|c| char_width(c, font)).sum::<f32>() * size / 1000.0 * PT_TO_MM
+}
+
+/// Replace characters the built-in fonts (WinAnsiEncoding) can't show
+fn pdf_safe(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\t' => ' ',
+            c if (c as u32) >= 32 && (c as u32) < 127 => c,
+            c if (c as u32) >= 160 && (c as u32) < 256 => c,
+            '€' | '‚' | 'ƒ' | '„' | '…' | '†' | '‡' | 'ˆ' | '‰' | 'Š' | '‹' | 'Œ' | 'Ž' | '‘'
+            | '’' | '“' | '”' | '•' | '–' | '—' | '˜' | '™' | 'š' | '›' | 'œ' | 'ž' | 'Ÿ' => {
+                c
+            }
+            '→' => '>',
+            '←' => '<',
+            '✓' | '✔' => 'v',
+            _ => '?',
+        })
+        .collect()
+}
+
+/// A positioned drawing operation, coordinates in mm from the bottom-left
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawOp {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        font: FontKind,
+        text: String,
+    },
+    Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        width: f32,
+        grey: f32,
+    },
+    Fill {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        grey: f32,
+    },
+}
+
+/// One laid-out page
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LaidOutPage {
+    pub ops: Vec<DrawOp>,
+}
+
+/// Built-in font family for body text
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FontFamily {
+    #[default]
+    Helvetica,
+    Times,
+    Courier,
+}
+
+impl FontFamily {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "times" | "times new roman" | "serif" => Self::Times,
+            "courier" | "mono" | "monospace" => Self::Courier,
+            _ => Self::Helvetica,
+        }
+    }
+}
+
+/// Page geometry and typography
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutOptions {
+    pub page_width: f32,
+    pub page_height: f32,
+    pub margin: f32,
+    pub base_size: f32,
+    pub line_spacing: f32,
+    pub family: FontFamily,
+    pub page_numbers: bool,
+    /// Full-page title before the content
+    pub cover_title: Option<String>,
+    /// Running header and footer; `{page}` and `{pages}` are substituted
+    pub header: Option<String>,
+    pub footer: Option<String>,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            page_width: 210.0,
+            page_height: 297.0,
+            margin: 20.0,
+            base_size: 11.0,
+            line_spacing: 1.4,
+            family: FontFamily::Helvetica,
+            page_numbers: true,
+            cover_title: None,
+            header: None,
+            footer: None,
+        }
+    }
+}
+
+impl LayoutOptions {
+    /// Options for a built-in template and page size
+    pub fn for_template(template: &str, page_size: &str, title: &str) -> Self {
+        let mut opts = Self::default().with_page_size(page_size);
+        match template {
+            "report" => opts.cover_title = Some(title.to_string()),
+            "minimal" => {
+                opts.margin = 15.0;
+                opts.base_size = 10.0;
+                opts.line_spacing = 1.3;
+                opts.page_numbers = false;
+            }
+            _ => {}
+        }
+        opts
+    }
+
+    /// Switch to US Letter for `"letter"`, keep A4 otherwise
+    pub fn with_page_size(mut self, page_size: &str) -> Self {
+        if page_size.eq_ignore_ascii_case("letter") {
+            self.page_width = 215.9;
+            self.page_height = 279.4;
+        }
+        self
+    }
+
+    fn content_width(&self) -> f32 {
+        self.page_width - 2.0 * self.margin
+    }
+
+    fn line_height(&self, size: f32) -> f32 {
+        size * self.line_spacing * PT_TO_MM
+    }
+}
+
+/// A word with its font, for wrapping
+struct Word {
+    text: String,
+    font: FontKind,
+    /// Whitespace separated it from the previous word
+    space_before: bool,
+}
+
+fn words_of(spans: &[Span]) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut pending_space = false;
+    for span in spans {
+        let font = FontKind::from(span.style);
+        let text = match &span.link {
+            Some(url) if *url != span.text => format!("{} ({})", span.text, url),
+            _ => span.text.clone(),
+        };
+        let mut current = String::new();
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !current.is_empty() {
+                    words.push(Word {
+                        text: std::mem::take(&mut current),
+                        font,
+                        space_before: pending_space,
+                    });
+                }
+                pending_space = true;
+            } else {
+                if current.is_empty() && words.is_empty() {
+                    pending_space = false;
+                }
+                current.push(c);
+            }
+        }
+        if !current.is_empty() {
+            words.push(Word {
+                text: current,
+                font,
+                space_before: pending_space,
+            });
+            pending_space = false;
+        }
+    }
+    words
+}
+
+/// A wrapped line: `(x offset, text, font)` fragments
+type WrappedLine = Vec<(f32, String, FontKind)>;
+
+/// Greedy word wrap of styled spans into lines no wider than `width` mm
+pub fn wrap_spans(spans: &[Span], width: f32, size: f32) -> Vec<WrappedLine> {
+    let mut lines: Vec<WrappedLine> = Vec::new();
+    let mut line: WrappedLine = Vec::new();
+    let mut x = 0.0f32;
+
+    for word in words_of(spans) {
+        let space = if word.space_before && !line.is_empty() {
+            text_width(" ", word.font, size)
+        } else {
+            0.0
+        };
+        let mut word_width = text_width(&word.text, word.font, size);
+
+        if !line.is_empty() && x + space + word_width > width {
+            lines.push(std::mem::take(&mut line));
+            x = 0.0;
+        }
+
+        // Words wider than the line are broken by characters
+        let mut text = word.text;
+        while word_width > width && text.chars().count() > 1 {
+            let mut taken = String::new();
+            let mut taken_width = 0.0;
+            for c in text.chars() {
+                let w = char_width(c, word.font) * size / 1000.0 * PT_TO_MM;
+                if taken_width + w > width - x && !taken.is_empty() {
+                    break;
+                }
+                taken.push(c);
+                taken_width += w;
+            }
+            text = text[taken.len()..].to_string();
+            line.push((x, taken, word.font));
+            lines.push(std::mem::take(&mut line));
+            x = 0.0;
+            word_width = text_width(&text, word.font, size);
+        }
+
+        let space = if line.is_empty() { 0.0 } else { space };
+        match line.last_mut() {
+            // Merge runs of the same font into one text operation
+            Some((_, fragment, font)) if *font == word.font => {
+                if space > 0.0 {
+                    fragment.push(' ');
+                }
+                fragment.push_str(&text);
+            }
+            _ => line.push((x + space, text, word.font)),
+        }
+        x += space + word_width;
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+struct Layouter<'a> {
+    opts: &'a LayoutOptions,
+    pages: Vec<LaidOutPage>,
+    /// Top of the free area on the current page (mm from bottom)
+    y: f32,
+}
+
+impl<'a> Layouter<'a> {
+    fn new(opts: &'a LayoutOptions) -> Self {
+        Self {
+            opts,
+            pages: vec![LaidOutPage::default()],
+            y: opts.page_height - opts.margin,
+        }
+    }
+
+    fn top(&self) -> f32 {
+        self.opts.page_height - self.opts.margin
+    }
+
+    fn bottom(&self) -> f32 {
+        // Leave room for the page number
+        self.opts.margin + if self.opts.page_numbers { 5.0 } else { 0.0 }
+    }
+
+    fn page_is_empty(&self) -> bool {
+        self.pages.last().is_none_or(|p| p.ops.is_empty())
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(LaidOutPage::default());
+        self.y = self.top();
+    }
+
+    /// Start a new page unless `height` fits in the remaining space
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < self.bottom() && !self.page_is_empty() {
+            self.new_page();
+        }
+    }
+
+    fn push(&mut self, op: DrawOp) {
+        if let Some(page) = self.pages.last_mut() {
+            page.ops.push(op);
+        }
+    }
+
+    fn gap(&mut self, mm: f32) {
+        if !self.page_is_empty() {
+            self.y -= mm;
+        }
+    }
+
+    /// Write wrapped lines at `x`, paginating as needed
+    fn write_lines(&mut self, lines: Vec<WrappedLine>, x: f32, size: f32) {
+        let line_height = self.opts.line_height(size);
+        for line in lines {
+            self.ensure(line_height);
+            self.y -= line_height;
+            let baseline = self.y + line_height * 0.25;
+            for (dx, text, font) in line {
+                self.push(DrawOp::Text {
+                    x: x + dx,
+                    y: baseline,
+                    size,
+                    font,
+                    text: pdf_safe(&text),
+                });
+            }
+        }
+    }
+
+    fn paragraph(&mut self, spans: &[Span], x: f32, width: f32, size: f32) {
+        let lines = wrap_spans(spans, width, size);
+        self.write_lines(lines, x, size);
+    }
+
+    fn heading(&mut self, level: u8, spans: &[Span]) {
+        let size = match level {
+            1 => 20.0,
+            2 => 16.0,
+            3 => 13.5,
+            _ => self.opts.base_size + 1.0,
+        };
+        let bold: Vec<Span> = spans
+            .iter()
+            .map(|s| Span {
+                style: SpanStyle::Bold,
+                ..s.clone()
+            })
+            .collect();
+        let width = self.opts.content_width();
+        let lines = wrap_spans(&bold, width, size);
+
+        // Keep the heading with at least one line of the following text
+        let needed = self.opts.line_height(size) * lines.len() as f32
+            + self.opts.line_height(self.opts.base_size) * 2.0;
+        self.gap(size * 0.6 * PT_TO_MM);
+        self.ensure(needed);
+        self.write_lines(lines, self.opts.margin, size);
+
+        if level <= 2 {
+            self.y -= 1.5;
+            let (x1, x2, y) = (
+                self.opts.margin,
+                self.opts.page_width - self.opts.margin,
+                self.y,
+            );
+            self.push(DrawOp::Line {
+                x1,
+                y1: y,
+                x2,
+                y2: y,
+                width: if level == 1 { 0.8 } else { 0.4 },
+                grey: 0.6,
+            });
+        }
+        self.y -= 2.0;
+    }
+
+    fn code(&mut self, text: &str) {
+        let size = self.opts.base_size - 1.5;
+        let line_height = self.opts.line_height(size);
+        let pad = 2.0;
+        let width = self.opts.content_width() - 2.0 * pad;
+        let max_chars = ((width / (600.0 * size / 1000.0 * PT_TO_MM)) as usize).max(1);
+
+        self.gap(1.0);
+        for source_line in text.lines() {
+            let chars: Vec<char> = source_line.chars().collect();
+            let chunks: Vec<String> = if chars.is_empty() {
+                vec![String::new()]
+            } else {
+                chars
+                    .chunks(max_chars)
+                    .map(|c| c.iter().collect())
+                    .collect()
+            };
+            for chunk in chunks {
+                self.ensure(line_height);
+                self.y -= line_height;
+                let x = self.opts.margin;
+                self.push(DrawOp::Fill {
+                    x,
+                    y: self.y,
+                    w: self.opts.content_width(),
+                    h: line_height,
+                    grey: 0.94,
+                });
+                self.push(DrawOp::Text {
+                    x: x + pad,
+                    y: self.y + line_height * 0.25,
+                    size,
+                    font: FontKind::Mono,
+                    text: pdf_safe(&chunk),
+                });
+            }
+        }
+        self.y -= 2.0;
+    }
+
+    fn list(&mut self, ordered: bool, items: &[Vec<Span>]) {
+        let size = self.opts.base_size;
+        let indent = 7.0;
+        let x = self.opts.margin + indent;
+        let width = self.opts.content_width() - indent;
+        for (n, item) in items.iter().enumerate() {
+            let marker = if ordered {
+                format!("{}.", n + 1)
+            } else {
+                "•".to_string()
+            };
+            let lines = wrap_spans(item, width, size);
+            let line_height = self.opts.line_height(size);
+            self.ensure(line_height);
+            let marker_y = self.y - line_height + line_height * 0.25;
+            self.push(DrawOp::Text {
+                x: self.opts.margin + 2.0,
+                y: marker_y,
+                size,
+                font: FontKind::Regular,
+                text: marker,
+            });
+            self.write_lines(lines, x, size);
+        }
+        self.y -= 1.5;
+    }
+
+    fn quote(&mut self, spans: &[Span]) {
+        let size = self.opts.base_size;
+        let indent = 6.0;
+        let italic: Vec<Span> = spans
+            .iter()
+            .map(|s| Span {
+                style: match s.style {
+                    SpanStyle::Bold | SpanStyle::BoldItalic => SpanStyle::BoldItalic,
+                    SpanStyle::Code => SpanStyle::Code,
+                    _ => SpanStyle::Italic,
+                },
+                ..s.clone()
+            })
+            .collect();
+        let lines = wrap_spans(&italic, self.opts.content_width() - indent, size);
+        let line_height = self.opts.line_height(size);
+        for line in lines {
+            self.ensure(line_height);
+            let top = self.y;
+            self.write_lines(vec![line], self.opts.margin + indent, size);
+            let x = self.opts.margin + 1.5;
+            self.push(DrawOp::Line {
+                x1: x,
+                y1: top,
+                x2: x,
+                y2: self.y,
+                width: 1.2,
+                grey: 0.7,
+            });
+        }
+        self.y -= 2.0;
+    }
+
+    fn table(&mut self, header: &[Vec<Span>], rows: &[Vec<Vec<Span>>]) {
+        let size = self.opts.base_size - 1.0;
+        let pad = 1.5;
+        let columns = rows
+            .iter()
+            .map(|r| r.len())
+            .chain([header.len()])
+            .max()
+            .unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+
+        // Column widths proportional to their widest cell, within bounds
+        let total = self.opts.content_width();
+        let natural: Vec<f32> = (0..columns)
+            .map(|c| {
+                std::iter::once(header)
+                    .chain(rows.iter().map(|r| r.as_slice()))
+                    .filter_map(|r| r.get(c))
+                    .map(|cell| text_width(&spans_text(cell), FontKind::Bold, size) + 2.0 * pad)
+                    .fold(12.0f32, f32::max)
+                    .min(total * 0.6)
+            })
+            .collect();
+        let scale = total / natural.iter().sum::<f32>();
+        let widths: Vec<f32> = natural.iter().map(|w| w * scale).collect();
+
+        let bold_header: Vec<Vec<Span>> = header
+            .iter()
+            .map(|cell| {
+                cell.iter()
+                    .map(|s| Span {
+                        style: SpanStyle::Bold,
+                        ..s.clone()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.gap(1.0);
+        self.table_row(&bold_header, &widths, size, pad, true);
+        for row in rows {
+            let page_before = self.pages.len();
+            let height = self.row_height(row, &widths, size, pad);
+            self.ensure(height);
+            // Repeat the header on each new page
+            if self.pages.len() != page_before {
+                self.table_row(&bold_header, &widths, size, pad, true);
+            }
+            self.table_row(row, &widths, size, pad, false);
+        }
+        self.y -= 3.0;
+    }
+
+    fn row_height(&self, row: &[Vec<Span>], widths: &[f32], size: f32, pad: f32) -> f32 {
+        let lines = widths
+            .iter()
+            .enumerate()
+            .map(|(c, w)| {
+                row.get(c)
+                    .map(|cell| wrap_spans(cell, w - 2.0 * pad, size).len())
+                    .unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        lines as f32 * self.opts.line_height(size) + 2.0 * pad
+    }
+
+    fn table_row(
+        &mut self,
+        row: &[Vec<Span>],
+        widths: &[f32],
+        size: f32,
+        pad: f32,
+        is_header: bool,
+    ) {
+        let height = self.row_height(row, widths, size, pad);
+        self.ensure(height);
+        let top = self.y;
+        let bottom = top - height;
+        let left = self.opts.margin;
+        let right = left + widths.iter().sum::<f32>();
+
+        if is_header {
+            self.push(DrawOp::Fill {
+                x: left,
+                y: bottom,
+                w: right - left,
+                h: height,
+                grey: 0.9,
+            });
+        }
+
+        let line_height = self.opts.line_height(size);
+        let mut x = left;
+        for (c, width) in widths.iter().enumerate() {
+            if let Some(cell) = row.get(c) {
+                let mut y = top - pad;
+                for line in wrap_spans(cell, width - 2.0 * pad, size) {
+                    y -= line_height;
+                    for (dx, text, font) in line {
+                        self.push(DrawOp::Text {
+                            x: x + pad + dx,
+                            y: y + line_height * 0.25,
+                            size,
+                            font,
+                            text: pdf_safe(&text),
+                        });
+                    }
+                }
+            }
+            x += width;
+        }
+
+        // Cell borders
+        self.push(DrawOp::Line {
+            x1: left,
+            y1: top,
+            x2: right,
+            y2: top,
+            width: 0.3,
+            grey: 0.5,
+        });
+        self.push(DrawOp::Line {
+            x1: left,
+            y1: bottom,
+            x2: right,
+            y2: bottom,
+            width: 0.3,
+            grey: 0.5,
+        });
+        let mut x = left;
+        for width in widths.iter().chain(std::iter::once(&0.0)) {
+            self.push(DrawOp::Line {
+                x1: x,
+                y1: top,
+                x2: x,
+                y2: bottom,
+                width: 0.3,
+                grey: 0.5,
+            });
+            x += width;
+        }
+        self.y = bottom;
+    }
+
+    fn cover(&mut self, title: &str) {
+        let size = 28.0;
+        let spans = vec![Span::plain(title, SpanStyle::Bold)];
+        let lines = wrap_spans(&spans, self.opts.content_width(), size);
+        self.y = self.opts.page_height * 0.6;
+        self.write_lines(lines, self.opts.margin, size);
+        self.y -= 4.0;
+        let (x1, x2, y) = (
+            self.opts.margin,
+            self.opts.page_width - self.opts.margin,
+            self.y,
+        );
+        self.push(DrawOp::Line {
+            x1,
+            y1: y,
+            x2,
+            y2: y,
+            width: 1.2,
+            grey: 0.3,
+        });
+        self.new_page();
+    }
+
+    /// Draw the running header, footer and page numbers on every page
+    fn decorate(&mut self) {
+        let opts = self.opts;
+        let total = self.pages.len();
+        let size = 9.0;
+        let fill = |template: &str, n: usize| {
+            pdf_safe(
+                &template
+                    .replace("{page}", &n.to_string())
+                    .replace("{pages}", &total.to_string()),
+            )
+        };
+        let centered =
+            |text: &str| (opts.page_width - text_width(text, FontKind::Regular, size)) / 2.0;
+
+        for (n, page) in self.pages.iter_mut().enumerate() {
+            let n = n + 1;
+            let mut text_at = |x: f32, y: f32, text: String| {
+                page.ops.push(DrawOp::Text {
+                    x,
+                    y,
+                    size,
+                    font: FontKind::Regular,
+                    text,
+                })
+            };
+
+            if let Some(header) = &opts.header {
+                let text = fill(header, n);
+                text_at(centered(&text), opts.page_height - opts.margin * 0.5, text);
+            }
+
+            let y = opts.margin * 0.5;
+            let number = format!("{} / {}", n, total);
+            match &opts.footer {
+                // Footer text on the left, page number on the right
+                Some(footer) => {
+                    text_at(opts.margin, y, fill(footer, n));
+                    if opts.page_numbers {
+                        let x = opts.page_width
+                            - opts.margin
+                            - text_width(&number, FontKind::Regular, size);
+                        text_at(x, y, number);
+                    }
+                }
+                None if opts.page_numbers => text_at(centered(&number), y, number),
+                None => {}
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<LaidOutPage> {
+        // A trailing page break shouldn't leave a blank page
+        if self.pages.len() > 1 && self.page_is_empty() {
+            self.pages.pop();
+        }
+        self.decorate();
+        self.pages
+    }
+}
+
+/// Lay out blocks into pages of positioned draw operations
+pub fn layout(blocks: &[Block], opts: &LayoutOptions) -> Vec<LaidOutPage> {
+    let mut l = Layouter::new(opts);
+    if let Some(title) = &opts.cover_title {
+        l.cover(title);
+    }
+
+    for block in blocks {
+        match block {
+            Block::Heading(level, spans) => l.heading(*level, spans),
+            Block::Paragraph(spans) => {
+                l.paragraph(spans, opts.margin, opts.content_width(), opts.base_size);
+                l.y -= opts.base_size * 0.5 * PT_TO_MM;
+            }
+            Block::Code { text, .. } => l.code(text),
+            Block::List { ordered, items } => l.list(*ordered, items),
+            Block::Quote(spans) => l.quote(spans),
+            Block::Table { header, rows } => l.table(header, rows),
+            Block::Rule => {
+                l.gap(2.0);
+                l.ensure(4.0);
+                let (x1, x2, y) = (opts.margin, opts.page_width - opts.margin, l.y);
+                l.push(DrawOp::Line {
+                    x1,
+                    y1: y,
+                    x2,
+                    y2: y,
+                    width: 0.4,
+                    grey: 0.6,
+                });
+                l.y -= 4.0;
+            }
+            Block::PageBreak => {
+                if !l.page_is_empty() {
+                    l.new_page();
+                }
+            }
+        }
+    }
+
+    l.finish()
+}
+
+/// Lay out plain text: each line is wrapped to the page width, blank lines
+/// are kept and pages are added as needed
+pub fn layout_text(text: &str, opts: &LayoutOptions) -> Vec<LaidOutPage> {
+    let mut l = Layouter::new(opts);
+    let style = if opts.family == FontFamily::Courier {
+        SpanStyle::Code
+    } else {
+        SpanStyle::Regular
+    };
+    let line_height = opts.line_height(opts.base_size);
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            l.gap(line_height);
+            continue;
+        }
+        l.paragraph(
+            &[Span::plain(line, style)],
+            opts.margin,
+            opts.content_width(),
+            opts.base_size,
+        );
+    }
+
+    l.finish()
+}
+
+/// Emit laid-out pages as a PDF file
+pub fn write_pdf(
+    pages: &[LaidOutPage],
+    opts: &LayoutOptions,
+    title: &str,
+    path: &Path,
+) -> Result<(), ToolError> {
+    use printpdf::path::PaintMode;
+    use printpdf::*;
+
+    let (doc, page1, layer1) =
+        PdfDocument::new(title, Mm(opts.page_width), Mm(opts.page_height), "Layer 1");
+    let font_error =
+        |e: printpdf::Error| ToolError::ExecutionFailed(format!("Erreur police: {}", e));
+    // Times is laid out with Helvetica metrics, which are slightly wider,
+    // so wrapped lines never overflow
+    let [regular, bold, italic, bold_italic] = match opts.family {
+        FontFamily::Helvetica | FontFamily::Courier => [
+            BuiltinFont::Helvetica,
+            BuiltinFont::HelveticaBold,
+            BuiltinFont::HelveticaOblique,
+            BuiltinFont::HelveticaBoldOblique,
+        ],
+        FontFamily::Times => [
+            BuiltinFont::TimesRoman,
+            BuiltinFont::TimesBold,
+            BuiltinFont::TimesItalic,
+            BuiltinFont::TimesBoldItalic,
+        ],
+    }
+    .map(|font| doc.add_builtin_font(font).map_err(font_error));
+    let (regular, bold, italic, bold_italic) = (regular?, bold?, italic?, bold_italic?);
+    let mono = doc
+        .add_builtin_font(BuiltinFont::Courier)
+        .map_err(font_error)?;
+    let black = Color::Greyscale(Greyscale::new(0.0, None));
+
+    for (n, page) in pages.iter().enumerate() {
+        let layer = if n == 0 {
+            doc.get_page(page1).get_layer(layer1)
+        } else {
+            let (p, l) = doc.add_page(Mm(opts.page_width), Mm(opts.page_height), "Layer 1");
+            doc.get_page(p).get_layer(l)
+        };
+
+        for op in &page.ops {
+            match op {
+                DrawOp::Text {
+                    x,
+                    y,
+                    size,
+                    font,
+                    text,
+                } => {
+                    let font_ref = match font {
+                        FontKind::Regular => &regular,
+                        FontKind::Bold => &bold,
+                        FontKind::Italic => &italic,
+                        FontKind::BoldItalic => &bold_italic,
+                        FontKind::Mono => &mono,
+                    };
+                    layer.use_text(text.clone(), *size, Mm(*x), Mm(*y), font_ref);
+                }
+                DrawOp::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    width,
+                    grey,
+                } => {
+                    layer.set_outline_color(Color::Greyscale(Greyscale::new(*grey, None)));
+                    layer.set_outline_thickness(*width / PT_TO_MM);
+                    layer.add_line(Line {
+                        points: vec![
+                            (Point::new(Mm(*x1), Mm(*y1)), false),
+                            (Point::new(Mm(*x2), Mm(*y2)), false),
+                        ],
+                        is_closed: false,
+                    });
+                }
+                DrawOp::Fill { x, y, w, h, grey } => {
+                    layer.set_fill_color(Color::Greyscale(Greyscale::new(*grey, None)));
+                    layer.add_rect(
+                        Rect::new(Mm(*x), Mm(*y), Mm(x + w), Mm(y + h)).with_mode(PaintMode::Fill),
+                    );
+                    layer.set_fill_color(black.clone());
+                }
+            }
+        }
+    }
+
+    // Render in memory first so a full disk is reported before writing
+    let mut writer = std::io::BufWriter::new(Vec::new());
+    doc.save(&mut writer)
+        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur sauvegarde PDF: {}", e)))?;
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur sauvegarde PDF: {}", e)))?;
+    ensure_free_space(path, bytes.len() as u64)?;
+    std::fs::write(path, bytes)
+        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur création fichier: {}", e)))
+}
+
+// ============================================================================
+// HTML rendering
+// ============================================================================
+
+const HTML_BASE_STYLE: &str = "body{max-width:46em;margin:2.5em auto;padding:0 1.5em;line-height:1.55;color:#222}\
+h1,h2{border-bottom:1px solid #ccc;padding-bottom:.2em}\
+pre{background:#f4f4f4;padding:.8em 1em;border-radius:4px;overflow-x:auto}\
+code{font-family:Consolas,Menlo,monospace;font-size:.92em}\
+:not(pre)>code{background:#f4f4f4;padding:.1em .3em;border-radius:3px}\
+table{border-collapse:collapse;margin:1em 0}th,td{border:1px solid #bbb;padding:.35em .7em;text-align:left}\
+th{background:#eee}blockquote{margin:1em 0;padding-left:1em;border-left:3px solid #bbb;color:#555;font-style:italic}\
+hr{border:0;border-top:1px solid #ccc}.page-break{page-break-after:always}\
+@media print{body{margin:0;max-width:none}}";
+
+/// Built-in HTML template by name
+fn html_template(name: &str) -> String {
+    let extra = match name {
+        "report" => "body{font-family:Georgia,'Times New Roman',serif}header.cover{min-height:60vh;display:flex;align-items:flex-end;border-bottom:3px solid #333;margin-bottom:2em}header.cover h1{font-size:2.6em;border:0}",
+        "minimal" => "body{font-family:system-ui,sans-serif;max-width:40em}h1,h2{border:0}",
+        _ => "body{font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif}",
+    };
+    let cover = if name == "report" {
+        "<header class=\"cover\"><h1>{{title}}</h1></header>\n"
+    } else {
+        ""
+    };
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{{{{title}}}}</title>\n<style>{}{}</style>\n</head>\n<body>\n{}{{{{content}}}}\n</body>\n</html>\n",
+        HTML_BASE_STYLE, extra, cover
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Link targets kept in HTML; others (`javascript:`, `data:`) lose the link
+fn is_safe_link(url: &str) -> bool {
+    let url = url.trim().to_ascii_lowercase();
+    ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
+fn spans_to_html(spans: &[Span]) -> String {
+    spans
+        .iter()
+        .map(|span| {
+            let text = escape_html(&span.text);
+            let styled = match span.style {
+                SpanStyle::Regular => text,
+                SpanStyle::Bold => format!("<strong>{}</strong>", text),
+                SpanStyle::Italic => format!("<em>{}</em>", text),
+                SpanStyle::BoldItalic => format!("<strong><em>{}</em></strong>", text),
+                SpanStyle::Code => format!("<code>{}</code>", text),
+            };
+            match &span.link {
+                Some(url) if is_safe_link(url) => {
+                    format!("<a href=\"{}\">{}</a>", escape_html(url), styled)
+                }
+                _ => styled,
+            }
+        })
+        .collect()
+}
+
+/// Render blocks as an HTML fragment
+pub fn blocks_to_html(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, spans) => {
+                out.push_str(&format!("<h{0}>{1}</h{0}>\n", level, spans_to_html(spans)))
+            }
+            Block::Paragraph(spans) => out.push_str(&format!("<p>{}</p>\n", spans_to_html(spans))),
+            Block::Code { lang, text } => {
+                let class = if lang.is_empty() {
+                    String::new()
+                } else {
+                    format!(" class=\"language-{}\"", escape_html(lang))
+                };
+                out.push_str(&format!(
+                    "<pre><code{}>{}</code></pre>\n",
+                    class,
+                    escape_html(text)
+                ));
+            }
+            Block::List { ordered, items } => {
+                let tag = if *ordered { "ol" } else { "ul" };
+                out.push_str(&format!("<{}>\n", tag));
+                for item in items {
+                    out.push_str(&format!("<li>{}</li>\n", spans_to_html(item)));
+                }
+                out.push_str(&format!("</{}>\n", tag));
+            }
+            Block::Quote(spans) => out.push_str(&format!(
+                "<blockquote>{}</blockquote>\n",
+                spans_to_html(spans)
+            )),
+            Block::Table { header, rows } => {
+                out.push_str("<table>\n<thead><tr>");
+                for cell in header {
+                    out.push_str(&format!("<th>{}</th>", spans_to_html(cell)));
+                }
+                out.push_str("</tr></thead>\n<tbody>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        out.push_str(&format!("<td>{}</td>", spans_to_html(cell)));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</tbody>\n</table>\n");
+            }
+            Block::Rule => out.push_str("<hr>\n"),
+            Block::PageBreak => out.push_str("<div class=\"page-break\"></div>\n"),
+        }
+    }
+    out
+}
+
+/// Fill a template's `{{title}}` and `{{content}}` placeholders
+pub fn render_html(blocks: &[Block], title: &str, template: &str) -> String {
+    template
+        .replace("{{title}}", &escape_html(title))
+        .replace("{{content}}", &blocks_to_html(blocks))
+}
+
+// ============================================================================
+// DocumentRenderTool
+// ============================================================================
+
+pub struct DocumentRenderTool;
+
+#[async_trait]
+impl Tool for DocumentRenderTool {
+    fn name(&self) -> &str {
+        "document_render"
+    }
+
+    fn description(&self) -> &str {
+        "Render a PDF (multi-page, automatic wrapping, tables, code) or HTML document from Markdown. Prefer it to pdf_create for any long or structured content."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path of the file to create (.pdf or .html)"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Markdown content (headings, lists, tables, code blocks). Page break: <!-- pagebreak -->"
+                },
+                "source_path": {
+                    "type": "string",
+                    "description": "Markdown file to read instead of content"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["pdf", "html"],
+                    "description": "Output format (default: inferred from the extension)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Document title (default: first Markdown heading)"
+                },
+                "template": {
+                    "type": "string",
+                    "description": "Template: 'default', 'report' (title page), 'minimal', or path to an HTML template containing {{content}}"
+                },
+                "page_size": {
+                    "type": "string",
+                    "enum": ["a4", "letter"],
+                    "description": "PDF page size (default: a4)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path_str = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+
+        let markdown = match (params["content"].as_str(), params["source_path"].as_str()) {
+            (Some(content), _) => content.to_string(),
+            (None, Some(source)) => std::fs::read_to_string(source).map_err(|e| {
+                ToolError::ExecutionFailed(format!("Erreur lecture '{}': {}", source, e))
+            })?,
+            (None, None) => {
+                return Err(ToolError::InvalidParameters(
+                    "content or source_path is required".into(),
+                ))
+            }
+        };
+
+        let path = PathBuf::from(path_str);
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        let format = match params["format"].as_str() {
+            Some(f) => f.to_lowercase(),
+            None if extension == "html" || extension == "htm" => "html".to_string(),
+            None => "pdf".to_string(),
+        };
+
+        let blocks = parse_markdown(&markdown);
+        let title = params["title"]
+            .as_str()
+            .map(|t| t.to_string())
+            .or_else(|| {
+                blocks.iter().find_map(|b| match b {
+                    Block::Heading(_, spans) => Some(spans_text(spans)),
+                    _ => None,
+                })
+            })
+            .unwrap_or_else(|| "Document".to_string());
+        let template = params["template"].as_str().unwrap_or("default");
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ToolError::ExecutionFailed(format!("Erreur création dossier: {}", e))
+            })?;
+        }
+
+        let pages = match format.as_str() {
+            "html" => {
+                let template_html = if template.ends_with(".html") || template.ends_with(".htm") {
+                    let custom = std::fs::read_to_string(template).map_err(|e| {
+                        ToolError::ExecutionFailed(format!(
+                            "Erreur lecture du modèle '{}': {}",
+                            template, e
+                        ))
+                    })?;
+                    if !custom.contains("{{content}}") {
+                        return Err(ToolError::InvalidParameters(
+                            "Le modèle HTML doit contenir {{content}}".into(),
+                        ));
+                    }
+                    custom
+                } else {
+                    html_template(template)
+                };
+                let html = render_html(&blocks, &title, &template_html);
+                ensure_free_space(&path, html.len() as u64)?;
+                std::fs::write(&path, html)
+                    .map_err(|e| ToolError::ExecutionFailed(format!("Erreur écriture: {}", e)))?;
+                None
+            }
+            "pdf" => {
+                let page_size = params["page_size"].as_str().unwrap_or("a4");
+                let opts = LayoutOptions::for_template(template, page_size, &title);
+                let pages = layout(&blocks, &opts);
+                write_pdf(&pages, &opts, &title, &path)?;
+                Some(pages.len())
+            }
+            other => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "Format inconnu: {}",
+                    other
+                )));
+            }
+        };
+
+        let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path_str,
+                "format": format,
+                "title": title,
+                "pages": pages,
+                "blocks": blocks.len(),
+                "size_bytes": size_bytes
+            }),
+            message: match pages {
+                Some(count) => format!("Document PDF créé: {} ({} pages)", path_str, count),
+                None => format!("Document HTML créé: {}", path_str),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# Rapport\n\nIntro avec **gras** et `code`.\n\n- un\n- deux\n\n| Nom | Valeur |\n|-----|--------|\n| a | 1 |\n\n```rust\nfn main() {}\n```\n\n> citation\n\n---\n";
+
+    #[test]
+    fn test_parse_markdown_blocks() {
+        let blocks = parse_markdown(SAMPLE);
+        assert!(matches!(&blocks[0], Block::Heading(1, _)));
+        assert!(matches!(&blocks[1], Block::Paragraph(_)));
+        assert!(matches!(&blocks[2], Block::List { ordered: false, items } if items.len() == 2));
+        assert!(
+            matches!(&blocks[3], Block::Table { header, rows } if header.len() == 2 && rows.len() == 1)
+        );
+        assert!(matches!(&blocks[4], Block::Code { lang, .. } if lang == "rust"));
+        assert!(matches!(&blocks[5], Block::Quote(_)));
+        assert_eq!(blocks[6], Block::Rule);
+    }
+
+    #[test]
+    fn test_parse_inline() {
+        let spans = parse_inline("a **b** *c* `d` [e](http://x) snake_case_name 2*3");
+        let styles: Vec<(String, SpanStyle)> =
+            spans.iter().map(|s| (s.text.clone(), s.style)).collect();
+        assert!(styles.contains(&("b".to_string(), SpanStyle::Bold)));
+        assert!(styles.contains(&("c".to_string(), SpanStyle::Italic)));
+        assert!(styles.contains(&("d".to_string(), SpanStyle::Code)));
+        assert_eq!(
+            spans
+                .iter()
+                .find(|s| s.text == "e")
+                .and_then(|s| s.link.clone()),
+            Some("http://x".to_string())
+        );
+        assert!(spans_text(&spans).contains("snake_case_name 2*3"));
+    }
+
+    #[test]
+    fn test_wrapping_stays_within_width() {
+        let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(20);
+        let spans = vec![
+            Span::plain(&text, SpanStyle::Regular),
+            Span::plain(" fin en gras", SpanStyle::Bold),
+        ];
+        let lines = wrap_spans(&spans, 100.0, 11.0);
+        assert!(lines.len() > 5);
+        for line in &lines {
+            let (x, text, font) = line.last().unwrap();
+            assert!(x + text_width(text, *font, 11.0) <= 100.0 + 0.01);
+        }
+        // A single huge word is broken instead of overflowing
+        let long = vec![Span::plain(&"x".repeat(400), SpanStyle::Code)];
+        assert!(wrap_spans(&long, 50.0, 10.0).len() > 1);
+    }
+
+    #[test]
+    fn test_layout_paginates() {
+        let markdown = (0..120)
+            .map(|n| format!("Paragraphe numéro {} avec du texte.\n", n))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let opts = LayoutOptions::default();
+        let pages = layout(&parse_markdown(&markdown), &opts);
+        assert!(pages.len() > 1);
+        for page in &pages {
+            for op in &page.ops {
+                if let DrawOp::Text { y, .. } = op {
+                    assert!(*y >= opts.margin * 0.5 - 0.01 && *y <= opts.page_height - opts.margin);
+                }
+            }
+        }
+
+        let with_cover = LayoutOptions::for_template("report", "a4", "Titre");
+        assert_eq!(layout(&parse_markdown("court"), &with_cover).len(), 2);
+        assert_eq!(
+            layout(&parse_markdown("a\n\n<!-- pagebreak -->\n\nb"), &opts).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_layout_text_header_footer() {
+        let opts = LayoutOptions {
+            family: FontFamily::Courier,
+            header: Some("Rapport {page}/{pages}".to_string()),
+            footer: Some("Confidentiel".to_string()),
+            ..LayoutOptions::default()
+        };
+        let text = format!("{}\n\n{}", "x".repeat(1000), "ligne\n".repeat(80));
+        let pages = layout_text(&text, &opts);
+        assert!(pages.len() >= 2);
+
+        let texts: Vec<&str> = pages[1]
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                DrawOp::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(texts.contains(&format!("Rapport 2/{}", pages.len()).as_str()));
+        assert!(texts.contains(&"Confidentiel"));
+        assert!(texts.contains(&format!("2 / {}", pages.len()).as_str()));
+    }
+
+    #[test]
+    fn test_render_html() {
+        let html = render_html(
+            &parse_markdown(SAMPLE),
+            "R&D <1>",
+            &html_template("default"),
+        );
+        assert!(html.contains("<title>R&amp;D &lt;1&gt;</title>"));
+        assert!(html.contains("<strong>gras</strong>"));
+        assert!(html.contains("<th>Nom</th>"));
+        assert!(html.contains("<pre><code class=\"language-rust\">fn main() {}</code></pre>"));
+        assert!(!html.contains("{{content}}"));
+    }
+
+    #[test]
+    fn test_render_html_drops_unsafe_links() {
+        let blocks = parse_markdown(
+            "[a](https://x.org) [b](mailto:me@x.org) [c](javascript:alert(1)) [d](data:text/html,x)",
+        );
+        let html = render_html(&blocks, "t", &html_template("default"));
+        assert!(html.contains("<a href=\"https://x.org\">a</a>"));
+        assert!(html.contains("<a href=\"mailto:me@x.org\">b</a>"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("data:text"));
+    }
+}
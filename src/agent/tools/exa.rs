@@ -7,11 +7,19 @@
 //! - deep_research: In-depth research with AI analysis
 
 use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde_json::Value;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolContext, ToolError, ToolProgress, ToolResult};
+use crate::agent::research::{self, ResearchTask};
+use crate::agent::tools::rate_limit::{self, Failure, ProviderLimits};
+use crate::system::proxy::{client_builder, ProxyClient};
+
+/// Rate limit and circuit breaker key, named in errors shown to the model
+const EXA_PROVIDER: &str = "Recherche web (Exa)";
 
 /// Exa search configuration
 #[derive(Clone, Debug)]
@@ -42,16 +50,17 @@ pub struct ExaMcpClient {
 }
 
 impl ExaMcpClient {
-    pub fn new(config: ExaSearchConfig) -> Self {
-        Self {
+    pub fn new(config: ExaSearchConfig) -> Result<Self, ToolError> {
+        let client = client_builder(ProxyClient::WebSearch)
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| ToolError::ExecutionFailed(format!("Client HTTP: {}", e)))?;
+        Ok(Self {
             config,
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(60))
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new()),
+            client,
             initialized: AtomicBool::new(false),
             request_id: AtomicU64::new(1),
-        }
+        })
     }
 
     fn next_id(&self) -> u64 {
@@ -68,37 +77,44 @@ impl ExaMcpClient {
 
         tracing::debug!("Exa MCP request: {} - {:?}", method, params);
 
+        rate_limit::run(EXA_PROVIDER, &ProviderLimits::search(), || self.send(&request)).await
+    }
+
+    /// One attempt at an MCP request
+    async fn send(&self, request: &Value) -> Result<Value, Failure> {
         let response = self
             .client
             .post(&self.config.mcp_url)
             .header("Accept", "application/json, text/event-stream")
             .header("Content-Type", "application/json")
-            .json(&request)
+            .json(request)
             .send()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("MCP request failed: {}", e)))?;
+            .map_err(|e| Failure::from_request(e, "MCP request failed"))?;
 
         let status = response.status();
+        let headers = response.headers().clone();
         let body = response
             .text()
             .await
             .unwrap_or_else(|_| "Unknown response".to_string());
 
         if !status.is_success() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "MCP HTTP error ({}): {}",
-                status, body
-            )));
+            return Err(Failure::from_status(
+                status,
+                &headers,
+                format!("MCP HTTP error ({}): {}", status, body),
+            ));
         }
 
-        let value = parse_mcp_body(&body)?;
+        let value = parse_mcp_body(&body).map_err(Failure::fatal)?;
 
         if let Some(err) = value.get("error") {
             let message = err
                 .get("message")
                 .and_then(|m| m.as_str())
                 .unwrap_or("MCP error");
-            return Err(ToolError::ExecutionFailed(message.to_string()));
+            return Err(Failure::fatal(ToolError::ExecutionFailed(message.to_string())));
         }
 
         Ok(value)
@@ -161,10 +177,10 @@ pub struct ExaSearchTool {
 }
 
 impl ExaSearchTool {
-    pub fn new(config: ExaSearchConfig) -> Self {
-        Self {
-            client: Arc::new(ExaMcpClient::new(config)),
-        }
+    pub fn new(config: ExaSearchConfig) -> Result<Self, ToolError> {
+        Ok(Self {
+            client: Arc::new(ExaMcpClient::new(config)?),
+        })
     }
     
     pub fn with_client(client: Arc<ExaMcpClient>) -> Self {
@@ -248,10 +264,10 @@ pub struct ExaCodeSearchTool {
 }
 
 impl ExaCodeSearchTool {
-    pub fn new(config: ExaSearchConfig) -> Self {
-        Self {
-            client: Arc::new(ExaMcpClient::new(config)),
-        }
+    pub fn new(config: ExaSearchConfig) -> Result<Self, ToolError> {
+        Ok(Self {
+            client: Arc::new(ExaMcpClient::new(config)?),
+        })
     }
     
     pub fn with_client(client: Arc<ExaMcpClient>) -> Self {
@@ -334,10 +350,10 @@ pub struct ExaCompanyResearchTool {
 }
 
 impl ExaCompanyResearchTool {
-    pub fn new(config: ExaSearchConfig) -> Self {
-        Self {
-            client: Arc::new(ExaMcpClient::new(config)),
-        }
+    pub fn new(config: ExaSearchConfig) -> Result<Self, ToolError> {
+        Ok(Self {
+            client: Arc::new(ExaMcpClient::new(config)?),
+        })
     }
     
     pub fn with_client(client: Arc<ExaMcpClient>) -> Self {
@@ -414,10 +430,10 @@ pub struct ExaDeepResearchStartTool {
 }
 
 impl ExaDeepResearchStartTool {
-    pub fn new(config: ExaSearchConfig) -> Self {
-        Self {
-            client: Arc::new(ExaMcpClient::new(config)),
-        }
+    pub fn new(config: ExaSearchConfig) -> Result<Self, ToolError> {
+        Ok(Self {
+            client: Arc::new(ExaMcpClient::new(config)?),
+        })
     }
     
     pub fn with_client(client: Arc<ExaMcpClient>) -> Self {
@@ -432,7 +448,7 @@ impl Tool for ExaDeepResearchStartTool {
     }
 
     fn description(&self) -> &str {
-        "Start an AI-powered deep research task. The AI will search the web, read many sources, and think deeply about your question. Returns a task_id to check results later with deep_research_check."
+        "Start an AI-powered deep research task. The AI will search the web, read many sources, and think deeply about your question. It runs in the background and keeps a research document with interim findings. Returns a task_id to check results later with deep_research_check."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -463,26 +479,32 @@ impl Tool for ExaDeepResearchStartTool {
             )
             .await?;
 
-        let task_id = result
-            .get("content")
-            .and_then(|c| c.as_array())
-            .and_then(|arr| arr.first())
-            .and_then(|item| item.get("text"))
-            .and_then(|t| t.as_str())
-            .unwrap_or("");
-        
-        // Try to extract task_id from response
-        let extracted_id = if task_id.contains("task_id") || task_id.contains("ID") {
-            task_id.to_string()
-        } else {
-            extract_text(&result)
-        };
+        let task_info = extract_text(&result);
+
+        // Hand the task to the background poller when its id can be found
+        if let Some(task_id) = parse_task_id(&task_info) {
+            let task = research::start(self.client.clone(), task_id.clone(), query.to_string());
+            return Ok(ToolResult {
+                success: true,
+                data: serde_json::json!({
+                    "query": query,
+                    "task_id": task_id,
+                    "status": "started",
+                    "document": task.document.map(|p| p.display().to_string()),
+                }),
+                message: format!(
+                    "Recherche approfondie démarrée en arrière-plan pour: {} (task_id: {}). \
+                     Continuez d'autres étapes si besoin, puis appelez deep_research_check pour le rapport.",
+                    query, task_id
+                ),
+            });
+        }
 
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({
                 "query": query,
-                "task_info": extracted_id,
+                "task_info": task_info,
                 "status": "started"
             }),
             message: format!("Recherche approfondie démarrée pour: {}", query),
@@ -500,17 +522,64 @@ pub struct ExaDeepResearchCheckTool {
 }
 
 impl ExaDeepResearchCheckTool {
-    pub fn new(config: ExaSearchConfig) -> Self {
-        Self {
-            client: Arc::new(ExaMcpClient::new(config)),
-        }
+    pub fn new(config: ExaSearchConfig) -> Result<Self, ToolError> {
+        Ok(Self {
+            client: Arc::new(ExaMcpClient::new(config)?),
+        })
     }
     
     pub fn with_client(client: Arc<ExaMcpClient>) -> Self {
         Self { client }
     }
+
+    /// Poll the task once, returning its status and content
+    async fn check(&self, task_id: &str) -> Result<(&'static str, String), ToolError> {
+        check_deep_research(&self.client, task_id).await
+    }
+
+    /// Result for a task managed by the background poller
+    fn managed_result(task: &ResearchTask) -> ToolResult {
+        let content = match (&task.report, &task.error) {
+            (Some(report), _) => report.clone(),
+            (None, Some(error)) => error.clone(),
+            (None, None) => task
+                .findings
+                .last()
+                .map(|f| f.text.clone())
+                .unwrap_or_default(),
+        };
+        ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "task_id": task.id,
+                "status": task.status.id(),
+                "elapsed_secs": task.elapsed_secs(),
+                "interim_findings": task.findings.len(),
+                "content": content,
+                "document": task.document.as_ref().map(|p| p.display().to_string()),
+            }),
+            message: format!("Statut recherche: {}", task.status.id()),
+        }
+    }
+
+    fn to_result(task_id: &str, status: &str, content_text: String) -> ToolResult {
+        ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "task_id": task_id,
+                "status": status,
+                "content": content_text
+            }),
+            message: format!("Statut recherche: {}", status),
+        }
+    }
 }
 
+/// How long a streaming check keeps polling before handing back to the agent
+const DEEP_RESEARCH_WAIT_SECS: u64 = 100;
+/// Delay between polls while waiting
+const DEEP_RESEARCH_POLL_SECS: u64 = 10;
+
 #[async_trait]
 impl Tool for ExaDeepResearchCheckTool {
     fn name(&self) -> &str {
@@ -539,36 +608,66 @@ impl Tool for ExaDeepResearchCheckTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("task_id is required".to_string()))?;
 
-        let result = self
-            .client
-            .call_tool(
-                "deep_researcher_check",
-                serde_json::json!({
-                    "taskId": task_id
-                }),
-            )
-            .await?;
+        if let Some(task) = research::get(task_id) {
+            return Ok(Self::managed_result(&task));
+        }
+        let (status, content_text) = self.check(task_id).await?;
+        Ok(Self::to_result(task_id, status, content_text))
+    }
 
-        let content_text = extract_text(&result);
-        
-        // Determine status based on content
-        let status = if content_text.contains("pending") || content_text.contains("running") {
-            "in_progress"
-        } else if content_text.contains("error") || content_text.contains("failed") {
-            "failed"
-        } else {
-            "completed"
-        };
+    /// Keeps polling while the research is running, reporting each status,
+    /// so the agent doesn't have to call the tool in a loop.
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let task_id = params["task_id"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("task_id is required".to_string()))?;
 
-        Ok(ToolResult {
-            success: true,
-            data: serde_json::json!({
-                "task_id": task_id,
-                "status": status,
-                "content": content_text
-            }),
-            message: format!("Statut recherche: {}", status),
-        })
+        let start = std::time::Instant::now();
+
+        // Managed task: wait on the background poller instead of polling
+        if research::get(task_id).is_some() {
+            loop {
+                let Some(task) = research::get(task_id) else {
+                    return Err(ToolError::NotFound(task_id.to_string()));
+                };
+                let elapsed = start.elapsed().as_secs();
+                if !task.is_running() || elapsed + DEEP_RESEARCH_POLL_SECS > DEEP_RESEARCH_WAIT_SECS {
+                    return Ok(Self::managed_result(&task));
+                }
+                let _ = ctx.progress.send(ToolProgress::Status(format!(
+                    "Recherche en cours... ({}s, {} étapes intermédiaires)",
+                    task.elapsed_secs(),
+                    task.findings.len()
+                )));
+                tokio::select! {
+                    _ = tokio::time::sleep(std::time::Duration::from_secs(DEEP_RESEARCH_POLL_SECS)) => {}
+                    _ = ctx.cancel.cancelled() => return Err(ToolError::Cancelled),
+                }
+            }
+        }
+
+        loop {
+            let (status, content_text) = tokio::select! {
+                checked = self.check(task_id) => checked?,
+                _ = ctx.cancel.cancelled() => return Err(ToolError::Cancelled),
+            };
+            let elapsed = start.elapsed().as_secs();
+            if status != "in_progress" || elapsed + DEEP_RESEARCH_POLL_SECS > DEEP_RESEARCH_WAIT_SECS {
+                return Ok(Self::to_result(task_id, status, content_text));
+            }
+            let _ = ctx.progress.send(ToolProgress::Status(format!(
+                "Recherche en cours... ({}s)",
+                elapsed
+            )));
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_secs(DEEP_RESEARCH_POLL_SECS)) => {}
+                _ = ctx.cancel.cancelled() => return Err(ToolError::Cancelled),
+            }
+        }
     }
 }
 
@@ -582,10 +681,10 @@ pub struct ExaCrawlTool {
 }
 
 impl ExaCrawlTool {
-    pub fn new(config: ExaSearchConfig) -> Self {
-        Self {
-            client: Arc::new(ExaMcpClient::new(config)),
-        }
+    pub fn new(config: ExaSearchConfig) -> Result<Self, ToolError> {
+        Ok(Self {
+            client: Arc::new(ExaMcpClient::new(config)?),
+        })
     }
     
     pub fn with_client(client: Arc<ExaMcpClient>) -> Self {
@@ -648,6 +747,51 @@ impl Tool for ExaCrawlTool {
 // Helper Functions
 // ============================================================================
 
+/// Poll a deep research task once, returning its status and content
+pub(crate) async fn check_deep_research(
+    client: &ExaMcpClient,
+    task_id: &str,
+) -> Result<(&'static str, String), ToolError> {
+    let result = client
+        .call_tool(
+            "deep_researcher_check",
+            serde_json::json!({
+                "taskId": task_id
+            }),
+        )
+        .await?;
+
+    let content_text = extract_text(&result);
+
+    // Determine status based on content
+    let status = if content_text.contains("pending") || content_text.contains("running") {
+        "in_progress"
+    } else if content_text.contains("error") || content_text.contains("failed") {
+        "failed"
+    } else {
+        "completed"
+    };
+
+    Ok((status, content_text))
+}
+
+/// Task id in a deep_researcher_start response, as JSON or plain text
+fn parse_task_id(text: &str) -> Option<String> {
+    if let Ok(value) = serde_json::from_str::<Value>(text.trim()) {
+        for key in ["taskId", "task_id", "researchId", "id"] {
+            if let Some(id) = value.get(key).and_then(|v| v.as_str()) {
+                return Some(id.to_string());
+            }
+        }
+    }
+    static TASK_ID_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?i)(?:task[_ ]?id|research[_ ]?id)["'`]?\s*[:=]?\s*["'`]?([A-Za-z0-9_-]{6,})"#).unwrap()
+    });
+    TASK_ID_RE
+        .captures(text)
+        .map(|c| c[1].to_string())
+}
+
 fn extract_text(result: &Value) -> String {
     // Try to get content array
     if let Some(content) = result.get("content").and_then(|v| v.as_array()) {
@@ -724,17 +868,17 @@ fn extract_last_sse_json(body: &str) -> Option<String> {
 // ============================================================================
 
 /// Create all Exa tools with a shared client
-pub fn create_exa_tools(config: ExaSearchConfig) -> Vec<Arc<dyn Tool>> {
-    let client = Arc::new(ExaMcpClient::new(config));
+pub fn create_exa_tools(config: ExaSearchConfig) -> Result<Vec<Arc<dyn Tool>>, ToolError> {
+    let client = Arc::new(ExaMcpClient::new(config)?);
     
-    vec![
+    Ok(vec![
         Arc::new(ExaSearchTool::with_client(client.clone())) as Arc<dyn Tool>,
         Arc::new(ExaCodeSearchTool::with_client(client.clone())) as Arc<dyn Tool>,
         Arc::new(ExaCompanyResearchTool::with_client(client.clone())) as Arc<dyn Tool>,
         Arc::new(ExaDeepResearchStartTool::with_client(client.clone())) as Arc<dyn Tool>,
         Arc::new(ExaDeepResearchCheckTool::with_client(client.clone())) as Arc<dyn Tool>,
         Arc::new(ExaCrawlTool::with_client(client)) as Arc<dyn Tool>,
-    ]
+    ])
 }
 
 #[cfg(test)]
@@ -744,7 +888,7 @@ mod tests {
     #[test]
     fn test_exa_tool_names() {
         let config = ExaSearchConfig::default();
-        let tools = create_exa_tools(config);
+        let tools = create_exa_tools(config).unwrap();
         
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"web_search"));
@@ -765,4 +909,17 @@ mod tests {
         assert!(text.contains("First result"));
         assert!(text.contains("Second result"));
     }
+
+    #[test]
+    fn test_parse_task_id() {
+        assert_eq!(
+            parse_task_id(r#"{"taskId": "01jx8abc", "status": "running"}"#).as_deref(),
+            Some("01jx8abc")
+        );
+        assert_eq!(
+            parse_task_id("Research started. Task ID: r_7f3e9a21. Check back later.").as_deref(),
+            Some("r_7f3e9a21")
+        );
+        assert_eq!(parse_task_id("Research started"), None);
+    }
 }
@@ -0,0 +1,306 @@
+//! RSS and Atom feeds
+//!
+//! `feed_read` fetches a feed and lists its items (title, link, date and a
+//! plain-text summary), newest first. The daily digest reuses [`fetch_feed`].
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::system::certificates::describe_error;
+use crate::system::proxy::{client_builder, ProxyClient};
+
+/// Characters kept from each item's summary
+const SUMMARY_CHARS: usize = 500;
+/// Items returned when the call doesn't set a limit
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedItem {
+    /// Stable id: the guid/id when the feed has one, else the link
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub published: Option<DateTime<Utc>>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feed {
+    pub title: String,
+    /// Newest first; undated items last, in feed order
+    pub items: Vec<FeedItem>,
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn child_text(node: roxmltree::Node, names: &[&str]) -> String {
+    names
+        .iter()
+        .filter_map(|name| child(node, name))
+        .map(|n| n.text().unwrap_or_default().trim().to_string())
+        .find(|text| !text.is_empty())
+        .unwrap_or_default()
+}
+
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(text)
+        .or_else(|_| DateTime::parse_from_rfc3339(text))
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Plain text of an HTML summary, cut to [`SUMMARY_CHARS`]
+fn plain_summary(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let text = words.join(" ");
+    match text.char_indices().nth(SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+fn rss_item(item: roxmltree::Node) -> FeedItem {
+    let link = child_text(item, &["link"]);
+    let id = child_text(item, &["guid"]);
+    FeedItem {
+        id: if id.is_empty() { link.clone() } else { id },
+        title: child_text(item, &["title"]),
+        link,
+        published: parse_date(&child_text(item, &["pubDate", "date"])),
+        summary: plain_summary(&child_text(item, &["description", "encoded"])),
+    }
+}
+
+fn atom_entry(entry: roxmltree::Node) -> FeedItem {
+    let link = entry
+        .children()
+        .filter(|n| n.is_element() && n.tag_name().name() == "link")
+        .find(|n| n.attribute("rel").is_none_or(|rel| rel == "alternate"))
+        .and_then(|n| n.attribute("href"))
+        .unwrap_or_default()
+        .to_string();
+    let id = child_text(entry, &["id"]);
+    FeedItem {
+        id: if id.is_empty() { link.clone() } else { id },
+        title: child_text(entry, &["title"]),
+        link,
+        published: parse_date(&child_text(entry, &["published", "updated"])),
+        summary: plain_summary(&child_text(entry, &["summary", "content"])),
+    }
+}
+
+/// Parse an RSS 2.0, RSS 1.0 (RDF) or Atom document
+pub fn parse_feed(xml: &str) -> Result<Feed, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let root = doc.root_element();
+    let (title, mut items) = match root.tag_name().name() {
+        "rss" | "RDF" => {
+            let title = child(root, "channel")
+                .map(|channel| child_text(channel, &["title"]))
+                .unwrap_or_default();
+            let items = root
+                .descendants()
+                .filter(|n| n.is_element() && n.tag_name().name() == "item")
+                .map(rss_item)
+                .collect::<Vec<_>>();
+            (title, items)
+        }
+        "feed" => {
+            let items = root
+                .children()
+                .filter(|n| n.is_element() && n.tag_name().name() == "entry")
+                .map(atom_entry)
+                .collect::<Vec<_>>();
+            (child_text(root, &["title"]), items)
+        }
+        other => return Err(format!("Not an RSS or Atom feed (<{}>)", other)),
+    };
+    // Stable sort: undated items keep their order, after the dated ones
+    items.sort_by_key(|item| std::cmp::Reverse(item.published));
+    Ok(Feed { title, items })
+}
+
+/// Download and parse the feed at `url`
+pub async fn fetch_feed(url: &str) -> Result<Feed, ToolError> {
+    let client = client_builder(ProxyClient::WebFetch)
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent("clawRS/0.2.0")
+        .build()
+        .map_err(|e| ToolError::ExecutionFailed(format!("Client HTTP: {}", e)))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP: {}", describe_error(&e))))?;
+    if !response.status().is_success() {
+        return Err(ToolError::ExecutionFailed(format!(
+            "HTTP {} pour {}",
+            response.status(),
+            url
+        )));
+    }
+    let xml = response
+        .text()
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire la réponse: {}", e)))?;
+    parse_feed(&xml).map_err(|e| ToolError::ExecutionFailed(format!("Flux illisible ({}): {}", url, e)))
+}
+
+// ============================================================================
+// FeedReadTool - List the items of an RSS/Atom feed
+// ============================================================================
+
+pub struct FeedReadTool;
+
+#[async_trait]
+impl Tool for FeedReadTool {
+    fn name(&self) -> &str {
+        "feed_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read an RSS or Atom feed and list its latest items (title, link, date, summary), newest first. Use to follow blogs, news sites, release notes or podcasts."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "URL of the RSS or Atom feed"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of items (default: 20)",
+                    "default": 20
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only items published after this date (RFC 3339, e.g. 2026-10-14T00:00:00Z)"
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let url = params["url"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("url is required".into()))?;
+        let limit = params["limit"].as_u64().map(|n| n as usize).unwrap_or(DEFAULT_LIMIT);
+        let since = match params["since"].as_str() {
+            Some(since) => Some(
+                DateTime::parse_from_rfc3339(since)
+                    .map_err(|e| ToolError::InvalidParameters(format!("since: {}", e)))?
+                    .with_timezone(&Utc),
+            ),
+            None => None,
+        };
+
+        let feed = fetch_feed(url).await?;
+        let items: Vec<&FeedItem> = feed
+            .items
+            .iter()
+            .filter(|item| match (since, item.published) {
+                (Some(since), Some(published)) => published > since,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .take(limit)
+            .collect();
+
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "url": url,
+                "title": feed.title,
+                "items": items,
+                "total_items": feed.items.len()
+            }),
+            message: format!("Flux lu: {} ({} éléments)", feed.title, items.len()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss() {
+        let xml = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Release notes</title>
+    <item>
+      <title>v1.0</title>
+      <link>https://example.com/v1</link>
+      <pubDate>Mon, 13 Oct 2025 09:00:00 +0000</pubDate>
+      <description>&lt;p&gt;First &amp;amp; stable&lt;/p&gt;</description>
+    </item>
+    <item>
+      <title>v1.1</title>
+      <link>https://example.com/v1.1</link>
+      <guid>release-1.1</guid>
+      <pubDate>Wed, 15 Oct 2025 09:00:00 +0000</pubDate>
+      <content:encoded>&lt;b&gt;Fixes&lt;/b&gt;</content:encoded>
+    </item>
+  </channel>
+</rss>"#;
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title, "Release notes");
+        let titles: Vec<&str> = feed.items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, vec!["v1.1", "v1.0"]);
+        assert_eq!(feed.items[0].id, "release-1.1");
+        assert_eq!(feed.items[0].summary, "Fixes");
+        assert_eq!(feed.items[1].id, "https://example.com/v1");
+        assert_eq!(feed.items[1].summary, "First & stable");
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Blog</title>
+  <entry>
+    <title>Hello</title>
+    <id>urn:post:1</id>
+    <link rel="self" href="https://example.com/feed/1"/>
+    <link href="https://example.com/hello"/>
+    <updated>2025-10-14T08:30:00+02:00</updated>
+    <summary>Short intro</summary>
+  </entry>
+</feed>"#;
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title, "Blog");
+        let item = &feed.items[0];
+        assert_eq!(item.link, "https://example.com/hello");
+        assert_eq!(item.published.unwrap().to_rfc3339(), "2025-10-14T06:30:00+00:00");
+        assert!(parse_feed("<html></html>").is_err());
+    }
+}
@@ -8,6 +8,7 @@ use serde_json::Value;
 use std::path::PathBuf;
 
 use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::system::disk::ensure_free_space;
 
 // ============================================================================
 // FileEditTool - String replacement editing (like Claude Code's StrReplace)
@@ -239,6 +240,7 @@ impl Tool for FileCreateTool {
             }
         }
 
+        ensure_free_space(&path_buf, content.len() as u64)?;
         tokio::fs::write(&path_buf, content)
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le fichier: {}", e)))?;
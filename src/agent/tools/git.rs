@@ -14,6 +14,7 @@ async fn run_git(args: &[&str], working_dir: Option<&str>) -> Result<(String, St
     for arg in args {
         cmd.arg(arg);
     }
+    cmd.kill_on_drop(true);
     if let Some(dir) = working_dir {
         cmd.current_dir(dir);
     }
@@ -14,6 +14,8 @@ use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 
 use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::system::certificates::describe_error;
+use crate::system::proxy::{client_builder, ProxyClient};
 
 // ============================================================================
 // MCP Server Configuration
@@ -329,16 +331,17 @@ pub struct HttpMcpClient {
 }
 
 impl HttpMcpClient {
-    pub fn new(config: McpServerConfig) -> Self {
-        Self {
+    pub fn new(config: McpServerConfig) -> Result<Self, ToolError> {
+        let client = client_builder(ProxyClient::Mcp)
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| ToolError::ExecutionFailed(format!("Client HTTP: {}", e)))?;
+        Ok(Self {
             config,
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(60))
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new()),
+            client,
             initialized: AtomicBool::new(false),
             request_id: AtomicU64::new(1),
-        }
+        })
     }
 
     fn next_id(&self) -> u64 {
@@ -378,7 +381,7 @@ impl HttpMcpClient {
             .json(&request)
             .send()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP MCP: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP MCP: {}", describe_error(&e))))?;
 
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
@@ -663,7 +666,17 @@ impl McpServerManager {
                     }
                 }
                 McpTransport::Http { .. } => {
-                    let client = Arc::new(HttpMcpClient::new(config.clone()));
+                    let client = match HttpMcpClient::new(config.clone()) {
+                        Ok(client) => Arc::new(client),
+                        Err(e) => {
+                            tracing::warn!(
+                                "Failed to create the HTTP client for MCP server '{}': {}",
+                                config.name,
+                                e
+                            );
+                            continue;
+                        }
+                    };
                     match client.list_tools().await {
                         Ok(tools) => {
                             tracing::info!(
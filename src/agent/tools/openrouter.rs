@@ -1,13 +1,17 @@
 //! OpenRouter AI consultation tool
 //!
-//! Allows the local AI to consult more powerful external AI models via OpenRouter API.
+//! Allows the local AI to consult more powerful external AI models via OpenRouter
+//! (or OpenAI, see `agent::escalation`).
 //! This is useful for complex reasoning tasks where the local model needs help.
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::agent::escalation::EscalationProvider;
 use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::system::certificates::describe_error;
+use crate::system::proxy::{client_builder, ProxyClient};
 
 // ============================================================================
 // OpenRouter Configuration
@@ -39,6 +43,9 @@ struct OpenRouterRequest {
     messages: Vec<ChatMessage>,
     max_tokens: u32,
     temperature: f32,
+    /// Ask OpenRouter to report token usage and cost
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,6 +58,18 @@ struct ChatMessage {
 struct OpenRouterResponse {
     choices: Option<Vec<Choice>>,
     error: Option<OpenRouterError>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    /// Only reported by OpenRouter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cost: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -81,7 +100,7 @@ impl Tool for OpenRouterConsultTool {
     }
 
     fn description(&self) -> &str {
-        "Consult a more powerful external AI model (via OpenRouter) for complex reasoning, explanations, or problems you struggle with. Use this when you need help understanding something complex or want a second opinion. The response will be optimized for your understanding."
+        "Consult a more powerful external AI model (via the cloud provider configured by the user) for complex reasoning, explanations, or problems you struggle with. Use this when you need help understanding something complex or want a second opinion. The response will be optimized for your understanding."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -125,16 +144,22 @@ impl Tool for OpenRouterConsultTool {
         let optimize_for_local = params["optimize_for_local"].as_bool().unwrap_or(true);
         
         // Get model from params, or use settings, or fall back to default
-        let model = params["model"]
-            .as_str()
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| get_configured_model());
+        let provider = get_configured_provider();
+        let model = provider.resolve_model(
+            &params["model"]
+                .as_str()
+                .map(|s| s.to_string())
+                .unwrap_or_else(get_configured_model),
+        );
         
         // Get API key from environment
-        let api_key = std::env::var("OPENROUTER_API_KEY")
-            .map_err(|_| ToolError::ExecutionFailed(
-                "OPENROUTER_API_KEY environment variable not set. Get a free key at https://openrouter.ai/keys".into()
-            ))?;
+        let api_key = std::env::var(provider.api_key_env())
+            .map_err(|_| ToolError::ExecutionFailed(match provider {
+                EscalationProvider::OpenRouter => {
+                    "OPENROUTER_API_KEY environment variable not set. Get a free key at https://openrouter.ai/keys".to_string()
+                }
+                other => format!("{} environment variable not set", other.api_key_env()),
+            }))?;
         
         // Build the user message
         let user_content = if let Some(ctx) = context {
@@ -164,16 +189,18 @@ impl Tool for OpenRouterConsultTool {
             messages,
             max_tokens,
             temperature: 0.7,
+            usage: (provider == EscalationProvider::OpenRouter)
+                .then(|| serde_json::json!({ "include": true })),
         };
         
         // Make HTTP request to OpenRouter
-        let client = reqwest::Client::builder()
+        let client = client_builder(ProxyClient::Escalation)
             .timeout(std::time::Duration::from_secs(120))
             .build()
             .map_err(|e| ToolError::ExecutionFailed(format!("Failed to create HTTP client: {}", e)))?;
         
         let response = client
-            .post("https://openrouter.ai/api/v1/chat/completions")
+            .post(provider.endpoint())
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .header("HTTP-Referer", "https://github.com/localm-ai/localm")
@@ -181,7 +208,7 @@ impl Tool for OpenRouterConsultTool {
             .json(&request)
             .send()
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("HTTP request failed: {}", e)))?;
+            .map_err(|e| ToolError::ExecutionFailed(format!("HTTP request failed: {}", describe_error(&e))))?;
         
         let status = response.status();
         let response_text = response
@@ -191,8 +218,8 @@ impl Tool for OpenRouterConsultTool {
         
         if !status.is_success() {
             return Err(ToolError::ExecutionFailed(format!(
-                "OpenRouter API error ({}): {}",
-                status, response_text
+                "{} API error ({}): {}",
+                provider.label(), status, response_text
             )));
         }
         
@@ -203,8 +230,8 @@ impl Tool for OpenRouterConsultTool {
         // Check for API error
         if let Some(error) = api_response.error {
             return Err(ToolError::ExecutionFailed(format!(
-                "OpenRouter error: {}",
-                error.message
+                "{} error: {}",
+                provider.label(), error.message
             )));
         }
         
@@ -218,7 +245,9 @@ impl Tool for OpenRouterConsultTool {
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({
+                "provider": provider,
                 "model": model,
+                "usage": api_response.usage,
                 "question": question,
                 "response": content,
                 "tokens_max": max_tokens,
@@ -253,6 +282,17 @@ fn get_configured_model() -> String {
     DEFAULT_MODEL.to_string()
 }
 
+/// Get the configured escalation provider from settings, or OpenRouter
+fn get_configured_provider() -> EscalationProvider {
+    crate::storage::get_data_dir()
+        .ok()
+        .and_then(|dir| std::fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|json| json.get("escalation")?.get("provider").cloned())
+        .and_then(|provider| serde_json::from_value(provider).ok())
+        .unwrap_or(EscalationProvider::OpenRouter)
+}
+
 /// Get list of popular models for UI dropdown
 pub fn get_popular_models() -> Vec<(&'static str, &'static str)> {
     vec![
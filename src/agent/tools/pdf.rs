@@ -7,6 +7,7 @@ use serde_json::Value;
 use std::path::PathBuf;
 
 use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::system::disk::ensure_free_space;
 
 // ============================================================================
 // PdfReadTool - Extract text from PDF
@@ -21,7 +22,7 @@ impl Tool for PdfReadTool {
     }
 
     fn description(&self) -> &str {
-        "Lire et extraire le texte d'un fichier PDF."
+        "Read a PDF file and extract its text."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -30,12 +31,12 @@ impl Tool for PdfReadTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Chemin vers le fichier PDF à lire"
+                    "description": "Path of the PDF file to read"
                 },
                 "pages": {
                     "type": "array",
                     "items": { "type": "integer" },
-                    "description": "Numéros des pages à extraire (optionnel, toutes par défaut)"
+                    "description": "Page numbers to extract (optional, all by default)"
                 }
             },
             "required": ["path"]
@@ -147,7 +148,7 @@ impl Tool for PdfCreateTool {
     }
 
     fn description(&self) -> &str {
-        "Créer un nouveau fichier PDF avec du contenu texte."
+        "Create a new PDF file with text content. Long lines are wrapped and pages added automatically."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -156,19 +157,45 @@ impl Tool for PdfCreateTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Chemin où sauvegarder le PDF"
+                    "description": "Path where the PDF is saved"
                 },
                 "title": {
                     "type": "string",
-                    "description": "Titre du document (optionnel)"
+                    "description": "Document title (optional)"
                 },
                 "content": {
                     "type": "string",
-                    "description": "Contenu texte du PDF"
+                    "description": "Text content of the PDF"
                 },
                 "font_size": {
                     "type": "number",
-                    "description": "Taille de police (défaut: 12)"
+                    "description": "Font size (default: 12)"
+                },
+                "font_family": {
+                    "type": "string",
+                    "enum": ["helvetica", "times", "courier"],
+                    "description": "Font (default: helvetica)"
+                },
+                "margin": {
+                    "type": "number",
+                    "description": "Margins in mm (default: 20)"
+                },
+                "page_size": {
+                    "type": "string",
+                    "enum": ["a4", "letter"],
+                    "description": "Page size (default: a4)"
+                },
+                "header": {
+                    "type": "string",
+                    "description": "Header of each page ({page} and {pages} are replaced)"
+                },
+                "footer": {
+                    "type": "string",
+                    "description": "Footer of each page ({page} and {pages} are replaced)"
+                },
+                "page_numbers": {
+                    "type": "boolean",
+                    "description": "Number the pages (default: true)"
                 }
             },
             "required": ["path", "content"]
@@ -176,7 +203,7 @@ impl Tool for PdfCreateTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
-        use printpdf::*;
+        use super::document::{layout_text, write_pdf, FontFamily, LayoutOptions};
 
         let path_str = params["path"]
             .as_str()
@@ -187,65 +214,30 @@ impl Tool for PdfCreateTool {
             .ok_or_else(|| ToolError::InvalidParameters("content is required".into()))?;
         
         let title = params["title"].as_str().unwrap_or("Document");
-        let font_size = params["font_size"].as_f64().unwrap_or(12.0) as f32;
-
-        // Create PDF document
-        let (doc, page1, layer1) = PdfDocument::new(
-            title,
-            Mm(210.0),  // A4 width
-            Mm(297.0),  // A4 height
-            "Layer 1"
-        );
-
-        let current_layer = doc.get_page(page1).get_layer(layer1);
-        
-        // Use built-in font
-        let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur police: {}", e))
-        })?;
-
-        // Add content - split into lines (collect as owned strings)
-        let lines: Vec<String> = content.lines().map(String::from).collect();
-        let line_count = lines.len();
-        let line_height = font_size * 1.5;
-        let mut y_position = 280.0; // Start from top
-        let x_position = 20.0;
-
-        for line in &lines {
-            if y_position < 20.0 {
-                // Would need to add new page - for now, stop
-                break;
-            }
-            
-            current_layer.use_text(
-                line,
-                font_size,
-                Mm(x_position),
-                Mm(y_position),
-                &font
-            );
-            
-            y_position -= line_height;
+        let defaults = LayoutOptions::default();
+        let opts = LayoutOptions {
+            base_size: params["font_size"].as_f64().unwrap_or(12.0).clamp(4.0, 72.0) as f32,
+            margin: params["margin"].as_f64().unwrap_or(20.0).clamp(5.0, 60.0) as f32,
+            family: FontFamily::from_name(params["font_family"].as_str().unwrap_or("helvetica")),
+            page_numbers: params["page_numbers"].as_bool().unwrap_or(true),
+            header: params["header"].as_str().map(String::from),
+            footer: params["footer"].as_str().map(String::from),
+            ..defaults
         }
+        .with_page_size(params["page_size"].as_str().unwrap_or("a4"));
+
+        let line_count = content.lines().count();
+        let pages = layout_text(content, &opts);
 
-        // Save PDF
         let path = PathBuf::from(path_str);
         if let Some(parent) = path.parent() {
-            if !parent.exists() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
                 std::fs::create_dir_all(parent).map_err(|e| {
                     ToolError::ExecutionFailed(format!("Erreur création dossier: {}", e))
                 })?;
             }
         }
-
-        let file = std::fs::File::create(&path).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur création fichier: {}", e))
-        })?;
-        
-        let mut buf_writer = std::io::BufWriter::new(file);
-        doc.save(&mut buf_writer).map_err(|e| {
-            ToolError::ExecutionFailed(format!("Erreur sauvegarde PDF: {}", e))
-        })?;
+        write_pdf(&pages, &opts, title, &path)?;
 
         Ok(ToolResult {
             success: true,
@@ -253,9 +245,10 @@ impl Tool for PdfCreateTool {
                 "path": path_str,
                 "title": title,
                 "lines": line_count,
+                "pages": pages.len(),
                 "size_bytes": std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0)
             }),
-            message: format!("PDF créé: {} ({} lignes)", path_str, line_count),
+            message: format!("PDF créé: {} ({} lignes, {} pages)", path_str, line_count, pages.len()),
         })
     }
 }
@@ -273,7 +266,7 @@ impl Tool for PdfAddPageTool {
     }
 
     fn description(&self) -> &str {
-        "Ajouter une page avec du texte à un PDF existant."
+        "Add a page of text to an existing PDF."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -282,11 +275,11 @@ impl Tool for PdfAddPageTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Chemin vers le PDF à modifier"
+                    "description": "Path of the PDF to modify"
                 },
                 "content": {
                     "type": "string",
-                    "description": "Contenu texte de la nouvelle page"
+                    "description": "Text content of the new page"
                 }
             },
             "required": ["path", "content"]
@@ -377,7 +370,12 @@ impl Tool for PdfAddPageTool {
         }
 
         // Save modified PDF
-        doc.save(&path).map_err(|e| {
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).map_err(|e| {
+            ToolError::ExecutionFailed(format!("Erreur sauvegarde PDF: {}", e))
+        })?;
+        ensure_free_space(&path, bytes.len() as u64)?;
+        std::fs::write(&path, bytes).map_err(|e| {
             ToolError::ExecutionFailed(format!("Erreur sauvegarde PDF: {}", e))
         })?;
 
@@ -406,7 +404,7 @@ impl Tool for PdfMergeTool {
     }
 
     fn description(&self) -> &str {
-        "Fusionner plusieurs fichiers PDF en un seul."
+        "Merge several PDF files into one."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -416,11 +414,11 @@ impl Tool for PdfMergeTool {
                 "input_files": {
                     "type": "array",
                     "items": { "type": "string" },
-                    "description": "Liste des chemins des PDFs à fusionner"
+                    "description": "Paths of the PDFs to merge"
                 },
                 "output_path": {
                     "type": "string",
-                    "description": "Chemin du PDF de sortie"
+                    "description": "Path of the output PDF"
                 }
             },
             "required": ["input_files", "output_path"]
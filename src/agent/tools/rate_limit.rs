@@ -0,0 +1,270 @@
+//! Rate limiting, retries and circuit breaking for web providers
+//!
+//! Each provider (Exa, a host reached by web_fetch, ...) gets a minimum
+//! interval between requests. Transient failures (network errors, 429, 5xx)
+//! are retried with exponential backoff, honouring `Retry-After`. After
+//! several calls in a row fail, the provider's circuit opens for a cooldown
+//! and calls fail immediately with a message telling the model to try
+//! something else, instead of hammering the API inside the agent loop.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::agent::tools::ToolError;
+use crate::system::certificates::describe_error;
+
+/// Limits applied to one provider
+#[derive(Debug, Clone)]
+pub struct ProviderLimits {
+    /// Minimum time between two requests
+    pub min_interval: Duration,
+    /// Attempts per call, the first one included
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each following one
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Failed calls in a row that open the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open
+    pub cooldown: Duration,
+}
+
+impl ProviderLimits {
+    /// Exa search API
+    pub fn search() -> Self {
+        Self {
+            min_interval: Duration::from_millis(500),
+            max_attempts: 3,
+            base_delay: Duration::from_millis(800),
+            max_delay: Duration::from_secs(8),
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(120),
+        }
+    }
+
+    /// Any host reached by web_fetch
+    pub fn web() -> Self {
+        Self {
+            min_interval: Duration::from_millis(200),
+            max_attempts: 2,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(5),
+            failure_threshold: 4,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+/// A failed attempt and whether it is worth retrying
+#[derive(Debug)]
+pub struct Failure {
+    pub error: ToolError,
+    pub retryable: bool,
+    /// Delay asked by the server (`Retry-After`)
+    pub retry_after: Option<Duration>,
+}
+
+impl Failure {
+    /// A failure retrying won't fix (bad request, parse error, ...)
+    pub fn fatal(error: ToolError) -> Self {
+        Self {
+            error,
+            retryable: false,
+            retry_after: None,
+        }
+    }
+
+    /// A request that never got a response; timeouts and connection errors
+    /// are retried, unless the certificate was rejected
+    pub fn from_request(error: reqwest::Error, context: &str) -> Self {
+        let message = describe_error(&error);
+        Self {
+            retryable: (error.is_timeout() || error.is_connect())
+                && !message.contains("certificate"),
+            error: ToolError::ExecutionFailed(format!("{}: {}", context, message)),
+            retry_after: None,
+        }
+    }
+
+    /// An error status; 408, 429 and 5xx are retried
+    pub fn from_status(
+        status: reqwest::StatusCode,
+        headers: &reqwest::header::HeaderMap,
+        message: String,
+    ) -> Self {
+        let retry_after = headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        Self {
+            error: ToolError::ExecutionFailed(message),
+            retryable: status.is_server_error()
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT,
+            retry_after,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProviderState {
+    next_slot: Option<Instant>,
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl ProviderState {
+    /// Time left before the circuit closes, if it is open
+    fn open_for(&self, now: Instant) -> Option<Duration> {
+        self.open_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// Reserve the next request slot; returns how long to wait for it
+    fn reserve(&mut self, limits: &ProviderLimits, now: Instant) -> Duration {
+        let slot = self.next_slot.filter(|s| *s > now).unwrap_or(now);
+        self.next_slot = Some(slot + limits.min_interval);
+        slot - now
+    }
+
+    fn record_success(&mut self) {
+        self.failures = 0;
+        self.open_until = None;
+    }
+
+    /// Count a failed call; returns whether the circuit just opened
+    fn record_failure(&mut self, limits: &ProviderLimits, now: Instant) -> bool {
+        self.failures += 1;
+        if self.failures >= limits.failure_threshold {
+            self.failures = 0;
+            self.open_until = Some(now + limits.cooldown);
+            return true;
+        }
+        false
+    }
+}
+
+static PROVIDERS: Lazy<Mutex<HashMap<String, ProviderState>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn with_state<R>(provider: &str, f: impl FnOnce(&mut ProviderState) -> R) -> R {
+    let mut providers = PROVIDERS.lock().unwrap_or_else(|e| e.into_inner());
+    f(providers.entry(provider.to_string()).or_default())
+}
+
+/// Delay before retry number `attempt` (1 for the first retry)
+fn backoff_delay(limits: &ProviderLimits, attempt: u32, retry_after: Option<Duration>) -> Duration {
+    let exponential = limits
+        .base_delay
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+    retry_after.unwrap_or(exponential).min(limits.max_delay)
+}
+
+fn unavailable(provider: &str, retry_in: Duration) -> ToolError {
+    ToolError::ExecutionFailed(format!(
+        "{} temporairement indisponible (trop d'échecs consécutifs), nouvel essai possible dans {} s. \
+         N'insistez pas : essayez une alternative (autre outil, autre source, ou répondez avec vos connaissances en le signalant).",
+        provider,
+        retry_in.as_secs().max(1)
+    ))
+}
+
+/// Run `attempt` for `provider` under its rate limit, retry policy and
+/// circuit breaker
+pub async fn run<T, F, Fut>(
+    provider: &str,
+    limits: &ProviderLimits,
+    mut attempt: F,
+) -> Result<T, ToolError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Failure>>,
+{
+    if let Some(open_for) = with_state(provider, |s| s.open_for(Instant::now())) {
+        tracing::debug!("Circuit open for {}, {:?} left", provider, open_for);
+        return Err(unavailable(provider, open_for));
+    }
+
+    let mut tries = 0;
+    loop {
+        tries += 1;
+        let wait = with_state(provider, |s| s.reserve(limits, Instant::now()));
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        let failure = match attempt().await {
+            Ok(value) => {
+                with_state(provider, |s| s.record_success());
+                return Ok(value);
+            }
+            Err(failure) => failure,
+        };
+
+        if !failure.retryable {
+            return Err(failure.error);
+        }
+        if tries >= limits.max_attempts {
+            let opened = with_state(provider, |s| s.record_failure(limits, Instant::now()));
+            if opened {
+                tracing::warn!("Circuit opened for {} after repeated failures", provider);
+                return Err(unavailable(provider, limits.cooldown));
+            }
+            return Err(failure.error);
+        }
+
+        let delay = backoff_delay(limits, tries, failure.retry_after);
+        tracing::debug!(
+            "{} failed ({}), retry {}/{} in {:?}",
+            provider,
+            failure.error,
+            tries,
+            limits.max_attempts - 1,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        let limits = ProviderLimits::search();
+        assert_eq!(backoff_delay(&limits, 1, None), Duration::from_millis(800));
+        assert_eq!(backoff_delay(&limits, 2, None), Duration::from_millis(1600));
+        assert_eq!(backoff_delay(&limits, 10, None), limits.max_delay);
+        assert_eq!(
+            backoff_delay(&limits, 1, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            backoff_delay(&limits, 1, Some(Duration::from_secs(600))),
+            limits.max_delay
+        );
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let limits = ProviderLimits::search();
+        let now = Instant::now();
+        let mut state = ProviderState::default();
+        assert!(!state.record_failure(&limits, now));
+        assert!(!state.record_failure(&limits, now));
+        assert!(state.record_failure(&limits, now));
+        assert!(state.open_for(now).is_some());
+        assert!(state.open_for(now + limits.cooldown).is_none());
+
+        state.record_success();
+        assert!(state.open_for(now).is_none());
+        assert_eq!(state.reserve(&limits, now), Duration::ZERO);
+        assert_eq!(state.reserve(&limits, now), limits.min_interval);
+    }
+}
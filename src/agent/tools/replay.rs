@@ -0,0 +1,26 @@
+//! Replaying tool results from a recording
+//!
+//! In replay mode the agent loop asks `replay_call` for each tool call
+//! instead of executing it, so a run can be repeated without side effects.
+
+use serde_json::Value;
+
+use crate::agent::tools::ToolResult;
+use crate::storage::tool_recordings::ToolRecording;
+
+/// Result served for a call, or an error when it was never recorded
+pub fn replay_call(
+    recording: &ToolRecording,
+    tool: &str,
+    params: &Value,
+) -> Result<ToolResult, String> {
+    recording
+        .next_outcome(tool, params)
+        .ok_or_else(|| {
+            format!(
+                "No recorded result for `{}` with these parameters (replay mode)",
+                tool
+            )
+        })?
+        .into_result()
+}
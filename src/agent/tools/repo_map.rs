@@ -0,0 +1,488 @@
+//! Repository map
+//!
+//! A condensed view of a workspace for onboarding the agent: languages by
+//! size, key files (manifests, READMEs, CI), likely entry points and the
+//! directory tree, trimmed to a token budget. Shallow entries are kept
+//! first, so a small budget still shows the overall layout.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::agent::escalation::estimate_tokens;
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::workspace_index;
+use crate::system::disk::format_size;
+
+/// Default budget of the map, in tokens
+pub const DEFAULT_TOKEN_BUDGET: usize = 1500;
+/// Budget of the map injected in the system prompt
+pub const PROMPT_TOKEN_BUDGET: usize = 800;
+/// Stop walking after this many files
+const MAX_FILES: usize = 20_000;
+
+/// Directories that are generated, vendored or tooling state
+const SKIPPED_DIRS: &[&str] = &[
+    "target",
+    "node_modules",
+    "dist",
+    "build",
+    "out",
+    "vendor",
+    "venv",
+    "__pycache__",
+    "coverage",
+];
+
+const KEY_FILES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "requirements.txt",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "CMakeLists.txt",
+    "Makefile",
+    "Dockerfile",
+    "docker-compose.yml",
+    "Gemfile",
+    "composer.json",
+    "tsconfig.json",
+    "AGENTS.md",
+    "CLAUDE.md",
+    "CONTRIBUTING.md",
+];
+
+const ENTRY_POINTS: &[&str] = &[
+    "main.rs",
+    "lib.rs",
+    "main.py",
+    "__main__.py",
+    "app.py",
+    "manage.py",
+    "main.go",
+    "index.js",
+    "index.ts",
+    "main.js",
+    "main.ts",
+    "server.js",
+    "App.tsx",
+    "Main.java",
+    "Program.cs",
+    "main.c",
+    "main.cpp",
+];
+
+pub fn language(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "rs" => "Rust",
+        "py" => "Python",
+        "js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "swift" => "Swift",
+        "sh" | "bash" => "Shell",
+        "html" | "htm" => "HTML",
+        "css" | "scss" => "CSS",
+        "md" => "Markdown",
+        "toml" | "yaml" | "yml" | "json" => "Config",
+        "sql" => "SQL",
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Path relative to the root
+    rel: PathBuf,
+    is_dir: bool,
+    /// Size, the whole subtree for a directory
+    bytes: u64,
+    /// Files in the subtree (directories only)
+    files: usize,
+    depth: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct RepoMap {
+    pub root: PathBuf,
+    pub total_files: usize,
+    pub total_bytes: u64,
+    /// Whether the walk stopped at `MAX_FILES`
+    pub truncated: bool,
+    /// (language, files, bytes), largest first
+    pub languages: Vec<(String, usize, u64)>,
+    pub key_files: Vec<String>,
+    pub entry_points: Vec<String>,
+    entries: Vec<Entry>,
+}
+
+fn is_skipped_dir(name: &str) -> bool {
+    name.starts_with('.') || SKIPPED_DIRS.contains(&name)
+}
+
+fn is_skipped_file(name: &str) -> bool {
+    name.starts_with('.') && name != ".gitignore"
+}
+
+/// Whether a path relative to the root is left out of the map: hidden, or
+/// inside a generated or vendored directory
+pub fn is_ignored(rel: &Path) -> bool {
+    let names: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    match names.split_last() {
+        Some((file, dirs)) => is_skipped_file(file) || dirs.iter().any(|d| is_skipped_dir(d)),
+        None => false,
+    }
+}
+
+/// Walk `dir`, appending its files; returns false once `MAX_FILES` is reached
+fn walk(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, u64)>) -> bool {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return true;
+    };
+    let mut children: Vec<_> = read_dir.flatten().collect();
+    children.sort_by_key(|e| e.file_name());
+
+    for child in children {
+        if files.len() >= MAX_FILES {
+            return false;
+        }
+        let name = child.file_name().to_string_lossy().to_string();
+        let path = child.path();
+        let Ok(file_type) = child.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if !is_skipped_dir(&name) && !walk(root, &path, files) {
+                return false;
+            }
+        } else if file_type.is_file() && !is_skipped_file(&name) {
+            let size = child.metadata().map(|m| m.len()).unwrap_or(0);
+            let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            files.push((rel, size));
+        }
+    }
+    true
+}
+
+/// Files under `dir` as (path relative to `root`, size), and whether the
+/// walk stopped at `MAX_FILES`
+pub fn list_files(root: &Path, dir: &Path) -> (Vec<(PathBuf, u64)>, bool) {
+    let mut files = Vec::new();
+    let complete = walk(root, dir, &mut files);
+    (files, !complete)
+}
+
+/// Map of the workspace at `root`, from the workspace index when it covers
+/// `root`, otherwise by walking it
+pub fn build_repo_map(root: &Path) -> RepoMap {
+    if let Some(index) = workspace_index::index_for(root) {
+        let index = index.read().unwrap_or_else(|e| e.into_inner());
+        // Still empty while the first indexing runs
+        if !index.files.is_empty() {
+            return RepoMap::from_files(root, &index.file_list(), index.truncated);
+        }
+    }
+    let (files, truncated) = list_files(root, root);
+    RepoMap::from_files(root, &files, truncated)
+}
+
+impl RepoMap {
+    /// Map of an already listed workspace, e.g. from the workspace index
+    pub fn from_files(root: &Path, files: &[(PathBuf, u64)], truncated: bool) -> RepoMap {
+        let mut map = RepoMap {
+            root: root.to_path_buf(),
+            truncated,
+            ..Default::default()
+        };
+        let mut files = files.to_vec();
+        files.sort();
+
+        // Directory entries are added the first time one of their files shows up
+        let mut dirs: HashMap<PathBuf, usize> = HashMap::new();
+        for (rel, size) in files {
+            let parents: Vec<PathBuf> = rel
+                .ancestors()
+                .skip(1)
+                .filter(|p| !p.as_os_str().is_empty())
+                .map(Path::to_path_buf)
+                .collect();
+            for (depth, dir) in parents.iter().rev().enumerate() {
+                let index = *dirs.entry(dir.clone()).or_insert_with(|| {
+                    map.entries.push(Entry {
+                        rel: dir.clone(),
+                        is_dir: true,
+                        bytes: 0,
+                        files: 0,
+                        depth,
+                    });
+                    map.entries.len() - 1
+                });
+                map.entries[index].files += 1;
+                map.entries[index].bytes += size;
+            }
+
+            let depth = parents.len();
+            let name = rel
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let rel_str = rel.to_string_lossy().replace('\\', "/");
+            if KEY_FILES.contains(&name.as_str())
+                || (depth == 0 && name.to_uppercase().starts_with("README"))
+            {
+                map.key_files.push(rel_str.clone());
+            }
+            let in_bin = rel.components().any(|c| c.as_os_str() == "bin");
+            if ENTRY_POINTS.contains(&name.as_str()) || (in_bin && language(&rel).is_some()) {
+                map.entry_points.push(rel_str);
+            }
+            map.total_files += 1;
+            map.total_bytes += size;
+            map.entries.push(Entry {
+                rel,
+                is_dir: false,
+                bytes: size,
+                files: 0,
+                depth,
+            });
+        }
+        map.compute_languages();
+        map
+    }
+
+    fn compute_languages(&mut self) {
+        let mut languages: HashMap<&str, (usize, u64)> = HashMap::new();
+        for entry in self.entries.iter().filter(|e| !e.is_dir) {
+            if let Some(lang) = language(&entry.rel) {
+                let stats = languages.entry(lang).or_default();
+                stats.0 += 1;
+                stats.1 += entry.bytes;
+            }
+        }
+        self.languages = languages
+            .into_iter()
+            .map(|(lang, (files, bytes))| (lang.to_string(), files, bytes))
+            .collect();
+        self.languages
+            .sort_by_key(|(lang, _, bytes)| (std::cmp::Reverse(*bytes), lang.clone()));
+    }
+}
+
+fn entry_line(entry: &Entry) -> String {
+    let name = entry
+        .rel
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let indent = "  ".repeat(entry.depth);
+    if entry.is_dir {
+        format!(
+            "{}{}/ ({} files, {})\n",
+            indent,
+            name,
+            entry.files,
+            format_size(entry.bytes)
+        )
+    } else {
+        format!("{}{} ({})\n", indent, name, format_size(entry.bytes))
+    }
+}
+
+impl RepoMap {
+    /// Markdown map fitting in about `token_budget` tokens
+    pub fn render(&self, token_budget: usize) -> String {
+        let mut out = format!(
+            "# Repo map: {}\n{} files, {}{}\n",
+            self.root.display(),
+            self.total_files,
+            format_size(self.total_bytes),
+            if self.truncated {
+                " (partial scan)"
+            } else {
+                ""
+            }
+        );
+        if !self.languages.is_empty() {
+            let languages: Vec<String> = self
+                .languages
+                .iter()
+                .take(6)
+                .map(|(lang, files, bytes)| {
+                    format!("{} ({} files, {})", lang, files, format_size(*bytes))
+                })
+                .collect();
+            out.push_str(&format!("Languages: {}\n", languages.join(", ")));
+        }
+        if !self.key_files.is_empty() {
+            out.push_str(&format!("Key files: {}\n", self.key_files.join(", ")));
+        }
+        if !self.entry_points.is_empty() {
+            out.push_str(&format!("Entry points: {}\n", self.entry_points.join(", ")));
+        }
+        out.push_str("\n## Tree\n");
+
+        // Shallowest entries first, directories before files, until the budget is spent
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.sort_by_key(|&i| (self.entries[i].depth, !self.entries[i].is_dir));
+        let mut remaining = token_budget.saturating_sub(estimate_tokens(&out));
+        let mut kept = Vec::new();
+        for i in order {
+            let cost = estimate_tokens(&entry_line(&self.entries[i]));
+            if cost > remaining {
+                break;
+            }
+            remaining -= cost;
+            kept.push(i);
+        }
+        let omitted = self.entries.len() - kept.len();
+        // Back to tree order; entries were pushed depth-first
+        kept.sort_unstable();
+        for i in kept {
+            out.push_str(&entry_line(&self.entries[i]));
+        }
+        if omitted > 0 {
+            out.push_str(&format!("… {} deeper entries omitted\n", omitted));
+        }
+        out
+    }
+}
+
+/// System prompt section describing the workspace at `root`
+pub fn workspace_prompt(root: &Path) -> String {
+    format!(
+        "## Workspace\nThe user's project is at `{}`; use paths under it. Call repo_map with a larger budget or tree for more detail, and workspace_search to find symbols or code by meaning.\n\n{}",
+        root.display(),
+        build_repo_map(root).render(PROMPT_TOKEN_BUDGET)
+    )
+}
+
+/// `repo_map` tool
+pub struct RepoMapTool;
+
+#[async_trait]
+impl Tool for RepoMapTool {
+    fn name(&self) -> &str {
+        "repo_map"
+    }
+
+    fn description(&self) -> &str {
+        "Condensed map of a project: languages, key files (manifests, README), entry points and directory tree with sizes, within a token budget. Use it first to get oriented in an unfamiliar codebase."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Project root (default: current dir)",
+                    "default": "."
+                },
+                "max_tokens": {
+                    "type": "integer",
+                    "description": "Token budget of the map (default: 1500)",
+                    "default": DEFAULT_TOKEN_BUDGET
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = PathBuf::from(params["path"].as_str().unwrap_or("."));
+        let budget = params["max_tokens"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_TOKEN_BUDGET)
+            .clamp(200, 20_000);
+        if !path.is_dir() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Le dossier '{}' n'existe pas",
+                path.display()
+            )));
+        }
+
+        let map = tokio::task::spawn_blocking(move || build_repo_map(&path))
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        let rendered = map.render(budget);
+
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "map": rendered,
+                "files": map.total_files,
+                "languages": map.languages.iter().map(|(l, _, _)| l).collect::<Vec<_>>(),
+            }),
+            message: format!(
+                "Carte du projet: {} fichier(s), {}",
+                map.total_files,
+                format_size(map.total_bytes)
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/agent/tools")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(root.join("README.md"), "# Demo").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/agent/mod.rs"), "pub mod tools;").unwrap();
+        std::fs::write(root.join("src/agent/tools/web.rs"), "// web").unwrap();
+        std::fs::write(root.join("target/debug/app"), "binary").unwrap();
+        std::fs::write(root.join(".git/HEAD"), "ref").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_build_repo_map() {
+        let dir = sample_repo();
+        let map = build_repo_map(dir.path());
+        assert_eq!(map.total_files, 5);
+        assert_eq!(map.key_files, vec!["Cargo.toml", "README.md"]);
+        assert_eq!(map.entry_points, vec!["src/main.rs"]);
+        assert_eq!(map.languages[0].0, "Rust");
+        assert_eq!(map.languages[0].1, 3);
+
+        let rendered = map.render(DEFAULT_TOKEN_BUDGET);
+        assert!(rendered.contains("src/ (3 files"));
+        assert!(rendered.contains("      web.rs"));
+        assert!(!rendered.contains("target"));
+        assert!(!rendered.contains("omitted"));
+    }
+
+    #[test]
+    fn test_render_budget_keeps_shallow_entries() {
+        let dir = sample_repo();
+        let map = build_repo_map(dir.path());
+        let header_tokens = estimate_tokens(&map.render(0));
+        let rendered = map.render(header_tokens + 20);
+        assert!(rendered.contains("src/"));
+        assert!(!rendered.contains("web.rs"));
+        assert!(rendered.contains("deeper entries omitted"));
+    }
+}
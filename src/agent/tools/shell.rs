@@ -4,10 +4,13 @@
 
 use async_trait::async_trait;
 use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
 use tokio::time::{timeout, Duration};
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{CancelToken, Tool, ToolContext, ToolError, ToolProgress, ToolProgressSender, ToolResult};
 
 // ============================================================================
 // BashTool - Full shell execution (like Claude Code's bash tool)
@@ -52,88 +55,165 @@ impl Tool for BashTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
-        let command_str = params["command"]
-            .as_str()
-            .ok_or_else(|| ToolError::InvalidParameters("command is required".into()))?;
-        let working_dir = params["working_dir"].as_str();
-        let timeout_secs = params["timeout_secs"].as_u64().unwrap_or(120);
-        let stdin_input = params["stdin"].as_str();
+        run_bash(params, None, None).await
+    }
 
-        // Build command
-        let (shell, shell_arg) = if cfg!(windows) {
-            ("powershell", vec!["-NoProfile", "-Command"])
-        } else {
-            ("bash", vec!["-c"])
-        };
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        run_bash(params, Some(ctx.progress), Some(ctx.cancel)).await
+    }
+}
 
-        let mut cmd = Command::new(shell);
-        for arg in &shell_arg {
-            cmd.arg(arg);
-        }
-        cmd.arg(command_str);
+/// Run a shell command, forwarding each output line to `progress` if given
+/// and killing the process if `cancel` fires.
+async fn run_bash(
+    params: Value,
+    progress: Option<ToolProgressSender>,
+    cancel: Option<CancelToken>,
+) -> Result<ToolResult, ToolError> {
+    let command_str = params["command"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("command is required".into()))?;
+    let working_dir = params["working_dir"].as_str();
+    let timeout_secs = params["timeout_secs"].as_u64().unwrap_or(120);
+    let stdin_input = params["stdin"].as_str();
+
+    // Build command
+    let (shell, shell_arg) = if cfg!(windows) {
+        ("powershell", vec!["-NoProfile", "-Command"])
+    } else {
+        ("bash", vec!["-c"])
+    };
 
-        if let Some(dir) = working_dir {
-            cmd.current_dir(dir);
-        }
+    let mut cmd = Command::new(shell);
+    for arg in &shell_arg {
+        cmd.arg(arg);
+    }
+    cmd.arg(command_str);
 
-        // Handle stdin
-        if stdin_input.is_some() {
-            cmd.stdin(std::process::Stdio::piped());
-        }
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
 
-        cmd.stdout(std::process::Stdio::piped());
-        cmd.stderr(std::process::Stdio::piped());
+    // Handle stdin; without input it is closed, since the command can't be
+    // interacted with (and reading the terminal from its own group would stop it)
+    if stdin_input.is_some() {
+        cmd.stdin(std::process::Stdio::piped());
+    } else {
+        cmd.stdin(std::process::Stdio::null());
+    }
 
-        // Execute with timeout
-        let result = timeout(Duration::from_secs(timeout_secs), async {
-            let mut child = cmd.spawn().map_err(|e| {
-                ToolError::ExecutionFailed(format!("Failed to launch command: {}", e))
-            })?;
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+    // Also kills the process when the timeout drops the future
+    cmd.kill_on_drop(true);
+    // Own process group, so cancelling reaches everything the command started
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    // Execute with timeout
+    let result = timeout(Duration::from_secs(timeout_secs), async {
+        let mut child = cmd.spawn().map_err(|e| {
+            ToolError::ExecutionFailed(format!("Failed to launch command: {}", e))
+        })?;
+        // Cancel, timeout or the caller dropping this future all end here
+        let mut tree = ProcessTreeGuard { pid: child.id() };
+
+        if let Some(input) = stdin_input {
+            if let Some(mut stdin) = child.stdin.take() {
+                use tokio::io::AsyncWriteExt;
+                let _ = stdin.write_all(input.as_bytes()).await;
+                drop(stdin);
+            }
+        }
 
-            if let Some(input) = stdin_input {
-                if let Some(mut stdin) = child.stdin.take() {
-                    use tokio::io::AsyncWriteExt;
-                    let _ = stdin.write_all(input.as_bytes()).await;
-                    drop(stdin);
-                }
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let run = async {
+            tokio::join!(
+                read_output(stdout, progress.clone()),
+                read_output(stderr, progress.clone()),
+                child.wait(),
+            )
+        };
+        let cancelled = async {
+            match cancel {
+                Some(ref token) => token.cancelled().await,
+                None => std::future::pending().await,
             }
+        };
+        let (stdout, stderr, status) = tokio::select! {
+            output = run => output,
+            _ = cancelled => {
+                drop(tree);
+                let _ = child.kill().await;
+                return Err(ToolError::Cancelled);
+            }
+        };
+        // Finished on its own: leave anything it deliberately left running
+        tree.pid = None;
+        let status =
+            status.map_err(|e| ToolError::ExecutionFailed(format!("Execution error: {}", e)))?;
+        Ok::<_, ToolError>((stdout, stderr, status))
+    })
+    .await;
+
+    match result {
+        Ok(Ok((stdout, stderr, status))) => {
+            let exit_code = status.code().unwrap_or(-1);
+
+            // Truncate very long output
+            let stdout_display = truncate_output(&stdout, 50000);
+            let stderr_display = truncate_output(&stderr, 10000);
+
+            Ok(ToolResult {
+                success: status.success(),
+                data: serde_json::json!({
+                    "stdout": stdout_display,
+                    "stderr": stderr_display,
+                    "exit_code": exit_code,
+                    "command": command_str
+                }),
+                message: if status.success() {
+                    format!("Command executed (code: {})", exit_code)
+                } else {
+                    format!("Command failed (code: {})", exit_code)
+                },
+            })
+        }
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(ToolError::Timeout),
+    }
+}
 
-            child
-                .wait_with_output()
-                .await
-                .map_err(|e| ToolError::ExecutionFailed(format!("Execution error: {}", e)))
-        })
-        .await;
-
-        match result {
-            Ok(Ok(output)) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                let exit_code = output.status.code().unwrap_or(-1);
-
-                // Truncate very long output
-                let stdout_display = truncate_output(&stdout, 50000);
-                let stderr_display = truncate_output(&stderr, 10000);
-
-                Ok(ToolResult {
-                    success: output.status.success(),
-                    data: serde_json::json!({
-                        "stdout": stdout_display,
-                        "stderr": stderr_display,
-                        "exit_code": exit_code,
-                        "command": command_str
-                    }),
-                    message: if output.status.success() {
-                        format!("Command executed (code: {})", exit_code)
-                    } else {
-                        format!("Command failed (code: {})", exit_code)
-                    },
-                })
+/// Collect a child output stream, reporting each line as it arrives
+async fn read_output<R: AsyncRead + Unpin>(
+    stream: Option<R>,
+    progress: Option<ToolProgressSender>,
+) -> String {
+    let Some(stream) = stream else {
+        return String::new();
+    };
+    let mut reader = BufReader::new(stream);
+    let mut output = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                if let Some(ref tx) = progress {
+                    let text = String::from_utf8_lossy(&line).trim_end().to_string();
+                    let _ = tx.send(ToolProgress::Output(text));
+                }
+                output.extend_from_slice(&line);
             }
-            Ok(Err(e)) => Err(e),
-            Err(_) => Err(ToolError::Timeout),
         }
     }
+    String::from_utf8_lossy(&output).to_string()
 }
 
 // ============================================================================
@@ -193,12 +273,18 @@ impl Tool for BashBackgroundTool {
 
         cmd.stdout(std::process::Stdio::null());
         cmd.stderr(std::process::Stdio::null());
+        // Own process group, so shutdown can stop everything it started
+        #[cfg(unix)]
+        cmd.process_group(0);
 
         let child = cmd.spawn().map_err(|e| {
             ToolError::ExecutionFailed(format!("Failed to launch command: {}", e))
         })?;
 
         let pid = child.id().unwrap_or(0);
+        if pid != 0 {
+            BACKGROUND_PIDS.lock().unwrap_or_else(|e| e.into_inner()).push(pid);
+        }
 
         Ok(ToolResult {
             success: true,
@@ -216,6 +302,52 @@ impl Tool for BashBackgroundTool {
 // Helpers
 // ============================================================================
 
+/// Processes started by `bash_background`, stopped when the app quits
+static BACKGROUND_PIDS: Lazy<Mutex<Vec<u32>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Kill every process tree started by `bash_background`; returns how many
+pub fn kill_background_processes() -> usize {
+    let pids = std::mem::take(&mut *BACKGROUND_PIDS.lock().unwrap_or_else(|e| e.into_inner()));
+    for pid in &pids {
+        kill_process_tree(*pid);
+    }
+    pids.len()
+}
+
+/// Kills a command's whole process tree when dropped, unless `pid` was
+/// cleared because the command finished
+struct ProcessTreeGuard {
+    pid: Option<u32>,
+}
+
+impl Drop for ProcessTreeGuard {
+    fn drop(&mut self) {
+        if let Some(pid) = self.pid.take() {
+            kill_process_tree(pid);
+        }
+    }
+}
+
+/// Kill `pid` and everything it started: its process group on Unix (see
+/// `process_group(0)` in [`run_bash`]), its process tree on Windows
+fn kill_process_tree(pid: u32) {
+    #[cfg(unix)]
+    let result = std::process::Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", pid)])
+        .stderr(std::process::Stdio::null())
+        .status();
+    #[cfg(windows)]
+    let result = std::process::Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    match result {
+        Ok(_) => tracing::info!("Killed process tree of {}", pid),
+        Err(e) => tracing::warn!("Failed to kill process tree of {}: {}", pid, e),
+    }
+}
+
 fn truncate_output(output: &str, max_chars: usize) -> String {
     if output.len() <= max_chars {
         output.to_string()
@@ -231,3 +363,67 @@ fn truncate_output(output: &str, max_chars: usize) -> String {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_cancel_kills_child_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let cancel = CancelToken::new();
+        let command = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+        let run = run_bash(serde_json::json!({ "command": command }), None, Some(cancel.clone()));
+        let trigger = async {
+            while !pid_file.exists() {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            cancel.cancel();
+        };
+        let (result, _) = tokio::join!(run, trigger);
+        assert!(matches!(result, Err(ToolError::Cancelled)));
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // Gone, or a zombie waiting for a reaper
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        let running = stat
+            .rsplit(')')
+            .next()
+            .and_then(|rest| rest.split_whitespace().next())
+            .is_some_and(|state| state != "Z");
+        assert!(!running, "background child should be killed with the command");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_kill_background_processes() {
+        let result = BashBackgroundTool
+            .execute(serde_json::json!({ "command": "sleep 30" }))
+            .await
+            .unwrap();
+        let pid = result.data["pid"].as_u64().unwrap();
+        assert!(kill_background_processes() >= 1);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+        let running = stat
+            .rsplit(')')
+            .next()
+            .and_then(|rest| rest.split_whitespace().next())
+            .is_some_and(|state| state != "Z");
+        assert!(!running, "background command should be killed on shutdown");
+        assert_eq!(kill_background_processes(), 0);
+    }
+
+    #[test]
+    fn test_truncate_output() {
+        let output = "a".repeat(100);
+        assert_eq!(truncate_output(&output, 200), output);
+        let truncated = truncate_output(&output, 20);
+        assert!(truncated.starts_with("aaaaaaaaaa\n"));
+        assert!(truncated.contains("80 characters omitted"));
+    }
+}
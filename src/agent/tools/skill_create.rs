@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use regex::Regex;
 use crate::agent::tools::{Tool, ToolResult, ToolError, ToolRegistry};
-use crate::agent::skills::SkillRegistry;
+use crate::agent::skills::{SkillPermission, SkillRegistry};
 use crate::storage::get_data_dir;
 
 pub struct SkillCreateTool {
@@ -104,7 +104,7 @@ impl Tool for SkillCreateTool {
                 "files": {
                     "type": "object",
                     "additionalProperties": { "type": "string" },
-                    "description": "Optional map of filenames to content (e.g. {'script.py': 'print(\"hello\")'}). Create Python scripts here to handle complex logic."
+                    "description": "Optional map of filenames to content (e.g. {'script.py': 'print(\"hello\")'}). Create Python scripts here to handle complex logic. The entrypoint (main.py, index.js, run.sh, run.py, main.ts or run.ps1) receives the call parameters as JSON, as its first argument and on stdin, and may print a JSON object as its result."
                 },
                 "is_global": {
                     "type": "boolean",
@@ -120,6 +120,11 @@ impl Tool for SkillCreateTool {
                     "type": "array",
                     "items": { "type": "string" },
                     "description": "List of tool names allowed for this skill (optional)"
+                },
+                "permissions": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["network", "write", "shell"] },
+                    "description": "Capabilities the skill's script needs: network requests, file writes outside its folder, shell commands. The user reviews them before the skill is created and approves them each time it runs."
                 }
             },
             "required": ["name", "description", "content"]
@@ -146,6 +151,18 @@ impl Tool for SkillCreateTool {
         let is_global = params["is_global"].as_bool().unwrap_or(false);
         let disable_auto_invoke = params["disable_auto_invoke"].as_bool().unwrap_or(false);
         let allowed_tools = params["allowed_tools"].as_array();
+        let mut permissions: Vec<SkillPermission> = Vec::new();
+        for value in params["permissions"].as_array().into_iter().flatten() {
+            let permission = value.as_str().and_then(SkillPermission::parse).ok_or_else(|| {
+                ToolError::InvalidParameters(format!(
+                    "Unknown permission {} (expected network, write or shell)",
+                    value
+                ))
+            })?;
+            if !permissions.contains(&permission) {
+                permissions.push(permission);
+            }
+        }
         let files = params["files"].as_object()
             .ok_or_else(|| {
                 tracing::error!("skill_create: files parameter is missing");
@@ -153,7 +170,7 @@ impl Tool for SkillCreateTool {
             })?;
 
         // Enforce executable presence
-        let valid_extensions = [".py", ".js", ".ts", ".sh"];
+        let valid_extensions = [".py", ".js", ".ts", ".sh", ".ps1"];
         let has_executable = files.keys().any(|k| valid_extensions.iter().any(|ext| k.ends_with(ext)));
         
         if !has_executable {
@@ -211,6 +228,15 @@ impl Tool for SkillCreateTool {
             }
         }
 
+        if !permissions.is_empty() {
+            let names: Vec<String> = permissions
+                .iter()
+                .filter_map(|p| serde_json::to_value(p).ok())
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect();
+            frontmatter.push_str(&format!("permissions: {}\n", names.join(", ")));
+        }
+
         frontmatter.push_str("---\n\n");
         frontmatter.push_str(content);
 
@@ -260,7 +286,8 @@ impl Tool for SkillCreateTool {
             data: serde_json::json!({
                 "path": file_path.display().to_string(),
                 "name": name,
-                "is_global": is_global
+                "is_global": is_global,
+                "permissions": permissions
             }),
             message: format!("Skill '{}' created successfully at {}. Skills reloaded.", name, file_path.display()),
         })
@@ -61,7 +61,8 @@ impl Tool for SkillInvokeTool {
                         "description": s.description,
                         "content": s.content,
                         "path": s.path,
-                        "allowed_tools": s.allowed_tools
+                        "allowed_tools": s.allowed_tools,
+                        "permissions": s.effective_permissions()
                     }),
                     message: format!("Skill '{}' invoked successfully.", s.name),
                 })
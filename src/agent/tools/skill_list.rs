@@ -32,7 +32,8 @@ impl Tool for SkillListTool {
                 "description": s.description,
                 "path": s.path,
                 "auto_invoke": !s.disable_auto_invoke,
-                "allowed_tools": s.allowed_tools
+                "allowed_tools": s.allowed_tools,
+                "permissions": s.effective_permissions()
             })
         }).collect();
         
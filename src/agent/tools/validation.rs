@@ -0,0 +1,280 @@
+//! Tool parameter validation against `parameters_schema`
+//!
+//! Supports the JSON-schema subset our tools use: object properties,
+//! `required`, primitive `type`s, `enum`, `minimum`/`maximum` and array
+//! `items`. Obvious type slips from the model (`"5"` for an integer,
+//! `"true"` for a boolean) are coerced rather than rejected.
+
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A single problem with one parameter
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamIssue {
+    /// Parameter path, e.g. `path` or `files[1]`
+    pub param: String,
+    /// What is wrong with it
+    pub problem: String,
+}
+
+/// Structured validation failure, formatted so the model can fix its call
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub tool: String,
+    pub issues: Vec<ParamIssue>,
+    /// One-line summary of the expected parameters
+    pub expected: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid parameters for `{}`:", self.tool)?;
+        for issue in &self.issues {
+            writeln!(f, "- `{}`: {}", issue.param, issue.problem)?;
+        }
+        write!(f, "Expected params: {}", self.expected)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validate `params` against `schema`, returning the (possibly coerced) params
+pub fn validate_params(tool: &str, schema: &Value, params: &Value) -> Result<Value, ValidationError> {
+    // Tools without a usable schema accept anything
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Ok(params.clone());
+    };
+
+    let mut issues = Vec::new();
+    let mut normalized = match params {
+        Value::Object(obj) => obj.clone(),
+        Value::Null => Map::new(),
+        other => {
+            issues.push(ParamIssue {
+                param: "params".to_string(),
+                problem: format!("must be an object, got {}", type_name(other)),
+            });
+            Map::new()
+        }
+    };
+
+    if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+        for name in required.iter().filter_map(|r| r.as_str()) {
+            if !matches!(normalized.get(name), Some(v) if !v.is_null()) {
+                let expected = properties
+                    .get(name)
+                    .and_then(|p| p.get("type"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("value");
+                issues.push(ParamIssue {
+                    param: name.to_string(),
+                    problem: format!("missing required parameter ({})", expected),
+                });
+            }
+        }
+    }
+
+    for (name, value) in normalized.iter_mut() {
+        // Unknown and null optional params are left for the tool to ignore
+        if value.is_null() {
+            continue;
+        }
+        if let Some(prop_schema) = properties.get(name) {
+            check_value(name, prop_schema, value, &mut issues);
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(Value::Object(normalized))
+    } else {
+        Err(ValidationError {
+            tool: tool.to_string(),
+            issues,
+            expected: describe_schema(schema),
+        })
+    }
+}
+
+/// Check one value against its property schema, coercing in place when safe
+fn check_value(path: &str, schema: &Value, value: &mut Value, issues: &mut Vec<ParamIssue>) {
+    if let Some(expected) = schema.get("type").and_then(|t| t.as_str()) {
+        if !matches_type(expected, value) {
+            match coerce(expected, value) {
+                Some(coerced) => *value = coerced,
+                None => {
+                    issues.push(ParamIssue {
+                        param: path.to_string(),
+                        problem: format!("must be {}, got {}", expected, type_name(value)),
+                    });
+                    return;
+                }
+            }
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            let options: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+            issues.push(ParamIssue {
+                param: path.to_string(),
+                problem: format!("must be one of {}", options.join(", ")),
+            });
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()) {
+            if n < min {
+                issues.push(ParamIssue {
+                    param: path.to_string(),
+                    problem: format!("must be >= {}", min),
+                });
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()) {
+            if n > max {
+                issues.push(ParamIssue {
+                    param: path.to_string(),
+                    problem: format!("must be <= {}", max),
+                });
+            }
+        }
+    }
+
+    if let (Some(items), Value::Array(values)) = (schema.get("items"), value) {
+        for (i, item) in values.iter_mut().enumerate() {
+            check_value(&format!("{}[{}]", path, i), items, item, issues);
+        }
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Fix common model slips; `None` if the value can't sensibly be converted
+fn coerce(expected: &str, value: &Value) -> Option<Value> {
+    match (expected, value) {
+        ("integer", Value::String(s)) => s.trim().parse::<i64>().ok().map(Value::from),
+        ("integer", Value::Number(n)) => n
+            .as_f64()
+            .filter(|f| f.fract() == 0.0)
+            .map(|f| Value::from(f as i64)),
+        ("number", Value::String(s)) => s.trim().parse::<f64>().ok().map(Value::from),
+        ("boolean", Value::String(s)) => match s.trim().to_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ("string", Value::Number(n)) => Some(Value::String(n.to_string())),
+        ("string", Value::Bool(b)) => Some(Value::String(b.to_string())),
+        // A single value where a list is expected
+        ("array", v) if !v.is_object() => Some(Value::Array(vec![v.clone()])),
+        _ => None,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Compact description like `path: string (required), limit: integer`
+fn describe_schema(schema: &Value) -> String {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return "{}".to_string();
+    };
+
+    properties
+        .iter()
+        .map(|(name, prop)| {
+            let ty = prop.get("type").and_then(|t| t.as_str()).unwrap_or("any");
+            if required.contains(&name.as_str()) {
+                format!("{}: {} (required)", name, ty)
+            } else {
+                format!("{}: {}", name, ty)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "limit": { "type": "integer", "minimum": 1 },
+                "recursive": { "type": "boolean" },
+                "mode": { "type": "string", "enum": ["fast", "full"] },
+                "files": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["path"]
+        })
+    }
+
+    #[test]
+    fn test_valid_params_pass_through() {
+        let params = json!({"path": "a.txt", "limit": 5, "extra": true});
+        assert_eq!(validate_params("t", &schema(), &params).unwrap(), params);
+    }
+
+    #[test]
+    fn test_coercion() {
+        let params = json!({"path": "a.txt", "limit": "5", "recursive": "true", "files": "b.txt"});
+        let normalized = validate_params("t", &schema(), &params).unwrap();
+        assert_eq!(normalized["limit"], json!(5));
+        assert_eq!(normalized["recursive"], json!(true));
+        assert_eq!(normalized["files"], json!(["b.txt"]));
+    }
+
+    #[test]
+    fn test_structured_errors() {
+        let params = json!({"limit": 0, "mode": "slow", "recursive": "maybe"});
+        let err = validate_params("file_list", &schema(), &params).unwrap_err();
+        let params_with_issues: Vec<&str> = err.issues.iter().map(|i| i.param.as_str()).collect();
+        assert!(params_with_issues.contains(&"path"));
+        assert!(params_with_issues.contains(&"limit"));
+        assert!(params_with_issues.contains(&"mode"));
+        assert!(params_with_issues.contains(&"recursive"));
+
+        let message = err.to_string();
+        assert!(message.contains("`file_list`"));
+        assert!(message.contains("path: string (required)"));
+    }
+
+    #[test]
+    fn test_non_object_params() {
+        let err = validate_params("t", &schema(), &json!("a.txt")).unwrap_err();
+        assert_eq!(err.issues[0].param, "params");
+        // Null params are fine when nothing is required
+        let optional = json!({"properties": {"q": {"type": "string"}}});
+        assert!(validate_params("t", &optional, &Value::Null).is_ok());
+    }
+}
@@ -5,7 +5,11 @@
 use async_trait::async_trait;
 use serde_json::Value;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolContext, ToolError, ToolProgress, ToolProgressSender, ToolResult};
+use crate::system::disk::ensure_free_space;
+use crate::agent::tools::rate_limit::{self, Failure, ProviderLimits};
+use crate::system::certificates::describe_error;
+use crate::system::proxy::{client_builder, ProxyClient};
 
 // ============================================================================
 // WebFetchTool - Fetch URL content
@@ -64,40 +68,70 @@ impl Tool for WebFetchTool {
         let body = params["body"].as_str();
         let max_length = params["max_length"].as_u64().unwrap_or(50000) as usize;
 
-        let client = reqwest::Client::builder()
+        let client = client_builder(ProxyClient::WebFetch)
             .timeout(std::time::Duration::from_secs(30))
             .user_agent("clawRS/0.2.0")
             .build()
             .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le client HTTP: {}", e)))?;
 
-        let mut request = match method.to_uppercase().as_str() {
-            "GET" => client.get(url),
-            "POST" => client.post(url),
-            "PUT" => client.put(url),
-            "DELETE" => client.delete(url),
-            "PATCH" => client.patch(url),
-            "HEAD" => client.head(url),
+        let method = match method.to_uppercase().as_str() {
+            "GET" => reqwest::Method::GET,
+            "POST" => reqwest::Method::POST,
+            "PUT" => reqwest::Method::PUT,
+            "DELETE" => reqwest::Method::DELETE,
+            "PATCH" => reqwest::Method::PATCH,
+            "HEAD" => reqwest::Method::HEAD,
             _ => return Err(ToolError::InvalidParameters(format!("Méthode HTTP inconnue: {}", method))),
         };
 
-        // Add headers
-        if let Some(hdrs) = headers {
-            for (key, value) in hdrs {
-                if let Some(val) = value.as_str() {
-                    request = request.header(key.as_str(), val);
+        // Rate limit and circuit breaker per host
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| url.to_string());
+
+        // Only reads are replayed; a write that may have reached the server
+        // is retried only when the connection was never made
+        let idempotent = matches!(method, reqwest::Method::GET | reqwest::Method::HEAD);
+
+        let response = rate_limit::run(&host, &ProviderLimits::web(), || {
+            let mut request = client.request(method.clone(), url);
+
+            // Add headers
+            if let Some(hdrs) = headers {
+                for (key, value) in hdrs {
+                    if let Some(val) = value.as_str() {
+                        request = request.header(key.as_str(), val);
+                    }
                 }
             }
-        }
 
-        // Add body
-        if let Some(b) = body {
-            request = request.body(b.to_string());
-        }
+            // Add body
+            if let Some(b) = body {
+                request = request.body(b.to_string());
+            }
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP: {}", e)))?;
+            async move {
+                let response = request.send().await.map_err(|e| {
+                    let never_sent = e.is_connect();
+                    let mut failure = Failure::from_request(e, "Erreur HTTP");
+                    failure.retryable &= idempotent || never_sent;
+                    failure
+                })?;
+                // Throttling and gateway errors are worth another try; other
+                // statuses are returned to the model as-is
+                let status = response.status();
+                if idempotent && matches!(status.as_u16(), 429 | 502 | 503 | 504) {
+                    return Err(Failure::from_status(
+                        status,
+                        response.headers(),
+                        format!("Erreur HTTP {} sur {}", status, url),
+                    ));
+                }
+                Ok(response)
+            }
+        })
+        .await?;
 
         let status = response.status().as_u16();
         let content_type = response
@@ -191,61 +225,109 @@ impl Tool for WebDownloadTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
-        let url = params["url"]
-            .as_str()
-            .ok_or_else(|| ToolError::InvalidParameters("url is required".into()))?;
-        let path = params["path"]
-            .as_str()
-            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
-
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .user_agent("clawRS/0.2.0")
-            .build()
-            .map_err(|e| ToolError::ExecutionFailed(format!("Client HTTP: {}", e)))?;
+        download(params, None).await
+    }
 
-        let response = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "HTTP {} pour {}",
-                response.status(),
-                url
-            )));
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        // Nothing is written until the body is complete, so dropping the
+        // download on cancel leaves no partial file behind
+        tokio::select! {
+            result = download(params, Some(ctx.progress)) => result,
+            _ = ctx.cancel.cancelled() => Err(ToolError::Cancelled),
         }
+    }
+}
 
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur lecture: {}", e)))?;
-
-        let path_buf = std::path::PathBuf::from(path);
-        if let Some(parent) = path_buf.parent() {
-            if !parent.exists() {
-                tokio::fs::create_dir_all(parent)
-                    .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+/// Download `url` to `path`, reporting progress as chunks arrive
+async fn download(
+    params: Value,
+    progress: Option<ToolProgressSender>,
+) -> Result<ToolResult, ToolError> {
+    let url = params["url"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("url is required".into()))?;
+    let path = params["path"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+
+    let client = client_builder(ProxyClient::WebFetch)
+        .timeout(std::time::Duration::from_secs(120))
+        .user_agent("clawRS/0.2.0")
+        .build()
+        .map_err(|e| ToolError::ExecutionFailed(format!("Client HTTP: {}", e)))?;
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP: {}", describe_error(&e))))?;
+
+    if !response.status().is_success() {
+        return Err(ToolError::ExecutionFailed(format!(
+            "HTTP {} pour {}",
+            response.status(),
+            url
+        )));
+    }
+
+    let total = response.content_length();
+    let mut bytes = Vec::with_capacity(total.unwrap_or(0) as usize);
+    let mut last_step = None;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur lecture: {}", e)))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if let Some(ref tx) = progress {
+            // Report every 5% (or every MB when the size is unknown)
+            let step = match total {
+                Some(total) if total > 0 => bytes.len() as u64 * 100 / total / 5,
+                _ => bytes.len() as u64 / (1024 * 1024),
+            };
+            if last_step != Some(step) {
+                last_step = Some(step);
+                let status = match total {
+                    Some(total) if total > 0 => format!(
+                        "{}% ({} / {} Ko)",
+                        bytes.len() as u64 * 100 / total,
+                        bytes.len() / 1024,
+                        total / 1024
+                    ),
+                    _ => format!("{} Ko", bytes.len() / 1024),
+                };
+                let _ = tx.send(ToolProgress::Status(status));
             }
         }
+    }
 
-        tokio::fs::write(&path_buf, &bytes)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible d'écrire: {}", e)))?;
-
-        Ok(ToolResult {
-            success: true,
-            data: serde_json::json!({
-                "url": url,
-                "path": path,
-                "bytes": bytes.len()
-            }),
-            message: format!("Téléchargé: {} -> {} ({} octets)", url, path, bytes.len()),
-        })
+    let path_buf = std::path::PathBuf::from(path);
+    if let Some(parent) = path_buf.parent() {
+        if !parent.exists() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+        }
     }
+
+    ensure_free_space(&path_buf, bytes.len() as u64)?;
+    tokio::fs::write(&path_buf, &bytes)
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Impossible d'écrire: {}", e)))?;
+
+    Ok(ToolResult {
+        success: true,
+        data: serde_json::json!({
+            "url": url,
+            "path": path,
+            "bytes": bytes.len()
+        }),
+        message: format!("Téléchargé: {} -> {} ({} octets)", url, path, bytes.len()),
+    })
 }
 
 // ============================================================================
@@ -0,0 +1,158 @@
+//! Workspace search
+//!
+//! Looks up the workspace index: code symbols by name, and passages by
+//! meaning when an embedding model is installed. Faster than grep on large
+//! projects and finds code described in words rather than by exact text.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::workspace_index::{self, ChunkHit};
+
+/// Lines of a passage shown in results
+const SNIPPET_LINES: usize = 15;
+
+/// First lines of a passage, numbered like file_read
+fn snippet(root: &Path, hit: &ChunkHit) -> String {
+    let content = std::fs::read_to_string(root.join(&hit.path)).unwrap_or_default();
+    content
+        .lines()
+        .enumerate()
+        .skip(hit.start_line - 1)
+        .take(SNIPPET_LINES.min(hit.end_line + 1 - hit.start_line))
+        .map(|(i, line)| format!("{:>5} | {}", i + 1, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub struct WorkspaceSearchTool;
+
+#[async_trait]
+impl Tool for WorkspaceSearchTool {
+    fn name(&self) -> &str {
+        "workspace_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the indexed workspace: 'symbols' finds functions, types and classes by name; 'semantic' finds passages by meaning (e.g. 'where are retries handled'). Returns paths and line numbers to read next."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Symbol name or description of the code to find"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["symbols", "semantic"],
+                    "description": "Search mode (default: symbols)",
+                    "default": "symbols"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum results (default: 10)",
+                    "default": 10
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let query = params["query"]
+            .as_str()
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("query is required".into()))?;
+        let limit = params["limit"].as_u64().unwrap_or(10).clamp(1, 50) as usize;
+        let semantic = params["mode"].as_str() == Some("semantic");
+
+        let index = workspace_index::current_index().ok_or_else(|| {
+            ToolError::ExecutionFailed(
+                "Aucun espace de travail indexé. Utilise grep ou file_search à la place.".into(),
+            )
+        })?;
+
+        if semantic {
+            let Some(embedding) = workspace_index::embed_query(query).await else {
+                return Err(ToolError::ExecutionFailed(
+                    "Recherche sémantique indisponible (aucun modèle d'embedding installé). Utilise mode 'symbols' ou grep.".into(),
+                ));
+            };
+            let (root, hits) = {
+                let index = index.read().unwrap_or_else(|e| e.into_inner());
+                (index.root.clone(), index.search_chunks(&embedding, limit))
+            };
+            let results: Vec<Value> = hits
+                .iter()
+                .map(|hit| {
+                    serde_json::json!({
+                        "path": hit.path,
+                        "lines": format!("{}-{}", hit.start_line, hit.end_line),
+                        "score": (hit.score * 1000.0).round() / 1000.0,
+                        "snippet": snippet(&root, hit),
+                    })
+                })
+                .collect();
+            return Ok(ToolResult {
+                success: true,
+                message: format!("{} passage(s) pour '{}'", results.len(), query),
+                data: serde_json::json!({ "root": root, "results": results }),
+            });
+        }
+
+        let (root, hits) = {
+            let index = index.read().unwrap_or_else(|e| e.into_inner());
+            (index.root.clone(), index.search_symbols(query, limit))
+        };
+        let results: Vec<Value> = hits
+            .iter()
+            .map(|hit| {
+                serde_json::json!({
+                    "path": hit.path,
+                    "line": hit.symbol.line,
+                    "kind": hit.symbol.kind,
+                    "name": hit.symbol.name,
+                })
+            })
+            .collect();
+        Ok(ToolResult {
+            success: true,
+            message: format!("{} symbole(s) pour '{}'", results.len(), query),
+            data: serde_json::json!({ "root": root, "results": results }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        let dir = tempfile::tempdir().unwrap();
+        let content: String = (1..=60).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(dir.path().join("a.rs"), content).unwrap();
+        let hit = ChunkHit {
+            path: "a.rs".to_string(),
+            start_line: 41,
+            end_line: 60,
+            score: 0.5,
+        };
+        let text = snippet(dir.path(), &hit);
+        assert_eq!(text.lines().count(), SNIPPET_LINES);
+        assert!(text.starts_with("   41 | line 41"));
+    }
+
+    #[tokio::test]
+    async fn test_requires_query() {
+        let result = WorkspaceSearchTool
+            .execute(serde_json::json!({ "query": " " }))
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+}
@@ -0,0 +1,428 @@
+//! Watched folders
+//!
+//! Each watched folder has a workflow: instructions, optionally a persona,
+//! run on every new file dropped into it. The answer is written next to the
+//! file (`report.pdf` gives `report.pdf.clawrs.md`) or saved as a new
+//! conversation, and a desktop notification reports each run. Runs use the
+//! model loaded in the app, one file at a time.
+
+use chrono::{DateTime, Utc};
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+use crate::agent::tools::CancelToken;
+use crate::inference::{collect_text, EngineError, InferenceBackend, SharedEngine};
+use crate::storage::conversations::{save_conversation, Conversation};
+use crate::storage::dataset::strip_thinking;
+use crate::storage::settings::load_settings;
+use crate::system::notifications::notify;
+use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+
+/// Appended to the source file name for answers written next to it
+pub const OUTPUT_SUFFIX: &str = ".clawrs.md";
+/// Longest document text sent to the model, in characters
+const MAX_INPUT_CHARS: usize = 48_000;
+/// Quiet time before a new file is considered complete
+const SETTLE: Duration = Duration::from_secs(2);
+/// Runs kept for the settings card
+const RECENT_RUNS: usize = 20;
+/// Files remembered as done, to ignore repeated events for them
+const DONE_KEPT: usize = 256;
+
+/// Built-in workflows offered in Settings, as (id, English label, French
+/// label, instructions)
+pub const WORKFLOW_TEMPLATES: &[(&str, &str, &str, &str)] = &[
+    (
+        "summary",
+        "Summary",
+        "Resume",
+        "Résume ce document en quelques paragraphes, dans la langue du document.",
+    ),
+    (
+        "key_points",
+        "Key points and actions",
+        "Points cles et actions",
+        "Liste les points clés du document puis les actions à mener, sous forme de listes à puces, dans la langue du document.",
+    ),
+    (
+        "translate",
+        "Translate to English",
+        "Traduire en anglais",
+        "Traduis ce document en anglais en conservant sa structure et sa mise en forme.",
+    ),
+];
+
+#[derive(Error, Debug)]
+pub enum WatchError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Inference error: {0}")]
+    Engine(#[from] EngineError),
+    #[error("Not a text or PDF file")]
+    Unsupported,
+    #[error("Could not read the PDF: {0}")]
+    Pdf(String),
+    #[error("No model loaded")]
+    NoModel,
+    #[error("Generation failed: {0}")]
+    Generation(String),
+    #[error("Could not save the conversation: {0}")]
+    Conversation(String),
+}
+
+/// Where a workflow's answer goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchOutput {
+    /// `<file><OUTPUT_SUFFIX>` in the watched folder
+    #[default]
+    NextToFile,
+    /// A new conversation with the file and the answer
+    Conversation,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchFolder {
+    pub path: PathBuf,
+    /// Instructions run on each new file
+    pub prompt: String,
+    /// Persona whose instructions become the system prompt
+    #[serde(default)]
+    pub persona: Option<String>,
+    #[serde(default)]
+    pub output: WatchOutput,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl WatchFolder {
+    pub fn new(path: PathBuf, prompt: String) -> Self {
+        Self {
+            path,
+            prompt,
+            persona: None,
+            output: WatchOutput::default(),
+            enabled: true,
+        }
+    }
+
+    /// Messages for one file: the persona as system prompt, then the
+    /// instructions with the document
+    pub fn messages(&self, persona_instructions: Option<String>, name: &str, text: &str) -> Vec<ChatMessage> {
+        let mut messages = Vec::new();
+        if let Some(instructions) = persona_instructions.filter(|i| !i.trim().is_empty()) {
+            messages.push(ChatMessage::new(ChatRole::System, instructions));
+        }
+        messages.push(ChatMessage::new(
+            ChatRole::User,
+            format!("{}\n\n<document name=\"{}\">\n{}\n</document>", self.prompt.trim(), name, text),
+        ));
+        messages
+    }
+}
+
+/// Whether a new path in a watched folder should run the workflow: a
+/// regular, visible, finished file that isn't one of our answers
+pub fn is_candidate(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let partial = [".part", ".crdownload", ".download", ".tmp", "~"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix));
+    !name.starts_with('.') && !partial && !name.ends_with(OUTPUT_SUFFIX) && path.is_file()
+}
+
+/// Where the answer for `file` is written with [`WatchOutput::NextToFile`]
+pub fn output_path(file: &Path) -> PathBuf {
+    let mut name = file.file_name().unwrap_or_default().to_os_string();
+    name.push(OUTPUT_SUFFIX);
+    file.with_file_name(name)
+}
+
+/// Text of a PDF or UTF-8 file, cut to [`MAX_INPUT_CHARS`]
+fn read_document(path: &Path) -> Result<String, WatchError> {
+    let is_pdf = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+    let text = if is_pdf {
+        pdf_extract::extract_text(path).map_err(|e| WatchError::Pdf(e.to_string()))?
+    } else {
+        String::from_utf8(std::fs::read(path)?).map_err(|_| WatchError::Unsupported)?
+    };
+    let text = text.trim();
+    Ok(match text.char_indices().nth(MAX_INPUT_CHARS) {
+        Some((end, _)) => format!("{}\n[…]", &text[..end]),
+        None => text.to_string(),
+    })
+}
+
+/// Outcome of one file, for the settings card
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchRun {
+    pub file: PathBuf,
+    pub finished_at: DateTime<Utc>,
+    /// Answer file or conversation title
+    pub result: Result<String, String>,
+}
+
+static CURRENT: Lazy<Mutex<Option<CancelToken>>> = Lazy::new(|| Mutex::new(None));
+static RUNS: Lazy<Mutex<VecDeque<WatchRun>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static CONVERSATIONS_ADDED: AtomicBool = AtomicBool::new(false);
+
+/// Latest runs, newest first
+pub fn recent_runs() -> Vec<WatchRun> {
+    RUNS.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+}
+
+/// Whether a workflow saved a conversation since the last call
+pub fn take_conversations_added() -> bool {
+    CONVERSATIONS_ADDED.swap(false, Ordering::Relaxed)
+}
+
+/// Stop watching all folders
+pub fn stop() {
+    if let Some(stop) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        stop.cancel();
+        tracing::info!("Stopped watching folders");
+    }
+}
+
+/// Watch the enabled folders and run their workflows with `engine`;
+/// replaces any previous watch
+pub fn start(folders: Vec<WatchFolder>, engine: SharedEngine) {
+    stop();
+    let folders: Vec<WatchFolder> = folders
+        .into_iter()
+        .filter(|f| f.enabled && f.path.is_dir() && !f.prompt.trim().is_empty())
+        .map(|mut f| {
+            // Events carry resolved paths
+            f.path = f.path.canonicalize().unwrap_or(f.path);
+            f
+        })
+        .collect();
+    if folders.is_empty() {
+        return;
+    }
+    let stop = CancelToken::new();
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(stop.clone());
+    tokio::spawn(run(folders, engine, stop));
+}
+
+async fn run(folders: Vec<WatchFolder>, engine: SharedEngine, stop: CancelToken) {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        // New files, including downloads renamed once complete
+        if let Ok(event) = event {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))) {
+                let _ = tx.send(event.paths);
+            }
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => return tracing::warn!("Cannot watch folders: {}", e),
+    };
+    for folder in &folders {
+        match watcher.watch(&folder.path, RecursiveMode::NonRecursive) {
+            Ok(()) => tracing::info!("Watching {}", folder.path.display()),
+            Err(e) => tracing::warn!("Cannot watch {}: {}", folder.path.display(), e),
+        }
+    }
+
+    // Keyed on the modification time: a file dropped again under the same
+    // name is new
+    let mut done: VecDeque<(PathBuf, SystemTime)> = VecDeque::new();
+    loop {
+        let first = tokio::select! {
+            paths = rx.recv() => paths,
+            _ = stop.cancelled() => return,
+        };
+        let Some(mut paths) = first else { return };
+        // Wait until the file stops changing (copies, downloads)
+        loop {
+            tokio::select! {
+                more = rx.recv() => match more {
+                    Some(more) => paths.extend(more),
+                    None => break,
+                },
+                _ = tokio::time::sleep(SETTLE) => break,
+                _ = stop.cancelled() => return,
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        for path in paths {
+            let Some(folder) = folders.iter().find(|f| path.parent() == Some(f.path.as_path())) else {
+                continue;
+            };
+            if !is_candidate(&path) || output_path(&path).exists() {
+                continue;
+            }
+            let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+                continue;
+            };
+            let key = (path.clone(), modified);
+            if done.contains(&key) {
+                continue;
+            }
+            if stop.is_cancelled() {
+                return;
+            }
+            let result = process(folder, &path, &engine).await;
+            // A failed file (no model loaded yet) runs again on its next event
+            if result.is_ok() {
+                remember(&mut done, key);
+            }
+            report(&path, result);
+        }
+    }
+}
+
+/// Add a file to those done, forgetting the oldest beyond [`DONE_KEPT`]
+fn remember(done: &mut VecDeque<(PathBuf, SystemTime)>, key: (PathBuf, SystemTime)) {
+    done.push_back(key);
+    if done.len() > DONE_KEPT {
+        done.pop_front();
+    }
+}
+
+/// Run the folder's workflow on `path` and store the answer
+async fn process(
+    folder: &WatchFolder,
+    path: &Path,
+    engine: &SharedEngine,
+) -> Result<String, WatchError> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let read_path = path.to_path_buf();
+    let text = tokio::task::spawn_blocking(move || read_document(&read_path))
+        .await
+        .map_err(|e| WatchError::Generation(e.to_string()))??;
+
+    let settings = load_settings();
+    let persona = folder
+        .persona
+        .as_deref()
+        .and_then(|id| settings.persona(id))
+        .map(|p| p.instructions(settings.language == "en"));
+    let messages = folder.messages(persona, &name, &text);
+
+    // The lock is only held to start the generation, like the chat loop
+    let rx = {
+        let engine = engine.lock().await;
+        if !engine.is_model_loaded() {
+            return Err(WatchError::NoModel);
+        }
+        engine.generate_stream_messages(messages, settings.generation_params())?.0
+    };
+    let output = tokio::task::spawn_blocking(move || collect_text(rx))
+        .await
+        .map_err(|e| WatchError::Generation(e.to_string()))?
+        .map_err(WatchError::Generation)?;
+    let answer = strip_thinking(&output).trim().to_string();
+
+    match folder.output {
+        WatchOutput::NextToFile => {
+            let target = output_path(path);
+            std::fs::write(&target, format!("{}\n", answer))?;
+            Ok(target.display().to_string())
+        }
+        WatchOutput::Conversation => {
+            let prompt = format!("{}\n\n📎 {} ({})", folder.prompt.trim(), name, path.display());
+            let mut conversation = Conversation::new(Some(ChatMessage::new(ChatRole::User, prompt)));
+            conversation.add_message(ChatMessage::new(ChatRole::Assistant, answer));
+            conversation.title = name;
+            conversation.persona = folder.persona.clone();
+            save_conversation(&conversation).map_err(|e| WatchError::Conversation(e.to_string()))?;
+            CONVERSATIONS_ADDED.store(true, Ordering::Relaxed);
+            Ok(conversation.title)
+        }
+    }
+}
+
+fn report(path: &Path, result: Result<String, WatchError>) {
+    let is_en = load_settings().language == "en";
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let result = result.map_err(|e| e.to_string());
+    match &result {
+        Ok(output) => {
+            tracing::info!("Watched folder workflow done for {}: {}", path.display(), output);
+            let title = if is_en { "ClawRS: file processed" } else { "ClawRS : fichier traite" };
+            notify(title, &name);
+        }
+        Err(e) => {
+            tracing::warn!("Watched folder workflow failed for {}: {}", path.display(), e);
+            let title = if is_en { "ClawRS: processing failed" } else { "ClawRS : echec du traitement" };
+            notify(title, &format!("{}: {}", name, e));
+        }
+    }
+    let mut runs = RUNS.lock().unwrap_or_else(|e| e.into_inner());
+    runs.push_front(WatchRun {
+        file: path.to_path_buf(),
+        finished_at: Utc::now(),
+        result,
+    });
+    runs.truncate(RECENT_RUNS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_and_output_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("report.pdf");
+        std::fs::write(&report, "x").unwrap();
+        assert!(is_candidate(&report));
+        assert_eq!(output_path(&report), dir.path().join("report.pdf.clawrs.md"));
+
+        for name in ["report.pdf.clawrs.md", ".hidden.md", "video.mp4.crdownload", "notes.md~"] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "x").unwrap();
+            assert!(!is_candidate(&path), "{}", name);
+        }
+        assert!(!is_candidate(dir.path()));
+    }
+
+    #[test]
+    fn test_remember_is_bounded() {
+        let mut done = VecDeque::new();
+        for i in 0..DONE_KEPT + 5 {
+            let key = (PathBuf::from(format!("{i}.txt")), SystemTime::UNIX_EPOCH);
+            remember(&mut done, key);
+        }
+        assert_eq!(done.len(), DONE_KEPT);
+        assert!(!done.contains(&(PathBuf::from("0.txt"), SystemTime::UNIX_EPOCH)));
+        assert!(done.contains(&(PathBuf::from("5.txt"), SystemTime::UNIX_EPOCH)));
+    }
+
+    #[test]
+    fn test_workflow_messages() {
+        let folder = WatchFolder::new(PathBuf::from("/inbox"), "Résume ce document.".to_string());
+        let messages = folder.messages(Some("Tu es rédacteur.".to_string()), "a.md", "# Title");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, ChatRole::System);
+        assert_eq!(
+            messages[1].content,
+            "Résume ce document.\n\n<document name=\"a.md\">\n# Title\n</document>"
+        );
+        assert_eq!(folder.messages(None, "a.md", "x").len(), 1);
+
+        let saved: WatchFolder = serde_json::from_str(r#"{"path":"/inbox","prompt":"p"}"#).unwrap();
+        assert!(saved.enabled);
+        assert_eq!(saved.output, WatchOutput::NextToFile);
+    }
+}
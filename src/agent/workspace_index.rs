@@ -0,0 +1,736 @@
+//! Workspace index
+//!
+//! Background index of the workspace folder: every file with its size, the
+//! code symbols of source files (functions, types, ...) and, when an
+//! embedding model is available, one embedding per chunk of text. It is
+//! persisted to `{data_dir}/index/{hash}.json`, kept up to date from file
+//! system events, and serves `repo_map`, `workspace_search` and the
+//! workspace section of the system prompt without walking the tree on
+//! every run.
+
+use chrono::{DateTime, Utc};
+use notify::{RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::agent::tools::repo_map::{is_ignored, language, list_files};
+use crate::agent::tools::CancelToken;
+use crate::inference::Embedder;
+use crate::storage::get_data_dir;
+
+/// Lines per chunk
+const CHUNK_LINES: usize = 40;
+/// Larger files are listed but not parsed
+const MAX_INDEXED_BYTES: u64 = 512 * 1024;
+/// Texts per embedding request
+const EMBED_BATCH: usize = 16;
+/// Quiet time before applying file system events
+const DEBOUNCE: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    /// `fn`, `struct`, `class`, `def`, ...
+    pub kind: String,
+    /// 1-based
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chunk {
+    /// 1-based, inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Normalized; empty until computed
+    #[serde(default)]
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub size: u64,
+    /// Modification time, seconds since the epoch
+    pub modified: u64,
+    #[serde(default)]
+    pub symbols: Vec<Symbol>,
+    #[serde(default)]
+    pub chunks: Vec<Chunk>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceIndex {
+    pub root: PathBuf,
+    /// Model the stored embeddings come from
+    #[serde(default)]
+    pub embedding_model: Option<PathBuf>,
+    /// Keyed by path relative to the root, `/`-separated
+    #[serde(default)]
+    pub files: BTreeMap<String, IndexedFile>,
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolHit {
+    pub path: String,
+    pub symbol: Symbol,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChunkHit {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+}
+
+/// Symbol patterns by language; without a fixed kind, group 1 is the kind
+/// and group 2 the name
+static SYMBOL_PATTERNS: Lazy<Vec<(&'static str, Regex, Option<&'static str>)>> = Lazy::new(|| {
+    [
+        ("Rust", r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(fn|struct|enum|trait|mod|type|static|const)\s+([A-Za-z_][A-Za-z0-9_]*)", None),
+        ("Rust", r"^\s*impl(?:<[^>]*>)?\s+(?:[\w:]+(?:<[^>]*>)?\s+for\s+)?([A-Za-z_][\w]*)", Some("impl")),
+        ("Python", r"^\s*(?:async\s+)?(def|class)\s+([A-Za-z_]\w*)", None),
+        ("JavaScript", r"^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?(function|class)\s*\*?\s*([A-Za-z_$][\w$]*)", None),
+        ("JavaScript", r"^\s*(?:export\s+)?(?:const|let)\s+([A-Za-z_$][\w$]*)\s*=\s*(?:async\s*)?(?:\([^)]*\)|[A-Za-z_$][\w$]*)\s*=>", Some("function")),
+        ("TypeScript", r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(function|class|interface|type|enum)\s+([A-Za-z_$][\w$]*)", None),
+        ("TypeScript", r"^\s*(?:export\s+)?(?:const|let)\s+([A-Za-z_$][\w$]*)\s*(?::[^=]+)?=\s*(?:async\s*)?\([^)]*\)\s*(?::[^=]+)?=>", Some("function")),
+        ("Go", r"^func\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)", Some("func")),
+        ("Go", r"^type\s+([A-Za-z_]\w*)", Some("type")),
+        ("Java", r"^\s*(?:(?:public|private|protected|static|final|abstract|sealed)\s+)*(class|interface|enum|record)\s+(\w+)", None),
+        ("Kotlin", r"^\s*(?:(?:public|private|internal|data|sealed|abstract|open)\s+)*(class|interface|object|fun)\s+(\w+)", None),
+        ("C#", r"^\s*(?:(?:public|private|protected|internal|static|sealed|abstract|partial)\s+)*(class|interface|enum|struct|record)\s+(\w+)", None),
+        ("Ruby", r"^\s*(def|class|module)\s+([A-Za-z_][\w.?!]*)", None),
+        ("PHP", r"^\s*(?:(?:public|private|protected|static|abstract|final)\s+)*(function|class|interface|trait)\s+(\w+)", None),
+        ("C", r"^(?:struct|enum|union)\s+(\w+)\s*\{", Some("struct")),
+        ("C++", r"^\s*(?:class|struct)\s+(\w+)", Some("class")),
+        ("Markdown", r"^#{1,3}\s+(.+?)\s*$", Some("heading")),
+    ]
+    .into_iter()
+    .map(|(lang, pattern, kind)| (lang, Regex::new(pattern).unwrap(), kind))
+    .collect()
+});
+
+/// Functions, types and other definitions in `content`
+pub fn extract_symbols(lang: &str, content: &str) -> Vec<Symbol> {
+    let patterns: Vec<_> = SYMBOL_PATTERNS
+        .iter()
+        .filter(|(l, _, _)| *l == lang)
+        .collect();
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+    let mut symbols = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        for (_, re, kind) in &patterns {
+            let Some(caps) = re.captures(line) else {
+                continue;
+            };
+            let (kind, name) = match kind {
+                Some(kind) => (kind.to_string(), caps[1].to_string()),
+                None => (caps[1].to_string(), caps[2].to_string()),
+            };
+            symbols.push(Symbol {
+                name,
+                kind,
+                line: i + 1,
+            });
+            break;
+        }
+    }
+    symbols
+}
+
+/// Line ranges of the chunks of a file with `line_count` lines
+fn chunk_ranges(line_count: usize) -> Vec<(usize, usize)> {
+    (0..line_count)
+        .step_by(CHUNK_LINES)
+        .map(|start| (start + 1, (start + CHUNK_LINES).min(line_count)))
+        .collect()
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn rel_key(rel: &Path) -> String {
+    rel.to_string_lossy().replace('\\', "/")
+}
+
+/// Text of a chunk, prefixed with its path so embeddings know the file
+fn chunk_text(key: &str, content: &str, chunk: &Chunk) -> String {
+    let lines: Vec<&str> = content
+        .lines()
+        .skip(chunk.start_line - 1)
+        .take(chunk.end_line + 1 - chunk.start_line)
+        .collect();
+    format!("{}\n{}", key, lines.join("\n"))
+}
+
+impl WorkspaceIndex {
+    fn index_file(&self, rel: &Path, size: u64) -> IndexedFile {
+        let path = self.root.join(rel);
+        let mut file = IndexedFile {
+            size,
+            modified: modified_secs(&path),
+            symbols: Vec::new(),
+            chunks: Vec::new(),
+        };
+        let Some(lang) = language(rel) else {
+            return file;
+        };
+        if size > MAX_INDEXED_BYTES {
+            return file;
+        }
+        // Binary or non-UTF-8 files stay listed only
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return file;
+        };
+        file.symbols = extract_symbols(lang, &content);
+        if lang != "Config" {
+            file.chunks = chunk_ranges(content.lines().count())
+                .into_iter()
+                .map(|(start_line, end_line)| Chunk {
+                    start_line,
+                    end_line,
+                    embedding: Vec::new(),
+                })
+                .collect();
+        }
+        file
+    }
+
+    /// Re-index `rel` unless its size and modification time are unchanged
+    fn refresh_file(&mut self, rel: &Path, size: u64) -> bool {
+        let key = rel_key(rel);
+        let unchanged = self
+            .files
+            .get(&key)
+            .is_some_and(|f| f.size == size && f.modified == modified_secs(&self.root.join(rel)));
+        if unchanged {
+            return false;
+        }
+        let file = self.index_file(rel, size);
+        self.files.insert(key, file);
+        true
+    }
+
+    /// Bring the whole index up to date; returns the number of changed files
+    pub fn update_all(&mut self) -> usize {
+        let root = self.root.clone();
+        let (listed, truncated) = list_files(&root, &root);
+        self.truncated = truncated;
+        let mut changed = 0;
+        let keys: std::collections::HashSet<String> =
+            listed.iter().map(|(rel, _)| rel_key(rel)).collect();
+        let before = self.files.len();
+        self.files.retain(|key, _| keys.contains(key));
+        changed += before - self.files.len();
+        for (rel, size) in listed {
+            if self.refresh_file(&rel, size) {
+                changed += 1;
+            }
+        }
+        self.updated_at = Some(Utc::now());
+        changed
+    }
+
+    /// Apply file system changes at `paths`; returns the number of changed files
+    pub fn update_paths(&mut self, paths: &[PathBuf]) -> usize {
+        let root = self.root.clone();
+        let mut changed = 0;
+        for path in paths {
+            let Ok(rel) = path.strip_prefix(&root) else {
+                continue;
+            };
+            if rel.as_os_str().is_empty() || is_ignored(rel) {
+                continue;
+            }
+            let key = rel_key(rel);
+            let prefix = format!("{}/", key);
+            if path.is_file() {
+                let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                if self.refresh_file(rel, size) {
+                    changed += 1;
+                }
+            } else if path.is_dir() {
+                let (listed, _) = list_files(&root, path);
+                let keys: std::collections::HashSet<String> =
+                    listed.iter().map(|(rel, _)| rel_key(rel)).collect();
+                let before = self.files.len();
+                self.files
+                    .retain(|k, _| !k.starts_with(&prefix) || keys.contains(k));
+                changed += before - self.files.len();
+                for (rel, size) in listed {
+                    if self.refresh_file(&rel, size) {
+                        changed += 1;
+                    }
+                }
+            } else {
+                // Removed or renamed away: drop the file or the whole directory
+                let before = self.files.len();
+                self.files
+                    .retain(|k, _| *k != key && !k.starts_with(&prefix));
+                changed += before - self.files.len();
+            }
+        }
+        if changed > 0 {
+            self.updated_at = Some(Utc::now());
+        }
+        changed
+    }
+
+    /// Files and sizes, for `RepoMap::from_files`
+    pub fn file_list(&self) -> Vec<(PathBuf, u64)> {
+        self.files
+            .iter()
+            .map(|(key, file)| (PathBuf::from(key), file.size))
+            .collect()
+    }
+
+    /// Symbols whose name matches `query`, best matches first
+    pub fn search_symbols(&self, query: &str, limit: usize) -> Vec<SymbolHit> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut hits: Vec<(u8, SymbolHit)> = Vec::new();
+        for (path, file) in &self.files {
+            for symbol in &file.symbols {
+                let name = symbol.name.to_lowercase();
+                let score = if name == query {
+                    3
+                } else if name.starts_with(&query) {
+                    2
+                } else if name.contains(&query) {
+                    1
+                } else {
+                    continue;
+                };
+                hits.push((
+                    score,
+                    SymbolHit {
+                        path: path.clone(),
+                        symbol: symbol.clone(),
+                    },
+                ));
+            }
+        }
+        hits.sort_by_key(|(score, hit)| {
+            (std::cmp::Reverse(*score), hit.path.clone(), hit.symbol.line)
+        });
+        hits.into_iter().take(limit).map(|(_, hit)| hit).collect()
+    }
+
+    /// Chunks closest to a normalized query embedding
+    pub fn search_chunks(&self, query: &[f32], limit: usize) -> Vec<ChunkHit> {
+        let mut hits: Vec<ChunkHit> = self
+            .files
+            .iter()
+            .flat_map(|(path, file)| {
+                file.chunks
+                    .iter()
+                    .filter(|c| c.embedding.len() == query.len())
+                    .map(move |c| ChunkHit {
+                        path: path.clone(),
+                        start_line: c.start_line,
+                        end_line: c.end_line,
+                        score: c.embedding.iter().zip(query).map(|(a, b)| a * b).sum(),
+                    })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        hits
+    }
+
+    /// (files, symbols, chunks, embedded chunks)
+    pub fn counts(&self) -> (usize, usize, usize, usize) {
+        let symbols = self.files.values().map(|f| f.symbols.len()).sum();
+        let chunks = self.files.values().map(|f| f.chunks.len()).sum();
+        let embedded = self
+            .files
+            .values()
+            .flat_map(|f| &f.chunks)
+            .filter(|c| !c.embedding.is_empty())
+            .count();
+        (self.files.len(), symbols, chunks, embedded)
+    }
+}
+
+// ============================================================================
+// Persistence
+// ============================================================================
+
+fn index_path(root: &Path) -> Option<PathBuf> {
+    let hash = format!("{:x}", Sha256::digest(root.to_string_lossy().as_bytes()));
+    Some(
+        get_data_dir()
+            .ok()?
+            .join("index")
+            .join(format!("{}.json", &hash[..16])),
+    )
+}
+
+fn load_index(root: &Path) -> WorkspaceIndex {
+    let loaded = index_path(root)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<WorkspaceIndex>(&json).ok())
+        .filter(|index| index.root == root);
+    loaded.unwrap_or_else(|| WorkspaceIndex {
+        root: root.to_path_buf(),
+        ..Default::default()
+    })
+}
+
+fn save_index(index: &WorkspaceIndex) {
+    let Some(path) = index_path(&index.root) else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| {
+            serde_json::to_string(index)
+                .map_err(std::io::Error::other)
+                .and_then(|json| std::fs::write(&path, json))
+        });
+    if let Err(e) = result {
+        tracing::warn!("Failed to save workspace index: {}", e);
+    }
+}
+
+// ============================================================================
+// Background indexer
+// ============================================================================
+
+struct Running {
+    root: PathBuf,
+    index: Arc<RwLock<WorkspaceIndex>>,
+    embedding: Option<(Embedder, PathBuf)>,
+    busy: Arc<AtomicBool>,
+    stop: CancelToken,
+}
+
+static CURRENT: Lazy<Mutex<Option<Running>>> = Lazy::new(|| Mutex::new(None));
+
+/// Index summary for the settings card
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexStatus {
+    pub root: PathBuf,
+    pub files: usize,
+    pub symbols: usize,
+    pub chunks: usize,
+    pub embedded: usize,
+    pub has_embedding_model: bool,
+    pub busy: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+pub fn status() -> Option<IndexStatus> {
+    let current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    let running = current.as_ref()?;
+    let index = running.index.read().unwrap_or_else(|e| e.into_inner());
+    let (files, symbols, chunks, embedded) = index.counts();
+    Some(IndexStatus {
+        root: running.root.clone(),
+        files,
+        symbols,
+        chunks,
+        embedded,
+        has_embedding_model: running.embedding.is_some(),
+        busy: running.busy.load(Ordering::Relaxed),
+        updated_at: index.updated_at,
+    })
+}
+
+/// Index of `root` if it is the indexed workspace
+pub fn index_for(root: &Path) -> Option<Arc<RwLock<WorkspaceIndex>>> {
+    let current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    let running = current.as_ref()?;
+    let same = running.root == root
+        || root
+            .canonicalize()
+            .is_ok_and(|r| running.root.canonicalize().is_ok_and(|w| w == r));
+    same.then(|| running.index.clone())
+}
+
+/// The indexed workspace, if any
+pub fn current_index() -> Option<Arc<RwLock<WorkspaceIndex>>> {
+    let current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    current.as_ref().map(|r| r.index.clone())
+}
+
+/// Embed `query` with the workspace's embedding model
+pub async fn embed_query(query: &str) -> Option<Vec<f32>> {
+    let (embedder, model) = {
+        let current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+        current.as_ref()?.embedding.clone()?
+    };
+    let query = query.to_string();
+    tokio::task::spawn_blocking(move || embedder.embed(&model, vec![query]))
+        .await
+        .ok()?
+        .map_err(|e| tracing::warn!("Failed to embed query: {}", e))
+        .ok()?
+        .into_iter()
+        .next()
+}
+
+/// Stop indexing the current workspace
+pub fn stop() {
+    if let Some(running) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        running.stop.cancel();
+        tracing::info!("Workspace indexer stopped for {}", running.root.display());
+    }
+}
+
+/// Index `root` in the background and follow its changes; replaces any
+/// previous workspace
+pub fn start(root: PathBuf, embedding: Option<(Embedder, PathBuf)>) {
+    stop();
+    let index = Arc::new(RwLock::new(WorkspaceIndex {
+        root: root.clone(),
+        ..Default::default()
+    }));
+    let busy = Arc::new(AtomicBool::new(true));
+    let stop = CancelToken::new();
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Running {
+        root: root.clone(),
+        index: index.clone(),
+        embedding: embedding.clone(),
+        busy: busy.clone(),
+        stop: stop.clone(),
+    });
+    tokio::spawn(run(root, index, embedding, busy, stop));
+}
+
+/// Compute missing embeddings file by file, without holding the index lock
+/// while the model runs
+fn embed_pending(
+    index: &RwLock<WorkspaceIndex>,
+    embedder: &Embedder,
+    model: &Path,
+    stop: &CancelToken,
+) {
+    {
+        let mut index = index.write().unwrap_or_else(|e| e.into_inner());
+        if index.embedding_model.as_deref() != Some(model) {
+            for chunk in index.files.values_mut().flat_map(|f| f.chunks.iter_mut()) {
+                chunk.embedding.clear();
+            }
+            index.embedding_model = Some(model.to_path_buf());
+        }
+    }
+    let pending: Vec<(String, u64)> = {
+        let index = index.read().unwrap_or_else(|e| e.into_inner());
+        index
+            .files
+            .iter()
+            .filter(|(_, f)| f.chunks.iter().any(|c| c.embedding.is_empty()))
+            .map(|(key, f)| (key.clone(), f.modified))
+            .collect()
+    };
+    let root = index.read().unwrap_or_else(|e| e.into_inner()).root.clone();
+    for (key, modified) in pending {
+        if stop.is_cancelled() {
+            return;
+        }
+        let Ok(content) = std::fs::read_to_string(root.join(&key)) else {
+            continue;
+        };
+        let chunks = match index
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .files
+            .get(&key)
+        {
+            Some(file) => file.chunks.clone(),
+            None => continue,
+        };
+        let mut embeddings = Vec::with_capacity(chunks.len());
+        for batch in chunks.chunks(EMBED_BATCH) {
+            let texts = batch
+                .iter()
+                .map(|c| chunk_text(&key, &content, c))
+                .collect();
+            match embedder.embed(model, texts) {
+                Ok(vectors) => embeddings.extend(vectors),
+                Err(e) => {
+                    tracing::warn!("Embedding failed, keeping the symbol index only: {}", e);
+                    return;
+                }
+            }
+        }
+        let mut index = index.write().unwrap_or_else(|e| e.into_inner());
+        // Skip if the file changed meanwhile; the next pass picks it up
+        if let Some(file) = index.files.get_mut(&key).filter(|f| f.modified == modified) {
+            for (chunk, embedding) in file.chunks.iter_mut().zip(embeddings) {
+                chunk.embedding = embedding;
+            }
+        }
+    }
+}
+
+/// Update the index, embed what changed and save it
+async fn refresh(
+    index: &Arc<RwLock<WorkspaceIndex>>,
+    embedding: &Option<(Embedder, PathBuf)>,
+    busy: &AtomicBool,
+    stop: &CancelToken,
+    paths: Option<Vec<PathBuf>>,
+) {
+    busy.store(true, Ordering::Relaxed);
+    let (index, embedding, stop) = (index.clone(), embedding.clone(), stop.clone());
+    let _ = tokio::task::spawn_blocking(move || {
+        let changed = {
+            let mut index = index.write().unwrap_or_else(|e| e.into_inner());
+            match &paths {
+                Some(paths) => index.update_paths(paths),
+                None => index.update_all(),
+            }
+        };
+        if let Some((embedder, model)) = &embedding {
+            embed_pending(&index, embedder, model, &stop);
+        }
+        if changed > 0 || paths.is_none() {
+            tracing::debug!("Workspace index: {} file(s) changed", changed);
+            save_index(&index.read().unwrap_or_else(|e| e.into_inner()));
+        }
+    })
+    .await;
+    busy.store(false, Ordering::Relaxed);
+}
+
+async fn run(
+    root: PathBuf,
+    index: Arc<RwLock<WorkspaceIndex>>,
+    embedding: Option<(Embedder, PathBuf)>,
+    busy: Arc<AtomicBool>,
+    stop: CancelToken,
+) {
+    // Start from the saved index so only what changed since is re-read
+    let load_root = root.clone();
+    if let Ok(saved) = tokio::task::spawn_blocking(move || load_index(&load_root)).await {
+        *index.write().unwrap_or_else(|e| e.into_inner()) = saved;
+    }
+    refresh(&index, &embedding, &busy, &stop, None).await;
+    let (files, symbols, _, embedded) = index.read().unwrap_or_else(|e| e.into_inner()).counts();
+    tracing::info!(
+        "Workspace indexed: {} files, {} symbols, {} embedded chunks",
+        files,
+        symbols,
+        embedded
+    );
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            let _ = tx.send(event.paths);
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            return tracing::warn!("Cannot watch workspace, index won't follow changes: {}", e)
+        }
+    };
+    if let Err(e) = watcher.watch(&root, RecursiveMode::Recursive) {
+        return tracing::warn!("Cannot watch workspace, index won't follow changes: {}", e);
+    }
+
+    loop {
+        let first = tokio::select! {
+            paths = rx.recv() => paths,
+            _ = stop.cancelled() => return,
+        };
+        let Some(mut paths) = first else { return };
+        // Let bursts (checkouts, builds) settle, then apply them at once
+        loop {
+            tokio::select! {
+                more = rx.recv() => match more {
+                    Some(more) => paths.extend(more),
+                    None => break,
+                },
+                _ = tokio::time::sleep(DEBOUNCE) => break,
+                _ = stop.cancelled() => return,
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        paths.retain(|p| p.strip_prefix(&root).is_ok_and(|rel| !is_ignored(rel)));
+        if !paths.is_empty() {
+            refresh(&index, &embedding, &busy, &stop, Some(paths)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_symbols() {
+        let rust = "pub struct Index {\n}\n\nimpl Default for Index {\n    pub async fn load(&self) {}\n}\npub(crate) enum Kind {}\n";
+        let symbols = extract_symbols("Rust", rust);
+        let names: Vec<(&str, &str, usize)> = symbols
+            .iter()
+            .map(|s| (s.kind.as_str(), s.name.as_str(), s.line))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("struct", "Index", 1),
+                ("impl", "Index", 4),
+                ("fn", "load", 5),
+                ("enum", "Kind", 7)
+            ]
+        );
+
+        let python = "class Parser:\n    def parse(self):\n        pass\n";
+        assert_eq!(extract_symbols("Python", python).len(), 2);
+        assert!(extract_symbols("Config", "a = 1").is_empty());
+    }
+
+    #[test]
+    fn test_incremental_update_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::create_dir_all(root.join("src/net")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("src/net/client.rs"), "pub struct HttpClient;\n").unwrap();
+
+        let mut index = WorkspaceIndex {
+            root: root.clone(),
+            ..Default::default()
+        };
+        assert_eq!(index.update_all(), 2);
+        assert_eq!(index.update_all(), 0);
+        let hits = index.search_symbols("client", 5);
+        assert_eq!(hits[0].path, "src/net/client.rs");
+        assert_eq!(hits[0].symbol.name, "HttpClient");
+
+        std::fs::write(root.join("src/lib.rs"), "pub fn run() {}\n").unwrap();
+        assert_eq!(index.update_paths(&[root.join("src/lib.rs")]), 1);
+        assert_eq!(index.search_symbols("run", 5).len(), 1);
+
+        std::fs::remove_dir_all(root.join("src/net")).unwrap();
+        assert_eq!(index.update_paths(&[root.join("src/net")]), 1);
+        assert!(index.search_symbols("client", 5).is_empty());
+        assert_eq!(index.file_list().len(), 2);
+    }
+}
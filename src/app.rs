@@ -2,17 +2,45 @@
 //!
 //! This module contains the main App component that serves as the root of the UI tree.
 
-use crate::inference::LlamaEngine;
-use crate::storage::conversations::Conversation;
-use crate::storage::settings::{AppSettings, load_settings};
+use crate::inference::model::read_block_count;
+use crate::inference::{InferenceBackend, SharedEngine};
+use crate::storage::conversations::{list_conversations, load_conversation, save_conversation, set_ephemeral, Conversation};
+use crate::storage::models::{scan_models_for_role, ModelRole};
+use crate::storage::settings::{AppSettings, load_settings, save_settings};
+use crate::system::certificates::set_custom_certificates;
+use crate::system::crash::{self, CrashSnapshot};
+use crate::system::gpu::{auto_gpu_layers, detect_gpu, ALL_GPU_LAYERS};
+use crate::system::notifications;
+use crate::system::proxy::set_proxy_settings;
+use crate::system::quick_actions;
+use crate::system::single_instance;
+use crate::types::message::unix_now;
+use crate::system::updates::{self, ReleaseInfo};
 use crate::ui::Layout;
+use crate::ui::playground::PlaygroundAttempt;
+use crate::agent::escalation::EscalationPreview;
+use crate::agent::focus::{is_focus_active, set_focus_until};
+use crate::agent::skills::runtime::set_skill_workspace;
+use crate::agent::run_progress::RunProgress;
+use crate::agent::permissions::set_window_focused;
+use crate::agent::tools::shell::kill_background_processes;
+use crate::agent::tools::CancelToken;
+use crate::agent::digest;
+use crate::agent::watch_folders;
+use crate::agent::workspace_index;
 use crate::agent::{Agent, AgentConfig};
+use dioxus::desktop::tao::event::Event;
+use dioxus::desktop::{use_window, use_wry_event_handler, WindowEvent};
 use dioxus::prelude::*;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use crate::ui::chat::message::Message;
 
+/// How long quitting waits for the engine to stop a generation
+const SHUTDOWN_WAIT: Duration = Duration::from_secs(3);
+
 /// Represents the current state of the model
 #[derive(Clone, PartialEq, Debug)]
 pub enum ModelState {
@@ -26,7 +54,7 @@ pub enum ModelState {
 #[derive(Clone)]
 pub struct AppState {
     pub agent: Arc<Agent>,
-    pub engine: Arc<Mutex<LlamaEngine>>,
+    pub engine: SharedEngine,
     pub current_conversation: Signal<Option<Conversation>>,
     pub conversations: Signal<Vec<Conversation>>,
     pub settings: Signal<AppSettings>,
@@ -36,18 +64,60 @@ pub struct AppState {
     pub is_generating: Signal<bool>,
     /// Active messages buffer - persists across navigation
     pub active_messages: Signal<Vec<Message>>,
+    /// Tool call (message index) highlighted after clicking a citation
+    pub focused_tool_call: Signal<Option<usize>>,
+    /// Message whose reasoning is held open by the in-chat search
+    pub search_focus: Signal<Option<usize>>,
+    /// Cancels the tool currently executing, if any
+    pub active_tool_cancel: Signal<Option<CancelToken>>,
+    /// Outgoing data shown in the consent dialog for a pending ai_consult call
+    pub escalation_preview: Signal<Option<EscalationPreview>>,
+    /// Bumped whenever a tool call is added to the audit log
+    pub audit_revision: Signal<u64>,
+    /// max_tokens override for the next reply only (`/long` or the input bar)
+    pub next_max_tokens: Signal<Option<u32>>,
+    /// Prompt handed from the welcome screen to the chat input (empty only focuses it)
+    pub pending_prompt: Signal<Option<String>>,
+    /// Text from outside the app (a `clawrs://` link, another launch) put in
+    /// the chat input for the user to review; never sent on its own
+    pub pending_draft: Signal<Option<String>>,
+    /// Timer, iteration and tool of the running agent, for the generating indicator
+    pub run_progress: Signal<Option<RunProgress>>,
+    /// Prompt tokens evaluated and total while the reply has no token yet
+    pub prefill_progress: Signal<Option<(u32, u32)>>,
+    /// Set by "wrap up now": the agent stops calling tools and answers
+    pub wrap_up: Arc<AtomicBool>,
+    /// When the open conversation was last written to disk
+    pub last_saved: Signal<Option<Instant>>,
+    /// Set from the routing chip: the next message ignores the routing rules
+    pub skip_route: Signal<bool>,
+    /// Set once the shutdown sequence has run
+    pub shutting_down: Arc<AtomicBool>,
+    /// Newer release found by the update check, shown in a banner
+    pub available_update: Signal<Option<ReleaseInfo>>,
+    /// Playground runs, in memory only
+    pub playground_attempts: Signal<Vec<PlaygroundAttempt>>,
+    /// Stops the running playground attempt
+    pub playground_stop: Arc<AtomicBool>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         tracing::info!("AppState initialized");
         let settings = load_settings();
+        set_ephemeral(settings.lock.enabled);
+        set_proxy_settings(&settings.proxy);
+        set_focus_until(settings.focus_until);
+        set_skill_workspace(settings.workspace.clone());
+        set_custom_certificates(&settings.ca_certificates);
         let mut agent_config = AgentConfig::default();
         agent_config.disabled_mcp_servers = settings.disabled_mcp_servers.clone();
+        agent_config.loop_config.reflection = settings.reflection_policy.clone();
+        let engine = settings.inference_backend();
         
         Self {
             agent: Arc::new(Agent::new(agent_config)),
-            engine: Arc::new(Mutex::new(LlamaEngine::new())),
+            engine: Arc::new(Mutex::new(engine)),
             current_conversation: Signal::new(None),
             conversations: Signal::new(Vec::new()),
             settings: Signal::new(settings),
@@ -55,8 +125,372 @@ impl AppState {
             stop_signal: Arc::new(AtomicBool::new(false)),
             is_generating: Signal::new(false),
             active_messages: Signal::new(Vec::new()),
+            focused_tool_call: Signal::new(None),
+            search_focus: Signal::new(None),
+            active_tool_cancel: Signal::new(None),
+            escalation_preview: Signal::new(None),
+            audit_revision: Signal::new(0),
+            next_max_tokens: Signal::new(None),
+            pending_prompt: Signal::new(None),
+            pending_draft: Signal::new(None),
+            run_progress: Signal::new(None),
+            prefill_progress: Signal::new(None),
+            wrap_up: Arc::new(AtomicBool::new(false)),
+            last_saved: Signal::new(None),
+            skip_route: Signal::new(false),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            available_update: Signal::new(None),
+            playground_attempts: Signal::new(Vec::new()),
+            playground_stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Quit cleanly: stop the run, free the model, stop background commands
+    /// and save the open conversation. Runs once; later calls do nothing.
+    pub fn shutdown(&self) {
+        if self.shutting_down.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tracing::info!("Shutting down");
+        self.stop_signal.store(true, Ordering::Relaxed);
+        if let Some(cancel) = self.active_tool_cancel.peek().as_ref() {
+            cancel.cancel();
+        }
+        self.agent.permission_manager.deny_all_pending();
+
+        // The chat loop only holds the engine briefly, to start a generation
+        let deadline = Instant::now() + SHUTDOWN_WAIT;
+        loop {
+            if let Ok(mut engine) = self.engine.try_lock() {
+                engine.shutdown(SHUTDOWN_WAIT);
+                break;
+            }
+            if Instant::now() >= deadline {
+                tracing::warn!("Engine busy, exiting without waiting for it");
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let killed = kill_background_processes();
+        if killed > 0 {
+            tracing::info!("Stopped {} background command(s)", killed);
+        }
+        self.save_active_conversation();
+        single_instance::release();
+    }
+
+    /// Open a new, empty conversation; `false` if it couldn't be saved
+    pub fn start_conversation(&self) -> bool {
+        let conversation = Conversation::new(None);
+        if let Err(e) = save_conversation(&conversation) {
+            tracing::error!("Failed to save conversation: {}", e);
+            return false;
+        }
+        let (mut current_conversation, mut conversations) =
+            (self.current_conversation, self.conversations);
+        current_conversation.set(Some(conversation));
+        if let Ok(list) = list_conversations() {
+            conversations.set(list);
+        }
+        true
+    }
+
+    /// Open a new conversation whose chat input sends `prompt`, as from a
+    /// welcome-screen suggestion
+    pub fn start_conversation_with(&self, prompt: String) {
+        if self.start_conversation() {
+            // Picked up by the chat input once ChatView mounts
+            let mut pending_prompt = self.pending_prompt;
+            pending_prompt.set(Some(prompt));
+        }
+    }
+
+    /// Open a new conversation with `text` waiting in the chat input, as
+    /// from a link or a forwarded launch: the user decides to send it
+    pub fn start_conversation_with_draft(&self, text: String) {
+        if self.start_conversation() {
+            let mut pending_draft = self.pending_draft;
+            pending_draft.set(Some(text));
+        }
+    }
+
+    /// Open a saved conversation by id, as from a `clawrs://` link
+    pub fn open_conversation(&self, id: &str) {
+        match load_conversation(id) {
+            Ok(conversation) => {
+                let mut current_conversation = self.current_conversation;
+                current_conversation.set(Some(conversation));
+            }
+            Err(e) => tracing::warn!("Could not open conversation {}: {}", id, e),
+        }
+    }
+
+    /// Save new tags on a conversation, keeping the open copy in sync so
+    /// the next chat save doesn't put the old ones back
+    pub fn set_conversation_tags(&self, id: &str, tags: Vec<String>) {
+        let Some(mut conversation) = self
+            .conversations
+            .peek()
+            .iter()
+            .find(|c| c.id == id)
+            .cloned()
+        else {
+            return;
+        };
+        if conversation.tags == tags {
+            return;
+        }
+        conversation.tags = tags.clone();
+        if let Err(e) = save_conversation(&conversation) {
+            tracing::error!("Failed to save conversation tags: {}", e);
+            return;
         }
+        let (mut current_conversation, mut conversations) =
+            (self.current_conversation, self.conversations);
+        if let Some(current) = current_conversation.write().as_mut() {
+            if current.id == id {
+                current.tags = tags;
+            }
+        }
+        if let Ok(list) = list_conversations() {
+            conversations.set(list);
+        }
+    }
+
+    /// Turn focus mode on for `minutes`, or off with `None`
+    pub fn set_focus(&self, minutes: Option<u64>) {
+        let until = minutes.map(|m| unix_now() + m * 60);
+        set_focus_until(until);
+        let mut settings = self.settings;
+        let mut settings = settings.write();
+        settings.focus_until = until;
+        if let Err(e) = save_settings(&settings) {
+            tracing::error!("Failed to save settings: {}", e);
+        }
+    }
+
+    /// Copy the active messages into the open conversation and save it
+    pub fn save_active_conversation(&self) {
+        let mut current_conversation = self.current_conversation;
+        let mut conversation = current_conversation.write();
+        let Some(conversation) = conversation.as_mut() else {
+            return;
+        };
+        let messages: Vec<_> = self
+            .active_messages
+            .peek()
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect();
+        if conversation.messages == messages {
+            return;
+        }
+        conversation.messages = messages;
+        match save_conversation(conversation) {
+            Ok(()) => {
+                let mut last_saved = self.last_saved;
+                last_saved.set(Some(Instant::now()));
+            }
+            Err(e) => tracing::error!("Failed to save conversation: {}", e),
+        }
+    }
+
+    /// Copy the open conversation, with the reply in progress, for the
+    /// crash handler
+    pub fn snapshot_session(&self) {
+        let Some(mut conversation) = self.current_conversation.peek().clone() else {
+            return;
+        };
+        conversation.messages = self
+            .active_messages
+            .peek()
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect();
+        crash::record_session(conversation);
+    }
+
+    /// Reopen the conversation saved when the previous launch crashed
+    pub fn restore_session(&self, snapshot: CrashSnapshot) {
+        if let Some(conversation) = snapshot.conversation {
+            if let Err(e) = save_conversation(&conversation) {
+                tracing::error!("Failed to save restored conversation: {}", e);
+                return;
+            }
+            tracing::info!("Restored conversation {} after a crash", conversation.id);
+            let (mut current_conversation, mut conversations) =
+                (self.current_conversation, self.conversations);
+            current_conversation.set(Some(conversation));
+            if let Ok(list) = list_conversations() {
+                conversations.set(list);
+            }
+        }
+        if let Err(e) = crash::discard_crash_snapshot() {
+            tracing::warn!("Failed to remove crash snapshot: {}", e);
+        }
+    }
+
+    /// Look for a newer release; `Ok(false)` when up to date or skipped
+    pub async fn check_for_update(&self) -> Result<bool, updates::UpdateError> {
+        let release = updates::check_for_update().await?;
+        let skipped = self.settings.peek().skipped_update.clone();
+        let release = release.filter(|r| skipped.as_deref() != Some(r.version.as_str()));
+        let found = release.is_some();
+        let mut available_update = self.available_update;
+        available_update.set(release);
+        Ok(found)
+    }
+
+    /// Quit and hand the verified installer at `path` over to the OS
+    pub fn install_update(&self, path: &std::path::Path) -> Result<(), updates::UpdateError> {
+        updates::prepare_installer(path)?;
+        // Save and release the single-instance port first: an AppImage
+        // started too early would hand itself over to this window
+        self.shutdown();
+        if let Err(e) = updates::launch_installer(path) {
+            tracing::error!("Failed to start installer {}: {}", path.display(), e);
+        }
+        std::process::exit(0);
     }
+
+    /// Index the workspace folder in the background, with embeddings when an
+    /// embedding model is installed; stops indexing when none is set
+    pub fn index_workspace(&self) {
+        let (workspace, embedding_model) = {
+            let settings = self.settings.peek();
+            let model = scan_models_for_role(&settings, ModelRole::Embedding)
+                .into_iter()
+                .next()
+                .map(|m| m.path);
+            (settings.workspace.clone().filter(|p| p.is_dir()), model)
+        };
+        let Some(workspace) = workspace else {
+            return workspace_index::stop();
+        };
+        let engine = self.engine.clone();
+        spawn(async move {
+            let embedding = match embedding_model {
+                Some(model) => {
+                    let mut engine = engine.lock().await;
+                    if !engine.is_initialized() {
+                        if let Err(e) = engine.init() {
+                            tracing::warn!("Workspace index without embeddings: {}", e);
+                        }
+                    }
+                    engine.embedder().map(|embedder| (embedder, model))
+                }
+                None => None,
+            };
+            workspace_index::start(workspace, embedding);
+        });
+    }
+
+    /// Watch the folders from settings and run their workflows on new files;
+    /// stops watching while locked, since workflows write files
+    pub fn watch_folders(&self) {
+        let settings = self.settings.peek();
+        if settings.lock.enabled {
+            watch_folders::stop();
+            return;
+        }
+        watch_folders::start(settings.watch_folders.clone(), self.engine.clone());
+    }
+
+    /// Load a model in the background and remember it for the next launch
+    ///
+    /// In auto GPU layers mode `gpu_layers` is ignored and picked from free VRAM.
+    pub fn load_model(&self, path: String, gpu_layers: u32) {
+        let app_state = self.clone();
+        spawn(async move {
+            let _ = app_state.switch_model(path, gpu_layers).await;
+        });
+    }
+
+    /// Load a model and wait for it; the error is also left in `model_state`
+    ///
+    /// With the remote backend `path` is a model id on the server.
+    pub async fn switch_model(&self, path: String, gpu_layers: u32) -> Result<(), String> {
+        let mut app_state = self.clone();
+        app_state.model_state.set(ModelState::Loading);
+        let (auto_layers, remote) = {
+            let settings = self.settings.peek();
+            (settings.gpu_layers_auto, settings.uses_remote_backend())
+        };
+        let mut gpu_layers = gpu_layers;
+        let result = {
+            let mut engine = app_state.engine.lock().await;
+            if !engine.is_initialized() {
+                if let Err(e) = engine.init() {
+                    app_state.model_state.set(ModelState::Error(e.to_string()));
+                    return Err(e.to_string());
+                }
+            }
+            if auto_layers && !remote {
+                // The model being replaced gives its VRAM back
+                let replaced_bytes = engine.model_info().map(|info| info.size_bytes).unwrap_or(0);
+                let model_path = path.clone();
+                gpu_layers = tokio::task::spawn_blocking(move || pick_gpu_layers(&model_path, replaced_bytes))
+                    .await
+                    .unwrap_or(ALL_GPU_LAYERS);
+                tracing::info!("Auto GPU layers: {}", gpu_layers);
+            }
+            engine.load_model_async(&path, gpu_layers).await
+        };
+        match result {
+            Ok(_) => {
+                {
+                    let mut settings = app_state.settings.write();
+                    if remote {
+                        settings.last_remote_model = Some(path.clone());
+                    } else {
+                        settings.last_model_path = Some(path.clone());
+                        settings.last_gpu_layers = Some(gpu_layers);
+                        settings.model_profile_mut(&path).last_used = Some(chrono::Utc::now());
+                    }
+                    if let Err(e) = save_settings(&settings) {
+                        tracing::error!("Failed to save settings: {}", e);
+                    }
+                }
+                app_state.model_state.set(ModelState::Loaded(path));
+                Ok(())
+            }
+            Err(e) => {
+                app_state.model_state.set(ModelState::Error(e.to_string()));
+                Err(e.to_string())
+            }
+        }
+    }
+
+    /// Replace the engine with one for the backend now in settings; the
+    /// previous one stops and frees its model
+    pub fn switch_backend(&self) {
+        let mut app_state = self.clone();
+        spawn(async move {
+            let backend = app_state.settings.peek().inference_backend();
+            let mut engine = app_state.engine.lock().await;
+            engine.shutdown(SHUTDOWN_WAIT);
+            *engine = backend;
+            drop(engine);
+            app_state.model_state.set(ModelState::NotLoaded);
+            tracing::info!("Inference backend: {}", app_state.settings.peek().backend);
+        });
+    }
+}
+
+/// GPU layers that fit the model at `path` in the VRAM free once the
+/// current model (`replaced_bytes`) is unloaded
+pub fn pick_gpu_layers(path: &str, replaced_bytes: u64) -> u32 {
+    let model_bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let gpu = detect_gpu();
+    let free_mb = gpu.free_vram_mb().map(|free| {
+        // Without a usage reading the free figure is already the total
+        let replaced_mb = if gpu.vram_usage_available { replaced_bytes / 1024 / 1024 } else { 0 };
+        (free + replaced_mb).min(gpu.vram_total_mb)
+    });
+    auto_gpu_layers(model_bytes, read_block_count(path), free_mb)
 }
 
 #[component]
@@ -76,6 +510,171 @@ pub fn App() -> Element {
         });
     }
 
+    // Index the workspace
+    {
+        let app_state = use_context::<AppState>();
+        use_hook(move || app_state.index_workspace());
+    }
+
+    // Watched folders, and the conversations their workflows save
+    {
+        let app_state = use_context::<AppState>();
+        use_hook(|| app_state.watch_folders());
+        let mut conversations = app_state.conversations;
+        use_future(move || async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                // Both flags are taken: `||` would leave the digest's set
+                let added = watch_folders::take_conversations_added() | digest::take_conversations_added();
+                if added {
+                    if let Ok(list) = list_conversations() {
+                        conversations.set(list);
+                    }
+                }
+            }
+        });
+    }
+
+    // Daily feed digest, checked every minute; skipped while locked
+    {
+        let app_state = use_context::<AppState>();
+        let engine = app_state.engine.clone();
+        let settings = app_state.settings;
+        use_future(move || {
+            let engine = engine.clone();
+            async move {
+                loop {
+                    let (locked, settings) = {
+                        let settings = settings.peek();
+                        (settings.lock.enabled, settings.digest.clone())
+                    };
+                    if !locked {
+                        digest::tick(engine.clone(), settings).await;
+                    }
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                }
+            }
+        });
+    }
+
+    // Notifications held back by focus mode, sent once it ends
+    use_future(|| async {
+        loop {
+            tokio::time::sleep(Duration::from_secs(20)).await;
+            if !is_focus_active() {
+                let _ = tokio::task::spawn_blocking(notifications::flush_held).await;
+            }
+        }
+    });
+
+    // Look for a new release when enabled
+    {
+        let app_state = use_context::<AppState>();
+        use_hook(move || {
+            if !app_state.settings.peek().check_for_updates {
+                return;
+            }
+            spawn(async move {
+                if let Err(e) = app_state.check_for_update().await {
+                    tracing::warn!("Update check failed: {}", e);
+                }
+            });
+        });
+    }
+
+    // Restore the last model when enabled
+    {
+        let app_state = use_context::<AppState>();
+        use_hook(move || {
+            let (enabled, path, gpu_layers, remote) = {
+                let settings = app_state.settings.peek();
+                let gpu_layers = settings.last_gpu_layers.unwrap_or(settings.gpu_layers);
+                let remote = settings.uses_remote_backend();
+                let path = if remote { settings.last_remote_model.clone() } else { settings.last_model_path.clone() };
+                (settings.auto_load_model, path, gpu_layers, remote)
+            };
+            match path {
+                Some(model) if enabled && remote => {
+                    tracing::info!("Selecting last remote model: {}", model);
+                    app_state.load_model(model, gpu_layers);
+                }
+                Some(path) if enabled && std::path::Path::new(&path).is_file() => {
+                    tracing::info!("Reloading last model: {}", path);
+                    app_state.load_model(path, gpu_layers);
+                }
+                Some(path) if enabled => tracing::warn!("Last model no longer exists: {}", path),
+                _ => {}
+            }
+        });
+    }
+
+    // Launch requests: this launch's arguments, then later launches handed
+    // over to this window, which comes to the front
+    {
+        let app_state = use_context::<AppState>();
+        let engine = app_state.engine.clone();
+        use_hook(move || quick_actions::attach_engine(engine));
+        let window = use_window();
+        use_future(move || {
+            let app_state = app_state.clone();
+            let window = window.clone();
+            async move {
+                loop {
+                    for request in single_instance::take_requests() {
+                        window.set_visible(true);
+                        window.set_minimized(false);
+                        window.set_focus();
+                        let (is_en, gpu_layers) = {
+                            let settings = app_state.settings.peek();
+                            (settings.language == "en", settings.gpu_layers)
+                        };
+                        // A double-clicked model replaces the loaded one
+                        if let Some(model) = request.models().last() {
+                            app_state.load_model(model.display().to_string(), gpu_layers);
+                        }
+                        if let Some(id) = &request.conversation {
+                            app_state.open_conversation(id);
+                        }
+                        // Links can come from any web page: never send them
+                        if let Some(prompt) = request.to_prompt(is_en) {
+                            app_state.start_conversation_with_draft(prompt);
+                        } else if request.new_conversation {
+                            app_state.start_conversation();
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_millis(250)).await;
+                }
+            }
+        });
+    }
+
+    // Stop workers and save the open conversation on exit, a reply still
+    // streaming included
+    {
+        let app_state = use_context::<AppState>();
+        use_wry_event_handler(move |event, _| {
+            let closing = matches!(
+                event,
+                Event::LoopDestroyed
+                    | Event::WindowEvent {
+                        event: WindowEvent::CloseRequested,
+                        ..
+                    }
+            );
+            if closing {
+                app_state.shutdown();
+            }
+            // Permission timeouts only count down while the window has focus
+            if let Event::WindowEvent {
+                event: WindowEvent::Focused(focused),
+                ..
+            } = event
+            {
+                set_window_focused(*focused);
+            }
+        });
+    }
+
     rsx! {
         Layout {}
     }
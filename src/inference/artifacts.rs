@@ -0,0 +1,212 @@
+//! Cleanup of chat-template artifacts in generated text
+//!
+//! Small models often write their template's special tokens (`<|im_end|>`,
+//! `<end_of_turn>`) into the reply, or start it with a role line such as
+//! `assistant:`. These are removed from the stream before it reaches the
+//! chat. The family is read from the model's chat template unless its
+//! profile sets one.
+
+use serde::{Deserialize, Serialize};
+
+/// Template family whose artifacts are stripped from a model's output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputCleanup {
+    /// Detected from the chat template; when unknown, the tokens no reply
+    /// would contain (Mistral's `<s>` could be HTML)
+    #[default]
+    Auto,
+    ChatMl,
+    Llama3,
+    Gemma,
+    Mistral,
+    Phi,
+    /// Leave the output untouched
+    Off,
+}
+
+impl OutputCleanup {
+    pub const ALL: [OutputCleanup; 7] = [
+        OutputCleanup::Auto,
+        OutputCleanup::ChatMl,
+        OutputCleanup::Llama3,
+        OutputCleanup::Gemma,
+        OutputCleanup::Mistral,
+        OutputCleanup::Phi,
+        OutputCleanup::Off,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            OutputCleanup::Auto => "auto",
+            OutputCleanup::ChatMl => "chat_ml",
+            OutputCleanup::Llama3 => "llama3",
+            OutputCleanup::Gemma => "gemma",
+            OutputCleanup::Mistral => "mistral",
+            OutputCleanup::Phi => "phi",
+            OutputCleanup::Off => "off",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|cleanup| cleanup.id() == id)
+    }
+
+    pub fn label(self, is_en: bool) -> &'static str {
+        match (self, is_en) {
+            (OutputCleanup::Auto, true) => "Cleanup: auto",
+            (OutputCleanup::Auto, false) => "Nettoyage : auto",
+            (OutputCleanup::ChatMl, _) => "ChatML (Qwen, ...)",
+            (OutputCleanup::Llama3, _) => "Llama 3",
+            (OutputCleanup::Gemma, _) => "Gemma",
+            (OutputCleanup::Mistral, _) => "Mistral",
+            (OutputCleanup::Phi, _) => "Phi",
+            (OutputCleanup::Off, true) => "Cleanup: off",
+            (OutputCleanup::Off, false) => "Nettoyage : desactive",
+        }
+    }
+
+    /// Family used for a model whose chat template is `template`
+    pub fn resolve(self, template: Option<&str>) -> Self {
+        if self != OutputCleanup::Auto {
+            return self;
+        }
+        let Some(template) = template else { return self };
+        [
+            ("<|im_start|>", OutputCleanup::ChatMl),
+            ("<|start_header_id|>", OutputCleanup::Llama3),
+            ("<start_of_turn>", OutputCleanup::Gemma),
+            ("[INST]", OutputCleanup::Mistral),
+            ("<|assistant|>", OutputCleanup::Phi),
+        ]
+        .into_iter()
+        .find(|(marker, _)| template.contains(marker))
+        .map_or(self, |(_, family)| family)
+    }
+
+    /// Template tokens removed wherever they appear
+    fn artifacts(self) -> Vec<&'static str> {
+        match self {
+            OutputCleanup::ChatMl => vec!["<|im_end|>", "<|im_start|>", "<|endoftext|>"],
+            OutputCleanup::Llama3 => vec![
+                "<|eot_id|>",
+                "<|start_header_id|>",
+                "<|end_header_id|>",
+                "<|begin_of_text|>",
+                "<|end_of_text|>",
+            ],
+            OutputCleanup::Gemma => vec!["<end_of_turn>", "<start_of_turn>", "<eos>", "<bos>"],
+            OutputCleanup::Mistral => vec!["[/INST]", "[INST]", "</s>", "<s>"],
+            OutputCleanup::Phi => vec!["<|end|>", "<|assistant|>", "<|user|>", "<|system|>", "<|endoftext|>"],
+            OutputCleanup::Auto => {
+                let families = [OutputCleanup::ChatMl, OutputCleanup::Llama3, OutputCleanup::Gemma, OutputCleanup::Phi];
+                let mut all: Vec<&'static str> = families.iter().flat_map(|family| family.artifacts()).collect();
+                all.sort_unstable();
+                all.dedup();
+                all
+            }
+            OutputCleanup::Off => Vec::new(),
+        }
+    }
+
+    /// Role lines removed from the start of a reply (compared ignoring case)
+    fn role_prefixes(self) -> Vec<&'static str> {
+        match self {
+            OutputCleanup::Off => Vec::new(),
+            OutputCleanup::Gemma => vec!["assistant:", "model\n"],
+            OutputCleanup::Auto => vec!["assistant:", "assistant\n", "model\n"],
+            _ => vec!["assistant:", "assistant\n"],
+        }
+    }
+}
+
+/// Removes template artifacts from a token stream
+///
+/// Like stop sequences, an artifact can straddle tokens (`"<|im_"` then
+/// `"end|>"`), so text that could start one is held back, as is the start of
+/// the reply until it is clear whether it is a role line.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactFilter {
+    artifacts: Vec<&'static str>,
+    role_prefixes: Vec<&'static str>,
+    held: String,
+    started: bool,
+}
+
+impl ArtifactFilter {
+    pub fn new(cleanup: OutputCleanup) -> Self {
+        Self {
+            artifacts: cleanup.artifacts(),
+            role_prefixes: cleanup.role_prefixes(),
+            held: String::new(),
+            started: cleanup == OutputCleanup::Off,
+        }
+    }
+
+    /// Text safe to emit after `text`
+    pub fn push(&mut self, text: &str) -> String {
+        if self.artifacts.is_empty() {
+            return text.to_string();
+        }
+        self.held.push_str(text);
+        for artifact in &self.artifacts {
+            if self.held.contains(artifact) {
+                self.held = self.held.replace(artifact, "");
+            }
+        }
+
+        if !self.started {
+            let start = self.held.trim_start();
+            let matches = |prefix: &str| start.get(..prefix.len()).is_some_and(|head| head.eq_ignore_ascii_case(prefix));
+            if let Some(prefix) = self.role_prefixes.iter().copied().find(|prefix| matches(prefix)) {
+                self.held = start[prefix.len()..].trim_start().to_string();
+                self.started = true;
+            } else if start.is_empty()
+                || self.role_prefixes.iter().any(|prefix| {
+                    prefix.get(..start.len()).is_some_and(|head| head.eq_ignore_ascii_case(start))
+                })
+            {
+                return String::new();
+            } else {
+                self.started = true;
+            }
+        }
+
+        // Hold back the longest suffix that starts an artifact
+        let split = self
+            .held
+            .char_indices()
+            .map(|(i, _)| i)
+            .find(|&i| self.artifacts.iter().any(|artifact| artifact.starts_with(&self.held[i..])))
+            .unwrap_or(self.held.len());
+        let emitted = self.held[..split].to_string();
+        self.held.drain(..split);
+        emitted
+    }
+
+    /// Text still held when the generation ends
+    pub fn finish(&mut self) -> String {
+        std::mem::take(&mut self.held)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(cleanup: OutputCleanup, tokens: &[&str]) -> String {
+        let mut filter = ArtifactFilter::new(cleanup);
+        let mut out: String = tokens.iter().map(|token| filter.push(token)).collect();
+        out.push_str(&filter.finish());
+        out
+    }
+
+    #[test]
+    fn test_artifact_filter() {
+        assert_eq!(run(OutputCleanup::ChatMl, &["Assist", "ant: Hello", " there<|im_", "end|>"]), "Hello there");
+        assert_eq!(run(OutputCleanup::Auto, &["<end_of_turn>", "model\n", "Hi <", "3"]), "Hi <3");
+        assert_eq!(run(OutputCleanup::Off, &["assistant: <|im_end|>"]), "assistant: <|im_end|>");
+        assert_eq!(OutputCleanup::Auto.resolve(Some("{{'<|im_start|>' + role}}")), OutputCleanup::ChatMl);
+        assert_eq!(OutputCleanup::Phi.resolve(Some("<|im_start|>")), OutputCleanup::Phi);
+    }
+}
@@ -21,6 +21,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use llama_cpp_2::context::params::LlamaContextParams;
 use llama_cpp_2::context::LlamaContext;
@@ -29,11 +30,17 @@ use llama_cpp_2::llama_batch::LlamaBatch;
 use llama_cpp_2::model::params::LlamaModelParams;
 use llama_cpp_2::model::{AddBos, LlamaChatMessage, LlamaModel, Special};
 use llama_cpp_2::sampling::LlamaSampler;
+use llama_cpp_2::token::LlamaToken;
+use async_trait::async_trait;
 use thiserror::Error;
 
-use crate::inference::model::{validate_gguf, ModelError};
-use crate::inference::streaming::StreamToken;
-use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+use crate::inference::artifacts::{ArtifactFilter, OutputCleanup};
+use crate::inference::model::{read_pooling_type, validate_gguf, ModelError};
+use crate::inference::streaming::{token_channel, StopMatcher, StreamToken, TokenSender};
+use crate::inference::truncation::{fit_messages, TruncationStrategy};
+use crate::storage::settings::CONTEXT_SIZES;
+use crate::system::gpu::get_total_vram_gb;
+use crate::types::message::{Message as ChatMessage, Role as ChatRole, StopReason, TokenLogprob};
 
 /// Errors that can occur during inference operations
 #[derive(Debug, Error, Clone)]
@@ -79,9 +86,36 @@ pub struct GenerationParams {
     pub temperature: f32,
     pub top_k: u32,
     pub top_p: f32,
+    /// Drop tokens less likely than this fraction of the likeliest one
+    /// (0 = disabled)
+    pub min_p: f32,
+    /// Locally typical sampling threshold (0 = disabled)
+    pub typical_p: f32,
     pub repeat_penalty: f32,
+    /// Flat penalty on tokens already in the last `penalty_last_n` (0 = off)
+    pub presence_penalty: f32,
+    /// Penalty growing with each repetition of a token (0 = off)
+    pub frequency_penalty: f32,
+    /// Tokens looked back on by the penalties; -1 = the whole context
+    pub penalty_last_n: i32,
     pub seed: u32,
     pub max_context_size: u32,
+    /// How messages are dropped when the prompt doesn't fit
+    pub truncation: TruncationStrategy,
+    /// Generation ends at the first of these, which is not emitted
+    pub stop_sequences: Vec<String>,
+    /// Send a `StreamToken::Logprob` for every sampled token (debugging)
+    pub logprobs: bool,
+    /// Template artifacts stripped from the output (from the model profile)
+    pub output_cleanup: OutputCleanup,
+    /// Keep the KV cache of the prompt prefix shared with the previous
+    /// generation instead of evaluating the whole prompt again
+    pub reuse_prefix: bool,
+    /// GBNF grammar the output must follow, starting from its `root` rule
+    pub grammar: Option<String>,
+    /// Keep `max_context_size` even when the model and its KV cache don't
+    /// fit in the detected VRAM, instead of shrinking the context
+    pub context_over_vram: bool,
 }
 
 impl Default for GenerationParams {
@@ -91,9 +125,21 @@ impl Default for GenerationParams {
             temperature: 0.7,
             top_k: 40,
             top_p: 0.95,
+            min_p: 0.05,
+            typical_p: 0.0,
             repeat_penalty: 1.1,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
+            penalty_last_n: 64,
             seed: 0,
             max_context_size: 16384, // 16K context - validated with LM Studio on 8GB VRAM
+            truncation: TruncationStrategy::default(),
+            stop_sequences: Vec::new(),
+            logprobs: false,
+            output_cleanup: OutputCleanup::Auto,
+            reuse_prefix: true,
+            grammar: None,
+            context_over_vram: false,
         }
     }
 }
@@ -105,9 +151,21 @@ impl GenerationParams {
             temperature: 0.0,
             top_k: 1,
             top_p: 1.0,
+            min_p: 0.0,
+            typical_p: 0.0,
             repeat_penalty: 1.0,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
+            penalty_last_n: 64,
             seed: 0,
             max_context_size: 4096,
+            truncation: TruncationStrategy::default(),
+            stop_sequences: Vec::new(),
+            logprobs: false,
+            output_cleanup: OutputCleanup::Auto,
+            reuse_prefix: true,
+            grammar: None,
+            context_over_vram: false,
         }
     }
     
@@ -117,9 +175,21 @@ impl GenerationParams {
             temperature: 0.7,
             top_k: 40,
             top_p: 0.9,
+            min_p: 0.05,
+            typical_p: 0.0,
             repeat_penalty: 1.1,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
+            penalty_last_n: 64,
             seed: 0,
             max_context_size: 8192,
+            truncation: TruncationStrategy::default(),
+            stop_sequences: Vec::new(),
+            logprobs: false,
+            output_cleanup: OutputCleanup::Auto,
+            reuse_prefix: true,
+            grammar: None,
+            context_over_vram: false,
         }
     }
     
@@ -129,9 +199,21 @@ impl GenerationParams {
             temperature: 0.8,
             top_k: 50,
             top_p: 0.95,
+            min_p: 0.1,
+            typical_p: 0.0,
             repeat_penalty: 1.1,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
+            penalty_last_n: 64,
             seed: 0,
             max_context_size: 16384,
+            truncation: TruncationStrategy::default(),
+            stop_sequences: Vec::new(),
+            logprobs: false,
+            output_cleanup: OutputCleanup::Auto,
+            reuse_prefix: true,
+            grammar: None,
+            context_over_vram: false,
         }
     }
 }
@@ -145,8 +227,49 @@ pub struct LoadedModelInfo {
     pub context_length: u32,
     pub param_count: u64,
     pub size_bytes: u64,
+    pub n_layer: u32,
+    pub n_head: u32,
+    /// Fewer than `n_head` with grouped-query attention
+    pub n_head_kv: u32,
+    /// GGUF pooling type, set by embedding models (see [`read_pooling_type`])
+    pub pooling_type: Option<u32>,
 }
 
+impl LoadedModelInfo {
+    /// Estimated size of the f16 KV cache for a context of `n_ctx` tokens
+    pub fn kv_cache_bytes(&self, n_ctx: u32) -> u64 {
+        let embd = self.embedding_dim.max(0) as u64;
+        let kv_dim = if self.n_head > 0 && self.n_head_kv > 0 {
+            embd / self.n_head as u64 * self.n_head_kv as u64
+        } else {
+            embd
+        };
+        // K and V, 2 bytes per value
+        2 * self.n_layer as u64 * n_ctx as u64 * kv_dim * 2
+    }
+
+    /// Largest standard context whose KV cache fits in `vram_bytes` next
+    /// to the weights; the smallest one when even that doesn't fit
+    pub fn max_context_in_vram(&self, vram_bytes: u64) -> u32 {
+        CONTEXT_SIZES
+            .iter()
+            .rev()
+            .copied()
+            .find(|&size| self.size_bytes + self.kv_cache_bytes(size) <= vram_bytes)
+            .unwrap_or(CONTEXT_SIZES[0])
+    }
+
+    /// Whether the model pools its output into one vector per text (mean,
+    /// CLS or last token), i.e. was built for embeddings
+    pub fn supports_embeddings(&self) -> bool {
+        matches!(self.pooling_type, Some(1..=3))
+    }
+}
+
+/// Tokens the chat template adds around each message (role markers,
+/// separators), on top of its content
+const MESSAGE_TEMPLATE_TOKENS: usize = 4;
+
 /// Commands sent to the worker thread
 enum WorkerCommand {
     Init,
@@ -156,10 +279,26 @@ enum WorkerCommand {
         response_tx: Sender<Result<LoadedModelInfo, EngineError>>,
     },
     UnloadModel,
+    /// Embed texts with the loaded model
+    Embed {
+        texts: Vec<String>,
+        response_tx: Sender<Result<Vec<Vec<f32>>, EngineError>>,
+    },
+    /// Embed texts with a separate embedding model, loaded on first use
+    EmbedWith {
+        model_path: PathBuf,
+        texts: Vec<String>,
+        response_tx: Sender<Result<Vec<Vec<f32>>, EngineError>>,
+    },
+    /// Count the tokens of each text with the loaded model
+    Tokenize {
+        texts: Vec<String>,
+        response_tx: Sender<Result<Vec<usize>, EngineError>>,
+    },
     Generate {
         messages: Vec<ChatMessage>,
         params: GenerationParams,
-        token_tx: Sender<StreamToken>,
+        token_tx: TokenSender,
         stop_signal: Arc<AtomicBool>,
     },
     Shutdown,
@@ -170,8 +309,13 @@ pub struct LlamaEngine {
     command_tx: Option<Sender<WorkerCommand>>,
     worker_handle: Option<JoinHandle<()>>,
     model_info: Option<LoadedModelInfo>,
+    /// Largest context that fits in VRAM with the loaded model; `None` on
+    /// the CPU or when no GPU was detected
+    vram_context_cap: Option<u32>,
     initialized: bool,
     model_loaded: bool,
+    /// Stop flag of the latest generation, raised on shutdown
+    generation_stop: std::sync::Mutex<Option<Arc<AtomicBool>>>,
 }
 
 impl LlamaEngine {
@@ -180,11 +324,42 @@ impl LlamaEngine {
             command_tx: None,
             worker_handle: None,
             model_info: None,
+            vram_context_cap: None,
             initialized: false,
             model_loaded: false,
+            generation_stop: std::sync::Mutex::new(None),
         }
     }
 
+    /// Stop any generation, then shut the worker down and wait up to
+    /// `timeout` for it to free the model; returns whether it finished
+    pub fn shutdown(&mut self, timeout: Duration) -> bool {
+        if let Some(stop) = self.generation_stop.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        if let Some(tx) = self.command_tx.take() {
+            let _ = tx.send(WorkerCommand::Shutdown);
+        }
+        self.initialized = false;
+        self.model_loaded = false;
+        self.model_info = None;
+        self.vram_context_cap = None;
+        let Some(handle) = self.worker_handle.take() else {
+            return true;
+        };
+        let deadline = Instant::now() + timeout;
+        while !handle.is_finished() {
+            if Instant::now() >= deadline {
+                tracing::warn!("LlamaEngine worker still busy after {:?}, not waiting", timeout);
+                return false;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = handle.join();
+        tracing::info!("LlamaEngine worker thread stopped");
+        true
+    }
+
     pub fn init(&mut self) -> Result<(), EngineError> {
         if self.initialized {
             return Ok(());
@@ -240,6 +415,9 @@ impl LlamaEngine {
         .map_err(|e| EngineError::WorkerError(format!("Task join error: {}", e)))?
         .map_err(|e| EngineError::WorkerError(e.to_string()))??;
 
+        // GPU detection may run a vendor tool
+        let vram_gb = tokio::task::spawn_blocking(get_total_vram_gb).await.ok().flatten();
+        self.vram_context_cap = vram_context_cap(&result, gpu_layers, vram_gb);
         self.model_info = Some(result.clone());
         self.model_loaded = true;
 
@@ -274,6 +452,7 @@ impl LlamaEngine {
             .recv()
             .map_err(|e| EngineError::WorkerError(e.to_string()))??;
 
+        self.vram_context_cap = vram_context_cap(&result, gpu_layers, get_total_vram_gb());
         self.model_info = Some(result.clone());
         self.model_loaded = true;
 
@@ -285,6 +464,7 @@ impl LlamaEngine {
             let _ = tx.send(WorkerCommand::UnloadModel);
         }
         self.model_info = None;
+        self.vram_context_cap = None;
         self.model_loaded = false;
         tracing::info!("Model unload requested");
     }
@@ -301,6 +481,87 @@ impl LlamaEngine {
         self.initialized
     }
 
+    /// Handle for computing embeddings on the worker thread, usable without
+    /// holding the engine lock
+    pub fn embedder(&self) -> Option<Embedder> {
+        self.command_tx.clone().map(|command_tx| Embedder { command_tx })
+    }
+
+    /// Normalized embedding of each text with the loaded model, which must
+    /// be an embedding model (one with a pooling type in its metadata)
+    ///
+    /// Vectors have unit length, so their dot product is the cosine
+    /// similarity:
+    ///
+    /// ```no_run
+    /// # async fn example(engine: &clawrs::inference::LlamaEngine) -> Result<(), clawrs::inference::EngineError> {
+    /// let texts = vec!["How do I reset my password?".to_string(), "I forgot my login".to_string()];
+    /// let vectors = engine.embed(texts).await?;
+    /// let similarity: f32 = vectors[0].iter().zip(&vectors[1]).map(|(a, b)| a * b).sum();
+    /// println!("cosine similarity: {similarity:.3}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EngineError> {
+        let command_tx = self
+            .command_tx
+            .as_ref()
+            .ok_or(EngineError::BackendNotInitialized)?;
+        let info = self.model_info.as_ref().ok_or(EngineError::NoModelLoaded)?;
+        if !info.supports_embeddings() {
+            return Err(EngineError::Inference(format!(
+                "{} is not an embedding model (no pooling type in its metadata)",
+                info.path
+            )));
+        }
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (response_tx, response_rx) = mpsc::channel();
+        command_tx
+            .send(WorkerCommand::Embed { texts, response_tx })
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+        tokio::task::spawn_blocking(move || response_rx.recv())
+            .await
+            .map_err(|e| EngineError::WorkerError(format!("Task join error: {}", e)))?
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?
+    }
+
+    /// Tokens of `text` with the loaded model (blocks until the worker is done)
+    pub fn count_tokens(&self, text: &str) -> Result<usize, EngineError> {
+        let response_rx = self.send_tokenize(vec![text.to_string()])?;
+        let counts = response_rx
+            .recv()
+            .map_err(|e| EngineError::WorkerError(e.to_string()))??;
+        Ok(counts.into_iter().sum())
+    }
+
+    /// Tokens `messages` take in a prompt, counted in one batch on the worker
+    pub async fn count_message_tokens(&self, messages: &[ChatMessage]) -> Result<usize, EngineError> {
+        let texts = messages.iter().map(|m| m.content.clone()).collect();
+        let response_rx = self.send_tokenize(texts)?;
+        let counts = tokio::task::spawn_blocking(move || response_rx.recv())
+            .await
+            .map_err(|e| EngineError::WorkerError(format!("Task join error: {}", e)))?
+            .map_err(|e| EngineError::WorkerError(e.to_string()))??;
+        Ok(counts.iter().sum::<usize>() + messages.len() * MESSAGE_TEMPLATE_TOKENS)
+    }
+
+    fn send_tokenize(&self, texts: Vec<String>) -> Result<Receiver<Result<Vec<usize>, EngineError>>, EngineError> {
+        let command_tx = self
+            .command_tx
+            .as_ref()
+            .ok_or(EngineError::BackendNotInitialized)?;
+        if !self.model_loaded {
+            return Err(EngineError::NoModelLoaded);
+        }
+        let (response_tx, response_rx) = mpsc::channel();
+        command_tx
+            .send(WorkerCommand::Tokenize { texts, response_tx })
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+        Ok(response_rx)
+    }
+
     pub fn generate_stream(
         &self,
         prompt: &str,
@@ -313,7 +574,7 @@ impl LlamaEngine {
     pub fn generate_stream_messages(
         &self,
         messages: Vec<ChatMessage>,
-        params: GenerationParams,
+        mut params: GenerationParams,
     ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
         let command_tx = self
             .command_tx
@@ -324,8 +585,19 @@ impl LlamaEngine {
             return Err(EngineError::NoModelLoaded);
         }
 
-        let (token_tx, token_rx) = mpsc::channel();
+        // A KV cache spilling into RAM slows generation down to a crawl
+        if let Some(cap) = self.vram_context_cap.filter(|&cap| cap < params.max_context_size) {
+            if params.context_over_vram {
+                tracing::debug!("Context {} exceeds the VRAM estimate ({}), kept by override", params.max_context_size, cap);
+            } else {
+                tracing::warn!("Context {} doesn't fit in VRAM with the model, using {}", params.max_context_size, cap);
+                params.max_context_size = cap;
+            }
+        }
+
+        let (token_tx, token_rx) = token_channel();
         let stop_signal = Arc::new(AtomicBool::new(false));
+        *self.generation_stop.lock().unwrap_or_else(|e| e.into_inner()) = Some(stop_signal.clone());
 
         command_tx
             .send(WorkerCommand::Generate {
@@ -340,6 +612,145 @@ impl LlamaEngine {
     }
 }
 
+/// Sends embedding requests to the engine's worker thread
+///
+/// Requests queue behind a running generation, since both share the worker.
+#[derive(Clone)]
+pub struct Embedder {
+    command_tx: Sender<WorkerCommand>,
+}
+
+impl Embedder {
+    /// Normalized embedding of each text (blocks until the worker is done)
+    pub fn embed(&self, model_path: &Path, texts: Vec<String>) -> Result<Vec<Vec<f32>>, EngineError> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.command_tx
+            .send(WorkerCommand::EmbedWith {
+                model_path: model_path.to_path_buf(),
+                texts,
+                response_tx,
+            })
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+        response_rx
+            .recv()
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?
+    }
+}
+
+/// Engine shared by the UI and background jobs, swapped when the backend
+/// setting changes
+pub type SharedEngine = Arc<tokio::sync::Mutex<Box<dyn InferenceBackend>>>;
+
+/// Streaming text generation, implemented by the llama.cpp engine, the
+/// OpenAI-compatible remote backend and by `MockEngine` in tests
+///
+/// Only generation is required; the defaults suit a backend with nothing
+/// to load locally.
+#[async_trait]
+pub trait InferenceBackend: Send + Sync {
+    fn generate_stream_messages(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError>;
+
+    fn init(&mut self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    /// Make `model` the one generations use: a GGUF path for the local
+    /// engine, a model id for a remote server
+    async fn load_model_async(&mut self, model: &str, gpu_layers: u32) -> Result<LoadedModelInfo, EngineError> {
+        let _ = (model, gpu_layers);
+        Err(EngineError::ModelLoad("This backend cannot load models".to_string()))
+    }
+
+    fn unload_model(&mut self) {}
+
+    /// Metadata of the loaded GGUF; `None` when it isn't known locally
+    fn model_info(&self) -> Option<&LoadedModelInfo> {
+        None
+    }
+
+    fn is_model_loaded(&self) -> bool {
+        true
+    }
+
+    fn embedder(&self) -> Option<Embedder> {
+        None
+    }
+
+    /// Tokens `messages` take in a prompt; callers fall back to an estimate
+    /// on `NoModelLoaded`
+    async fn count_message_tokens(&self, messages: &[ChatMessage]) -> Result<usize, EngineError> {
+        let _ = messages;
+        Err(EngineError::NoModelLoaded)
+    }
+
+    /// Models the backend can switch to without a local file
+    async fn list_models(&self) -> Result<Vec<String>, EngineError> {
+        Ok(Vec::new())
+    }
+
+    /// Stop any generation and free the model; returns whether it finished
+    /// within `timeout`
+    fn shutdown(&mut self, timeout: Duration) -> bool {
+        let _ = timeout;
+        true
+    }
+}
+
+#[async_trait]
+impl InferenceBackend for LlamaEngine {
+    fn generate_stream_messages(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        LlamaEngine::generate_stream_messages(self, messages, params)
+    }
+
+    fn init(&mut self) -> Result<(), EngineError> {
+        LlamaEngine::init(self)
+    }
+
+    fn is_initialized(&self) -> bool {
+        LlamaEngine::is_initialized(self)
+    }
+
+    async fn load_model_async(&mut self, model: &str, gpu_layers: u32) -> Result<LoadedModelInfo, EngineError> {
+        LlamaEngine::load_model_async(self, model, gpu_layers).await
+    }
+
+    fn unload_model(&mut self) {
+        LlamaEngine::unload_model(self)
+    }
+
+    fn model_info(&self) -> Option<&LoadedModelInfo> {
+        LlamaEngine::model_info(self)
+    }
+
+    fn is_model_loaded(&self) -> bool {
+        LlamaEngine::is_model_loaded(self)
+    }
+
+    fn embedder(&self) -> Option<Embedder> {
+        LlamaEngine::embedder(self)
+    }
+
+    async fn count_message_tokens(&self, messages: &[ChatMessage]) -> Result<usize, EngineError> {
+        LlamaEngine::count_message_tokens(self, messages).await
+    }
+
+    fn shutdown(&mut self, timeout: Duration) -> bool {
+        LlamaEngine::shutdown(self, timeout)
+    }
+}
+
 impl Default for LlamaEngine {
     fn default() -> Self {
         Self::new()
@@ -374,6 +785,13 @@ struct WorkerState {
     ctx_n_batch: u32,
     /// Optimal thread count (cached)
     n_threads: i32,
+    /// Tokens whose keys and values are in the context's KV cache, in order
+    cached_tokens: Vec<LlamaToken>,
+    /// Embedding context of the loaded model, reused across `Embed` calls;
+    /// dropped before the model like `ctx`
+    embed_ctx: Option<LlamaContext<'static>>,
+    /// Embedding model, kept apart from the chat model
+    embed_model: Option<(PathBuf, LlamaModel)>,
 }
 
 impl WorkerState {
@@ -385,6 +803,9 @@ impl WorkerState {
             ctx_n_ctx: 0,
             ctx_n_batch: 0,
             n_threads: get_optimal_threads(),
+            cached_tokens: Vec::new(),
+            embed_ctx: None,
+            embed_model: None,
         }
     }
 }
@@ -416,8 +837,9 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
                 gpu_layers,
                 response_tx,
             }) => {
-                // Drop existing context FIRST (before model)
+                // Drop existing contexts FIRST (before model)
                 state.ctx = None;
+                state.embed_ctx = None;
                 state.ctx_n_ctx = 0;
                 state.ctx_n_batch = 0;
                 state.model = None;
@@ -433,13 +855,39 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
                 }
             }
             Ok(WorkerCommand::UnloadModel) => {
-                // Drop context FIRST, then model
+                // Drop contexts FIRST, then model
                 state.ctx = None;
+                state.embed_ctx = None;
                 state.ctx_n_ctx = 0;
                 state.ctx_n_batch = 0;
                 state.model = None;
                 tracing::info!("Model and context unloaded");
             }
+            Ok(WorkerCommand::Embed { texts, response_tx }) => {
+                let _ = response_tx.send(run_loaded_embeddings(&mut state, &texts));
+            }
+            Ok(WorkerCommand::EmbedWith {
+                model_path,
+                texts,
+                response_tx,
+            }) => {
+                let _ = response_tx.send(run_embeddings(&mut state, &model_path, &texts));
+            }
+            Ok(WorkerCommand::Tokenize { texts, response_tx }) => {
+                let counts = match &state.model {
+                    Some(model) => texts
+                        .iter()
+                        .map(|text| {
+                            model
+                                .str_to_token(text, AddBos::Never)
+                                .map(|tokens| tokens.len())
+                                .map_err(|e| EngineError::Tokenization(e.to_string()))
+                        })
+                        .collect(),
+                    None => Err(EngineError::NoModelLoaded),
+                };
+                let _ = response_tx.send(counts);
+            }
             Ok(WorkerCommand::Generate {
                 messages,
                 params,
@@ -447,18 +895,20 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
                 stop_signal,
             }) => {
                 if state.backend.is_none() || state.model.is_none() {
-                    let _ = token_tx.send(StreamToken::Error("No model loaded".to_string()));
+                    token_tx.send(StreamToken::Error("No model loaded".to_string()));
                     continue;
                 }
                 
                 if let Err(e) = run_generation_persistent(&mut state, &messages, params, &token_tx, &stop_signal) {
-                    let _ = token_tx.send(StreamToken::Error(e));
+                    token_tx.send(StreamToken::Error(e));
                 }
             }
             Ok(WorkerCommand::Shutdown) => {
-                // Clean shutdown: drop context first, then model
+                // Clean shutdown: drop contexts first, then model
                 state.ctx = None;
+                state.embed_ctx = None;
                 state.model = None;
+                state.embed_model = None;
                 state.backend = None;
                 tracing::info!("Worker thread shut down");
                 break;
@@ -509,6 +959,10 @@ fn load_model_internal(
         context_length: model.n_ctx_train(),
         param_count: model.n_params() as u64,
         size_bytes: model.size() as u64,
+        n_layer: model.n_layer(),
+        n_head: model.n_head(),
+        n_head_kv: model.n_head_kv(),
+        pooling_type: read_pooling_type(path),
     };
 
     tracing::info!(
@@ -529,7 +983,7 @@ fn run_generation_persistent(
     state: &mut WorkerState,
     messages: &[ChatMessage],
     params: GenerationParams,
-    tx: &Sender<StreamToken>,
+    tx: &TokenSender,
     stop_signal: &Arc<AtomicBool>,
 ) -> Result<(), String> {
     let start_time = std::time::Instant::now();
@@ -537,29 +991,29 @@ fn run_generation_persistent(
     let backend = state.backend.as_ref().ok_or("Backend not initialized")?;
     let model = state.model.as_ref().ok_or("Model not loaded")?;
 
-    // Build prompt
-    let prompt = match build_chat_prompt_from_messages(model, messages) {
-        Ok(p) => p,
-        Err(e) => {
-            tracing::warn!("Chat template error: {e}, using fallback");
-            build_fallback_prompt(messages)
-        }
-    };
-
-    // Tokenize
-    let tokens = model
-        .str_to_token(&prompt, AddBos::Always)
-        .map_err(|e| format!("Tokenization failed: {}", e))?;
-    
-    let prompt_len = tokens.len() as u32;
     let model_max = model.n_ctx_train();
     
     // Use the SMALLER of model max and user's configured max context
     // This is critical: model may support 128K but user's GPU can only handle 4K
     let effective_max = std::cmp::min(params.max_context_size, model_max);
+    let min_gen = 256u32;
+
+    // Drop whole messages when the prompt is too long, before the token-level
+    // cut in run_inference has to take the start of the prompt
+    let prompt_budget = effective_max.saturating_sub(min_gen) as usize;
+    // The whole prompt is tokenized once; dropped messages are subtracted
+    let total = tokenize_messages(model, messages).map_or(usize::MAX, |tokens| tokens.len());
+    let messages = fit_messages(messages.to_vec(), params.truncation, prompt_budget, total, |message| {
+        model
+            .str_to_token(&message.content, AddBos::Never)
+            .map_or(0, |tokens| tokens.len())
+            + MESSAGE_TEMPLATE_TOKENS
+    })?;
+    let prompt = tokenize_prompt(model, &messages)?;
+    
+    let prompt_len = prompt.tokens.len() as u32;
     
     // Calculate needed context size
-    let min_gen = 256u32;
     let needed = std::cmp::min(prompt_len + params.max_tokens, effective_max);
     let needed = std::cmp::max(needed, prompt_len + min_gen);
     let needed = std::cmp::min(needed, effective_max);
@@ -611,6 +1065,7 @@ fn run_generation_persistent(
     if need_new_ctx {
         // Drop old context first to free VRAM
         state.ctx = None;
+        state.cached_tokens.clear();
         state.ctx_n_ctx = 0;
         state.ctx_n_batch = 0;
         
@@ -643,9 +1098,6 @@ fn run_generation_persistent(
     let ctx = state.ctx.as_mut().ok_or("Context disappeared")?;
     let actual_n_ctx = state.ctx_n_ctx;
     
-    // Clear the KV cache for fresh generation
-    ctx.clear_kv_cache();
-    
     // Clamp max_tokens to fit in context
     let available = actual_n_ctx.saturating_sub(prompt_len).max(64);
     let effective_max = std::cmp::min(params.max_tokens, available);
@@ -667,7 +1119,122 @@ fn run_generation_persistent(
     );
 
     let n_batch = calculate_optimal_batch(actual_n_ctx, prompt_len);
-    run_inference(ctx, model, tokens, clamped, actual_n_ctx, n_batch, tx, stop_signal)
+    run_inference(ctx, model, prompt, &mut state.cached_tokens, clamped, actual_n_ctx, n_batch, tx, stop_signal)
+}
+
+// =============================================================================
+// Embeddings
+// =============================================================================
+
+/// Context size for embeddings; longer texts are truncated
+const EMBED_CTX: u32 = 512;
+
+fn run_embeddings(
+    state: &mut WorkerState,
+    model_path: &Path,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, EngineError> {
+    let backend = state.backend.as_ref().ok_or(EngineError::BackendNotInitialized)?;
+
+    if state.embed_model.as_ref().map(|(p, _)| p.as_path()) != Some(model_path) {
+        state.embed_model = None;
+        let model = LlamaModel::load_from_file(backend, model_path, &LlamaModelParams::default())
+            .map_err(|e| EngineError::ModelLoad(e.to_string()))?;
+        tracing::info!("Embedding model loaded: {:?}", model_path);
+        state.embed_model = Some((model_path.to_path_buf(), model));
+    }
+    let (_, model) = state.embed_model.as_ref().ok_or(EngineError::NoModelLoaded)?;
+
+    let n_ctx = embed_ctx_size(model);
+    let mut ctx = model
+        .new_context(backend, embed_context_params(n_ctx, state.n_threads))
+        .map_err(|e| EngineError::ContextCreate(e.to_string()))?;
+    embed_texts(&mut ctx, model, texts, n_ctx)
+}
+
+/// Embed with the loaded model, creating its embedding context on first use
+fn run_loaded_embeddings(state: &mut WorkerState, texts: &[String]) -> Result<Vec<Vec<f32>>, EngineError> {
+    let backend = state.backend.as_ref().ok_or(EngineError::BackendNotInitialized)?;
+    let model = state.model.as_ref().ok_or(EngineError::NoModelLoaded)?;
+    let n_ctx = embed_ctx_size(model);
+
+    if state.embed_ctx.is_none() {
+        // SAFETY: as for the chat context, the embedding context is always
+        // dropped before the model it borrows
+        let model_static: &'static LlamaModel = unsafe { &*(model as *const LlamaModel) };
+        let ctx = model_static
+            .new_context(backend, embed_context_params(n_ctx, state.n_threads))
+            .map_err(|e| EngineError::ContextCreate(e.to_string()))?;
+        tracing::info!("Embedding context created: {} ctx", n_ctx);
+        state.embed_ctx = Some(ctx);
+    }
+    let ctx = state
+        .embed_ctx
+        .as_mut()
+        .ok_or_else(|| EngineError::ContextCreate("Embedding context disappeared".to_string()))?;
+    embed_texts(ctx, model, texts, n_ctx)
+}
+
+fn embed_ctx_size(model: &LlamaModel) -> u32 {
+    EMBED_CTX.min(model.n_ctx_train().max(64))
+}
+
+fn embed_context_params(n_ctx: u32, n_threads: i32) -> LlamaContextParams {
+    // Encoder models need the whole input in one micro-batch
+    LlamaContextParams::default()
+        .with_n_ctx(NonZeroU32::new(n_ctx))
+        .with_n_batch(n_ctx)
+        .with_n_ubatch(n_ctx)
+        .with_n_threads(n_threads)
+        .with_n_threads_batch(n_threads)
+        .with_embeddings(true)
+}
+
+/// Run each text through `ctx` on its own and return its pooled embedding,
+/// scaled to unit length
+fn embed_texts(
+    ctx: &mut LlamaContext<'_>,
+    model: &LlamaModel,
+    texts: &[String],
+    n_ctx: u32,
+) -> Result<Vec<Vec<f32>>, EngineError> {
+    let mut embeddings = Vec::with_capacity(texts.len());
+    let mut batch = LlamaBatch::new(n_ctx as usize, 1);
+    for text in texts {
+        let mut tokens = model
+            .str_to_token(text, AddBos::Always)
+            .map_err(|e| EngineError::Tokenization(e.to_string()))?;
+        tokens.truncate(n_ctx as usize);
+
+        ctx.clear_kv_cache();
+        batch.clear();
+        batch
+            .add_sequence(&tokens, 0, false)
+            .map_err(|e| EngineError::Inference(e.to_string()))?;
+        ctx.decode(&mut batch)
+            .map_err(|e| EngineError::Inference(e.to_string()))?;
+        let embedding = ctx
+            .embeddings_seq_ith(0)
+            .map_err(|e| EngineError::Inference(e.to_string()))?;
+        embeddings.push(normalize(embedding));
+    }
+    Ok(embeddings)
+}
+
+/// `v` scaled to unit length
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+    v.iter().map(|x| x / norm).collect()
+}
+
+/// Context cap for a model offloaded with `gpu_layers` given the detected
+/// VRAM; no cap on the CPU or without a detected GPU
+fn vram_context_cap(info: &LoadedModelInfo, gpu_layers: u32, vram_gb: Option<f64>) -> Option<u32> {
+    if gpu_layers == 0 {
+        return None;
+    }
+    let vram_bytes = (vram_gb? * 1024.0 * 1024.0 * 1024.0) as u64;
+    Some(info.max_context_in_vram(vram_bytes))
 }
 
 /// Pick a good context size (round up for reusability)
@@ -743,6 +1310,65 @@ fn build_chat_prompt_from_messages(
         .map_err(|e| format!("Template apply error: {e}"))
 }
 
+/// Prompt tokens of `messages` with the model's chat template
+fn tokenize_messages(
+    model: &LlamaModel,
+    messages: &[ChatMessage],
+) -> Result<Vec<LlamaToken>, String> {
+    let prompt = match build_chat_prompt_from_messages(model, messages) {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::warn!("Chat template error: {e}, using fallback");
+            build_fallback_prompt(messages)
+        }
+    };
+    model
+        .str_to_token(&prompt, AddBos::Always)
+        .map_err(|e| format!("Tokenization failed: {}", e))
+}
+
+/// Prompt tokens; the first `protected` (the system messages) are kept
+/// when the prompt has to be cut
+struct PromptTokens {
+    tokens: Vec<LlamaToken>,
+    protected: usize,
+}
+
+/// Prompt tokens of `messages`, with the span of its leading system messages
+fn tokenize_prompt(model: &LlamaModel, messages: &[ChatMessage]) -> Result<PromptTokens, String> {
+    let tokens = tokenize_messages(model, messages)?;
+    let system_end = messages
+        .iter()
+        .position(|m| m.role != ChatRole::System)
+        .unwrap_or(messages.len());
+    // The system messages rendered alone share their tokens with the start
+    // of the full prompt, up to where the first user turn begins
+    let protected = if system_end == 0 || system_end == messages.len() {
+        0
+    } else {
+        tokenize_messages(model, &messages[..system_end]).map_or(0, |system| common_prefix_len(&system, &tokens))
+    };
+    Ok(PromptTokens { tokens, protected })
+}
+
+/// Number of leading tokens `a` and `b` share
+fn common_prefix_len<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// Cut `tokens` to `max`, keeping the first `protected` and the most recent
+/// ones; at least a quarter of `max` goes to the recent end, which holds the
+/// question and the reply header
+fn truncate_prompt<T: Copy>(tokens: &[T], protected: usize, max: usize) -> Vec<T> {
+    if tokens.len() <= max {
+        return tokens.to_vec();
+    }
+    let head = protected.min(max - max.div_ceil(4));
+    let mut kept = tokens[..head].to_vec();
+    kept.extend_from_slice(&tokens[tokens.len() - (max - head)..]);
+    kept
+}
+
 fn build_fallback_prompt(messages: &[ChatMessage]) -> String {
     let mut out = String::with_capacity(4096);
     for msg in messages {
@@ -767,40 +1393,63 @@ fn build_fallback_prompt(messages: &[ChatMessage]) -> String {
 fn run_inference(
     ctx: &mut LlamaContext,
     model: &LlamaModel,
-    mut prompt_tokens: Vec<llama_cpp_2::token::LlamaToken>,
+    prompt: PromptTokens,
+    cached_tokens: &mut Vec<LlamaToken>,
     params: GenerationParams,
     n_ctx: u32,
     n_batch: u32,
-    tx: &Sender<StreamToken>,
+    tx: &TokenSender,
     stop_signal: &Arc<AtomicBool>,
 ) -> Result<(), String> {
     let inference_start = std::time::Instant::now();
     
-    if prompt_tokens.is_empty() {
+    if prompt.tokens.is_empty() {
         return Err("Empty prompt".to_string());
     }
 
-    // Truncate prompt if needed (keep most recent tokens)
+    // Truncate prompt if needed: the system prompt stays, the middle of the
+    // conversation goes
     let max_prompt = (n_ctx as usize).saturating_sub(params.max_tokens as usize).max(1);
-    if prompt_tokens.len() > max_prompt {
-        let start = prompt_tokens.len() - max_prompt;
-        prompt_tokens = prompt_tokens[start..].to_vec();
-        tracing::warn!("Prompt truncated to {} tokens", prompt_tokens.len());
+    let prompt_tokens = truncate_prompt(&prompt.tokens, prompt.protected, max_prompt);
+    if prompt_tokens.len() < prompt.tokens.len() {
+        tracing::warn!(
+            "Prompt truncated to {} tokens ({} system tokens kept)",
+            prompt_tokens.len(),
+            prompt.protected.min(prompt_tokens.len())
+        );
     }
 
-    // Process prompt in batches
+    // Keep the cache of the prefix shared with the previous generation (an
+    // agent iteration repeats the system prompt and history); the last
+    // prompt token is always decoded again for its logits
+    let mut reused = if params.reuse_prefix {
+        common_prefix_len(cached_tokens, &prompt_tokens).min(prompt_tokens.len() - 1)
+    } else {
+        0
+    };
+    // Some models (recurrent ones) can't drop part of their cache
+    if reused == 0 || !ctx.clear_kv_cache_seq(Some(0), Some(reused as u32), None).unwrap_or(false) {
+        ctx.clear_kv_cache();
+        reused = 0;
+    }
+    cached_tokens.truncate(reused);
+    if reused > 0 {
+        tracing::info!("Reused {} prefix tokens", reused);
+    }
+
+    // Process the rest of the prompt in batches
     let batch_size = std::cmp::max(1, n_batch) as usize;
     let mut batch = LlamaBatch::new(batch_size, 1);
     let prompt_len = prompt_tokens.len();
 
     let prompt_start = std::time::Instant::now();
-    for (chunk_index, chunk) in prompt_tokens.chunks(batch_size).enumerate() {
+    for (chunk_index, chunk) in prompt_tokens[reused..].chunks(batch_size).enumerate() {
         if stop_signal.load(Ordering::Relaxed) {
             return Ok(());
         }
         
         batch.clear();
-        let offset = chunk_index * batch_size;
+        let offset = reused + chunk_index * batch_size;
         for (i, token) in chunk.iter().enumerate() {
             let global_index = offset + i;
             let is_last = global_index + 1 == prompt_len;
@@ -811,32 +1460,67 @@ fn run_inference(
 
         ctx.decode(&mut batch)
             .map_err(|e| format!("Decode error: {}", e))?;
+        cached_tokens.extend_from_slice(chunk);
+        tx.send(StreamToken::Progress {
+            processed: (offset + chunk.len()) as u32,
+            total: prompt_len as u32,
+        });
     }
     
     let prompt_time = prompt_start.elapsed();
+    let evaluated = prompt_len - reused;
     tracing::info!(
         "Prompt: {} tokens in {:?} ({:.0} t/s)",
-        prompt_len, prompt_time, prompt_len as f64 / prompt_time.as_secs_f64()
+        evaluated, prompt_time, evaluated as f64 / prompt_time.as_secs_f64()
     );
 
     // Sampler
     let seed = if params.seed == 0 { rand_seed() } else { params.seed };
 
+    // The grammar goes first so the other samplers only see allowed tokens;
+    // one that doesn't parse leaves the output unconstrained
+    let grammar = params.grammar.as_deref().and_then(|grammar| {
+        LlamaSampler::grammar(model, grammar, "root")
+            .map_err(|e| tracing::warn!("Invalid grammar, generating without it: {}", e))
+            .ok()
+    });
+    let mut samplers: Vec<LlamaSampler> = grammar.into_iter().collect();
+    // `repeat_penalty` is left out, as it always was: presets carry 1.1
+    // and applying it now would change every existing reply
+    if (params.presence_penalty != 0.0 || params.frequency_penalty != 0.0) && params.penalty_last_n != 0 {
+        samplers.push(LlamaSampler::penalties(
+            params.penalty_last_n,
+            1.0,
+            params.frequency_penalty,
+            params.presence_penalty,
+        ));
+    }
     let mut sampler = if params.temperature < 0.01 {
-        LlamaSampler::greedy()
+        samplers.push(LlamaSampler::greedy());
+        LlamaSampler::chain_simple(samplers)
     } else {
-        LlamaSampler::chain_simple([
-            LlamaSampler::top_k(params.top_k as i32),
-            LlamaSampler::top_p(params.top_p, 1),
-            LlamaSampler::temp(params.temperature),
-            LlamaSampler::dist(seed),
-        ])
+        // Same order as llama.cpp: penalties, top-k, typical, top-p, min-p, temperature
+        samplers.push(LlamaSampler::top_k(params.top_k as i32));
+        if params.typical_p > 0.0 && params.typical_p < 1.0 {
+            samplers.push(LlamaSampler::typical(params.typical_p, 1));
+        }
+        samplers.push(LlamaSampler::top_p(params.top_p, 1));
+        if params.min_p > 0.0 {
+            samplers.push(LlamaSampler::min_p(params.min_p, 1));
+        }
+        samplers.push(LlamaSampler::temp(params.temperature));
+        samplers.push(LlamaSampler::dist(seed));
+        LlamaSampler::chain_simple(samplers)
     };
 
     let mut n_decoded = prompt_tokens.len() as i32;
     let mut tokens_generated = 0u32;
     let mut utf8_buffer: Vec<u8> = Vec::with_capacity(32);
     let mut hit_eos = false;  // Track if we stopped due to EOS
+    let template = model.chat_template(None).ok().and_then(|t| t.to_string().ok());
+    let mut artifacts = ArtifactFilter::new(params.output_cleanup.resolve(template.as_deref()));
+    let mut stops = StopMatcher::new(&params.stop_sequences);
+    let mut hit_stop = false;
 
     let gen_start = std::time::Instant::now();
     
@@ -849,20 +1533,42 @@ fn run_inference(
         sampler.accept(new_token);
 
         if model.is_eog_token(new_token) {
-            flush_utf8_buffer(&mut utf8_buffer, tx);
             hit_eos = true;
             break;
         }
 
         tokens_generated += 1;
 
+        // Read before the next decode overwrites the logits
+        if params.logprobs {
+            let logits = ctx.get_logits_ith(batch.n_tokens() - 1);
+            let (logprob, top) = logprobs_from_logits(logits, new_token.0 as usize, TOP_LOGPROBS);
+            let text = |id: usize| {
+                let bytes = model.token_to_bytes(LlamaToken(id as i32), Special::Tokenize).unwrap_or_default();
+                String::from_utf8_lossy(&bytes).into_owned()
+            };
+            let sent = tx.send(StreamToken::Logprob(TokenLogprob {
+                text: text(new_token.0 as usize),
+                logprob,
+                top: top.into_iter().map(|(id, logprob)| (text(id), logprob)).collect(),
+            }));
+            if !sent {
+                break;
+            }
+        }
+
         let token_bytes = model
             .token_to_bytes(new_token, Special::Tokenize)
             .map_err(|e| format!("Token convert error: {}", e))?;
 
         utf8_buffer.extend_from_slice(&token_bytes);
-        
-        if !emit_valid_utf8(&mut utf8_buffer, tx) {
+
+        let (text, stopped) = stops.push(&artifacts.push(&take_valid_utf8(&mut utf8_buffer)));
+        if !text.is_empty() && !tx.send(StreamToken::Token(text)) {
+            break;
+        }
+        if stopped {
+            hit_stop = true;
             break;
         }
 
@@ -873,11 +1579,25 @@ fn run_inference(
 
         ctx.decode(&mut batch)
             .map_err(|e| format!("Decode error: {}", e))?;
+        cached_tokens.push(new_token);
 
         n_decoded += 1;
     }
 
-    flush_utf8_buffer(&mut utf8_buffer, tx);
+    // Whatever is left: an incomplete character is dropped, text held for a
+    // possible artifact or stop sequence is released
+    if !hit_stop {
+        let rest = String::from_utf8(std::mem::take(&mut utf8_buffer)).unwrap_or_default();
+        let rest = artifacts.push(&rest) + &artifacts.finish();
+        let (mut text, stopped) = stops.push(&rest);
+        if !stopped {
+            text.push_str(&stops.finish());
+        }
+        hit_stop = stopped;
+        if !text.is_empty() {
+            tx.send(StreamToken::Token(text));
+        }
+    }
 
     let gen_time = gen_start.elapsed();
     let total_time = inference_start.elapsed();
@@ -887,18 +1607,26 @@ fn run_inference(
             tokens_generated, gen_time,
             tokens_generated as f64 / gen_time.as_secs_f64(),
             total_time,
-            if !hit_eos { " [TRUNCATED]" } else { "" }
+            if hit_stop { " [STOP SEQUENCE]" } else if !hit_eos { " [TRUNCATED]" } else { "" }
         );
     }
 
     // Send appropriate completion signal
-    if hit_eos || stop_signal.load(Ordering::Relaxed) {
-        let _ = tx.send(StreamToken::Done);
+    if hit_eos || hit_stop || stop_signal.load(Ordering::Relaxed) {
+        tx.send(StreamToken::Done);
     } else {
-        // Hit max_tokens without EOS - response is truncated
-        let _ = tx.send(StreamToken::Truncated {
+        // Hit max_tokens without EOS - response is truncated. max_tokens was
+        // clamped to what the prompt left free, so reaching n_ctx means the
+        // context is what ran out
+        let reason = if prompt_len as u32 + params.max_tokens >= n_ctx {
+            StopReason::Context
+        } else {
+            StopReason::MaxTokens
+        };
+        tx.send(StreamToken::Truncated {
             tokens_generated,
             max_tokens: params.max_tokens,
+            reason,
         });
     }
     Ok(())
@@ -908,49 +1636,36 @@ fn run_inference(
 // UTF-8 helpers
 // =============================================================================
 
-#[inline]
-fn flush_utf8_buffer(buffer: &mut Vec<u8>, tx: &Sender<StreamToken>) {
-    if !buffer.is_empty() {
-        if let Ok(s) = String::from_utf8(std::mem::take(buffer)) {
-            if !s.is_empty() {
-                let _ = tx.send(StreamToken::Token(s));
-            }
-        }
+/// Alternatives recorded with each token's logprob
+const TOP_LOGPROBS: usize = 5;
+
+/// Log-probability of `chosen` and of the `top` likeliest tokens (most
+/// likely first), from raw logits: the model's own distribution, before
+/// temperature and the other samplers
+fn logprobs_from_logits(logits: &[f32], chosen: usize, top: usize) -> (f32, Vec<(usize, f32)>) {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let log_sum = max + logits.iter().map(|&logit| (logit - max).exp()).sum::<f32>().ln();
+    let mut ranked: Vec<usize> = (0..logits.len()).collect();
+    let top = top.min(ranked.len());
+    if top > 0 {
+        ranked.select_nth_unstable_by(top - 1, |&a, &b| logits[b].total_cmp(&logits[a]));
     }
+    ranked.truncate(top);
+    ranked.sort_by(|&a, &b| logits[b].total_cmp(&logits[a]));
+    let chosen = logits.get(chosen).map_or(f32::NEG_INFINITY, |&logit| logit - log_sum);
+    (chosen, ranked.into_iter().map(|id| (id, logits[id] - log_sum)).collect())
 }
 
-#[inline]
-fn emit_valid_utf8(buffer: &mut Vec<u8>, tx: &Sender<StreamToken>) -> bool {
-    if let Ok(s) = std::str::from_utf8(buffer) {
-        if !s.is_empty() {
-            if tx.send(StreamToken::Token(s.to_string())).is_err() {
-                return false;
-            }
-        }
-        buffer.clear();
-        return true;
-    }
-    
-    // Find valid UTF-8 prefix
-    let mut valid_len = buffer.len();
-    while valid_len > 0 {
-        if std::str::from_utf8(&buffer[..valid_len]).is_ok() {
-            break;
-        }
-        valid_len -= 1;
-    }
-    
-    if valid_len > 0 {
-        let s = unsafe { std::str::from_utf8_unchecked(&buffer[..valid_len]) };
-        if !s.is_empty() {
-            if tx.send(StreamToken::Token(s.to_string())).is_err() {
-                return false;
-            }
-        }
-        buffer.drain(..valid_len);
-    }
-    
-    true
+/// Longest valid UTF-8 prefix of `buffer`, removed from it; an incomplete
+/// character at the end stays for the next token
+fn take_valid_utf8(buffer: &mut Vec<u8>) -> String {
+    let valid_len = match std::str::from_utf8(buffer) {
+        Ok(_) => buffer.len(),
+        Err(e) => e.valid_up_to(),
+    };
+    let text = String::from_utf8_lossy(&buffer[..valid_len]).into_owned();
+    buffer.drain(..valid_len);
+    text
 }
 
 fn rand_seed() -> u32 {
@@ -979,6 +1694,38 @@ mod tests {
         assert!((params.temperature - 0.7).abs() < 0.001);
     }
 
+    #[test]
+    fn test_logprobs_from_logits() {
+        // ln(1), ln(2), ln(3), ln(4): probabilities 0.1, 0.2, 0.3, 0.4
+        let logits: Vec<f32> = [1.0f32, 2.0, 3.0, 4.0].iter().map(|x| x.ln()).collect();
+        let (chosen, top) = logprobs_from_logits(&logits, 1, 2);
+        assert!((chosen.exp() - 0.2).abs() < 1e-5);
+        assert_eq!(top.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![3, 2]);
+        assert!((top[0].1.exp() - 0.4).abs() < 1e-5);
+        assert_eq!(logprobs_from_logits(&logits, 0, 10).1.len(), 4);
+    }
+
+    #[test]
+    fn test_truncate_prompt_keeps_system_tokens() {
+        let tokens: Vec<u32> = (0..100).collect();
+        let kept = truncate_prompt(&tokens, 10, 30);
+        assert_eq!(kept.len(), 30);
+        assert_eq!(&kept[..10], &tokens[..10]);
+        assert_eq!(&kept[10..], &tokens[80..]);
+        // A system prompt too long for the context leaves room for the question
+        let kept = truncate_prompt(&tokens, 40, 30);
+        assert_eq!(&kept[..22], &tokens[..22]);
+        assert_eq!(kept.last(), Some(&99));
+        assert_eq!(truncate_prompt(&tokens, 10, 200), tokens);
+    }
+
+    #[test]
+    fn test_common_prefix_len() {
+        assert_eq!(common_prefix_len(&[1, 2, 3, 4], &[1, 2, 5]), 2);
+        assert_eq!(common_prefix_len(&[1, 2], &[1, 2, 3]), 2);
+        assert_eq!(common_prefix_len::<i32>(&[], &[1]), 0);
+    }
+
     #[test]
     fn test_pick_context_size() {
         assert_eq!(pick_context_size(1000, 32768), 2048);
@@ -987,10 +1734,128 @@ mod tests {
         assert_eq!(pick_context_size(10000, 32768), 16384);
     }
 
+    #[test]
+    fn test_kv_cache_bytes() {
+        // Llama 3 8B: 32 layers, 4096 embd, 32 heads, 8 KV heads
+        let info = LoadedModelInfo {
+            path: String::new(),
+            vocab_size: 128256,
+            embedding_dim: 4096,
+            context_length: 8192,
+            param_count: 8_000_000_000,
+            size_bytes: 0,
+            n_layer: 32,
+            n_head: 32,
+            n_head_kv: 8,
+            pooling_type: None,
+        };
+        assert_eq!(info.kv_cache_bytes(8192), 1 << 30);
+        assert_eq!(info.kv_cache_bytes(131072), 16 << 30);
+    }
+
+    #[test]
+    fn test_vram_context_cap() {
+        // Llama 3 8B at 5 GB: 1 GB of KV cache per 8K tokens
+        let info = LoadedModelInfo {
+            path: String::new(),
+            vocab_size: 128256,
+            embedding_dim: 4096,
+            context_length: 8192,
+            param_count: 8_000_000_000,
+            size_bytes: 5 << 30,
+            n_layer: 32,
+            n_head: 32,
+            n_head_kv: 8,
+            pooling_type: None,
+        };
+        assert_eq!(vram_context_cap(&info, 99, Some(8.0)), Some(16384));
+        assert_eq!(vram_context_cap(&info, 99, Some(24.0)), Some(131072));
+        // Even the smallest context spills: keep it rather than nothing
+        assert_eq!(vram_context_cap(&info, 99, Some(4.0)), Some(2048));
+        assert_eq!(vram_context_cap(&info, 0, Some(8.0)), None);
+        assert_eq!(vram_context_cap(&info, 99, None), None);
+    }
+
     #[test]
     fn test_unload_without_model() {
         let mut engine = LlamaEngine::new();
         engine.unload_model();
         assert!(!engine.is_model_loaded());
     }
+
+    #[test]
+    fn test_count_tokens_without_model() {
+        let engine = LlamaEngine::new();
+        assert!(matches!(engine.count_tokens("hello"), Err(EngineError::BackendNotInitialized)));
+    }
+
+    /// Engine talking to a fake worker thread that answers `Embed` with
+    /// `reply`, as if a model with `pooling_type` were loaded
+    fn engine_with_fake_worker(
+        pooling_type: Option<u32>,
+        reply: impl Fn(&[String]) -> Result<Vec<Vec<f32>>, EngineError> + Send + 'static,
+    ) -> LlamaEngine {
+        let (command_tx, command_rx) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            while let Ok(command) = command_rx.recv() {
+                match command {
+                    WorkerCommand::Embed { texts, response_tx } => {
+                        let _ = response_tx.send(reply(&texts));
+                    }
+                    WorkerCommand::Shutdown => break,
+                    _ => {}
+                }
+            }
+        });
+        let mut engine = LlamaEngine::new();
+        engine.command_tx = Some(command_tx);
+        engine.worker_handle = Some(handle);
+        engine.initialized = true;
+        engine.model_loaded = true;
+        engine.model_info = Some(LoadedModelInfo {
+            path: "/models/embed.gguf".to_string(),
+            vocab_size: 30522,
+            embedding_dim: 2,
+            context_length: 512,
+            param_count: 0,
+            size_bytes: 0,
+            n_layer: 12,
+            n_head: 12,
+            n_head_kv: 12,
+            pooling_type,
+        });
+        engine
+    }
+
+    #[tokio::test]
+    async fn test_embed_through_worker() {
+        let engine = engine_with_fake_worker(Some(1), |texts| {
+            Ok(texts.iter().map(|t| normalize(&[t.len() as f32, 1.0])).collect())
+        });
+        let vectors = engine.embed(vec!["a".to_string(), "abc".to_string()]).await.unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0], normalize(&[1.0, 1.0]));
+        assert_eq!(vectors[1], normalize(&[3.0, 1.0]));
+        assert!(engine.embed(Vec::new()).await.unwrap().is_empty());
+
+        let failing = engine_with_fake_worker(Some(2), |_| Err(EngineError::Inference("decode failed".to_string())));
+        assert!(matches!(failing.embed(vec!["x".to_string()]).await, Err(EngineError::Inference(_))));
+    }
+
+    #[tokio::test]
+    async fn test_embed_needs_embedding_model() {
+        for pooling_type in [None, Some(0), Some(4)] {
+            let engine = engine_with_fake_worker(pooling_type, |_| Ok(Vec::new()));
+            assert!(matches!(engine.embed(vec!["x".to_string()]).await, Err(EngineError::Inference(_))));
+        }
+        let engine = LlamaEngine::new();
+        assert!(matches!(engine.embed(vec!["x".to_string()]).await, Err(EngineError::BackendNotInitialized)));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let v = normalize(&[3.0, 4.0]);
+        assert!((v[0] - 0.6).abs() < 1e-6 && (v[1] - 0.8).abs() < 1e-6);
+        assert_eq!(normalize(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
 }
@@ -0,0 +1,180 @@
+//! Scripted inference engine
+//!
+//! `MockEngine` streams pre-written replies instead of running a model, so
+//! the agent loop can be exercised without a GPU or a model file. Every
+//! prompt it receives is recorded for assertions.
+//!
+//! Only built for tests and with the `mock` feature.
+
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use crate::inference::engine::{EngineError, GenerationParams, InferenceBackend};
+use crate::inference::streaming::StreamToken;
+use crate::types::message::{Message as ChatMessage, StopReason};
+
+/// One scripted generation
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockReply {
+    /// Streams the text, then `Done`
+    Text(String),
+    /// Streams the text, then `Truncated` as if max_tokens was hit
+    Truncated(String),
+    /// Streams an error token
+    Error(String),
+}
+
+impl MockReply {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+}
+
+/// Inference engine replaying scripted replies in order
+#[derive(Debug, Default)]
+pub struct MockEngine {
+    replies: Mutex<VecDeque<MockReply>>,
+    prompts: Mutex<Vec<Vec<ChatMessage>>>,
+}
+
+impl MockEngine {
+    pub fn new(replies: impl IntoIterator<Item = MockReply>) -> Self {
+        Self {
+            replies: Mutex::new(replies.into_iter().collect()),
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Prompts received so far, one entry per generation
+    pub fn prompts(&self) -> Vec<Vec<ChatMessage>> {
+        self.prompts.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Scripted replies not consumed yet
+    pub fn remaining(&self) -> usize {
+        self.replies.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+/// Split text into word-sized tokens, keeping whitespace attached
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() && !current.trim().is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+impl InferenceBackend for MockEngine {
+    fn generate_stream_messages(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        let prompt_words: Vec<u32> = messages.iter().map(|m| tokenize(&m.content).len() as u32).collect();
+        self.prompts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(messages);
+        let reply = self
+            .replies
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+            .ok_or_else(|| EngineError::Inference("MockEngine: no scripted reply left".into()))?;
+
+        let (tx, rx) = mpsc::channel();
+        let (text, last) = match reply {
+            MockReply::Text(text) => (text, StreamToken::Done),
+            MockReply::Truncated(text) => {
+                let tokens_generated = tokenize(&text).len() as u32;
+                (
+                    text,
+                    StreamToken::Truncated {
+                        tokens_generated,
+                        max_tokens: params.max_tokens,
+                        reason: StopReason::MaxTokens,
+                    },
+                )
+            }
+            MockReply::Error(error) => (String::new(), StreamToken::Error(error)),
+        };
+        // The receiver is returned below, so these sends cannot fail.
+        // Words stand in for tokens: one progress step per prompt message
+        let total: u32 = prompt_words.iter().sum();
+        let mut processed = 0;
+        for words in prompt_words {
+            processed += words;
+            let _ = tx.send(StreamToken::Progress { processed, total });
+        }
+        for token in tokenize(&text) {
+            let _ = tx.send(StreamToken::Token(token));
+        }
+        let _ = tx.send(last);
+        Ok((rx, Arc::new(AtomicBool::new(false))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::message::Role;
+
+    #[test]
+    fn test_mock_engine_streams_script() {
+        let engine = MockEngine::new([
+            MockReply::text("Hello there, world"),
+            MockReply::Truncated("cut off".into()),
+        ]);
+        let prompt = vec![ChatMessage::new(Role::User, "hi")];
+
+        let (rx, _) = engine
+            .generate_stream_messages(prompt.clone(), GenerationParams::default())
+            .unwrap();
+        let tokens: Vec<StreamToken> = rx.iter().collect();
+        let text: String = tokens.iter().filter_map(|t| t.as_token()).collect();
+        assert_eq!(text, "Hello there, world");
+        assert_eq!(tokens.len(), 5);
+        assert!(tokens[4].is_done());
+
+        let (rx, _) = engine
+            .generate_stream_messages(prompt, GenerationParams::default())
+            .unwrap();
+        assert!(rx.iter().last().unwrap().is_truncated());
+
+        assert_eq!(engine.prompts().len(), 2);
+        assert_eq!(engine.remaining(), 0);
+        assert!(engine
+            .generate_stream_messages(Vec::new(), GenerationParams::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_progress_precedes_tokens() {
+        let engine = MockEngine::new([MockReply::text("Sure, here it is")]);
+        let prompt = vec![
+            ChatMessage::new(Role::System, "Be brief"),
+            ChatMessage::new(Role::User, "Show me the file"),
+        ];
+        let (rx, _) = engine.generate_stream_messages(prompt, GenerationParams::default()).unwrap();
+        let tokens: Vec<StreamToken> = rx.iter().collect();
+
+        // Progress*, then Token*, then Done
+        let progress = tokens.iter().take_while(|t| matches!(t, StreamToken::Progress { .. })).count();
+        let text = tokens[progress..].iter().take_while(|t| t.is_token()).count();
+        assert_eq!(progress, 2);
+        assert_eq!(text, 4);
+        assert_eq!(tokens.len(), progress + text + 1);
+        assert!(tokens.last().unwrap().is_done());
+        assert!(matches!(tokens[progress - 1], StreamToken::Progress { processed: 6, total: 6 }));
+    }
+}
@@ -1,12 +1,22 @@
 //! LLM inference engine
 //!
-//! This module handles all interaction with llama-cpp for model loading and inference.
+//! This module handles all interaction with llama-cpp for model loading and inference,
+//! and the OpenAI-compatible remote backend used instead of it.
 
+pub mod artifacts;
 pub mod engine;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub mod model;
+pub mod remote;
 pub mod streaming;
+pub mod truncation;
 
 // Re-export main types for convenience
-pub use engine::{EngineError, GenerationParams, LlamaEngine, LoadedModelInfo};
+pub use engine::{Embedder, EngineError, GenerationParams, InferenceBackend, LlamaEngine, LoadedModelInfo, SharedEngine};
+#[cfg(any(test, feature = "mock"))]
+pub use mock::{MockEngine, MockReply};
 pub use model::{validate_gguf, GgufMetadata, ModelError, GGUF_MAGIC};
-pub use streaming::StreamToken;
+pub use remote::OpenAiCompatBackend;
+pub use streaming::{collect_text, StreamToken};
+pub use truncation::TruncationStrategy;
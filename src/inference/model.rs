@@ -3,7 +3,7 @@
 //! Handles model loading, unloading, and configuration.
 
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 use thiserror::Error;
 
@@ -91,6 +91,86 @@ pub fn validate_gguf<P: AsRef<Path>>(path: P) -> Result<GgufMetadata, ModelError
     })
 }
 
+fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> std::io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_gguf_string(reader: &mut impl Read) -> std::io::Result<String> {
+    let len = read_u64(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Skip a metadata value of GGUF type `value_type`
+fn skip_gguf_value(reader: &mut impl Read, value_type: u32) -> std::io::Result<()> {
+    let size = match value_type {
+        0 | 1 | 7 => 1,
+        2 | 3 => 2,
+        4..=6 => 4,
+        10..=12 => 8,
+        8 => read_u64(reader)?,
+        9 => {
+            let element_type = read_u32(reader)?;
+            let count = read_u64(reader)?;
+            for _ in 0..count {
+                skip_gguf_value(reader, element_type)?;
+            }
+            return Ok(());
+        }
+        other => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Unknown GGUF value type {}", other),
+            ))
+        }
+    };
+    std::io::copy(&mut reader.take(size), &mut std::io::sink())?;
+    Ok(())
+}
+
+/// First u32 metadata value whose key ends with `suffix`
+fn read_u32_metadata<P: AsRef<Path>>(path: P, suffix: &str) -> Option<u32> {
+    let metadata = validate_gguf(&path).ok()?;
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    reader.seek(SeekFrom::Start(24)).ok()?;
+    for _ in 0..metadata.metadata_kv_count {
+        let key = read_gguf_string(&mut reader).ok()?;
+        let value_type = read_u32(&mut reader).ok()?;
+        if key.ends_with(suffix) && value_type == 4 {
+            return read_u32(&mut reader).ok();
+        }
+        skip_gguf_value(&mut reader, value_type).ok()?;
+    }
+    None
+}
+
+/// Number of transformer layers (`{arch}.block_count`) read from the GGUF
+/// metadata, without loading the model
+pub fn read_block_count<P: AsRef<Path>>(path: P) -> Option<u32> {
+    read_u32_metadata(path, ".block_count")
+}
+
+/// Context length the model was trained on (`{arch}.context_length`), read
+/// without loading the model
+pub fn read_context_length<P: AsRef<Path>>(path: P) -> Option<u32> {
+    read_u32_metadata(path, ".context_length")
+}
+
+/// How the model pools token states into one vector (`{arch}.pooling_type`:
+/// 1 mean, 2 CLS, 3 last, 4 rank); only embedding models set it
+pub fn read_pooling_type<P: AsRef<Path>>(path: P) -> Option<u32> {
+    read_u32_metadata(path, ".pooling_type")
+}
+
 /// Checks if a file appears to be a GGUF model file based on extension and magic bytes.
 pub fn is_gguf_file<P: AsRef<Path>>(path: P) -> bool {
     let path = path.as_ref();
@@ -137,6 +217,39 @@ mod tests {
         assert_eq!(metadata.metadata_kv_count, 5);
     }
 
+    #[test]
+    fn test_read_block_count() {
+        fn write_string(file: &mut NamedTempFile, s: &str) {
+            file.write_all(&(s.len() as u64).to_le_bytes()).unwrap();
+            file.write_all(s.as_bytes()).unwrap();
+        }
+
+        let mut file = tempfile::Builder::new().suffix(".gguf").tempfile().unwrap();
+        file.write_all(&GGUF_MAGIC.to_le_bytes()).unwrap();
+        file.write_all(&3u32.to_le_bytes()).unwrap();
+        file.write_all(&0u64.to_le_bytes()).unwrap();
+        file.write_all(&3u64.to_le_bytes()).unwrap();
+        // general.architecture = "llama"
+        write_string(&mut file, "general.architecture");
+        file.write_all(&8u32.to_le_bytes()).unwrap();
+        write_string(&mut file, "llama");
+        // tokenizer.ggml.tokens = ["a", "b"]
+        write_string(&mut file, "tokenizer.ggml.tokens");
+        file.write_all(&9u32.to_le_bytes()).unwrap();
+        file.write_all(&8u32.to_le_bytes()).unwrap();
+        file.write_all(&2u64.to_le_bytes()).unwrap();
+        write_string(&mut file, "a");
+        write_string(&mut file, "b");
+        // llama.block_count = 32
+        write_string(&mut file, "llama.block_count");
+        file.write_all(&4u32.to_le_bytes()).unwrap();
+        file.write_all(&32u32.to_le_bytes()).unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(read_block_count(file.path()), Some(32));
+        assert_eq!(read_block_count(create_test_gguf().path()), None);
+    }
+
     #[test]
     fn test_validate_gguf_invalid_magic() {
         let mut file = tempfile::Builder::new().suffix(".gguf").tempfile().unwrap();
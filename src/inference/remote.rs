@@ -0,0 +1,424 @@
+//! OpenAI-compatible remote backend
+//!
+//! Runs generations on llama-server, Ollama or any server exposing
+//! `/v1/chat/completions`, typically a bigger machine on the LAN. Nothing
+//! is loaded locally: "loading" a model checks that `/v1/models` lists it
+//! and makes it the one requests use. Each generation streams on its own
+//! thread, so the receiver works like the local engine's.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::inference::engine::{EngineError, GenerationParams, InferenceBackend, LoadedModelInfo};
+use crate::inference::streaming::{token_channel, StreamToken, TokenSender};
+use crate::system::certificates::describe_error;
+use crate::system::proxy::{client_builder, ProxyClient};
+use crate::types::message::{Message as ChatMessage, Role as ChatRole, StopReason};
+
+/// How long to wait for the server to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a streaming request checks its stop flag while the server is
+/// silent (prompt evaluation)
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+/// Generations on a remote OpenAI-compatible server
+pub struct OpenAiCompatBackend {
+    base_url: String,
+    api_key: String,
+    /// Model id sent with each request
+    model: Option<String>,
+}
+
+impl OpenAiCompatBackend {
+    pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            api_key: api_key.into(),
+            model: None,
+        }
+    }
+
+    /// Use `model` without checking that the server lists it
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+}
+
+/// `path` under the server's `/v1`, whether or not `base_url` includes it
+fn endpoint(base_url: &str, path: &str) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    let base = base.strip_suffix("/v1").unwrap_or(base);
+    format!("{}/v1/{}", base, path)
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    client_builder(ProxyClient::RemoteBackend)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| describe_error(&e))
+}
+
+fn authorize(request: reqwest::RequestBuilder, api_key: &str) -> reqwest::RequestBuilder {
+    if api_key.trim().is_empty() {
+        request
+    } else {
+        request.bearer_auth(api_key.trim())
+    }
+}
+
+/// Ids of the models the server at `base_url` serves, sorted
+pub async fn list_models(base_url: &str, api_key: &str) -> Result<Vec<String>, EngineError> {
+    if base_url.trim().is_empty() {
+        return Err(EngineError::BackendInit("No server URL set".to_string()));
+    }
+    let client = http_client().map_err(EngineError::BackendInit)?;
+    let response = authorize(client.get(endpoint(base_url, "models")), api_key)
+        .send()
+        .await
+        .map_err(|e| EngineError::BackendInit(describe_error(&e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(EngineError::BackendInit(format!("{}: {}", status, body.trim())));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| EngineError::BackendInit(describe_error(&e)))?;
+    Ok(parse_models(&body))
+}
+
+fn parse_models(body: &Value) -> Vec<String> {
+    let mut ids: Vec<String> = body["data"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m["id"].as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Body of a streaming `/v1/chat/completions` request
+///
+/// `top_k`, `min_p` and `grammar` are llama-server extensions that strict
+/// servers may reject, so they are only sent when they change something:
+/// sampling values other than the defaults (which are llama-server's too),
+/// a grammar when there is one. `repeat_penalty` is never sent, since the
+/// local engine doesn't apply it either.
+fn request_body(model: &str, messages: &[ChatMessage], params: &GenerationParams) -> Value {
+    let messages: Vec<Value> = messages
+        .iter()
+        .map(|m| {
+            let role = match m.role {
+                ChatRole::System => "system",
+                ChatRole::User => "user",
+                ChatRole::Assistant => "assistant",
+            };
+            json!({ "role": role, "content": m.content })
+        })
+        .collect();
+    let mut body = json!({
+        "model": model,
+        "messages": messages,
+        "stream": true,
+        "max_tokens": params.max_tokens,
+        "temperature": params.temperature,
+        "top_p": params.top_p,
+        "presence_penalty": params.presence_penalty,
+        "frequency_penalty": params.frequency_penalty,
+    });
+    let defaults = GenerationParams::default();
+    if params.top_k != defaults.top_k {
+        body["top_k"] = json!(params.top_k);
+    }
+    if params.min_p != defaults.min_p {
+        body["min_p"] = json!(params.min_p);
+    }
+    if params.seed != 0 {
+        body["seed"] = json!(params.seed);
+    }
+    if !params.stop_sequences.is_empty() {
+        body["stop"] = json!(params.stop_sequences);
+    }
+    if let Some(grammar) = &params.grammar {
+        body["grammar"] = json!(grammar);
+    }
+    body
+}
+
+/// One server-sent event of a streaming completion
+#[derive(Debug, Clone, PartialEq)]
+enum SseEvent {
+    /// Text of a chunk and the finish reason it carries, if any
+    Chunk { text: String, finish_reason: Option<String> },
+    /// `data: [DONE]`
+    Done,
+}
+
+/// Event on an SSE `line`; `None` for comments, blank lines and other fields
+fn parse_sse_line(line: &str) -> Result<Option<SseEvent>, String> {
+    let Some(data) = line.trim_end_matches(['\r', '\n']).strip_prefix("data:") else {
+        return Ok(None);
+    };
+    let data = data.trim();
+    if data == "[DONE]" {
+        return Ok(Some(SseEvent::Done));
+    }
+    let chunk: Value = serde_json::from_str(data).map_err(|e| format!("Invalid stream chunk: {}", e))?;
+    if let Some(error) = chunk.get("error") {
+        let message = error["message"].as_str().map(String::from).unwrap_or_else(|| error.to_string());
+        return Err(message);
+    }
+    let choice = &chunk["choices"][0];
+    Ok(Some(SseEvent::Chunk {
+        text: choice["delta"]["content"].as_str().unwrap_or_default().to_string(),
+        finish_reason: choice["finish_reason"].as_str().map(String::from),
+    }))
+}
+
+/// Stream a completion into `token_tx`; the last token is sent by the caller
+/// from the returned finish reason
+async fn stream_completion(
+    url: String,
+    api_key: String,
+    body: Value,
+    token_tx: &TokenSender,
+    stop_signal: &AtomicBool,
+) -> Result<(u32, Option<String>), String> {
+    let client = http_client()?;
+    let mut response = authorize(client.post(url), &api_key)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| describe_error(&e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("{}: {}", status, body.trim()));
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut generated = 0u32;
+    let mut finish_reason = None;
+    loop {
+        if stop_signal.load(Ordering::Relaxed) {
+            return Ok((generated, None));
+        }
+        let chunk = match tokio::time::timeout(STOP_POLL, response.chunk()).await {
+            Err(_) => continue,
+            Ok(chunk) => chunk.map_err(|e| describe_error(&e))?,
+        };
+        let Some(chunk) = chunk else {
+            return Ok((generated, finish_reason));
+        };
+        buffer.extend_from_slice(&chunk);
+        // Lines are split on bytes so multi-byte characters split across
+        // chunks are decoded whole
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            match parse_sse_line(&String::from_utf8_lossy(&line))? {
+                None => {}
+                Some(SseEvent::Done) => return Ok((generated, finish_reason)),
+                Some(SseEvent::Chunk { text, finish_reason: reason }) => {
+                    if !text.is_empty() {
+                        generated += 1;
+                        if !token_tx.send(StreamToken::Token(text)) {
+                            return Ok((generated, None));
+                        }
+                    }
+                    if reason.is_some() {
+                        finish_reason = reason;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl InferenceBackend for OpenAiCompatBackend {
+    fn generate_stream_messages(
+        &self,
+        messages: Vec<ChatMessage>,
+        params: GenerationParams,
+    ) -> Result<(Receiver<StreamToken>, Arc<AtomicBool>), EngineError> {
+        let model = self.model.as_ref().ok_or(EngineError::NoModelLoaded)?;
+        let body = request_body(model, &messages, &params);
+        let url = endpoint(&self.base_url, "chat/completions");
+        let api_key = self.api_key.clone();
+        let max_tokens = params.max_tokens;
+
+        let (token_tx, token_rx) = token_channel();
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        let stop = stop_signal.clone();
+        // Its own runtime, so generations also start outside of Tokio
+        // (quick actions)
+        thread::Builder::new()
+            .name("remote-generation".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        token_tx.send(StreamToken::Error(e.to_string()));
+                        return;
+                    }
+                };
+                let result = runtime.block_on(stream_completion(url, api_key, body, &token_tx, &stop));
+                let last = match result {
+                    Ok((tokens_generated, Some(reason))) if reason == "length" => StreamToken::Truncated {
+                        tokens_generated,
+                        max_tokens,
+                        reason: StopReason::MaxTokens,
+                    },
+                    Ok(_) => StreamToken::Done,
+                    Err(e) => StreamToken::Error(e),
+                };
+                token_tx.send(last);
+            })
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+
+        Ok((token_rx, stop_signal))
+    }
+
+    async fn load_model_async(&mut self, model: &str, _gpu_layers: u32) -> Result<LoadedModelInfo, EngineError> {
+        let models = list_models(&self.base_url, &self.api_key)
+            .await
+            .map_err(|e| EngineError::ModelLoad(e.to_string()))?;
+        if !models.iter().any(|m| m == model) {
+            return Err(EngineError::ModelLoad(format!(
+                "{} is not served by {}",
+                model,
+                self.base_url.trim()
+            )));
+        }
+        self.model = Some(model.to_string());
+        tracing::info!("Using remote model {} on {}", model, self.base_url.trim());
+        Ok(LoadedModelInfo {
+            path: model.to_string(),
+            vocab_size: 0,
+            embedding_dim: 0,
+            context_length: 0,
+            param_count: 0,
+            size_bytes: 0,
+            n_layer: 0,
+            n_head: 0,
+            n_head_kv: 0,
+            pooling_type: None,
+        })
+    }
+
+    fn unload_model(&mut self) {
+        self.model = None;
+    }
+
+    fn is_model_loaded(&self) -> bool {
+        self.model.is_some()
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, EngineError> {
+        list_models(&self.base_url, &self.api_key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint() {
+        assert_eq!(endpoint("http://nas:8080", "models"), "http://nas:8080/v1/models");
+        assert_eq!(endpoint(" http://nas:8080/v1/ ", "models"), "http://nas:8080/v1/models");
+        assert_eq!(
+            endpoint("http://nas:11434/", "chat/completions"),
+            "http://nas:11434/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_parse_sse_line() {
+        let line = r#"data: {"choices":[{"index":0,"delta":{"content":"Hel"},"finish_reason":null}]}"#;
+        assert_eq!(
+            parse_sse_line(line),
+            Ok(Some(SseEvent::Chunk { text: "Hel".to_string(), finish_reason: None }))
+        );
+        let line = "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"length\"}]}\r\n";
+        assert_eq!(
+            parse_sse_line(line),
+            Ok(Some(SseEvent::Chunk { text: String::new(), finish_reason: Some("length".to_string()) }))
+        );
+        assert_eq!(parse_sse_line("data: [DONE]"), Ok(Some(SseEvent::Done)));
+        assert_eq!(parse_sse_line(": keep-alive"), Ok(None));
+        assert_eq!(parse_sse_line(""), Ok(None));
+        assert_eq!(
+            parse_sse_line(r#"data: {"error":{"message":"model not found"}}"#),
+            Err("model not found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_request_body() {
+        let params = GenerationParams {
+            stop_sequences: vec!["\nUser:".to_string()],
+            ..GenerationParams::default()
+        };
+        let messages = vec![
+            ChatMessage::new(ChatRole::System, "Be brief"),
+            ChatMessage::new(ChatRole::User, "hi"),
+        ];
+        let body = request_body("qwen2.5:14b", &messages, &params);
+        assert_eq!(body["model"], "qwen2.5:14b");
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "hi");
+        assert_eq!(body["max_tokens"], params.max_tokens);
+        assert_eq!(body["stop"][0], "\nUser:");
+        assert!(body.get("seed").is_none());
+        assert!(body.get("grammar").is_none());
+        // Extensions at their defaults stay out of the body
+        assert!(body.get("top_k").is_none());
+        assert!(body.get("min_p").is_none());
+        assert!(body.get("repeat_penalty").is_none());
+
+        let params = GenerationParams {
+            top_k: 20,
+            min_p: 0.1,
+            grammar: Some("root ::= \"yes\"".to_string()),
+            ..GenerationParams::default()
+        };
+        let body = request_body("qwen2.5:14b", &messages, &params);
+        assert_eq!(body["top_k"], 20);
+        assert!(body["min_p"].as_f64().is_some_and(|min_p| (min_p - 0.1).abs() < 1e-6));
+        assert_eq!(body["grammar"], "root ::= \"yes\"");
+        assert!(body.get("repeat_penalty").is_none());
+    }
+
+    #[test]
+    fn test_parse_models() {
+        let body = json!({ "object": "list", "data": [{ "id": "mistral" }, { "id": "llama3" }, { "object": "model" }] });
+        assert_eq!(parse_models(&body), vec!["llama3", "mistral"]);
+        assert!(parse_models(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_generate_without_model() {
+        let backend = OpenAiCompatBackend::new("http://localhost:8080", "");
+        assert!(!backend.is_model_loaded());
+        assert!(matches!(
+            backend.generate_stream_messages(Vec::new(), GenerationParams::default()),
+            Err(EngineError::NoModelLoaded)
+        ));
+    }
+}
@@ -2,6 +2,15 @@
 //!
 //! Handles token-by-token streaming output from the model.
 
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::types::message::{StopReason, TokenLogprob};
+
 /// Represents a token emitted during streaming inference.
 #[derive(Debug, Clone)]
 pub enum StreamToken {
@@ -10,9 +19,14 @@ pub enum StreamToken {
     /// Generation completed successfully (EOS token reached)
     Done,
     /// Generation hit max_tokens limit without EOS (response may be incomplete)
-    Truncated { tokens_generated: u32, max_tokens: u32 },
+    Truncated { tokens_generated: u32, max_tokens: u32, reason: StopReason },
     /// An error occurred during generation
     Error(String),
+    /// Probabilities of the token just sampled, when requested
+    Logprob(TokenLogprob),
+    /// Prompt evaluation progress, sent after each batch before the first
+    /// token
+    Progress { processed: u32, total: u32 },
 }
 
 impl StreamToken {
@@ -53,6 +67,164 @@ impl StreamToken {
     }
 }
 
+/// Cuts a token stream at the first stop sequence
+///
+/// Stop sequences can straddle tokens (`"\nUs"` then `"er:"`), so text that
+/// could be the start of one is held back until the next token settles it.
+#[derive(Debug, Clone, Default)]
+pub struct StopMatcher {
+    stops: Vec<String>,
+    held: String,
+}
+
+impl StopMatcher {
+    pub fn new(stops: &[String]) -> Self {
+        Self {
+            stops: stops.iter().filter(|s| !s.is_empty()).cloned().collect(),
+            held: String::new(),
+        }
+    }
+
+    /// Text safe to emit after `text`, and whether a stop sequence was hit;
+    /// the stop sequence and anything after it are dropped
+    pub fn push(&mut self, text: &str) -> (String, bool) {
+        if self.stops.is_empty() {
+            return (text.to_string(), false);
+        }
+        self.held.push_str(text);
+        if let Some(at) = self.stops.iter().filter_map(|stop| self.held.find(stop.as_str())).min() {
+            let emitted = self.held[..at].to_string();
+            self.held.clear();
+            return (emitted, true);
+        }
+        // Hold back the longest suffix that starts a stop sequence
+        let split = self
+            .held
+            .char_indices()
+            .map(|(i, _)| i)
+            .find(|&i| self.stops.iter().any(|stop| stop.starts_with(&self.held[i..])))
+            .unwrap_or(self.held.len());
+        let emitted = self.held[..split].to_string();
+        self.held.drain(..split);
+        (emitted, false)
+    }
+
+    /// Text still held when the generation ends without a stop
+    pub fn finish(&mut self) -> String {
+        std::mem::take(&mut self.held)
+    }
+}
+
+/// Tokens buffered between the worker and the UI before the worker waits
+pub const TOKEN_CHANNEL_CAPACITY: usize = 1024;
+
+/// How long the worker waits on a full channel before giving up on a
+/// reader that stopped draining it
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+static GENERATIONS: AtomicU64 = AtomicU64::new(0);
+static BACKPRESSURE_WAITS: AtomicU64 = AtomicU64::new(0);
+static STALLED: AtomicU64 = AtomicU64::new(0);
+static ORPHANED: AtomicU64 = AtomicU64::new(0);
+
+/// Counters of the token channels since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ChannelMetrics {
+    pub generations: u64,
+    /// Sends that found the channel full and had to wait
+    pub backpressure_waits: u64,
+    /// Generations aborted because the reader stopped draining
+    pub stalled: u64,
+    /// Generations aborted because the reader was dropped
+    pub orphaned: u64,
+}
+
+pub fn channel_metrics() -> ChannelMetrics {
+    ChannelMetrics {
+        generations: GENERATIONS.load(Ordering::Relaxed),
+        backpressure_waits: BACKPRESSURE_WAITS.load(Ordering::Relaxed),
+        stalled: STALLED.load(Ordering::Relaxed),
+        orphaned: ORPHANED.load(Ordering::Relaxed),
+    }
+}
+
+/// Bounded channel carrying one generation's tokens to the UI
+pub fn token_channel() -> (TokenSender, Receiver<StreamToken>) {
+    token_channel_with(TOKEN_CHANNEL_CAPACITY, STALL_TIMEOUT)
+}
+
+fn token_channel_with(capacity: usize, stall_timeout: Duration) -> (TokenSender, Receiver<StreamToken>) {
+    GENERATIONS.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = mpsc::sync_channel(capacity);
+    (TokenSender { tx, stall_timeout, gave_up: Cell::new(false) }, rx)
+}
+
+/// Worker end of a token channel, with a watchdog
+///
+/// A full channel makes the worker wait (backpressure) rather than grow
+/// without bound; a reader that is dropped, or that doesn't drain anything
+/// for `STALL_TIMEOUT`, makes every later send fail so the generation ends.
+pub struct TokenSender {
+    tx: SyncSender<StreamToken>,
+    stall_timeout: Duration,
+    gave_up: Cell<bool>,
+}
+
+impl TokenSender {
+    /// Send `token`, waiting while the channel is full; false once the
+    /// reader is gone or stalled, when the generation should stop
+    pub fn send(&self, token: StreamToken) -> bool {
+        if self.gave_up.get() {
+            return false;
+        }
+        let mut token = token;
+        let mut waiting_since = None;
+        loop {
+            match self.tx.try_send(token) {
+                Ok(()) => return true,
+                Err(TrySendError::Disconnected(_)) => {
+                    let metrics = channel_metrics();
+                    ORPHANED.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!("Token reader dropped, generation aborted ({:?})", metrics);
+                    self.gave_up.set(true);
+                    return false;
+                }
+                Err(TrySendError::Full(rejected)) => token = rejected,
+            }
+            let since = *waiting_since.get_or_insert_with(|| {
+                BACKPRESSURE_WAITS.fetch_add(1, Ordering::Relaxed);
+                Instant::now()
+            });
+            if since.elapsed() >= self.stall_timeout {
+                STALLED.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    "Token channel stalled for {:?}, generation aborted ({:?})",
+                    self.stall_timeout,
+                    channel_metrics()
+                );
+                self.gave_up.set(true);
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+/// Whole text of a generation, blocking until it ends; a truncated reply
+/// is returned as is
+pub fn collect_text(rx: std::sync::mpsc::Receiver<StreamToken>) -> Result<String, String> {
+    let mut text = String::new();
+    while let Ok(token) = rx.recv() {
+        match token {
+            StreamToken::Token(t) => text.push_str(&t),
+            StreamToken::Done | StreamToken::Truncated { .. } => break,
+            StreamToken::Error(e) => return Err(e),
+            StreamToken::Logprob(_) | StreamToken::Progress { .. } => {}
+        }
+    }
+    Ok(text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +248,66 @@ mod tests {
         assert!(error.is_error());
         assert_eq!(error.as_error(), Some("test error"));
     }
+
+    /// Feed a fake token stream, returning the emitted text and whether it stopped
+    fn run_stops(stops: &[&str], tokens: &[&str]) -> (String, bool) {
+        let stops: Vec<String> = stops.iter().map(|s| s.to_string()).collect();
+        let mut matcher = StopMatcher::new(&stops);
+        let mut out = String::new();
+        for token in tokens {
+            let (text, stopped) = matcher.push(token);
+            out.push_str(&text);
+            if stopped {
+                return (out, true);
+            }
+        }
+        out.push_str(&matcher.finish());
+        (out, false)
+    }
+
+    #[test]
+    fn test_stop_sequences() {
+        assert_eq!(run_stops(&[], &["Hi", " there"]), ("Hi there".to_string(), false));
+        assert_eq!(run_stops(&["</s>"], &["Done.", "</s>", "junk"]), ("Done.".to_string(), true));
+        // Split across tokens
+        assert_eq!(run_stops(&["\nUser:"], &["Sure", ".\nUs", "er:", " hi"]), ("Sure.".to_string(), true));
+        assert_eq!(run_stops(&["<|im_start|>"], &["ok<|im", "_st", "art|>user"]), ("ok".to_string(), true));
+        // A partial match that doesn't complete is released
+        assert_eq!(run_stops(&["\nUser:"], &["a\nUs", "ually"]), ("a\nUsually".to_string(), false));
+        assert_eq!(run_stops(&["\nUser:"], &["end\nUs"]), ("end\nUs".to_string(), false));
+        // Earliest stop wins, multi-byte text is held on char boundaries
+        assert_eq!(run_stops(&["B", "éA"], &["xé", "A B"]), ("x".to_string(), true));
+    }
+
+    #[test]
+    fn test_token_sender_watchdog() {
+        let (tx, rx) = token_channel_with(1, Duration::from_millis(20));
+        assert!(tx.send(StreamToken::Token("a".into())));
+        // Nobody drains: the next send waits, then gives up for good
+        assert!(!tx.send(StreamToken::Token("b".into())));
+        assert_eq!(rx.try_recv().ok().and_then(|t| t.as_token().map(str::to_string)), Some("a".into()));
+        assert!(!tx.send(StreamToken::Done));
+
+        let (tx, rx) = token_channel_with(1, Duration::from_secs(5));
+        drop(rx);
+        assert!(!tx.send(StreamToken::Done));
+        let metrics = channel_metrics();
+        assert!(metrics.stalled >= 1 && metrics.orphaned >= 1 && metrics.backpressure_waits >= 1);
+    }
+
+    #[test]
+    fn test_collect_text() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(StreamToken::Progress { processed: 8, total: 8 }).unwrap();
+        for token in ["Hel", "lo"] {
+            tx.send(StreamToken::Token(token.to_string())).unwrap();
+        }
+        tx.send(StreamToken::Done).unwrap();
+        tx.send(StreamToken::Token("ignored".to_string())).unwrap();
+        assert_eq!(collect_text(rx), Ok("Hello".to_string()));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(StreamToken::Error("oom".to_string())).unwrap();
+        assert_eq!(collect_text(rx), Err("oom".to_string()));
+    }
 }
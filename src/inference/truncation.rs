@@ -0,0 +1,184 @@
+//! Prompt truncation at the message level
+//!
+//! When the prompt doesn't fit in the context, whole messages are dropped
+//! before tokenization rather than cutting the oldest tokens, which could
+//! remove the system prompt or the user's question.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+
+/// What to do with a prompt longer than the context allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Keep the system prompt and the last user message, drop the oldest
+    /// other messages first
+    #[default]
+    ProtectEnds,
+    /// Keep the system prompt, the start and the end of the conversation,
+    /// drop messages from the middle outwards
+    MiddleOut,
+    /// Refuse to generate
+    FailFast,
+}
+
+impl TruncationStrategy {
+    pub const ALL: [TruncationStrategy; 3] = [
+        TruncationStrategy::ProtectEnds,
+        TruncationStrategy::MiddleOut,
+        TruncationStrategy::FailFast,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            TruncationStrategy::ProtectEnds => "protect_ends",
+            TruncationStrategy::MiddleOut => "middle_out",
+            TruncationStrategy::FailFast => "fail_fast",
+        }
+    }
+
+    pub fn label(self, is_en: bool) -> &'static str {
+        match (self, is_en) {
+            (TruncationStrategy::ProtectEnds, true) => "Drop oldest, keep system prompt and question",
+            (TruncationStrategy::ProtectEnds, false) => "Retirer les plus anciens, garder prompt systeme et question",
+            (TruncationStrategy::MiddleOut, true) => "Drop from the middle",
+            (TruncationStrategy::MiddleOut, false) => "Retirer depuis le milieu",
+            (TruncationStrategy::FailFast, true) => "Fail without generating",
+            (TruncationStrategy::FailFast, false) => "Echouer sans generer",
+        }
+    }
+}
+
+/// Order in which the messages may be dropped; the leading system messages
+/// and the last user message never are
+fn drop_order(messages: &[ChatMessage], strategy: TruncationStrategy) -> Vec<usize> {
+    let system_end = messages
+        .iter()
+        .position(|m| m.role != ChatRole::System)
+        .unwrap_or(messages.len());
+    let last_user = messages.iter().rposition(|m| m.role == ChatRole::User);
+    let mut droppable: Vec<usize> = (system_end..messages.len())
+        .filter(|&i| Some(i) != last_user)
+        .collect();
+    if strategy == TruncationStrategy::MiddleOut {
+        let middle = (system_end + messages.len()) as f64 / 2.0;
+        droppable.sort_by(|&a, &b| (a as f64 - middle).abs().total_cmp(&(b as f64 - middle).abs()));
+    }
+    droppable
+}
+
+/// Drop messages until the prompt fits in `budget` tokens
+///
+/// `total` is the token count of the whole prompt and `message_tokens` the
+/// share of one message in it: each message is counted once and its share
+/// subtracted when it is dropped, rather than counting the prompt again.
+/// Errors when the strategy is fail-fast or when the protected messages
+/// alone don't fit.
+pub fn fit_messages(
+    messages: Vec<ChatMessage>,
+    strategy: TruncationStrategy,
+    budget: usize,
+    total: usize,
+    message_tokens: impl Fn(&ChatMessage) -> usize,
+) -> Result<Vec<ChatMessage>, String> {
+    let mut tokens = total;
+    if tokens <= budget {
+        return Ok(messages);
+    }
+    if strategy == TruncationStrategy::FailFast {
+        return Err(format!(
+            "Prompt too long: {} tokens for {} available (truncation disabled)",
+            tokens, budget
+        ));
+    }
+
+    let order = drop_order(&messages, strategy);
+    let mut kept = vec![true; messages.len()];
+    let mut dropped = 0;
+    for index in order {
+        if tokens <= budget {
+            break;
+        }
+        kept[index] = false;
+        dropped += 1;
+        tokens = tokens.saturating_sub(message_tokens(&messages[index]));
+    }
+    if tokens > budget {
+        return Err(format!(
+            "Prompt too long: {} tokens for {} available even with only the system prompt and the last question",
+            tokens, budget
+        ));
+    }
+    tracing::warn!(
+        "Prompt truncated ({:?}): {} message(s) dropped, ~{} tokens",
+        strategy,
+        dropped,
+        tokens
+    );
+    Ok(messages
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(message, keep)| keep.then_some(message))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<ChatMessage> {
+        let mut messages = vec![ChatMessage::new(ChatRole::System, "s".repeat(10))];
+        for i in 0..4 {
+            messages.push(ChatMessage::new(ChatRole::User, format!("u{}", i).repeat(5)));
+            messages.push(ChatMessage::new(ChatRole::Assistant, format!("a{}", i).repeat(5)));
+        }
+        messages.push(ChatMessage::new(ChatRole::User, "q".repeat(10)));
+        messages
+    }
+
+    fn length(message: &ChatMessage) -> usize {
+        message.content.len()
+    }
+
+    fn fit(strategy: TruncationStrategy, budget: usize) -> Result<Vec<ChatMessage>, String> {
+        let messages = conversation();
+        let total = messages.iter().map(length).sum();
+        fit_messages(messages, strategy, budget, total, length)
+    }
+
+    fn firsts(messages: &[ChatMessage]) -> String {
+        messages.iter().map(|m| &m.content[..2]).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn test_protect_ends() {
+        // 100 chars; 60 fit the system prompt, the question and 4 messages
+        let fitted = fit(TruncationStrategy::ProtectEnds, 60).unwrap();
+        assert_eq!(firsts(&fitted), "ss u2 a2 u3 a3 qq");
+        let untouched = fit(TruncationStrategy::ProtectEnds, 100).unwrap();
+        assert_eq!(untouched.len(), 10);
+        assert!(fit(TruncationStrategy::ProtectEnds, 15).is_err());
+        assert!(fit(TruncationStrategy::FailFast, 60).is_err());
+    }
+
+    #[test]
+    fn test_each_message_counted_once() {
+        let counted = std::cell::Cell::new(0);
+        let messages = conversation();
+        let total = messages.iter().map(length).sum();
+        fit_messages(messages, TruncationStrategy::ProtectEnds, 60, total, |m| {
+            counted.set(counted.get() + 1);
+            length(m)
+        })
+        .unwrap();
+        // Four messages dropped, four counted
+        assert_eq!(counted.get(), 4);
+    }
+
+    #[test]
+    fn test_middle_out() {
+        let fitted = fit(TruncationStrategy::MiddleOut, 60).unwrap();
+        assert_eq!(firsts(&fitted), "ss u0 a0 u1 a3 qq");
+    }
+}
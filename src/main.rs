@@ -7,21 +7,53 @@ use tracing::info;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 use clawrs::app::App;
+use clawrs::system::quick_actions::{self, QuickAction};
+use clawrs::system::single_instance::{self, LaunchRequest};
 
 fn main() {
+    // Before anything resolves the data directory
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    clawrs::storage::portable::take_flag(&mut args);
+
+    // Quick actions print their answer and exit; logs go to stderr
+    if let Some(action) = QuickAction::from_args(&args) {
+        tracing_subscriber::registry()
+            .with(fmt::layer().with_writer(std::io::stderr))
+            .with(EnvFilter::from_default_env().add_directive("clawrs=warn".parse().unwrap()))
+            .init();
+        std::process::exit(quick_actions::run_cli(action));
+    }
+
     // Initialize tracing subscriber for logging
     tracing_subscriber::registry()
         .with(fmt::layer())
+        .with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(clawrs::system::log_buffer::RecentLogs),
+        )
         .with(EnvFilter::from_default_env().add_directive("clawrs=info".parse().unwrap()))
         .init();
 
     info!("Starting ClawRS v{}", env!("CARGO_PKG_VERSION"));
+    clawrs::system::crash::install_panic_hook();
 
     // Initialize storage directory structure
     if let Err(e) = clawrs::storage::init_storage() {
         tracing::error!("Failed to initialize storage: {}", e);
     }
 
+    // One instance at a time: a second launch hands its arguments over and exits
+    let request = LaunchRequest::from_args(args);
+    match single_instance::acquire(request) {
+        Ok(true) => {}
+        Ok(false) => {
+            info!("ClawRS is already running, handed over to the open window");
+            return;
+        }
+        Err(e) => tracing::warn!("Single-instance check failed, starting anyway: {}", e),
+    }
+
     // Launch Dioxus desktop application
     dioxus::LaunchBuilder::desktop()
         .with_cfg(
@@ -0,0 +1,399 @@
+//! Image attachments
+//!
+//! Images referenced in a conversation (screenshots, generated charts, files
+//! the user points at) are copied to `{data_dir}/attachments/{conversation_id}/`
+//! so they keep displaying after the original file is moved or deleted.
+//! Text files opened with the app are inlined into the message instead.
+
+use crate::storage::conversations::Conversation;
+use crate::storage::{get_data_dir, StorageError};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg", "ico"];
+
+/// Images larger than this are neither copied nor displayed
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Text files inlined by [`text_attachment`], with their code fence language
+const TEXT_EXTENSIONS: [(&str, &str); 10] = [
+    ("md", "markdown"),
+    ("markdown", "markdown"),
+    ("txt", "text"),
+    ("rst", "rst"),
+    ("log", "text"),
+    ("csv", "csv"),
+    ("json", "json"),
+    ("toml", "toml"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+];
+
+/// Text beyond this is cut from an inlined file
+const MAX_TEXT_ATTACHMENT_BYTES: usize = 64 * 1024;
+
+/// An image copied next to the conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageAttachment {
+    /// Path as it appeared in the message
+    pub source: String,
+    /// File name inside the conversation's attachments directory
+    pub file: String,
+}
+
+/// Where an image reference should be displayed from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageSource {
+    Local(PathBuf),
+    Remote(String),
+}
+
+fn is_remote(reference: &str) -> bool {
+    reference.starts_with("http://") || reference.starts_with("https://")
+}
+
+fn is_absolute_path(reference: &str) -> bool {
+    let bytes = reference.as_bytes();
+    reference.starts_with('/')
+        || reference.starts_with("~/")
+        || (bytes.len() > 2
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'\\' | b'/'))
+}
+
+fn has_image_extension(reference: &str) -> bool {
+    let path = reference.split(['?', '#']).next().unwrap_or(reference);
+    path.rsplit_once('.')
+        .is_some_and(|(_, ext)| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Image URLs and absolute image paths mentioned in a message, in order
+///
+/// Markdown links, backticks and quotes around the reference are ignored.
+/// Relative paths are skipped: they depend on the working directory.
+pub fn find_image_references(content: &str) -> Vec<String> {
+    let mut references: Vec<String> = Vec::new();
+    let tokens = content.split(|c: char| {
+        c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '<' | '>' | '`' | '"' | '\'')
+    });
+    for token in tokens {
+        let token = token.trim_end_matches(['.', ',', ';', ':', '!', '?', '*']);
+        let token = token.strip_prefix("file://").unwrap_or(token);
+        if (is_remote(token) || is_absolute_path(token))
+            && has_image_extension(token)
+            && !references.iter().any(|r| r == token)
+        {
+            references.push(token.to_string());
+        }
+    }
+    references
+}
+
+fn expand_home(reference: &str) -> PathBuf {
+    match reference.strip_prefix("~/") {
+        Some(rest) => directories::BaseDirs::new()
+            .map(|dirs| dirs.home_dir().join(rest))
+            .unwrap_or_else(|| PathBuf::from(reference)),
+        None => PathBuf::from(reference),
+    }
+}
+
+fn is_displayable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() <= MAX_IMAGE_BYTES)
+}
+
+/// Directory holding a conversation's copied images
+pub fn get_attachments_dir(conversation_id: &str) -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("attachments").join(conversation_id))
+}
+
+/// Copy every local image referenced by the conversation that isn't stored yet
+///
+/// Returns how many images were added to `conversation.attachments`.
+/// Nothing is copied for ephemeral conversations.
+pub fn store_images(conversation: &mut Conversation) -> Result<usize, StorageError> {
+    if crate::storage::conversations::is_ephemeral() {
+        return Ok(0);
+    }
+    let dir = get_attachments_dir(&conversation.id)?;
+    let references: Vec<String> = conversation
+        .messages
+        .iter()
+        .flat_map(|m| find_image_references(&m.content))
+        .filter(|r| !is_remote(r))
+        .collect();
+
+    let mut stored = 0;
+    for reference in references {
+        if conversation
+            .attachments
+            .iter()
+            .any(|a| a.source == reference)
+        {
+            continue;
+        }
+        let path = expand_home(&reference);
+        // Already one of ours (e.g. a message copied from another conversation)
+        if path.starts_with(&dir) || !is_displayable(&path) {
+            continue;
+        }
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        fs::create_dir_all(&dir)?;
+        let file = format!(
+            "{}-{}",
+            &uuid::Uuid::new_v4().simple().to_string()[..8],
+            name
+        );
+        fs::copy(&path, dir.join(&file))?;
+        conversation.attachments.push(ImageAttachment {
+            source: reference,
+            file,
+        });
+        stored += 1;
+    }
+
+    if stored > 0 {
+        tracing::debug!(
+            "Stored {} image(s) for conversation {}",
+            stored,
+            conversation.id
+        );
+    }
+    Ok(stored)
+}
+
+/// Resolve a reference to something displayable, preferring the stored copy
+///
+/// Returns `None` for local images that no longer exist anywhere.
+pub fn resolve_image(
+    conversation_id: Option<&str>,
+    attachments: &[ImageAttachment],
+    reference: &str,
+) -> Option<ImageSource> {
+    if is_remote(reference) {
+        return Some(ImageSource::Remote(reference.to_string()));
+    }
+    let stored = conversation_id.zip(attachments.iter().find(|a| a.source == reference));
+    if let Some((id, attachment)) = stored {
+        if let Ok(path) = get_attachments_dir(id).map(|dir| dir.join(&attachment.file)) {
+            if is_displayable(&path) {
+                return Some(ImageSource::Local(path));
+            }
+        }
+    }
+    let path = expand_home(reference);
+    is_displayable(&path).then_some(ImageSource::Local(path))
+}
+
+/// Inline a local image as a `data:` URI the webview can display
+pub fn image_data_uri(path: &Path) -> Option<String> {
+    if !is_displayable(path) {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+    let subtype = match ext.as_str() {
+        "jpg" => "jpeg",
+        "svg" => "svg+xml",
+        "ico" => "x-icon",
+        other => other,
+    };
+    Some(format!(
+        "data:image/{};base64,{}",
+        subtype,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// A text or markdown file as a message block: its name, then its content
+/// in a code fence; `None` for other, unreadable or non-UTF-8 files
+pub fn text_attachment(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+    let (_, language) = TEXT_EXTENSIONS.iter().find(|(e, _)| *e == ext)?;
+    let content = fs::read_to_string(path).ok()?;
+    let name = path.file_name()?.to_string_lossy();
+    let mut body = content.trim_end().to_string();
+    if body.len() > MAX_TEXT_ATTACHMENT_BYTES {
+        let mut end = MAX_TEXT_ATTACHMENT_BYTES;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("\n[...]");
+    }
+    // A longer fence than any inside the file keeps it from closing early
+    let mut fence = "```".to_string();
+    while body.contains(&fence) {
+        fence.push('`');
+    }
+    Some(format!(
+        "📎 {} ({})\n{}{}\n{}\n{}",
+        name,
+        path.display(),
+        fence,
+        language,
+        body,
+        fence
+    ))
+}
+
+/// Byte ranges of the bodies of the [`text_attachment`] blocks in `text`
+fn text_attachment_bodies(text: &str) -> Vec<(usize, usize)> {
+    let mut bodies = Vec::new();
+    let mut lines = text.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line))
+    });
+    while let Some((_, line)) = lines.next() {
+        if !line.starts_with("📎 ") {
+            continue;
+        }
+        let Some((body_start, fence_line)) = lines.next() else {
+            break;
+        };
+        let fence: String = fence_line.chars().take_while(|&c| c == '`').collect();
+        if fence.len() < 3 {
+            continue;
+        }
+        let body_start = body_start + fence_line.len();
+        for (start, line) in lines.by_ref() {
+            if line.trim_end() == fence {
+                // The newline before the closing fence stays
+                bodies.push((body_start, start.saturating_sub(1).max(body_start)));
+                break;
+            }
+        }
+    }
+    bodies
+}
+
+/// Bytes of the inlined text files in `text`
+pub fn text_attachment_bytes(text: &str) -> usize {
+    text_attachment_bodies(text).iter().map(|(start, end)| end - start).sum()
+}
+
+/// Whether `text` holds inlined text files
+pub fn has_text_attachments(text: &str) -> bool {
+    !text_attachment_bodies(text).is_empty()
+}
+
+/// Cut about `remove_bytes` from the inlined text files of `text`, each
+/// losing the same share of its end; returns `text` unchanged without any
+pub fn trim_text_attachments(text: &str, remove_bytes: usize) -> String {
+    let bodies = text_attachment_bodies(text);
+    let total: usize = bodies.iter().map(|(start, end)| end - start).sum();
+    if total == 0 || remove_bytes == 0 {
+        return text.to_string();
+    }
+    let keep = total.saturating_sub(remove_bytes);
+    let mut trimmed = String::with_capacity(text.len());
+    let mut copied = 0;
+    for (start, end) in bodies {
+        let mut cut = start + (end - start) * keep / total;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        trimmed.push_str(&text[copied..cut]);
+        if cut < end {
+            trimmed.push_str("\n[...]");
+        }
+        copied = end;
+    }
+    trimmed.push_str(&text[copied..]);
+    trimmed
+}
+
+/// Copy a conversation's attachments to its duplicate
+pub fn copy_attachments(from_id: &str, to_id: &str) -> Result<(), StorageError> {
+    let source = get_attachments_dir(from_id)?;
+    if !source.exists() {
+        return Ok(());
+    }
+    let target = get_attachments_dir(to_id)?;
+    fs::create_dir_all(&target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        fs::copy(entry.path(), target.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// Delete a conversation's attachments
+pub fn delete_attachments(conversation_id: &str) -> Result<(), StorageError> {
+    let dir = get_attachments_dir(conversation_id)?;
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_attachment() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        fs::write(&notes, "# Plan\n```rust\nfn main() {}\n```\n").unwrap();
+        let block = text_attachment(&notes).unwrap();
+        assert!(block.starts_with("📎 notes.md ("));
+        assert!(block.contains("\n````markdown\n# Plan\n```rust"));
+        assert!(block.ends_with("```\n````"));
+
+        let image = dir.path().join("shot.png");
+        fs::write(&image, [0u8, 1, 2]).unwrap();
+        assert_eq!(text_attachment(&image), None);
+        assert_eq!(text_attachment(&dir.path().join("missing.txt")), None);
+
+        let log = dir.path().join("run.log");
+        fs::write(&log, "a".repeat(1000)).unwrap();
+        let message = format!("Why?\n{}\nThanks", text_attachment(&log).unwrap());
+        assert!(has_text_attachments(&message));
+        let trimmed = trim_text_attachments(&message, 600);
+        assert!(trimmed.contains(&format!("\n{}\n[...]\n```\nThanks", "a".repeat(400))));
+        assert!(!trimmed.contains(&"a".repeat(401)));
+        assert!(!has_text_attachments("Why?\n```\ncode\n```"));
+    }
+
+    #[test]
+    fn test_find_image_references() {
+        let content = "Capture saved to `/tmp/shot.png`. See ![chart](https://example.com/c.JPG?x=1) \
+                       and [plot](/home/me/plot.svg), not ./local.png nor /tmp/notes.txt. Again: /tmp/shot.png";
+        assert_eq!(
+            find_image_references(content),
+            vec![
+                "/tmp/shot.png".to_string(),
+                "https://example.com/c.JPG?x=1".to_string(),
+                "/home/me/plot.svg".to_string(),
+            ]
+        );
+        assert_eq!(
+            find_image_references(r"Voir C:\Users\me\a.webp"),
+            vec![r"C:\Users\me\a.webp".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_resolve_image_falls_back_to_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.png");
+        fs::write(&path, b"\x89PNG").unwrap();
+        let reference = path.to_string_lossy().to_string();
+
+        assert_eq!(
+            resolve_image(Some("missing-conversation"), &[], &reference),
+            Some(ImageSource::Local(path.clone()))
+        );
+        assert_eq!(resolve_image(None, &[], "/nonexistent/x.png"), None);
+        assert!(image_data_uri(&path)
+            .unwrap()
+            .starts_with("data:image/png;base64,"));
+    }
+}
@@ -0,0 +1,387 @@
+//! Per-conversation tool audit log
+//!
+//! Every executed tool call is appended to
+//! `{data_dir}/audit/{conversation_id}.jsonl`. The log backs the privacy
+//! badge: it records which calls reached the network and which domains
+//! they contacted, so users can check what stayed on-device.
+
+use crate::agent::permissions::PermissionLevel;
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// One executed tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub tool: String,
+    pub level: PermissionLevel,
+    /// Hosts contacted by the call (empty for local tools)
+    #[serde(default)]
+    pub domains: Vec<String>,
+    pub success: bool,
+    /// Served from a cache, so nothing left the machine
+    #[serde(default)]
+    pub from_cache: bool,
+    /// Execution time (absent in entries written by older versions)
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Error or failure message of an unsuccessful call
+    #[serde(default)]
+    pub error: Option<String>,
+    /// File name of the model that issued the call
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl AuditEntry {
+    /// Whether this call actually sent data off the machine
+    pub fn used_network(&self) -> bool {
+        self.level == PermissionLevel::Network && !self.from_cache
+    }
+}
+
+/// Privacy level of a conversation, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PrivacyLevel {
+    /// No network tool ran
+    Local,
+    /// Web tools ran (search, fetch, downloads, MCP servers)
+    Network,
+    /// Conversation content was sent to a cloud model (ai_consult)
+    Cloud,
+}
+
+/// What the privacy badge shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrivacySummary {
+    pub level: PrivacyLevel,
+    pub network_calls: usize,
+    /// Contacted hosts, sorted and deduplicated
+    pub domains: Vec<String>,
+}
+
+/// Summarize a conversation's audit log
+pub fn summarize(entries: &[AuditEntry]) -> PrivacySummary {
+    let mut level = PrivacyLevel::Local;
+    let mut network_calls = 0;
+    let mut domains = BTreeSet::new();
+
+    for entry in entries.iter().filter(|e| e.used_network()) {
+        network_calls += 1;
+        let entry_level = if entry.tool == "ai_consult" {
+            PrivacyLevel::Cloud
+        } else {
+            PrivacyLevel::Network
+        };
+        level = level.max(entry_level);
+        domains.extend(entry.domains.iter().cloned());
+    }
+
+    PrivacySummary {
+        level,
+        network_calls,
+        domains: domains.into_iter().collect(),
+    }
+}
+
+/// Common error strings kept per tool in the health report
+const TOP_ERRORS: usize = 3;
+
+/// Usage and reliability of one tool across all conversations
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: usize,
+    pub failures: usize,
+    /// Median duration of the calls that recorded one
+    pub median_ms: Option<u64>,
+    /// Most frequent error strings with their count
+    pub top_errors: Vec<(String, usize)>,
+    /// Models by number of calls, most frequent first
+    pub models: Vec<(String, usize)>,
+}
+
+impl ToolStats {
+    pub fn success_rate(&self) -> f64 {
+        if self.calls == 0 {
+            return 1.0;
+        }
+        (self.calls - self.failures) as f64 / self.calls as f64
+    }
+}
+
+/// Errors mostly differ by paths or ids; group on the first line, truncated
+fn error_key(error: &str) -> String {
+    let line = error.lines().next().unwrap_or_default().trim();
+    match line.char_indices().nth(80) {
+        Some((index, _)) => format!("{}…", &line[..index]),
+        None => line.to_string(),
+    }
+}
+
+fn ranked(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+/// Aggregate audit entries into per-tool stats, worst success rate first
+pub fn tool_stats(entries: &[AuditEntry]) -> Vec<ToolStats> {
+    let mut by_tool: HashMap<&str, Vec<&AuditEntry>> = HashMap::new();
+    for entry in entries {
+        by_tool.entry(entry.tool.as_str()).or_default().push(entry);
+    }
+
+    let mut stats: Vec<ToolStats> = by_tool
+        .into_iter()
+        .map(|(tool, calls)| {
+            let mut durations: Vec<u64> = calls.iter().filter_map(|e| e.duration_ms).collect();
+            durations.sort_unstable();
+            let mut errors: HashMap<String, usize> = HashMap::new();
+            let mut models: HashMap<String, usize> = HashMap::new();
+            for entry in &calls {
+                if !entry.success {
+                    let error = entry.error.as_deref().unwrap_or("unknown error");
+                    *errors.entry(error_key(error)).or_default() += 1;
+                }
+                if let Some(model) = &entry.model {
+                    *models.entry(model.clone()).or_default() += 1;
+                }
+            }
+            let mut top_errors = ranked(errors);
+            top_errors.truncate(TOP_ERRORS);
+            ToolStats {
+                tool: tool.to_string(),
+                calls: calls.len(),
+                failures: calls.iter().filter(|e| !e.success).count(),
+                median_ms: durations.get(durations.len() / 2).copied(),
+                top_errors,
+                models: ranked(models),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        a.success_rate()
+            .total_cmp(&b.success_rate())
+            .then_with(|| b.calls.cmp(&a.calls))
+            .then_with(|| a.tool.cmp(&b.tool))
+    });
+    stats
+}
+
+/// Hosts a network tool call contacts
+///
+/// `service_url` is the endpoint used by tools that don't take a URL
+/// (the Exa MCP server for searches, the cloud provider for ai_consult).
+pub fn network_domains(tool: &str, params: &Value, service_url: Option<&str>) -> Vec<String> {
+    let mut urls: Vec<&str> = Vec::new();
+    for key in ["url", "urls"] {
+        match params.get(key) {
+            Some(Value::String(url)) => urls.push(url),
+            Some(Value::Array(items)) => urls.extend(items.iter().filter_map(|v| v.as_str())),
+            _ => {}
+        }
+    }
+    if urls.is_empty() {
+        urls.extend(service_url);
+    }
+
+    let mut domains: Vec<String> = urls.into_iter().filter_map(host_of).collect();
+    if domains.is_empty() && tool.starts_with("mcp_") {
+        domains.push(format!("MCP ({})", tool));
+    }
+    domains.sort();
+    domains.dedup();
+    domains
+}
+
+fn host_of(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url.trim()).ok()?;
+    parsed.host_str().map(|h| h.trim_start_matches("www.").to_string())
+}
+
+fn get_audit_dir() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("audit"))
+}
+
+fn get_audit_path(conversation_id: &str) -> Result<PathBuf, StorageError> {
+    Ok(get_audit_dir()?.join(format!("{}.jsonl", conversation_id)))
+}
+
+/// Append an entry to a conversation's audit log (skipped for ephemeral
+/// conversations)
+pub fn record_tool_call(conversation_id: &str, entry: &AuditEntry) -> Result<(), StorageError> {
+    if crate::storage::conversations::is_ephemeral() {
+        return Ok(());
+    }
+    fs::create_dir_all(get_audit_dir()?)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_audit_path(conversation_id)?)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Load a conversation's audit log, skipping unreadable lines
+pub fn load_audit_log(conversation_id: &str) -> Vec<AuditEntry> {
+    get_audit_path(conversation_id)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Load the audit logs of every conversation
+pub fn load_all_audit_logs() -> Vec<AuditEntry> {
+    let Ok(entries) = get_audit_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect::<Vec<AuditEntry>>()
+        })
+        .collect()
+}
+
+/// Audit log of the most recently active conversation
+pub fn latest_audit_log() -> Vec<AuditEntry> {
+    let Ok(entries) = get_audit_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())
+        .and_then(|entry| fs::read_to_string(entry.path()).ok())
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Copy a conversation's audit log to another conversation
+pub fn copy_audit_log(from_id: &str, to_id: &str) -> Result<(), StorageError> {
+    let source = get_audit_path(from_id)?;
+    if source.exists() {
+        fs::copy(source, get_audit_path(to_id)?)?;
+    }
+    Ok(())
+}
+
+/// Remove a conversation's audit log
+pub fn delete_audit_log(conversation_id: &str) -> Result<(), StorageError> {
+    let path = get_audit_path(conversation_id)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(tool: &str, level: PermissionLevel, domains: &[&str], from_cache: bool) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            tool: tool.to_string(),
+            level,
+            domains: domains.iter().map(|d| d.to_string()).collect(),
+            success: true,
+            from_cache,
+            duration_ms: None,
+            error: None,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_tool_stats() {
+        let call = |tool: &str, duration: u64, error: Option<&str>, model: &str| AuditEntry {
+            success: error.is_none(),
+            duration_ms: Some(duration),
+            error: error.map(str::to_string),
+            model: Some(model.to_string()),
+            ..entry(tool, PermissionLevel::ReadOnly, &[], false)
+        };
+        let log = vec![
+            call("file_read", 10, None, "qwen.gguf"),
+            call("file_read", 30, None, "qwen.gguf"),
+            call("file_read", 20, None, "qwen.gguf"),
+            call("bash", 500, Some("Timeout dépassé"), "llama.gguf"),
+            call("bash", 100, None, "qwen.gguf"),
+            call("bash", 900, Some("Timeout dépassé"), "llama.gguf"),
+            call("bash", 50, Some("exit code 1\nstderr..."), "llama.gguf"),
+        ];
+        let stats = tool_stats(&log);
+        assert_eq!(stats[0].tool, "bash");
+        assert_eq!((stats[0].calls, stats[0].failures), (4, 3));
+        assert_eq!(stats[0].median_ms, Some(500));
+        assert_eq!(
+            stats[0].top_errors,
+            vec![("Timeout dépassé".to_string(), 2), ("exit code 1".to_string(), 1)]
+        );
+        assert_eq!(stats[0].models[0], ("llama.gguf".to_string(), 3));
+        assert_eq!(stats[1].tool, "file_read");
+        assert_eq!(stats[1].success_rate(), 1.0);
+        assert_eq!(stats[1].median_ms, Some(20));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let local = vec![entry("file_read", PermissionLevel::ReadOnly, &[], false)];
+        assert_eq!(summarize(&local).level, PrivacyLevel::Local);
+
+        let mut log = local.clone();
+        log.push(entry("web_fetch", PermissionLevel::Network, &["docs.rs"], false));
+        log.push(entry("web_search", PermissionLevel::Network, &["mcp.exa.ai"], false));
+        log.push(entry("web_fetch", PermissionLevel::Network, &["docs.rs"], false));
+        // Cache hits never left the machine
+        log.push(entry("web_fetch", PermissionLevel::Network, &["example.com"], true));
+        let summary = summarize(&log);
+        assert_eq!(summary.level, PrivacyLevel::Network);
+        assert_eq!(summary.network_calls, 3);
+        assert_eq!(summary.domains, vec!["docs.rs", "mcp.exa.ai"]);
+
+        log.push(entry("ai_consult", PermissionLevel::Network, &["openrouter.ai"], false));
+        assert_eq!(summarize(&log).level, PrivacyLevel::Cloud);
+    }
+
+    #[test]
+    fn test_network_domains() {
+        let params = json!({"url": "https://www.rust-lang.org/learn"});
+        assert_eq!(network_domains("web_fetch", &params, None), vec!["rust-lang.org"]);
+
+        let params = json!({"urls": ["https://a.com/x", "https://b.org", "https://a.com/y"]});
+        assert_eq!(network_domains("web_crawl", &params, None), vec!["a.com", "b.org"]);
+
+        let params = json!({"query": "rust"});
+        assert_eq!(
+            network_domains("web_search", &params, Some("https://mcp.exa.ai/mcp")),
+            vec!["mcp.exa.ai"]
+        );
+        assert_eq!(network_domains("mcp_github_search", &params, None), vec!["MCP (mcp_github_search)"]);
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! Manages saving and loading of chat conversations.
 
+use crate::storage::attachments::ImageAttachment;
 use crate::storage::{get_data_dir, StorageError};
 use crate::types::message::Message;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use uuid::Uuid;
@@ -23,6 +25,25 @@ pub struct Conversation {
     pub created_at: DateTime<Utc>,
     /// When the conversation was last updated
     pub updated_at: DateTime<Utc>,
+    /// Persona id; `None` uses the default agent
+    #[serde(default)]
+    pub persona: Option<String>,
+    /// Images referenced by the messages, copied next to the conversation
+    #[serde(default)]
+    pub attachments: Vec<ImageAttachment>,
+    /// Free-form labels, lowercase (see [`parse_tags`])
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Tags added automatically so far (see
+    /// [`apply_auto_tags`](crate::agent::tagging::apply_auto_tags)); one
+    /// removed by hand stays removed
+    #[serde(default)]
+    pub auto_tags: Vec<String>,
+    /// Language the agent answers in (see
+    /// [`RESPONSE_LANGUAGES`](crate::agent::language::RESPONSE_LANGUAGES));
+    /// `None` follows the user
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 impl Conversation {
@@ -44,6 +65,11 @@ impl Conversation {
             messages,
             created_at: now,
             updated_at: now,
+            persona: None,
+            attachments: Vec::new(),
+            tags: Vec::new(),
+            auto_tags: Vec::new(),
+            language: None,
         }
     }
 
@@ -57,6 +83,65 @@ impl Conversation {
         self.messages.push(message);
         self.updated_at = Utc::now();
     }
+
+    /// Copy into a new conversation, keeping messages up to and including
+    /// index `up_to` (all of them when `None`)
+    pub fn duplicate(&self, up_to: Option<usize>) -> Self {
+        let now = Utc::now();
+        let end = up_to.map_or(self.messages.len(), |i| (i + 1).min(self.messages.len()));
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title: format!("{} (copy)", self.title.trim_end_matches(" (copy)")),
+            messages: self.messages[..end].to_vec(),
+            created_at: now,
+            updated_at: now,
+            persona: self.persona.clone(),
+            attachments: self.attachments.clone(),
+            tags: self.tags.clone(),
+            auto_tags: self.auto_tags.clone(),
+            language: self.language.clone(),
+        }
+    }
+
+    /// Remove every message but keep the conversation and its title
+    pub fn clear_messages(&mut self) {
+        self.messages.clear();
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Split comma-separated input into trimmed, lowercase, unique tags
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',') {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// How long deleted conversations stay in the trash
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// A conversation moved to the trash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrashedConversation {
+    pub conversation: Conversation,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl TrashedConversation {
+    /// Whether the retention period has elapsed at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now - self.deleted_at >= chrono::Duration::days(TRASH_RETENTION_DAYS)
+    }
+
+    /// Days left before permanent deletion
+    pub fn days_remaining(&self, now: DateTime<Utc>) -> i64 {
+        (TRASH_RETENTION_DAYS - (now - self.deleted_at).num_days()).max(0)
+    }
 }
 
 /// Generate a conversation title from a message
@@ -71,6 +156,51 @@ fn generate_title(content: &str) -> String {
     }
 }
 
+/// In-memory store used instead of disk while ephemeral (locked mode)
+#[cfg(not(test))]
+static EPHEMERAL: std::sync::Mutex<Option<HashMap<String, Conversation>>> =
+    std::sync::Mutex::new(None);
+
+// One store per test thread, so a test turning it on doesn't send the
+// conversations of tests running in parallel to memory
+#[cfg(test)]
+thread_local! {
+    static EPHEMERAL: std::cell::RefCell<Option<HashMap<String, Conversation>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Run `f` on the ephemeral store slot, `None` while on disk
+#[cfg(not(test))]
+fn ephemeral_slot<T>(f: impl FnOnce(&mut Option<HashMap<String, Conversation>>) -> T) -> T {
+    f(&mut EPHEMERAL.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+#[cfg(test)]
+fn ephemeral_slot<T>(f: impl FnOnce(&mut Option<HashMap<String, Conversation>>) -> T) -> T {
+    EPHEMERAL.with(|store| f(&mut store.borrow_mut()))
+}
+
+/// Keep conversations in memory only, or go back to disk
+///
+/// Turning it off drops every conversation of the ephemeral session.
+pub fn set_ephemeral(enabled: bool) {
+    ephemeral_slot(|store| match (enabled, store.is_some()) {
+        (true, false) => *store = Some(HashMap::new()),
+        (false, true) => *store = None,
+        _ => {}
+    });
+}
+
+/// Whether conversations are currently kept off disk
+pub fn is_ephemeral() -> bool {
+    ephemeral_slot(|store| store.is_some())
+}
+
+/// Run `f` on the in-memory store, or return `None` when not ephemeral
+fn with_ephemeral<T>(f: impl FnOnce(&mut HashMap<String, Conversation>) -> T) -> Option<T> {
+    ephemeral_slot(|store| store.as_mut().map(f))
+}
+
 /// Get the conversations directory
 fn get_conversations_dir() -> Result<PathBuf, StorageError> {
     Ok(get_data_dir()?.join("conversations"))
@@ -81,8 +211,22 @@ fn get_conversation_path(id: &str) -> Result<PathBuf, StorageError> {
     Ok(get_conversations_dir()?.join(format!("{}.json", id)))
 }
 
+/// Get the trash directory
+fn get_trash_dir() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("trash"))
+}
+
+/// Get the trash file path for a conversation
+fn get_trash_path(id: &str) -> Result<PathBuf, StorageError> {
+    Ok(get_trash_dir()?.join(format!("{}.json", id)))
+}
+
 /// Save a conversation to disk
 pub fn save_conversation(conversation: &Conversation) -> Result<(), StorageError> {
+    if with_ephemeral(|store| store.insert(conversation.id.clone(), conversation.clone())).is_some()
+    {
+        return Ok(());
+    }
     let dir = get_conversations_dir()?;
     std::fs::create_dir_all(&dir)?;
     let path = get_conversation_path(&conversation.id)?;
@@ -94,6 +238,9 @@ pub fn save_conversation(conversation: &Conversation) -> Result<(), StorageError
 
 /// Load a conversation from disk
 pub fn load_conversation(id: &str) -> Result<Conversation, StorageError> {
+    if let Some(conversation) = with_ephemeral(|store| store.get(id).cloned()) {
+        return conversation.ok_or_else(|| StorageError::ConversationNotFound(id.to_string()));
+    }
     let path = get_conversation_path(id)?;
 
     if !path.exists() {
@@ -110,6 +257,12 @@ pub fn load_conversation(id: &str) -> Result<Conversation, StorageError> {
 ///
 /// Returns a list of conversations sorted by updated_at (most recent first)
 pub fn list_conversations() -> Result<Vec<Conversation>, StorageError> {
+    if let Some(mut conversations) =
+        with_ephemeral(|store| store.values().cloned().collect::<Vec<_>>())
+    {
+        conversations.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+        return Ok(conversations);
+    }
     let conversations_dir = get_conversations_dir()?;
 
     if !conversations_dir.exists() {
@@ -147,6 +300,11 @@ pub fn list_conversations() -> Result<Vec<Conversation>, StorageError> {
 
 /// Delete a conversation
 pub fn delete_conversation(id: &str) -> Result<(), StorageError> {
+    if let Some(removed) = with_ephemeral(|store| store.remove(id)) {
+        return removed
+            .map(|_| ())
+            .ok_or_else(|| StorageError::ConversationNotFound(id.to_string()));
+    }
     let path = get_conversation_path(id)?;
 
     if !path.exists() {
@@ -154,10 +312,160 @@ pub fn delete_conversation(id: &str) -> Result<(), StorageError> {
     }
 
     fs::remove_file(path)?;
+    if let Err(e) = crate::storage::audit_log::delete_audit_log(id) {
+        tracing::warn!("Failed to delete audit log for {}: {}", id, e);
+    }
+    if let Err(e) = crate::storage::attachments::delete_attachments(id) {
+        tracing::warn!("Failed to delete attachments for {}: {}", id, e);
+    }
     tracing::debug!("Deleted conversation: {}", id);
     Ok(())
 }
 
+/// Move conversations to the trash
+///
+/// Their audit logs are kept so a restored conversation keeps its privacy
+/// history. Returns how many conversations were moved. Ephemeral
+/// conversations have no trash and are dropped right away.
+pub fn trash_conversations(ids: &[String]) -> Result<usize, StorageError> {
+    if let Some(removed) =
+        with_ephemeral(|store| ids.iter().filter(|id| store.remove(*id).is_some()).count())
+    {
+        return Ok(removed);
+    }
+    fs::create_dir_all(get_trash_dir()?)?;
+    let now = Utc::now();
+    let mut moved = 0;
+
+    for id in ids {
+        let conversation = match load_conversation(id) {
+            Ok(conversation) => conversation,
+            Err(StorageError::ConversationNotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        let trashed = TrashedConversation {
+            conversation,
+            deleted_at: now,
+        };
+        fs::write(get_trash_path(id)?, serde_json::to_string_pretty(&trashed)?)?;
+        fs::remove_file(get_conversation_path(id)?)?;
+        moved += 1;
+    }
+
+    tracing::info!("Moved {} conversation(s) to trash", moved);
+    Ok(moved)
+}
+
+/// List trashed conversations, most recently deleted first
+pub fn list_trash() -> Result<Vec<TrashedConversation>, StorageError> {
+    if is_ephemeral() {
+        return Ok(vec![]);
+    }
+    let dir = get_trash_dir()?;
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut trashed: Vec<TrashedConversation> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+        .filter_map(|path| {
+            let json = fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&json)
+                .map_err(|e| tracing::warn!("Failed to parse trash file {:?}: {}", path, e))
+                .ok()
+        })
+        .collect();
+    trashed.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(trashed)
+}
+
+/// Move a conversation back out of the trash
+pub fn restore_conversation(id: &str) -> Result<Conversation, StorageError> {
+    let path = get_trash_path(id)?;
+    if !path.exists() {
+        return Err(StorageError::ConversationNotFound(id.to_string()));
+    }
+
+    let trashed: TrashedConversation = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    save_conversation(&trashed.conversation)?;
+    // While locked the restored copy only lives in memory; the trash on disk
+    // stays as it was
+    if !is_ephemeral() {
+        fs::remove_file(path)?;
+    }
+    Ok(trashed.conversation)
+}
+
+/// Permanently delete a trashed conversation, its audit log and attachments
+pub fn purge_trashed(id: &str) -> Result<(), StorageError> {
+    let path = get_trash_path(id)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    if let Err(e) = crate::storage::audit_log::delete_audit_log(id) {
+        tracing::warn!("Failed to delete audit log for {}: {}", id, e);
+    }
+    if let Err(e) = crate::storage::attachments::delete_attachments(id) {
+        tracing::warn!("Failed to delete attachments for {}: {}", id, e);
+    }
+    Ok(())
+}
+
+/// Permanently delete everything in the trash
+pub fn empty_trash() -> Result<usize, StorageError> {
+    let trashed = list_trash()?;
+    for item in &trashed {
+        purge_trashed(&item.conversation.id)?;
+    }
+    Ok(trashed.len())
+}
+
+/// Permanently delete trashed conversations past the retention period
+pub fn purge_expired_trash() -> Result<usize, StorageError> {
+    let now = Utc::now();
+    let expired: Vec<String> = list_trash()?
+        .into_iter()
+        .filter(|item| item.is_expired(now))
+        .map(|item| item.conversation.id)
+        .collect();
+    for id in &expired {
+        purge_trashed(id)?;
+    }
+    if !expired.is_empty() {
+        tracing::info!("Purged {} expired conversation(s) from trash", expired.len());
+    }
+    Ok(expired.len())
+}
+
+/// Duplicate a saved conversation into a new one
+///
+/// The audit log is copied too, so the copy's privacy badge reflects the
+/// tool calls behind the messages it inherits, and so are its images.
+pub fn duplicate_conversation(
+    conversation: &Conversation,
+    up_to: Option<usize>,
+) -> Result<Conversation, StorageError> {
+    let copy = conversation.duplicate(up_to);
+    save_conversation(&copy)?;
+    if let Err(e) = crate::storage::audit_log::copy_audit_log(&conversation.id, &copy.id) {
+        tracing::warn!("Failed to copy audit log for {}: {}", conversation.id, e);
+    }
+    if let Err(e) = crate::storage::attachments::copy_attachments(&conversation.id, &copy.id) {
+        tracing::warn!("Failed to copy attachments for {}: {}", conversation.id, e);
+    }
+    Ok(copy)
+}
+
+/// Delete all messages of a conversation but keep the conversation
+pub fn clear_conversation_messages(id: &str) -> Result<Conversation, StorageError> {
+    let mut conversation = load_conversation(id)?;
+    conversation.clear_messages();
+    save_conversation(&conversation)?;
+    Ok(conversation)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,4 +522,75 @@ mod tests {
         assert_eq!(conv.title, deserialized.title);
         assert_eq!(conv.messages.len(), deserialized.messages.len());
     }
+
+    #[test]
+    fn test_ephemeral_store() {
+        set_ephemeral(true);
+        let conv = Conversation::new(Some(Message::new(Role::User, "Kiosk question")));
+        save_conversation(&conv).unwrap();
+        assert_eq!(load_conversation(&conv.id).unwrap(), conv);
+        assert_eq!(list_conversations().unwrap(), vec![conv.clone()]);
+        assert_eq!(
+            trash_conversations(std::slice::from_ref(&conv.id)).unwrap(),
+            1
+        );
+        assert!(list_conversations().unwrap().is_empty());
+        set_ephemeral(false);
+        assert!(!is_ephemeral());
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags(" Rust, code,,rust , Français "),
+            vec!["rust", "code", "français"]
+        );
+        assert!(parse_tags(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_clear_messages_keeps_title() {
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "Keep this title")));
+        conv.add_message(Message::new(Role::Assistant, "Reply"));
+        conv.clear_messages();
+
+        assert!(conv.messages.is_empty());
+        assert_eq!(conv.title, "Keep this title");
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "Question")));
+        conv.add_message(Message::new(Role::Assistant, "Answer"));
+        conv.add_message(Message::new(Role::User, "Follow-up"));
+
+        let full = conv.duplicate(None);
+        assert_ne!(full.id, conv.id);
+        assert_eq!(full.title, "Question (copy)");
+        assert_eq!(full.messages, conv.messages);
+
+        let branch = full.duplicate(Some(1));
+        assert_eq!(branch.title, "Question (copy)");
+        assert_eq!(branch.messages.len(), 2);
+        assert_eq!(branch.messages[1].content, "Answer");
+        assert_eq!(conv.duplicate(Some(99)).messages.len(), 3);
+    }
+
+    #[test]
+    fn test_trash_retention() {
+        let now = Utc::now();
+        let trashed = TrashedConversation {
+            conversation: Conversation::new(None),
+            deleted_at: now - chrono::Duration::days(10),
+        };
+        assert!(!trashed.is_expired(now));
+        assert_eq!(trashed.days_remaining(now), 20);
+
+        let old = TrashedConversation {
+            deleted_at: now - chrono::Duration::days(TRASH_RETENTION_DAYS),
+            ..trashed
+        };
+        assert!(old.is_expired(now));
+        assert_eq!(old.days_remaining(now), 0);
+    }
 }
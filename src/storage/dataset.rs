@@ -0,0 +1,401 @@
+//! Fine-tuning dataset export
+//!
+//! Turns saved conversations into JSONL training files: ShareGPT, ChatML
+//! (`messages` arrays) or Alpaca instruction pairs. Conversations can be
+//! narrowed down by tag and by 👍/👎 feedback, and personal data can be
+//! scrubbed before anything is written. Tool output (system messages) and
+//! thinking blocks never make it into the dataset. Files are written to
+//! `{data_dir}/datasets/`.
+
+use crate::storage::conversations::Conversation;
+use crate::storage::feedback::{FeedbackEntry, Rating};
+use crate::storage::{get_data_dir, StorageError};
+use crate::types::message::Role;
+use chrono::Local;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+
+/// Output format, one JSON record per line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DatasetFormat {
+    /// `{"conversations": [{"from": "human"|"gpt", "value": ...}]}`
+    #[default]
+    ShareGpt,
+    /// `{"messages": [{"role": "user"|"assistant", "content": ...}]}`
+    ChatMl,
+    /// `{"instruction": ..., "input": "", "output": ...}`, one per exchange
+    Alpaca,
+}
+
+impl DatasetFormat {
+    pub const ALL: [DatasetFormat; 3] = [Self::ShareGpt, Self::ChatMl, Self::Alpaca];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::ShareGpt => "sharegpt",
+            Self::ChatMl => "chatml",
+            Self::Alpaca => "alpaca",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ShareGpt => "ShareGPT",
+            Self::ChatMl => "ChatML",
+            Self::Alpaca => "Alpaca",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.id() == id)
+    }
+}
+
+/// Which exchanges to keep, based on ratings
+///
+/// Multi-turn formats apply it to whole conversations, Alpaca to each
+/// exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeedbackFilter {
+    #[default]
+    Any,
+    /// Drop anything rated 👎
+    NotDisliked,
+    /// Keep only what was rated 👍 (and never 👎)
+    Liked,
+}
+
+/// Personal data to mask in exported text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrubOptions {
+    pub emails: bool,
+    pub phone_numbers: bool,
+    pub ip_addresses: bool,
+    /// API keys, tokens, passwords and URL query strings
+    pub secrets: bool,
+    /// Home directory paths, which contain the user name
+    pub home_dir: bool,
+}
+
+impl Default for ScrubOptions {
+    fn default() -> Self {
+        Self {
+            emails: true,
+            phone_numbers: true,
+            ip_addresses: true,
+            secrets: true,
+            home_dir: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DatasetOptions {
+    pub format: DatasetFormat,
+    pub feedback: FeedbackFilter,
+    pub scrub: ScrubOptions,
+}
+
+static EMAIL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+        .expect("valid email regex")
+});
+static IP_ADDRESS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b")
+        .expect("valid IP regex")
+});
+/// Digit groups with separators, or `+` and a long run of digits
+static PHONE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{1,4}\)[ .-]?)?\d{1,4}(?:[ .-]\d{2,4}){2,5}|\+\d{9,14}")
+        .expect("valid phone regex")
+});
+static API_KEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:sk-[A-Za-z0-9_-]{16,}|ghp_[A-Za-z0-9]{20,}|hf_[A-Za-z0-9]{20,}|AKIA[0-9A-Z]{16}|xox[abps]-[A-Za-z0-9-]{10,})")
+        .expect("valid API key regex")
+});
+static SECRET_ASSIGNMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\b(api[_-]?key|token|password|passwd|secret)(\s*[:=]\s*)["']?[^\s"',;]+"#)
+        .expect("valid secret regex")
+});
+static URL_QUERY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(https?://[^\s?#]+)\?[^\s)]+").expect("valid URL query regex"));
+
+/// Mask personal data in `text`
+pub fn scrub(text: &str, options: &ScrubOptions, home: Option<&str>) -> String {
+    let mut text = text.to_string();
+    if options.home_dir {
+        if let Some(home) = home.filter(|h| !h.is_empty()) {
+            text = text.replace(home, "~");
+        }
+    }
+    if options.secrets {
+        text = API_KEY.replace_all(&text, "[SECRET]").into_owned();
+        text = SECRET_ASSIGNMENT
+            .replace_all(&text, "$1$2[SECRET]")
+            .into_owned();
+        text = URL_QUERY.replace_all(&text, "$1?[SECRET]").into_owned();
+    }
+    if options.emails {
+        text = EMAIL.replace_all(&text, "[EMAIL]").into_owned();
+    }
+    if options.ip_addresses {
+        text = IP_ADDRESS.replace_all(&text, "[IP]").into_owned();
+    }
+    if options.phone_numbers {
+        // Dates and versions also look like digit groups: require a phone-sized number
+        text = PHONE
+            .replace_all(&text, |caps: &regex::Captures| {
+                let digits = caps[0].chars().filter(char::is_ascii_digit).count();
+                if (9..=15).contains(&digits) {
+                    "[PHONE]".to_string()
+                } else {
+                    caps[0].to_string()
+                }
+            })
+            .into_owned();
+    }
+    text
+}
+
+/// Remove `<think>`/`<thinking>` blocks, including an unclosed trailing one
+pub fn strip_thinking(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    loop {
+        let open = ["<think>", "<thinking>"]
+            .into_iter()
+            .filter_map(|tag| rest.find(tag).map(|i| (i, tag)))
+            .min_by_key(|(i, _)| *i);
+        let Some((start, tag)) = open else {
+            out.push_str(rest);
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let close = if tag == "<think>" {
+            "</think>"
+        } else {
+            "</thinking>"
+        };
+        match rest[start..].find(close) {
+            Some(end) => rest = &rest[start + end + close.len()..],
+            None => break,
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Consecutive messages of one role merged together
+struct Turn {
+    user: bool,
+    content: String,
+    rating: Option<Rating>,
+}
+
+/// User and assistant turns; an agent run's several assistant messages
+/// (split by tool output) become one turn. A 👎 on any part wins.
+fn turns(conversation: &Conversation, feedback: &[FeedbackEntry]) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+    for (index, message) in conversation.messages.iter().enumerate() {
+        let user = match message.role {
+            Role::User => true,
+            Role::Assistant => false,
+            Role::System => continue,
+        };
+        let content = if user {
+            message.content.trim().to_string()
+        } else {
+            strip_thinking(&message.content)
+        };
+        if content.is_empty() {
+            continue;
+        }
+        let rating = feedback
+            .iter()
+            .find(|f| f.conversation_id == conversation.id && f.message_index == index)
+            .map(|f| f.rating);
+        match turns.last_mut() {
+            Some(last) if last.user == user => {
+                last.content.push_str("\n\n");
+                last.content.push_str(&content);
+                if last.rating != Some(Rating::Down) {
+                    last.rating = rating.or(last.rating);
+                }
+            }
+            _ => turns.push(Turn {
+                user,
+                content,
+                rating,
+            }),
+        }
+    }
+    // Training samples start with a prompt and end with an answer
+    while turns.first().is_some_and(|t| !t.user) {
+        turns.remove(0);
+    }
+    while turns.last().is_some_and(|t| t.user) {
+        turns.pop();
+    }
+    turns
+}
+
+fn keep(filter: FeedbackFilter, ratings: impl Iterator<Item = Option<Rating>>) -> bool {
+    let ratings: Vec<_> = ratings.flatten().collect();
+    match filter {
+        FeedbackFilter::Any => true,
+        FeedbackFilter::NotDisliked => !ratings.contains(&Rating::Down),
+        FeedbackFilter::Liked => ratings.contains(&Rating::Up) && !ratings.contains(&Rating::Down),
+    }
+}
+
+/// Dataset records for the given conversations
+pub fn build_records(
+    conversations: &[Conversation],
+    feedback: &[FeedbackEntry],
+    options: &DatasetOptions,
+    home: Option<&str>,
+) -> Vec<Value> {
+    let format = options.format;
+    let mut records = Vec::new();
+    for conversation in conversations {
+        let turns = turns(conversation, feedback);
+        if turns.is_empty() {
+            continue;
+        }
+        let text = |turn: &Turn| scrub(&turn.content, &options.scrub, home);
+        match format {
+            DatasetFormat::Alpaca => {
+                for pair in turns.chunks(2) {
+                    let [prompt, answer] = pair else { continue };
+                    if keep(options.feedback, std::iter::once(answer.rating)) {
+                        records.push(json!({
+                            "instruction": text(prompt),
+                            "input": "",
+                            "output": text(answer),
+                        }));
+                    }
+                }
+            }
+            DatasetFormat::ShareGpt | DatasetFormat::ChatMl => {
+                if !keep(options.feedback, turns.iter().map(|t| t.rating)) {
+                    continue;
+                }
+                let record = if format == DatasetFormat::ShareGpt {
+                    let items: Vec<Value> = turns
+                        .iter()
+                        .map(|t| json!({"from": if t.user { "human" } else { "gpt" }, "value": text(t)}))
+                        .collect();
+                    json!({ "conversations": items })
+                } else {
+                    let items: Vec<Value> = turns
+                        .iter()
+                        .map(|t| json!({"role": if t.user { "user" } else { "assistant" }, "content": text(t)}))
+                        .collect();
+                    json!({ "messages": items })
+                };
+                records.push(record);
+            }
+        }
+    }
+    records
+}
+
+/// Conversations carrying at least one of `tags` (all of them when empty)
+pub fn matches_tags(conversation: &Conversation, tags: &[String]) -> bool {
+    tags.is_empty() || conversation.tags.iter().any(|t| tags.contains(t))
+}
+
+/// Write the dataset as JSONL; returns the file and the number of records
+pub fn export_dataset(
+    conversations: &[Conversation],
+    feedback: &[FeedbackEntry],
+    options: &DatasetOptions,
+) -> Result<(PathBuf, usize), StorageError> {
+    let format = options.format;
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().display().to_string());
+    let records = build_records(conversations, feedback, options, home.as_deref());
+
+    let dir = get_data_dir()?.join("datasets");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "clawrs-{}-{}.jsonl",
+        format.id(),
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let mut content = String::new();
+    for record in &records {
+        content.push_str(&serde_json::to_string(record)?);
+        content.push('\n');
+    }
+    fs::write(&path, content)?;
+    tracing::info!(
+        "Exported {} dataset records to {}",
+        records.len(),
+        path.display()
+    );
+    Ok((path, records.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::message::Message;
+
+    #[test]
+    fn test_scrub() {
+        let text = "Mail alice@example.com from /home/alice/work, call +33 6 12 34 56 78, \
+                    host 192.168.1.20, api_key=abc123 and sk-abcdefghijklmnopqrstu on 2024-01-15";
+        let scrubbed = scrub(text, &ScrubOptions::default(), Some("/home/alice"));
+        assert_eq!(
+            scrubbed,
+            "Mail [EMAIL] from ~/work, call [PHONE], \
+             host [IP], api_key=[SECRET] and [SECRET] on 2024-01-15"
+        );
+    }
+
+    #[test]
+    fn test_build_records() {
+        let mut conv = Conversation::new(Some(Message::new(Role::User, "Q1")));
+        conv.add_message(Message::new(Role::Assistant, "<think>hmm</think>A1"));
+        conv.add_message(Message::new(Role::System, "tool output"));
+        conv.add_message(Message::new(Role::Assistant, "A1 bis"));
+        conv.add_message(Message::new(Role::User, "Q2"));
+        conv.add_message(Message::new(Role::Assistant, "A2"));
+        conv.add_message(Message::new(Role::User, "unanswered"));
+        let disliked = FeedbackEntry {
+            timestamp: chrono::Utc::now(),
+            model: "m".into(),
+            conversation_id: conv.id.clone(),
+            message_index: 5,
+            rating: Rating::Down,
+            comment: String::new(),
+            prompt: String::new(),
+            response: String::new(),
+        };
+        let convs = [conv];
+        let options = DatasetOptions {
+            format: DatasetFormat::ChatMl,
+            ..Default::default()
+        };
+        let chatml = build_records(&convs, &[], &options, None);
+        assert_eq!(
+            chatml[0]["messages"][1]["content"],
+            Value::from("A1\n\nA1 bis")
+        );
+        assert_eq!(chatml[0]["messages"].as_array().unwrap().len(), 4);
+
+        let feedback = [disliked];
+        let mut options = DatasetOptions {
+            format: DatasetFormat::ShareGpt,
+            feedback: FeedbackFilter::NotDisliked,
+            ..Default::default()
+        };
+        assert!(build_records(&convs, &feedback, &options, None).is_empty());
+        options.format = DatasetFormat::Alpaca;
+        let alpaca = build_records(&convs, &feedback, &options, None);
+        assert_eq!(alpaca.len(), 1);
+        assert_eq!(alpaca[0]["instruction"], "Q1");
+    }
+}
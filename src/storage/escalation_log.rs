@@ -0,0 +1,167 @@
+//! Escalation cost ledger
+//!
+//! One JSON line per `ai_consult` call in `{data_dir}/escalations.jsonl`,
+//! used to show spending in settings and enforce the monthly budget.
+
+use crate::agent::escalation::{
+    estimate_tokens, model_pricing, price, EscalationPreview, EscalationProvider, EscalationReason,
+};
+use crate::agent::tools::ToolResult;
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A completed escalation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationRecord {
+    pub timestamp: DateTime<Utc>,
+    pub conversation_id: Option<String>,
+    pub provider: EscalationProvider,
+    pub model: String,
+    pub reason: EscalationReason,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Actual cost reported by the provider, or our estimate
+    pub cost_usd: f64,
+}
+
+impl EscalationRecord {
+    /// Build a record from a successful `ai_consult` result, preferring the
+    /// provider's reported usage over our estimates
+    pub fn from_result(preview: &EscalationPreview, conversation_id: Option<String>, result: &ToolResult) -> Self {
+        let usage = result.data.get("usage");
+        let prompt_tokens = usage
+            .and_then(|u| u.get("prompt_tokens"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(preview.estimated_tokens as u64);
+        let completion_tokens = usage
+            .and_then(|u| u.get("completion_tokens"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or_else(|| {
+                let response = result.data.get("response").and_then(|v| v.as_str()).unwrap_or_default();
+                estimate_tokens(response) as u64
+            });
+        let cost_usd = usage
+            .and_then(|u| u.get("cost"))
+            .and_then(|v| v.as_f64())
+            .or_else(|| {
+                model_pricing(&preview.model).map(|(input, output)| {
+                    price(input, output, prompt_tokens as usize, completion_tokens as usize)
+                })
+            })
+            .unwrap_or(0.0);
+
+        Self {
+            timestamp: Utc::now(),
+            conversation_id,
+            provider: preview.provider,
+            model: preview.model.clone(),
+            reason: preview.reason.clone(),
+            prompt_tokens,
+            completion_tokens,
+            cost_usd,
+        }
+    }
+}
+
+fn get_log_path() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("escalations.jsonl"))
+}
+
+/// Append a record to the ledger
+pub fn record_escalation(record: &EscalationRecord) -> Result<(), StorageError> {
+    let path = get_log_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Load every record, skipping unreadable lines
+pub fn load_escalations() -> Vec<EscalationRecord> {
+    let Ok(path) = get_log_path() else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Total spent in the calendar month containing `now`
+pub fn month_spend(records: &[EscalationRecord], now: DateTime<Utc>) -> f64 {
+    records
+        .iter()
+        .filter(|r| r.timestamp.year() == now.year() && r.timestamp.month() == now.month())
+        .map(|r| r.cost_usd)
+        .sum()
+}
+
+/// Spending for the current month, read from disk
+pub fn current_month_spend() -> f64 {
+    month_spend(&load_escalations(), Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn record(timestamp: DateTime<Utc>, cost_usd: f64) -> EscalationRecord {
+        EscalationRecord {
+            timestamp,
+            conversation_id: None,
+            provider: EscalationProvider::OpenRouter,
+            model: "openai/gpt-4o".to_string(),
+            reason: EscalationReason::UserRequest,
+            prompt_tokens: 100,
+            completion_tokens: 200,
+            cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_month_spend() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+        let records = vec![
+            record(now, 0.5),
+            record(now - Duration::days(10), 0.25),
+            record(now - Duration::days(40), 3.0),
+        ];
+        assert!((month_spend(&records, now) - 0.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_record_from_result() {
+        let preview = EscalationPreview::new(
+            EscalationProvider::OpenAi,
+            "gpt-4o",
+            EscalationReason::UserRequest,
+            &serde_json::json!({"question": "why?"}),
+        );
+        let reported = ToolResult {
+            success: true,
+            data: serde_json::json!({"usage": {"prompt_tokens": 10, "completion_tokens": 20, "cost": 0.002}}),
+            message: String::new(),
+        };
+        let rec = EscalationRecord::from_result(&preview, None, &reported);
+        assert_eq!((rec.prompt_tokens, rec.completion_tokens), (10, 20));
+        assert_eq!(rec.cost_usd, 0.002);
+
+        // No reported cost: priced from the model table
+        let unreported = ToolResult {
+            data: serde_json::json!({"usage": {"prompt_tokens": 1_000_000, "completion_tokens": 0}}),
+            ..reported
+        };
+        let rec = EscalationRecord::from_result(&preview, None, &unreported);
+        assert!((rec.cost_usd - 2.5).abs() < 1e-9);
+    }
+}
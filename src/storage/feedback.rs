@@ -0,0 +1,243 @@
+//! Per-message feedback
+//!
+//! 👍/👎 ratings on assistant messages, one JSON line per rating in
+//! `{data_dir}/feedback/{model}.jsonl`. Each entry keeps the prompt and the
+//! rated response, so the log can be used to compare models and later be
+//! exported as a preference dataset.
+
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rating {
+    Up,
+    Down,
+}
+
+/// A rating on one assistant message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedbackEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Model file name loaded when the message was rated
+    pub model: String,
+    pub conversation_id: String,
+    /// Position of the message in the conversation
+    pub message_index: usize,
+    pub rating: Rating,
+    #[serde(default)]
+    pub comment: String,
+    /// The user message the response answered
+    pub prompt: String,
+    pub response: String,
+}
+
+impl FeedbackEntry {
+    fn same_message(&self, conversation_id: &str, message_index: usize) -> bool {
+        self.conversation_id == conversation_id && self.message_index == message_index
+    }
+}
+
+/// Ratings for one model
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelFeedback {
+    pub model: String,
+    pub up: usize,
+    pub down: usize,
+}
+
+impl ModelFeedback {
+    /// Share of positive ratings
+    pub fn approval(&self) -> f64 {
+        let total = self.up + self.down;
+        if total == 0 {
+            return 0.0;
+        }
+        self.up as f64 / total as f64
+    }
+}
+
+/// Keep one rating per message: replace an existing one or add it
+pub fn upsert(entries: &mut Vec<FeedbackEntry>, entry: FeedbackEntry) {
+    entries.retain(|e| !e.same_message(&entry.conversation_id, entry.message_index));
+    entries.push(entry);
+}
+
+/// Counts per model, most rated first
+pub fn summarize(entries: &[FeedbackEntry]) -> Vec<ModelFeedback> {
+    let mut summaries: Vec<ModelFeedback> = Vec::new();
+    for entry in entries {
+        let index = match summaries.iter().position(|s| s.model == entry.model) {
+            Some(index) => index,
+            None => {
+                summaries.push(ModelFeedback {
+                    model: entry.model.clone(),
+                    up: 0,
+                    down: 0,
+                });
+                summaries.len() - 1
+            }
+        };
+        match entry.rating {
+            Rating::Up => summaries[index].up += 1,
+            Rating::Down => summaries[index].down += 1,
+        }
+    }
+    summaries.sort_by(|a, b| {
+        (b.up + b.down)
+            .cmp(&(a.up + a.down))
+            .then(a.model.cmp(&b.model))
+    });
+    summaries
+}
+
+fn get_feedback_dir() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("feedback"))
+}
+
+fn get_feedback_path(model: &str) -> Result<PathBuf, StorageError> {
+    let name: String = model
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok(get_feedback_dir()?.join(format!("{}.jsonl", name)))
+}
+
+/// Load one model's ratings, skipping unreadable lines
+pub fn load_feedback(model: &str) -> Vec<FeedbackEntry> {
+    get_feedback_path(model)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn save_feedback(model: &str, entries: &[FeedbackEntry]) -> Result<(), StorageError> {
+    fs::create_dir_all(get_feedback_dir()?)?;
+    let path = get_feedback_path(model)?;
+    if entries.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&serde_json::to_string(entry)?);
+        content.push('\n');
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Save a rating, replacing any earlier rating of the same message
+pub fn record_feedback(entry: &FeedbackEntry) -> Result<(), StorageError> {
+    let mut entries = load_feedback(&entry.model);
+    upsert(&mut entries, entry.clone());
+    save_feedback(&entry.model, &entries)
+}
+
+/// Remove the rating of a message
+pub fn remove_feedback(
+    model: &str,
+    conversation_id: &str,
+    message_index: usize,
+) -> Result<(), StorageError> {
+    let mut entries = load_feedback(model);
+    entries.retain(|e| !e.same_message(conversation_id, message_index));
+    save_feedback(model, &entries)
+}
+
+/// Every rating across models, newest first
+pub fn load_all_feedback() -> Vec<FeedbackEntry> {
+    let Ok(entries) = get_feedback_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    let mut all: Vec<FeedbackEntry> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<FeedbackEntry>(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    all.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+    all
+}
+
+/// The rating of a message, whichever model it was filed under
+pub fn find_feedback(conversation_id: &str, message_index: usize) -> Option<FeedbackEntry> {
+    load_all_feedback()
+        .into_iter()
+        .find(|e| e.same_message(conversation_id, message_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(model: &str, index: usize, rating: Rating) -> FeedbackEntry {
+        FeedbackEntry {
+            timestamp: Utc::now(),
+            model: model.to_string(),
+            conversation_id: "c1".to_string(),
+            message_index: index,
+            rating,
+            comment: String::new(),
+            prompt: "Question".to_string(),
+            response: "Réponse".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_upsert_replaces_rating() {
+        let mut entries = vec![entry("qwen", 1, Rating::Up), entry("qwen", 3, Rating::Up)];
+        upsert(&mut entries, entry("qwen", 1, Rating::Down));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries
+                .iter()
+                .find(|e| e.message_index == 1)
+                .unwrap()
+                .rating,
+            Rating::Down
+        );
+    }
+
+    #[test]
+    fn test_summarize() {
+        let entries = vec![
+            entry("qwen", 1, Rating::Up),
+            entry("llama", 1, Rating::Down),
+            entry("qwen", 3, Rating::Up),
+            entry("qwen", 5, Rating::Down),
+        ];
+        let summary = summarize(&entries);
+        assert_eq!(
+            summary[0],
+            ModelFeedback {
+                model: "qwen".into(),
+                up: 2,
+                down: 1
+            }
+        );
+        assert_eq!(summary[1].model, "llama");
+        assert!((summary[0].approval() - 2.0 / 3.0).abs() < 1e-9);
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! Provides functionality to download GGUF models from HuggingFace Hub.
 
-use crate::storage::get_data_dir;
+use crate::storage::get_base_data_dir;
+use crate::system::certificates::describe_error;
+use crate::system::proxy::{client_builder, ProxyClient};
+use crate::system::disk::ensure_free_space;
 use std::fs;
 use std::path::PathBuf;
 use tokio::fs::File;
@@ -163,8 +166,8 @@ pub async fn download_model(
 
     let safe_filename = sanitize_local_filename(&filename)?;
 
-    // Get models directory
-    let models_dir = get_data_dir()
+    // Get models directory (shared by all profiles)
+    let models_dir = get_base_data_dir()
         .map_err(|e| format!("Failed to get data dir: {}", e))?
         .join("models");
 
@@ -185,7 +188,7 @@ pub async fn download_model(
 
     // Download the file
     tracing::info!("Downloading from: {}", download_url);
-    let client = reqwest::Client::builder()
+    let client = client_builder(ProxyClient::ModelDownloads)
         .timeout(std::time::Duration::from_secs(3600)) // 1 hour timeout for large models
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
@@ -195,7 +198,7 @@ pub async fn download_model(
         .header("User-Agent", "clawRS/0.2.0")
         .send()
         .await
-        .map_err(|e| format!("Download failed: {}", e))?;
+        .map_err(|e| format!("Download failed: {}", describe_error(&e)))?;
 
     if !response.status().is_success() {
         return Err(format!("Download failed with status: {}", response.status()));
@@ -206,6 +209,7 @@ pub async fn download_model(
         .ok_or("Could not determine file size")?;
     
     tracing::info!("File size: {} bytes ({} MB)", total_size, total_size / 1024 / 1024);
+    ensure_free_space(&models_dir, total_size).map_err(|e| e.to_string())?;
 
     // Write to temp file first
     let mut temp_file = File::create(&temp_path)
@@ -251,12 +255,14 @@ pub async fn download_model(
 async fn list_gguf_files(repo_id: &str) -> Result<Vec<String>, String> {
     let api_url = format!("https://huggingface.co/api/models/{}/tree/main", repo_id);
 
-    let client = reqwest::Client::new();
+    let client = client_builder(ProxyClient::ModelDownloads)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
     let response = client
         .get(&api_url)
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch repo info: {}", e))?;
+        .map_err(|e| format!("Failed to fetch repo info: {}", describe_error(&e)))?;
 
     if !response.status().is_success() {
         return Err(format!("API error: {}", response.status()));
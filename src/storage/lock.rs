@@ -0,0 +1,140 @@
+//! Locked mode
+//!
+//! For kiosks, classrooms and demos: while locked, settings can't be opened
+//! without the PIN (if one is set), only read-only tools are offered to the
+//! agent and conversations live in memory only (see
+//! [`conversations::set_ephemeral`](crate::storage::conversations::set_ephemeral)).
+
+use crate::agent::get_tool_permission;
+use crate::agent::permissions::PermissionLevel;
+use base64::Engine;
+use ring::pbkdf2;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LockSettings {
+    pub enabled: bool,
+    /// Salted PBKDF2 hash of the PIN (see [`hash_pin`]); `None` unlocks
+    /// without one
+    #[serde(default)]
+    pub pin_hash: Option<String>,
+}
+
+/// PBKDF2 rounds for new PINs: a short PIN only resists brute force on a
+/// copied settings file if each guess is slow
+const PIN_ITERATIONS: u32 = 600_000;
+const PIN_HASH_PREFIX: &str = "pbkdf2-sha256";
+
+/// `pbkdf2-sha256$<iterations>$<salt>$<hash>`, base64 salt and hash
+fn hash_pin(pin: &str) -> String {
+    let salt = uuid::Uuid::new_v4().into_bytes();
+    let iterations = NonZeroU32::new(PIN_ITERATIONS).unwrap_or(NonZeroU32::MIN);
+    let mut hash = [0u8; 32];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, pin.as_bytes(), &mut hash);
+    let engine = base64::engine::general_purpose::STANDARD;
+    format!(
+        "{PIN_HASH_PREFIX}${}${}${}",
+        iterations,
+        engine.encode(salt),
+        engine.encode(hash)
+    )
+}
+
+/// Whether `pin` matches a hash from [`hash_pin`]; false for anything else
+fn verify_pin(pin: &str, stored: &str) -> bool {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let mut parts = stored.split('$');
+    let (Some(PIN_HASH_PREFIX), Some(iterations), Some(salt), Some(hash), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let (Some(iterations), Ok(salt), Ok(hash)) = (
+        iterations.parse().ok().and_then(NonZeroU32::new),
+        engine.decode(salt),
+        engine.decode(hash),
+    ) else {
+        return false;
+    };
+    pbkdf2::verify(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, pin.as_bytes(), &hash).is_ok()
+}
+
+impl LockSettings {
+    /// Lock, with a PIN unless `pin` is empty
+    pub fn lock(&mut self, pin: &str) {
+        let pin = pin.trim();
+        self.enabled = true;
+        self.pin_hash = (!pin.is_empty()).then(|| hash_pin(pin));
+    }
+
+    /// Unlock if `pin` matches; returns whether it did
+    pub fn unlock(&mut self, pin: &str) -> bool {
+        let matches = match &self.pin_hash {
+            Some(hash) => verify_pin(pin.trim(), hash),
+            None => true,
+        };
+        if matches {
+            self.enabled = false;
+            self.pin_hash = None;
+        }
+        matches
+    }
+
+    pub fn has_pin(&self) -> bool {
+        self.pin_hash.is_some()
+    }
+}
+
+/// Tools the agent keeps while locked: read-only ones, minus skills (only
+/// `skill_list` stays), which may run scripts with their own grants
+pub fn allowed_when_locked(tool: &str) -> bool {
+    let is_skill = tool.starts_with("skill_") && tool != "skill_list";
+    !is_skill && get_tool_permission(tool) == PermissionLevel::ReadOnly
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_with_pin() {
+        let mut lock = LockSettings::default();
+        lock.lock(" 1234 ");
+        assert!(lock.enabled && lock.has_pin());
+        assert!(!lock.pin_hash.as_deref().unwrap().contains("1234"));
+        // Salted: the same PIN never gives the same hash twice
+        assert_ne!(lock.pin_hash.as_deref(), Some(hash_pin("1234").as_str()));
+        assert!(!lock.unlock("0000"));
+        assert!(lock.enabled);
+        assert!(lock.unlock("1234"));
+        assert!(!lock.enabled && !lock.has_pin());
+
+        lock.lock("");
+        assert!(lock.enabled && !lock.has_pin());
+        assert!(lock.unlock(""));
+    }
+
+    #[test]
+    fn test_verify_pin_rejects_malformed_hashes() {
+        let hash = hash_pin("42");
+        assert!(verify_pin("42", &hash));
+        assert!(!verify_pin("43", &hash));
+        assert!(!verify_pin("42", &format!("{hash}$extra")));
+        assert!(!verify_pin("42", "pbkdf2-sha256$0$AAAA$AAAA"));
+        // Unsalted SHA-256 hex, as earlier builds stored it
+        assert!(!verify_pin("42", "73475cb40a568e8da8a045ced110137e159f890ac4da883b6b17dc651b3a8049"));
+    }
+
+    #[test]
+    fn test_allowed_when_locked() {
+        assert!(allowed_when_locked("file_read"));
+        assert!(!allowed_when_locked("file_write"));
+        assert!(!allowed_when_locked("bash"));
+        assert!(!allowed_when_locked("command"));
+        assert!(!allowed_when_locked("web_search"));
+        assert!(!allowed_when_locked("skill_invoke"));
+        assert!(!allowed_when_locked("skill_weather"));
+        assert!(allowed_when_locked("skill_list"));
+    }
+}
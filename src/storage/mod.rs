@@ -5,10 +5,26 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
+pub mod attachments;
+pub mod audit_log;
 pub mod conversations;
+pub mod dataset;
+pub mod escalation_log;
+pub mod feedback;
 pub mod huggingface;
+pub mod lock;
+pub mod model_profiles;
+pub mod model_sources;
 pub mod models;
+pub mod ollama;
+pub mod portable;
+pub mod profiles;
 pub mod settings;
+pub mod snippets;
+pub mod suggestions;
+pub mod system_prompts;
+pub mod tool_cache;
+pub mod tool_recordings;
 
 /// Storage-related errors
 #[derive(Debug, Error)]
@@ -23,23 +39,37 @@ pub enum StorageError {
     ConversationNotFound(String),
 }
 
-/// Get the application data directory
-///
-/// Returns the platform-specific application data directory:
+/// Get the platform-specific base data directory, shared by all profiles:
 /// - Windows: `C:\Users\{user}\AppData\Roaming\clawRS\clawRS`
 /// - macOS: `/Users/{user}/Library/Application Support/com.clawRS.clawRS`
 /// - Linux: `/home/{user}/.local/share/clawRS`
-pub fn get_data_dir() -> Result<PathBuf, StorageError> {
+///
+/// In [`portable`] mode, `data/` beside the executable instead.
+pub fn get_base_data_dir() -> Result<PathBuf, StorageError> {
+    if let Some(dir) = portable::portable_data_dir() {
+        return Ok(dir);
+    }
     directories::ProjectDirs::from("com", "clawRS", "clawRS")
         .map(|dirs| dirs.data_dir().to_path_buf())
         .ok_or_else(|| StorageError::DataDirError("Could not determine data directory".to_string()))
 }
 
+/// Get the application data directory of the active profile
+///
+/// The default profile uses the base data directory itself; see
+/// [`profiles`] for the others.
+pub fn get_data_dir() -> Result<PathBuf, StorageError> {
+    Ok(profiles::profile_dir(
+        &get_base_data_dir()?,
+        &profiles::active_profile(),
+    ))
+}
+
 /// Initialize the storage directory structure
 ///
 /// Creates the following directories:
 /// - `{data_dir}/conversations/` - For conversation JSON files
-/// - `{data_dir}/models/` - Default models directory
+/// - `{base_data_dir}/models/` - Default models directory, shared by profiles
 /// - `{data_dir}/settings.json` - Created by settings module
 pub fn init_storage() -> Result<(), StorageError> {
     let data_dir = get_data_dir()?;
@@ -49,7 +79,7 @@ pub fn init_storage() -> Result<(), StorageError> {
     std::fs::create_dir_all(&conversations_dir)?;
 
     // Create default models directory
-    let models_dir = data_dir.join("models");
+    let models_dir = get_base_data_dir()?.join("models");
     std::fs::create_dir_all(&models_dir)?;
 
     tracing::info!("Initialized storage at: {}", data_dir.display());
@@ -0,0 +1,188 @@
+//! Per-model profiles: display aliases, favorites, last use and output
+//! cleanup
+//!
+//! Profiles are keyed by model path and stored in the settings. Pickers use
+//! them to show favorites first and to fold quantizations of the same model
+//! (`Qwen2.5-7B-Q4_K_M`, `Qwen2.5-7B-Q8_0`, ...) behind a "show all" toggle.
+
+use crate::inference::artifacts::OutputCleanup;
+use crate::storage::models::ModelInfo;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelProfile {
+    /// Name shown instead of the file name
+    #[serde(default)]
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// When the model was last loaded
+    #[serde(default)]
+    pub last_used: Option<DateTime<Utc>>,
+    /// Template artifacts stripped from its replies
+    #[serde(default)]
+    pub output_cleanup: OutputCleanup,
+}
+
+/// Profiles by model path
+pub type ModelProfiles = HashMap<String, ModelProfile>;
+
+fn key(model: &ModelInfo) -> String {
+    model.path.to_string_lossy().to_string()
+}
+
+/// Alias if set, else the file name without extension
+pub fn picker_name(model: &ModelInfo, profiles: &ModelProfiles) -> String {
+    profiles
+        .get(&key(model))
+        .and_then(|p| p.alias.clone())
+        .filter(|alias| !alias.trim().is_empty())
+        .unwrap_or_else(|| model.display_name().to_string())
+}
+
+pub fn is_favorite(model: &ModelInfo, profiles: &ModelProfiles) -> bool {
+    profiles.get(&key(model)).is_some_and(|p| p.favorite)
+}
+
+fn is_quant_token(token: &str) -> bool {
+    let upper = token.to_ascii_uppercase();
+    let quant = upper
+        .strip_prefix("IQ")
+        .or_else(|| upper.strip_prefix('Q'))
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+    quant || matches!(upper.as_str(), "F16" | "F32" | "BF16" | "FP16")
+}
+
+/// Model name with the quantization suffix removed, used to group variants
+pub fn quant_group(filename: &str) -> String {
+    let stem = filename.strip_suffix(".gguf").unwrap_or(filename);
+    let tokens: Vec<&str> = stem.split(['-', '.', '_']).collect();
+    // Quant labels like Q4_K_M span several tokens; cut at the first one
+    match tokens.iter().position(|t| is_quant_token(t)) {
+        Some(0) | None => stem.to_lowercase(),
+        Some(index) => {
+            let cut: usize = tokens[..index].iter().map(|t| t.len() + 1).sum();
+            stem[..cut - 1].to_lowercase()
+        }
+    }
+}
+
+/// Order models for a picker: favorites first, then by name
+///
+/// Unless `show_all` is set, only one variant of each quantization group is
+/// kept (favorites and `keep`, usually the loaded model, always stay): the
+/// most recently used one, else the first. Returns the models and how many
+/// were hidden.
+pub fn arrange_for_picker(
+    models: &[ModelInfo],
+    profiles: &ModelProfiles,
+    keep: Option<&str>,
+    show_all: bool,
+) -> (Vec<ModelInfo>, usize) {
+    let mut shown: Vec<ModelInfo> = if show_all {
+        models.to_vec()
+    } else {
+        let mut groups: HashMap<String, Vec<&ModelInfo>> = HashMap::new();
+        for model in models {
+            groups
+                .entry(quant_group(&model.filename))
+                .or_default()
+                .push(model);
+        }
+        models
+            .iter()
+            .filter(|model| {
+                let group = &groups[&quant_group(&model.filename)];
+                if group.len() == 1
+                    || is_favorite(model, profiles)
+                    || keep == Some(key(model).as_str())
+                {
+                    return true;
+                }
+                let primary = group
+                    .iter()
+                    .max_by_key(|m| profiles.get(&key(m)).and_then(|p| p.last_used))
+                    .filter(|m| profiles.get(&key(m)).is_some_and(|p| p.last_used.is_some()))
+                    .unwrap_or(&group[0]);
+                primary.path == model.path
+            })
+            .cloned()
+            .collect()
+    };
+    let hidden = models.len() - shown.len();
+    shown.sort_by_key(|m| {
+        (
+            !is_favorite(m, profiles),
+            picker_name(m, profiles).to_lowercase(),
+        )
+    });
+    (shown, hidden)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::ModelRole;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn model(name: &str) -> ModelInfo {
+        ModelInfo {
+            path: PathBuf::from(format!("/models/{}", name)),
+            filename: name.to_string(),
+            size_bytes: 0,
+            last_modified: SystemTime::now(),
+            role: ModelRole::Chat,
+        }
+    }
+
+    #[test]
+    fn test_quant_group() {
+        assert_eq!(
+            quant_group("Qwen2.5-7B-Instruct-Q4_K_M.gguf"),
+            "qwen2.5-7b-instruct"
+        );
+        assert_eq!(
+            quant_group("qwen2.5-7b-instruct-q8_0.gguf"),
+            "qwen2.5-7b-instruct"
+        );
+        assert_eq!(quant_group("Llama-3.2-3B.IQ3_XS.gguf"), "llama-3.2-3b");
+        assert_eq!(quant_group("phi-3-mini-f16.gguf"), "phi-3-mini");
+        assert_eq!(quant_group("mistral-7b.gguf"), "mistral-7b");
+    }
+
+    #[test]
+    fn test_arrange_for_picker() {
+        let models = vec![
+            model("Qwen2.5-7B-Q4_K_M.gguf"),
+            model("Qwen2.5-7B-Q8_0.gguf"),
+            model("mistral-7b-Q4_K_M.gguf"),
+        ];
+        let mut profiles = ModelProfiles::new();
+        profiles.insert(
+            "/models/Qwen2.5-7B-Q8_0.gguf".to_string(),
+            ModelProfile {
+                last_used: Some(Utc::now()),
+                ..Default::default()
+            },
+        );
+        profiles.insert(
+            "/models/mistral-7b-Q4_K_M.gguf".to_string(),
+            ModelProfile {
+                favorite: true,
+                alias: Some("Chat – Mistral".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let (shown, hidden) = arrange_for_picker(&models, &profiles, None, false);
+        assert_eq!(hidden, 1);
+        let names: Vec<String> = shown.iter().map(|m| picker_name(m, &profiles)).collect();
+        assert_eq!(names, vec!["Chat – Mistral", "Qwen2.5-7B-Q8_0"]);
+
+        let (shown, hidden) = arrange_for_picker(&models, &profiles, None, true);
+        assert_eq!((shown.len(), hidden), (3, 0));
+    }
+}
@@ -0,0 +1,168 @@
+//! Model folders of other local LLM apps
+//!
+//! LM Studio and GPT4All keep plain GGUF files in well-known folders. When
+//! the user opts in, those folders are scanned alongside the models
+//! directory and their models are badged with the app they came from.
+
+use crate::storage::models::{ModelInfo, ModelRole};
+use crate::storage::ollama::is_ollama_blob;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// LM Studio nests models as `<publisher>/<repo>/<file>.gguf`
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Another app whose model folder can be scanned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalSource {
+    LmStudio,
+    Gpt4All,
+}
+
+impl ExternalSource {
+    pub const ALL: [ExternalSource; 2] = [Self::LmStudio, Self::Gpt4All];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::LmStudio => "LM Studio",
+            Self::Gpt4All => "GPT4All",
+        }
+    }
+
+    /// Folders this app is known to store models in, most recent layout first
+    pub fn known_paths(&self) -> Vec<PathBuf> {
+        match self {
+            Self::LmStudio => directories::UserDirs::new()
+                .map(|dirs| {
+                    let home = dirs.home_dir();
+                    vec![
+                        home.join(".lmstudio").join("models"),
+                        home.join(".cache").join("lm-studio").join("models"),
+                    ]
+                })
+                .unwrap_or_default(),
+            Self::Gpt4All => directories::BaseDirs::new()
+                .map(|dirs| vec![dirs.data_local_dir().join("nomic.ai").join("GPT4All")])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// First known folder present on this machine
+    pub fn detect(&self) -> Option<PathBuf> {
+        self.known_paths().into_iter().find(|p| p.is_dir())
+    }
+}
+
+/// GGUF models under `dir`, up to a few folders deep
+pub fn scan_source_directory(dir: &Path) -> Vec<ModelInfo> {
+    let mut models = Vec::new();
+    walk(dir, 0, &mut models);
+    models.sort_by(|a, b| a.filename.cmp(&b.filename));
+    tracing::info!(
+        "Found {} external model(s) in {}",
+        models.len(),
+        dir.display()
+    );
+    models
+}
+
+fn walk(dir: &Path, depth: usize, out: &mut Vec<ModelInfo>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                walk(&path, depth + 1, out);
+            }
+            continue;
+        }
+        let is_gguf = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("gguf"));
+        let Some(filename) = path.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        // Vision projectors ship next to the weights but can't be loaded alone
+        if !is_gguf || filename.to_lowercase().starts_with("mmproj") {
+            continue;
+        }
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        out.push(ModelInfo {
+            role: ModelRole::from_filename(&filename).unwrap_or_default(),
+            path,
+            filename,
+            size_bytes: metadata.len(),
+            last_modified: metadata
+                .modified()
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH),
+        });
+    }
+}
+
+/// Badge naming the app a model file belongs to, `None` for own models
+pub fn source_badge(path: &Path) -> Option<&'static str> {
+    if is_ollama_blob(path) {
+        return Some("Ollama");
+    }
+    ExternalSource::ALL
+        .into_iter()
+        .find(|source| {
+            source
+                .known_paths()
+                .iter()
+                .any(|root| path.starts_with(root))
+        })
+        .map(|source| source.label())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_source_directory_nested() {
+        let root = TempDir::new().unwrap();
+        let repo = root.path().join("lmstudio-community/Qwen2.5-7B-GGUF");
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("Qwen2.5-7B-Q4_K_M.gguf"), b"GGUF").unwrap();
+        fs::write(repo.join("mmproj-model-f16.gguf"), b"GGUF").unwrap();
+        fs::write(repo.join("README.md"), b"readme").unwrap();
+        fs::write(root.path().join("nomic-embed-text-v1.5.f16.gguf"), b"GGUF").unwrap();
+
+        let models = scan_source_directory(root.path());
+        assert_eq!(models.len(), 2);
+        let qwen = models
+            .iter()
+            .find(|m| m.filename.starts_with("Qwen"))
+            .unwrap();
+        assert_eq!(qwen.role, ModelRole::Chat);
+        let embed = models
+            .iter()
+            .find(|m| m.filename.starts_with("nomic"))
+            .unwrap();
+        assert_eq!(embed.role, ModelRole::Embedding);
+    }
+
+    #[test]
+    fn test_source_badge() {
+        assert_eq!(
+            source_badge(Path::new("/data/ollama/blobs/sha256-abc")),
+            Some("Ollama")
+        );
+        assert_eq!(source_badge(Path::new("/data/models/model.gguf")), None);
+        if let Some(root) = ExternalSource::LmStudio.known_paths().into_iter().next() {
+            assert_eq!(
+                source_badge(&root.join("a/b/model.gguf")),
+                Some("LM Studio")
+            );
+        }
+    }
+}
@@ -1,13 +1,82 @@
 //! Model metadata storage
 //!
-//! Tracks installed models and their configurations.
+//! Tracks installed models and their configurations. Models are tagged with
+//! a role (chat, embedding, draft, whisper) from the directory they were
+//! found in or, for files in the chat directory, from their filename.
 
+use crate::storage::model_sources::scan_source_directory;
+use crate::storage::ollama::{ollama_models_dir, scan_ollama_models};
+use crate::storage::settings::AppSettings;
 use crate::storage::{get_data_dir, StorageError};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+/// What a model file is used for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelRole {
+    /// Main conversational model
+    #[default]
+    Chat,
+    /// Sentence embedding model (retrieval, memory)
+    Embedding,
+    /// Small model for speculative decoding
+    Draft,
+    /// Speech-to-text model
+    Whisper,
+}
+
+impl ModelRole {
+    pub const ALL: [ModelRole; 4] = [Self::Chat, Self::Embedding, Self::Draft, Self::Whisper];
+
+    pub fn label(&self, is_en: bool) -> &'static str {
+        match (self, is_en) {
+            (Self::Chat, true) => "Chat",
+            (Self::Chat, false) => "Conversation",
+            (Self::Embedding, _) => "Embedding",
+            (Self::Draft, true) => "Draft (speculative)",
+            (Self::Draft, false) => "Brouillon (speculatif)",
+            (Self::Whisper, _) => "Whisper",
+        }
+    }
+
+    /// Default subdirectory of the models directory for this role
+    pub fn subdirectory(&self) -> &'static str {
+        match self {
+            Self::Chat => "",
+            Self::Embedding => "embedding",
+            Self::Draft => "draft",
+            Self::Whisper => "whisper",
+        }
+    }
+
+    /// File extensions of models for this role
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            // whisper.cpp ships ggml-*.bin files
+            Self::Whisper => &["gguf", "bin"],
+            _ => &["gguf"],
+        }
+    }
+
+    /// Guess a role from well-known model names
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let name = filename.to_lowercase();
+        let embedding_markers = ["embed", "bge-", "e5-", "gte-", "minilm", "mxbai"];
+        if name.contains("whisper") {
+            Some(Self::Whisper)
+        } else if embedding_markers.iter().any(|m| name.contains(m)) {
+            Some(Self::Embedding)
+        } else if name.contains("draft") {
+            Some(Self::Draft)
+        } else {
+            None
+        }
+    }
+}
+
 /// Information about a GGUF model file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
@@ -19,11 +88,14 @@ pub struct ModelInfo {
     pub size_bytes: u64,
     /// Last modification time
     pub last_modified: SystemTime,
+    /// What the model is used for
+    #[serde(default)]
+    pub role: ModelRole,
 }
 
 impl ModelInfo {
     /// Create a ModelInfo from a file path
-    fn from_path(path: PathBuf) -> Result<Self, std::io::Error> {
+    fn from_path(path: PathBuf, role: ModelRole) -> Result<Self, std::io::Error> {
         let metadata = fs::metadata(&path)?;
         let filename = path
             .file_name()
@@ -36,9 +108,15 @@ impl ModelInfo {
             filename,
             size_bytes: metadata.len(),
             last_modified: metadata.modified()?,
+            role,
         })
     }
 
+    /// Name shown in model pickers
+    pub fn display_name(&self) -> &str {
+        self.filename.strip_suffix(".gguf").unwrap_or(&self.filename)
+    }
+
     /// Get a human-readable size string
     pub fn size_string(&self) -> String {
         let bytes = self.size_bytes as f64;
@@ -57,8 +135,14 @@ impl ModelInfo {
 
 /// Scan a directory for GGUF model files
 ///
-/// Returns a list of ModelInfo for all .gguf files found in the directory
-pub fn scan_models_directory(directory: &PathBuf) -> Result<Vec<ModelInfo>, StorageError> {
+/// Returns a list of ModelInfo for all .gguf files found in the directory,
+/// tagged with the role their filename suggests (chat otherwise)
+pub fn scan_models_directory(directory: &Path) -> Result<Vec<ModelInfo>, StorageError> {
+    scan_directory(directory, None)
+}
+
+/// Scan a directory, tagging every model with `role` when given
+fn scan_directory(directory: &Path, role: Option<ModelRole>) -> Result<Vec<ModelInfo>, StorageError> {
     if !directory.exists() {
         tracing::warn!("Models directory does not exist: {}", directory.display());
         return Ok(vec![]);
@@ -69,26 +153,36 @@ pub fn scan_models_directory(directory: &PathBuf) -> Result<Vec<ModelInfo>, Stor
         return Ok(vec![]);
     }
 
+    let extensions = role.unwrap_or_default().extensions();
     let mut models = vec![];
 
     for entry in fs::read_dir(directory)? {
         let entry = entry?;
         let path = entry.path();
 
-        // Check if it's a .gguf file
-        if path.is_file() {
-            if let Some(extension) = path.extension() {
-                if extension.to_str() == Some("gguf") {
-                    match ModelInfo::from_path(path.clone()) {
-                        Ok(model_info) => {
-                            tracing::debug!("Found model: {}", model_info.filename);
-                            models.push(model_info);
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to read model file {:?}: {}", path, e);
-                        }
-                    }
-                }
+        // Check it's a model file
+        let is_model = path.is_file()
+            && path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| extensions.contains(&e));
+        if !is_model {
+            continue;
+        }
+
+        let file_role = role.unwrap_or_else(|| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(ModelRole::from_filename)
+                .unwrap_or_default()
+        });
+        match ModelInfo::from_path(path.clone(), file_role) {
+            Ok(model_info) => {
+                tracing::debug!("Found model: {} ({:?})", model_info.filename, model_info.role);
+                models.push(model_info);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read model file {:?}: {}", path, e);
             }
         }
     }
@@ -101,6 +195,42 @@ pub fn scan_models_directory(directory: &PathBuf) -> Result<Vec<ModelInfo>, Stor
     Ok(models)
 }
 
+/// Models usable for `role`
+///
+/// Combines the role's own directory with files in the chat directory whose
+/// names identify them as that role, plus matching Ollama models when the
+/// import is enabled and models from the enabled external app folders.
+/// Chat results exclude auxiliary models.
+pub fn scan_models_for_role(settings: &AppSettings, role: ModelRole) -> Vec<ModelInfo> {
+    let mut models: Vec<ModelInfo> = scan_directory(&settings.models_directory, None)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|m| m.role == role)
+        .collect();
+
+    let mut extra = Vec::new();
+    if role != ModelRole::Chat {
+        extra.extend(scan_directory(&settings.models_directory_for(role), Some(role)).unwrap_or_default());
+    }
+    if settings.ollama_import {
+        if let Some(root) = ollama_models_dir().filter(|root| root.is_dir()) {
+            extra.extend(scan_ollama_models(&root).into_iter().filter(|m| m.role == role));
+        }
+    }
+    for source in &settings.external_model_sources {
+        if let Some(dir) = source.detect() {
+            extra.extend(scan_source_directory(&dir).into_iter().filter(|m| m.role == role));
+        }
+    }
+    for model in extra {
+        if !models.iter().any(|m| m.path == model.path) {
+            models.push(model);
+        }
+    }
+    models.sort_by(|a, b| a.filename.cmp(&b.filename));
+    models
+}
+
 /// Scan the default models directory
 ///
 /// Uses the models directory from the application data directory
@@ -123,7 +253,7 @@ mod tests {
         // Create a test file
         File::create(&model_path).unwrap();
 
-        let model_info = ModelInfo::from_path(model_path.clone()).unwrap();
+        let model_info = ModelInfo::from_path(model_path.clone(), ModelRole::Chat).unwrap();
 
         assert_eq!(model_info.filename, "test_model.gguf");
         assert_eq!(model_info.path, model_path);
@@ -137,6 +267,7 @@ mod tests {
             filename: "test.gguf".to_string(),
             size_bytes: 1024,
             last_modified: SystemTime::now(),
+            role: ModelRole::Chat,
         };
 
         assert_eq!(model_info.size_string(), "1.00 KB");
@@ -146,6 +277,7 @@ mod tests {
             filename: "large.gguf".to_string(),
             size_bytes: 1024 * 1024 * 1024 * 3, // 3 GB
             last_modified: SystemTime::now(),
+            role: ModelRole::Chat,
         };
 
         assert!(large_model.size_string().contains("GB"));
@@ -160,7 +292,7 @@ mod tests {
         File::create(temp_dir.path().join("model2.gguf")).unwrap();
         File::create(temp_dir.path().join("not_a_model.txt")).unwrap();
 
-        let models = scan_models_directory(&temp_dir.path().to_path_buf()).unwrap();
+        let models = scan_models_directory(temp_dir.path()).unwrap();
 
         assert_eq!(models.len(), 2);
         assert!(models.iter().any(|m| m.filename == "model1.gguf"));
@@ -179,8 +311,40 @@ mod tests {
     #[test]
     fn test_scan_empty_directory() {
         let temp_dir = TempDir::new().unwrap();
-        let models = scan_models_directory(&temp_dir.path().to_path_buf()).unwrap();
+        let models = scan_models_directory(temp_dir.path()).unwrap();
 
         assert_eq!(models.len(), 0);
     }
+
+    #[test]
+    fn test_scan_models_for_role() {
+        let temp_dir = TempDir::new().unwrap();
+        let whisper_dir = TempDir::new().unwrap();
+        File::create(temp_dir.path().join("qwen2.5-7b-instruct.gguf")).unwrap();
+        File::create(temp_dir.path().join("nomic-embed-text-v1.5.gguf")).unwrap();
+        File::create(whisper_dir.path().join("ggml-base.en.bin")).unwrap();
+
+        let mut settings = AppSettings {
+            models_directory: temp_dir.path().to_path_buf(),
+            ollama_import: false,
+            ..AppSettings::default()
+        };
+        settings
+            .model_directories
+            .set(ModelRole::Whisper, Some(whisper_dir.path().to_path_buf()));
+
+        let chat = scan_models_for_role(&settings, ModelRole::Chat);
+        assert_eq!(chat.len(), 1);
+        assert_eq!(chat[0].filename, "qwen2.5-7b-instruct.gguf");
+
+        let embedding = scan_models_for_role(&settings, ModelRole::Embedding);
+        assert_eq!(embedding.len(), 1);
+        assert_eq!(embedding[0].role, ModelRole::Embedding);
+
+        let whisper = scan_models_for_role(&settings, ModelRole::Whisper);
+        assert_eq!(whisper.len(), 1);
+        assert_eq!(whisper[0].filename, "ggml-base.en.bin");
+
+        assert!(scan_models_for_role(&settings, ModelRole::Draft).is_empty());
+    }
 }
@@ -0,0 +1,209 @@
+//! Ollama model store discovery
+//!
+//! Ollama keeps GGUF weights as content-addressed blobs
+//! (`{root}/blobs/sha256-<digest>`) referenced by manifests
+//! (`{root}/manifests/<registry>/<namespace>/<model>/<tag>`). Reading the
+//! manifests gives us friendly names for the blobs, which llama.cpp can
+//! load in place, so nothing is copied.
+
+use crate::storage::models::{ModelInfo, ModelRole};
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Media type of the layer holding the model weights
+const MODEL_LAYER: &str = "application/vnd.ollama.image.model";
+
+/// Registry whose models are shown without a host prefix
+const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Layer {
+    #[serde(rename = "mediaType")]
+    media_type: String,
+    digest: String,
+}
+
+/// Root of the Ollama store: `$OLLAMA_MODELS`, else `~/.ollama/models`
+pub fn ollama_models_dir() -> Option<PathBuf> {
+    if let Some(custom) = std::env::var_os("OLLAMA_MODELS").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(custom));
+    }
+    directories::UserDirs::new().map(|dirs| dirs.home_dir().join(".ollama").join("models"))
+}
+
+/// Friendly name from a manifest path relative to `manifests/`:
+/// `registry.ollama.ai/library/llama3.1/8b` becomes `llama3.1:8b`
+pub fn friendly_name(relative: &Path) -> Option<String> {
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let (tag, rest) = parts.split_last()?;
+    let (model, prefix) = rest.split_last()?;
+
+    let prefix: Vec<&str> = match prefix {
+        [registry, namespace] if registry == DEFAULT_REGISTRY && namespace == "library" => vec![],
+        [registry, rest @ ..] if registry == DEFAULT_REGISTRY => rest.iter().map(String::as_str).collect(),
+        all => all.iter().map(String::as_str).collect(),
+    };
+    let mut name = prefix.join("/");
+    if !name.is_empty() {
+        name.push('/');
+    }
+    Some(format!("{}{}:{}", name, model, tag))
+}
+
+/// Blob file for a `sha256:<hex>` digest; older Ollama versions kept the colon
+fn blob_path(root: &Path, digest: &str) -> Option<PathBuf> {
+    let blobs = root.join("blobs");
+    [digest.replace(':', "-"), digest.to_string()]
+        .into_iter()
+        .map(|name| blobs.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Whether `path` is a blob inside an Ollama store
+pub fn is_ollama_blob(path: &Path) -> bool {
+    let in_blobs = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .is_some_and(|name| name == "blobs");
+    let digest_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with("sha256"));
+    in_blobs && digest_name
+}
+
+fn is_gguf(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .map(|_| &magic == b"GGUF")
+        .unwrap_or(false)
+}
+
+/// Collect manifest files under `dir`
+fn collect_manifests(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_manifests(&path, out);
+        } else if path.is_file() {
+            out.push(path);
+        }
+    }
+}
+
+/// Models in the Ollama store at `root`, named after their manifests
+///
+/// Each returned `ModelInfo` points at the blob itself. Blobs that aren't
+/// GGUF (adapters, projectors) and manifests without weights are skipped.
+pub fn scan_ollama_models(root: &Path) -> Vec<ModelInfo> {
+    let manifests_dir = root.join("manifests");
+    let mut manifests = Vec::new();
+    collect_manifests(&manifests_dir, &mut manifests);
+
+    let mut models: Vec<ModelInfo> = Vec::new();
+    for manifest_path in manifests {
+        let Some(name) = manifest_path
+            .strip_prefix(&manifests_dir)
+            .ok()
+            .and_then(friendly_name)
+        else {
+            continue;
+        };
+        let manifest: Manifest = match fs::read_to_string(&manifest_path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                tracing::debug!("Skipping Ollama manifest {:?}: {}", manifest_path, e);
+                continue;
+            }
+        };
+
+        let Some(blob) = manifest
+            .layers
+            .iter()
+            .find(|layer| layer.media_type == MODEL_LAYER)
+            .and_then(|layer| blob_path(root, &layer.digest))
+        else {
+            continue;
+        };
+        if !is_gguf(&blob) || models.iter().any(|m| m.path == blob) {
+            continue;
+        }
+
+        let Ok(metadata) = fs::metadata(&blob) else {
+            continue;
+        };
+        models.push(ModelInfo {
+            role: ModelRole::from_filename(&name).unwrap_or_default(),
+            path: blob,
+            filename: name,
+            size_bytes: metadata.len(),
+            last_modified: metadata.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH),
+        });
+    }
+
+    models.sort_by(|a, b| a.filename.cmp(&b.filename));
+    tracing::info!("Found {} Ollama model(s) in {}", models.len(), root.display());
+    models
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_friendly_name() {
+        let name = |p: &str| friendly_name(Path::new(p));
+        assert_eq!(name("registry.ollama.ai/library/llama3.1/8b").as_deref(), Some("llama3.1:8b"));
+        assert_eq!(name("registry.ollama.ai/user/mymodel/latest").as_deref(), Some("user/mymodel:latest"));
+        assert_eq!(
+            name("hf.co/bartowski/Qwen2.5-7B-GGUF/Q4_K_M").as_deref(),
+            Some("hf.co/bartowski/Qwen2.5-7B-GGUF:Q4_K_M")
+        );
+        assert_eq!(name("latest"), None);
+    }
+
+    #[test]
+    fn test_scan_ollama_models() {
+        let root = TempDir::new().unwrap();
+        let blobs = root.path().join("blobs");
+        fs::create_dir_all(&blobs).unwrap();
+        fs::write(blobs.join("sha256-aaa"), b"GGUF\x03\x00\x00\x00rest").unwrap();
+        fs::write(blobs.join("sha256-bbb"), b"{\"not\": \"gguf\"}").unwrap();
+
+        let manifest = |digest: &str| {
+            format!(r#"{{"layers":[{{"mediaType":"{}","digest":"{}","size":12}}]}}"#, MODEL_LAYER, digest)
+        };
+        let library = root.path().join("manifests/registry.ollama.ai/library");
+        fs::create_dir_all(library.join("qwen2.5")).unwrap();
+        fs::create_dir_all(library.join("broken")).unwrap();
+        fs::write(library.join("qwen2.5/7b"), manifest("sha256:aaa")).unwrap();
+        // Same blob under a second tag is listed once
+        fs::write(library.join("qwen2.5/latest"), manifest("sha256:aaa")).unwrap();
+        fs::write(library.join("broken/latest"), manifest("sha256:bbb")).unwrap();
+
+        let models = scan_ollama_models(root.path());
+        assert_eq!(models.len(), 1);
+        assert!(models[0].filename.starts_with("qwen2.5:"));
+        assert_eq!(models[0].path, blobs.join("sha256-aaa"));
+        assert!(is_ollama_blob(&models[0].path));
+    }
+}
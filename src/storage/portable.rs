@@ -0,0 +1,125 @@
+//! Portable mode: all data in a folder beside the executable
+//!
+//! With a `portable.txt` file next to the binary, or when launched with
+//! `--portable` (which creates that file, so later launches stay portable),
+//! settings, conversations, profiles and the default models directory live
+//! in `data/` beside it instead of the user's data directory. The app can
+//! then run from an external drive and move between machines.
+//!
+//! "Beside the executable" means beside what the user sees and moves: the
+//! AppImage file on Linux (its binary runs from a read-only mount) and the
+//! `.app` bundle on macOS.
+
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Marker file that turns portable mode on
+pub const PORTABLE_MARKER: &str = "portable.txt";
+/// Command-line flag that turns portable mode on
+pub const PORTABLE_FLAG: &str = "--portable";
+/// Data folder created beside the executable
+const DATA_FOLDER: &str = "data";
+
+const MARKER_CONTENT: &str = "ClawRS stores its data in the data folder beside this file.\nDelete this file to use the user's data directory again.\n";
+
+static FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Resolved on first use, after `main` has looked at the flag
+static PORTABLE_DIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    let exe = std::env::current_exe().ok()?;
+    let appimage = std::env::var_os("APPIMAGE").map(PathBuf::from);
+    let dir = resolve(&app_dir(&exe, appimage.as_deref())?, FLAG.load(Ordering::Relaxed));
+    if let Some(dir) = &dir {
+        tracing::info!("Portable mode: data in {}", dir.display());
+    }
+    dir
+});
+
+/// Folder holding the app as the user sees it: the one containing the
+/// AppImage (`$APPIMAGE`) or the `.app` bundle, else the executable's
+fn app_dir(exe: &Path, appimage: Option<&Path>) -> Option<PathBuf> {
+    if let Some(appimage) = appimage.filter(|path| path.is_absolute()) {
+        return appimage.parent().map(Path::to_path_buf);
+    }
+    // Contents/MacOS/<binary> inside <name>.app
+    let bundle = exe
+        .ancestors()
+        .nth(3)
+        .filter(|bundle| bundle.extension().is_some_and(|ext| ext == "app"))
+        .filter(|bundle| exe.parent() == Some(bundle.join("Contents").join("MacOS").as_path()));
+    match bundle {
+        Some(bundle) => bundle.parent().map(Path::to_path_buf),
+        None => exe.parent().map(Path::to_path_buf),
+    }
+}
+
+/// Data folder for an executable in `exe_dir`, when portable mode is on
+fn resolve(exe_dir: &Path, flag: bool) -> Option<PathBuf> {
+    let marker = exe_dir.join(PORTABLE_MARKER);
+    if flag && !marker.exists() {
+        if let Err(e) = std::fs::write(&marker, MARKER_CONTENT) {
+            tracing::warn!("Could not create {}: {}", marker.display(), e);
+        }
+    }
+    (flag || marker.is_file()).then(|| exe_dir.join(DATA_FOLDER))
+}
+
+/// Take `--portable` out of the command line and remember it; call first
+/// thing in `main`, before anything touches the data directory
+pub fn take_flag(args: &mut Vec<String>) {
+    if strip_flag(args) {
+        FLAG.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Remove `--portable` from `args`; whether it was there
+fn strip_flag(args: &mut Vec<String>) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != PORTABLE_FLAG);
+    args.len() != before
+}
+
+/// Data folder beside the executable, `None` outside portable mode
+pub fn portable_data_dir() -> Option<PathBuf> {
+    PORTABLE_DIR.clone()
+}
+
+pub fn is_portable() -> bool {
+    PORTABLE_DIR.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(resolve(dir.path(), false), None);
+
+        assert_eq!(resolve(dir.path(), true), Some(dir.path().join("data")));
+        // The flag left the marker: the next launch stays portable without it
+        assert!(dir.path().join(PORTABLE_MARKER).is_file());
+        assert_eq!(resolve(dir.path(), false), Some(dir.path().join("data")));
+
+        let mut args = vec!["--portable".to_string(), "hello".to_string()];
+        assert!(strip_flag(&mut args));
+        assert_eq!(args, vec!["hello".to_string()]);
+        assert!(!strip_flag(&mut args));
+    }
+
+    #[test]
+    fn test_app_dir() {
+        let exe = Path::new("/opt/clawrs/clawrs");
+        assert_eq!(app_dir(exe, None), Some(PathBuf::from("/opt/clawrs")));
+
+        // An AppImage runs from a temporary mount
+        let mounted = Path::new("/tmp/.mount_ClawRSx1/usr/bin/clawrs");
+        let appimage = Path::new("/media/usb/ClawRS.AppImage");
+        assert_eq!(app_dir(mounted, Some(appimage)), Some(PathBuf::from("/media/usb")));
+
+        let bundled = Path::new("/Volumes/USB/ClawRS.app/Contents/MacOS/clawrs");
+        assert_eq!(app_dir(bundled, None), Some(PathBuf::from("/Volumes/USB")));
+    }
+}
@@ -0,0 +1,203 @@
+//! App profiles
+//!
+//! Each profile (work, personal, ...) has its own data directory, so its
+//! settings, personas, conversations and logs never mix with another's.
+//! The default profile keeps the base data directory; others live in
+//! `{base}/profiles/{id}/`. The profile list and the active profile are
+//! stored in `{base}/profiles.json`.
+
+use crate::storage::{get_base_data_dir, StorageError};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Id of the profile using the base data directory
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+impl Profile {
+    pub fn is_default(&self) -> bool {
+        self.id == DEFAULT_PROFILE
+    }
+
+    /// Display name; the default profile's is translated
+    pub fn label(&self, is_en: bool) -> String {
+        match (self.is_default(), is_en) {
+            (true, true) => "Default".to_string(),
+            (true, false) => "Par defaut".to_string(),
+            (false, _) => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    /// `None` is the default profile
+    #[serde(default)]
+    active: Option<String>,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+/// Active profile, read once from `profiles.json`
+static ACTIVE: Lazy<RwLock<Option<String>>> =
+    Lazy::new(|| RwLock::new(load_profiles_file().active));
+
+fn get_profiles_path() -> Result<PathBuf, StorageError> {
+    Ok(get_base_data_dir()?.join("profiles.json"))
+}
+
+fn load_profiles_file() -> ProfilesFile {
+    get_profiles_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| tracing::warn!("Failed to parse profiles.json: {}", e))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+fn save_profiles_file(file: &ProfilesFile) -> Result<(), StorageError> {
+    let path = get_profiles_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(file)?)?;
+    Ok(())
+}
+
+/// Data directory of a profile under `base`
+pub fn profile_dir(base: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_PROFILE {
+        base.to_path_buf()
+    } else {
+        base.join("profiles").join(id)
+    }
+}
+
+/// Id of the active profile
+pub fn active_profile() -> String {
+    ACTIVE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Every profile, the default one first
+pub fn list_profiles() -> Vec<Profile> {
+    let mut profiles = vec![Profile {
+        id: DEFAULT_PROFILE.to_string(),
+        name: "Default".to_string(),
+    }];
+    profiles.extend(load_profiles_file().profiles);
+    profiles
+}
+
+/// Lowercase ASCII id derived from `name`, unique among `taken`
+fn profile_id(name: &str, taken: &[String]) -> String {
+    let mut base = String::new();
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            base.push(c);
+        } else if !base.ends_with('-') {
+            base.push('-');
+        }
+    }
+    let base = match base.trim_matches('-') {
+        "" => "profile".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+    let mut id = base.clone();
+    let mut n = 2;
+    while id == DEFAULT_PROFILE || taken.contains(&id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+/// Add a profile and create its data directory; doesn't switch to it
+pub fn create_profile(name: &str) -> Result<Profile, StorageError> {
+    let mut file = load_profiles_file();
+    let taken: Vec<String> = file.profiles.iter().map(|p| p.id.clone()).collect();
+    let profile = Profile {
+        id: profile_id(name, &taken),
+        name: name.trim().to_string(),
+    };
+    fs::create_dir_all(profile_dir(&get_base_data_dir()?, &profile.id))?;
+    file.profiles.push(profile.clone());
+    save_profiles_file(&file)?;
+    tracing::info!("Created profile {}", profile.id);
+    Ok(profile)
+}
+
+/// Make `id` the active profile; every later storage call uses its directory
+pub fn switch_profile(id: &str) -> Result<(), StorageError> {
+    let mut file = load_profiles_file();
+    if id != DEFAULT_PROFILE && !file.profiles.iter().any(|p| p.id == id) {
+        return Err(StorageError::DataDirError(format!(
+            "Unknown profile: {}",
+            id
+        )));
+    }
+    file.active = (id != DEFAULT_PROFILE).then(|| id.to_string());
+    save_profiles_file(&file)?;
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = file.active;
+    tracing::info!("Switched to profile {}", id);
+    Ok(())
+}
+
+/// Remove a profile and everything in its data directory
+///
+/// The default profile and the active one can't be deleted.
+pub fn delete_profile(id: &str) -> Result<(), StorageError> {
+    if id == DEFAULT_PROFILE || id == active_profile() {
+        return Err(StorageError::DataDirError(format!(
+            "Profile {} can't be deleted while in use",
+            id
+        )));
+    }
+    let mut file = load_profiles_file();
+    file.profiles.retain(|p| p.id != id);
+    save_profiles_file(&file)?;
+    let dir = profile_dir(&get_base_data_dir()?, id);
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    tracing::info!("Deleted profile {}", id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_id() {
+        let taken = vec!["work".to_string()];
+        assert_eq!(profile_id("Perso / Famille", &taken), "perso-famille");
+        assert_eq!(profile_id(" Work ", &taken), "work-2");
+        assert_eq!(profile_id("Default", &taken), "default-2");
+        assert_eq!(profile_id("???", &taken), "profile");
+    }
+
+    #[test]
+    fn test_profile_dir() {
+        let base = Path::new("/data/clawRS");
+        assert_eq!(profile_dir(base, DEFAULT_PROFILE), base);
+        assert_eq!(
+            profile_dir(base, "work"),
+            Path::new("/data/clawRS/profiles/work")
+        );
+    }
+}
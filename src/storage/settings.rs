@@ -2,10 +2,74 @@
 //!
 //! Manages persistence of user preferences and application settings.
 
-use crate::storage::{get_data_dir, StorageError};
+use crate::agent::escalation::EscalationPolicy;
+use crate::agent::personas::Persona;
+use crate::agent::digest::DigestSettings;
+use crate::agent::routing::RoutingSettings;
+use crate::agent::tagging::AutoTagSettings;
+use crate::agent::watch_folders::WatchFolder;
+use crate::agent::ReflectionPolicy;
+use crate::inference::artifacts::OutputCleanup;
+use crate::inference::engine::{GenerationParams, InferenceBackend, LlamaEngine};
+use crate::inference::remote::OpenAiCompatBackend;
+use crate::inference::truncation::TruncationStrategy;
+use crate::storage::lock::LockSettings;
+use crate::storage::model_profiles::{ModelProfile, ModelProfiles};
+use crate::storage::model_sources::ExternalSource;
+use crate::storage::snippets::Snippet;
+use crate::storage::suggestions::WelcomeSuggestion;
+use crate::storage::system_prompts::{resolve_system_prompt, SystemPromptPreset};
+use crate::storage::models::ModelRole;
+use crate::storage::tool_recordings::ToolReplayMode;
+use crate::system::proxy::ProxySettings;
+use crate::storage::{get_base_data_dir, get_data_dir, portable, StorageError};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Highest max_tokens accepted
+pub const MAX_TOKENS_LIMIT: u32 = 65536;
+
+/// Longest look-back accepted for the repetition penalties
+pub const MAX_PENALTY_LAST_N: i32 = 8192;
+
+/// Autosave intervals offered in Settings, in seconds; 0 turns autosave off
+pub const AUTOSAVE_INTERVALS: [u32; 5] = [0, 3, 10, 30, 60];
+
+/// Permission timeouts offered in Settings, in seconds; 0 waits for the user
+/// with the run paused
+pub const PERMISSION_TIMEOUTS: [u32; 6] = [0, 30, 60, 120, 300, 600];
+
+/// `backend` running generations in-process with llama.cpp
+pub const BACKEND_LOCAL: &str = "local";
+
+/// `backend` sending generations to an OpenAI-compatible server
+pub const BACKEND_OPENAI_COMPAT: &str = "openai_compat";
+
+/// Why the settings page refuses a max_tokens value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxTokensError {
+    NotANumber,
+    OutOfRange,
+    /// Larger than the context window, which can't hold the reply
+    ExceedsContext,
+}
+
+/// Parse a max_tokens typed in the settings, within the limits `validate` enforces
+pub fn parse_max_tokens(input: &str, context_size: u32) -> Result<u32, MaxTokensError> {
+    let value: u32 = input.trim().parse().map_err(|_| MaxTokensError::NotANumber)?;
+    if !(1..=MAX_TOKENS_LIMIT).contains(&value) {
+        return Err(MaxTokensError::OutOfRange);
+    }
+    if value > context_size {
+        return Err(MaxTokensError::ExceedsContext);
+    }
+    Ok(value)
+}
+
+/// Context window sizes offered in the settings
+pub const CONTEXT_SIZES: [u32; 7] = [2048, 4096, 8192, 16384, 32768, 65536, 131072];
 
 /// Application settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,18 +78,90 @@ pub struct AppSettings {
     pub temperature: f32,
     /// Top-p (nucleus sampling) parameter (0.0 - 1.0)
     pub top_p: f32,
+    /// Min-p sampling parameter (0.0 - 1.0, 0 = disabled)
+    #[serde(default = "default_min_p")]
+    pub min_p: f32,
+    /// Typical-p sampling parameter (0.0 - 1.0, 0 = disabled)
+    #[serde(default)]
+    pub typical_p: f32,
+    /// Presence penalty (-2.0 - 2.0, 0 = disabled)
+    #[serde(default)]
+    pub presence_penalty: f32,
+    /// Frequency penalty (-2.0 - 2.0, 0 = disabled)
+    #[serde(default)]
+    pub frequency_penalty: f32,
+    /// Tokens the penalties look back on (-1 = whole context)
+    #[serde(default = "default_penalty_last_n")]
+    pub penalty_last_n: i32,
     /// Top-k sampling parameter
     pub top_k: u32,
     /// Maximum number of tokens to generate
     pub max_tokens: u32,
+    /// Ignore `max_tokens` and let a reply use whatever context the prompt leaves
+    #[serde(default)]
+    pub max_tokens_auto: bool,
     /// Context window size
     pub context_size: u32,
+    /// Keep `context_size` when the model and its KV cache don't fit in the
+    /// detected VRAM; otherwise the engine shrinks the context to fit
+    #[serde(default)]
+    pub context_over_vram: bool,
+    /// How messages are dropped when a prompt doesn't fit the context
+    #[serde(default)]
+    pub prompt_truncation: TruncationStrategy,
+    /// Generation stops at the first of these (e.g. `</s>`, `\nUser:`)
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+    /// Record per-token logprobs with each reply, for the debug view
+    #[serde(default)]
+    pub debug_logprobs: bool,
+    /// Keep the KV cache of the prompt prefix between generations; off to
+    /// rule it out when debugging
+    #[serde(default = "default_kv_cache_reuse")]
+    pub kv_cache_reuse: bool,
+    /// Constrain the reply with a grammar when a tool call is expected, so
+    /// the call is always well-formed JSON
+    #[serde(default = "default_constrained_tool_calls")]
+    pub constrained_tool_calls: bool,
     /// System prompt prepended to conversations
     pub system_prompt: String,
+    /// Where `system_prompt` comes from; rebuilt from it on every load
+    #[serde(default)]
+    pub system_prompt_preset: SystemPromptPreset,
+    /// Text of the custom preset
+    #[serde(default)]
+    pub custom_system_prompt: String,
     /// Number of GPU layers to offload (0 = CPU only)
     pub gpu_layers: u32,
+    /// Pick the GPU layers from free VRAM each time a model loads
+    #[serde(default)]
+    pub gpu_layers_auto: bool,
     /// Directory where model files (.gguf) are stored
     pub models_directory: PathBuf,
+    /// Directories for auxiliary models (embedding, draft, whisper)
+    #[serde(default)]
+    pub model_directories: ModelDirectories,
+    /// List models from the local Ollama store and load them in place
+    #[serde(default = "default_ollama_import")]
+    pub ollama_import: bool,
+    /// Model folders of other local LLM apps to scan as well (opt-in)
+    #[serde(default)]
+    pub external_model_sources: Vec<ExternalSource>,
+    /// Aliases, favorites and last use by model path
+    #[serde(default)]
+    pub model_profiles: ModelProfiles,
+    /// Where generations run: "local" or "openai_compat"
+    #[serde(default = "default_backend")]
+    pub backend: String,
+    /// Base URL of the OpenAI-compatible server, e.g. `http://192.168.1.20:8080`
+    #[serde(default)]
+    pub remote_base_url: String,
+    /// Bearer token for the server; empty sends none
+    #[serde(default)]
+    pub remote_api_key: String,
+    /// Last model used on the server (for auto-loading on startup)
+    #[serde(default)]
+    pub last_remote_model: Option<String>,
     /// UI theme: "dark" or "light"
     pub theme: String,
     /// Font size: "small", "medium", or "large"
@@ -36,30 +172,160 @@ pub struct AppSettings {
     /// Last loaded model path (for auto-loading on startup)
     #[serde(default)]
     pub last_model_path: Option<String>,
-    /// Auto-load last model on startup
+    /// GPU layers the last model was loaded with
+    #[serde(default)]
+    pub last_gpu_layers: Option<u32>,
+    /// Reload the last model on startup (opt-in)
     #[serde(default = "default_auto_load")]
     pub auto_load_model: bool,
+    /// Seconds between saves while a reply streams; 0 saves only at the end
+    #[serde(default = "default_autosave_interval")]
+    pub autosave_interval_secs: u32,
+    /// "Open with ClawRS" registered for .gguf and text files (opt-in)
+    #[serde(default)]
+    pub file_associations: bool,
+    /// Look for a new release on GitHub at launch (opt-in)
+    #[serde(default)]
+    pub check_for_updates: bool,
+    /// Release the user chose to skip; not offered again
+    #[serde(default)]
+    pub skipped_update: Option<String>,
     /// UI and agent language: "fr" or "en"
     #[serde(default = "default_language")]
     pub language: String,
+    /// Offer tools to the model in chat (quick settings toggle)
+    #[serde(default = "default_tools_enabled")]
+    pub tools_enabled: bool,
     /// Auto-approve ALL tool calls without asking (dangerous but convenient)
     #[serde(default)]
     pub auto_approve_all_tools: bool,
     /// List of tool names that are auto-approved (allowlist)
     #[serde(default)]
     pub tool_allowlist: Vec<String>,
+    /// Seconds a permission request waits (window focused) before it is
+    /// denied; 0 pauses the run until the user answers
+    #[serde(default = "default_permission_timeout")]
+    pub permission_timeout_secs: u32,
+    /// Language of the tool descriptions given to the model ("en" or "fr");
+    /// English by default, the one models follow best
+    #[serde(default = "default_tool_language")]
+    pub tool_language: String,
     /// List of disabled MCP server IDs
     #[serde(default)]
     pub disabled_mcp_servers: Vec<String>,
     /// OpenRouter model to use for ai_consult tool (default: openrouter/pony-alpha)
     #[serde(default = "default_openrouter_model")]
     pub openrouter_model: String,
+    /// Reflection policy for the agent loop
+    #[serde(default)]
+    pub reflection_policy: ReflectionPolicy,
+    /// Cache results of idempotent tools (web_search, web_fetch, pdf_read) on disk
+    #[serde(default)]
+    pub persistent_tool_cache: bool,
+    /// Sanitize and delimit web/file content before the model reads it
+    #[serde(default = "default_injection_defense")]
+    pub prompt_injection_defense: bool,
+    /// Record tool results, or replay a recording instead of running tools
+    #[serde(default)]
+    pub tool_replay: ToolReplayMode,
+    /// When and how the agent may escalate to a cloud model (ai_consult)
+    #[serde(default)]
+    pub escalation: EscalationPolicy,
+    /// Generation preset applied to chat replies; `None` uses the values above
+    #[serde(default)]
+    pub active_preset: Option<String>,
+    /// User-defined generation presets
+    #[serde(default)]
+    pub custom_presets: Vec<GenerationPreset>,
+    /// User-defined personas
+    #[serde(default)]
+    pub custom_personas: Vec<Persona>,
+    /// Preferred model path by persona id, set from Settings > Tools
+    #[serde(default)]
+    pub persona_models: HashMap<String, String>,
+    /// Locked mode for shared machines
+    #[serde(default)]
+    pub lock: LockSettings,
+    /// Proxy for network tools and downloads
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// Imported root certificates (PEM files in the data directory)
+    #[serde(default)]
+    pub ca_certificates: Vec<String>,
+    /// Project folder the agent works in; its repo map goes into the system prompt
+    #[serde(default)]
+    pub workspace: Option<PathBuf>,
+    /// Folders whose new files run a workflow
+    #[serde(default)]
+    pub watch_folders: Vec<WatchFolder>,
+    /// Daily digest of RSS/Atom feeds
+    #[serde(default)]
+    pub digest: DigestSettings,
+    /// Rules picking the model or persona per message
+    #[serde(default)]
+    pub routing: RoutingSettings,
+    /// Rules tagging conversations from their content
+    #[serde(default)]
+    pub auto_tags: AutoTagSettings,
+    /// Welcome-screen cards; `None` shows the built-in ones in the UI language
+    #[serde(default)]
+    pub welcome_suggestions: Option<Vec<WelcomeSuggestion>>,
+    /// Prompt snippets expanded with `/snippet name` in the chat input
+    #[serde(default)]
+    pub snippets: Vec<Snippet>,
+    /// End of focus mode in Unix seconds (see [`crate::agent::focus`])
+    #[serde(default)]
+    pub focus_until: Option<u64>,
 }
 
-fn default_auto_load() -> bool {
+fn default_ollama_import() -> bool {
+    true
+}
+
+fn default_injection_defense() -> bool {
     true
 }
 
+fn default_tools_enabled() -> bool {
+    true
+}
+
+fn default_penalty_last_n() -> i32 {
+    64
+}
+
+fn default_min_p() -> f32 {
+    0.05
+}
+
+fn default_constrained_tool_calls() -> bool {
+    true
+}
+
+fn default_kv_cache_reuse() -> bool {
+    true
+}
+
+fn default_backend() -> String {
+    BACKEND_LOCAL.to_string()
+}
+
+fn default_auto_load() -> bool {
+    false
+}
+
+fn default_autosave_interval() -> u32 {
+    3
+}
+
+fn default_permission_timeout() -> u32 {
+    120
+}
+
+fn default_tool_language() -> String {
+    "en".to_string()
+}
+
 fn default_language() -> String {
     "fr".to_string()
 }
@@ -94,11 +360,6 @@ pub fn default_system_prompt_for_lang(lang: &str) -> String {
     } else {
         "\n- Use standard Unix commands: ls, cat, grep, find, etc."
     };
-    let cmd_example = if std::env::consts::OS == "windows" {
-        "dir"
-    } else {
-        "ls -la"
-    };
 
     let response_lang_instruction = if lang == "en" {
         "Always respond in English."
@@ -123,35 +384,13 @@ You know the user's home directory: {home}. Use it directly, never ask.
 
 ## Tools
 
-You have these tools. Use them IMMEDIATELY when relevant — do NOT ask the user for information you can look up yourself.
-
-### web_search
-Search the web for current information.
-```json
-{{"tool": "web_search", "params": {{"query": "search terms", "num_results": 5}}}}
-```
-
-### file_read
-Read a file's content. You know the user's paths, use them.
-```json
-{{"tool": "file_read", "params": {{"path": "{home}{sep}Desktop{sep}example.txt"}}}}
-```
+The tools you can call are described below under **Available Tools**, with their exact names and parameters; that list changes with the settings, so trust it over anything you remember. Use them IMMEDIATELY when relevant — do NOT ask the user for information you can look up yourself. If there is no such section, tools are off: answer from your own knowledge.
 
-### file_list
-List a directory's contents.
-```json
-{{"tool": "file_list", "params": {{"path": "{home}{sep}Desktop"}}}}
-```
-
-### command
-Execute a shell command.{cmd_info}
-```json
-{{"tool": "command", "params": {{"command": "{cmd_example}", "timeout_secs": 30}}}}
-```
+Shell commands:{cmd_info}
 
 ## Rules
 
-1. **ACT, don't ask.** If the user says "list my desktop", use file_list with their Desktop path immediately. Do NOT ask them for the path.
+1. **ACT, don't ask.** If the user says "list my desktop", list their Desktop path immediately. Do NOT ask them for the path.
 2. **Use tools proactively.** If you need info, use a tool. Don't say "I can't access your files" — you CAN.
 3. **Derive paths.** Desktop = Home{sep}Desktop, Documents = Home{sep}Documents, etc.
 4. **One tool per message.** Call one tool, wait for the result, then respond or call another.
@@ -164,7 +403,7 @@ Execute a shell command.{cmd_info}
 
 **CRITICAL RULES:**
 - NEVER say "done" or "file created" BEFORE receiving system confirmation
-- AFTER each creation/modification, VERIFY with file_list or file_read that it actually exists
+- AFTER each creation/modification, VERIFY with a listing or read tool that it actually exists
 - If you haven't seen "[TOOL_RESULT]" or a system result, the tool was NOT executed
 - NEVER generate fake tool results - the SYSTEM executes them, not you
 - If you need to confirm an action, USE a verification tool FIRST"#,
@@ -174,7 +413,6 @@ Execute a shell command.{cmd_info}
         home = home,
         sep = sep,
         cmd_info = cmd_info,
-        cmd_example = cmd_example,
     )
 }
 
@@ -183,30 +421,344 @@ impl Default for AppSettings {
         Self {
             temperature: 0.7,
             top_p: 0.9,
+            min_p: default_min_p(),
+            typical_p: 0.0,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
+            penalty_last_n: default_penalty_last_n(),
             top_k: 40,
             max_tokens: 4096,    // 4K output - OK with 16K context
+            max_tokens_auto: false,
             context_size: 16384, // 16K context - user confirmed 36 tok/s in LM Studio with 16K on 8GB VRAM
+            context_over_vram: false,
+            prompt_truncation: TruncationStrategy::default(),
+            stop_sequences: Vec::new(),
+            debug_logprobs: false,
+            kv_cache_reuse: default_kv_cache_reuse(),
+            constrained_tool_calls: default_constrained_tool_calls(),
             system_prompt: default_system_prompt(),
+            system_prompt_preset: SystemPromptPreset::Default,
+            custom_system_prompt: String::new(),
             gpu_layers: 99, // Offload all layers to GPU by default
-            models_directory: get_data_dir()
+            gpu_layers_auto: false,
+            // Shared by all profiles
+            models_directory: get_base_data_dir()
                 .ok()
                 .map(|d| d.join("models"))
                 .unwrap_or_else(|| PathBuf::from("./models")),
+            model_directories: ModelDirectories::default(),
+            ollama_import: default_ollama_import(),
+            external_model_sources: Vec::new(),
+            model_profiles: ModelProfiles::new(),
+            backend: default_backend(),
+            remote_base_url: String::new(),
+            remote_api_key: String::new(),
+            last_remote_model: None,
             theme: "dark".to_string(),
             font_size: "medium".to_string(),
             exa_mcp_url: "https://mcp.exa.ai/mcp".to_string(),
             last_model_path: None,
-            auto_load_model: true,
+            last_gpu_layers: None,
+            auto_load_model: default_auto_load(),
+            autosave_interval_secs: default_autosave_interval(),
+            file_associations: false,
+            check_for_updates: false,
+            skipped_update: None,
             language: "fr".to_string(),
+            tools_enabled: true,
             auto_approve_all_tools: false,
             tool_allowlist: Vec::new(),
+            permission_timeout_secs: default_permission_timeout(),
+            tool_language: default_tool_language(),
             disabled_mcp_servers: Vec::new(),
             openrouter_model: default_openrouter_model(),
+            reflection_policy: ReflectionPolicy::default(),
+            persistent_tool_cache: false,
+            prompt_injection_defense: default_injection_defense(),
+            tool_replay: ToolReplayMode::Off,
+            escalation: EscalationPolicy::default(),
+            active_preset: None,
+            custom_presets: Vec::new(),
+            custom_personas: Vec::new(),
+            persona_models: HashMap::new(),
+            lock: LockSettings::default(),
+            proxy: ProxySettings::default(),
+            ca_certificates: Vec::new(),
+            workspace: None,
+            watch_folders: Vec::new(),
+            digest: DigestSettings::default(),
+            routing: RoutingSettings::default(),
+            auto_tags: AutoTagSettings::default(),
+            welcome_suggestions: None,
+            snippets: Vec::new(),
+            focus_until: None,
         }
     }
 }
 
+/// Per-role model directories; unset roles use a subdirectory of
+/// `models_directory` named after the role
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelDirectories {
+    #[serde(default)]
+    pub embedding: Option<PathBuf>,
+    #[serde(default)]
+    pub draft: Option<PathBuf>,
+    #[serde(default)]
+    pub whisper: Option<PathBuf>,
+}
+
+impl ModelDirectories {
+    /// The configured directory for an auxiliary role
+    pub fn get(&self, role: ModelRole) -> Option<&PathBuf> {
+        match role {
+            ModelRole::Chat => None,
+            ModelRole::Embedding => self.embedding.as_ref(),
+            ModelRole::Draft => self.draft.as_ref(),
+            ModelRole::Whisper => self.whisper.as_ref(),
+        }
+    }
+
+    /// Set or clear the directory for an auxiliary role
+    pub fn set(&mut self, role: ModelRole, path: Option<PathBuf>) {
+        match role {
+            ModelRole::Chat => {}
+            ModelRole::Embedding => self.embedding = path,
+            ModelRole::Draft => self.draft = path,
+            ModelRole::Whisper => self.whisper = path,
+        }
+    }
+}
+
+/// Named sampling parameters selectable next to the chat input
+///
+/// Context size is not part of a preset since it is fixed when the model
+/// is loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationPreset {
+    pub name: String,
+    pub temperature: f32,
+    pub top_k: u32,
+    pub top_p: f32,
+    #[serde(default)]
+    pub min_p: f32,
+    #[serde(default)]
+    pub typical_p: f32,
+    pub repeat_penalty: f32,
+    pub max_tokens: u32,
+}
+
+impl GenerationPreset {
+    fn from_params(name: &str, params: GenerationParams) -> Self {
+        Self {
+            name: name.to_string(),
+            temperature: params.temperature,
+            top_k: params.top_k,
+            top_p: params.top_p,
+            min_p: params.min_p,
+            typical_p: params.typical_p,
+            repeat_penalty: params.repeat_penalty,
+            max_tokens: params.max_tokens,
+        }
+    }
+
+    /// Presets backed by the engine's parameter sets
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::from_params("Precise", GenerationParams::fast()),
+            Self::from_params("Balanced", GenerationParams::balanced()),
+            Self::from_params("Creative", GenerationParams::quality()),
+        ]
+    }
+
+    pub fn is_builtin(name: &str) -> bool {
+        Self::builtin().iter().any(|p| p.name == name)
+    }
+
+    /// Display name; built-in presets are translated
+    pub fn label(&self, is_en: bool) -> String {
+        match (self.name.as_str(), is_en) {
+            ("Precise", false) => "Precis".to_string(),
+            ("Balanced", false) => "Equilibre".to_string(),
+            ("Creative", false) => "Creatif".to_string(),
+            (name, _) => name.to_string(),
+        }
+    }
+
+    fn apply(&self, params: &mut GenerationParams) {
+        params.temperature = self.temperature;
+        params.top_k = self.top_k;
+        params.top_p = self.top_p;
+        params.min_p = self.min_p;
+        params.typical_p = self.typical_p;
+        params.repeat_penalty = self.repeat_penalty;
+        params.max_tokens = self.max_tokens;
+    }
+}
+
 impl AppSettings {
+    /// Profile of the model at `path`, created on first use
+    pub fn model_profile_mut(&mut self, path: &str) -> &mut ModelProfile {
+        self.model_profiles.entry(path.to_string()).or_default()
+    }
+
+    /// Built-in presets followed by the user's
+    pub fn presets(&self) -> Vec<GenerationPreset> {
+        let mut presets = GenerationPreset::builtin();
+        presets.extend(self.custom_presets.iter().cloned());
+        presets
+    }
+
+    /// Built-in personas followed by the user's, with preferred models applied
+    pub fn personas(&self) -> Vec<Persona> {
+        let mut personas = Persona::builtin();
+        personas.extend(self.custom_personas.iter().cloned());
+        for persona in &mut personas {
+            if let Some(model) = self.persona_models.get(&persona.id) {
+                persona.preferred_model = Some(model.clone());
+            }
+        }
+        personas
+    }
+
+    pub fn persona(&self, id: &str) -> Option<Persona> {
+        self.personas().into_iter().find(|p| p.id == id)
+    }
+
+    /// Set `system_prompt` from the system prompt preset, in the UI language
+    pub fn apply_system_prompt_preset(&mut self) {
+        self.system_prompt = resolve_system_prompt(
+            self.system_prompt_preset,
+            &self.custom_system_prompt,
+            &self.language,
+        );
+    }
+
+    /// Whether generations go to the OpenAI-compatible server
+    pub fn uses_remote_backend(&self) -> bool {
+        self.backend == BACKEND_OPENAI_COMPAT
+    }
+
+    /// Engine for the selected backend, with no model loaded
+    pub fn inference_backend(&self) -> Box<dyn InferenceBackend> {
+        if self.uses_remote_backend() {
+            Box::new(OpenAiCompatBackend::new(
+                self.remote_base_url.clone(),
+                self.remote_api_key.clone(),
+            ))
+        } else {
+            Box::new(LlamaEngine::new())
+        }
+    }
+
+    /// Parameters for a chat reply, with the active preset applied
+    pub fn generation_params(&self) -> GenerationParams {
+        let mut params = GenerationParams {
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_k: self.top_k,
+            top_p: self.top_p,
+            min_p: self.min_p,
+            typical_p: self.typical_p,
+            repeat_penalty: 1.1,
+            presence_penalty: self.presence_penalty,
+            frequency_penalty: self.frequency_penalty,
+            penalty_last_n: self.penalty_last_n,
+            seed: 0,
+            max_context_size: self.context_size,
+            truncation: self.prompt_truncation,
+            stop_sequences: self.stop_sequences.clone(),
+            logprobs: self.debug_logprobs,
+            output_cleanup: OutputCleanup::Auto,
+            reuse_prefix: self.kv_cache_reuse,
+            grammar: None,
+            context_over_vram: self.context_over_vram,
+        };
+        if let Some(preset) = self
+            .active_preset
+            .as_ref()
+            .and_then(|name| self.presets().into_iter().find(|p| &p.name == name))
+        {
+            preset.apply(&mut params);
+        }
+        // The engine clamps it to what the prompt leaves free
+        if self.max_tokens_auto {
+            params.max_tokens = params.max_context_size;
+        }
+        params
+    }
+
+    /// Save the current slider values as a custom preset and activate it
+    ///
+    /// Replaces a custom preset of the same name. Returns false for empty
+    /// names and names of built-in presets.
+    pub fn save_custom_preset(&mut self, name: &str) -> bool {
+        let name = name.trim();
+        if name.is_empty() || GenerationPreset::is_builtin(name) {
+            return false;
+        }
+        let preset = GenerationPreset {
+            name: name.to_string(),
+            temperature: self.temperature,
+            top_k: self.top_k,
+            top_p: self.top_p,
+            min_p: self.min_p,
+            typical_p: self.typical_p,
+            repeat_penalty: 1.1,
+            max_tokens: self.max_tokens,
+        };
+        match self.custom_presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = preset,
+            None => self.custom_presets.push(preset),
+        }
+        self.active_preset = Some(name.to_string());
+        true
+    }
+
+    /// Delete a custom preset, falling back to the sliders if it was active
+    pub fn delete_custom_preset(&mut self, name: &str) {
+        self.custom_presets.retain(|p| p.name != name);
+        if self.active_preset.as_deref() == Some(name) {
+            self.active_preset = None;
+        }
+    }
+
+    /// Directory scanned for models of `role`
+    pub fn models_directory_for(&self, role: ModelRole) -> PathBuf {
+        match role {
+            ModelRole::Chat => self.models_directory.clone(),
+            _ => self
+                .model_directories
+                .get(role)
+                .cloned()
+                .unwrap_or_else(|| self.models_directory.join(role.subdirectory())),
+        }
+    }
+
+    /// Point model paths that no longer exist (the portable drive got
+    /// another letter or mount point) at the models folder of `data_dir`
+    fn relocate_models(&mut self, data_dir: &Path) {
+        if !self.models_directory.is_dir() {
+            let models = data_dir.join("models");
+            tracing::info!(
+                "Models directory {} not found, using {}",
+                self.models_directory.display(),
+                models.display()
+            );
+            self.models_directory = models;
+        }
+        let moved = self
+            .last_model_path
+            .as_deref()
+            .filter(|path| !Path::new(path).is_file())
+            .and_then(|path| Path::new(path).file_name())
+            .map(|name| self.models_directory.join(name))
+            .filter(|path| path.is_file());
+        if let Some(path) = moved {
+            self.last_model_path = Some(path.display().to_string());
+        }
+    }
+
     /// Validate settings values
     ///
     /// Ensures all parameters are within acceptable ranges.
@@ -214,35 +766,26 @@ impl AppSettings {
     pub fn validate(&mut self) {
         self.temperature = self.temperature.clamp(0.0, 2.0);
         self.top_p = self.top_p.clamp(0.0, 1.0);
+        self.min_p = self.min_p.clamp(0.0, 1.0);
+        self.typical_p = self.typical_p.clamp(0.0, 1.0);
+        self.presence_penalty = self.presence_penalty.clamp(-2.0, 2.0);
+        self.frequency_penalty = self.frequency_penalty.clamp(-2.0, 2.0);
+        self.penalty_last_n = self.penalty_last_n.clamp(-1, MAX_PENALTY_LAST_N);
 
         if self.top_k == 0 {
             self.top_k = 40;
         }
 
-        self.max_tokens = self.max_tokens.clamp(1, 65536);
+        self.max_tokens = self.max_tokens.clamp(1, MAX_TOKENS_LIMIT);
 
-        // Valid context sizes
-        let valid_context_sizes = [2048, 4096, 8192, 16384, 32768, 65536, 131072];
-        if !valid_context_sizes.contains(&self.context_size) {
-            self.context_size = *valid_context_sizes
+        // Valid context sizes; the settings page warns when one won't fit in VRAM
+        if !CONTEXT_SIZES.contains(&self.context_size) {
+            self.context_size = *CONTEXT_SIZES
                 .iter()
                 .min_by_key(|&&size| (size as i64 - self.context_size as i64).abs())
                 .unwrap_or(&4096);
         }
 
-        // === VRAM-aware context cap ===
-        // Prevent KV cache from overflowing dedicated VRAM.
-        // 7B Q4_K_M ~4.1 GB; 16K context KV cache ~2 GB → fits in 8 GB.
-        let max_safe_context = get_vram_safe_context_size();
-        if self.context_size > max_safe_context {
-            tracing::warn!(
-                "Context size {} too large for available VRAM, capping to {}",
-                self.context_size,
-                max_safe_context
-            );
-            self.context_size = max_safe_context;
-        }
-
         // Cap max_tokens to context_size (can't generate more than context allows)
         if self.max_tokens > self.context_size {
             self.max_tokens = self.context_size / 2;
@@ -252,10 +795,24 @@ impl AppSettings {
             self.theme = "dark".to_string();
         }
 
+        if self.backend != BACKEND_LOCAL && self.backend != BACKEND_OPENAI_COMPAT {
+            self.backend = default_backend();
+        }
+
         if !["small", "medium", "large"].contains(&self.font_size.as_str()) {
             self.font_size = "medium".to_string();
         }
 
+        self.reflection_policy.max_recoveries = self.reflection_policy.max_recoveries.min(10);
+        if self.reflection_policy.long_task_iterations == 0 {
+            self.reflection_policy.long_task_iterations = 1;
+        }
+
+        if !self.escalation.monthly_budget_usd.is_finite() || self.escalation.monthly_budget_usd < 0.0 {
+            self.escalation.monthly_budget_usd = 0.0;
+        }
+        self.escalation.after_failed_iterations = self.escalation.after_failed_iterations.min(20);
+
         if self.exa_mcp_url.trim().is_empty() {
             self.exa_mcp_url = "https://mcp.exa.ai/mcp".to_string();
         }
@@ -266,33 +823,6 @@ impl AppSettings {
     }
 }
 
-/// Estimate the maximum safe context size based on available VRAM.
-/// This prevents the KV cache from spilling into shared GPU memory (RAM), which is slow.
-/// Tuned so 8 GB VRAM allows 16K context (7B model ~4.1 GB + 16K KV ~2 GB).
-fn get_vram_safe_context_size() -> u32 {
-    let vram_gb = crate::system::gpu::get_total_vram_gb().unwrap_or(0.0);
-
-    if vram_gb <= 0.0 {
-        return 16384; // default when VRAM unknown
-    }
-
-    // Heuristic: 50% VRAM for model, 50% for KV cache. 7B 16K ≈ 2 GB KV.
-    // Per 1K context for 7B: ~128 MB. Use 128 so 8 GB -> 4 GB for KV -> 32K cap.
-    let vram_for_kv = vram_gb * 0.5;
-    let max_ctx_k = (vram_for_kv * 1024.0 / 128.0) as u32;
-    let max_ctx = max_ctx_k * 1024;
-
-    let sizes = [131072, 65536, 32768, 16384, 8192, 4096, 2048];
-    for &s in &sizes {
-        if s <= max_ctx {
-            tracing::info!("VRAM: {:.1} GB -> max safe context: {}K", vram_gb, s / 1024);
-            return s;
-        }
-    }
-
-    2048
-}
-
 /// Get the settings file path
 fn get_settings_path() -> Result<PathBuf, StorageError> {
     Ok(get_data_dir()?.join("settings.json"))
@@ -323,12 +853,16 @@ fn load_settings_internal() -> Result<AppSettings, StorageError> {
     let json = fs::read_to_string(&path)?;
     let mut settings: AppSettings = serde_json::from_str(&json)?;
 
-    // Always use system prompt from code so app reflects current version on reload
-    settings.system_prompt = default_system_prompt_for_lang(&settings.language);
+    // Rebuild the preset prompts from code so the app reflects the current version on reload
+    settings.apply_system_prompt_preset();
 
     // Validate loaded settings
     settings.validate();
 
+    if portable::is_portable() {
+        settings.relocate_models(&get_base_data_dir()?);
+    }
+
     tracing::debug!("Loaded settings from disk");
     Ok(settings)
 }
@@ -353,6 +887,25 @@ pub fn save_settings(settings: &AppSettings) -> Result<(), StorageError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_relocate_models() {
+        let data = tempfile::tempdir().unwrap();
+        std::fs::create_dir(data.path().join("models")).unwrap();
+        std::fs::write(data.path().join("models/model.gguf"), b"").unwrap();
+
+        let mut settings = AppSettings {
+            models_directory: PathBuf::from("/gone/models"),
+            last_model_path: Some("/gone/models/model.gguf".to_string()),
+            ..AppSettings::default()
+        };
+        settings.relocate_models(data.path());
+        assert_eq!(settings.models_directory, data.path().join("models"));
+        assert_eq!(
+            settings.last_model_path,
+            Some(data.path().join("models/model.gguf").display().to_string())
+        );
+    }
+
     #[test]
     fn test_default_settings() {
         let settings = AppSettings::default();
@@ -363,6 +916,29 @@ mod tests {
         assert_eq!(settings.font_size, "medium");
     }
 
+    #[test]
+    fn test_generation_presets() {
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.generation_params().temperature, settings.temperature);
+
+        settings.active_preset = Some("Precise".to_string());
+        let params = settings.generation_params();
+        assert_eq!(params.temperature, GenerationParams::fast().temperature);
+        assert_eq!(params.top_k, GenerationParams::fast().top_k);
+        // Context size stays tied to the loaded model
+        assert_eq!(params.max_context_size, settings.context_size);
+
+        assert!(!settings.save_custom_preset("Balanced"));
+        settings.temperature = 1.3;
+        assert!(settings.save_custom_preset("Brainstorm"));
+        assert_eq!(settings.active_preset.as_deref(), Some("Brainstorm"));
+        assert_eq!(settings.generation_params().temperature, 1.3);
+
+        settings.delete_custom_preset("Brainstorm");
+        assert!(settings.active_preset.is_none());
+        assert_eq!(settings.presets().len(), 3);
+    }
+
     #[test]
     fn test_settings_validation() {
         let mut settings = AppSettings::default();
@@ -376,6 +952,15 @@ mod tests {
         settings.validate();
         assert_eq!(settings.temperature, 0.0);
 
+        settings.min_p = -0.5;
+        settings.typical_p = 3.0;
+        settings.validate();
+        assert_eq!((settings.min_p, settings.typical_p), (0.0, 1.0));
+        settings.presence_penalty = 5.0;
+        settings.penalty_last_n = -7;
+        settings.validate();
+        assert_eq!((settings.presence_penalty, settings.penalty_last_n), (2.0, -1));
+
         // Test top_p clamping
         settings.top_p = 2.0;
         settings.validate();
@@ -390,6 +975,43 @@ mod tests {
         settings.font_size = "huge".to_string();
         settings.validate();
         assert_eq!(settings.font_size, "medium");
+
+        // Unknown backends fall back to the local engine
+        settings.backend = "grpc".to_string();
+        settings.validate();
+        assert_eq!(settings.backend, BACKEND_LOCAL);
+        assert!(!settings.uses_remote_backend());
+
+        // Context sizes snap to the nearest offered one
+        settings.context_size = 100_000;
+        settings.validate();
+        assert_eq!(settings.context_size, 131072);
+        settings.context_size = 3000;
+        settings.validate();
+        assert_eq!(settings.context_size, 2048);
+    }
+
+    #[test]
+    fn test_default_prompt_leaves_tools_to_the_registry() {
+        let prompt = default_system_prompt_for_lang("en");
+        assert!(prompt.contains("Available Tools"));
+        assert!(!prompt.contains("\"tool\":"));
+        assert!(prompt.contains("Always respond in English."));
+    }
+
+    #[test]
+    fn test_parse_max_tokens() {
+        assert_eq!(parse_max_tokens(" 8192 ", 16384), Ok(8192));
+        assert_eq!(parse_max_tokens("lots", 16384), Err(MaxTokensError::NotANumber));
+        assert_eq!(parse_max_tokens("0", 16384), Err(MaxTokensError::OutOfRange));
+        assert_eq!(parse_max_tokens("70000", 131072), Err(MaxTokensError::OutOfRange));
+        assert_eq!(parse_max_tokens("32768", 16384), Err(MaxTokensError::ExceedsContext));
+
+        let settings = AppSettings {
+            max_tokens_auto: true,
+            ..Default::default()
+        };
+        assert_eq!(settings.generation_params().max_tokens, settings.context_size);
     }
 
     #[test]
@@ -402,6 +1024,14 @@ mod tests {
         assert_eq!(settings.temperature, deserialized.temperature);
         assert_eq!(settings.top_p, deserialized.top_p);
         assert_eq!(settings.theme, deserialized.theme);
+
+        // Settings saved before the autosave option keep the old 3 s interval
+        let mut value = serde_json::to_value(&settings).unwrap();
+        value.as_object_mut().unwrap().remove("autosave_interval_secs");
+        value.as_object_mut().unwrap().remove("permission_timeout_secs");
+        let older: AppSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(older.autosave_interval_secs, 3);
+        assert_eq!(older.permission_timeout_secs, 120);
     }
 
     #[test]
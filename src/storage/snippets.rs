@@ -0,0 +1,181 @@
+//! Snippet library: reusable prompt scaffolding
+//!
+//! Typing `/snippet name` in the chat input replaces the input with the
+//! snippet's text, for review before sending. `{{variable}}` placeholders
+//! are filled from the command: `key=value` arguments by name, the other
+//! arguments in order of first appearance (quote values with spaces).
+//! `{{date}}` and `{{time}}` default to now; anything left unfilled stays
+//! as is so it stands out in the input. Saved in `AppSettings::snippets`.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Input command expanding a snippet
+pub const SNIPPET_COMMAND: &str = "/snippet";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snippet {
+    /// Used after `/snippet`; letters, digits, `-` and `_`
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Text with `{{variable}}` placeholders
+    pub body: String,
+}
+
+impl Snippet {
+    pub fn has_valid_name(&self) -> bool {
+        !self.name.is_empty()
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    }
+}
+
+/// Snippet called `name`, ignoring case
+pub fn find_snippet<'a>(snippets: &'a [Snippet], name: &str) -> Option<&'a Snippet> {
+    snippets
+        .iter()
+        .find(|snippet| snippet.name.eq_ignore_ascii_case(name))
+}
+
+/// Placeholder names in `body`, in order of first appearance
+pub fn variables(body: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if !name.is_empty() && !name.contains('{') && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Split on whitespace, keeping "double-quoted parts" together
+fn split_args(text: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+/// Snippet name and arguments of a `/snippet name args...` input
+pub fn parse_snippet_command(text: &str) -> Option<(String, Vec<String>)> {
+    let rest = text.trim_start().strip_prefix(SNIPPET_COMMAND)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut args = split_args(rest);
+    if args.is_empty() {
+        return Some((String::new(), args));
+    }
+    let name = args.remove(0);
+    Some((name, args))
+}
+
+/// `body` with its placeholders filled from `args`
+pub fn expand_snippet(body: &str, args: &[String], now: DateTime<Local>) -> String {
+    let mut values: HashMap<String, String> = HashMap::new();
+    let mut positional = Vec::new();
+    for arg in args {
+        match arg.split_once('=') {
+            Some((key, value)) if !key.is_empty() && !key.contains(char::is_whitespace) => {
+                values.insert(key.to_string(), value.to_string());
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    let mut positional = positional.into_iter();
+    for name in variables(body) {
+        if values.contains_key(&name) {
+            continue;
+        }
+        let value = match name.as_str() {
+            "date" => Some(now.format("%Y-%m-%d").to_string()),
+            "time" => Some(now.format("%H:%M").to_string()),
+            _ => positional.next(),
+        };
+        if let Some(value) = value {
+            values.insert(name, value);
+        }
+    }
+
+    let mut expanded = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        match values.get(after[..end].trim()) {
+            Some(value) => expanded.push_str(value),
+            None => expanded.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_snippet_command() {
+        assert_eq!(
+            parse_snippet_command(r#"/snippet refund "Jean Dupont" order=42"#),
+            Some((
+                "refund".to_string(),
+                vec!["Jean Dupont".to_string(), "order=42".to_string()]
+            ))
+        );
+        assert_eq!(parse_snippet_command("/snippet"), Some((String::new(), vec![])));
+        assert_eq!(parse_snippet_command("/snippets"), None);
+        assert_eq!(parse_snippet_command("hello"), None);
+    }
+
+    #[test]
+    fn test_expand_snippet() {
+        let now = Local.with_ymd_and_hms(2024, 3, 5, 9, 30, 0).unwrap();
+        let body = "Hello {{ name }}, order {{order}} ({{date}}). {{name}}: {{reason}}";
+        assert_eq!(variables(body), vec!["name", "order", "date", "reason"]);
+        let args = vec!["order=42".to_string(), "Jean".to_string()];
+        assert_eq!(
+            expand_snippet(body, &args, now),
+            "Hello Jean, order 42 (2024-03-05). Jean: {{reason}}"
+        );
+    }
+}
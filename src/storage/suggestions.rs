@@ -0,0 +1,138 @@
+//! Welcome-screen suggestions
+//!
+//! The prompt cards shown on the welcome screen. Until the user edits them,
+//! the built-in set is used in the UI language; once edited, the list is
+//! saved in `AppSettings::welcome_suggestions` as is.
+
+use serde::{Deserialize, Serialize};
+
+/// Most cards shown on the welcome screen
+pub const MAX_SUGGESTIONS: usize = 8;
+
+/// Icons to pick from, as (id, SVG path)
+pub const SUGGESTION_ICONS: &[(&str, &str)] = &[
+    ("map", "M9 20l-5.447-2.724A1 1 0 0 1 3 16.382V5.618a1 1 0 0 1 1.447-.894L9 7m0 13l6-3m-6 3V7m6 10l4.553 2.276A1 1 0 0 0 21 18.382V7.618a1 1 0 0 0-.553-.894L15 4m0 13V4m0 0L9 7"),
+    ("book", "M12 6.253v13m0-13C10.832 5.477 9.246 5 7.5 5S4.168 5.477 3 6.253v13C4.168 18.477 5.754 18 7.5 18s3.332.477 4.5 1.253m0-13C13.168 5.477 14.754 5 16.5 5c1.747 0 3.332.477 4.5 1.253v13C19.832 18.477 18.247 18 16.5 18c-1.746 0-3.332.477-4.5 1.253"),
+    ("pencil", "M11 5H6a2 2 0 0 0-2 2v11a2 2 0 0 0 2 2h11a2 2 0 0 0 2-2v-5m-1.414-9.414a2 2 0 1 1 2.828 2.828L11.828 15H9v-2.828l8.586-8.586z"),
+    ("code", "M10 20l4-16m4 4l4 4-4 4M6 16l-4-4 4-4"),
+    ("search", "M21 21l-6-6m2-5a7 7 0 1 1-14 0 7 7 0 0 1 14 0z"),
+    ("bulb", "M9 18h6M10 22h4M12 2a7 7 0 0 0-4 12.74V17h8v-2.26A7 7 0 0 0 12 2z"),
+    ("chat", "M21 15a2 2 0 0 1-2 2H7l-4 4V5a2 2 0 0 1 2-2h14a2 2 0 0 1 2 2z"),
+    ("chart", "M3 3v18h18M7 16v-5m5 5V8m5 8v-3"),
+];
+
+/// SVG path of an icon id; unknown ids get the chat bubble
+pub fn icon_path(id: &str) -> &'static str {
+    SUGGESTION_ICONS
+        .iter()
+        .find(|(icon, _)| *icon == id)
+        .or_else(|| SUGGESTION_ICONS.iter().find(|(icon, _)| *icon == "chat"))
+        .map(|(_, path)| *path)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WelcomeSuggestion {
+    /// Id from [`SUGGESTION_ICONS`]
+    pub icon: String,
+    pub title: String,
+    #[serde(default)]
+    pub subtitle: String,
+    /// Sent to the chat input when the card is clicked
+    pub prompt: String,
+}
+
+impl WelcomeSuggestion {
+    fn new(icon: &str, title: &str, subtitle: &str, prompt: &str) -> Self {
+        Self {
+            icon: icon.to_string(),
+            title: title.to_string(),
+            subtitle: subtitle.to_string(),
+            prompt: prompt.to_string(),
+        }
+    }
+
+    /// A card needs a title and a prompt to be shown
+    pub fn is_complete(&self) -> bool {
+        !self.title.trim().is_empty() && !self.prompt.trim().is_empty()
+    }
+}
+
+/// Built-in suggestions in the UI language
+pub fn default_suggestions(is_en: bool) -> Vec<WelcomeSuggestion> {
+    if is_en {
+        vec![
+            WelcomeSuggestion::new(
+                "map",
+                "Plan",
+                "a trip, a project...",
+                "Help me plan a trip to Paris. What are the must-sees and best times to visit?",
+            ),
+            WelcomeSuggestion::new(
+                "book",
+                "Explain",
+                "a complex concept",
+                "Explain quantum computing in simple terms that anyone can understand.",
+            ),
+            WelcomeSuggestion::new(
+                "pencil",
+                "Write",
+                "an email, a document...",
+                "Help me write a professional email to my manager asking for time off.",
+            ),
+            WelcomeSuggestion::new(
+                "code",
+                "Code",
+                "debug, explain...",
+                "I have a bug in my code. Can you help me debug it?",
+            ),
+        ]
+    } else {
+        vec![
+            WelcomeSuggestion::new("map", "Planifier", "un voyage, un projet...", "Aide-moi a planifier un voyage a Paris. Quels sont les incontournables et les meilleures periodes ?"),
+            WelcomeSuggestion::new("book", "Expliquer", "un concept complexe", "Explique-moi l'informatique quantique en termes simples que n'importe qui peut comprendre."),
+            WelcomeSuggestion::new("pencil", "Rediger", "un email, un texte...", "Aide-moi a ecrire un email professionnel a mon manager pour demander des conges."),
+            WelcomeSuggestion::new("code", "Coder", "debugger, expliquer...", "J'ai un bug dans mon code. Peux-tu m'aider a le debugger ?"),
+        ]
+    }
+}
+
+/// Suggestions to show: the saved ones if any, otherwise the built-in set
+pub fn effective_suggestions(
+    saved: Option<&[WelcomeSuggestion]>,
+    is_en: bool,
+) -> Vec<WelcomeSuggestion> {
+    match saved {
+        Some(saved) => saved
+            .iter()
+            .filter(|s| s.is_complete())
+            .take(MAX_SUGGESTIONS)
+            .cloned()
+            .collect(),
+        None => default_suggestions(is_en),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_path() {
+        assert!(icon_path("code").starts_with("M10 20"));
+        assert_eq!(icon_path("unknown"), icon_path("chat"));
+    }
+
+    #[test]
+    fn test_effective_suggestions() {
+        assert_eq!(effective_suggestions(None, true)[0].title, "Plan");
+        let saved = vec![
+            WelcomeSuggestion::new("bulb", "Ideas", "", "Give me ideas"),
+            WelcomeSuggestion::new("chat", "  ", "", "no title"),
+        ];
+        let shown = effective_suggestions(Some(&saved), false);
+        assert_eq!(shown, vec![saved[0].clone()]);
+        // Removing every card is kept, not replaced by the defaults
+        assert!(effective_suggestions(Some(&[]), true).is_empty());
+    }
+}
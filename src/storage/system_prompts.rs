@@ -0,0 +1,159 @@
+//! System prompt presets
+//!
+//! The default agent prompt is rebuilt from code on every launch, in the UI
+//! language. The minimal preset drops the tool walkthrough; a custom prompt
+//! is kept in `AppSettings::custom_system_prompt` and survives reloads.
+
+use crate::storage::settings::default_system_prompt_for_lang;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPromptPreset {
+    /// Full agent prompt with environment and tool instructions
+    #[default]
+    Default,
+    /// A few lines, for small models or plain chat
+    Minimal,
+    /// The user's own text
+    Custom,
+}
+
+impl SystemPromptPreset {
+    pub const ALL: [SystemPromptPreset; 3] = [Self::Default, Self::Minimal, Self::Custom];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Minimal => "minimal",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.id() == id)
+    }
+
+    pub fn label(&self, is_en: bool) -> &'static str {
+        match (self, is_en) {
+            (Self::Default, true) => "Default agent",
+            (Self::Default, false) => "Agent par defaut",
+            (Self::Minimal, true) => "Minimal",
+            (Self::Minimal, false) => "Minimal",
+            (Self::Custom, true) => "Custom",
+            (Self::Custom, false) => "Personnalise",
+        }
+    }
+}
+
+/// Short prompt without the tool walkthrough; tools still get their own
+/// instructions from the agent prompt builder
+pub fn minimal_system_prompt(lang: &str) -> String {
+    let language = if lang == "en" {
+        "Always respond in English."
+    } else {
+        "Always respond in French (toujours repondre en francais)."
+    };
+    format!(
+        "You are clawRS, a helpful AI assistant running locally on the user's machine.\n{}\nBe concise and accurate. Say so when you don't know.",
+        language
+    )
+}
+
+/// Prompt text for `preset`; an empty custom prompt falls back to the default
+pub fn resolve_system_prompt(preset: SystemPromptPreset, custom: &str, lang: &str) -> String {
+    match preset {
+        SystemPromptPreset::Minimal => minimal_system_prompt(lang),
+        SystemPromptPreset::Custom if !custom.trim().is_empty() => custom.to_string(),
+        _ => default_system_prompt_for_lang(lang),
+    }
+}
+
+/// One line of a line-by-line diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff from `old` to `new` (longest common subsequence)
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    // lcs[i][j]: common lines between a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            diff.push(DiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|line| DiffLine::Removed(line.to_string())));
+    diff.extend(b[j..].iter().map(|line| DiffLine::Added(line.to_string())));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_system_prompt() {
+        let default = default_system_prompt_for_lang("en");
+        assert_eq!(
+            resolve_system_prompt(SystemPromptPreset::Default, "ignored", "en"),
+            default
+        );
+        assert_eq!(
+            resolve_system_prompt(SystemPromptPreset::Custom, "Be brief.", "en"),
+            "Be brief."
+        );
+        assert_eq!(
+            resolve_system_prompt(SystemPromptPreset::Custom, "  ", "en"),
+            default
+        );
+        assert!(resolve_system_prompt(SystemPromptPreset::Minimal, "", "fr").contains("francais"));
+        assert_eq!(
+            SystemPromptPreset::from_id("minimal"),
+            Some(SystemPromptPreset::Minimal)
+        );
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nB\nc\nd");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Added("B".to_string()),
+                DiffLine::Same("c".to_string()),
+                DiffLine::Added("d".to_string()),
+            ]
+        );
+        assert!(diff_lines("x\ny", "x\ny")
+            .iter()
+            .all(|line| matches!(line, DiffLine::Same(_))));
+    }
+}
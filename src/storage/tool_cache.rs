@@ -0,0 +1,168 @@
+//! Persistent tool result cache
+//!
+//! Stores results of expensive idempotent tools (web search, page fetches,
+//! PDF parsing) on disk so repeated questions about the same source don't
+//! hit the network or re-parse the document. Entries expire after a
+//! per-tool TTL. Opt-in via `AppSettings::persistent_tool_cache`.
+
+use crate::agent::loop_runner::tool_call_key;
+use crate::agent::tools::ToolResult;
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// A cached tool result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCacheEntry {
+    /// Tool that produced the result
+    pub tool: String,
+    /// Parameters the tool was called with
+    pub params: Value,
+    /// The result returned by the tool
+    pub result: ToolResult,
+    /// When the result was stored
+    pub stored_at: DateTime<Utc>,
+}
+
+impl ToolCacheEntry {
+    /// Whether the entry is past its tool's TTL at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        match cache_ttl(&self.tool) {
+            Some(ttl) => now - self.stored_at > ttl,
+            None => true,
+        }
+    }
+}
+
+/// Time-to-live for a cacheable tool, `None` if the tool is never cached
+pub fn cache_ttl(tool: &str) -> Option<Duration> {
+    match tool {
+        // Search results go stale quickly
+        "web_search" => Some(Duration::hours(1)),
+        "web_fetch" => Some(Duration::hours(24)),
+        // Keyed on the file's mtime, so a long TTL is safe
+        "pdf_read" => Some(Duration::days(7)),
+        _ => None,
+    }
+}
+
+/// Get the tool cache directory
+fn get_cache_dir() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("tool_cache"))
+}
+
+/// Cache key for a call; local files also hash their modification time
+fn cache_key(tool: &str, params: &Value) -> u64 {
+    let mtime = params
+        .get("path")
+        .and_then(|p| p.as_str())
+        .and_then(|p| fs::metadata(p).ok())
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    match mtime {
+        Some(secs) => tool_call_key(tool, &serde_json::json!({ "params": params, "mtime": secs })),
+        None => tool_call_key(tool, params),
+    }
+}
+
+fn get_entry_path(tool: &str, params: &Value) -> Result<PathBuf, StorageError> {
+    Ok(get_cache_dir()?.join(format!("{:016x}.json", cache_key(tool, params))))
+}
+
+/// Look up a fresh cached result for a tool call
+pub fn get_cached_result(tool: &str, params: &Value) -> Option<ToolResult> {
+    cache_ttl(tool)?;
+    let path = get_entry_path(tool, params).ok()?;
+    let json = fs::read_to_string(&path).ok()?;
+    let entry: ToolCacheEntry = serde_json::from_str(&json).ok()?;
+
+    // Guard against hash collisions
+    if entry.tool != tool || &entry.params != params {
+        return None;
+    }
+
+    if entry.is_expired(Utc::now()) {
+        let _ = fs::remove_file(&path);
+        return None;
+    }
+
+    tracing::debug!("Tool cache hit: {}", tool);
+    Some(entry.result)
+}
+
+/// Store a successful tool result if the tool is cacheable
+pub fn store_result(tool: &str, params: &Value, result: &ToolResult) -> Result<(), StorageError> {
+    if cache_ttl(tool).is_none() || !result.success {
+        return Ok(());
+    }
+
+    let dir = get_cache_dir()?;
+    fs::create_dir_all(&dir)?;
+    let entry = ToolCacheEntry {
+        tool: tool.to_string(),
+        params: params.clone(),
+        result: result.clone(),
+        stored_at: Utc::now(),
+    };
+    fs::write(get_entry_path(tool, params)?, serde_json::to_string(&entry)?)?;
+    Ok(())
+}
+
+/// Delete every cached result, returning how many entries were removed
+pub fn clear_tool_cache() -> Result<usize, StorageError> {
+    let dir = get_cache_dir()?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+    tracing::info!("Cleared {} tool cache entries", removed);
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(tool: &str, age: Duration) -> ToolCacheEntry {
+        ToolCacheEntry {
+            tool: tool.to_string(),
+            params: serde_json::json!({"query": "rust"}),
+            result: ToolResult {
+                success: true,
+                data: Value::Null,
+                message: "ok".to_string(),
+            },
+            stored_at: Utc::now() - age,
+        }
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        assert!(cache_ttl("web_search").is_some());
+        assert!(cache_ttl("pdf_read").is_some());
+        assert!(cache_ttl("bash").is_none());
+        assert!(cache_ttl("file_read").is_none());
+    }
+
+    #[test]
+    fn test_entry_expiry() {
+        let now = Utc::now();
+        assert!(!entry("web_search", Duration::minutes(10)).is_expired(now));
+        assert!(entry("web_search", Duration::hours(2)).is_expired(now));
+        assert!(!entry("web_fetch", Duration::hours(2)).is_expired(now));
+        assert!(entry("bash", Duration::zero()).is_expired(now));
+    }
+}
@@ -0,0 +1,231 @@
+//! Tool call recordings for replay
+//!
+//! In record mode every executed tool call is appended to
+//! `{data_dir}/recordings/{name}.jsonl`. In replay mode the recorded results
+//! are served back instead of running the tools, which makes demos work
+//! offline and lets tests exercise prompts and the agent loop
+//! deterministically.
+
+use crate::agent::loop_runner::tool_call_key;
+use crate::agent::tools::ToolResult;
+use crate::storage::{get_data_dir, StorageError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Whether tool calls are recorded, replayed or run normally
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "name", rename_all = "snake_case")]
+pub enum ToolReplayMode {
+    #[default]
+    Off,
+    /// Run tools and append their results to the named recording
+    Record(String),
+    /// Serve results from the named recording without running tools
+    Replay(String),
+}
+
+/// What a recorded call returned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedOutcome {
+    Ok(ToolResult),
+    Err(String),
+}
+
+impl RecordedOutcome {
+    pub fn into_result(self) -> Result<ToolResult, String> {
+        match self {
+            Self::Ok(result) => Ok(result),
+            Self::Err(error) => Err(error),
+        }
+    }
+}
+
+/// One recorded tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    pub tool: String,
+    pub params: Value,
+    pub outcome: RecordedOutcome,
+}
+
+/// A loaded recording, served call by call
+///
+/// Calls repeated with identical parameters (polling, retries) get their
+/// recorded results in order; once exhausted the last one is repeated.
+#[derive(Debug, Default)]
+pub struct ToolRecording {
+    calls: Vec<RecordedCall>,
+    cursors: Mutex<HashMap<u64, usize>>,
+}
+
+impl ToolRecording {
+    pub fn new(calls: Vec<RecordedCall>) -> Self {
+        Self {
+            calls,
+            cursors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn calls(&self) -> &[RecordedCall] {
+        &self.calls
+    }
+
+    /// Names of the tools appearing in the recording, deduplicated
+    pub fn tools(&self) -> Vec<String> {
+        let mut tools: Vec<String> = self.calls.iter().map(|c| c.tool.clone()).collect();
+        tools.sort();
+        tools.dedup();
+        tools
+    }
+
+    /// Next recorded outcome for a call, `None` if it was never recorded
+    pub fn next_outcome(&self, tool: &str, params: &Value) -> Option<RecordedOutcome> {
+        let key = tool_call_key(tool, params);
+        let matches: Vec<&RecordedCall> = self
+            .calls
+            .iter()
+            .filter(|c| tool_call_key(&c.tool, &c.params) == key)
+            .collect();
+        let last = matches.len().checked_sub(1)?;
+        let mut cursors = self.cursors.lock().expect("cursors mutex poisoned");
+        let cursor = cursors.entry(key).or_insert(0);
+        let outcome = matches[(*cursor).min(last)].outcome.clone();
+        *cursor += 1;
+        Some(outcome)
+    }
+}
+
+fn get_recordings_dir() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join("recordings"))
+}
+
+/// Recording names may only contain characters safe in a file name
+pub fn sanitize_recording_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn get_recording_path(name: &str) -> Result<PathBuf, StorageError> {
+    Ok(get_recordings_dir()?.join(format!("{}.jsonl", sanitize_recording_name(name))))
+}
+
+/// Append a call to a recording
+pub fn record_call(name: &str, call: &RecordedCall) -> Result<(), StorageError> {
+    fs::create_dir_all(get_recordings_dir()?)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_recording_path(name)?)?;
+    writeln!(file, "{}", serde_json::to_string(call)?)?;
+    Ok(())
+}
+
+/// Load a recording, skipping unreadable lines
+pub fn load_recording(name: &str) -> Result<ToolRecording, StorageError> {
+    let content = fs::read_to_string(get_recording_path(name)?)?;
+    Ok(ToolRecording::new(
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+    ))
+}
+
+/// Names of the saved recordings, sorted
+pub fn list_recordings() -> Vec<String> {
+    let Ok(entries) = get_recordings_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "jsonl")
+                .then(|| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Remove a recording
+pub fn delete_recording(name: &str) -> Result<(), StorageError> {
+    let path = get_recording_path(name)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(tool: &str, params: Value, message: &str) -> RecordedCall {
+        RecordedCall {
+            tool: tool.to_string(),
+            params,
+            outcome: RecordedOutcome::Ok(ToolResult {
+                success: true,
+                data: Value::Null,
+                message: message.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_replay_order() {
+        let recording = ToolRecording::new(vec![
+            call("deep_research_check", json!({"id": "1"}), "running"),
+            call("web_search", json!({"query": "rust"}), "results"),
+            call("deep_research_check", json!({"id": "1"}), "done"),
+        ]);
+        let next = |tool: &str, params: Value| match recording.next_outcome(tool, &params) {
+            Some(RecordedOutcome::Ok(result)) => Some(result.message),
+            _ => None,
+        };
+        assert_eq!(
+            next("deep_research_check", json!({"id": "1"})).as_deref(),
+            Some("running")
+        );
+        assert_eq!(
+            next("deep_research_check", json!({"id": "1"})).as_deref(),
+            Some("done")
+        );
+        assert_eq!(
+            next("deep_research_check", json!({"id": "1"})).as_deref(),
+            Some("done")
+        );
+        assert_eq!(
+            next("web_search", json!({"query": "rust"})).as_deref(),
+            Some("results")
+        );
+        assert_eq!(next("web_search", json!({"query": "go"})), None);
+        assert_eq!(recording.tools(), vec!["deep_research_check", "web_search"]);
+    }
+
+    #[test]
+    fn test_mode_serialization() {
+        let mode = ToolReplayMode::Replay("demo".to_string());
+        let json = serde_json::to_string(&mode).unwrap();
+        assert_eq!(json, r#"{"mode":"replay","name":"demo"}"#);
+        assert_eq!(serde_json::from_str::<ToolReplayMode>(&json).unwrap(), mode);
+        assert_eq!(sanitize_recording_name(" my demo/1 "), "my_demo_1");
+    }
+}
@@ -0,0 +1,129 @@
+//! Custom root certificates
+//!
+//! Corporate networks often intercept TLS with their own root CA, which the
+//! system store may not know about. Imported PEM files are copied to
+//! `{data_dir}/certificates/` and trusted, on top of the system roots, by
+//! every client built with [`client_builder`](crate::system::proxy::client_builder).
+
+use crate::storage::get_data_dir;
+use once_cell::sync::Lazy;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Certificates parsed from the imported files
+static CERTIFICATES: Lazy<RwLock<Vec<reqwest::Certificate>>> =
+    Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Read every certificate of a PEM file (a bundle may hold several)
+fn read_pem(path: &Path) -> Result<Vec<reqwest::Certificate>, String> {
+    let pem = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    if certificates.is_empty() {
+        return Err(format!("{}: no PEM certificate found", path.display()));
+    }
+    Ok(certificates)
+}
+
+/// Check a PEM file and copy it into the data directory
+///
+/// Returns the path of the copy, to be stored in the settings.
+pub fn import_certificate(path: &Path) -> Result<PathBuf, String> {
+    read_pem(path)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("{}: not a file", path.display()))?;
+    let dir = get_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("certificates");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let dest = dir.join(file_name);
+    fs::copy(path, &dest).map_err(|e| e.to_string())?;
+    tracing::info!("Imported certificate {}", dest.display());
+    Ok(dest)
+}
+
+/// Trust the certificates in `paths` for clients built from now on
+pub fn set_custom_certificates(paths: &[String]) {
+    let mut certificates = Vec::new();
+    for path in paths {
+        match read_pem(Path::new(path)) {
+            Ok(found) => certificates.extend(found),
+            Err(e) => tracing::warn!("Skipping certificate: {}", e),
+        }
+    }
+    *CERTIFICATES.write().unwrap_or_else(|e| e.into_inner()) = certificates;
+}
+
+/// Add the imported certificates to a client builder
+pub fn add_custom_certificates(mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    for certificate in CERTIFICATES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+    {
+        builder = builder.add_root_certificate(certificate.clone());
+    }
+    builder
+}
+
+fn is_certificate_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "certificate",
+        "unknownissuer",
+        "unknown issuer",
+        "self signed",
+        "self-signed",
+        "local issuer",
+    ]
+    .iter()
+    .any(|needle| message.contains(needle))
+}
+
+/// Full text of a request error, with a hint when the server certificate
+/// was rejected
+pub fn describe_error(error: &reqwest::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    if is_certificate_error(&message) {
+        message.push_str(
+            " (certificate not trusted: behind a corporate proxy, import its root CA in Settings > Tools > Certificates)",
+        );
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_certificate_error() {
+        assert!(is_certificate_error(
+            "invalid peer certificate: UnknownIssuer"
+        ));
+        assert!(is_certificate_error(
+            "error:0A000086:SSL routines: certificate verify failed (self-signed certificate in certificate chain)"
+        ));
+        assert!(!is_certificate_error("operation timed out"));
+    }
+
+    #[test]
+    fn test_read_pem_rejects_garbage() {
+        let dir = std::env::temp_dir().join("clawrs-test-certificates");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("not-a-cert.pem");
+        fs::write(&path, "hello").unwrap();
+        assert!(read_pem(&path).is_err());
+        assert!(read_pem(&dir.join("missing.pem")).is_err());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
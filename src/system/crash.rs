@@ -0,0 +1,139 @@
+//! Emergency snapshot of the session on a crash
+//!
+//! The chat loop keeps a copy of the open conversation, with the reply being
+//! generated, in memory. A panic hook writes it to
+//! `{data_dir}/crash_snapshot.json` together with the tool trace of the last
+//! run, and the next launch offers to restore it. The hook cannot reach the
+//! UI signals, which live on the UI thread, hence the separate copy. A native
+//! crash that kills the process without unwinding (a segfault in a GPU
+//! driver) never reaches the hook; only the periodic autosave covers it.
+
+use crate::storage::audit_log::{latest_audit_log, AuditEntry};
+use crate::storage::conversations::Conversation;
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Minimum time between two copies of a streaming reply
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(2);
+
+const SNAPSHOT_FILE: &str = "crash_snapshot.json";
+
+/// Conversation in flight, as last recorded by the chat loop
+static SESSION: Lazy<Mutex<Option<Conversation>>> = Lazy::new(|| Mutex::new(None));
+
+/// What was saved when the app panicked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashSnapshot {
+    pub crashed_at: DateTime<Utc>,
+    /// Panic message
+    pub message: String,
+    /// `file:line` of the panic
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub thread: Option<String>,
+    /// Open conversation, including the unfinished reply
+    #[serde(default)]
+    pub conversation: Option<Conversation>,
+    /// Tool calls of the last run
+    #[serde(default)]
+    pub last_run: Vec<AuditEntry>,
+}
+
+fn snapshot_path() -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir()?.join(SNAPSHOT_FILE))
+}
+
+/// Remember the conversation in flight
+pub fn record_session(conversation: Conversation) {
+    if let Ok(mut session) = SESSION.lock() {
+        *session = Some(conversation);
+    }
+}
+
+/// Forget the conversation in flight once it is safely on disk
+pub fn clear_session() {
+    if let Ok(mut session) = SESSION.lock() {
+        *session = None;
+    }
+}
+
+/// Text of a panic payload (`panic!` with a literal or a format string)
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Save the session on panic, then run the previous hook (which prints the
+/// message). Call once, early in `main`.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // `try_lock`: the panic may have happened while the session was held
+        let conversation = SESSION.try_lock().ok().and_then(|session| session.clone());
+        let snapshot = CrashSnapshot {
+            crashed_at: Utc::now(),
+            message: panic_message(info.payload()),
+            location: info.location().map(|l| format!("{}:{}", l.file(), l.line())),
+            thread: std::thread::current().name().map(str::to_string),
+            conversation,
+            last_run: latest_audit_log(),
+        };
+        let written = snapshot_path().ok().and_then(|path| {
+            let json = serde_json::to_vec_pretty(&snapshot).ok()?;
+            fs::write(&path, json).ok().map(|_| path)
+        });
+        match written {
+            Some(path) => tracing::error!("ClawRS crashed; session saved to {}", path.display()),
+            None => tracing::error!("ClawRS crashed; the session could not be saved"),
+        }
+        previous(info);
+    }));
+}
+
+/// Snapshot left by the previous launch, if it crashed
+pub fn load_crash_snapshot() -> Option<CrashSnapshot> {
+    let path = snapshot_path().ok()?;
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            tracing::warn!("Unreadable crash snapshot, removing it: {}", e);
+            let _ = fs::remove_file(&path);
+            None
+        }
+    }
+}
+
+/// Remove the snapshot once restored or dismissed
+pub fn discard_crash_snapshot() -> Result<(), StorageError> {
+    let path = snapshot_path()?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message() {
+        let literal = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        assert_eq!(panic_message(literal.as_ref()), "boom");
+        let formatted = std::panic::catch_unwind(|| panic!("layer {}", 3)).unwrap_err();
+        assert_eq!(panic_message(formatted.as_ref()), "layer 3");
+        assert_eq!(panic_message(&42u32), "unknown panic");
+    }
+}
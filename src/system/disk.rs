@@ -0,0 +1,138 @@
+//! Free disk space checks
+//!
+//! Used before downloads and file generation so they fail upfront with a
+//! readable message instead of an IO error halfway through.
+
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Kept free on top of what a write needs
+const HEADROOM_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Below this the data directory is reported as nearly full
+pub const LOW_SPACE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+#[error("Not enough disk space: needs {}, {} free", format_size(*.needed), format_size(*.available))]
+pub struct InsufficientSpace {
+    pub needed: u64,
+    pub available: u64,
+}
+
+/// Human-readable size, e.g. "8.2 GB" or "350 MB"
+pub fn format_size(bytes: u64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.1} GB", bytes / GB)
+    } else if bytes >= MB {
+        format!("{:.0} MB", bytes / MB)
+    } else {
+        format!("{:.0} KB", (bytes / 1024.0).ceil())
+    }
+}
+
+/// Closest existing ancestor, since targets often don't exist yet
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    absolute
+        .ancestors()
+        .find(|p| p.exists())
+        .map(Path::to_path_buf)
+}
+
+/// Bytes available to the current user on the volume holding `path`
+///
+/// A single filesystem call, cheap enough for the UI thread. `None` when it
+/// can't be determined; callers then let the write proceed.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = existing_ancestor(path)?;
+    available_space_impl(&path)
+}
+
+// The statvfs field types differ between platforms
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn available_space_impl(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and `stat` points to writable memory
+    // of the right size; it is only read when the call succeeds
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stat.assume_init()
+    };
+    Some((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+#[cfg(windows)]
+fn available_space_impl(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    // SAFETY: `wide` is NUL-terminated and the out pointer is a valid u64;
+    // the other outputs are optional and passed as null
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn available_space_impl(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Whether `needed` bytes fit in `available` with the headroom left free;
+/// an unknown free space lets the write proceed
+fn check_space(needed: u64, available: Option<u64>) -> Result<(), InsufficientSpace> {
+    match available {
+        Some(available) if available < needed.saturating_add(HEADROOM_BYTES) => {
+            Err(InsufficientSpace { needed, available })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fail when writing `needed` bytes at `path` would fill the volume
+pub fn ensure_free_space(path: &Path, needed: u64) -> Result<(), InsufficientSpace> {
+    check_space(needed, available_space(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insufficient_space_message() {
+        let error = InsufficientSpace {
+            needed: 8_804_682_957,
+            available: 3_328_599_654,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Not enough disk space: needs 8.2 GB, 3.1 GB free"
+        );
+    }
+
+    #[test]
+    fn test_check_space() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        assert!(check_space(GB, Some(2 * GB)).is_ok());
+        // The headroom counts: exactly the size needed isn't enough
+        assert!(check_space(GB, Some(GB)).is_err());
+        assert!(check_space(GB, None).is_ok());
+    }
+}
@@ -0,0 +1,235 @@
+//! "Open with ClawRS" for models and text files
+//!
+//! Optional, per user: double-clicking a `.gguf` loads it as the chat model,
+//! and opening a markdown or text file with ClawRS starts a conversation
+//! with it attached (see [`LaunchRequest`](crate::system::single_instance::LaunchRequest)).
+//! The `clawrs://` link scheme is registered alongside.
+//! Linux gets a desktop entry and a MIME type for `.gguf`; Windows gets
+//! per-user registry classes. On macOS document types and URL schemes come
+//! from the app bundle's Info.plist and can't be registered at runtime.
+
+use crate::system::single_instance::URL_SCHEME;
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use thiserror::Error;
+
+const GGUF_MIME: &str = "application/x-gguf";
+
+fn scheme_mime() -> String {
+    format!("x-scheme-handler/{}", URL_SCHEME)
+}
+
+#[derive(Error, Debug)]
+pub enum AssociationError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not locate the user data directory")]
+    NoDataDir,
+    #[error("Command failed: {0}")]
+    Command(String),
+    #[error("File associations come from the app bundle on this system")]
+    Unsupported,
+}
+
+/// Whether associations can be registered from the app on this system
+pub fn is_supported() -> bool {
+    cfg!(any(target_os = "linux", windows))
+}
+
+/// Register the associations for the running executable
+pub fn register() -> Result<(), AssociationError> {
+    let exe = std::env::current_exe()?;
+    platform::register(&exe)?;
+    tracing::info!("Registered file associations for {}", exe.display());
+    Ok(())
+}
+
+/// Remove the associations added by [`register`]
+pub fn unregister() -> Result<(), AssociationError> {
+    platform::unregister()?;
+    tracing::info!("Removed file associations");
+    Ok(())
+}
+
+/// freedesktop.org entry: files and links are passed as arguments, one
+/// instance receives them all
+pub fn desktop_entry(exe: &Path) -> String {
+    let scheme = scheme_mime();
+    let mime_types: Vec<&str> = std::iter::once(GGUF_MIME)
+        .chain(["text/markdown", "text/x-markdown", "text/plain"])
+        .chain(std::iter::once(scheme.as_str()))
+        .collect();
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=ClawRS\n\
+         Comment=Local LLM chat\n\
+         Exec=\"{}\" %U\n\
+         Terminal=false\n\
+         Categories=Utility;Development;\n\
+         MimeType={};\n",
+        exe.display().to_string().replace('"', "\\\""),
+        mime_types.join(";")
+    )
+}
+
+/// shared-mime-info definition of `.gguf`, which no system knows about
+pub fn gguf_mime_package() -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n  \
+         <mime-type type=\"{}\">\n    \
+         <comment>GGUF model</comment>\n    \
+         <glob pattern=\"*.gguf\"/>\n  \
+         </mime-type>\n\
+         </mime-info>\n",
+        GGUF_MIME
+    )
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::fs;
+
+    const DESKTOP_FILE: &str = "clawrs.desktop";
+
+    /// Run a helper; a missing helper isn't an error, the files still work
+    /// once the desktop picks them up
+    fn run_optional(program: &str, args: &[&str]) {
+        match std::process::Command::new(program).args(args).output() {
+            Ok(output) if !output.status.success() => tracing::warn!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::debug!("{} not run: {}", program, e),
+        }
+    }
+
+    fn user_data_dir() -> Result<PathBuf, AssociationError> {
+        directories::BaseDirs::new()
+            .map(|dirs| dirs.data_dir().to_path_buf())
+            .ok_or(AssociationError::NoDataDir)
+    }
+
+    pub fn register(exe: &Path) -> Result<(), AssociationError> {
+        let data = user_data_dir()?;
+        let applications = data.join("applications");
+        let mime_packages = data.join("mime").join("packages");
+        fs::create_dir_all(&applications)?;
+        fs::create_dir_all(&mime_packages)?;
+        fs::write(applications.join(DESKTOP_FILE), desktop_entry(exe))?;
+        fs::write(mime_packages.join("clawrs-gguf.xml"), gguf_mime_package())?;
+
+        run_optional("update-mime-database", &[&data.join("mime").to_string_lossy()]);
+        run_optional("update-desktop-database", &[&applications.to_string_lossy()]);
+        run_optional("xdg-mime", &["default", DESKTOP_FILE, GGUF_MIME]);
+        run_optional("xdg-mime", &["default", DESKTOP_FILE, &scheme_mime()]);
+        Ok(())
+    }
+
+    pub fn unregister() -> Result<(), AssociationError> {
+        let data = user_data_dir()?;
+        for file in [
+            data.join("applications").join(DESKTOP_FILE),
+            data.join("mime").join("packages").join("clawrs-gguf.xml"),
+        ] {
+            if file.exists() {
+                fs::remove_file(file)?;
+            }
+        }
+        run_optional("update-mime-database", &[&data.join("mime").to_string_lossy()]);
+        run_optional("update-desktop-database", &[&data.join("applications").to_string_lossy()]);
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    const MODEL_PROG_ID: &str = "ClawRS.Model";
+    const DOCUMENT_PROG_ID: &str = "ClawRS.Document";
+    /// Offered in "Open with"; `.gguf` also opens with ClawRS by default
+    const DOCUMENT_EXTENSIONS: [&str; 3] = ["md", "markdown", "txt"];
+
+    fn reg(args: &[&str]) -> Result<(), AssociationError> {
+        let output = std::process::Command::new("reg").args(args).output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(AssociationError::Command(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        }
+    }
+
+    fn classes(key: &str) -> String {
+        format!("HKCU\\Software\\Classes\\{}", key)
+    }
+
+    pub fn register(exe: &Path) -> Result<(), AssociationError> {
+        let command = format!("\"{}\" \"%1\"", exe.display());
+        for (prog_id, label) in [(MODEL_PROG_ID, "GGUF model"), (DOCUMENT_PROG_ID, "ClawRS document")] {
+            reg(&["add", &classes(prog_id), "/ve", "/d", label, "/f"])?;
+            let open = classes(&format!("{}\\shell\\open\\command", prog_id));
+            reg(&["add", &open, "/ve", "/d", &command, "/f"])?;
+        }
+        reg(&["add", &classes(".gguf"), "/ve", "/d", MODEL_PROG_ID, "/f"])?;
+
+        reg(&["add", &classes(URL_SCHEME), "/ve", "/d", "URL:ClawRS link", "/f"])?;
+        reg(&["add", &classes(URL_SCHEME), "/v", "URL Protocol", "/d", "", "/f"])?;
+        let open = classes(&format!("{}\\shell\\open\\command", URL_SCHEME));
+        reg(&["add", &open, "/ve", "/d", &command, "/f"])?;
+        for ext in DOCUMENT_EXTENSIONS {
+            let open_with = classes(&format!(".{}\\OpenWithProgids", ext));
+            reg(&["add", &open_with, "/v", DOCUMENT_PROG_ID, "/t", "REG_NONE", "/f"])?;
+        }
+        Ok(())
+    }
+
+    pub fn unregister() -> Result<(), AssociationError> {
+        // Keys that are already gone are fine
+        let _ = reg(&["delete", &classes(MODEL_PROG_ID), "/f"]);
+        let _ = reg(&["delete", &classes(DOCUMENT_PROG_ID), "/f"]);
+        let _ = reg(&["delete", &classes(".gguf"), "/ve", "/f"]);
+        let _ = reg(&["delete", &classes(URL_SCHEME), "/f"]);
+        for ext in DOCUMENT_EXTENSIONS {
+            let open_with = classes(&format!(".{}\\OpenWithProgids", ext));
+            let _ = reg(&["delete", &open_with, "/v", DOCUMENT_PROG_ID, "/f"]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use super::*;
+
+    pub fn register(_exe: &Path) -> Result<(), AssociationError> {
+        Err(AssociationError::Unsupported)
+    }
+
+    pub fn unregister() -> Result<(), AssociationError> {
+        Err(AssociationError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(Path::new("/opt/ClawRS/clawrs"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nExec=\"/opt/ClawRS/clawrs\" %U\n"));
+        assert!(entry.contains(
+            "\nMimeType=application/x-gguf;text/markdown;text/x-markdown;text/plain;x-scheme-handler/clawrs;\n"
+        ));
+        assert!(gguf_mime_package().contains("<glob pattern=\"*.gguf\"/>"));
+    }
+}
@@ -15,6 +15,43 @@ pub struct GpuInfo {
     pub is_available: bool,
 }
 
+/// Layer count that offloads the whole model
+pub const ALL_GPU_LAYERS: u32 = 99;
+
+/// VRAM kept free for the KV cache and compute buffers in auto mode
+const AUTO_LAYERS_RESERVE_MB: u64 = 1536;
+
+impl GpuInfo {
+    /// Free VRAM, or the total when usage can't be read; `None` without a GPU
+    pub fn free_vram_mb(&self) -> Option<u64> {
+        if !self.is_available || self.vram_total_mb == 0 {
+            return None;
+        }
+        Some(if self.vram_usage_available {
+            self.vram_total_mb.saturating_sub(self.vram_used_mb)
+        } else {
+            self.vram_total_mb
+        })
+    }
+}
+
+/// GPU layers for a model of `model_bytes` with `n_layer` layers given
+/// `free_vram_mb`: everything when it fits, otherwise the share of layers
+/// that does. Without a detected GPU llama.cpp decides, so offload all.
+pub fn auto_gpu_layers(model_bytes: u64, n_layer: Option<u32>, free_vram_mb: Option<u64>) -> u32 {
+    let Some(free_mb) = free_vram_mb else {
+        return ALL_GPU_LAYERS;
+    };
+    let model_mb = (model_bytes / 1024 / 1024).max(1);
+    let budget_mb = free_mb.saturating_sub(AUTO_LAYERS_RESERVE_MB);
+    if model_mb <= budget_mb {
+        return ALL_GPU_LAYERS;
+    }
+    // Unknown layer count: 32 is typical of 7-8B models
+    let n_layer = n_layer.unwrap_or(32) as u64;
+    (n_layer * budget_mb / model_mb) as u32
+}
+
 /// Get total dedicated VRAM in GB (returns 0.0 if detection fails)
 pub fn get_total_vram_gb() -> Option<f64> {
     let gpu = detect_gpu();
@@ -148,3 +185,34 @@ fn detect_gpu_wmic() -> Option<GpuInfo> {
         is_available: true,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_vram() {
+        let mut gpu = GpuInfo {
+            name: "RTX".to_string(),
+            vram_total_mb: 8192,
+            vram_used_mb: 2048,
+            vram_usage_available: true,
+            is_available: true,
+        };
+        assert_eq!(gpu.free_vram_mb(), Some(6144));
+        gpu.vram_usage_available = false;
+        assert_eq!(gpu.free_vram_mb(), Some(8192));
+        assert_eq!(GpuInfo::default().free_vram_mb(), None);
+    }
+
+    #[test]
+    fn test_auto_gpu_layers() {
+        let gb = 1024 * 1024 * 1024;
+        // 4.5 GB model in 8 GB free: everything fits
+        assert_eq!(auto_gpu_layers(9 * gb / 2, Some(32), Some(8192)), ALL_GPU_LAYERS);
+        // 8 GB model in 6 GB free: 4.5 GB of budget, so 18 of 32 layers
+        assert_eq!(auto_gpu_layers(8 * gb, Some(32), Some(6144)), 18);
+        assert_eq!(auto_gpu_layers(8 * gb, Some(32), Some(1024)), 0);
+        assert_eq!(auto_gpu_layers(8 * gb, Some(32), None), ALL_GPU_LAYERS);
+    }
+}
@@ -0,0 +1,70 @@
+//! In-memory buffer of recent log lines
+//!
+//! Logs only go to the terminal, which most users never see. A copy of the
+//! last lines is kept here so they can be included in a diagnostic bundle.
+
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Mutex;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Number of log lines kept
+const MAX_LINES: usize = 2000;
+
+static RECENT_LOGS: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_LINES)));
+
+fn push_line(line: &str) {
+    let mut logs = RECENT_LOGS.lock().expect("log buffer mutex poisoned");
+    if logs.len() == MAX_LINES {
+        logs.pop_front();
+    }
+    logs.push_back(line.to_string());
+}
+
+/// Recent log lines, oldest first
+pub fn recent_logs() -> Vec<String> {
+    RECENT_LOGS
+        .lock()
+        .expect("log buffer mutex poisoned")
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// `MakeWriter` for a `tracing_subscriber::fmt` layer writing to the buffer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecentLogs;
+
+/// Writer for one event; lines are stored when it is dropped
+pub struct RecentLogsWriter {
+    buffer: Vec<u8>,
+}
+
+impl io::Write for RecentLogsWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RecentLogsWriter {
+    fn drop(&mut self) {
+        for line in String::from_utf8_lossy(&self.buffer).lines() {
+            push_line(line);
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for RecentLogs {
+    type Writer = RecentLogsWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RecentLogsWriter { buffer: Vec::new() }
+    }
+}
@@ -2,5 +2,16 @@
 //!
 //! This module provides system-level functionality like GPU detection and resource monitoring.
 
+pub mod certificates;
+pub mod crash;
+pub mod diagnostics;
+pub mod disk;
+pub mod file_associations;
 pub mod gpu;
+pub mod log_buffer;
+pub mod notifications;
+pub mod proxy;
+pub mod quick_actions;
 pub mod resources;
+pub mod single_instance;
+pub mod updates;
@@ -0,0 +1,118 @@
+//! Desktop notifications
+//!
+//! Sent through the system's own tool (`notify-send`, `osascript`,
+//! PowerShell), so a missing tool only costs the notification. During focus
+//! mode they are queued and sent by [`flush_held`] once it ends.
+
+use crate::agent::focus::is_focus_active;
+use std::collections::VecDeque;
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Notifications kept during focus mode; older ones are dropped past this
+const MAX_HELD: usize = 10;
+
+/// Notifications held back by focus mode, oldest first
+static HELD: Mutex<VecDeque<(String, String)>> = Mutex::new(VecDeque::new());
+
+/// Show a notification, or queue it while focus mode is on; failures are
+/// logged, not returned
+pub fn notify(title: &str, body: &str) {
+    if is_focus_active() {
+        tracing::debug!("Notification held back by focus mode: {}", title);
+        hold(&mut HELD.lock().unwrap_or_else(|e| e.into_inner()), title, body);
+        return;
+    }
+    send(title, body);
+}
+
+/// Send the notifications queued during focus mode once it is over
+///
+/// Runs the platform tool for each, so call it off the UI thread.
+pub fn flush_held() {
+    if is_focus_active() {
+        return;
+    }
+    let held = std::mem::take(&mut *HELD.lock().unwrap_or_else(|e| e.into_inner()));
+    if !held.is_empty() {
+        tracing::info!("Sending {} notification(s) held back by focus mode", held.len());
+    }
+    for (title, body) in held {
+        send(&title, &body);
+    }
+}
+
+fn hold(held: &mut VecDeque<(String, String)>, title: &str, body: &str) {
+    if held.len() == MAX_HELD {
+        held.pop_front();
+    }
+    held.push_back((title.to_string(), body.to_string()));
+}
+
+fn send(title: &str, body: &str) {
+    let result = platform_command(title, body).and_then(|mut command| command.output().ok());
+    match result {
+        Some(output) if output.status.success() => {}
+        Some(output) => tracing::debug!(
+            "Notification failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        None => tracing::debug!("No notification tool for: {}", title),
+    }
+}
+
+/// Single-quoted string for AppleScript and PowerShell
+fn quoted(text: &str, escape: &str) -> String {
+    format!("'{}'", text.replace('\'', escape))
+}
+
+fn platform_command(title: &str, body: &str) -> Option<Command> {
+    if cfg!(target_os = "linux") {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=ClawRS", title, body]);
+        Some(command)
+    } else if cfg!(target_os = "macos") {
+        // AppleScript strings are double-quoted
+        let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!(
+            "display notification \"{}\" with title \"{}\"",
+            escape(body),
+            escape(title)
+        );
+        let mut command = Command::new("osascript");
+        command.args(["-e", &script]);
+        Some(command)
+    } else if cfg!(windows) {
+        let script = format!(
+            "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+             $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+             $text = $xml.GetElementsByTagName('text'); \
+             $text.Item(0).AppendChild($xml.CreateTextNode({})) > $null; \
+             $text.Item(1).AppendChild($xml.CreateTextNode({})) > $null; \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('ClawRS').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+            quoted(title, "''"),
+            quoted(body, "''")
+        );
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        Some(command)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hold_keeps_the_latest() {
+        let mut held = VecDeque::new();
+        for i in 0..MAX_HELD + 2 {
+            hold(&mut held, "Digest", &format!("run {i}"));
+        }
+        assert_eq!(held.len(), MAX_HELD);
+        assert_eq!(held.front().map(|(_, body)| body.as_str()), Some("run 2"));
+        assert_eq!(held.back().map(|(_, body)| body.as_str()), Some("run 11"));
+    }
+}
@@ -0,0 +1,335 @@
+//! Quick actions from the command line
+//!
+//! `clawrs ask <prompt>`, `clawrs summarize <file>` and `clawrs title <file>`
+//! print the model's answer on stdout, for launchers (Raycast, Alfred,
+//! PowerToys Run) and scripts. The open window answers with the model it
+//! has loaded; without one, the last used model is loaded headless, or
+//! asked for on the remote server when that backend is selected.
+//! A file of `-`, or no prompt or file with piped input, reads stdin.
+
+use crate::app::pick_gpu_layers;
+use crate::inference::{
+    collect_text, EngineError, GenerationParams, InferenceBackend, LlamaEngine, OpenAiCompatBackend, SharedEngine,
+    StreamToken,
+};
+use crate::storage::dataset::strip_thinking;
+use crate::storage::settings::{load_settings, AppSettings};
+use crate::system::single_instance;
+use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+use serde::{Deserialize, Serialize};
+use std::io::{IsTerminal, Read};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::sync::OnceLock;
+use std::time::Duration;
+use thiserror::Error;
+
+pub const USAGE: &str = "Usage:
+  clawrs ask <prompt>
+  clawrs summarize <file>
+  clawrs title <file>
+
+Use - as the file, or pipe the input, to read stdin.";
+
+/// Longest input sent to the model, in characters
+const MAX_INPUT_CHARS: usize = 48_000;
+const TITLE_MAX_CHARS: usize = 60;
+/// How long a headless run waits for its engine to stop
+const HEADLESS_SHUTDOWN_WAIT: Duration = Duration::from_secs(3);
+
+#[derive(Error, Debug)]
+pub enum QuickActionError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Inference error: {0}")]
+    Engine(#[from] EngineError),
+    #[error("No model to run: load one in ClawRS first")]
+    NoModel,
+    #[error("Generation failed: {0}")]
+    Generation(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum QuickAction {
+    Ask { prompt: String },
+    Summarize { name: String, text: String },
+    Title { name: String, text: String },
+}
+
+/// The open window's answer to a quick action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ActionReply {
+    Done { text: String },
+    Failed { error: String },
+    /// No model loaded there; the launch runs the action itself
+    NoModel,
+}
+
+impl QuickAction {
+    /// The action named by the command line, `None` for a normal launch
+    pub fn from_args(args: &[String]) -> Option<Result<Self, QuickActionError>> {
+        let (command, rest) = args.split_first()?;
+        let parsed = match command.as_str() {
+            "ask" => read_prompt(rest).map(|prompt| Self::Ask { prompt }),
+            "summarize" => read_document(rest).map(|(name, text)| Self::Summarize { name, text }),
+            "title" => read_document(rest).map(|(name, text)| Self::Title { name, text }),
+            _ => return None,
+        };
+        Some(parsed)
+    }
+
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        let prompt = match self {
+            Self::Ask { prompt } => prompt.clone(),
+            Self::Summarize { name, text } => format!(
+                "Résume le document « {} » de façon claire et concise, dans la langue du document. Réponds uniquement avec le résumé.\n\n<document>\n{}\n</document>",
+                name, text
+            ),
+            Self::Title { name, text } => format!(
+                "Propose un titre court ({} caractères maximum) pour le document « {} », dans la langue du document. Réponds uniquement avec le titre.\n\n<document>\n{}\n</document>",
+                TITLE_MAX_CHARS, name, text
+            ),
+        };
+        vec![ChatMessage::new(ChatRole::User, prompt)]
+    }
+
+    pub fn params(&self, settings: &AppSettings) -> GenerationParams {
+        let params = GenerationParams {
+            max_tokens: settings.max_tokens,
+            temperature: settings.temperature,
+            top_k: settings.top_k,
+            top_p: settings.top_p,
+            min_p: settings.min_p,
+            typical_p: settings.typical_p,
+            presence_penalty: settings.presence_penalty,
+            frequency_penalty: settings.frequency_penalty,
+            penalty_last_n: settings.penalty_last_n,
+            max_context_size: settings.context_size,
+            ..GenerationParams::default()
+        };
+        match self {
+            Self::Title { .. } => GenerationParams {
+                max_tokens: 60,
+                temperature: 0.3,
+                ..params
+            },
+            _ => params,
+        }
+    }
+
+    /// The printed answer: reasoning removed, a title on one line
+    pub fn finish(&self, output: &str) -> String {
+        let answer = strip_thinking(output);
+        let answer = answer.trim();
+        match self {
+            Self::Title { .. } => {
+                let line = answer.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+                let title = line.trim().trim_matches(['"', '\'', '*', '#', ' ']);
+                title.chars().take(TITLE_MAX_CHARS).collect()
+            }
+            _ => answer.to_string(),
+        }
+    }
+}
+
+fn read_stdin(what: &str) -> Result<String, QuickActionError> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Err(QuickActionError::Usage(format!("Missing {}", what)));
+    }
+    let mut text = String::new();
+    stdin.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+fn truncate_input(text: &str) -> String {
+    match text.char_indices().nth(MAX_INPUT_CHARS) {
+        Some((end, _)) => format!("{}\n[…]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn read_prompt(args: &[String]) -> Result<String, QuickActionError> {
+    let prompt = match args {
+        [] => read_stdin("prompt")?,
+        [dash] if dash == "-" => read_stdin("prompt")?,
+        words => words.join(" "),
+    };
+    let prompt = prompt.trim();
+    if prompt.is_empty() {
+        return Err(QuickActionError::Usage("Empty prompt".to_string()));
+    }
+    Ok(truncate_input(prompt))
+}
+
+/// (name, text) of the document to work on
+fn read_document(args: &[String]) -> Result<(String, String), QuickActionError> {
+    let (name, bytes) = match args {
+        [] => ("stdin".to_string(), read_stdin("file")?.into_bytes()),
+        [dash] if dash == "-" => ("stdin".to_string(), read_stdin("file")?.into_bytes()),
+        [file] => {
+            let path = Path::new(file);
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| file.clone());
+            (name, std::fs::read(path)?)
+        }
+        _ => return Err(QuickActionError::Usage("One file at a time".to_string())),
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    if text.trim().is_empty() {
+        return Err(QuickActionError::Usage(format!("{} is empty", name)));
+    }
+    Ok((name, truncate_input(text.trim())))
+}
+
+fn generate(
+    engine: &dyn InferenceBackend,
+    action: &QuickAction,
+    settings: &AppSettings,
+) -> Result<Receiver<StreamToken>, QuickActionError> {
+    let (rx, _) = engine.generate_stream_messages(action.messages(), action.params(settings))?;
+    Ok(rx)
+}
+
+fn collect(action: &QuickAction, rx: Receiver<StreamToken>) -> Result<String, QuickActionError> {
+    let output = collect_text(rx).map_err(QuickActionError::Generation)?;
+    Ok(action.finish(&output))
+}
+
+/// Engine of the open window, for actions handed over by other launches
+static ENGINE: OnceLock<SharedEngine> = OnceLock::new();
+
+/// Let other launches run their actions on this window's engine
+pub fn attach_engine(engine: SharedEngine) {
+    let _ = ENGINE.set(engine);
+}
+
+/// Run an action handed over by another launch (blocks; not on the runtime)
+pub fn serve(action: &QuickAction) -> ActionReply {
+    let Some(engine) = ENGINE.get() else {
+        return ActionReply::NoModel;
+    };
+    // The lock is only held to start the generation, like the chat loop
+    let started = {
+        let engine = engine.blocking_lock();
+        if !engine.is_model_loaded() {
+            return ActionReply::NoModel;
+        }
+        generate(&**engine, action, &load_settings())
+    };
+    match started.and_then(|rx| collect(action, rx)) {
+        Ok(text) => ActionReply::Done { text },
+        Err(e) => ActionReply::Failed { error: e.to_string() },
+    }
+}
+
+/// Load the last used model in a private engine and run the action
+fn run_headless(action: &QuickAction) -> Result<String, QuickActionError> {
+    let settings = load_settings();
+    if settings.uses_remote_backend() {
+        let model = settings.last_remote_model.clone().ok_or(QuickActionError::NoModel)?;
+        let backend = OpenAiCompatBackend::new(settings.remote_base_url.clone(), settings.remote_api_key.clone())
+            .with_model(model);
+        return generate(&backend, action, &settings).and_then(|rx| collect(action, rx));
+    }
+    let model = settings
+        .last_model_path
+        .clone()
+        .filter(|path| Path::new(path).is_file())
+        .ok_or(QuickActionError::NoModel)?;
+    let gpu_layers = if settings.gpu_layers_auto {
+        pick_gpu_layers(&model, 0)
+    } else {
+        settings.last_gpu_layers.unwrap_or(settings.gpu_layers)
+    };
+
+    let mut engine = LlamaEngine::new();
+    engine.init()?;
+    engine.load_model(&model, gpu_layers)?;
+    let result = generate(&engine, action, &settings).and_then(|rx| collect(action, rx));
+    engine.shutdown(HEADLESS_SHUTDOWN_WAIT);
+    result
+}
+
+/// Run on the open window if there is one, headless otherwise
+pub fn run(action: &QuickAction) -> Result<String, QuickActionError> {
+    match single_instance::ask_running(action) {
+        Ok(ActionReply::Done { text }) => return Ok(text),
+        Ok(ActionReply::Failed { error }) => return Err(QuickActionError::Generation(error)),
+        Ok(ActionReply::NoModel) => tracing::info!("The open window has no model loaded, running headless"),
+        Err(e) => tracing::debug!("No running instance answered ({}), running headless", e),
+    }
+    run_headless(action)
+}
+
+/// Print the answer (or the error) and return the process exit code
+pub fn run_cli(parsed: Result<QuickAction, QuickActionError>) -> i32 {
+    match parsed.and_then(|action| run(&action)) {
+        Ok(text) => {
+            println!("{}", text);
+            0
+        }
+        Err(QuickActionError::Usage(message)) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            2
+        }
+        Err(e) => {
+            eprintln!("clawrs: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert!(QuickAction::from_args(&args(&["notes.md"])).is_none());
+        assert!(QuickAction::from_args(&[]).is_none());
+
+        let ask = QuickAction::from_args(&args(&["ask", "what", "is", "GGUF?"])).unwrap().unwrap();
+        assert_eq!(ask, QuickAction::Ask { prompt: "what is GGUF?".to_string() });
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        std::fs::write(&file, "\n# Release notes\n").unwrap();
+        let title = QuickAction::from_args(&args(&["title", &file.display().to_string()]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            title,
+            QuickAction::Title {
+                name: "notes.md".to_string(),
+                text: "# Release notes".to_string()
+            }
+        );
+        let too_many = QuickAction::from_args(&args(&["summarize", "a.md", "b.md"])).unwrap();
+        assert!(matches!(too_many, Err(QuickActionError::Usage(_))));
+    }
+
+    #[test]
+    fn test_finish() {
+        let title = QuickAction::Title {
+            name: "notes.md".to_string(),
+            text: String::new(),
+        };
+        assert_eq!(
+            title.finish("<think>short and clear</think>\n\n\"Release notes 2.0\"\nExtra line"),
+            "Release notes 2.0"
+        );
+        let ask = QuickAction::Ask { prompt: String::new() };
+        assert_eq!(ask.finish("<think>hm</think> Line one\nLine two \n"), "Line one\nLine two");
+    }
+}
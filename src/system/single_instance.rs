@@ -0,0 +1,431 @@
+//! Single running instance
+//!
+//! The first instance listens on a localhost port recorded in
+//! `instance.json` in the data directory. A second launch hands its command
+//! line to that port and exits, and the open window comes to the front with
+//! the forwarded prompt or files in the chat input, waiting to be sent. A stale file (crashed instance, port taken
+//! by something else) fails the handshake and the new launch takes over.
+//!
+//! Deep links (`clawrs://new?prompt=...`, `clawrs://conversation/<id>`) are
+//! launch arguments like any other, so they reach the open window the same
+//! way once the scheme is registered (see `system::file_associations`).
+//! Quick actions (`clawrs ask ...`) are answered on the same connection.
+
+use crate::storage::attachments::text_attachment;
+use crate::system::quick_actions::{self, ActionReply, QuickAction};
+use crate::storage::{get_base_data_dir, StorageError};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use thiserror::Error;
+
+const INSTANCE_FILE: &str = "instance.json";
+/// Held while a launch decides whether it is the running instance, so two
+/// launches at once don't both start
+const LOCK_FILE: &str = "instance.lock";
+/// How long a second launch waits for the running instance to answer
+const HANDOFF_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long a quick action waits for the running instance's answer
+const ACTION_TIMEOUT: Duration = Duration::from_secs(600);
+/// Largest handoff accepted, to ignore junk sent to the port
+const MAX_HANDOFF_BYTES: u64 = 1024 * 1024;
+const ACK: &str = "ok";
+/// Custom URL scheme for links from editors, launchers and scripts
+pub const URL_SCHEME: &str = "clawrs";
+
+#[derive(Error, Debug)]
+pub enum InstanceError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid instance data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("The running instance did not accept the handoff")]
+    Rejected,
+}
+
+/// What a launch asks for, from its command line
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchRequest {
+    /// Text put in the chat input of a new conversation (never sent on its
+    /// own: links can come from any page)
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Existing files named on the command line
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+    /// Conversation to open, from `clawrs://conversation/<id>`
+    #[serde(default)]
+    pub conversation: Option<String>,
+    /// `clawrs://new` without a prompt: open an empty conversation
+    #[serde(default)]
+    pub new_conversation: bool,
+}
+
+impl LaunchRequest {
+    /// Arguments naming an existing file (as a path or a `file://` URI) are
+    /// files, `clawrs://` links are applied; the rest form the prompt
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut request = Self::default();
+        let mut words = Vec::new();
+        let mut files = Vec::new();
+        for arg in args {
+            // File managers launching `%U` pass files as `file://` URIs
+            let local = file_uri_path(&arg);
+            let path = local.as_deref().unwrap_or(Path::new(&arg));
+            if is_link(&arg) {
+                if !request.apply_link(&arg, &mut words) {
+                    tracing::warn!("Ignored unknown link: {}", arg);
+                }
+            } else if path.is_file() {
+                files.push(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+            } else if !arg.trim().is_empty() {
+                words.push(arg);
+            }
+        }
+        request.prompt = Some(words.join(" ")).filter(|p| !p.is_empty());
+        request.files = files;
+        request
+    }
+
+    /// Apply `clawrs://new[?prompt=...]` or `clawrs://conversation/<id>`;
+    /// `false` for a link this version doesn't know
+    fn apply_link(&mut self, link: &str, words: &mut Vec<String>) -> bool {
+        let Ok(url) = reqwest::Url::parse(link) else {
+            return false;
+        };
+        let segments: Vec<String> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default();
+        match (url.host_str(), segments.as_slice()) {
+            (Some("new"), []) => {
+                let prompt = url
+                    .query_pairs()
+                    .find(|(key, _)| key == "prompt")
+                    .map(|(_, value)| value.trim().to_string())
+                    .filter(|value| !value.is_empty());
+                match prompt {
+                    Some(prompt) => words.push(prompt),
+                    None => self.new_conversation = true,
+                }
+                true
+            }
+            (Some("conversation"), [id]) if is_conversation_id(id) => {
+                self.conversation = Some(id.clone());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prompt.is_none()
+            && self.files.is_empty()
+            && self.conversation.is_none()
+            && !self.new_conversation
+    }
+
+    /// `.gguf` files, loaded as the chat model
+    pub fn models(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().filter(|file| is_model(file))
+    }
+
+    /// Message for the chat input, with text files inlined and other files
+    /// by path; `None` when there is nothing to send
+    pub fn to_prompt(&self, is_en: bool) -> Option<String> {
+        let documents: Vec<&PathBuf> = self.files.iter().filter(|file| !is_model(file)).collect();
+        let mut parts = Vec::new();
+        match &self.prompt {
+            Some(prompt) => parts.push(prompt.clone()),
+            None if documents.is_empty() => return None,
+            None if is_en => parts.push("Here are these files:".to_string()),
+            None => parts.push("Voici ces fichiers :".to_string()),
+        }
+        for document in documents {
+            parts.push(text_attachment(document).unwrap_or_else(|| document.display().to_string()));
+        }
+        Some(parts.join("\n\n"))
+    }
+}
+
+fn is_link(arg: &str) -> bool {
+    arg.get(..URL_SCHEME.len() + 1)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}:", URL_SCHEME)))
+}
+
+/// Local path of a `file://` URI, percent-decoded
+fn file_uri_path(arg: &str) -> Option<PathBuf> {
+    if !arg.get(..7).is_some_and(|prefix| prefix.eq_ignore_ascii_case("file://")) {
+        return None;
+    }
+    reqwest::Url::parse(arg).ok()?.to_file_path().ok()
+}
+
+/// Conversation ids are UUIDs; anything else could name a path
+fn is_conversation_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn is_model(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gguf"))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InstanceInfo {
+    port: u16,
+    /// Shared secret, so only someone who can read the data dir can hand off
+    token: String,
+    pid: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Handoff {
+    token: String,
+    #[serde(default)]
+    request: LaunchRequest,
+    #[serde(default)]
+    action: Option<QuickAction>,
+}
+
+/// Requests waiting for the UI, including this launch's own
+static REQUESTS: Lazy<Mutex<Vec<LaunchRequest>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Become the running instance, or hand `request` to the one already running
+///
+/// Returns `false` when another instance took the request and this process
+/// should exit.
+pub fn acquire(request: LaunchRequest) -> Result<bool, InstanceError> {
+    acquire_at(&get_base_data_dir()?.join(INSTANCE_FILE), request)
+}
+
+fn acquire_at(path: &Path, request: LaunchRequest) -> Result<bool, InstanceError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Released when this returns (or the process dies): the next launch then
+    // finds the instance file written
+    let lock = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_file_name(LOCK_FILE))?;
+    lock.lock()?;
+
+    match forward(path, &request) {
+        Ok(()) => return Ok(false),
+        Err(e) if path.exists() => tracing::info!("No running instance answered ({}), starting", e),
+        Err(_) => {}
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let info = InstanceInfo {
+        port: listener.local_addr()?.port(),
+        token: uuid::Uuid::new_v4().to_string(),
+        pid: std::process::id(),
+    };
+    write_private(path, &serde_json::to_string(&info)?)?;
+    if !request.is_empty() {
+        REQUESTS.lock().unwrap_or_else(|e| e.into_inner()).push(request);
+    }
+
+    let token = info.token;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A quick action holds its connection until the answer is ready
+            let token = token.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = receive(stream, &token) {
+                    tracing::warn!("Ignored a handoff from another launch: {}", e);
+                }
+            });
+        }
+    });
+    Ok(true)
+}
+
+/// Write `contents` readable by the user only: the file holds the token
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    // A file left by an older version may be readable by others
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+/// Send a handoff to the instance recorded in `path`; its answer is the
+/// next line on the returned stream
+fn send(
+    path: &Path,
+    request: LaunchRequest,
+    action: Option<QuickAction>,
+) -> Result<BufReader<TcpStream>, InstanceError> {
+    let info: InstanceInfo = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let address = SocketAddr::from(([127, 0, 0, 1], info.port));
+    let mut stream = TcpStream::connect_timeout(&address, HANDOFF_TIMEOUT)?;
+    stream.set_read_timeout(Some(HANDOFF_TIMEOUT))?;
+    let handoff = Handoff {
+        token: info.token,
+        request,
+        action,
+    };
+    stream.write_all(format!("{}\n", serde_json::to_string(&handoff)?).as_bytes())?;
+    Ok(BufReader::new(stream))
+}
+
+/// Send `request` to the instance recorded in `path` and wait for its answer
+fn forward(path: &Path, request: &LaunchRequest) -> Result<(), InstanceError> {
+    let mut answer = String::new();
+    send(path, request.clone(), None)?.read_line(&mut answer)?;
+    if answer.trim() == ACK {
+        Ok(())
+    } else {
+        Err(InstanceError::Rejected)
+    }
+}
+
+/// Run `action` on the running instance's model
+pub fn ask_running(action: &QuickAction) -> Result<ActionReply, InstanceError> {
+    let path = get_base_data_dir()?.join(INSTANCE_FILE);
+    let mut reader = send(&path, LaunchRequest::default(), Some(action.clone()))?;
+    reader.get_ref().set_read_timeout(Some(ACTION_TIMEOUT))?;
+    let mut answer = String::new();
+    reader.read_line(&mut answer)?;
+    Ok(serde_json::from_str(&answer)?)
+}
+
+fn receive(stream: TcpStream, token: &str) -> Result<(), InstanceError> {
+    stream.set_read_timeout(Some(HANDOFF_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_HANDOFF_BYTES)).read_line(&mut line)?;
+    let handoff: Handoff = serde_json::from_str(&line)?;
+    if handoff.token != token {
+        return Err(InstanceError::Rejected);
+    }
+    if let Some(action) = handoff.action {
+        tracing::info!("Running a quick action for another launch");
+        let reply = quick_actions::serve(&action);
+        (&stream).write_all(format!("{}\n", serde_json::to_string(&reply)?).as_bytes())?;
+        return Ok(());
+    }
+    tracing::info!("Another launch handed over to this window");
+    REQUESTS.lock().unwrap_or_else(|e| e.into_inner()).push(handoff.request);
+    (&stream).write_all(format!("{}\n", ACK).as_bytes())?;
+    Ok(())
+}
+
+/// Requests received since the last call, oldest first
+pub fn take_requests() -> Vec<LaunchRequest> {
+    std::mem::take(&mut *REQUESTS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Remove the instance file on exit, if it is still this process's
+pub fn release() {
+    let Ok(path) = get_base_data_dir().map(|dir| dir.join(INSTANCE_FILE)) else {
+        return;
+    };
+    let ours = fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<InstanceInfo>(&text).ok())
+        .is_some_and(|info| info.pid == std::process::id());
+    if ours {
+        let _ = fs::remove_file(&path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_request_from_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        fs::write(&file, "x").unwrap();
+        let request = LaunchRequest::from_args(vec![
+            "summarize".to_string(),
+            file.display().to_string(),
+            "please".to_string(),
+        ]);
+        assert_eq!(request.prompt.as_deref(), Some("summarize please"));
+        assert_eq!(request.files, vec![file.canonicalize().unwrap()]);
+
+        // "Open with" from a file manager passes a URI
+        let spaced = dir.path().join("my notes.md");
+        fs::write(&spaced, "x").unwrap();
+        let uri = reqwest::Url::from_file_path(&spaced).unwrap().to_string();
+        let request = LaunchRequest::from_args(vec![uri]);
+        assert!(request.prompt.is_none());
+        assert_eq!(request.files, vec![spaced.canonicalize().unwrap()]);
+
+        let opened = LaunchRequest {
+            files: vec![file.clone(), PathBuf::from("/models/qwen.GGUF"), PathBuf::from("/tmp/a.pdf")],
+            ..Default::default()
+        };
+        let prompt = opened.to_prompt(true).unwrap();
+        assert!(prompt.starts_with("Here are these files:\n\n📎 notes.md"));
+        assert!(prompt.ends_with("\n```\n\n/tmp/a.pdf"));
+        assert_eq!(opened.models().collect::<Vec<_>>(), vec![&PathBuf::from("/models/qwen.GGUF")]);
+
+        let model_only = LaunchRequest {
+            files: vec![PathBuf::from("/models/qwen.gguf")],
+            ..Default::default()
+        };
+        assert!(model_only.to_prompt(true).is_none());
+    }
+
+    #[test]
+    fn test_deep_links() {
+        let args = |args: &[&str]| LaunchRequest::from_args(args.iter().map(|a| a.to_string()));
+
+        let request = args(&["clawrs://new?prompt=Explain%20this&lang=en"]);
+        assert_eq!(request.prompt.as_deref(), Some("Explain this"));
+        assert!(!request.new_conversation);
+        assert!(args(&["CLAWRS://new"]).new_conversation);
+
+        let id = "0b5c6c8e-6a43-4a36-9d0b-1f6bd8c1f0a2";
+        let request = args(&[&format!("clawrs://conversation/{}", id)]);
+        assert_eq!(request.conversation.as_deref(), Some(id));
+
+        assert!(args(&["clawrs://conversation/..%2F..%2Fsettings"]).is_empty());
+        assert!(args(&["clawrs://settings"]).is_empty());
+    }
+
+    #[test]
+    fn test_second_launch_hands_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(INSTANCE_FILE);
+        // A stale file from a crashed instance doesn't block the launch
+        fs::write(&path, r#"{"port":1,"token":"old","pid":0}"#).unwrap();
+        assert!(acquire_at(&path, LaunchRequest::default()).unwrap());
+
+        let request = LaunchRequest {
+            prompt: Some("hello".to_string()),
+            ..Default::default()
+        };
+        assert!(!acquire_at(&path, request.clone()).unwrap());
+        assert_eq!(take_requests(), vec![request]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Update checks against GitHub releases (opt-in)
+//!
+//! When enabled in settings, the latest release is looked up at launch and
+//! shown with its changelog. Installing downloads the installer for this
+//! platform (`.msi`/`.exe`, `.dmg` or `.AppImage`) to `{data_dir}/updates/`,
+//! checks its Ed25519 signature (the `<asset>.sig` file of the release,
+//! base64) against the key built into official builds, then hands it to the
+//! OS. A build without the key can check and show changelogs but not install.
+
+use crate::storage::{get_data_dir, StorageError};
+use crate::system::proxy::{client_builder, ProxyClient};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/0xZKnw/clawRS/releases/latest";
+const USER_AGENT: &str = concat!("clawRS/", env!("CARGO_PKG_VERSION"));
+/// Base64 Ed25519 public key the release assets are signed with, set by the
+/// release build
+const RELEASE_KEY: Option<&str> = option_env!("CLAWRS_RELEASE_KEY");
+
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("GitHub answered {0}")]
+    Status(reqwest::StatusCode),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("No installer for this platform in release {0}")]
+    NoAsset(String),
+    #[error("Release {0} has no signature for its installer")]
+    NoSignature(String),
+    #[error("This build has no release key and cannot verify updates")]
+    NoReleaseKey,
+    #[error("Invalid signature: the download was discarded")]
+    BadSignature,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a release
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(rename = "browser_download_url")]
+    pub url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// A release newer than the running version
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseInfo {
+    /// Version without the leading `v`, e.g. "0.3.0"
+    pub version: String,
+    pub name: String,
+    /// Release notes (Markdown)
+    pub changelog: String,
+    /// Release page, for builds that can't install
+    pub page_url: String,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Installer for this platform and its signature
+    pub installer: Option<ReleaseAsset>,
+    pub signature: Option<ReleaseAsset>,
+}
+
+impl ReleaseInfo {
+    /// Whether this build can download and install the release itself
+    pub fn installable(&self) -> bool {
+        RELEASE_KEY.is_some() && self.installer.is_some() && self.signature.is_some()
+    }
+}
+
+/// `major.minor.patch` of "v0.3.1", "0.3" or "0.3.1-beta" (suffix ignored)
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().trim_start_matches('v').split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+/// Whether `latest` is a newer version than `current`
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    matches!((parse_version(latest), parse_version(current)), (Some(l), Some(c)) if l > c)
+}
+
+/// File name endings of the installers for this platform, preferred first
+fn installer_suffixes() -> &'static [&'static str] {
+    if cfg!(target_os = "windows") {
+        &[".msi", ".exe"]
+    } else if cfg!(target_os = "macos") {
+        &[".dmg"]
+    } else {
+        &[".appimage"]
+    }
+}
+
+/// Installer among `assets` for a platform with `suffixes`, and its signature
+fn pick_installer(
+    assets: &[ReleaseAsset],
+    suffixes: &[&str],
+) -> (Option<ReleaseAsset>, Option<ReleaseAsset>) {
+    let installer = suffixes.iter().find_map(|suffix| {
+        assets
+            .iter()
+            .find(|asset| asset.name.to_lowercase().ends_with(suffix))
+            .cloned()
+    });
+    let signature = installer.as_ref().and_then(|installer| {
+        let name = format!("{}.sig", installer.name);
+        assets.iter().find(|asset| asset.name == name).cloned()
+    });
+    (installer, signature)
+}
+
+/// Latest release, when it is newer than this build
+pub async fn check_for_update() -> Result<Option<ReleaseInfo>, UpdateError> {
+    let client = client_builder(ProxyClient::Updates)
+        .timeout(Duration::from_secs(20))
+        .build()?;
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(UpdateError::Status(response.status()));
+    }
+    let release: GithubRelease = response.json().await?;
+    let current = env!("CARGO_PKG_VERSION");
+    if !is_newer(&release.tag_name, current) {
+        tracing::info!("No update: latest release {} (running {})", release.tag_name, current);
+        return Ok(None);
+    }
+    let (installer, signature) = pick_installer(&release.assets, installer_suffixes());
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    tracing::info!("Update available: {} (running {})", version, current);
+    Ok(Some(ReleaseInfo {
+        name: release.name.filter(|name| !name.trim().is_empty()).unwrap_or_else(|| version.clone()),
+        version,
+        changelog: release.body.unwrap_or_default(),
+        page_url: release.html_url,
+        published_at: release.published_at,
+        installer,
+        signature,
+    }))
+}
+
+/// Check `data` against a base64 Ed25519 `signature` and public `key`
+fn verify_signature(data: &[u8], signature: &str, key: &str) -> Result<(), UpdateError> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let signature = engine.decode(signature.trim()).map_err(|_| UpdateError::BadSignature)?;
+    let key = engine.decode(key.trim()).map_err(|_| UpdateError::NoReleaseKey)?;
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, key)
+        .verify(data, &signature)
+        .map_err(|_| UpdateError::BadSignature)
+}
+
+/// Download the release installer, verify it and return its path;
+/// `progress` gets the bytes downloaded and the total
+pub async fn download_update(
+    release: &ReleaseInfo,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<PathBuf, UpdateError> {
+    let key = RELEASE_KEY.ok_or(UpdateError::NoReleaseKey)?;
+    let installer = release
+        .installer
+        .as_ref()
+        .ok_or_else(|| UpdateError::NoAsset(release.version.clone()))?;
+    let signature_asset = release
+        .signature
+        .as_ref()
+        .ok_or_else(|| UpdateError::NoSignature(release.version.clone()))?;
+
+    let client = client_builder(ProxyClient::Updates)
+        .timeout(Duration::from_secs(1800))
+        .build()?;
+    let signature = client
+        .get(&signature_asset.url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let dir = get_data_dir()?.join("updates");
+    tokio::fs::create_dir_all(&dir).await?;
+    // Asset names come from the release, not from the user
+    let file_name = Path::new(&installer.name)
+        .file_name()
+        .ok_or_else(|| UpdateError::NoAsset(release.version.clone()))?;
+    let path = dir.join(file_name);
+
+    tracing::info!("Downloading update {} from {}", release.version, installer.url);
+    let mut response = client
+        .get(&installer.url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?;
+    let total = response.content_length().unwrap_or(installer.size);
+    let mut data = Vec::with_capacity(total as usize);
+    while let Some(chunk) = response.chunk().await? {
+        data.extend_from_slice(&chunk);
+        progress(data.len() as u64, total);
+    }
+
+    verify_signature(&data, &signature, key)?;
+    let mut file = tokio::fs::File::create(&path).await?;
+    file.write_all(&data).await?;
+    file.flush().await?;
+    tracing::info!("Update {} verified and saved to {}", release.version, path.display());
+    Ok(path)
+}
+
+/// Make a downloaded installer runnable (AppImages need the executable bit)
+pub fn prepare_installer(path: &Path) -> Result<(), UpdateError> {
+    #[cfg(unix)]
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("appimage")) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Start the installer: `msiexec` for an `.msi`, `open` for a `.dmg`, the
+/// file itself for an `.exe` or an AppImage
+pub fn launch_installer(path: &Path) -> Result<(), UpdateError> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut command = match extension.as_str() {
+        "msi" => {
+            let mut command = std::process::Command::new("msiexec");
+            command.arg("/i").arg(path);
+            command
+        }
+        "dmg" => {
+            let mut command = std::process::Command::new("open");
+            command.arg(path);
+            command
+        }
+        _ => std::process::Command::new(path),
+    };
+    command.spawn()?;
+    tracing::info!("Started installer {}", path.display());
+    Ok(())
+}
+
+/// Open the release page in the browser
+pub fn open_release_page(url: &str) -> std::io::Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program).arg(url).spawn().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::KeyPair;
+
+    #[test]
+    fn test_versions_and_assets() {
+        assert!(is_newer("v0.3.0", "0.2.9"));
+        assert!(is_newer("1.0", "0.9.12"));
+        assert!(!is_newer("v0.2.0", "0.2.0"));
+        assert!(!is_newer("nightly", "0.2.0"));
+        assert_eq!(parse_version("0.3.1-beta.2"), Some((0, 3, 1)));
+
+        let asset = |name: &str| ReleaseAsset { name: name.to_string(), url: format!("https://x/{}", name), size: 0 };
+        let assets = [asset("ClawRS-0.3.0.exe"), asset("ClawRS-0.3.0.msi"), asset("ClawRS-0.3.0.msi.sig"), asset("ClawRS-0.3.0.AppImage")];
+        let (installer, signature) = pick_installer(&assets, &[".msi", ".exe"]);
+        assert_eq!(installer.unwrap().name, "ClawRS-0.3.0.msi");
+        assert_eq!(signature.unwrap().name, "ClawRS-0.3.0.msi.sig");
+        let (installer, signature) = pick_installer(&assets, &[".appimage"]);
+        assert_eq!(installer.unwrap().name, "ClawRS-0.3.0.AppImage");
+        assert!(signature.is_none());
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let engine = base64::engine::general_purpose::STANDARD;
+        let key = engine.encode(pair.public_key().as_ref());
+        let signature = engine.encode(pair.sign(b"installer").as_ref());
+
+        assert!(verify_signature(b"installer", &signature, &key).is_ok());
+        assert!(matches!(verify_signature(b"tampered", &signature, &key), Err(UpdateError::BadSignature)));
+    }
+}
@@ -24,6 +24,83 @@ pub struct Message {
     pub content: String,
     /// Timestamp when the message was created
     pub timestamp: u64,
+    /// Model and sampling settings that produced an assistant reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<GenerationMeta>,
+}
+
+/// Why a reply stopped before the model ended it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// The max_tokens setting was reached
+    MaxTokens,
+    /// The context window was full
+    Context,
+}
+
+/// One generated token with its log-probability and the likeliest
+/// alternatives, recorded in debug mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    pub text: String,
+    pub logprob: f32,
+    /// (text, logprob), most likely first
+    #[serde(default)]
+    pub top: Vec<(String, f32)>,
+}
+
+impl TokenLogprob {
+    pub fn probability(&self) -> f32 {
+        self.logprob.exp()
+    }
+}
+
+/// Provenance of a generated message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationMeta {
+    /// Model file name
+    pub model: String,
+    pub temperature: f32,
+    pub top_p: f32,
+    pub top_k: u32,
+    pub max_tokens: u32,
+    pub context_size: u32,
+    /// Time from request to the last token
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Routing rule that picked the model or persona
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<String>,
+    /// Set when the reply was cut off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<StopReason>,
+    /// Per-token probabilities, when recorded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logprobs: Vec<TokenLogprob>,
+}
+
+impl GenerationMeta {
+    /// One-line description of the settings, e.g. for a tooltip
+    pub fn describe(&self, is_en: bool) -> String {
+        let route = match &self.route {
+            Some(rule) if is_en => format!(" · routed by \"{}\"", rule),
+            Some(rule) => format!(" · route \"{}\"", rule),
+            None => String::new(),
+        };
+        format!(
+            "{} · temp {:.2} · top-p {:.2} · top-k {} · max {} {} · ctx {}K · {:.1}s{}",
+            self.model,
+            self.temperature,
+            self.top_p,
+            self.top_k,
+            self.max_tokens,
+            if is_en { "tokens" } else { "jetons" },
+            self.context_size / 1024,
+            self.duration_ms as f64 / 1000.0,
+            route,
+        )
+    }
 }
 
 impl Message {
@@ -32,14 +109,20 @@ impl Message {
         Self {
             role,
             content: content.into(),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0),
+            timestamp: unix_now(),
+            generation: None,
         }
     }
 }
 
+/// Current time in seconds since the Unix epoch
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 /// Clean thinking tags from content for display
 /// This is a safety measure to prevent thinking from appearing to users
 pub fn clean_thinking_tags(content: &str) -> String {
@@ -83,6 +166,32 @@ mod tests {
         assert!(msg.timestamp > 0);
     }
 
+    #[test]
+    fn test_generation_meta() {
+        // Messages saved before generation metadata existed still load
+        let old = r#"{"role":"Assistant","content":"Hi","timestamp":1}"#;
+        let msg: Message = serde_json::from_str(old).unwrap();
+        assert_eq!(msg.generation, None);
+        assert!(!serde_json::to_string(&msg).unwrap().contains("generation"));
+
+        let meta = GenerationMeta {
+            model: "qwen3-8b.gguf".to_string(),
+            temperature: 0.7,
+            top_p: 0.95,
+            top_k: 40,
+            max_tokens: 4096,
+            context_size: 16384,
+            duration_ms: 12_340,
+            route: None,
+            stop: None,
+            logprobs: Vec::new(),
+        };
+        assert_eq!(
+            meta.describe(true),
+            "qwen3-8b.gguf · temp 0.70 · top-p 0.95 · top-k 40 · max 4096 tokens · ctx 16K · 12.3s"
+        );
+    }
+
     #[test]
     fn test_role_equality() {
         assert_eq!(Role::User, Role::User);
@@ -0,0 +1,313 @@
+//! Context budget check before sending
+//!
+//! The prompt the message would produce (same estimate as the context bar)
+//! plus the room for the reply is compared with the usable context: the
+//! context size, capped by what the loaded model was trained on. When it
+//! doesn't fit the send is held and a dialog offers ways out, instead of
+//! letting the engine cut the end of the prompt.
+
+use crate::app::{AppState, ModelState};
+use crate::inference::model::read_context_length;
+use crate::inference::InferenceBackend;
+use crate::storage::attachments::{has_text_attachments, trim_text_attachments};
+use crate::storage::models::{scan_models_for_role, ModelRole};
+use crate::storage::settings::{save_settings, CONTEXT_SIZES};
+use crate::ui::chat::compact_history;
+use crate::ui::chat::context_bar::{agent_prompts, estimate_text_tokens, pinned_budget, reply_reserve, ContextUsage};
+use crate::ui::chat::input::parse_long_command;
+use crate::ui::chat::message::{Message, MessageRole};
+use dioxus::prelude::*;
+
+/// Room always kept for the reply, even when max_tokens is automatic
+pub const MIN_REPLY_RESERVE: usize = 512;
+
+/// A message that would not fit in the context
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetOverflow {
+    /// Estimated prompt, the message included
+    pub prompt_tokens: usize,
+    pub reply_reserve: usize,
+    /// Usable context
+    pub capacity: usize,
+    /// Context the loaded model was trained on, when known
+    pub model_max: Option<u32>,
+}
+
+impl BudgetOverflow {
+    /// `Some` when the prompt and the reply reserve exceed `capacity`
+    pub fn check(prompt_tokens: usize, reply_reserve: usize, capacity: usize, model_max: Option<u32>) -> Option<Self> {
+        (prompt_tokens + reply_reserve > capacity).then_some(Self {
+            prompt_tokens,
+            reply_reserve,
+            capacity,
+            model_max,
+        })
+    }
+
+    pub fn needed(&self) -> usize {
+        self.prompt_tokens + self.reply_reserve
+    }
+
+    pub fn excess(&self) -> usize {
+        self.needed().saturating_sub(self.capacity)
+    }
+
+    /// Smallest context size that fits, within what the model was trained on
+    pub fn fitting_context_size(&self) -> Option<u32> {
+        CONTEXT_SIZES
+            .iter()
+            .copied()
+            .find(|&size| size as usize >= self.needed() && self.model_max.is_none_or(|max| size <= max))
+    }
+}
+
+/// Overflow the draft would cause if sent now; `None` when it fits or no
+/// model is loaded
+pub fn check_send(app_state: &AppState, draft: &str) -> Option<BudgetOverflow> {
+    if !matches!(*app_state.model_state.peek(), ModelState::Loaded(_)) {
+        return None;
+    }
+    let (message, long_tokens) = parse_long_command(draft);
+    let reserve = long_tokens
+        .unwrap_or_else(|| reply_reserve(app_state))
+        .max(MIN_REPLY_RESERVE as u32) as usize;
+    let context_size = app_state.settings.peek().context_size;
+    let model_max = app_state
+        .engine
+        .try_lock()
+        .ok()
+        .and_then(|engine| engine.model_info().map(|info| info.context_length))
+        .filter(|&length| length > 0);
+    let capacity = model_max.map_or(context_size, |max| max.min(context_size)) as usize;
+    let (base, full) = agent_prompts(app_state);
+    let usage = ContextUsage::estimate(
+        &base,
+        &full,
+        pinned_budget(app_state),
+        &app_state.active_messages.peek(),
+        &message,
+        reserve,
+        capacity,
+    );
+    BudgetOverflow::check(usage.prompt_tokens(), reserve, capacity, model_max)
+}
+
+/// Held send: compact, trim attachments, raise the context size or switch model
+#[component]
+pub fn BudgetDialog(
+    overflow: BudgetOverflow,
+    draft: String,
+    on_close: EventHandler<()>,
+    on_retry: EventHandler<String>,
+) -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, context_size, gpu_layers, loaded) = {
+        let settings = app_state.settings.read();
+        let loaded = match &*app_state.model_state.read() {
+            ModelState::Loaded(path) => Some(path.clone()),
+            _ => None,
+        };
+        (settings.language == "en", settings.context_size, settings.gpu_layers, loaded)
+    };
+    let needed = overflow.needed();
+    let history_tokens: usize = app_state
+        .active_messages
+        .read()
+        .iter()
+        .map(|m| estimate_text_tokens(&m.content))
+        .sum();
+    let can_compact = history_tokens > 0;
+    let can_trim = has_text_attachments(&draft);
+    let raise_to = overflow.fitting_context_size().filter(|&size| size > context_size);
+
+    // Other chat models trained on enough context: (path, name, context)
+    let settings_signal = app_state.settings;
+    let mut switching = use_signal(|| None::<String>);
+    let models = use_resource(move || {
+        let settings = settings_signal.peek().clone();
+        let loaded = loaded.clone();
+        async move {
+            tokio::task::spawn_blocking(move || {
+                scan_models_for_role(&settings, ModelRole::Chat)
+                    .into_iter()
+                    .filter(|m| loaded.as_deref() != Some(&*m.path.to_string_lossy()))
+                    .filter_map(|m| {
+                        let length = read_context_length(&m.path)?;
+                        (length as usize >= needed).then(|| {
+                            (m.path.to_string_lossy().to_string(), m.display_name().to_string(), length)
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default()
+        }
+    });
+    let models = models.read().clone().unwrap_or_default();
+
+    let app_state_compact = app_state.clone();
+    let app_state_raise = app_state.clone();
+    let draft_compact = draft.clone();
+    let draft_trim = draft.clone();
+    let draft_raise = draft.clone();
+    let option_class = "w-full text-left p-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] hover:bg-white/[0.06] transition-colors";
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/60 backdrop-blur-2xl z-50 flex items-center justify-center p-4",
+            div {
+                class: "w-full max-w-lg glass-strong rounded-2xl overflow-hidden animate-scale-in",
+
+                div { class: "p-6 border-b border-[var(--border-subtle)]",
+                    h2 { class: "text-lg font-semibold text-[var(--text-primary)] mb-2",
+                        if is_en { "This message doesn't fit" } else { "Ce message ne tient pas" }
+                    }
+                    p { class: "text-sm text-[var(--text-secondary)]",
+                        if is_en {
+                            "The prompt needs about {overflow.prompt_tokens} tokens plus {overflow.reply_reserve} for the reply, but only {overflow.capacity} fit in the context. Sent as is, its end would be cut."
+                        } else {
+                            "Le prompt demande environ {overflow.prompt_tokens} tokens plus {overflow.reply_reserve} pour la reponse, mais le contexte n'en contient que {overflow.capacity}. Envoye tel quel, sa fin serait coupee."
+                        }
+                    }
+                }
+
+                div { class: "p-6 space-y-2",
+                    if can_compact {
+                        button {
+                            class: option_class,
+                            onclick: move |_| {
+                                let mut messages = app_state_compact.active_messages;
+                                compact_history(&mut messages.write());
+                                messages.write().push(Message::new(
+                                    MessageRole::System,
+                                    if is_en { "💾 Context compressed before sending." } else { "💾 Contexte compresse avant l'envoi." },
+                                ));
+                                on_retry.call(draft_compact.clone());
+                            },
+                            div { class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "Compact now" } else { "Compacter maintenant" }
+                            }
+                            div { class: "text-xs text-[var(--text-tertiary)]",
+                                if is_en { "Shorten long messages and keep only the latest ones" } else { "Raccourcir les longs messages et ne garder que les derniers" }
+                            }
+                        }
+                    }
+                    if can_trim {
+                        button {
+                            class: option_class,
+                            onclick: move |_| {
+                                // Tokens are ~4 bytes; a little more keeps the estimate under
+                                let remove = (overflow.excess() + 64) * 4;
+                                on_retry.call(trim_text_attachments(&draft_trim, remove));
+                            },
+                            div { class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "Trim attachments" } else { "Raccourcir les pieces jointes" }
+                            }
+                            div { class: "text-xs text-[var(--text-tertiary)]",
+                                if is_en { "Cut the end of the attached files until the message fits" } else { "Couper la fin des fichiers joints jusqu'a ce que le message tienne" }
+                            }
+                        }
+                    }
+                    if let Some(size) = raise_to {
+                        button {
+                            class: option_class,
+                            onclick: move |_| {
+                                {
+                                    let mut settings = app_state_raise.settings;
+                                    let mut settings = settings.write();
+                                    settings.context_size = size;
+                                    if let Err(e) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", e);
+                                    }
+                                }
+                                on_retry.call(draft_raise.clone());
+                            },
+                            div { class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "Raise context size to {size / 1024}K" } else { "Passer le contexte a {size / 1024}K" }
+                            }
+                            div { class: "text-xs text-[var(--text-tertiary)]",
+                                if is_en { "Uses more memory; generation may slow down" } else { "Utilise plus de memoire ; la generation peut ralentir" }
+                            }
+                        }
+                    }
+                    for (path, name, length) in models {
+                        {
+                            let app_state = app_state.clone();
+                            let draft = draft.clone();
+                            let size = CONTEXT_SIZES
+                                .iter()
+                                .copied()
+                                .find(|&size| size as usize >= needed && size <= length)
+                                .unwrap_or(context_size)
+                                .max(context_size);
+                            rsx! {
+                                button {
+                                    key: "{path}",
+                                    class: option_class,
+                                    disabled: switching().is_some(),
+                                    onclick: move |_| {
+                                        let app_state = app_state.clone();
+                                        let path = path.clone();
+                                        let draft = draft.clone();
+                                        {
+                                            let mut settings = app_state.settings;
+                                            let mut settings = settings.write();
+                                            settings.context_size = size;
+                                            if let Err(e) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", e);
+                                            }
+                                        }
+                                        // The dialog stays up while loading, so this task lives on
+                                        switching.set(Some(name.clone()));
+                                        spawn(async move {
+                                            let loaded = app_state.switch_model(path, gpu_layers).await.is_ok();
+                                            switching.set(None);
+                                            if loaded {
+                                                on_retry.call(draft);
+                                            }
+                                        });
+                                    },
+                                    div { class: "text-sm font-medium text-[var(--text-primary)]",
+                                        if is_en { "Switch to {name}" } else { "Passer a {name}" }
+                                    }
+                                    div { class: "text-xs text-[var(--text-tertiary)]",
+                                        if is_en { "Trained on {length / 1024}K tokens of context" } else { "Entraine sur {length / 1024}K tokens de contexte" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "px-6 pb-6 flex items-center justify-between gap-3",
+                    span { class: "text-xs text-[var(--text-tertiary)] truncate",
+                        if let Some(name) = switching() {
+                            if is_en { "Loading {name}..." } else { "Chargement de {name}..." }
+                        }
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-xl text-sm text-[var(--text-secondary)] hover:bg-white/[0.06] transition-colors",
+                        onclick: move |_| on_close.call(()),
+                        if is_en { "Edit the message" } else { "Modifier le message" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_overflow() {
+        assert_eq!(BudgetOverflow::check(3000, 1000, 4096, None), None);
+        let overflow = BudgetOverflow::check(7000, 2000, 8192, Some(32768)).unwrap();
+        assert_eq!(overflow.excess(), 808);
+        assert_eq!(overflow.fitting_context_size(), Some(16384));
+        // Nothing bigger than what the model was trained on
+        let overflow = BudgetOverflow::check(7000, 2000, 8192, Some(8192)).unwrap();
+        assert_eq!(overflow.fitting_context_size(), None);
+    }
+}
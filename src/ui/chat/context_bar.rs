@@ -0,0 +1,333 @@
+//! Context window bar shown above the chat input
+//!
+//! Splits the prompt the next turn would send into segments (system prompt,
+//! tool instructions, the workspace map pinned to every prompt, history,
+//! inlined files, tool results, the draft being typed and the room reserved
+//! for the reply). Uses a ~4 chars/token estimate, cheap
+//! enough for every keystroke; the proactive compression, whose threshold
+//! is marked on the bar, counts real tokens when a model is loaded.
+
+use crate::agent::prompts::build_agent_system_prompt;
+use crate::agent::runner::is_tool_result_message;
+use crate::agent::tools::repo_map::{workspace_prompt, PROMPT_TOKEN_BUDGET};
+use crate::app::AppState;
+use crate::storage::attachments::text_attachment_bytes;
+use crate::ui::chat::message::{Message, MessageRole};
+use dioxus::prelude::*;
+
+/// Share of the context window above which the history is compressed
+pub const COMPRESSION_THRESHOLD_PERCENT: usize = 75;
+
+/// Most recent messages included in a prompt
+pub const MAX_PROMPT_HISTORY: usize = 40;
+
+/// Rough token estimate (~4 chars per token)
+pub fn estimate_text_tokens(text: &str) -> usize {
+    text.len() / 4
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    System,
+    Tools,
+    Pinned,
+    History,
+    Attachments,
+    ToolResults,
+    Draft,
+    Reply,
+}
+
+impl Segment {
+    fn label(&self, is_en: bool) -> &'static str {
+        match (self, is_en) {
+            (Self::System, true) => "System prompt",
+            (Self::System, false) => "Prompt systeme",
+            (Self::Tools, true) => "Tools",
+            (Self::Tools, false) => "Outils",
+            (Self::Pinned, true) => "Pinned workspace",
+            (Self::Pinned, false) => "Espace de travail epingle",
+            (Self::History, true) => "History",
+            (Self::History, false) => "Historique",
+            (Self::Attachments, true) => "Attachments",
+            (Self::Attachments, false) => "Pieces jointes",
+            (Self::ToolResults, true) => "Tool results",
+            (Self::ToolResults, false) => "Resultats d'outils",
+            (Self::Draft, true) => "Draft",
+            (Self::Draft, false) => "Brouillon",
+            (Self::Reply, true) => "Reply reserve",
+            (Self::Reply, false) => "Reserve reponse",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Self::System => "#a78bfa",
+            Self::Tools => "#60a5fa",
+            Self::Pinned => "#22d3ee",
+            Self::History => "#34d399",
+            Self::Attachments => "#fb923c",
+            Self::ToolResults => "#fbbf24",
+            Self::Draft => "#f472b6",
+            Self::Reply => "rgba(255,255,255,0.15)",
+        }
+    }
+}
+
+/// Estimated composition of the context window
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextUsage {
+    pub segments: Vec<(Segment, usize)>,
+    pub capacity: usize,
+}
+
+impl ContextUsage {
+    /// `agent_prompt` is the full system prompt with tool instructions,
+    /// `pinned` the tokens of the workspace section added to it,
+    /// `reply_reserve` the max_tokens of the next reply
+    pub fn estimate(
+        base_prompt: &str,
+        agent_prompt: &str,
+        pinned: usize,
+        messages: &[Message],
+        draft: &str,
+        reply_reserve: usize,
+        capacity: usize,
+    ) -> Self {
+        let system = estimate_text_tokens(base_prompt);
+        let tools = estimate_text_tokens(agent_prompt).saturating_sub(system);
+
+        let start = messages.len().saturating_sub(MAX_PROMPT_HISTORY);
+        let (mut history, mut attachments, mut tool_results) = (0, 0, 0);
+        for message in &messages[start..] {
+            let tokens = estimate_text_tokens(&message.content);
+            if message.role == MessageRole::System && is_tool_result_message(&message.content) {
+                tool_results += tokens;
+            } else {
+                let attached = (text_attachment_bytes(&message.content) / 4).min(tokens);
+                attachments += attached;
+                history += tokens - attached;
+            }
+        }
+
+        Self {
+            segments: vec![
+                (Segment::System, system),
+                (Segment::Tools, tools),
+                (Segment::Pinned, pinned),
+                (Segment::History, history),
+                (Segment::Attachments, attachments),
+                (Segment::ToolResults, tool_results),
+                (Segment::Draft, estimate_text_tokens(draft)),
+                (Segment::Reply, reply_reserve),
+            ],
+            capacity,
+        }
+    }
+
+    /// Tokens of the prompt itself, without the reply reserve
+    pub fn prompt_tokens(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|(segment, _)| *segment != Segment::Reply)
+            .map(|(_, tokens)| tokens)
+            .sum()
+    }
+
+    /// Whether sending now would trigger the proactive compression
+    pub fn will_compress(&self) -> bool {
+        self.prompt_tokens() > self.capacity * COMPRESSION_THRESHOLD_PERCENT / 100
+    }
+}
+
+/// System prompt alone, then with the tool instructions
+pub fn agent_prompts(app_state: &AppState) -> (String, String) {
+    let (base, tools_enabled, tool_language) = {
+        let settings = app_state.settings.read();
+        (settings.system_prompt.clone(), settings.tools_enabled, settings.tool_language.clone())
+    };
+    let agent = &app_state.agent;
+    let full = if agent.config.enable_tools && tools_enabled {
+        let tools = agent.tool_registry.list_tools_in(&tool_language);
+        build_agent_system_prompt(&base, &tools, None, None)
+    } else {
+        base.clone()
+    };
+    (base, full)
+}
+
+/// Most the workspace section can take, without building it: its map is
+/// rendered within `PROMPT_TOKEN_BUDGET`, plus a short header
+pub fn pinned_budget(app_state: &AppState) -> usize {
+    let settings = app_state.settings.peek();
+    let pinned = settings.tools_enabled && settings.workspace.as_ref().is_some_and(|w| w.is_dir());
+    if pinned { PROMPT_TOKEN_BUDGET + 60 } else { 0 }
+}
+
+/// max_tokens set aside for the next reply
+///
+/// In auto mode the reply takes whatever is left, so nothing is set aside.
+pub fn reply_reserve(app_state: &AppState) -> u32 {
+    let settings = app_state.settings.read();
+    (app_state.next_max_tokens)().unwrap_or(if settings.max_tokens_auto { 0 } else { settings.generation_params().max_tokens })
+}
+
+#[component]
+pub fn ContextBar(draft: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings;
+
+    // Rebuilding the tool instructions is only needed when the prompt changes
+    let prompts = {
+        let app_state = app_state.clone();
+        use_memo(move || agent_prompts(&app_state))
+    };
+
+    // Workspace map the next turn pins to the system prompt, built off the
+    // UI thread like the chat does
+    let pinned = use_resource(move || async move {
+        let workspace = {
+            let settings = settings.read();
+            settings.workspace.clone().filter(|w| settings.tools_enabled && w.is_dir())
+        };
+        match workspace {
+            Some(root) => tokio::task::spawn_blocking(move || workspace_prompt(&root))
+                .await
+                .unwrap_or_default(),
+            None => String::new(),
+        }
+    });
+
+    let (is_en, params) = {
+        let settings = settings.read();
+        (settings.language == "en", settings.generation_params())
+    };
+    let reply_reserve = reply_reserve(&app_state);
+    let usage = {
+        let (base, full) = &*prompts.read();
+        let pinned = pinned.read().as_deref().map_or(0, estimate_text_tokens);
+        ContextUsage::estimate(
+            base,
+            full,
+            pinned,
+            &app_state.active_messages.read(),
+            &draft,
+            reply_reserve as usize,
+            params.max_context_size as usize,
+        )
+    };
+
+    let total: usize = usage.segments.iter().map(|(_, tokens)| tokens).sum();
+    let scale = total.max(usage.capacity).max(1) as f64;
+    let used = usage.prompt_tokens();
+    let capacity = usage.capacity;
+    let summary_class = if usage.will_compress() {
+        "text-[10px] font-mono text-[#fbbf24]"
+    } else {
+        "text-[10px] font-mono text-[var(--text-tertiary)]"
+    };
+    let summary_title = if usage.will_compress() {
+        if is_en {
+            "Above the compression threshold: older messages will be summarized"
+        } else {
+            "Au-dela du seuil de compression : les anciens messages seront resumes"
+        }
+    } else {
+        ""
+    };
+    let threshold_left = COMPRESSION_THRESHOLD_PERCENT as f64 * capacity as f64 / scale;
+
+    rsx! {
+        div {
+            class: "mb-2 px-2",
+            div {
+                class: "relative flex h-1.5 w-full rounded-full overflow-hidden bg-white/[0.04]",
+                for (segment, tokens) in usage.segments.iter().copied().filter(|(_, t)| *t > 0) {
+                    div {
+                        key: "{segment:?}",
+                        class: "h-full",
+                        style: "width: {tokens as f64 * 100.0 / scale:.2}%; background: {segment.color()};",
+                        title: "{segment.label(is_en)}: ~{tokens} tokens",
+                    }
+                }
+                div {
+                    class: "absolute top-0 h-full w-px bg-[var(--text-tertiary)]",
+                    style: "left: {threshold_left:.2}%;",
+                    title: if is_en { "Compression threshold" } else { "Seuil de compression" },
+                }
+            }
+            div {
+                class: "flex flex-wrap items-center gap-x-3 gap-y-0.5 mt-1",
+                for (segment, tokens) in usage.segments.iter().copied().filter(|(_, t)| *t > 0) {
+                    span {
+                        key: "{segment:?}",
+                        class: "flex items-center gap-1 text-[10px] text-[var(--text-tertiary)]",
+                        span { class: "w-1.5 h-1.5 rounded-full", style: "background: {segment.color()};" }
+                        "{segment.label(is_en)} {tokens}"
+                    }
+                }
+                span {
+                    class: "ml-auto {summary_class}",
+                    title: "{summary_title}",
+                    "~{used} / {capacity}"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::injection::{wrap_external_content, SanitizeReport};
+    use crate::agent::runner::format_tool_result_for_system;
+    use crate::agent::tools::ToolResult;
+
+    fn message(role: MessageRole, content: &str) -> Message {
+        Message::new(role, content)
+    }
+
+    #[test]
+    fn test_context_usage_segments() {
+        let result = ToolResult {
+            success: true,
+            data: serde_json::json!({ "content": "r".repeat(700) }),
+            message: "Read notes.md".to_string(),
+        };
+        let tool_result = format_tool_result_for_system("file_read", &result);
+        let wrapped = wrap_external_content("web_fetch", &tool_result, &SanitizeReport::default());
+        let attachment = format!("📎 notes.md (/tmp/notes.md)\n```\n{}\n```", "n".repeat(800));
+        let messages = vec![
+            message(MessageRole::User, &"u".repeat(400)),
+            message(MessageRole::System, &tool_result),
+            message(MessageRole::System, &wrapped),
+            message(MessageRole::Assistant, &"a".repeat(400)),
+            message(MessageRole::User, &attachment),
+        ];
+        let usage = ContextUsage::estimate(
+            &"s".repeat(200),
+            &"s".repeat(600),
+            30,
+            &messages,
+            "hi there",
+            1000,
+            4096,
+        );
+        let get = |s: Segment| usage.segments.iter().find(|(seg, _)| *seg == s).unwrap().1;
+        assert_eq!(get(Segment::System), 50);
+        assert_eq!(get(Segment::Tools), 100);
+        assert_eq!(get(Segment::Pinned), 30);
+        assert_eq!(get(Segment::Attachments), 200);
+        assert_eq!(get(Segment::History), 200 + estimate_text_tokens(&attachment) - 200);
+        assert_eq!(
+            get(Segment::ToolResults),
+            estimate_text_tokens(&tool_result) + estimate_text_tokens(&wrapped)
+        );
+        assert_eq!(get(Segment::Draft), 2);
+        assert!(!usage.will_compress());
+
+        let long = vec![message(MessageRole::User, &"x".repeat(4 * 3100))];
+        let usage = ContextUsage::estimate("", "", 0, &long, "", 0, 4096);
+        assert!(usage.will_compress());
+    }
+}
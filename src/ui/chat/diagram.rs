@@ -0,0 +1,153 @@
+//! Mermaid diagrams in messages
+//!
+//! ```` ```mermaid ```` fences are rendered to SVG in-process by a pure Rust
+//! renderer: no browser engine, no network. The block keeps a toggle back to
+//! the source and exports the diagram as PNG to the Downloads folder.
+
+use crate::app::AppState;
+use crate::storage::get_data_dir;
+use dioxus::prelude::*;
+use mermaid_rs_renderer::render::write_output_png;
+use mermaid_rs_renderer::{render_with_options, RenderConfig, RenderOptions, Theme};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DiagramError {
+    #[error("Invalid diagram: {0}")]
+    Invalid(String),
+    #[error("PNG export failed: {0}")]
+    Export(String),
+}
+
+fn theme(dark: bool) -> Theme {
+    if dark {
+        Theme::dark()
+    } else {
+        Theme::modern()
+    }
+}
+
+/// Render Mermaid source to an SVG document
+pub fn render_mermaid(source: &str, dark: bool) -> Result<String, DiagramError> {
+    let options = RenderOptions {
+        theme: theme(dark),
+        ..RenderOptions::default()
+    };
+    render_with_options(source, options).map_err(|e| DiagramError::Invalid(e.to_string()))
+}
+
+/// Downloads folder, or `{data_dir}/exports` when there is none
+fn exports_dir() -> PathBuf {
+    directories::UserDirs::new()
+        .and_then(|dirs| dirs.download_dir().map(|d| d.to_path_buf()))
+        .or_else(|| get_data_dir().ok().map(|d| d.join("exports")))
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Render the diagram and save it as a PNG; returns the file written
+pub fn export_png(source: &str, dark: bool) -> Result<PathBuf, DiagramError> {
+    let svg = render_mermaid(source, dark)?;
+    let dir = exports_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| DiagramError::Export(e.to_string()))?;
+    let path = dir.join(format!(
+        "diagram-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    write_output_png(&svg, &path, &RenderConfig::default(), &theme(dark))
+        .map_err(|e| DiagramError::Export(e.to_string()))?;
+    tracing::info!("Exported diagram to {}", path.display());
+    Ok(path)
+}
+
+/// Rendered diagram with source toggle and PNG export
+///
+/// Source that doesn't parse (including a fence still being streamed) is
+/// shown as a plain code block.
+#[component]
+pub fn MermaidBlock(source: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, dark) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.theme != "light")
+    };
+    let mut show_source = use_signal(|| false);
+    let mut export_result = use_signal(|| None::<Result<PathBuf, String>>);
+    let rendered = render_mermaid(&source, dark);
+    let is_rendered = rendered.is_ok();
+
+    let toolbar_button = "px-2 py-0.5 rounded-md text-[11px] font-medium transition-colors text-[var(--text-tertiary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06]";
+    let export_source = source.clone();
+
+    rsx! {
+        div { class: "my-3 rounded-xl overflow-hidden border border-[var(--border-subtle)]",
+            style: "background: #121110;",
+            div { class: "code-header",
+                span { "mermaid" }
+                if is_rendered {
+                    div { class: "flex items-center gap-1",
+                        button {
+                            class: toolbar_button,
+                            onclick: move |_| show_source.set(!show_source()),
+                            if show_source() {
+                                if is_en { "Diagram" } else { "Diagramme" }
+                            } else {
+                                "Source"
+                            }
+                        }
+                        button {
+                            class: toolbar_button,
+                            title: if is_en { "Save as PNG in Downloads" } else { "Enregistrer en PNG dans Telechargements" },
+                            onclick: move |_| {
+                                let result = export_png(&export_source, dark).map_err(|e| e.to_string());
+                                export_result.set(Some(result));
+                            },
+                            "PNG"
+                        }
+                    }
+                }
+            }
+            match rendered {
+                Ok(svg) if !show_source() => rsx! {
+                    div {
+                        class: "mermaid-diagram p-4 overflow-x-auto flex justify-center",
+                        dangerous_inner_html: "{svg}"
+                    }
+                },
+                _ => rsx! {
+                    pre { class: "p-4 overflow-x-auto",
+                        code { class: "text-sm font-mono leading-relaxed",
+                            style: "color: #E8E2DB;",
+                            "{source}"
+                        }
+                    }
+                },
+            }
+            match export_result.read().clone() {
+                Some(Ok(path)) => rsx! {
+                    p { class: "px-4 pb-2 text-[11px] text-[var(--success)] break-all",
+                        if is_en { "Saved to {path.display()}" } else { "Enregistre dans {path.display()}" }
+                    }
+                },
+                Some(Err(error)) => rsx! {
+                    p { class: "px-4 pb-2 text-[11px] text-[var(--error)] break-all", "{error}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_mermaid() {
+        let svg =
+            render_mermaid("flowchart LR\n  A[Agent] -->|calls| B[Tool <b>x</b>]", true).unwrap();
+        assert!(svg.contains("<svg"));
+        assert!(!svg.contains("<b>"));
+        assert!(render_mermaid("flowchart LR\n  A --> ", true).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! 👍/👎 buttons under assistant messages
+//!
+//! Ratings are filed under the model loaded when the message is rated;
+//! clicking the active thumb again removes the rating.
+
+use crate::app::{AppState, ModelState};
+use crate::storage::feedback::{
+    find_feedback, record_feedback, remove_feedback, FeedbackEntry, Rating,
+};
+use crate::types::message::Role;
+use dioxus::prelude::*;
+
+/// File name of the loaded model
+fn current_model(app_state: &AppState) -> String {
+    match &*app_state.model_state.read() {
+        ModelState::Loaded(path) => path.rsplit(['/', '\\']).next().unwrap_or(path).to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+#[component]
+pub fn FeedbackBar(index: usize, response: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let conversation_id = app_state
+        .current_conversation
+        .read()
+        .as_ref()
+        .map(|c| c.id.clone());
+    let hook_conversation_id = conversation_id.clone();
+    let mut feedback =
+        use_signal(move || hook_conversation_id.and_then(|id| find_feedback(&id, index)));
+    let mut comment = use_signal(|| {
+        feedback
+            .peek()
+            .as_ref()
+            .map(|f| f.comment.clone())
+            .unwrap_or_default()
+    });
+
+    let Some(conversation_id) = conversation_id else {
+        return rsx! {};
+    };
+    // Nothing is written to disk in locked mode
+    if (app_state.is_generating)() || app_state.settings.read().lock.enabled {
+        return rsx! {};
+    }
+
+    let rate = {
+        let conversation_id = conversation_id.clone();
+        move |rating: Rating| {
+            let app_state = app_state.clone();
+            let conversation_id = conversation_id.clone();
+            let response = response.clone();
+            move |_| {
+                let current = feedback.read().clone();
+                if let Some(current) = current.as_ref().filter(|f| f.rating == rating) {
+                    if let Err(e) = remove_feedback(&current.model, &conversation_id, index) {
+                        tracing::error!("Failed to remove feedback: {}", e);
+                    }
+                    feedback.set(None);
+                    return;
+                }
+                // The user message this response answered
+                let prompt = app_state
+                    .current_conversation
+                    .read()
+                    .as_ref()
+                    .and_then(|c| {
+                        c.messages[..index.min(c.messages.len())]
+                            .iter()
+                            .rev()
+                            .find(|m| m.role == Role::User)
+                            .map(|m| m.content.clone())
+                    })
+                    .unwrap_or_default();
+                let entry = FeedbackEntry {
+                    timestamp: chrono::Utc::now(),
+                    model: current_model(&app_state),
+                    conversation_id: conversation_id.clone(),
+                    message_index: index,
+                    rating,
+                    comment: comment.read().trim().to_string(),
+                    prompt,
+                    response: response.clone(),
+                };
+                // A rating moved to another model must leave the old file
+                if let Some(previous) = current.filter(|f| f.model != entry.model) {
+                    let _ = remove_feedback(&previous.model, &conversation_id, index);
+                }
+                match record_feedback(&entry) {
+                    Ok(()) => feedback.set(Some(entry)),
+                    Err(e) => tracing::error!("Failed to save feedback: {}", e),
+                }
+            }
+        }
+    };
+    let mut save_comment = move || {
+        let Some(mut entry) = feedback.read().clone() else {
+            return;
+        };
+        entry.comment = comment.read().trim().to_string();
+        match record_feedback(&entry) {
+            Ok(()) => feedback.set(Some(entry)),
+            Err(e) => tracing::error!("Failed to save feedback: {}", e),
+        }
+    };
+
+    let active = feedback.read().as_ref().map(|f| f.rating);
+    let thumb = |selected: bool| {
+        if selected {
+            "px-1.5 py-0.5 rounded-md text-[11px] bg-[var(--accent-soft)]"
+        } else {
+            "opacity-0 group-hover:opacity-100 transition-opacity px-1.5 py-0.5 rounded-md text-[11px] grayscale hover:grayscale-0 hover:bg-white/[0.06]"
+        }
+    };
+
+    rsx! {
+        div { class: "inline-flex items-center gap-1",
+            button {
+                class: thumb(active == Some(Rating::Up)),
+                title: if is_en { "Good answer" } else { "Bonne reponse" },
+                onclick: rate(Rating::Up),
+                "👍"
+            }
+            button {
+                class: thumb(active == Some(Rating::Down)),
+                title: if is_en { "Bad answer" } else { "Mauvaise reponse" },
+                onclick: rate(Rating::Down),
+                "👎"
+            }
+            if active.is_some() {
+                input {
+                    class: "ml-1 w-56 px-2 py-0.5 rounded-md text-[11px] bg-transparent border border-[var(--border-subtle)] text-[var(--text-secondary)] focus:outline-none focus:border-[var(--accent-primary)]",
+                    placeholder: if is_en { "Comment (optional)" } else { "Commentaire (optionnel)" },
+                    value: "{comment}",
+                    oninput: move |e| comment.set(e.value()),
+                    onkeydown: move |e: KeyboardEvent| {
+                        if e.key() == Key::Enter {
+                            save_comment();
+                        }
+                    },
+                    onblur: move |_| save_comment(),
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,14 @@
 //! Chat input component - Premium glass style with send button inside
 
-use crate::app::AppState;
+use crate::app::{AppState, ModelState};
 use crate::agent::skills::loader::SkillLoader;
 use crate::agent::skills::Skill;
+use crate::storage::snippets::{expand_snippet, find_snippet, parse_snippet_command, Snippet, SNIPPET_COMMAND};
+use crate::ui::chat::budget::{check_send, BudgetDialog, BudgetOverflow};
+use crate::ui::chat::context_bar::ContextBar;
+use crate::ui::chat::personas::PersonaBar;
+use crate::ui::chat::presets::PresetBar;
+use crate::ui::chat::routing::RouteChip;
 use dioxus::prelude::*;
 
 /// Estimate how many rows the textarea needs based on content
@@ -16,6 +22,50 @@ fn compute_rows(text: &str) -> usize {
     total.clamp(1, 8)
 }
 
+/// Snippets whose name starts with the one being typed after `/snippet `
+fn snippet_matches(snippets: &[Snippet], text: &str) -> Vec<Snippet> {
+    let Some(query) = text
+        .strip_prefix(SNIPPET_COMMAND)
+        .and_then(|rest| rest.strip_prefix(' '))
+        .filter(|query| !query.contains(char::is_whitespace))
+    else {
+        return Vec::new();
+    };
+    let query = query.to_lowercase();
+    snippets
+        .iter()
+        .filter(|snippet| snippet.name.to_lowercase().starts_with(&query))
+        .take(8)
+        .cloned()
+        .collect()
+}
+
+/// max_tokens used by a bare `/long`
+pub const LONG_REPLY_TOKENS: u32 = 16384;
+
+/// Split a leading `/long [tokens]` command off a message
+///
+/// Returns the remaining text and the requested max_tokens, if any. A bare
+/// `/long` asks for [`LONG_REPLY_TOKENS`].
+pub fn parse_long_command(text: &str) -> (String, Option<u32>) {
+    let trimmed = text.trim_start();
+    let Some(rest) = trimmed.strip_prefix("/long") else {
+        return (text.to_string(), None);
+    };
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        // Another command, e.g. a skill named "/longform"
+        return (text.to_string(), None);
+    }
+    let rest = rest.trim_start();
+    let (first, remainder) = rest
+        .split_once(char::is_whitespace)
+        .unwrap_or((rest, ""));
+    match first.parse::<u32>() {
+        Ok(tokens) => (remainder.trim_start().to_string(), Some(tokens.clamp(256, 65536))),
+        Err(_) => (rest.to_string(), Some(LONG_REPLY_TOKENS)),
+    }
+}
+
 #[component]
 pub fn ChatInput(
     on_send: EventHandler<String>,
@@ -30,6 +80,92 @@ pub fn ChatInput(
     
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
+    let mut textarea = use_signal(|| None::<std::rc::Rc<MountedData>>);
+
+    // A message that would overflow the context is held for the budget dialog
+    let mut overflow = use_signal(|| None::<BudgetOverflow>);
+    let try_send = {
+        let app_state = app_state.clone();
+        use_callback(move |message: String| {
+            if let Some(found) = check_send(&app_state, &message) {
+                text.set(message);
+                overflow.set(Some(found));
+                return;
+            }
+            overflow.set(None);
+            // A retry from the budget dialog sends the draft put back above
+            text.set(String::new());
+            autocomplete_open.set(false);
+            on_send.call(message);
+        })
+    };
+
+    // `/snippet name ...` fills the input for review instead of sending;
+    // holds the name when no snippet has it
+    let mut unknown_snippet = use_signal(|| None::<String>);
+    let submit = {
+        let app_state = app_state.clone();
+        use_callback(move |message: String| {
+            let Some((name, args)) = parse_snippet_command(&message) else {
+                unknown_snippet.set(None);
+                try_send.call(message);
+                return;
+            };
+            let settings = app_state.settings.read();
+            match find_snippet(&settings.snippets, &name) {
+                Some(snippet) => {
+                    text.set(expand_snippet(&snippet.body, &args, chrono::Local::now()));
+                    unknown_snippet.set(None);
+                }
+                None => {
+                    text.set(message);
+                    unknown_snippet.set(Some(name));
+                }
+            }
+        })
+    };
+
+    // Prompt from a welcome-screen suggestion: send it right away when a
+    // model is loaded, otherwise leave it in the input for review
+    let mut pending_prompt = app_state.pending_prompt;
+    let model_state = app_state.model_state;
+    use_effect(move || {
+        if pending_prompt.read().is_none() {
+            return;
+        }
+        let Some(element) = textarea() else {
+            return;
+        };
+        let prompt = pending_prompt.take().unwrap_or_default();
+        let model_loaded = matches!(*model_state.peek(), ModelState::Loaded(_));
+        if model_loaded && !is_generating && !prompt.trim().is_empty() {
+            try_send.call(prompt);
+        } else {
+            text.set(prompt);
+        }
+        spawn(async move {
+            if let Err(error) = element.set_focus(true).await {
+                tracing::debug!("Failed to focus chat input: {:?}", error);
+            }
+        });
+    });
+
+    // Text from a link or another launch: only filled in, the user sends it
+    let mut pending_draft = app_state.pending_draft;
+    use_effect(move || {
+        if pending_draft.read().is_none() {
+            return;
+        }
+        let Some(element) = textarea() else {
+            return;
+        };
+        text.set(pending_draft.take().unwrap_or_default());
+        spawn(async move {
+            if let Err(error) = element.set_focus(true).await {
+                tracing::debug!("Failed to focus chat input: {:?}", error);
+            }
+        });
+    });
 
     // Load skills on mount
     use_effect(move || {
@@ -39,7 +175,18 @@ pub fn ChatInput(
         });
     });
 
+    let snippets_state = app_state.settings;
     let handle_keydown = move |evt: KeyboardEvent| {
+        // Tab completes the snippet name being typed
+        if evt.key() == Key::Tab {
+            let matches = snippet_matches(&snippets_state.read().snippets, &text());
+            if let Some(snippet) = matches.first() {
+                evt.prevent_default();
+                text.set(format!("{} {} ", SNIPPET_COMMAND, snippet.name));
+                return;
+            }
+        }
+
         // Autocomplete navigation
         if autocomplete_open() {
             let skills_len = filtered_skills.read().len();
@@ -79,9 +226,10 @@ pub fn ChatInput(
         } else if evt.key() == Key::Enter && !evt.modifiers().contains(Modifiers::SHIFT) {
             evt.prevent_default();
             if !is_generating && !text().trim().is_empty() {
-                on_send.call(text());
+                let message = text();
                 text.set(String::new());
                 autocomplete_open.set(false);
+                submit.call(message);
             }
         }
     };
@@ -114,6 +262,7 @@ pub fn ChatInput(
         }
     };
 
+    let snippet_list = snippet_matches(&app_state.settings.read().snippets, &text());
     let can_send = !is_generating && !text().trim().is_empty();
     let rows = compute_rows(&text());
     let rows_str = format!("{}", rows);
@@ -223,6 +372,57 @@ pub fn ChatInput(
                     }
                 }
 
+                // Snippet names after `/snippet `
+                if !snippet_list.is_empty() {
+                    div {
+                        class: "absolute left-0 bottom-full mb-2 w-full rounded-xl overflow-hidden z-50 glass-md animate-fade-in-up",
+                        style: "max-height: 240px; border: 1px solid var(--border-medium); box-shadow: 0 12px 32px -4px rgba(30,25,20,0.35);",
+                        div {
+                            class: "px-3 py-2 border-b border-[var(--border-subtle)] bg-white/5",
+                            span {
+                                class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
+                                if is_en { "Snippets (Tab to complete)" } else { "Snippets (Tab pour completer)" }
+                            }
+                        }
+                        div {
+                            class: "overflow-y-auto custom-scrollbar",
+                            style: "max-height: 200px;",
+                            for snippet in snippet_list {
+                                button {
+                                    key: "{snippet.name}",
+                                    onclick: {
+                                        let name = snippet.name.clone();
+                                        move |_| text.set(format!("{} {} ", SNIPPET_COMMAND, name))
+                                    },
+                                    class: "w-full text-left px-3 py-2 transition-colors flex flex-col gap-0.5 text-[var(--text-primary)] hover:bg-white/5",
+                                    span { class: "font-semibold text-sm", "{snippet.name}" }
+                                    span { class: "text-xs opacity-70 truncate",
+                                        if snippet.description.is_empty() {
+                                            "{snippet.body}"
+                                        } else {
+                                            "{snippet.description}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(found) = overflow() {
+                    BudgetDialog {
+                        overflow: found,
+                        draft: text(),
+                        on_close: move |_| overflow.set(None),
+                        on_retry: move |message: String| try_send.call(message),
+                    }
+                }
+
+                PersonaBar {}
+                PresetBar {}
+                RouteChip { draft: text() }
+                ContextBar { draft: text() }
+
                 // Glass input container
                 div {
                     class: "{container_class}",
@@ -238,6 +438,7 @@ pub fn ChatInput(
                         onkeydown: handle_keydown,
                         disabled: is_generating,
                         rows: "{rows_str}",
+                        onmounted: move |e: MountedEvent| textarea.set(Some(e.data())),
                     }
 
                     // Send / Stop button
@@ -259,8 +460,9 @@ pub fn ChatInput(
                         button {
                             onclick: move |_| {
                                 if can_send {
-                                    on_send.call(text());
+                                    let message = text();
                                     text.set(String::new());
+                                    submit.call(message);
                                 }
                             },
                             disabled: !can_send,
@@ -284,11 +486,43 @@ pub fn ChatInput(
                 }
 
                 // Hint text
-                p {
-                    class: "text-center text-[11px] text-[var(--text-tertiary)] mt-2 opacity-40",
-                    "{hint}"
+                if let Some(name) = unknown_snippet() {
+                    p {
+                        class: "text-center text-[11px] text-[var(--error)] mt-2",
+                        if is_en {
+                            "No snippet named \"{name}\". Add it in Settings > Appearance."
+                        } else {
+                            "Aucun snippet nomme \"{name}\". Ajoutez-le dans Parametres > Apparence."
+                        }
+                    }
+                } else {
+                    p {
+                        class: "text-center text-[11px] text-[var(--text-tertiary)] mt-2 opacity-40",
+                        "{hint}"
+                    }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_long_command() {
+        assert_eq!(parse_long_command("hello"), ("hello".to_string(), None));
+        assert_eq!(
+            parse_long_command("/long write a novel"),
+            ("write a novel".to_string(), Some(LONG_REPLY_TOKENS))
+        );
+        assert_eq!(
+            parse_long_command("/long 12000 write a novel"),
+            ("write a novel".to_string(), Some(12000))
+        );
+        assert_eq!(parse_long_command("/long"), (String::new(), Some(LONG_REPLY_TOKENS)));
+        assert_eq!(parse_long_command("/long 10"), (String::new(), Some(256)));
+        assert_eq!(parse_long_command("/longform x"), ("/longform x".to_string(), None));
+    }
+}
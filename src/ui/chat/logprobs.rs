@@ -0,0 +1,104 @@
+//! Debug view of a reply's per-token probabilities
+//!
+//! Recorded when "Record token probabilities" is on in the inference
+//! settings. Each token is colored by how sure the model was of it, and its
+//! tooltip lists the alternatives it was weighed against.
+
+use crate::app::AppState;
+use crate::types::message::TokenLogprob;
+use dioxus::prelude::*;
+
+/// Tokens below this probability count as low confidence
+const LOW_CONFIDENCE: f32 = 0.5;
+
+/// Background and text colors for a token picked with `probability`
+fn confidence_style(probability: f32) -> &'static str {
+    if probability > 0.9 {
+        "background: var(--success-bg); color: var(--success);"
+    } else if probability > LOW_CONFIDENCE {
+        "background: rgba(234, 179, 8, 0.15); color: #eab308;"
+    } else if probability > 0.2 {
+        "background: var(--warning-bg); color: var(--warning);"
+    } else {
+        "background: var(--error-bg); color: var(--error);"
+    }
+}
+
+/// Mean probability and number of low-confidence tokens
+fn summarize(tokens: &[TokenLogprob]) -> (f32, usize) {
+    if tokens.is_empty() {
+        return (0.0, 0);
+    }
+    let probabilities = tokens.iter().map(TokenLogprob::probability);
+    let mean = probabilities.clone().sum::<f32>() / tokens.len() as f32;
+    (mean, probabilities.filter(|&p| p < LOW_CONFIDENCE).count())
+}
+
+/// Tooltip of one token: its probability, then the alternatives
+fn token_title(token: &TokenLogprob) -> String {
+    let mut title = format!("{:?}  p={:.3}", token.text, token.probability());
+    for (text, logprob) in &token.top {
+        title.push_str(&format!("\n  {:?}  {:.3}", text, logprob.exp()));
+    }
+    title
+}
+
+#[component]
+pub fn LogprobsView(tokens: Vec<TokenLogprob>) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut open = use_signal(|| false);
+    let (mean, low) = summarize(&tokens);
+
+    rsx! {
+        div { class: "relative inline-flex",
+            button {
+                class: "px-1.5 py-0.5 rounded-md text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06]",
+                title: if is_en { "Token probabilities" } else { "Probabilites des tokens" },
+                onclick: move |_| open.toggle(),
+                "🔬"
+            }
+            if open() {
+                div { class: "absolute bottom-full left-0 mb-1.5 z-20 w-[32rem] max-w-[80vw] p-3 rounded-xl glass-md shadow-lg text-xs text-[var(--text-secondary)]",
+                    div { class: "flex items-center justify-between mb-2",
+                        span { class: "font-medium text-[var(--text-primary)]",
+                            if is_en { "Token probabilities" } else { "Probabilites des tokens" }
+                        }
+                        span {
+                            if is_en {
+                                "{tokens.len()} tokens · mean p={mean:.2} · {low} below {LOW_CONFIDENCE}"
+                            } else {
+                                "{tokens.len()} tokens · p moyenne={mean:.2} · {low} sous {LOW_CONFIDENCE}"
+                            }
+                        }
+                    }
+                    div { class: "max-h-72 overflow-y-auto custom-scrollbar font-mono whitespace-pre-wrap break-words leading-relaxed",
+                        for (i, token) in tokens.iter().enumerate() {
+                            span {
+                                key: "{i}",
+                                class: "rounded-sm cursor-help",
+                                style: "{confidence_style(token.probability())}",
+                                title: "{token_title(token)}",
+                                "{token.text}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let token = |p: f32| TokenLogprob { text: "a".into(), logprob: p.ln(), top: Vec::new() };
+        let (mean, low) = summarize(&[token(1.0), token(0.4), token(0.1)]);
+        assert!((mean - 0.5).abs() < 1e-5);
+        assert_eq!(low, 2);
+        assert_eq!(summarize(&[]), (0.0, 0));
+    }
+}
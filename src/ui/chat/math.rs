@@ -0,0 +1,841 @@
+//! LaTeX to MathML conversion for math in messages
+//!
+//! Covers what models actually write in answers: fractions, roots, scripts,
+//! big operators, Greek letters, accents, font styles, `\left`/`\right`,
+//! matrices and `cases`. The webview typesets MathML natively, so there is no
+//! JavaScript or font bundle to ship. Unknown commands are shown in an error
+//! style instead of failing the whole formula; only structural errors
+//! (unbalanced braces, missing arguments) make the caller fall back to the
+//! raw source.
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum MathError {
+    #[error("Unbalanced braces")]
+    UnbalancedBraces,
+    #[error("Missing argument for \\{0}")]
+    MissingArgument(String),
+    #[error("Missing \\right")]
+    MissingRight,
+    #[error("Missing \\end{{{0}}}")]
+    MissingEnd(String),
+}
+
+/// Convert a LaTeX formula (without its `$` delimiters) to a `<math>` element
+pub fn latex_to_mathml(latex: &str, display: bool) -> Result<String, MathError> {
+    let mut parser = Parser {
+        chars: latex.chars().collect(),
+        pos: 0,
+        display,
+        variant: None,
+    };
+    let rows = parser.parse_rows()?;
+    if parser.pos < parser.chars.len() {
+        // Only a stray \end{...} stops the top level early
+        return Err(MathError::UnbalancedBraces);
+    }
+    let body = if rows.len() == 1 && rows[0].len() == 1 {
+        rows.into_iter().flatten().collect()
+    } else {
+        table(&rows, "center")
+    };
+    Ok(format!(
+        "<math display=\"{}\">{}</math>",
+        if display { "block" } else { "inline" },
+        body
+    ))
+}
+
+/// Font style applied to letters by `\mathbb` and friends
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Variant {
+    Bold,
+    DoubleStruck,
+    Script,
+    Fraktur,
+    SansSerif,
+}
+
+/// Where a run of items stops
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stop {
+    Brace,
+    Bracket,
+    Right,
+    /// `&`, `\\`, `\end` or the end of input
+    Cell,
+}
+
+#[rustfmt::skip]
+const GREEK: [(&str, char); 40] = [
+    ("alpha", 'α'), ("beta", 'β'), ("gamma", 'γ'), ("delta", 'δ'), ("epsilon", 'ϵ'),
+    ("varepsilon", 'ε'), ("zeta", 'ζ'), ("eta", 'η'), ("theta", 'θ'), ("vartheta", 'ϑ'),
+    ("iota", 'ι'), ("kappa", 'κ'), ("lambda", 'λ'), ("mu", 'μ'), ("nu", 'ν'),
+    ("xi", 'ξ'), ("pi", 'π'), ("varpi", 'ϖ'), ("rho", 'ρ'), ("varrho", 'ϱ'),
+    ("sigma", 'σ'), ("varsigma", 'ς'), ("tau", 'τ'), ("upsilon", 'υ'), ("phi", 'ϕ'),
+    ("varphi", 'φ'), ("chi", 'χ'), ("psi", 'ψ'), ("omega", 'ω'), ("Gamma", 'Γ'),
+    ("Delta", 'Δ'), ("Theta", 'Θ'), ("Lambda", 'Λ'), ("Xi", 'Ξ'), ("Pi", 'Π'),
+    ("Sigma", 'Σ'), ("Upsilon", 'Υ'), ("Phi", 'Φ'), ("Psi", 'Ψ'), ("Omega", 'Ω'),
+];
+
+/// Symbols rendered as upright identifiers
+#[rustfmt::skip]
+const IDENTIFIERS: [(&str, &str); 9] = [
+    ("infty", "∞"), ("partial", "∂"), ("nabla", "∇"), ("emptyset", "∅"),
+    ("varnothing", "∅"), ("hbar", "ℏ"), ("ell", "ℓ"), ("Re", "ℜ"), ("Im", "ℑ"),
+];
+
+#[rustfmt::skip]
+const OPERATORS: [(&str, &str); 76] = [
+    ("times", "×"), ("cdot", "⋅"), ("pm", "±"), ("mp", "∓"), ("div", "÷"),
+    ("ast", "∗"), ("star", "⋆"), ("circ", "∘"), ("bullet", "∙"), ("oplus", "⊕"),
+    ("otimes", "⊗"), ("setminus", "∖"), ("le", "≤"), ("leq", "≤"), ("ge", "≥"),
+    ("geq", "≥"), ("neq", "≠"), ("ne", "≠"), ("ll", "≪"), ("gg", "≫"),
+    ("approx", "≈"), ("equiv", "≡"), ("sim", "∼"), ("simeq", "≃"), ("cong", "≅"),
+    ("propto", "∝"), ("to", "→"), ("rightarrow", "→"), ("leftarrow", "←"), ("gets", "←"),
+    ("leftrightarrow", "↔"), ("Rightarrow", "⇒"), ("Leftarrow", "⇐"), ("Leftrightarrow", "⇔"), ("implies", "⟹"),
+    ("impliedby", "⟸"), ("iff", "⟺"), ("mapsto", "↦"), ("longrightarrow", "⟶"), ("uparrow", "↑"),
+    ("downarrow", "↓"), ("in", "∈"), ("notin", "∉"), ("ni", "∋"), ("subset", "⊂"),
+    ("subseteq", "⊆"), ("supset", "⊃"), ("supseteq", "⊇"), ("cup", "∪"), ("cap", "∩"),
+    ("forall", "∀"), ("exists", "∃"), ("nexists", "∄"), ("neg", "¬"), ("lnot", "¬"),
+    ("land", "∧"), ("wedge", "∧"), ("lor", "∨"), ("vee", "∨"), ("perp", "⊥"),
+    ("parallel", "∥"), ("mid", "∣"), ("angle", "∠"), ("ldots", "…"), ("dots", "…"),
+    ("cdots", "⋯"), ("vdots", "⋮"), ("ddots", "⋱"), ("prime", "′"), ("colon", ":"),
+    ("langle", "⟨"), ("rangle", "⟩"), ("lfloor", "⌊"), ("rfloor", "⌋"), ("lceil", "⌈"),
+    ("rceil", "⌉"),
+];
+
+/// Big operators and whether their limits go above and below in display mode
+#[rustfmt::skip]
+const BIG_OPERATORS: [(&str, &str, bool); 12] = [
+    ("sum", "∑", true), ("prod", "∏", true), ("coprod", "∐", true), ("bigcup", "⋃", true),
+    ("bigcap", "⋂", true), ("bigoplus", "⨁", true), ("bigotimes", "⨂", true), ("int", "∫", false),
+    ("iint", "∬", false), ("iiint", "∭", false), ("oint", "∮", false), ("bigvee", "⋁", true),
+];
+
+/// Named functions and whether their subscripts go below in display mode
+#[rustfmt::skip]
+const FUNCTIONS: [(&str, bool); 30] = [
+    ("sin", false), ("cos", false), ("tan", false), ("cot", false), ("sec", false),
+    ("csc", false), ("arcsin", false), ("arccos", false), ("arctan", false), ("sinh", false),
+    ("cosh", false), ("tanh", false), ("log", false), ("ln", false), ("lg", false),
+    ("exp", false), ("deg", false), ("dim", false), ("ker", false), ("arg", false),
+    ("lim", true), ("liminf", true), ("limsup", true), ("max", true), ("min", true),
+    ("sup", true), ("inf", true), ("det", true), ("gcd", true), ("Pr", true),
+];
+
+/// Accents: command, mark, and whether it goes under the base
+#[rustfmt::skip]
+const ACCENTS: [(&str, &str, bool); 12] = [
+    ("hat", "^", false), ("widehat", "^", false), ("bar", "¯", false), ("overline", "‾", false),
+    ("vec", "→", false), ("overrightarrow", "→", false), ("dot", "˙", false), ("ddot", "¨", false),
+    ("tilde", "~", false), ("widetilde", "~", false), ("check", "ˇ", false), ("underline", "_", true),
+];
+
+/// Commands that only affect layout details we don't reproduce
+#[rustfmt::skip]
+const IGNORED: [&str; 7] = [
+    "displaystyle", "textstyle", "scriptstyle", "limits", "nolimits", "nonumber", "notag",
+];
+
+#[rustfmt::skip]
+const SPACES: [(&str, &str); 7] = [
+    (",", "0.1667em"), (":", "0.2222em"), (";", "0.2778em"), (" ", "0.25em"),
+    ("quad", "1em"), ("qquad", "2em"), ("!", "-0.1667em"),
+];
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn mrow(items: Vec<String>) -> String {
+    if items.len() == 1 {
+        items.into_iter().next().unwrap_or_default()
+    } else {
+        format!("<mrow>{}</mrow>", items.concat())
+    }
+}
+
+fn mo(op: &str) -> String {
+    format!("<mo>{}</mo>", escape(op))
+}
+
+fn stretchy(delimiter: &str, form: &str) -> String {
+    if delimiter.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<mo stretchy=\"true\" form=\"{}\">{}</mo>",
+            form,
+            escape(delimiter)
+        )
+    }
+}
+
+fn table(rows: &[Vec<String>], align: &str) -> String {
+    let rows: String = rows
+        .iter()
+        .map(|row| {
+            let cells: String = row.iter().map(|c| format!("<mtd>{}</mtd>", c)).collect();
+            format!("<mtr>{}</mtr>", cells)
+        })
+        .collect();
+    format!("<mtable columnalign=\"{}\">{}</mtable>", align, rows)
+}
+
+fn offset(base: u32, index: u32) -> String {
+    char::from_u32(base + index)
+        .map(String::from)
+        .unwrap_or_default()
+}
+
+/// Map a letter to its Unicode mathematical alphanumeric form
+fn styled(c: char, variant: Variant) -> String {
+    let exception = match (variant, c) {
+        (Variant::DoubleStruck, 'C') => Some('ℂ'),
+        (Variant::DoubleStruck, 'H') => Some('ℍ'),
+        (Variant::DoubleStruck, 'N') => Some('ℕ'),
+        (Variant::DoubleStruck, 'P') => Some('ℙ'),
+        (Variant::DoubleStruck, 'Q') => Some('ℚ'),
+        (Variant::DoubleStruck, 'R') => Some('ℝ'),
+        (Variant::DoubleStruck, 'Z') => Some('ℤ'),
+        (Variant::Script, 'B') => Some('ℬ'),
+        (Variant::Script, 'E') => Some('ℰ'),
+        (Variant::Script, 'F') => Some('ℱ'),
+        (Variant::Script, 'H') => Some('ℋ'),
+        (Variant::Script, 'I') => Some('ℐ'),
+        (Variant::Script, 'L') => Some('ℒ'),
+        (Variant::Script, 'M') => Some('ℳ'),
+        (Variant::Script, 'R') => Some('ℛ'),
+        (Variant::Script, 'e') => Some('ℯ'),
+        (Variant::Script, 'g') => Some('ℊ'),
+        (Variant::Script, 'o') => Some('ℴ'),
+        (Variant::Fraktur, 'C') => Some('ℭ'),
+        (Variant::Fraktur, 'H') => Some('ℌ'),
+        (Variant::Fraktur, 'I') => Some('ℑ'),
+        (Variant::Fraktur, 'R') => Some('ℜ'),
+        (Variant::Fraktur, 'Z') => Some('ℨ'),
+        _ => None,
+    };
+    if let Some(c) = exception {
+        return c.to_string();
+    }
+    let (upper, lower) = match variant {
+        Variant::Bold => (0x1D400, 0x1D41A),
+        Variant::DoubleStruck => (0x1D538, 0x1D552),
+        Variant::Script => (0x1D49C, 0x1D4B6),
+        Variant::Fraktur => (0x1D504, 0x1D51E),
+        Variant::SansSerif => (0x1D5A0, 0x1D5BA),
+    };
+    match c {
+        'A'..='Z' => offset(upper, c as u32 - 'A' as u32),
+        'a'..='z' => offset(lower, c as u32 - 'a' as u32),
+        _ => c.to_string(),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    display: bool,
+    variant: Option<Variant>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the input continues with `\name` (not a longer command)
+    fn at_command(&self, name: &str) -> bool {
+        let mut i = self.pos;
+        if self.chars.get(i) != Some(&'\\') {
+            return false;
+        }
+        i += 1;
+        for c in name.chars() {
+            if self.chars.get(i) != Some(&c) {
+                return false;
+            }
+            i += 1;
+        }
+        // `\\beta` is a row break followed by "beta", `\\endx` is no \end
+        !name.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !self.chars.get(i).is_some_and(|c| c.is_ascii_alphabetic())
+    }
+
+    fn at_stop(&self, stop: Stop) -> bool {
+        match stop {
+            Stop::Brace => self.peek() == Some('}'),
+            Stop::Bracket => self.peek() == Some(']'),
+            Stop::Right => self.at_command("right"),
+            Stop::Cell => {
+                self.peek() == Some('&') || self.at_command("\\") || self.at_command("end")
+            }
+        }
+    }
+
+    fn read_command(&mut self) -> String {
+        // Called with the cursor on the backslash
+        self.pos += 1;
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos == start && self.peek().is_some() {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Raw text of a `{...}` argument, for `\text` and environment names
+    fn read_raw_group(&mut self, command: &str) -> Result<String, MathError> {
+        self.skip_whitespace();
+        if !self.eat('{') {
+            // `\mathrm d`: a single character
+            return match self.peek() {
+                Some(c) if c.is_alphanumeric() => {
+                    self.pos += 1;
+                    Ok(c.to_string())
+                }
+                _ => Err(MathError::MissingArgument(command.to_string())),
+            };
+        }
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    let text = self.chars[start..self.pos].iter().collect();
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Err(MathError::UnbalancedBraces)
+    }
+
+    fn parse_items(&mut self, stop: Stop) -> Result<Vec<String>, MathError> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.at_stop(stop) {
+                return Ok(items);
+            }
+            match self.peek() {
+                None if stop == Stop::Cell => return Ok(items),
+                None if stop == Stop::Right => return Err(MathError::MissingRight),
+                None => return Err(MathError::UnbalancedBraces),
+                Some('}') => return Err(MathError::UnbalancedBraces),
+                Some(_) => {}
+            }
+            if let Some(item) = self.parse_item()? {
+                items.push(item);
+            }
+        }
+    }
+
+    /// Cells separated by `&`, rows by `\\`, up to `\end` or the end of input
+    fn parse_rows(&mut self) -> Result<Vec<Vec<String>>, MathError> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        loop {
+            let items = self.parse_items(Stop::Cell)?;
+            row.push(mrow(items));
+            if self.eat('&') {
+                continue;
+            }
+            if self.at_command("\\") {
+                self.pos += 2;
+                rows.push(std::mem::take(&mut row));
+                continue;
+            }
+            break;
+        }
+        rows.push(row);
+        // A trailing \\ leaves an empty last row
+        if rows.len() > 1
+            && rows
+                .last()
+                .is_some_and(|r| r.len() == 1 && r[0] == "<mrow></mrow>")
+        {
+            rows.pop();
+        }
+        Ok(rows)
+    }
+
+    /// A base followed by its sub/superscripts
+    fn parse_item(&mut self) -> Result<Option<String>, MathError> {
+        let Some((base, limits)) = self.parse_base()? else {
+            return Ok(None);
+        };
+        let (mut sub, mut sup) = (None, None);
+        let mut primes = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('^') => {
+                    self.pos += 1;
+                    sup = Some(self.parse_argument("^")?);
+                }
+                Some('_') => {
+                    self.pos += 1;
+                    sub = Some(self.parse_argument("_")?);
+                }
+                Some('\'') => {
+                    self.pos += 1;
+                    primes.push('′');
+                }
+                _ => break,
+            }
+        }
+        if !primes.is_empty() {
+            let primes = mo(&primes);
+            sup = Some(match sup {
+                Some(sup) => mrow(vec![primes, sup]),
+                None => primes,
+            });
+        }
+        let (under, over) = if limits && self.display {
+            ("munder", "mover")
+        } else {
+            ("msub", "msup")
+        };
+        Ok(Some(match (sub, sup) {
+            (None, None) => base,
+            (Some(sub), None) => format!("<{0}>{1}{2}</{0}>", under, base, sub),
+            (None, Some(sup)) => format!("<{0}>{1}{2}</{0}>", over, base, sup),
+            (Some(sub), Some(sup)) => {
+                let both = if limits && self.display {
+                    "munderover"
+                } else {
+                    "msubsup"
+                };
+                format!("<{0}>{1}{2}{3}</{0}>", both, base, sub, sup)
+            }
+        }))
+    }
+
+    /// A required argument: a group or a single item without scripts
+    fn parse_argument(&mut self, command: &str) -> Result<String, MathError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('}') | Some('&') | Some('^') | Some('_') => {
+                Err(MathError::MissingArgument(command.to_string()))
+            }
+            _ => match self.parse_base()? {
+                Some((base, _)) => Ok(base),
+                None => self.parse_argument(command),
+            },
+        }
+    }
+
+    /// A `{...}` group's content, for arguments that always take a group
+    fn parse_group(&mut self, stop: Stop) -> Result<String, MathError> {
+        let items = self.parse_items(stop)?;
+        self.pos += 1;
+        Ok(mrow(items))
+    }
+
+    /// One atom; the flag tells whether its scripts are limits
+    fn parse_base(&mut self) -> Result<Option<(String, bool)>, MathError> {
+        self.skip_whitespace();
+        let Some(c) = self.peek() else {
+            return Ok(None);
+        };
+        let atom = match c {
+            '{' => {
+                self.pos += 1;
+                self.parse_group(Stop::Brace)?
+            }
+            '\\' => return self.parse_command(),
+            // Scripts with nothing to attach to
+            '^' | '_' => "<mrow></mrow>".to_string(),
+            '0'..='9' => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_digit())
+                    || (self.peek() == Some('.')
+                        && self
+                            .chars
+                            .get(self.pos + 1)
+                            .is_some_and(|c| c.is_ascii_digit()))
+                {
+                    self.pos += 1;
+                }
+                let number: String = self.chars[start..self.pos].iter().collect();
+                format!("<mn>{}</mn>", number)
+            }
+            '~' => {
+                self.pos += 1;
+                "<mspace width=\"0.25em\"/>".to_string()
+            }
+            c if c.is_alphabetic() => {
+                self.pos += 1;
+                let letter = match self.variant {
+                    Some(variant) => styled(c, variant),
+                    None => c.to_string(),
+                };
+                format!("<mi>{}</mi>", escape(&letter))
+            }
+            c => {
+                self.pos += 1;
+                let op = match c {
+                    '-' => '−',
+                    '*' => '∗',
+                    c => c,
+                };
+                mo(&op.to_string())
+            }
+        };
+        Ok(Some((atom, false)))
+    }
+
+    fn parse_command(&mut self) -> Result<Option<(String, bool)>, MathError> {
+        let name = self.read_command();
+        let lookup = |table: &[(&str, &str)]| {
+            table
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, s)| s.to_string())
+        };
+
+        if let Some((_, c)) = GREEK.iter().find(|(n, _)| *n == name) {
+            // Capital Greek letters are upright
+            let attribute = if c.is_uppercase() {
+                " mathvariant=\"normal\""
+            } else {
+                ""
+            };
+            return Ok(Some((format!("<mi{}>{}</mi>", attribute, c), false)));
+        }
+        if let Some(symbol) = lookup(&IDENTIFIERS) {
+            return Ok(Some((
+                format!("<mi mathvariant=\"normal\">{}</mi>", symbol),
+                false,
+            )));
+        }
+        if let Some(symbol) = lookup(&OPERATORS) {
+            return Ok(Some((mo(&symbol), false)));
+        }
+        if let Some(width) = lookup(&SPACES) {
+            return Ok(Some((format!("<mspace width=\"{}\"/>", width), false)));
+        }
+        if let Some((_, symbol, limits)) = BIG_OPERATORS.iter().find(|(n, _, _)| *n == name) {
+            let op = if self.display {
+                format!(
+                    "<mo largeop=\"true\" movablelimits=\"false\">{}</mo>",
+                    symbol
+                )
+            } else {
+                mo(symbol)
+            };
+            return Ok(Some((op, *limits)));
+        }
+        if let Some((_, limits)) = FUNCTIONS.iter().find(|(n, _)| *n == name) {
+            return Ok(Some((format!("<mi>{}</mi>", name), *limits)));
+        }
+        if let Some((_, mark, under)) = ACCENTS.iter().find(|(n, _, _)| *n == name) {
+            let base = self.parse_argument(&name)?;
+            let element = if *under {
+                format!(
+                    "<munder accentunder=\"true\">{}<mo>{}</mo></munder>",
+                    base, mark
+                )
+            } else {
+                format!("<mover accent=\"true\">{}<mo>{}</mo></mover>", base, mark)
+            };
+            return Ok(Some((element, false)));
+        }
+        if IGNORED.contains(&name.as_str()) {
+            return Ok(None);
+        }
+
+        let element = match name.as_str() {
+            // Row separator outside a table, e.g. inside braces
+            "\\" => return Ok(None),
+            "{" | "}" | "%" | "$" | "#" | "&" | "_" | "|" => {
+                mo(if name == "|" { "‖" } else { &name })
+            }
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let numerator = self.parse_argument(&name)?;
+                let denominator = self.parse_argument(&name)?;
+                format!("<mfrac>{}{}</mfrac>", numerator, denominator)
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let n = self.parse_argument(&name)?;
+                let k = self.parse_argument(&name)?;
+                format!(
+                    "<mrow>{}<mfrac linethickness=\"0\">{}{}</mfrac>{}</mrow>",
+                    stretchy("(", "prefix"),
+                    n,
+                    k,
+                    stretchy(")", "postfix")
+                )
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                if self.eat('[') {
+                    let index = self.parse_group(Stop::Bracket)?;
+                    let radicand = self.parse_argument(&name)?;
+                    format!("<mroot>{}{}</mroot>", radicand, index)
+                } else {
+                    format!("<msqrt>{}</msqrt>", self.parse_argument(&name)?)
+                }
+            }
+            "text" | "textrm" | "textit" | "textbf" | "textsf" | "texttt" | "mbox" => {
+                let text = self.read_raw_group(&name)?;
+                format!("<mtext>{}</mtext>", escape(&text))
+            }
+            "mathrm" | "operatorname" => {
+                let text = self.read_raw_group(&name)?;
+                let text = text.trim();
+                let attribute = if text.chars().count() == 1 {
+                    " mathvariant=\"normal\""
+                } else {
+                    ""
+                };
+                format!("<mi{}>{}</mi>", attribute, escape(text))
+            }
+            "mathbb" | "mathbf" | "boldsymbol" | "bm" | "mathcal" | "mathscr" | "mathfrak"
+            | "mathsf" | "mathit" => {
+                let variant = match name.as_str() {
+                    "mathbb" => Some(Variant::DoubleStruck),
+                    "mathcal" | "mathscr" => Some(Variant::Script),
+                    "mathfrak" => Some(Variant::Fraktur),
+                    "mathsf" => Some(Variant::SansSerif),
+                    "mathit" => None,
+                    _ => Some(Variant::Bold),
+                };
+                let previous = std::mem::replace(&mut self.variant, variant);
+                let argument = self.parse_argument(&name);
+                self.variant = previous;
+                argument?
+            }
+            "left" => {
+                let open = self.read_delimiter(&name)?;
+                let items = self.parse_items(Stop::Right)?;
+                self.read_command();
+                let close = self.read_delimiter("right")?;
+                format!(
+                    "<mrow>{}{}{}</mrow>",
+                    stretchy(&open, "prefix"),
+                    items.concat(),
+                    stretchy(&close, "postfix")
+                )
+            }
+            "right" => return Err(MathError::UnbalancedBraces),
+            "big" | "Big" | "bigg" | "Bigg" | "bigl" | "Bigl" | "biggl" | "Biggl" | "bigr"
+            | "Bigr" | "biggr" | "Biggr" | "bigm" | "Bigm" => {
+                let size = match name.trim_end_matches(['l', 'r', 'm']) {
+                    "big" => "1.2em",
+                    "Big" => "1.8em",
+                    "bigg" => "2.4em",
+                    _ => "3em",
+                };
+                let delimiter = self.read_delimiter(&name)?;
+                format!(
+                    "<mo minsize=\"{0}\" maxsize=\"{0}\">{1}</mo>",
+                    size,
+                    escape(&delimiter)
+                )
+            }
+            "overbrace" | "underbrace" => {
+                let base = self.parse_argument(&name)?;
+                let element = if name == "overbrace" {
+                    format!("<mover>{}<mo stretchy=\"true\">⏞</mo></mover>", base)
+                } else {
+                    format!("<munder>{}<mo stretchy=\"true\">⏟</mo></munder>", base)
+                };
+                return Ok(Some((element, true)));
+            }
+            "not" => {
+                let negated = self.parse_argument(&name)?;
+                match negated.strip_suffix("</mo>") {
+                    Some(op) => format!("{}\u{338}</mo>", op),
+                    None => negated,
+                }
+            }
+            "boxed" => format!(
+                "<mrow style=\"border: 1px solid; padding: 0.2em;\">{}</mrow>",
+                self.parse_argument(&name)?
+            ),
+            "color" => {
+                self.read_raw_group(&name)?;
+                return Ok(None);
+            }
+            "textcolor" => {
+                self.read_raw_group(&name)?;
+                self.parse_argument(&name)?
+            }
+            "label" | "tag" => {
+                self.read_raw_group(&name)?;
+                return Ok(None);
+            }
+            "bmod" => "<mo>mod</mo>".to_string(),
+            "pmod" => format!(
+                "<mrow><mo>(</mo><mi>mod</mi><mspace width=\"0.3333em\"/>{}<mo>)</mo></mrow>",
+                self.parse_argument(&name)?
+            ),
+            "begin" => self.parse_environment()?,
+            _ => format!("<merror><mtext>\\{}</mtext></merror>", escape(&name)),
+        };
+        Ok(Some((element, false)))
+    }
+
+    /// The delimiter after `\left`, `\right` or `\big`; `.` means none
+    fn read_delimiter(&mut self, command: &str) -> Result<String, MathError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Err(MathError::MissingArgument(command.to_string())),
+            Some('\\') => {
+                let name = self.read_command();
+                Ok(match name.as_str() {
+                    "{" | "lbrace" => "{".to_string(),
+                    "}" | "rbrace" => "}".to_string(),
+                    "|" | "Vert" => "‖".to_string(),
+                    "vert" | "lvert" | "rvert" => "|".to_string(),
+                    "lVert" | "rVert" => "‖".to_string(),
+                    _ => OPERATORS
+                        .iter()
+                        .find(|(n, _)| *n == name)
+                        .map(|(_, s)| s.to_string())
+                        .unwrap_or_default(),
+                })
+            }
+            Some('.') => {
+                self.pos += 1;
+                Ok(String::new())
+            }
+            Some(c) => {
+                self.pos += 1;
+                Ok(c.to_string())
+            }
+        }
+    }
+
+    fn parse_environment(&mut self) -> Result<String, MathError> {
+        let name = self.read_raw_group("begin")?;
+        if name == "array" {
+            // Column spec
+            self.read_raw_group("array")?;
+        }
+        let rows = self.parse_rows()?;
+        if !self.at_command("end") {
+            return Err(MathError::MissingEnd(name));
+        }
+        self.read_command();
+        if self.read_raw_group("end")? != name {
+            return Err(MathError::MissingEnd(name));
+        }
+
+        let (open, close) = match name.as_str() {
+            "pmatrix" => ("(", ")"),
+            "bmatrix" => ("[", "]"),
+            "Bmatrix" => ("{", "}"),
+            "cases" => ("{", ""),
+            "vmatrix" => ("|", "|"),
+            "Vmatrix" => ("‖", "‖"),
+            _ => ("", ""),
+        };
+        let align = match name.as_str() {
+            "cases" => "left",
+            "aligned" | "align" | "align*" | "split" | "eqnarray" => "right left",
+            _ => "center",
+        };
+        let table = table(&rows, align);
+        if open.is_empty() && close.is_empty() {
+            Ok(table)
+        } else {
+            Ok(format!(
+                "<mrow>{}{}{}</mrow>",
+                stretchy(open, "prefix"),
+                table,
+                stretchy(close, "postfix")
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inline(latex: &str) -> String {
+        latex_to_mathml(latex, false).unwrap()
+    }
+
+    #[test]
+    fn test_structures() {
+        assert_eq!(
+            inline(r"\frac{a}{b} + \sqrt{x^2}"),
+            "<math display=\"inline\"><mrow><mfrac><mi>a</mi><mi>b</mi></mfrac><mo>+</mo>\
+             <msqrt><msup><mi>x</mi><mn>2</mn></msup></msqrt></mrow></math>"
+        );
+        assert_eq!(
+            latex_to_mathml(r"\sum_{i=1}^n i", true).unwrap(),
+            "<math display=\"block\"><mrow><munderover><mo largeop=\"true\" movablelimits=\"false\">∑</mo>\
+             <mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></munderover><mi>i</mi></mrow></math>"
+        );
+        assert!(inline(r"\sum_{i=1}^n i").contains("<msubsup>"));
+        assert!(inline(r"x \in \mathbb{R}, \alpha \le 3.14")
+            .contains("<mi>ℝ</mi><mo>,</mo><mi>α</mi><mo>≤</mo><mn>3.14</mn>"));
+        assert!(inline(r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}")
+            .contains("<mtable columnalign=\"center\"><mtr><mtd><mn>1</mn></mtd><mtd><mn>2</mn></mtd></mtr>"));
+        assert!(inline(r"\left( \frac{1}{2} \right)").starts_with(
+            "<math display=\"inline\"><mrow><mo stretchy=\"true\" form=\"prefix\">(</mo>"
+        ));
+    }
+
+    #[test]
+    fn test_escaping_and_errors() {
+        assert_eq!(
+            inline(r"a < b \text{<b>x</b>}"),
+            "<math display=\"inline\"><mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi>\
+             <mtext>&lt;b&gt;x&lt;/b&gt;</mtext></mrow></math>"
+        );
+        assert!(inline(r"\foo x").contains("<merror><mtext>\\foo</mtext></merror>"));
+        assert_eq!(
+            latex_to_mathml(r"\frac{a", false),
+            Err(MathError::UnbalancedBraces)
+        );
+        assert_eq!(
+            latex_to_mathml(r"a}", false),
+            Err(MathError::UnbalancedBraces)
+        );
+        assert_eq!(
+            latex_to_mathml(r"\frac", false),
+            Err(MathError::MissingArgument("frac".into()))
+        );
+        assert_eq!(
+            latex_to_mathml(r"\left( x", false),
+            Err(MathError::MissingRight)
+        );
+        assert_eq!(
+            latex_to_mathml(r"\begin{cases} x \end{matrix}", false),
+            Err(MathError::MissingEnd("cases".into()))
+        );
+    }
+}
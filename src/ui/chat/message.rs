@@ -1,6 +1,14 @@
 //! Message display components with Markdown rendering
 
 use crate::app::AppState;
+use crate::ui::chat::diagram::MermaidBlock;
+use crate::ui::chat::feedback::FeedbackBar;
+use crate::ui::chat::logprobs::LogprobsView;
+use crate::ui::chat::math::latex_to_mathml;
+use crate::ui::chat::stop_reason::StopReasonBadge;
+use crate::storage::attachments::{find_image_references, image_data_uri, resolve_image, ImageSource};
+use crate::storage::conversations::{duplicate_conversation, list_conversations};
+use crate::types::message::GenerationMeta;
 use dioxus::prelude::*;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -14,6 +22,21 @@ pub enum MessageRole {
 pub struct Message {
     pub role: MessageRole,
     pub content: String,
+    /// Seconds since the Unix epoch; 0 when unknown
+    pub timestamp: u64,
+    /// Model and settings behind an assistant reply
+    pub generation: Option<GenerationMeta>,
+}
+
+impl Message {
+    pub fn new(role: MessageRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            timestamp: crate::types::message::unix_now(),
+            generation: None,
+        }
+    }
 }
 
 // Convert storage Message to UI Message
@@ -26,6 +49,8 @@ impl From<crate::types::message::Message> for Message {
                 crate::types::message::Role::System => MessageRole::System,
             },
             content: msg.content,
+            timestamp: msg.timestamp,
+            generation: msg.generation,
         }
     }
 }
@@ -33,20 +58,22 @@ impl From<crate::types::message::Message> for Message {
 // Convert UI Message to storage Message
 impl From<Message> for crate::types::message::Message {
     fn from(msg: Message) -> Self {
-        crate::types::message::Message::new(
-            match msg.role {
+        crate::types::message::Message {
+            role: match msg.role {
                 MessageRole::User => crate::types::message::Role::User,
                 MessageRole::Assistant => crate::types::message::Role::Assistant,
                 MessageRole::System => crate::types::message::Role::System,
             },
-            msg.content,
-        )
+            content: msg.content,
+            timestamp: msg.timestamp,
+            generation: msg.generation,
+        }
     }
 }
 
 // Content parts for parsed message content
 #[derive(Clone, PartialEq, Debug)]
-enum ContentPart {
+pub enum ContentPart {
     Text(String),
     Thinking(String),          // Completed <think>...</think> block
     ThinkingStreaming(String), // Open <think> block still being generated
@@ -56,7 +83,7 @@ enum ContentPart {
 /// Supports both <think>...</think> and <thinking>...</thinking> tags.
 /// Incomplete tags are rendered as live streaming blocks.
 /// Also strips <request>...</request> tags (rendered as normal text).
-fn parse_thinking_blocks(content: &str) -> Vec<ContentPart> {
+pub fn parse_thinking_blocks(content: &str) -> Vec<ContentPart> {
     // First: strip <request>...</request> tags, keeping inner content as normal text
     let cleaned = strip_xml_tags(content, "request");
 
@@ -142,14 +169,18 @@ fn ThinkingBlock(content: String) -> Element {
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
     let mut is_expanded = use_signal(|| false);
+    // Held open while a search match inside it is current
+    let search_open = try_use_context::<MessageIndex>()
+        .is_some_and(|MessageIndex(idx)| *app_state.search_focus.read() == Some(idx));
+    let expanded = is_expanded() || search_open;
 
-    let chevron_class = if is_expanded() {
+    let chevron_class = if expanded {
         "thinking-chevron expanded"
     } else {
         "thinking-chevron"
     };
 
-    let content_class = if is_expanded() {
+    let content_class = if expanded {
         "thinking-content expanded"
     } else {
         "thinking-content"
@@ -188,6 +219,25 @@ fn ThinkingBlock(content: String) -> Element {
     }
 }
 
+/// "Processing context… 43%" in a reply still waiting for its first token
+#[component]
+fn PrefillLine() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let Some((processed, total)) = (app_state.prefill_progress)() else {
+        return rsx! {};
+    };
+    let percent = processed as u64 * 100 / total.max(1) as u64;
+
+    rsx! {
+        p {
+            class: "text-xs text-[var(--text-tertiary)] animate-pulse",
+            title: "{processed} / {total} tokens",
+            if is_en { "Processing context… {percent}%" } else { "Traitement du contexte… {percent} %" }
+        }
+    }
+}
+
 /// Streaming thinking block - elegant, subtle with soft animation
 #[component]
 fn ThinkingBlockStreaming(content: String) -> Element {
@@ -244,7 +294,7 @@ fn ThinkingBlockStreaming(content: String) -> Element {
 
 /// Markdown content renderer
 #[component]
-fn MarkdownContent(content: String) -> Element {
+pub fn MarkdownContent(content: String) -> Element {
     let blocks = parse_markdown_blocks(&content);
 
     rsx! {
@@ -302,15 +352,21 @@ fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
             continue;
         }
 
-        // Math block $$...$$
-        if trimmed.starts_with("$$") {
-            let first_line_content = trimmed.trim_start_matches('$').trim();
+        // Math block $$...$$ or \[...\]
+        let math_delimiters = if trimmed.starts_with("$$") {
+            Some(("$$", "$$"))
+        } else if trimmed.starts_with("\\[") {
+            Some(("\\[", "\\]"))
+        } else {
+            None
+        };
+        if let Some((open, close)) = math_delimiters {
+            let first_line_content = trimmed[open.len()..].trim();
             let mut math_lines = Vec::new();
 
-            if first_line_content.ends_with("$$") {
+            if let Some(math) = first_line_content.strip_suffix(close) {
                 // Single line math block
-                let math = first_line_content.trim_end_matches('$').trim();
-                blocks.push(MarkdownBlock::MathBlock(math.to_string()));
+                blocks.push(MarkdownBlock::MathBlock(math.trim().to_string()));
                 i += 1;
                 continue;
             }
@@ -321,8 +377,8 @@ fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
             i += 1;
             while i < lines.len() {
                 let l = lines[i];
-                if l.trim().contains("$$") {
-                    let before_end = l.trim().trim_end_matches('$').trim();
+                if let Some(end) = l.find(close) {
+                    let before_end = l[..end].trim();
                     if !before_end.is_empty() {
                         math_lines.push(before_end.to_string());
                     }
@@ -483,6 +539,8 @@ fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
                 || t.starts_with("- ")
                 || t.starts_with("* ")
                 || t.starts_with("> ")
+                || t.starts_with("$$")
+                || t.starts_with("\\[")
             {
                 break;
             }
@@ -518,6 +576,9 @@ fn render_block(block: MarkdownBlock) -> Element {
                 }
             }
         }
+        MarkdownBlock::CodeBlock(lang, code) if lang.trim() == "mermaid" => rsx! {
+            MermaidBlock { source: code }
+        },
         MarkdownBlock::CodeBlock(lang, code) => rsx! {
             div { class: "my-3 rounded-xl overflow-hidden border border-[var(--border-subtle)]",
                 style: "background: #121110;",
@@ -558,12 +619,18 @@ fn render_block(block: MarkdownBlock) -> Element {
                 }
             }
         },
-        MarkdownBlock::MathBlock(math) => rsx! {
-            div { class: "my-4 p-4 rounded-xl bg-[var(--bg-tertiary)]/50 border border-[var(--border-subtle)] overflow-x-auto",
-                pre { class: "font-mono text-sm text-[var(--accent-primary)] text-center whitespace-pre-wrap",
-                    "{math}"
+        MarkdownBlock::MathBlock(math) => match latex_to_mathml(&math, true) {
+            Ok(mathml) => rsx! {
+                div { class: "math-block my-4 overflow-x-auto", dangerous_inner_html: "{mathml}" }
+            },
+            // Not parseable: show the source rather than a broken formula
+            Err(_) => rsx! {
+                div { class: "my-4 p-4 rounded-xl bg-[var(--bg-tertiary)]/50 border border-[var(--border-subtle)] overflow-x-auto",
+                    pre { class: "font-mono text-sm text-[var(--accent-primary)] text-center whitespace-pre-wrap",
+                        "{math}"
+                    }
                 }
-            }
+            },
         },
         MarkdownBlock::HorizontalRule => rsx! {
             hr { class: "border-none h-px bg-[var(--border-subtle)] my-6" }
@@ -625,6 +692,7 @@ enum InlineSegment {
     Code(String),
     Link(String, String), // (text, url)
     InlineMath(String),
+    Citation(String), // [tool_name] source citation
 }
 
 fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
@@ -655,9 +723,36 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
             }
         }
 
-        // Inline math $...$
-        if chars[i] == '$' && !matches!(chars.get(i + 1), Some('$')) {
-            if let Some(close_offset) = chars[i + 1..].iter().position(|&c| c == '$') {
+        // Inline math \(...\)
+        if chars[i] == '\\' && chars.get(i + 1) == Some(&'(') {
+            let close = (i + 2..chars.len().saturating_sub(1))
+                .find(|&j| chars[j] == '\\' && chars[j + 1] == ')');
+            if let Some(end) = close {
+                if !current_text.is_empty() {
+                    segments.push(InlineSegment::Text(current_text.clone()));
+                    current_text.clear();
+                }
+                let math: String = chars[i + 2..end].iter().collect();
+                segments.push(InlineSegment::InlineMath(math));
+                i = end + 2;
+                continue;
+            }
+        }
+
+        // Inline math $...$; like pandoc, "$5 and $10" is not math: the
+        // opening $ must touch its content and the closing one can't precede a digit
+        if chars[i] == '$'
+            && !matches!(chars.get(i + 1), Some('$'))
+            && chars.get(i + 1).is_some_and(|c| !c.is_whitespace())
+        {
+            let close_offset = chars[i + 1..].iter().enumerate().position(|(k, &c)| {
+                let j = i + 1 + k;
+                c == '$'
+                    && k > 0
+                    && !chars[j - 1].is_whitespace()
+                    && !chars.get(j + 1).is_some_and(|n| n.is_ascii_digit())
+            });
+            if let Some(close_offset) = close_offset {
                 if !current_text.is_empty() {
                     segments.push(InlineSegment::Text(current_text.clone()));
                     current_text.clear();
@@ -757,6 +852,10 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
                 }
                 if i < chars.len() {
                     let url: String = chars[url_start..i].iter().collect();
+                    // Markdown image ![alt](src): shown in the gallery under the message
+                    if current_text.ends_with('!') {
+                        current_text.pop();
+                    }
                     if !current_text.is_empty() {
                         segments.push(InlineSegment::Text(current_text.clone()));
                         current_text.clear();
@@ -765,6 +864,18 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
                     i += 1;
                     continue;
                 }
+            } else if i < chars.len() {
+                // Citation [tool_name] (the [source] convention from the agent prompt)
+                let label: String = chars[text_start..i].iter().collect();
+                if is_citation_label(&label) {
+                    if !current_text.is_empty() {
+                        segments.push(InlineSegment::Text(current_text.clone()));
+                        current_text.clear();
+                    }
+                    segments.push(InlineSegment::Citation(label));
+                    i += 1;
+                    continue;
+                }
             }
             // Not a valid link, backtrack
             i = bracket_start;
@@ -805,14 +916,97 @@ fn render_segment(segment: InlineSegment) -> Element {
                 "{text}"
             }
         },
-        InlineSegment::InlineMath(math) => rsx! {
-            code { class: "px-1.5 py-0.5 rounded-md bg-[var(--accent-primary)]/10 text-[var(--accent-primary)] font-mono text-[0.9em] italic", "{math}" }
+        InlineSegment::InlineMath(math) => match latex_to_mathml(&math, false) {
+            Ok(mathml) => rsx! {
+                span { class: "math-inline", dangerous_inner_html: "{mathml}" }
+            },
+            Err(_) => rsx! {
+                code { class: "px-1.5 py-0.5 rounded-md bg-[var(--accent-primary)]/10 text-[var(--accent-primary)] font-mono text-[0.9em] italic", "{math}" }
+            },
+        },
+        InlineSegment::Citation(tool) => rsx! {
+            CitationChip { tool: tool }
         },
     }
 }
 
+/// Check if a bracketed label looks like a tool name citation (e.g. `file_read`)
+fn is_citation_label(label: &str) -> bool {
+    let len = label.chars().count();
+    (2..=64).contains(&len)
+        && label.starts_with(|c: char| c.is_ascii_lowercase())
+        && label
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+/// Index of the message currently being rendered, so citations can resolve
+/// the tool calls that happened before it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct MessageIndex(usize);
+
+/// Find the most recent successful call of `tool` before message `before`
+fn find_cited_tool_call(messages: &[Message], before: usize, tool: &str) -> Option<usize> {
+    let end = before.min(messages.len());
+    (0..end).rev().find(|&idx| {
+        let msg = &messages[idx];
+        msg.role == MessageRole::Assistant
+            && is_tool_message(&msg.content) == Some(ToolMessageType::Result)
+            && extract_tool_name(&msg.content).as_deref() == Some(tool)
+    })
+}
+
+/// Inline citation chip linking a claim to the tool result it was based on.
+/// Resolved citations jump to the tool card and expand its raw result;
+/// citations of tools that never ran in this conversation are flagged as unverified.
+#[component]
+fn CitationChip(tool: String) -> Element {
+    let mut app_state = use_context::<AppState>();
+    let message_index = try_use_context::<MessageIndex>();
+    let is_en = app_state.settings.read().language == "en";
+
+    let target = message_index
+        .and_then(|MessageIndex(idx)| find_cited_tool_call(&app_state.active_messages.read(), idx, &tool));
+
+    // Not a tool at all: render the brackets as plain text
+    if target.is_none() && app_state.agent.tool_registry.get(&tool).is_none() {
+        return rsx! { "[{tool}]" };
+    }
+
+    match target {
+        Some(call_idx) => {
+            let title = if is_en { "Show the tool result behind this claim" } else { "Voir le resultat d'outil a l'origine de cette affirmation" };
+            rsx! {
+                button {
+                    class: "inline-flex items-center gap-1 px-1.5 rounded-md font-mono text-[0.8em] align-baseline hover:underline",
+                    style: "background: var(--success-bg); color: var(--success);",
+                    title: "{title}",
+                    onclick: move |_| {
+                        app_state.focused_tool_call.set(Some(call_idx));
+                        document::eval(&format!(
+                            "document.getElementById('tool-call-{call_idx}')?.scrollIntoView({{behavior: 'smooth', block: 'center'}})"
+                        ));
+                    },
+                    "✓ {tool}"
+                }
+            }
+        }
+        None => {
+            let title = if is_en { "Unverified: no matching tool result in this conversation" } else { "Non verifie : aucun resultat d'outil correspondant dans cette conversation" };
+            rsx! {
+                span {
+                    class: "inline-flex items-center gap-1 px-1.5 rounded-md font-mono text-[0.8em] align-baseline",
+                    style: "border: 1px dashed var(--warning); color: var(--warning);",
+                    title: "{title}",
+                    "? {tool}"
+                }
+            }
+        }
+    }
+}
+
 /// Check if content is a tool-related message
-fn is_tool_message(content: &str) -> Option<ToolMessageType> {
+pub fn is_tool_message(content: &str) -> Option<ToolMessageType> {
     let trimmed = content.trim();
 
     // Detect by leading emoji
@@ -847,7 +1041,7 @@ fn is_tool_message(content: &str) -> Option<ToolMessageType> {
 }
 
 #[derive(Clone, PartialEq, Debug)]
-enum ToolMessageType {
+pub enum ToolMessageType {
     InProgress,
     PermissionRequired,
     PermissionDenied,
@@ -925,11 +1119,37 @@ fn extract_permission_level(content: &str) -> Option<String> {
 
 /// Premium tool status card component - ultra minimal design
 #[component]
-fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
+fn ToolCard(
+    message_type: ToolMessageType,
+    content: String,
+    index: usize,
+    raw_result: Option<String>,
+) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut is_expanded = use_signal(|| false);
     let tool_name = extract_tool_name(&content).unwrap_or_else(|| "tool".to_string());
     let detail = extract_detail(&content);
     let duration = extract_duration(&content);
 
+    let is_en = app_state.settings.read().language == "en";
+
+    // Images the tool produced or read (screenshots, charts)
+    let images = if message_type == ToolMessageType::Result {
+        let mut images = find_image_references(&content);
+        for reference in raw_result.as_deref().map(find_image_references).unwrap_or_default() {
+            if !images.contains(&reference) {
+                images.push(reference);
+            }
+        }
+        images
+    } else {
+        Vec::new()
+    };
+
+    // Results served from a cache are prefixed with ♻️
+    let from_cache = detail.as_deref().is_some_and(|d| d.starts_with('♻'));
+    let detail = detail.map(|d| d.trim_start_matches(['♻', '\u{fe0f}', ' ']).to_string());
+
     // Minimal accent colors using CSS variables
     let (accent_var, status_icon) = match message_type {
         ToolMessageType::InProgress => ("var(--accent-primary)", "●"),
@@ -941,10 +1161,22 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
     };
 
     let show_spinner = message_type == ToolMessageType::InProgress;
+    // Live progress reported by streaming tools, on the lines after the header
+    let live_progress = if show_spinner {
+        content.split_once('\n').map(|(_, rest)| rest.trim_end().to_string()).filter(|p| !p.is_empty())
+    } else {
+        None
+    };
     let is_success = message_type == ToolMessageType::Result;
     let is_error =
         message_type == ToolMessageType::Error || message_type == ToolMessageType::PermissionDenied;
 
+    // Expanded either by the user or by clicking a citation that points here
+    let is_focused = *app_state.focused_tool_call.read() == Some(index);
+    let show_raw = raw_result.is_some() && (is_expanded() || is_focused);
+    let row_cursor = if raw_result.is_some() { "cursor: pointer;" } else { "" };
+    let focus_ring = if is_focused { " box-shadow: 0 0 0 1px var(--success);" } else { "" };
+
     // Compute duration style outside rsx for type inference
     let duration_style = if is_success {
         "color: var(--success);"
@@ -956,6 +1188,7 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
 
     rsx! {
         div {
+            id: "tool-call-{index}",
             class: "animate-fade-in",
             style: "margin: 0.35rem 0;",
 
@@ -963,9 +1196,10 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
             div {
                 class: "flex items-center gap-2",
                 style: format!(
-                    "padding: 0.4rem 0.5rem; border-left: 2px solid {}; background: linear-gradient(90deg, rgba(42,107,124,0.03) 0%, transparent 100%); border-radius: 0 8px 8px 0;",
-                    accent_var
+                    "padding: 0.4rem 0.5rem; border-left: 2px solid {}; background: linear-gradient(90deg, rgba(42,107,124,0.03) 0%, transparent 100%); border-radius: 0 8px 8px 0;{}{}",
+                    accent_var, row_cursor, focus_ring
                 ),
+                onclick: move |_| is_expanded.set(!is_expanded()),
 
                 // Status indicator - dot or spinner
                 if show_spinner {
@@ -1011,6 +1245,15 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
                 // Right side - duration only (no verbose labels)
                 div { class: "flex-1" } // spacer
 
+                if from_cache {
+                    span {
+                        class: "font-mono text-[10px] px-1.5 rounded",
+                        style: "color: var(--accent-primary); background: var(--accent-soft);",
+                        title: if is_en { "Served from cache" } else { "Résultat servi depuis le cache" },
+                        "♻ cache"
+                    }
+                }
+
                 if let Some(ref dur) = duration {
                     span {
                         class: "font-mono text-[10px]",
@@ -1018,13 +1261,238 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
                         "{dur}"
                     }
                 }
+
+                if show_spinner && app_state.active_tool_cancel.read().is_some() {
+                    button {
+                        class: "text-[10px] px-1.5 rounded transition-colors hover:opacity-80",
+                        style: "color: var(--error); border: 1px solid var(--error);",
+                        title: if is_en { "Cancel this tool" } else { "Annuler cet outil" },
+                        onclick: move |evt: MouseEvent| {
+                            evt.stop_propagation();
+                            if let Some(cancel) = app_state.active_tool_cancel.read().as_ref() {
+                                cancel.cancel();
+                            }
+                        },
+                        if is_en { "Cancel" } else { "Annuler" }
+                    }
+                }
+            }
+
+            if let Some(ref progress) = live_progress {
+                pre {
+                    class: "text-[11px] font-mono whitespace-pre-wrap overflow-x-auto",
+                    style: "margin: 0.25rem 0 0 0.5rem; padding: 0.4rem 0.75rem; color: var(--text-tertiary); border-left: 2px solid var(--border-subtle);",
+                    "{progress}"
+                }
+            }
+
+            if !images.is_empty() {
+                div { style: "margin: 0.25rem 0 0 0.5rem;",
+                    MessageImages { references: images }
+                }
+            }
+
+            // Exact result the model received (what citations point to)
+            if show_raw {
+                if let Some(ref raw) = raw_result {
+                    pre {
+                        class: "text-[11px] font-mono whitespace-pre-wrap overflow-x-auto max-h-64 overflow-y-auto custom-scrollbar",
+                        style: "margin: 0.25rem 0 0 0.5rem; padding: 0.5rem 0.75rem; color: var(--text-secondary); background: var(--bg-tertiary); border-radius: 8px;",
+                        "{raw}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Images referenced by a message, resolved against the conversation's
+/// stored copies; missing local files are skipped
+#[component]
+fn MessageImages(references: Vec<String>) -> Element {
+    let app_state = use_context::<AppState>();
+    let sources: Vec<(String, ImageSource)> = {
+        let conversation = app_state.current_conversation.read();
+        let id = conversation.as_ref().map(|c| c.id.as_str());
+        let attachments = conversation.as_ref().map(|c| c.attachments.as_slice()).unwrap_or_default();
+        references
+            .iter()
+            .filter_map(|r| resolve_image(id, attachments, r).map(|source| (r.clone(), source)))
+            .collect()
+    };
+
+    rsx! {
+        div { class: "flex flex-wrap gap-2 my-2",
+            for (reference, source) in sources {
+                ImageThumb { key: "{reference}", reference: reference.clone(), source }
             }
         }
     }
 }
 
+/// Thumbnail with click-to-zoom; remote images load only when asked to
 #[component]
-pub fn MessageBubble(message: Message) -> Element {
+fn ImageThumb(reference: String, source: ImageSource) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut zoomed = use_signal(|| false);
+    let mut load_remote = use_signal(|| false);
+    // Local files are read once per thumbnail, not on every render
+    let local_source = source.clone();
+    let src = use_memo(move || match &local_source {
+        ImageSource::Local(path) => image_data_uri(path),
+        ImageSource::Remote(url) => Some(url.clone()),
+    });
+    let name = reference.rsplit(['/', '\\']).next().unwrap_or(&reference).to_string();
+
+    if let ImageSource::Remote(url) = &source {
+        if !load_remote() {
+            let host = url.split('/').nth(2).unwrap_or_default().to_string();
+            return rsx! {
+                button {
+                    class: "px-3 py-2 rounded-lg text-xs transition-colors bg-[var(--bg-tertiary)] text-[var(--text-secondary)] hover:text-[var(--text-primary)]",
+                    title: "{url}",
+                    onclick: move |_| load_remote.set(true),
+                    if is_en { "Show image from {host}" } else { "Afficher l'image de {host}" }
+                }
+            };
+        }
+    }
+
+    let Some(src) = src() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        img {
+            class: "max-h-64 max-w-full rounded-lg cursor-zoom-in border border-[var(--border-subtle)] object-contain",
+            src: "{src}",
+            alt: "{name}",
+            title: "{reference}",
+            onclick: move |_| zoomed.set(true),
+        }
+        if zoomed() {
+            div {
+                class: "fixed inset-0 z-50 flex items-center justify-center p-8 cursor-zoom-out animate-fade-in",
+                style: "background: rgba(0, 0, 0, 0.8);",
+                onclick: move |_| zoomed.set(false),
+                img {
+                    class: "max-w-full max-h-full rounded-lg object-contain",
+                    src: "{src}",
+                    alt: "{name}",
+                }
+            }
+        }
+    }
+}
+
+/// "Continue from here": copies the conversation up to this message into a
+/// new one, leaving the original untouched
+#[component]
+fn BranchButton(index: usize) -> Element {
+    let mut app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+
+    if (app_state.is_generating)() {
+        return rsx! {};
+    }
+
+    rsx! {
+        button {
+            class: "opacity-0 group-hover:opacity-100 transition-opacity inline-flex items-center gap-1 px-1.5 py-0.5 rounded-md text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06]",
+            title: if is_en { "Continue from this message in a new conversation" } else { "Continuer a partir de ce message dans une nouvelle conversation" },
+            onclick: move |_| {
+                let Some(conversation) = app_state.current_conversation.read().clone() else {
+                    return;
+                };
+                match duplicate_conversation(&conversation, Some(index)) {
+                    Ok(copy) => {
+                        app_state.current_conversation.set(Some(copy));
+                        if let Ok(conversations) = list_conversations() {
+                            app_state.conversations.set(conversations);
+                        }
+                    }
+                    Err(e) => tracing::error!("Failed to duplicate conversation: {}", e),
+                }
+            },
+            svg {
+                width: "11", height: "11", view_box: "0 0 24 24", fill: "none",
+                stroke: "currentColor", stroke_width: "2", stroke_linecap: "round", stroke_linejoin: "round",
+                line { x1: "6", y1: "3", x2: "6", y2: "15" }
+                circle { cx: "18", cy: "6", r: "3" }
+                circle { cx: "6", cy: "18", r: "3" }
+                path { d: "M18 9a9 9 0 0 1-9 9" }
+            }
+            if is_en { "Branch" } else { "Bifurquer" }
+        }
+    }
+}
+
+/// Send time, and for replies the model and settings, shown on hover; a
+/// routed reply also keeps a chip naming its model
+#[component]
+fn MessageDetails(timestamp: u64, generation: Option<GenerationMeta>) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut open = use_signal(|| false);
+
+    let Some(time) = chrono::DateTime::from_timestamp(timestamp as i64, 0).filter(|_| timestamp > 0) else {
+        return rsx! {};
+    };
+    let time = time.with_timezone(&chrono::Local);
+    let short = time.format("%H:%M").to_string();
+    // Routed replies always show the model that answered
+    let routed = generation.clone().filter(|meta| meta.route.is_some());
+    let full = if is_en {
+        time.format("%Y-%m-%d %H:%M:%S").to_string()
+    } else {
+        time.format("%d/%m/%Y %H:%M:%S").to_string()
+    };
+
+    rsx! {
+        if let Some(meta) = routed {
+            span {
+                class: "px-2 py-0.5 rounded-full text-[10px] bg-white/[0.06] text-[var(--text-tertiary)] truncate max-w-[14rem]",
+                title: "{meta.describe(is_en)}",
+                "🔀 {meta.model}"
+            }
+        }
+        div { class: "relative inline-flex items-center gap-1 opacity-0 group-hover:opacity-100 transition-opacity text-[11px] text-[var(--text-tertiary)]",
+            span { title: "{full}", "{short}" }
+            if let Some(meta) = generation {
+                button {
+                    class: "px-1 rounded-md hover:text-[var(--text-primary)] hover:bg-white/[0.06] truncate max-w-[14rem]",
+                    title: "{meta.describe(is_en)}",
+                    onclick: move |_| open.toggle(),
+                    "· {meta.model}"
+                }
+                if open() {
+                    div { class: "absolute bottom-full left-0 mb-1.5 z-20 w-64 p-3 rounded-xl glass-md shadow-lg text-xs text-[var(--text-secondary)] space-y-1",
+                        div { class: "font-medium text-[var(--text-primary)] break-all", "{meta.model}" }
+                        div { "{full}" }
+                        div { "Temperature: {meta.temperature:.2}" }
+                        div { "Top-p: {meta.top_p:.2} · Top-k: {meta.top_k}" }
+                        div { if is_en { "Max tokens" } else { "Jetons max" } ": {meta.max_tokens}" }
+                        div { if is_en { "Context" } else { "Contexte" } ": {meta.context_size}" }
+                        div { if is_en { "Duration" } else { "Duree" } ": {meta.duration_ms as f64 / 1000.0:.1}s" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn MessageBubble(
+    message: Message,
+    /// Position of the message in the conversation (used to resolve citations)
+    #[props(default)]
+    index: usize,
+    /// Raw tool result injected for the model right after this tool message
+    #[props(default)]
+    tool_result: Option<String>,
+) -> Element {
+    use_context_provider(|| MessageIndex(index));
     let is_user = message.role == MessageRole::User;
 
     // Check if this is a tool-related message
@@ -1034,13 +1502,17 @@ pub fn MessageBubble(message: Message) -> Element {
                 div { class: "message-layout",
                     ToolCard {
                         message_type: tool_type,
-                        content: message.content.clone()
+                        content: message.content.clone(),
+                        index: index,
+                        raw_result: tool_result.clone(),
                     }
                 }
             };
         }
     }
 
+    let images = find_image_references(&message.content);
+
     let content_parts = if !is_user {
         parse_thinking_blocks(&message.content)
     } else {
@@ -1051,13 +1523,18 @@ pub fn MessageBubble(message: Message) -> Element {
         // User message — right-aligned, accent-tinted glass
         rsx! {
             div { class: "message-layout animate-fade-in-up",
-                div { class: "flex justify-end mb-4",
+                div { class: "group flex justify-end items-center gap-2 mb-4",
+                    MessageDetails { timestamp: message.timestamp, generation: None }
+                    BranchButton { index }
                     div {
                         class: "message-user px-4 py-3 max-w-[85%]",
                         div {
                             class: "text-[15px] leading-relaxed text-[var(--text-primary)]",
                             "{message.content}"
                         }
+                        if !images.is_empty() {
+                            div { class: "mt-2", MessageImages { references: images } }
+                        }
                     }
                 }
             }
@@ -1066,7 +1543,7 @@ pub fn MessageBubble(message: Message) -> Element {
         // Assistant message — with small avatar, no bubble
         rsx! {
             div { class: "message-layout animate-fade-in-up",
-                div { class: "flex items-start gap-3 mb-4",
+                div { class: "group flex items-start gap-3 mb-4",
                     // LocalClaw avatar — small circle with gradient
                     div {
                         class: "flex-shrink-0 w-6 h-6 rounded-full flex items-center justify-center mt-1",
@@ -1087,6 +1564,9 @@ pub fn MessageBubble(message: Message) -> Element {
                     // Content
                     div {
                         class: "flex-1 min-w-0",
+                        if message.content.is_empty() {
+                            PrefillLine {}
+                        }
                         for part in content_parts {
                             match part {
                                 ContentPart::Thinking(text) => rsx! {
@@ -1100,9 +1580,89 @@ pub fn MessageBubble(message: Message) -> Element {
                                 },
                             }
                         }
+                        if !images.is_empty() {
+                            MessageImages { references: images }
+                        }
+                        div { class: "mt-1 flex items-center gap-1",
+                            BranchButton { index }
+                            FeedbackBar { index, response: message.content.clone() }
+                            MessageDetails { timestamp: message.timestamp, generation: message.generation.clone() }
+                            if let Some(reason) = message.generation.as_ref().and_then(|meta| meta.stop) {
+                                StopReasonBadge { index, reason }
+                            }
+                            if let Some(meta) = message.generation.as_ref().filter(|meta| !meta.logprobs.is_empty()) {
+                                LogprobsView { tokens: meta.logprobs.clone() }
+                            }
+                        }
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: MessageRole, content: &str) -> Message {
+        Message::new(role, content)
+    }
+
+    #[test]
+    fn test_parse_citation_segments() {
+        let segments = parse_inline_markdown("Selon la doc [file_read], voir [le lien](https://a.b) et [x].");
+        let citations: Vec<_> = segments
+            .iter()
+            .filter_map(|s| match s {
+                InlineSegment::Citation(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(citations, vec!["file_read"]);
+        assert!(segments.iter().any(|s| matches!(s, InlineSegment::Link(..))));
+        assert!(!is_citation_label("Source"));
+        assert!(!is_citation_label("1"));
+    }
+
+    #[test]
+    fn test_parse_math() {
+        let math = |text: &str| -> Vec<String> {
+            parse_inline_markdown(text)
+                .into_iter()
+                .filter_map(|s| match s {
+                    InlineSegment::InlineMath(m) => Some(m),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(math("Soit $x^2$ et \\(\\alpha\\)."), vec!["x^2", "\\alpha"]);
+        assert!(math("Entre $5 et $10 par mois").is_empty());
+
+        let blocks = parse_markdown_blocks("Donc :\n\\[\n\\frac{a}{b}\n\\]\nFin");
+        assert!(matches!(&blocks[1], MarkdownBlock::MathBlock(m) if m == "\\frac{a}{b}"));
+    }
+
+    #[test]
+    fn test_markdown_image_drops_bang() {
+        let segments = parse_inline_markdown("Voici ![graphique](/tmp/chart.png)");
+        assert!(matches!(&segments[0], InlineSegment::Text(t) if t == "Voici "));
+        assert!(matches!(&segments[1], InlineSegment::Link(t, u) if t == "graphique" && u == "/tmp/chart.png"));
+    }
+
+    #[test]
+    fn test_find_cited_tool_call() {
+        let messages = vec![
+            msg(MessageRole::User, "lis le fichier"),
+            msg(MessageRole::Assistant, "✅ `file_read` (0.1s): Fichier lu"),
+            msg(MessageRole::System, "{\"tool\":\"file_read\"}"),
+            msg(MessageRole::Assistant, "✅ `web_search` (1.2s): 5 résultats"),
+            msg(MessageRole::System, "{\"tool\":\"web_search\"}"),
+            msg(MessageRole::Assistant, "D'apres [file_read] et [web_search]..."),
+        ];
+        assert_eq!(find_cited_tool_call(&messages, 5, "file_read"), Some(1));
+        assert_eq!(find_cited_tool_call(&messages, 5, "web_search"), Some(3));
+        assert_eq!(find_cited_tool_call(&messages, 2, "web_search"), None);
+        assert_eq!(find_cited_tool_call(&messages, 5, "bash"), None);
+    }
+}
@@ -3,93 +3,209 @@
 //! Contains the main chat view, message display, and input components.
 //! Implements an advanced agentic loop inspired by Claude Code and OpenCode.
 
+pub mod budget;
+pub mod context_bar;
+pub mod diagram;
+pub mod feedback;
 pub mod input;
+pub mod logprobs;
+pub mod math;
 pub mod message;
+pub mod personas;
+pub mod presets;
+pub mod privacy;
+pub mod research;
+pub mod routing;
+pub mod run_timer;
+pub mod save_indicator;
+pub mod search;
+pub mod stop_reason;
+pub mod timeline;
 
 use dioxus::prelude::*;
-use input::ChatInput;
+use context_bar::{estimate_text_tokens, COMPRESSION_THRESHOLD_PERCENT, MAX_PROMPT_HISTORY};
+use input::{parse_long_command, ChatInput};
 use message::{Message, MessageBubble, MessageRole};
+use privacy::PrivacyBadge;
+use research::ResearchCard;
+use run_timer::RunTimer;
+use save_indicator::SaveIndicator;
+use search::ChatSearchBar;
+use timeline::{conversation_days, DaySeparator, JumpToDate};
+use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 
 use crate::agent::{
-    extract_tool_call,
+    extract_tool_calls,
     format_tool_result_for_system,
     get_tool_permission,
     PermissionRequest,
     PermissionResult,
     PermissionDecision,
+    PermissionLevel,
     AgentContext,
+    AgentEvent,
     AgentState,
 };
+use crate::agent::escalation::EscalationPreview;
+use crate::agent::injection;
+use crate::agent::language::{language_directive, status_in_english};
+use crate::agent::grammar::tool_call_grammar;
+use crate::agent::quality::is_garbage_text;
+use crate::agent::skills::{required_level, skill_call_permissions};
 use crate::agent::loop_runner::ToolHistoryEntry;
-use crate::agent::tools::ToolResult;
-use crate::agent::prompts::build_agent_system_prompt;
+use crate::agent::focus;
+use crate::agent::tagging::apply_auto_tags;
+use crate::agent::run_progress::{wrap_up_instruction, RunProgress, MAX_RUNTIME};
+use crate::agent::tools::{CancelToken, ToolContext, ToolError, ToolProgress, ToolResult};
+use crate::agent::tools::repo_map::workspace_prompt;
+use crate::agent::prompts::{build_agent_system_prompt, build_model_capabilities};
 use crate::agent::prompts::build_reflection_prompt;
-use crate::agent::prompts::build_context_compression_prompt;
 use crate::agent::prompts::build_title_generation_prompt;
 use crate::app::{AppState, ModelState};
-use crate::inference::engine::GenerationParams;
+use crate::inference::engine::{EngineError, GenerationParams, InferenceBackend};
 use crate::inference::streaming::StreamToken;
-use crate::storage::conversations::save_conversation;
-use crate::types::message::{Message as StorageMessage, Role as StorageRole};
+use crate::storage::audit_log::{self, AuditEntry};
+use crate::storage::conversations::{list_conversations, save_conversation};
+use crate::storage::attachments::store_images;
+use crate::storage::escalation_log::{self, EscalationRecord};
+use crate::storage::lock::allowed_when_locked;
+use crate::storage::tool_cache;
+use crate::system::crash::{self, SNAPSHOT_INTERVAL};
+use crate::storage::tool_recordings::{self, RecordedCall, RecordedOutcome, ToolRecording, ToolReplayMode};
+use crate::agent::tools::replay::replay_call;
+use crate::types::message::{GenerationMeta, Message as StorageMessage, Role as StorageRole};
 use chrono::Utc;
 use uuid::Uuid;
 use std::time::Instant;
 
-/// Detect if generated text is garbage/corrupted (model hallucinating)
-fn is_garbage_text(content: &str) -> bool {
-    let lower = content.to_lowercase();
-    
-    // Patterns that indicate model is generating fake tool outputs
-    let garbage_patterns = [
-        "assistantcommentary",
-        "userresponse",
-        "toolresult:",
-        "✅ pdf_read:",
-        "✅ file_read:",
-        "contenu du pdf:",
-    ];
-    
-    for pattern in garbage_patterns {
-        if lower.matches(pattern).count() > 3 {
-            tracing::warn!("Garbage detected: pattern '{}' repeated", pattern);
-            return true;
+/// Rolling view of a running tool's progress (latest status + output tail)
+#[derive(Default)]
+struct ToolProgressLog {
+    status: Option<String>,
+    lines: std::collections::VecDeque<String>,
+}
+
+impl ToolProgressLog {
+    /// Output lines kept under the running-tool indicator
+    const MAX_LINES: usize = 8;
+
+    fn push(&mut self, update: ToolProgress) {
+        match update {
+            ToolProgress::Status(status) => self.status = Some(status),
+            ToolProgress::Output(line) => {
+                if self.lines.len() == Self::MAX_LINES {
+                    self.lines.pop_front();
+                }
+                self.lines.push_back(crate::truncate_str(&line, 200).to_string());
+            }
         }
     }
-    
-    // Check for abnormal word/char ratio (text stuck together without spaces)
-    let words = content.split_whitespace().count();
-    if content.len() > 300 && words > 0 {
-        let avg_word_len = content.len() / words;
-        if avg_word_len > 25 {
-            tracing::warn!("Garbage detected: abnormal word length ratio {}", avg_word_len);
-            return true;
+
+    fn render(&self) -> String {
+        let mut out = Vec::with_capacity(self.lines.len() + 1);
+        if let Some(ref status) = self.status {
+            out.push(format!("… {}", status));
         }
+        out.extend(self.lines.iter().cloned());
+        out.join("\n")
     }
-    
-    // Check for excessive repetition of any 10+ char sequence
-    if content.len() > 200 {
-        let chunks: Vec<&str> = content.as_bytes()
-            .chunks(20)
-            .filter_map(|c| std::str::from_utf8(c).ok())
-            .collect();
-        if chunks.len() > 5 {
-            let first = chunks[0];
-            let repeat_count = chunks.iter().filter(|c| *c == &first).count();
-            if repeat_count > 3 {
-                tracing::warn!("Garbage detected: repeated chunk pattern");
-                return true;
+}
+
+/// Why a tool call is refused before it runs
+enum ToolRefusal {
+    /// Outside the tool set of the persona with this name
+    Persona(String),
+    /// Network tools are off during focus mode
+    Focus,
+    /// Only read-only tools are left in locked mode
+    Locked,
+}
+
+/// Show the refusal in the status line, then tell the model which tools it
+/// has instead, in the language of the status lines, and leave an empty
+/// reply for its next attempt
+fn refuse_tool_call(msgs: &mut Vec<Message>, tool: &str, reason: &ToolRefusal, available_tools: &[String], en: bool) {
+    let (status, cause) = match (reason, en) {
+        (ToolRefusal::Persona(name), true) => (
+            format!("🚫 Tool `{}` isn't available to the {} persona.", tool, name),
+            format!("The tool `{}` isn't available to this persona.", tool),
+        ),
+        (ToolRefusal::Persona(name), false) => (
+            format!("🚫 Outil `{}` non disponible pour le persona {}.", tool, name),
+            format!("L'outil `{}` n'est pas disponible dans ce persona.", tool),
+        ),
+        (ToolRefusal::Focus, true) => (
+            format!("🔕 Tool `{}` is off during focus mode.", tool),
+            format!("The tool `{}` is disabled: focus mode cuts network access.", tool),
+        ),
+        (ToolRefusal::Focus, false) => (
+            format!("🔕 Outil `{}` desactive pendant le mode concentration.", tool),
+            format!("L'outil `{}` est désactivé : le mode concentration coupe l'accès réseau.", tool),
+        ),
+        (ToolRefusal::Locked, true) => (
+            format!("🔒 Tool `{}` is disabled in locked mode.", tool),
+            format!("The tool `{}` is disabled: the app is in locked mode (read-only).", tool),
+        ),
+        (ToolRefusal::Locked, false) => (
+            format!("🔒 Outil `{}` désactivé en mode verrouillé.", tool),
+            format!("L'outil `{}` est désactivé : l'application est en mode verrouillé (lecture seule).", tool),
+        ),
+    };
+    let instruction = if en {
+        format!("{} Available tools: {}. Use one of them or answer directly.", cause, available_tools.join(", "))
+    } else {
+        format!("{} Outils disponibles : {}. Utilise-en un ou réponds directement.", cause, available_tools.join(", "))
+    };
+    if let Some(last) = msgs.last_mut() {
+        last.content = status;
+    }
+    msgs.push(Message::new(MessageRole::System, instruction));
+    msgs.push(Message::new(MessageRole::Assistant, String::new()));
+}
+
+/// Tokens a prompt takes: counted by the loaded model, or estimated at
+/// ~4 chars per token when none is loaded
+async fn count_prompt_tokens(app_state: &AppState, messages: &[StorageMessage]) -> usize {
+    match app_state.engine.lock().await.count_message_tokens(messages).await {
+        Ok(count) => count,
+        Err(e) => {
+            if !matches!(e, EngineError::NoModelLoaded | EngineError::BackendNotInitialized) {
+                tracing::warn!("Token count failed, using the estimate: {}", e);
             }
+            messages.iter().map(|m| estimate_text_tokens(&m.content)).sum()
         }
     }
-    
-    false
 }
 
-/// Estimate token count from message content (~4 chars per token)
-#[allow(dead_code)]
-fn estimate_tokens(messages: &[Message]) -> usize {
-    messages.iter().map(|m| m.content.len() / 4).sum()
+/// Zero-cost pruning of the history: long messages are cut and, past six
+/// messages, only the last four are kept behind a marker
+pub fn compact_history(msgs: &mut Vec<Message>) {
+    let msg_count = msgs.len();
+
+    // Truncate long messages
+    for msg in msgs.iter_mut() {
+        if msg.content.len() > 2000 {
+            msg.content = format!(
+                "{}...\n[Tronqué: {} caractères originaux]",
+                &msg.content.chars().take(1500).collect::<String>(),
+                msg.content.len()
+            );
+        }
+    }
+
+    // Keep only recent messages if too many
+    if msg_count > 6 {
+        let keep = 4;
+        let summary = format!(
+            "[{} messages précédents compressés]",
+            msg_count - keep
+        );
+        let recent: Vec<_> = msgs.iter().rev().take(keep).cloned().collect();
+        msgs.clear();
+        msgs.push(Message::new(MessageRole::System, summary));
+        msgs.extend(recent.into_iter().rev());
+    }
 }
 
 #[component]
@@ -143,26 +259,39 @@ pub fn ChatView() -> Element {
         let mut app_state = app_state.clone();
         move |text: String| {
             if !matches!(*app_state.model_state.read(), ModelState::Loaded(_)) {
-                messages.write().push(Message {
-                    role: MessageRole::Assistant,
-                    content: "Model not loaded. Please select and load a model first.".to_string(),
-                });
+                messages.write().push(Message::new(
+                    MessageRole::Assistant,
+                    "Model not loaded. Please select and load a model first.".to_string(),
+                ));
                 return;
             }
 
+            // `/long` raises max_tokens for this reply; alone it arms the next one
+            let (text, long_tokens) = parse_long_command(&text);
+            if text.trim().is_empty() {
+                if long_tokens.is_some() {
+                    app_state.next_max_tokens.set(long_tokens);
+                }
+                return;
+            }
+            let max_tokens_override = long_tokens.or_else(|| app_state.next_max_tokens.take());
+
+            let mut escalation_reason = app_state.settings.read().escalation.trigger_for_request(&text);
+            // Routing rules may hand this message to another model or persona
+            let route = if app_state.skip_route.take() {
+                None
+            } else {
+                app_state.settings.read().routing.route(&text)
+            };
+
             // Add user message immediately
-            messages.write().push(Message {
-                role: MessageRole::User,
-                content: text,
-            });
+            messages.write().push(Message::new(MessageRole::User, text));
 
             // Add empty assistant message to stream into
-            messages.write().push(Message {
-                role: MessageRole::Assistant,
-                content: String::new(),
-            });
+            messages.write().push(Message::new(MessageRole::Assistant, String::new()));
 
             app_state.stop_signal.store(false, Ordering::Relaxed);
+            app_state.wrap_up.store(false, Ordering::Relaxed);
             app_state.is_generating.set(true);
 
             let mut messages = messages.clone();
@@ -173,31 +302,123 @@ pub fn ChatView() -> Element {
                 // Initialize agent context for this run
                 let mut agent_ctx = AgentContext::new();
                 agent_ctx.state = AgentState::Analyzing;
+
+                // The routed model is loaded first and stays loaded afterwards
+                if let Some(model) = route.as_ref().and_then(|r| r.rule.model.clone()) {
+                    let loaded = matches!(&*app_state.model_state.peek(), ModelState::Loaded(path) if *path == model);
+                    if !loaded {
+                        tracing::info!("Routing to {} ({:?})", model, route.as_ref().map(|r| r.kind));
+                        let gpu_layers = app_state.settings.peek().gpu_layers;
+                        if let Err(e) = app_state.switch_model(model.clone(), gpu_layers).await {
+                            let is_en = app_state.settings.peek().language == "en";
+                            if let Some(last) = messages.write().last_mut() {
+                                last.content = if is_en {
+                                    format!("The routed model failed to load: {}", e)
+                                } else {
+                                    format!("Le modele choisi par le routage n'a pas pu etre charge : {}", e)
+                                };
+                            }
+                            app_state.is_generating.set(false);
+                            return;
+                        }
+                    }
+                }
                 
-                let (params, base_system_prompt, tools_enabled, tool_timeout_secs, max_iterations) = {
+                let (mut params, base_system_prompt, tools_enabled, tool_timeout_secs, max_iterations, reflection, escalation) = {
                     let settings = app_state.settings.read();
-                    let params = GenerationParams {
-                        max_tokens: settings.max_tokens,
-                        temperature: settings.temperature,
-                        top_k: settings.top_k,
-                        top_p: settings.top_p,
-                        repeat_penalty: 1.1,
-                        seed: 0,
-                        max_context_size: settings.context_size,
-                    };
-
                     (
-                        params,
+                        settings.generation_params(),
                         settings.system_prompt.clone(),
-                        app_state.agent.config.enable_tools,
+                        app_state.agent.config.enable_tools && settings.tools_enabled,
                         app_state.agent.config.tool_timeout_secs,
                         app_state.agent.config.loop_config.max_iterations,
+                        // Read from settings so policy changes apply to the next run
+                        settings.reflection_policy.clone(),
+                        settings.escalation.clone(),
                     )
                 };
+                if let Some(max_tokens) = max_tokens_override {
+                    params.max_tokens = max_tokens.min(params.max_context_size);
+                }
+                let constrained_tool_calls = app_state.settings.read().constrained_tool_calls;
+                if let ModelState::Loaded(path) = &*app_state.model_state.read() {
+                    if let Some(profile) = app_state.settings.read().model_profiles.get(path) {
+                        params.output_cleanup = profile.output_cleanup;
+                    }
+                }
+
+                // Persona of this conversation, or the routed one: extra instructions, tool subset, loop limit
+                let persona = route
+                    .as_ref()
+                    .and_then(|r| r.rule.persona.clone())
+                    .or_else(|| app_state.current_conversation.read().as_ref().and_then(|c| c.persona.clone()))
+                    .and_then(|id| app_state.settings.read().persona(&id));
+                let base_system_prompt = match &persona {
+                    Some(persona) => {
+                        let is_en = app_state.settings.read().language == "en";
+                        format!("{}\n\n{}", base_system_prompt, persona.instructions(is_en)).trim().to_string()
+                    }
+                    None => base_system_prompt,
+                };
+                let max_iterations = persona.as_ref().and_then(|p| p.max_iterations).unwrap_or(max_iterations);
+                app_state.run_progress.set(Some(RunProgress::new(max_iterations)));
+                // Response language of this conversation, which also sets the language of status lines
+                let language = app_state.current_conversation.read().as_ref().and_then(|c| c.language.clone());
+                let status_en = status_in_english(language.as_deref(), &app_state.settings.read().language);
+                let base_system_prompt = match language_directive(language.as_deref()) {
+                    Some(directive) => format!("{}\n\n{}", base_system_prompt, directive).trim().to_string(),
+                    None => base_system_prompt,
+                };
+                // What the loaded model can hold, so it sizes its tool calls and replies
+                let base_system_prompt = format!(
+                    "{}\n\n{}",
+                    base_system_prompt,
+                    build_model_capabilities(params.max_context_size, params.max_tokens)
+                )
+                .trim()
+                .to_string();
+                // Workspace map, so the agent knows the project before exploring it
+                let workspace = app_state.settings.read().workspace.clone().filter(|w| w.is_dir());
+                let base_system_prompt = match workspace {
+                    Some(root) if tools_enabled => {
+                        let section = tokio::task::spawn_blocking(move || workspace_prompt(&root))
+                            .await
+                            .unwrap_or_default();
+                        format!("{}\n\n{}", base_system_prompt, section).trim().to_string()
+                    }
+                    _ => base_system_prompt,
+                };
+                // Locked mode keeps read-only tools only
+                let locked = app_state.settings.read().lock.enabled;
+                let tool_language = app_state.settings.read().tool_language.clone();
+                let visible_tools = {
+                    let registry = app_state.agent.tool_registry.clone();
+                    let persona = persona.clone();
+                    move || {
+                        let tools = registry.list_tools_in(&tool_language);
+                        let tools = match &persona {
+                            Some(persona) => persona.filter_tools(tools),
+                            None => tools,
+                        };
+                        tools.into_iter().filter(|t| !locked || allowed_when_locked(&t.name)).collect::<Vec<_>>()
+                    }
+                };
+                let mut escalation_suggested = false;
+                let conversation_id = app_state.current_conversation.read().as_ref().map(|c| c.id.clone());
+
+                // Record/replay mode: a missing recording replays nothing rather than running real tools
+                let replay_mode = app_state.settings.read().tool_replay.clone();
+                let replay = match &replay_mode {
+                    ToolReplayMode::Replay(name) => Some(tool_recordings::load_recording(name).unwrap_or_else(|e| {
+                        tracing::warn!("Failed to load recording {}: {}", name, e);
+                        ToolRecording::default()
+                    })),
+                    _ => None,
+                };
 
                 // Build the enhanced system prompt with tools
                 let system_prompt = if tools_enabled {
-                    let tools = app_state.agent.tool_registry.list_tools();
+                    let tools = visible_tools();
                     build_agent_system_prompt(&base_system_prompt, &tools, Some(&agent_ctx), None)
                 } else {
                     base_system_prompt.clone()
@@ -205,6 +426,16 @@ pub fn ChatView() -> Element {
 
                 // Compression guard counter (allows proactive + post-truncation before stopping)
                 let mut compression_count: u32 = 0;
+                // "Wrap up now" was clicked: one last reply, without tools
+                let mut finalizing = false;
+                // A reflection prompt or a malformed call asked for a tool call:
+                // the next reply is constrained to text or a valid one
+                let mut expect_tool_call = false;
+                // Last copy of the session kept for the crash handler
+                let mut last_snapshot = Instant::now();
+                // Calls dropped from a multi-call reply, told to the model once
+                // that reply's call has its result
+                let mut deferred_notice: Option<String> = None;
 
                 // Advanced agent loop
                 while agent_ctx.iteration < max_iterations {
@@ -215,24 +446,67 @@ pub fn ChatView() -> Element {
                         tracing::info!("Agent stopped by user at iteration {}", agent_ctx.iteration);
                         break;
                     }
+                    app_state.snapshot_session();
+                    if let Some(progress) = app_state.run_progress.write().as_mut() {
+                        progress.apply(&AgentEvent::Progress {
+                            iteration: agent_ctx.iteration,
+                            max_iterations,
+                            message: String::new(),
+                        });
+                    }
+
+                    // After the result of the call that ran, before the next reply
+                    if let Some(notice) = deferred_notice.take() {
+                        let mut msgs = messages.write();
+                        let placeholder = msgs
+                            .last()
+                            .map(|m| m.role == MessageRole::Assistant && m.content.is_empty())
+                            .unwrap_or(false);
+                        let at = if placeholder { msgs.len() - 1 } else { msgs.len() };
+                        msgs.insert(at, Message::new(MessageRole::System, notice));
+                    }
+
+                    // Wrap up on request: ask for the final answer instead of stopping short
+                    if !finalizing && app_state.wrap_up.load(Ordering::Relaxed) {
+                        tracing::info!("Wrap-up requested at iteration {}", agent_ctx.iteration);
+                        finalizing = true;
+                        let mut msgs = messages.write();
+                        let placeholder = msgs
+                            .last()
+                            .map(|m| m.role == MessageRole::Assistant && m.content.is_empty())
+                            .unwrap_or(false);
+                        let at = if placeholder { msgs.len() - 1 } else { msgs.len() };
+                        msgs.insert(at, Message::new(MessageRole::System, wrap_up_instruction(status_en).to_string()));
+                        if !placeholder {
+                            msgs.push(Message::new(MessageRole::Assistant, String::new()));
+                        }
+                    }
 
                     // Check for stuck loop
                     if agent_ctx.is_stuck() {
                         let mut msgs = messages.write();
-                        msgs.push(Message {
-                            role: MessageRole::Assistant,
-                            content: "⚠️ J'ai détecté que je répète les mêmes actions. Laisse-moi reformuler ma réponse.".to_string(),
-                        });
+                        msgs.push(Message::new(
+                            MessageRole::Assistant,
+                            if status_en {
+                                "⚠️ I noticed I'm repeating the same actions. Let me rephrase my answer.".to_string()
+                            } else {
+                                "⚠️ J'ai détecté que je répète les mêmes actions. Laisse-moi reformuler ma réponse.".to_string()
+                            },
+                        ));
                         break;
                     }
 
                     // Check max runtime (5 minutes)
-                    if agent_ctx.elapsed().as_secs() > 300 {
+                    if agent_ctx.elapsed() > MAX_RUNTIME {
                         let mut msgs = messages.write();
-                        msgs.push(Message {
-                            role: MessageRole::Assistant,
-                            content: "⏱️ Temps d'exécution maximal atteint. Voici ce que j'ai trouvé jusqu'à présent.".to_string(),
-                        });
+                        msgs.push(Message::new(
+                            MessageRole::Assistant,
+                            if status_en {
+                                "⏱️ Maximum run time reached. Here is what I found so far.".to_string()
+                            } else {
+                                "⏱️ Temps d'exécution maximal atteint. Voici ce que j'ai trouvé jusqu'à présent.".to_string()
+                            },
+                        ));
                         break;
                     }
 
@@ -248,16 +522,17 @@ pub fn ChatView() -> Element {
                         }
 
                         // Keep more history for better context
-                        let max_history = 40usize;
-                        if history.len() > max_history {
-                            history = history[history.len() - max_history..].to_vec();
+                        if history.len() > MAX_PROMPT_HISTORY {
+                            history = history[history.len() - MAX_PROMPT_HISTORY..].to_vec();
                         }
 
                         let mut prompt_messages: Vec<StorageMessage> = Vec::new();
                         
                         // System prompt with dynamic context injection
-                        let dynamic_prompt = if agent_ctx.iteration > 1 && tools_enabled {
-                            let tools = app_state.agent.tool_registry.list_tools();
+                        let dynamic_prompt = if finalizing {
+                            base_system_prompt.clone()
+                        } else if agent_ctx.iteration > 1 && tools_enabled {
+                            let tools = visible_tools();
                             build_agent_system_prompt(&base_system_prompt, &tools, Some(&agent_ctx), None)
                         } else {
                             system_prompt.clone()
@@ -276,59 +551,31 @@ pub fn ChatView() -> Element {
 
                     // === PROACTIVE COMPRESSION ===
                     // Check if we're approaching context limit BEFORE generation
-                    let estimated_tokens: usize = prompt_messages.iter()
-                        .map(|m| m.content.len() / 4)
-                        .sum();
-                    let threshold = (params.max_context_size as usize) * 75 / 100;
+                    let prompt_tokens = count_prompt_tokens(&app_state, &prompt_messages).await;
+                    let threshold = (params.max_context_size as usize) * COMPRESSION_THRESHOLD_PERCENT / 100;
                     
-                    if estimated_tokens > threshold && compression_count == 0 {
+                    if prompt_tokens > threshold && compression_count == 0 {
                         tracing::info!(
                             "Proactive compression: {}% capacity ({}/{} tokens)",
-                            estimated_tokens * 100 / params.max_context_size as usize,
-                            estimated_tokens,
+                            prompt_tokens * 100 / params.max_context_size as usize,
+                            prompt_tokens,
                             params.max_context_size
                         );
                         
                         // Apply zero-cost pruning to messages signal
-                        {
-                            let mut msgs = messages.write();
-                            let msg_count = msgs.len();
-                            
-                            // Truncate long system messages
-                            for msg in msgs.iter_mut() {
-                                if msg.content.len() > 2000 {
-                                    msg.content = format!(
-                                        "{}...\n[Tronqué: {} caractères originaux]",
-                                        &msg.content.chars().take(1500).collect::<String>(),
-                                        msg.content.len()
-                                    );
-                                }
-                            }
-                            
-                            // Keep only recent messages if too many
-                            if msg_count > 6 {
-                                let keep = 4;
-                                let summary = format!(
-                                    "[{} messages précédents compressés]",
-                                    msg_count - keep
-                                );
-                                let recent: Vec<_> = msgs.iter().rev().take(keep).cloned().collect();
-                                msgs.clear();
-                                msgs.push(Message {
-                                    role: MessageRole::System,
-                                    content: summary,
-                                });
-                                msgs.extend(recent.into_iter().rev());
-                            }
-                        }
+                        compact_history(&mut messages.write());
                         
                         compression_count += 1;
 
                         // Notify user
-                        messages.write().push(Message {
-                            role: MessageRole::System,
-                            content: "💾 Compression proactive du contexte appliquée.".to_string(),
-                        });
+                        messages.write().push(Message::new(
+                            MessageRole::System,
+                            if status_en {
+                                "💾 Context compressed ahead of the limit.".to_string()
+                            } else {
+                                "💾 Compression proactive du contexte appliquée.".to_string()
+                            },
+                        ));
 
                         // Restart loop to rebuild prompt_messages from compressed messages
                         continue;
@@ -336,18 +583,27 @@ pub fn ChatView() -> Element {
 
                     // Generate response
                     agent_ctx.state = AgentState::Thinking;
+                    let generation_started = Instant::now();
                     
+                    let mut generation_params = params.clone();
+                    if std::mem::take(&mut expect_tool_call) && constrained_tool_calls && tools_enabled && !finalizing {
+                        generation_params.grammar = Some(tool_call_grammar(&visible_tools()));
+                    }
                     let (rx, stop_signal) = {
                         let engine = app_state.engine.lock().await;
-                        match engine.generate_stream_messages(prompt_messages, params.clone()) {
+                        match engine.generate_stream_messages(prompt_messages, generation_params) {
                             Ok(result) => result,
                             Err(e) => {
                                 agent_ctx.consecutive_errors += 1;
-                                messages.write().push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: format!("❌ Erreur de génération: {e}"),
-                                });
-                                if agent_ctx.consecutive_errors >= 3 {
+                                messages.write().push(Message::new(
+                                    MessageRole::Assistant,
+                                    if status_en {
+                                        format!("❌ Generation error: {e}")
+                                    } else {
+                                        format!("❌ Erreur de génération: {e}")
+                                    },
+                                ));
+                                if !reflection.can_recover(agent_ctx.consecutive_errors) {
                                     break;
                                 }
                                 continue;
@@ -357,7 +613,8 @@ pub fn ChatView() -> Element {
 
                     // Stream tokens - drain all available tokens per tick for smooth display
                     let mut stream_done = false;
-                    let mut was_truncated = false;
+                    let mut stop_reason = None;
+                    let mut logprobs = Vec::new();
                     while !stream_done {
                         if app_state.stop_signal.load(Ordering::Relaxed) {
                             stop_signal.store(true, Ordering::Relaxed);
@@ -373,22 +630,31 @@ pub fn ChatView() -> Element {
                                     batch_text.push_str(&text);
                                     got_any = true;
                                 }
+                                Ok(StreamToken::Progress { processed, total }) => {
+                                    app_state.prefill_progress.set(Some((processed, total)));
+                                    got_any = true;
+                                }
+                                Ok(StreamToken::Logprob(logprob)) => logprobs.push(logprob),
                                 Ok(StreamToken::Done) => {
                                     stream_done = true;
                                     break;
                                 }
-                                Ok(StreamToken::Truncated { tokens_generated, max_tokens }) => {
+                                Ok(StreamToken::Truncated { tokens_generated, max_tokens, reason }) => {
                                     tracing::warn!(
-                                        "Response truncated: {} tokens generated out of {} max",
-                                        tokens_generated, max_tokens
+                                        "Response truncated ({:?}): {} tokens generated out of {} max",
+                                        reason, tokens_generated, max_tokens
                                     );
-                                    was_truncated = true;
+                                    stop_reason = Some(reason);
                                     stream_done = true;
                                     break;
                                 }
                                 Ok(StreamToken::Error(e)) => {
                                     agent_ctx.consecutive_errors += 1;
-                                    batch_text.push_str(&format!("\n\n❌ Erreur: {e}"));
+                                    batch_text.push_str(&if status_en {
+                                        format!("\n\n❌ Error: {e}")
+                                    } else {
+                                        format!("\n\n❌ Erreur: {e}")
+                                    });
                                     stream_done = true;
                                     break;
                                 }
@@ -400,6 +666,11 @@ pub fn ChatView() -> Element {
                             }
                         }
                         
+                        // The first token (or the end) replaces the prompt progress
+                        if (!batch_text.is_empty() || stream_done) && app_state.prefill_progress.peek().is_some() {
+                            app_state.prefill_progress.set(None);
+                        }
+
                         // Apply all tokens in one write (reduces re-renders)
                         if !batch_text.is_empty() {
                             let mut msgs = messages.write();
@@ -409,7 +680,11 @@ pub fn ChatView() -> Element {
                                 // Check for garbage text (model hallucinating)
                                 if last.content.len() > 200 && is_garbage_text(&last.content) {
                                     tracing::error!("Garbage text detected, stopping generation");
-                                    last.content = "⚠️ Génération interrompue: texte corrompu détecté. Reformulons.\n\n".to_string();
+                                    last.content = if status_en {
+                                        "⚠️ Generation stopped: corrupted text detected. Let's rephrase.\n\n".to_string()
+                                    } else {
+                                        "⚠️ Génération interrompue: texte corrompu détecté. Reformulons.\n\n".to_string()
+                                    };
                                     stream_done = true;
                                     // Break the outer loop after this
                                 }
@@ -420,192 +695,61 @@ pub fn ChatView() -> Element {
                             // No tokens available, yield briefly
                             tokio::time::sleep(std::time::Duration::from_millis(5)).await;
                             
-                            // Periodic save during generation (every 3 seconds)
-                            if last_save_time.read().elapsed().as_secs() >= 3 {
-                                let msgs = messages.read();
-                                let storage_messages: Vec<StorageMessage> = msgs.iter()
-                                    .cloned()
-                                    .map(|m| m.into())
-                                    .collect();
-                                
-                                let mut conv_write = app_state.current_conversation.write();
-                                if let Some(ref mut conv) = *conv_write {
-                                    conv.messages = storage_messages;
-                                    let _ = save_conversation(conv);
-                                }
-                                drop(conv_write);
+                            // Periodic save during generation (interval from Settings, 0 = off)
+                            let autosave_secs = app_state.settings.read().autosave_interval_secs as u64;
+                            if autosave_secs > 0 && last_save_time.read().elapsed().as_secs() >= autosave_secs {
+                                app_state.save_active_conversation();
                                 last_save_time.set(Instant::now());
                             }
+                            if last_snapshot.elapsed() >= SNAPSHOT_INTERVAL {
+                                app_state.snapshot_session();
+                                last_snapshot = Instant::now();
+                            }
                         }
                     }
 
-                    // === OPTIMIZED CONTEXT COMPRESSION ===
-                    // If response was truncated due to context saturation, apply smart compression
-                    if was_truncated && !app_state.stop_signal.load(Ordering::Relaxed) {
-                        // Guard: allow proactive + post-truncation (2 total) before stopping
-                        if compression_count >= 2 {
-                            tracing::warn!("Already compressed {} times this session, stopping to avoid loop", compression_count);
-                            break;
-                        }
-                        compression_count += 1;
-                        
-                        let msg_count = messages.read().len();
-                        let total_chars: usize = messages.read().iter().map(|m| m.content.len()).sum();
-                        
-                        tracing::info!("Context saturated ({} msgs, {} chars), applying compression", msg_count, total_chars);
-                        
-                        // === PHASE 1: ZERO-COST PRUNING (no LLM) ===
-                        // Truncate long system messages (tool results, etc.) - they're already processed
-                        let mut chars_saved = 0usize;
-                        {
-                            let mut msgs = messages.write();
-                            for msg in msgs.iter_mut() {
-                                if msg.role == MessageRole::System && msg.content.len() > 2000 {
-                                    let original_len = msg.content.len();
-                                    // Keep first 500 chars + indicator
-                                    let truncated = format!(
-                                        "{}...\n\n[Contenu tronqué - {} caractères]",
-                                        &msg.content[..500.min(msg.content.len())],
-                                        original_len
-                                    );
-                                    chars_saved += original_len - truncated.len();
-                                    msg.content = truncated;
-                                }
-                            }
-                        }
-                        
-                        if chars_saved > 0 {
-                            tracing::info!("Zero-cost pruning saved {} chars", chars_saved);
-                        }
-                        
-                        // Check if pruning was enough
-                        let new_total: usize = messages.read().iter().map(|m| m.content.len()).sum();
-                        if new_total < 12000 && agent_ctx.iteration < 3 {
-                            // Pruning was enough AND we haven't retried too many times
-                            tracing::info!("Pruning sufficient ({}→{} chars), one more attempt", total_chars, new_total);
-                            continue;
-                        } else if new_total < 12000 {
-                            // Pruning worked but we've already retried, stop here
-                            tracing::info!("Pruning done, stopping after {} iterations", agent_ctx.iteration);
-                            break;
-                        }
-                        
-                        // === PHASE 2: LLM SUMMARY (if pruning wasn't enough) ===
-                        if msg_count > 2 {
-                            // Indicate compression to user
-                            {
-                                let mut msgs = messages.write();
-                                if let Some(last) = msgs.last_mut() {
-                                    if !last.content.is_empty() && !last.content.contains("Compression") {
-                                        last.content.push_str("\n\n⚡ *Compression du contexte...*");
-                                    }
-                                }
-                            }
-                            
-                            // Build compact summary request (only key info, very truncated)
-                            let summary_request: String = {
-                                let msgs = messages.read();
-                                msgs.iter()
-                                    .take(msg_count.saturating_sub(2))
-                                    .filter(|m| m.role != MessageRole::System)
-                                    .map(|m| {
-                                        let role = match m.role {
-                                            MessageRole::User => "U",
-                                            MessageRole::Assistant => "A",
-                                            MessageRole::System => "S",
-                                        };
-                                        let content = if m.content.len() > 200 {
-                                            format!("{}...", &m.content[..200])
-                                        } else {
-                                            m.content.clone()
-                                        };
-                                        format!("[{}]: {}", role, content)
-                                    })
-                                    .collect::<Vec<_>>()
-                                    .join("\n")
-                            };
-                            
-                            let compression_prompt = format!(
-                                "{}\n\n---\n{}",
-                                build_context_compression_prompt(),
-                                summary_request
-                            );
-                            
-                            let summary_params = GenerationParams {
-                                max_tokens: 600,
-                                temperature: 0.2,
-                                max_context_size: 4096,
-                                ..params.clone()
-                            };
-                            
-                            let summary_messages = vec![
-                                StorageMessage::new(StorageRole::User, compression_prompt),
-                            ];
-                            
-                            let summary = {
-                                let engine = app_state.engine.lock().await;
-                                if let Ok((rx, _)) = engine.generate_stream_messages(summary_messages, summary_params) {
-                                    let mut text = String::new();
-                                    while let Ok(token) = rx.recv() {
-                                        match token {
-                                            StreamToken::Token(t) => text.push_str(&t),
-                                            StreamToken::Done | StreamToken::Truncated { .. } => break,
-                                            StreamToken::Error(_) => break,
-                                        }
-                                    }
-                                    text
-                                } else {
-                                    "Conversation précédente résumée.".to_string()
-                                }
-                            };
-                            
-                            tracing::info!("LLM summary: {} chars", summary.len());
-                            
-                            // Replace messages with summary + last message
-                            {
-                                let mut msgs = messages.write();
-                                let last_msg = msgs.last().cloned();
-                                msgs.clear();
-                                
-                                msgs.push(Message {
-                                    role: MessageRole::System,
-                                    content: format!("📋 {}", summary),
-                                });
-                                
-                                if let Some(msg) = last_msg {
-                                    if !msg.content.is_empty() {
-                                        msgs.push(msg);
-                                    }
-                                }
-                                
-                                msgs.push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: String::new(),
-                                });
-                            }
-                            
-                            continue;
-                        } else {
-                            tracing::warn!("Cannot compress further, stopping");
-                            break;
-                        }
+                    // Record what produced this reply, shown on hover and kept when saved
+                    let model = match &*app_state.model_state.read() {
+                        ModelState::Loaded(path) => std::path::Path::new(path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string()),
+                        _ => None,
+                    };
+                    if let Some(last) = messages.write().last_mut().filter(|m| m.role == MessageRole::Assistant) {
+                        last.generation = Some(GenerationMeta {
+                            model: model.unwrap_or_default(),
+                            temperature: params.temperature,
+                            top_p: params.top_p,
+                            top_k: params.top_k,
+                            max_tokens: params.max_tokens,
+                            context_size: params.max_context_size,
+                            duration_ms: generation_started.elapsed().as_millis() as u64,
+                            route: route.as_ref().map(|r| r.rule.name.clone()),
+                            stop: stop_reason,
+                            logprobs,
+                        });
+                    }
+
+                    // A cut-off reply stops the turn; the badge under it explains why
+                    // and offers to continue, raise the limit or compact
+                    if stop_reason.is_some() {
+                        break;
                     }
 
                     // Check if stream ended with errors
                     let last_content = messages.read().last().map(|m| m.content.clone()).unwrap_or_default();
-                    let had_stream_error = last_content.contains("❌ Erreur:");
+                    let had_stream_error = last_content.contains("❌ Erreur:") || last_content.contains("❌ Error:");
                     
                     if had_stream_error {
                         // Stream error — give LLM a chance to recover
-                        if agent_ctx.consecutive_errors < 3 {
-                            messages.write().push(Message {
-                                role: MessageRole::System,
-                                content: "Une erreur est survenue pendant la génération. Reformule ta réponse ou essaie une approche différente.".to_string(),
-                            });
-                            messages.write().push(Message {
-                                role: MessageRole::Assistant,
-                                content: String::new(),
-                            });
+                        if reflection.can_recover(agent_ctx.consecutive_errors) {
+                            let instruction = if status_en {
+                                "An error occurred during generation. Rephrase your answer or try a different approach."
+                            } else {
+                                "Une erreur est survenue pendant la génération. Reformule ta réponse ou essaie une approche différente."
+                            };
+                            messages.write().push(Message::new(MessageRole::System, instruction.to_string()));
+                            messages.write().push(Message::new(MessageRole::Assistant, String::new()));
                             continue;
                         } else {
                             break;
@@ -615,7 +759,7 @@ pub fn ChatView() -> Element {
                     // Reset consecutive errors on successful generation
                     agent_ctx.consecutive_errors = 0;
 
-                    if !tools_enabled {
+                    if !tools_enabled || finalizing {
                         break;
                     }
 
@@ -631,12 +775,24 @@ pub fn ChatView() -> Element {
                     // Store last response for context
                     agent_ctx.last_response = Some(last_text.clone());
 
-                    let tool_call = match extract_tool_call(&last_text) {
+                    let mut extracted_calls = extract_tool_calls(&last_text).into_iter();
+                    let mut tool_call = match extracted_calls.next() {
                         Some(call) => {
                             tracing::info!("Tool call extracted: {} with params keys: {:?}",
                                 call.tool,
                                 call.params.as_object().map(|o| o.keys().cloned().collect::<Vec<_>>()).unwrap_or_default()
                             );
+                            // One call per iteration: ask the model to re-issue the rest
+                            let skipped: Vec<String> = extracted_calls.map(|c| format!("`{}`", c.tool)).collect();
+                            if !skipped.is_empty() {
+                                tracing::info!("Deferring {} extra tool call(s)", skipped.len());
+                                // Pushed now, it would become the status line below
+                                deferred_notice = Some(format!(
+                                    "Plusieurs appels d'outils détectés : seul `{}` est exécuté maintenant. Relance ensuite {} si c'est toujours nécessaire, un appel à la fois.",
+                                    call.tool,
+                                    skipped.join(", ")
+                                ));
+                            }
                             call
                         }
                         None => {
@@ -645,17 +801,17 @@ pub fn ChatView() -> Element {
                             let looks_like_failed_json = (last_text.contains("{\"tool\"") || last_text.contains("{ \"tool\"")) 
                                 && last_text.contains("\"params\"");
                             
-                            if looks_like_failed_json && agent_ctx.consecutive_errors < 2 {
+                            if looks_like_failed_json && reflection.can_recover(agent_ctx.consecutive_errors + 1) {
                                 // LLM tried to call a tool but the JSON was malformed
                                 agent_ctx.consecutive_errors += 1;
-                                messages.write().push(Message {
-                                    role: MessageRole::System,
-                                    content: "Le format JSON de l'appel d'outil était invalide. Rappel: utilise exactement ce format sans texte avant ni après:\n```json\n{\"tool\": \"nom_outil\", \"params\": {...}}\n```\nRéessaie avec le bon format.".to_string(),
-                                });
-                                messages.write().push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: String::new(),
-                                });
+                                let instruction = if status_en {
+                                    "The tool call JSON was invalid. Reminder: use exactly this format, with no text before or after:\n```json\n{\"tool\": \"tool_name\", \"params\": {...}}\n```\nTry again with the correct format."
+                                } else {
+                                    "Le format JSON de l'appel d'outil était invalide. Rappel: utilise exactement ce format sans texte avant ni après:\n```json\n{\"tool\": \"nom_outil\", \"params\": {...}}\n```\nRéessaie avec le bon format."
+                                };
+                                messages.write().push(Message::new(MessageRole::System, instruction.to_string()));
+                                messages.write().push(Message::new(MessageRole::Assistant, String::new()));
+                                expect_tool_call = true;
                                 continue;
                             }
                             
@@ -670,16 +826,163 @@ pub fn ChatView() -> Element {
                     {
                         let mut msgs = messages.write();
                         if let Some(last) = msgs.last_mut() {
-                            last.content = format!(
-                                "🔧 Utilisation de l'outil `{}`... (itération {}/{})",
-                                tool_call.tool, agent_ctx.iteration, max_iterations
-                            );
+                            last.content = if status_en {
+                                format!(
+                                    "🔧 Using tool `{}`... (iteration {}/{})",
+                                    tool_call.tool, agent_ctx.iteration, max_iterations
+                                )
+                            } else {
+                                format!(
+                                    "🔧 Utilisation de l'outil `{}`... (itération {}/{})",
+                                    tool_call.tool, agent_ctx.iteration, max_iterations
+                                )
+                            };
+                        }
+                    }
+
+                    // Tools outside the persona's subset, or cut by focus or
+                    // locked mode, are refused before anything else
+                    let refusal = if let Some(persona) = persona.as_ref().filter(|p| !p.allows_tool(&tool_call.tool)) {
+                        tracing::info!("Tool {} not available to persona {}", tool_call.tool, persona.id);
+                        Some(ToolRefusal::Persona(persona.name.clone()))
+                    } else if focus::blocks_tool(&tool_call.tool) {
+                        tracing::info!("Tool {} blocked by focus mode", tool_call.tool);
+                        Some(ToolRefusal::Focus)
+                    } else if locked && !allowed_when_locked(&tool_call.tool) {
+                        tracing::info!("Tool {} blocked by locked mode", tool_call.tool);
+                        Some(ToolRefusal::Locked)
+                    } else {
+                        None
+                    };
+                    if let Some(refusal) = refusal {
+                        agent_ctx.consecutive_errors += 1;
+                        let available_tools: Vec<String> = visible_tools().iter().map(|t| t.name.clone()).collect();
+                        refuse_tool_call(&mut messages.write(), &tool_call.tool, &refusal, &available_tools, status_en);
+                        if !reflection.can_recover(agent_ctx.consecutive_errors) {
+                            break;
                         }
+                        continue;
                     }
 
-                    // Permission check
-                    let permission_level = get_tool_permission(&tool_call.tool);
-                    let target = tool_call
+                    // Validate params against the tool schema before asking for permission
+                    if app_state.agent.tool_registry.get(&tool_call.tool).is_some() {
+                        match app_state.agent.tool_registry.validate_params(&tool_call.tool, &tool_call.params) {
+                            Ok(normalized) => tool_call.params = normalized,
+                            Err(e) => {
+                                tracing::warn!("Invalid params for {}: {}", tool_call.tool, e);
+                                agent_ctx.consecutive_errors += 1;
+                                let mut msgs = messages.write();
+                                if let Some(last) = msgs.last_mut() {
+                                    last.content = if status_en {
+                                        format!("❌ Invalid parameters for `{}`", tool_call.tool)
+                                    } else {
+                                        format!("❌ Paramètres invalides pour `{}`", tool_call.tool)
+                                    };
+                                }
+                                msgs.push(Message::new(
+                                    MessageRole::System,
+                                    format!("{}\nCorrige l'appel et réessaie.", e),
+                                ));
+                                msgs.push(Message::new(MessageRole::Assistant, String::new()));
+                                if !reflection.can_recover(agent_ctx.consecutive_errors) {
+                                    break;
+                                }
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Identical read-only call already made this run: reuse its result
+                    if let Some(cached) = agent_ctx.cached_result(&tool_call.tool, &tool_call.params).cloned() {
+                        tracing::info!("Duplicate tool call suppressed: {}", tool_call.tool);
+                        agent_ctx.tool_history.push(ToolHistoryEntry {
+                            tool_name: tool_call.tool.clone(),
+                            params: tool_call.params.clone(),
+                            result: Some(cached.clone()),
+                            error: None,
+                            timestamp: Utc::now().timestamp() as u64,
+                            duration_ms: 0,
+                        });
+
+                        let mut msgs = messages.write();
+                        if let Some(last) = msgs.last_mut() {
+                            last.content = if status_en {
+                                format!("✅ `{}` (0.0s): ♻️ Already fetched, reused", tool_call.tool)
+                            } else {
+                                format!("✅ `{}` (0.0s): ♻️ Résultat déjà obtenu, réutilisé", tool_call.tool)
+                            };
+                        }
+                        let tool_result_text = format_tool_result_for_system(&tool_call.tool, &cached);
+                        let tool_result_text = if tool_result_text.len() > 4000 {
+                            let truncated: String = tool_result_text.chars().take(3500).collect();
+                            format!("{}...\n[Résultat tronqué: {} caractères au total]", truncated, tool_result_text.len())
+                        } else {
+                            tool_result_text
+                        };
+                        let notice = if status_en {
+                            format!(
+                                "You already called `{}` with exactly these parameters. You already have this information, do not make this call again:\n\n{}",
+                                tool_call.tool, tool_result_text
+                            )
+                        } else {
+                            format!(
+                                "Tu as déjà appelé `{}` avec exactement ces paramètres. Tu as déjà cette information, ne refais pas cet appel:\n\n{}",
+                                tool_call.tool, tool_result_text
+                            )
+                        };
+                        msgs.push(Message::new(MessageRole::System, notice));
+                        msgs.push(Message::new(MessageRole::Assistant, String::new()));
+                        agent_ctx.state = AgentState::Reflecting;
+                        continue;
+                    }
+
+                    // Cloud escalation: policy gate, then always ask for consent
+                    let escalation_preview = if tool_call.tool == "ai_consult" {
+                        match escalation.authorize(escalation_reason.as_ref(), escalation_log::current_month_spend()) {
+                            Ok(reason) => {
+                                let model = tool_call
+                                    .params
+                                    .get("model")
+                                    .and_then(|v| v.as_str())
+                                    .map(|s| s.to_string())
+                                    .unwrap_or_else(|| app_state.settings.read().openrouter_model.clone());
+                                let model = escalation.provider.resolve_model(&model);
+                                Some(EscalationPreview::new(escalation.provider, &model, reason, &tool_call.params))
+                            }
+                            Err(denied) => {
+                                tracing::info!("Escalation refused: {:?}", denied);
+                                let mut msgs = messages.write();
+                                if let Some(last) = msgs.last_mut() {
+                                    last.content = if status_en {
+                                        "🚫 Escalation to an external model not allowed.".to_string()
+                                    } else {
+                                        "🚫 Escalade vers un modèle externe non autorisée.".to_string()
+                                    };
+                                }
+                                msgs.push(Message::new(MessageRole::System, denied.system_note()));
+                                msgs.push(Message::new(MessageRole::Assistant, String::new()));
+                                continue;
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
+                    // Permission check; skills are checked against the permissions they declare
+                    let skill_permissions = skill_call_permissions(
+                        &app_state.agent.skill_registry,
+                        &tool_call.tool,
+                        &tool_call.params,
+                    );
+                    let permission_level = skill_permissions
+                        .as_deref()
+                        .filter(|_| tool_call.tool != "skill_create")
+                        .and_then(required_level)
+                        .unwrap_or_else(|| get_tool_permission(&tool_call.tool));
+                    let target = escalation_preview
+                        .as_ref()
+                        .map(|p| format!("{} · {}", p.provider.label(), p.model))
+                        .or_else(|| tool_call
                         .params
                         .get("path")
                         .and_then(|v| v.as_str())
@@ -687,7 +990,7 @@ pub fn ChatView() -> Element {
                         .or_else(|| tool_call.params.get("command").and_then(|v| v.as_str()))
                         .or_else(|| tool_call.params.get("url").and_then(|v| v.as_str()))
                         .or_else(|| tool_call.params.get("company_name").and_then(|v| v.as_str()))
-                        .map(|s| s.to_string())
+                        .map(|s| s.to_string()))
                         .unwrap_or_else(|| tool_call.params.to_string());
 
                     let permission_request = PermissionRequest {
@@ -702,10 +1005,10 @@ pub fn ChatView() -> Element {
 
                     // Check auto-approve settings before asking user
                     // Internal safe tools are always auto-approved
-                    let is_internal_safe_tool = matches!(tool_call.tool.as_str(),
+                    let is_internal_safe_tool = skill_permissions.is_none() && matches!(tool_call.tool.as_str(),
                         "skill_create" | "skill_invoke" | "skill_list" | "think" | "todo_write"
                     );
-                    let auto_approved = {
+                    let auto_approved = escalation_preview.is_none() && {
                         let settings = app_state.settings.read();
                         settings.auto_approve_all_tools
                             || settings.tool_allowlist.contains(&tool_call.tool)
@@ -715,6 +1018,13 @@ pub fn ChatView() -> Element {
 
                     let permission_result = if auto_approved {
                         PermissionResult::Approved
+                    } else if escalation_preview.is_some() {
+                        app_state.escalation_preview.set(escalation_preview.clone());
+                        app_state
+                            .agent
+                            .permission_manager
+                            .request_consent(permission_request.clone())
+                            .await
                     } else {
                         app_state
                             .agent
@@ -728,45 +1038,67 @@ pub fn ChatView() -> Element {
                         PermissionResult::Pending => {
                             agent_ctx.state = AgentState::WaitingForUser;
                             tracing::info!("Waiting for user approval for tool: {}", tool_call.tool);
+                            // 0 waits for the user: the run is paused, not timed out
+                            let timeout_secs = app_state.settings.read().permission_timeout_secs;
+                            let timeout = (timeout_secs > 0).then(|| std::time::Duration::from_secs(timeout_secs as u64));
                             {
                                 let mut msgs = messages.write();
                                 if let Some(last) = msgs.last_mut() {
-                                    last.content = format!(
-                                        "⏳ Autorisation requise pour `{}` ({}).\nCible: {}",
-                                        tool_call.tool,
-                                        permission_level.label(),
-                                        target
-                                    );
+                                    let icon = if timeout.is_some() { "⏳" } else { "⏸️" };
+                                    last.content = if status_en {
+                                        format!(
+                                            "{} Approval required for `{}` ({}).\nTarget: {}",
+                                            icon,
+                                            tool_call.tool,
+                                            permission_level.label(),
+                                            target
+                                        )
+                                    } else {
+                                        format!(
+                                            "{} Autorisation requise pour `{}` ({}).\nCible: {}",
+                                            icon,
+                                            tool_call.tool,
+                                            permission_level.label(),
+                                            target
+                                        )
+                                    };
                                 }
                             }
 
-                            match app_state
+                            let waiting_since = Instant::now();
+                            let decision = app_state
                                 .agent
                                 .permission_manager
-                                .wait_for_decision(
-                                    permission_request.id,
-                                    std::time::Duration::from_secs(120),
-                                )
-                                .await
-                            {
+                                .wait_for_decision(permission_request.id, timeout)
+                                .await;
+                            // Time spent waiting for the user doesn't count against
+                            // the run's runtime limit: it resumes where it was
+                            let waited = waiting_since.elapsed();
+                            agent_ctx.start_time += waited;
+                            if let Some(progress) = app_state.run_progress.write().as_mut() {
+                                progress.started_at += waited;
+                            }
+                            match decision {
                                 Some(PermissionDecision::Approved) => true,
                                 Some(PermissionDecision::Denied) => {
                                     let mut msgs = messages.write();
                                     if let Some(last) = msgs.last_mut() {
-                                        last.content = format!(
-                                            "🚫 Permission refusée pour `{}`.",
-                                            tool_call.tool
-                                        );
+                                        last.content = if status_en {
+                                            format!("🚫 Permission denied for `{}`.", tool_call.tool)
+                                        } else {
+                                            format!("🚫 Permission refusée pour `{}`.", tool_call.tool)
+                                        };
                                     }
                                     false
                                 }
                                 None => {
                                     let mut msgs = messages.write();
                                     if let Some(last) = msgs.last_mut() {
-                                        last.content = format!(
-                                            "⏱️ Délai expiré pour `{}`.",
-                                            tool_call.tool
-                                        );
+                                        last.content = if status_en {
+                                            format!("⏱️ Timed out waiting for `{}`.", tool_call.tool)
+                                        } else {
+                                            format!("⏱️ Délai expiré pour `{}`.", tool_call.tool)
+                                        };
                                     }
                                     false
                                 }
@@ -775,15 +1107,20 @@ pub fn ChatView() -> Element {
                         PermissionResult::Denied => {
                             let mut msgs = messages.write();
                             if let Some(last) = msgs.last_mut() {
-                                last.content = format!(
-                                    "🚫 Permission refusée pour `{}`.",
-                                    tool_call.tool
-                                );
+                                last.content = if status_en {
+                                    format!("🚫 Permission denied for `{}`.", tool_call.tool)
+                                } else {
+                                    format!("🚫 Permission refusée pour `{}`.", tool_call.tool)
+                                };
                             }
                             false
                         }
                     };
 
+                    if escalation_preview.is_some() {
+                        app_state.escalation_preview.set(None);
+                    }
+
                     if !approved {
                         // Record denied permission in context and try alternative
                         agent_ctx.tool_history.push(ToolHistoryEntry {
@@ -796,17 +1133,14 @@ pub fn ChatView() -> Element {
                         });
                         
                         // Add message to help LLM find alternative
-                        messages.write().push(Message {
-                            role: MessageRole::System,
-                            content: format!(
+                        messages.write().push(Message::new(
+                            MessageRole::System,
+                            format!(
                                 "L'outil {} a été refusé. Essaie une autre approche ou réponds avec les informations disponibles.",
                                 tool_call.tool
                             ),
-                        });
-                        messages.write().push(Message {
-                            role: MessageRole::Assistant,
-                            content: String::new(),
-                        });
+                        ));
+                        messages.write().push(Message::new(MessageRole::Assistant, String::new()));
                         continue;
                     }
 
@@ -817,43 +1151,153 @@ pub fn ChatView() -> Element {
                             agent_ctx.consecutive_errors += 1;
                             let mut msgs = messages.write();
                             if let Some(last) = msgs.last_mut() {
-                                last.content = format!("❌ Outil introuvable: `{}`.", tool_call.tool);
+                                last.content = if status_en {
+                                    format!("❌ Tool not found: `{}`.", tool_call.tool)
+                                } else {
+                                    format!("❌ Outil introuvable: `{}`.", tool_call.tool)
+                                };
                             }
                             // Let the LLM try a different tool
-                            let available_tools: Vec<String> = app_state.agent.tool_registry.list_tools().iter().map(|t| t.name.clone()).collect();
-                            msgs.push(Message {
-                                role: MessageRole::System,
-                                content: format!(
+                            let available_tools: Vec<String> = visible_tools().iter().map(|t| t.name.clone()).collect();
+                            msgs.push(Message::new(
+                                MessageRole::System,
+                                format!(
                                     "L'outil `{}` n'existe pas. Voici les outils disponibles: {}. Utilise un des outils existants ou réponds directement.",
                                     tool_call.tool,
                                     available_tools.join(", ")
                                 ),
-                            });
-                            msgs.push(Message {
-                                role: MessageRole::Assistant,
-                                content: String::new(),
-                            });
-                            if agent_ctx.consecutive_errors >= 3 {
+                            ));
+                            msgs.push(Message::new(MessageRole::Assistant, String::new()));
+                            if !reflection.can_recover(agent_ctx.consecutive_errors) {
                                 break;
                             }
                             continue;
                         }
                     };
 
+                    let persistent_cache = app_state.settings.read().persistent_tool_cache;
+                    let replayed = replay
+                        .as_ref()
+                        .map(|recording| replay_call(recording, &tool_call.tool, &tool_call.params));
+                    let cached = if persistent_cache && replayed.is_none() {
+                        tool_cache::get_cached_result(&tool_call.tool, &tool_call.params)
+                    } else {
+                        None
+                    };
+                    let from_cache = cached.is_some() || replayed.is_some();
+                    let mut tool_cancelled = false;
+
                     tracing::info!("Executing tool: {} with timeout {}s", tool_call.tool, tool_timeout_secs);
                     let start_time = Instant::now();
-                    let tool_result: Result<ToolResult, String> = match tokio::time::timeout(
-                        std::time::Duration::from_secs(tool_timeout_secs),
-                        tool.execute(tool_call.params.clone()),
-                    )
-                    .await
-                    {
-                        Ok(Ok(result)) => Ok(result),
-                        Ok(Err(e)) => Err(e.to_string()),
-                        Err(_) => Err("Timeout dépassé".to_string()),
+                    let tool_result: Result<ToolResult, String> = if let Some(outcome) = replayed {
+                        outcome
+                    } else if let Some(result) = cached {
+                        Ok(result)
+                    } else {
+                        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+                        let cancel = CancelToken::new();
+                        app_state.active_tool_cancel.set(Some(cancel.clone()));
+                        if let Some(progress) = app_state.run_progress.write().as_mut() {
+                            progress.apply(&AgentEvent::ToolCallStarted {
+                                tool: tool_call.tool.clone(),
+                                params: tool_call.params.clone(),
+                            });
+                        }
+                        let execution = tokio::time::timeout(
+                            std::time::Duration::from_secs(tool_timeout_secs),
+                            tool.execute_with_context(
+                                tool_call.params.clone(),
+                                ToolContext { progress: progress_tx, cancel: cancel.clone() },
+                            ),
+                        );
+                        tokio::pin!(execution);
+
+                        // Show progress under the running-tool indicator as it arrives
+                        let header = messages.read().last().map(|m| m.content.clone()).unwrap_or_default();
+                        let mut progress_log = ToolProgressLog::default();
+                        let outcome = loop {
+                            tokio::select! {
+                                outcome = &mut execution => break outcome,
+                                // Tools that don't watch the token are dropped, which kills their processes
+                                _ = cancel.cancelled() => break Ok(Err(ToolError::Cancelled)),
+                                Some(update) = progress_rx.recv() => {
+                                    progress_log.push(update);
+                                    if let Some(last) = messages.write().last_mut() {
+                                        last.content = format!("{}\n{}", header, progress_log.render());
+                                    }
+                                }
+                            }
+                        };
+                        app_state.active_tool_cancel.set(None);
+                        if let Some(progress) = app_state.run_progress.write().as_mut() {
+                            progress.tool = None;
+                        }
+                        match outcome {
+                            Ok(Ok(result)) => Ok(result),
+                            Ok(Err(ToolError::Cancelled)) => {
+                                tool_cancelled = true;
+                                Err(ToolError::Cancelled.to_string())
+                            }
+                            Ok(Err(e)) => Err(e.to_string()),
+                            Err(_) => Err("Timeout dépassé".to_string()),
+                        }
                     };
                     let duration_ms = start_time.elapsed().as_millis() as u64;
 
+                    // Cached results are recorded too: a replay must not depend on the cache
+                    if let ToolReplayMode::Record(name) = &replay_mode {
+                        if !tool_cancelled {
+                            let call = RecordedCall {
+                                tool: tool_call.tool.clone(),
+                                params: tool_call.params.clone(),
+                                outcome: match &tool_result {
+                                    Ok(result) => RecordedOutcome::Ok(result.clone()),
+                                    Err(e) => RecordedOutcome::Err(e.clone()),
+                                },
+                            };
+                            if let Err(e) = tool_recordings::record_call(name, &call) {
+                                tracing::warn!("Failed to record tool call: {}", e);
+                            }
+                        }
+                    }
+
+                    // Audit log backing the conversation's privacy badge
+                    if let Some(conversation_id) = &conversation_id {
+                        let domains = if permission_level == PermissionLevel::Network {
+                            let service_url = escalation_preview
+                                .as_ref()
+                                .map(|p| p.provider.endpoint().to_string())
+                                .unwrap_or_else(|| app_state.settings.read().exa_mcp_url.clone());
+                            audit_log::network_domains(&tool_call.tool, &tool_call.params, Some(&service_url))
+                        } else {
+                            Vec::new()
+                        };
+                        let entry = AuditEntry {
+                            timestamp: Utc::now(),
+                            tool: tool_call.tool.clone(),
+                            level: permission_level,
+                            domains,
+                            success: matches!(&tool_result, Ok(result) if result.success),
+                            from_cache,
+                            duration_ms: Some(duration_ms),
+                            error: match &tool_result {
+                                Ok(result) if !result.success => Some(result.message.clone()),
+                                Ok(_) => None,
+                                Err(e) => Some(e.clone()),
+                            },
+                            model: match &*app_state.model_state.read() {
+                                ModelState::Loaded(path) => std::path::Path::new(path)
+                                    .file_name()
+                                    .map(|name| name.to_string_lossy().to_string()),
+                                _ => None,
+                            },
+                        };
+                        match audit_log::record_tool_call(conversation_id, &entry) {
+                            Ok(()) => *app_state.audit_revision.write() += 1,
+                            Err(e) => tracing::warn!("Failed to write audit log: {}", e),
+                        }
+                    }
+
                     // Process result and update context
                     agent_ctx.state = AgentState::Observing;
                     
@@ -862,6 +1306,26 @@ pub fn ChatView() -> Element {
                             tracing::info!("Tool {} executed successfully in {}ms: success={}, message_len={}",
                                 tool_call.tool, duration_ms, result.success, result.message.len()
                             );
+                            agent_ctx.record_result(&tool_call.tool, &tool_call.params, &result);
+                            if let Some(preview) = &escalation_preview {
+                                let conversation_id = app_state
+                                    .current_conversation
+                                    .read()
+                                    .as_ref()
+                                    .map(|c| c.id.clone());
+                                if let Err(e) = escalation_log::record_escalation(&EscalationRecord::from_result(
+                                    preview,
+                                    conversation_id,
+                                    &result,
+                                )) {
+                                    tracing::warn!("Failed to record escalation cost: {}", e);
+                                }
+                            }
+                            if persistent_cache && !from_cache {
+                                if let Err(e) = tool_cache::store_result(&tool_call.tool, &tool_call.params, &result) {
+                                    tracing::warn!("Failed to cache result of {}: {}", tool_call.tool, e);
+                                }
+                            }
                             // Record success in history
                             agent_ctx.tool_history.push(ToolHistoryEntry {
                                 tool_name: tool_call.tool.clone(),
@@ -880,35 +1344,94 @@ pub fn ChatView() -> Element {
                                 result.message.clone()
                             };
                             
-                            messages.write().push(Message {
-                                role: MessageRole::Assistant,
-                                content: format!(
-                                    "✅ `{}` ({:.1}s): {}",
+                            messages.write().push(Message::new(
+                                MessageRole::Assistant,
+                                format!(
+                                    "✅ `{}` ({:.1}s): {}{}",
                                     tool_call.tool,
                                     duration_ms as f64 / 1000.0,
+                                    if from_cache { "♻️ " } else { "" },
                                     result_preview
                                 ),
-                            });
+                            ));
+
+                            // Web and file content is untrusted: strip tool calls from it and flag injection attempts
+                            let injection_report = (app_state.settings.read().prompt_injection_defense
+                                && injection::is_external_content_tool(&tool_call.tool))
+                                .then(|| injection::sanitize_result(&result));
+                            if let Some((_, report)) = injection_report.as_ref().filter(|(_, r)| r.is_suspicious()) {
+                                tracing::warn!("Suspected prompt injection in {} result: {}", tool_call.tool, report.describe());
+                                messages.write().push(Message::new(
+                                    MessageRole::Assistant,
+                                    if status_en {
+                                        format!(
+                                            "⚠️ Possible prompt injection in the result of `{}` ({}). The content is treated as plain data.",
+                                            tool_call.tool,
+                                            report.describe()
+                                        )
+                                    } else {
+                                        format!(
+                                            "⚠️ Tentative d'injection de prompt possible dans le résultat de `{}` ({}). Le contenu est traité comme de simples données.",
+                                            tool_call.tool,
+                                            report.describe()
+                                        )
+                                    },
+                                ));
+                            }
 
                             // Inject tool result for LLM (capped to prevent context overflow)
-                            let tool_result_text = format_tool_result_for_system(&tool_call.tool, &result);
+                            let prompt_result = injection_report.as_ref().map(|(clean, _)| clean).unwrap_or(&result);
+                            let tool_result_text = format_tool_result_for_system(&tool_call.tool, prompt_result);
                             let tool_result_text = if tool_result_text.len() > 4000 {
                                 let truncated: String = tool_result_text.chars().take(3500).collect();
                                 format!("{}...\n[Résultat tronqué: {} caractères au total]", truncated, tool_result_text.len())
                             } else {
                                 tool_result_text
                             };
-                            messages.write().push(Message {
-                                role: MessageRole::System,
-                                content: tool_result_text,
-                            });
+                            let tool_result_text = match &injection_report {
+                                Some((_, report)) => injection::wrap_external_content(&tool_call.tool, &tool_result_text, report),
+                                None => tool_result_text,
+                            };
+                            expect_tool_call = reflection.reflect_after_success(agent_ctx.iteration);
+                            let tool_result_text = if expect_tool_call {
+                                build_reflection_prompt(&tool_call.tool, &tool_result_text, true)
+                            } else {
+                                tool_result_text
+                            };
+                            messages.write().push(Message::new(MessageRole::System, tool_result_text));
 
                             // Prepare for reflection/next iteration
                             agent_ctx.state = AgentState::Reflecting;
-                            messages.write().push(Message {
-                                role: MessageRole::Assistant,
-                                content: String::new(),
+                            messages.write().push(Message::new(MessageRole::Assistant, String::new()));
+                        }
+                        Err(_) if tool_cancelled => {
+                            tracing::info!("Tool {} cancelled by user after {}ms", tool_call.tool, duration_ms);
+                            agent_ctx.tool_history.push(ToolHistoryEntry {
+                                tool_name: tool_call.tool.clone(),
+                                params: tool_call.params.clone(),
+                                result: None,
+                                error: Some("Cancelled by user".to_string()),
+                                timestamp: Utc::now().timestamp() as u64,
+                                duration_ms,
                             });
+
+                            // Not a tool failure: no error count, no reflection
+                            let mut msgs = messages.write();
+                            if let Some(last) = msgs.last_mut() {
+                                last.content = if status_en {
+                                    format!("🚫 `{}` cancelled by the user.", tool_call.tool)
+                                } else {
+                                    format!("🚫 `{}` annulé par l'utilisateur.", tool_call.tool)
+                                };
+                            }
+                            msgs.push(Message::new(
+                                MessageRole::System,
+                                format!(
+                                    "L'utilisateur a annulé l'outil `{}`. Ne le relance pas: continue autrement ou réponds avec les informations disponibles.",
+                                    tool_call.tool
+                                ),
+                            ));
+                            msgs.push(Message::new(MessageRole::Assistant, String::new()));
                         }
                         Err(e) => {
                             tracing::warn!("Tool {} failed after {}ms: {}", tool_call.tool, duration_ms, e);
@@ -925,10 +1448,11 @@ pub fn ChatView() -> Element {
                             agent_ctx.consecutive_errors += 1;
                             
                             // Show error and inject reflection prompt
-                            let error_msg = format!(
-                                "❌ Erreur `{}`: {}",
-                                tool_call.tool, e
-                            );
+                            let error_msg = if status_en {
+                                format!("❌ Error in `{}`: {}", tool_call.tool, e)
+                            } else {
+                                format!("❌ Erreur `{}`: {}", tool_call.tool, e)
+                            };
                             
                             let mut msgs = messages.write();
                             if let Some(last) = msgs.last_mut() {
@@ -936,29 +1460,33 @@ pub fn ChatView() -> Element {
                             }
                             
                             // Give LLM a chance to recover
-                            if agent_ctx.consecutive_errors < 4 {
-                                msgs.push(Message {
-                                    role: MessageRole::System,
-                                    content: build_reflection_prompt(&tool_call.tool, &e, false),
-                                });
-                                msgs.push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: String::new(),
-                                });
+                            if reflection.can_recover(agent_ctx.consecutive_errors) {
+                                expect_tool_call = reflection.reflect_after_failure(agent_ctx.consecutive_errors);
+                                let mut content = if expect_tool_call {
+                                    build_reflection_prompt(&tool_call.tool, &e, false)
+                                } else {
+                                    format!("L'outil `{}` a échoué: {}", tool_call.tool, e)
+                                };
+                                if !escalation_suggested {
+                                    if let Some(reason) = escalation.trigger_for_failures(agent_ctx.consecutive_errors) {
+                                        escalation_suggested = true;
+                                        escalation_reason.get_or_insert(reason);
+                                        content.push_str("\n\nSi tu bloques, tu peux demander l'aide d'un modèle externe plus puissant avec l'outil `ai_consult` (l'utilisateur devra l'approuver).");
+                                    }
+                                }
+                                msgs.push(Message::new(MessageRole::System, content));
+                                msgs.push(Message::new(MessageRole::Assistant, String::new()));
                                 agent_ctx.state = AgentState::Reflecting;
                             } else {
                                 // Too many errors — add a final message explaining the situation
-                                msgs.push(Message {
-                                    role: MessageRole::System,
-                                    content: format!(
+                                msgs.push(Message::new(
+                                    MessageRole::System,
+                                    format!(
                                         "Trop d'erreurs consécutives ({}). Arrête d'utiliser des outils et donne une réponse finale à l'utilisateur en expliquant ce que tu as essayé et ce qui n'a pas marché. Propose des solutions alternatives si possible.",
                                         agent_ctx.consecutive_errors
                                     ),
-                                });
-                                msgs.push(Message {
-                                    role: MessageRole::Assistant,
-                                    content: String::new(),
-                                });
+                                ));
+                                msgs.push(Message::new(MessageRole::Assistant, String::new()));
                                 // One last generation attempt for the final message
                             }
                         }
@@ -966,6 +1494,7 @@ pub fn ChatView() -> Element {
                 }
 
                 app_state.is_generating.set(false);
+                app_state.run_progress.set(None);
 
                 {
                     let mut msgs = messages.write();
@@ -1014,9 +1543,21 @@ pub fn ChatView() -> Element {
                                 temperature: 0.3,
                                 top_k: 40,
                                 top_p: 0.9,
+                                min_p: params.min_p,
+                                typical_p: 0.0,
                                 repeat_penalty: 1.1,
+                                presence_penalty: 0.0,
+                                frequency_penalty: 0.0,
+                                penalty_last_n: 64,
                                 seed: 0,
                                 max_context_size: 2048,
+                                truncation: params.truncation,
+                                stop_sequences: params.stop_sequences.clone(),
+                                logprobs: false,
+                                output_cleanup: params.output_cleanup,
+                                reuse_prefix: params.reuse_prefix,
+                                grammar: None,
+                                context_over_vram: params.context_over_vram,
                             };
                             
                             let title_messages = vec![
@@ -1033,6 +1574,7 @@ pub fn ChatView() -> Element {
                                             StreamToken::Token(t) => text.push_str(&t),
                                             StreamToken::Done | StreamToken::Truncated { .. } => break,
                                             StreamToken::Error(_) => break,
+                                            StreamToken::Logprob(_) | StreamToken::Progress { .. } => {}
                                         }
                                     }
                                     // Clean up the title (remove thinking tags, quotes if present, trim)
@@ -1077,11 +1619,32 @@ pub fn ChatView() -> Element {
                         .map(|m| m.into())
                         .collect();
                     
+                    let (auto_tags, workspace) = {
+                        let settings = app_state.settings.read();
+                        (settings.auto_tags.clone(), settings.workspace.clone())
+                    };
+                    let mut tagged = false;
                     let mut conv_write = app_state.current_conversation.write();
                     if let Some(ref mut conv) = *conv_write {
                         conv.messages = storage_messages;
-                        if let Err(e) = save_conversation(conv) {
-                            tracing::error!("Failed to save conversation: {}", e);
+                        let suggested = auto_tags.suggest(&conv.messages, workspace.as_deref());
+                        tagged = apply_auto_tags(conv, suggested);
+                        if let Err(e) = store_images(conv) {
+                            tracing::warn!("Failed to store image attachments: {}", e);
+                        }
+                        match save_conversation(conv) {
+                            Ok(()) => {
+                                app_state.last_saved.set(Some(Instant::now()));
+                                crash::clear_session();
+                            }
+                            Err(e) => tracing::error!("Failed to save conversation: {}", e),
+                        }
+                    }
+                    drop(conv_write);
+                    // New tags show up in the sidebar filters
+                    if tagged {
+                        if let Ok(conversations) = list_conversations() {
+                            app_state.conversations.set(conversations);
                         }
                     }
                 }
@@ -1094,20 +1657,65 @@ pub fn ChatView() -> Element {
         let mut app_state = app_state.clone();
         move |_| {
             app_state.stop_signal.store(true, Ordering::Relaxed);
+            if let Some(cancel) = app_state.active_tool_cancel.read().as_ref() {
+                cancel.cancel();
+            }
+            // A run waiting for approval gets a refusal rather than a dialog left open
+            let denied = app_state.agent.permission_manager.deny_all_pending();
+            if denied > 0 {
+                tracing::info!("Stop denied {} pending permission request(s)", denied);
+            }
+            app_state.escalation_preview.set(None);
             app_state.is_generating.set(false);
+            app_state.run_progress.set(None);
         }
     };
 
+    // Separators above the first message of each day, for multi-day chats
+    let is_en = app_state.settings.read().language == "en";
+    let day_separators: HashMap<usize, String> =
+        conversation_days(&messages.read(), is_en).into_iter().collect();
+
     rsx! {
         div { class: "flex flex-col flex-1 min-h-0 relative",
+
+            // Privacy badge (what left the machine in this conversation)
+            div { class: "absolute top-3 right-6 z-10 flex items-center gap-2",
+                SaveIndicator {}
+                PrivacyBadge {}
+            }
+
+            // Jump to a day of a long conversation
+            div { class: "absolute top-3 left-6 z-10",
+                JumpToDate {}
+            }
+
+            // In-chat search (Ctrl+F)
+            ChatSearchBar {}
             
             // Messages Area — narrower for readability
             div { class: "flex-1 min-h-0 overflow-y-auto px-4 py-4 custom-scrollbar scroll-smooth",
+                id: "chat-messages",
                 div { class: "max-w-3xl mx-auto w-full flex flex-col gap-1 pb-4",
                     // Message List
                     for (idx, msg) in messages.read().iter().enumerate() {
                         if msg.role != MessageRole::System {
-                            MessageBubble { key: "{idx}", message: msg.clone() }
+                            div { key: "{idx}",
+                                if let Some(label) = day_separators.get(&idx) {
+                                    DaySeparator { index: idx, label: label.clone() }
+                                }
+                                div { id: "chat-msg-{idx}",
+                                    MessageBubble {
+                                        message: msg.clone(),
+                                        index: idx,
+                                        tool_result: messages
+                                            .read()
+                                            .get(idx + 1)
+                                            .filter(|next| next.role == MessageRole::System)
+                                            .map(|next| next.content.clone()),
+                                    }
+                                }
+                            }
                         }
                     }
                     
@@ -1125,6 +1733,7 @@ pub fn ChatView() -> Element {
                                     div { class: "w-1.5 h-1.5 rounded-full bg-[var(--accent-primary)] opacity-60 animate-bounce delay-75" }
                                     div { class: "w-1.5 h-1.5 rounded-full bg-[var(--accent-primary)] opacity-60 animate-bounce delay-150" }
                                 }
+                                RunTimer {}
                             }
                         }
                     }
@@ -1133,6 +1742,9 @@ pub fn ChatView() -> Element {
                 }
             }
 
+            // Background deep research tasks
+            ResearchCard {}
+
             // Input Area
             ChatInput {
                 on_send: handle_send,
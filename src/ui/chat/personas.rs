@@ -0,0 +1,142 @@
+//! Persona picker shown above the chat input
+//!
+//! The persona is stored on the conversation. Selecting one with a preferred
+//! model loads that model unless it is already loaded. The bar also holds
+//! the conversation's response language.
+
+use crate::agent::language::RESPONSE_LANGUAGES;
+use crate::app::{AppState, ModelState};
+use crate::storage::conversations::save_conversation;
+use dioxus::prelude::*;
+
+/// Set the persona of the open conversation and load its preferred model
+/// unless it is already loaded
+pub fn set_persona(app_state: &mut AppState, id: Option<String>) {
+    {
+        let mut current = app_state.current_conversation.write();
+        let Some(conversation) = current.as_mut() else {
+            return;
+        };
+        conversation.persona = id.clone();
+        if let Err(error) = save_conversation(conversation) {
+            tracing::error!("Failed to save conversation: {}", error);
+        }
+    }
+    let (model, gpu_layers) = {
+        let settings = app_state.settings.read();
+        let model = id.and_then(|id| settings.persona(&id)).and_then(|p| p.preferred_model);
+        (model, settings.gpu_layers)
+    };
+    let Some(model) = model else {
+        return;
+    };
+    let loaded = match &*app_state.model_state.read() {
+        ModelState::Loaded(path) => path == &model,
+        ModelState::Loading => true,
+        _ => false,
+    };
+    if !loaded {
+        app_state.load_model(model, gpu_layers);
+    }
+}
+
+#[component]
+pub fn PersonaBar() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read();
+    let is_en = settings.language == "en";
+    // (id, label, tooltip)
+    let personas: Vec<(String, String, String)> = settings
+        .personas()
+        .into_iter()
+        .map(|p| {
+            let tools = if p.allow_tools.is_empty() {
+                if is_en {
+                    "all tools".to_string()
+                } else {
+                    "tous les outils".to_string()
+                }
+            } else {
+                p.allow_tools.join(", ")
+            };
+            let mut title = if p.deny_tools.is_empty() {
+                tools
+            } else {
+                format!("{} − {}", tools, p.deny_tools.join(", "))
+            };
+            if let Some(model) = &p.preferred_model {
+                let file = model.rsplit(['/', '\\']).next().unwrap_or(model);
+                title.push_str(&format!(" · {}", file));
+            }
+            (p.id.clone(), p.label(is_en), title)
+        })
+        .collect();
+    drop(settings);
+    let active = app_state
+        .current_conversation
+        .read()
+        .as_ref()
+        .and_then(|c| c.persona.clone());
+    let language = app_state
+        .current_conversation
+        .read()
+        .as_ref()
+        .and_then(|c| c.language.clone())
+        .unwrap_or_default();
+    let generating = *app_state.is_generating.read();
+    let mut app_state_language = app_state.clone();
+
+    let select = move |id: Option<String>| {
+        let mut app_state = app_state.clone();
+        move |_| set_persona(&mut app_state, id.clone())
+    };
+    let pill = |selected: bool| {
+        if selected {
+            "px-2.5 py-0.5 rounded-full text-[11px] font-medium transition-colors bg-[var(--accent-soft)] text-[var(--accent-primary)]"
+        } else {
+            "px-2.5 py-0.5 rounded-full text-[11px] font-medium transition-colors text-[var(--text-tertiary)] hover:bg-white/[0.06] disabled:opacity-50"
+        }
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center gap-1 mb-1 px-2",
+            button {
+                class: pill(active.is_none()),
+                disabled: generating,
+                title: if is_en { "Default agent with every enabled tool" } else { "Agent par defaut avec tous les outils actives" },
+                onclick: select(None),
+                if is_en { "Default" } else { "Par defaut" }
+            }
+            for (id, label, title) in personas {
+                button {
+                    key: "{id}",
+                    class: pill(active.as_deref() == Some(id.as_str())),
+                    disabled: generating,
+                    title: "{title}",
+                    onclick: select(Some(id.clone())),
+                    "{label}"
+                }
+            }
+            select {
+                class: "ml-auto px-1.5 py-0.5 rounded-full text-[11px] bg-transparent text-[var(--text-tertiary)] hover:bg-white/[0.06] focus:outline-none disabled:opacity-50",
+                disabled: generating,
+                title: if is_en { "Language the agent answers in" } else { "Langue des reponses de l'agent" },
+                onchange: move |e: Event<FormData>| {
+                    let mut current = app_state_language.current_conversation.write();
+                    let Some(conversation) = current.as_mut() else {
+                        return;
+                    };
+                    conversation.language = Some(e.value()).filter(|code| !code.is_empty());
+                    if let Err(error) = save_conversation(conversation) {
+                        tracing::error!("Failed to save conversation: {}", error);
+                    }
+                },
+                option { value: "", selected: language.is_empty(), if is_en { "Respond in: auto" } else { "Repondre en : auto" } }
+                for (code, name) in RESPONSE_LANGUAGES.iter() {
+                    option { key: "{code}", value: "{code}", selected: language == *code, "{name}" }
+                }
+            }
+        }
+    }
+}
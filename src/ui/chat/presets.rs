@@ -0,0 +1,86 @@
+//! Generation preset bar shown above the chat input
+//!
+//! Also holds the one-shot "long reply" toggle, which raises max_tokens for
+//! the next message only (same as prefixing it with `/long`).
+
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use crate::ui::chat::input::LONG_REPLY_TOKENS;
+use dioxus::prelude::*;
+
+#[component]
+pub fn PresetBar() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read();
+    let is_en = settings.language == "en";
+    let active = settings.active_preset.clone();
+    // (name, label, tooltip)
+    let presets: Vec<(String, String, String)> = settings
+        .presets()
+        .into_iter()
+        .map(|p| {
+            let title = format!(
+                "T {:.1} · top_k {} · top_p {:.2} · {} tokens",
+                p.temperature, p.top_k, p.top_p, p.max_tokens
+            );
+            (p.name.clone(), p.label(is_en), title)
+        })
+        .collect();
+    drop(settings);
+    let mut next_max_tokens = app_state.next_max_tokens;
+    let long_armed = next_max_tokens().is_some();
+    let long_label = match next_max_tokens() {
+        Some(tokens) if is_en => format!("Long reply · {} tokens", tokens),
+        Some(tokens) => format!("Reponse longue · {} tokens", tokens),
+        None if is_en => "Long reply".to_string(),
+        None => "Reponse longue".to_string(),
+    };
+
+    let select = move |name: Option<String>| {
+        let mut app_state = app_state.clone();
+        move |_| {
+            let mut settings = app_state.settings.write();
+            settings.active_preset = name.clone();
+            if let Err(error) = save_settings(&settings) {
+                tracing::error!("Failed to save settings: {}", error);
+            }
+        }
+    };
+    let pill = |selected: bool| {
+        if selected {
+            "px-2.5 py-0.5 rounded-full text-[11px] font-medium transition-colors bg-[var(--accent-soft)] text-[var(--accent-primary)]"
+        } else {
+            "px-2.5 py-0.5 rounded-full text-[11px] font-medium transition-colors text-[var(--text-tertiary)] hover:bg-white/[0.06]"
+        }
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center gap-1 mb-2 px-2",
+            button {
+                class: pill(active.is_none()),
+                title: if is_en { "Use the values from Settings" } else { "Utiliser les valeurs des parametres" },
+                onclick: select(None),
+                if is_en { "Manual" } else { "Manuel" }
+            }
+            for (name, label, title) in presets {
+                button {
+                    key: "{name}",
+                    class: pill(active.as_deref() == Some(name.as_str())),
+                    title: "{title}",
+                    onclick: select(Some(name.clone())),
+                    "{label}"
+                }
+            }
+            button {
+                class: "ml-auto {pill(long_armed)}",
+                title: if is_en { "Raise max tokens for the next message only (or start it with /long)" } else { "Augmenter max tokens pour le prochain message seulement (ou commencer par /long)" },
+                onclick: move |_| {
+                    let armed = next_max_tokens().is_some();
+                    next_max_tokens.set(if armed { None } else { Some(LONG_REPLY_TOKENS) });
+                },
+                "{long_label}"
+            }
+        }
+    }
+}
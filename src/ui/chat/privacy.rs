@@ -0,0 +1,83 @@
+//! Conversation privacy badge
+//!
+//! Green when every tool ran on-device, yellow once a web tool reached the
+//! network (with the contacted domains), red when content was sent to a
+//! cloud model. Computed from the conversation's audit log.
+
+use crate::app::AppState;
+use crate::storage::audit_log::{load_audit_log, summarize, PrivacyLevel};
+use dioxus::prelude::*;
+
+#[component]
+pub fn PrivacyBadge() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let current_conversation = app_state.current_conversation;
+    let audit_revision = app_state.audit_revision;
+    let mut expanded = use_signal(|| false);
+
+    // Only reload the log when a tool is recorded or the conversation changes
+    let summary = use_memo(move || {
+        let _ = audit_revision();
+        let id = current_conversation.read().as_ref().map(|c| c.id.clone())?;
+        Some(summarize(&load_audit_log(&id)))
+    });
+
+    let Some(summary) = summary() else {
+        return rsx! {};
+    };
+
+    let (label, title, style) = match summary.level {
+        PrivacyLevel::Local => (
+            "100% local",
+            if is_en { "No network tool ran in this conversation" } else { "Aucun outil reseau n'a ete utilise dans cette conversation" },
+            "background: rgba(52,211,153,0.10); color: #34d399; border: 1px solid rgba(52,211,153,0.25);",
+        ),
+        PrivacyLevel::Network => (
+            if is_en { "Web access" } else { "Acces web" },
+            if is_en { "Web tools contacted the domains below" } else { "Des outils web ont contacte les domaines ci-dessous" },
+            "background: rgba(251,191,36,0.10); color: #fbbf24; border: 1px solid rgba(251,191,36,0.25);",
+        ),
+        PrivacyLevel::Cloud => (
+            if is_en { "Sent to cloud model" } else { "Envoye a un modele cloud" },
+            if is_en { "Part of this conversation was sent to a remote AI model" } else { "Une partie de cette conversation a ete envoyee a un modele IA distant" },
+            "background: rgba(248,113,113,0.10); color: #f87171; border: 1px solid rgba(248,113,113,0.25);",
+        ),
+    };
+    let has_domains = !summary.domains.is_empty();
+
+    rsx! {
+        div {
+            class: "relative flex justify-end",
+
+            button {
+                class: "flex items-center gap-1.5 px-2.5 py-1 rounded-full text-[11px] font-medium transition-opacity hover:opacity-80",
+                style: "{style}",
+                title: "{title}",
+                onclick: move |_| {
+                    if has_domains {
+                        expanded.toggle();
+                    }
+                },
+                span { class: "w-1.5 h-1.5 rounded-full", style: "background: currentColor;" }
+                "{label}"
+                if summary.network_calls > 0 {
+                    span { class: "opacity-70", "· {summary.network_calls}" }
+                }
+            }
+
+            if expanded() && has_domains {
+                div {
+                    class: "absolute right-0 top-full mt-1 z-20 min-w-[200px] p-3 rounded-xl glass-md animate-fade-in",
+                    p {
+                        class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold mb-1.5",
+                        if is_en { "Contacted domains" } else { "Domaines contactes" }
+                    }
+                    for domain in summary.domains.iter() {
+                        p { class: "text-xs font-mono text-[var(--text-secondary)]", "{domain}" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Deep research progress card
+//!
+//! Lists the research tasks of this session above the input: query,
+//! elapsed time, interim findings and, once done, where the report is.
+
+use crate::agent::research::{self, ResearchStatus};
+use crate::app::AppState;
+use dioxus::prelude::*;
+
+/// First `max` characters of the last line worth showing
+fn preview(text: &str, max: usize) -> String {
+    let line = text
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    if line.chars().count() > max {
+        format!("{}…", line.chars().take(max).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+#[component]
+pub fn ResearchCard() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut revision = use_signal(research::revision);
+
+    // The poller runs outside Dioxus; follow it with a cheap counter check
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            let current = research::revision();
+            if current != *revision.peek() {
+                revision.set(current);
+            }
+        }
+    });
+
+    let _ = revision();
+    let tasks: Vec<_> = research::list().into_iter().filter(|t| !t.dismissed).collect();
+    if tasks.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "max-w-3xl mx-auto w-full px-4 flex flex-col gap-2 mb-2",
+            for task in tasks {
+                {
+                    let (label, style) = match (task.status, is_en) {
+                        (ResearchStatus::Running, true) => ("Researching", "color: var(--accent-primary);"),
+                        (ResearchStatus::Running, false) => ("Recherche en cours", "color: var(--accent-primary);"),
+                        (ResearchStatus::Completed, true) => ("Report ready", "color: #34d399;"),
+                        (ResearchStatus::Completed, false) => ("Rapport pret", "color: #34d399;"),
+                        (ResearchStatus::Failed, true) => ("Failed", "color: #f87171;"),
+                        (ResearchStatus::Failed, false) => ("Echec", "color: #f87171;"),
+                        (ResearchStatus::Cancelled, true) => ("Stopped", "color: var(--text-tertiary);"),
+                        (ResearchStatus::Cancelled, false) => ("Arretee", "color: var(--text-tertiary);"),
+                    };
+                    let elapsed = task.elapsed_secs();
+                    let elapsed = format!("{}:{:02}", elapsed / 60, elapsed % 60);
+                    let steps = task.findings.len();
+                    let latest = task
+                        .error
+                        .as_deref()
+                        .or(task.findings.last().map(|f| f.text.as_str()))
+                        .map(|t| preview(t, 160))
+                        .unwrap_or_default();
+                    let document = task.document.as_ref().map(|p| p.display().to_string());
+                    let running = task.is_running();
+                    let id = task.id.clone();
+                    rsx! {
+                        div {
+                            key: "{task.id}",
+                            class: "p-3 rounded-xl glass-md animate-fade-in",
+                            div { class: "flex items-center gap-2",
+                                span { class: "text-sm", "🔬" }
+                                span {
+                                    class: "flex-1 truncate text-sm font-medium text-[var(--text-primary)]",
+                                    title: "{task.query}",
+                                    "{task.query}"
+                                }
+                                span { class: "text-[11px] font-medium", style: "{style}", "{label}" }
+                                span { class: "text-[11px] text-[var(--text-tertiary)] tabular-nums", "{elapsed}" }
+                                if running {
+                                    button {
+                                        class: "px-2 py-0.5 rounded-md text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-error)] hover:bg-white/[0.06]",
+                                        onclick: move |_| research::cancel(&id),
+                                        if is_en { "Stop" } else { "Arreter" }
+                                    }
+                                } else {
+                                    button {
+                                        class: "px-1.5 rounded-md text-xs text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                        title: if is_en { "Hide" } else { "Masquer" },
+                                        onclick: move |_| research::dismiss(&id),
+                                        "✕"
+                                    }
+                                }
+                            }
+                            if running {
+                                div { class: "mt-2 h-1 rounded-full bg-white/[0.06] overflow-hidden",
+                                    div { class: "h-full w-1/3 rounded-full bg-[var(--accent-primary)] opacity-60 animate-pulse" }
+                                }
+                            }
+                            if !latest.is_empty() {
+                                p { class: "mt-2 text-xs text-[var(--text-secondary)] line-clamp-2", "{latest}" }
+                            }
+                            div { class: "mt-1.5 flex items-center gap-3 text-[11px] text-[var(--text-tertiary)]",
+                                span {
+                                    if is_en { "{steps} interim findings" } else { "{steps} etapes intermediaires" }
+                                }
+                                if let Some(path) = document {
+                                    span { class: "truncate font-mono", title: "{path}", "{path}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview() {
+        assert_eq!(preview("Searching...\nReading 4 sources\n\n", 100), "Reading 4 sources");
+        assert_eq!(preview("abcdef", 3), "abc…");
+        assert_eq!(preview("", 10), "");
+    }
+}
@@ -0,0 +1,61 @@
+//! Routing chip shown above the chat input
+//!
+//! Shows which rule, model and persona the draft would be routed to, and
+//! lets the next message skip the routing rules.
+
+use crate::app::AppState;
+use dioxus::prelude::*;
+
+#[component]
+pub fn RouteChip(draft: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read();
+    if !settings.routing.enabled || draft.trim().is_empty() {
+        return rsx! {};
+    }
+    let is_en = settings.language == "en";
+    let mut skip_route = app_state.skip_route;
+    let skipped = skip_route();
+
+    let label = match settings.routing.route(&draft) {
+        _ if skipped => {
+            if is_en {
+                "🔀 Routing skipped for this message".to_string()
+            } else {
+                "🔀 Routage ignore pour ce message".to_string()
+            }
+        }
+        Some(route) => {
+            let mut target = vec![route.rule.name.clone()];
+            if let Some(model) = &route.rule.model {
+                target.push(model.rsplit(['/', '\\']).next().unwrap_or(model).to_string());
+            }
+            if let Some(persona) = route.rule.persona.as_deref().and_then(|id| settings.persona(id)) {
+                target.push(persona.label(is_en));
+            }
+            format!("🔀 {} → {}", route.kind.label(is_en), target.join(" · "))
+        }
+        None => return rsx! {},
+    };
+    let title = match (skipped, is_en) {
+        (true, true) => "Click to route this message again",
+        (true, false) => "Cliquer pour router a nouveau ce message",
+        (false, true) => "Click to keep the current model and persona for this message",
+        (false, false) => "Cliquer pour garder le modele et le persona actuels pour ce message",
+    };
+
+    rsx! {
+        div { class: "flex items-center mb-1 px-2",
+            button {
+                class: if skipped {
+                    "px-2.5 py-0.5 rounded-full text-[11px] text-[var(--text-tertiary)] line-through hover:bg-white/[0.06] truncate max-w-full"
+                } else {
+                    "px-2.5 py-0.5 rounded-full text-[11px] font-medium bg-[var(--accent-soft)] text-[var(--accent-primary)] hover:opacity-80 truncate max-w-full"
+                },
+                title: "{title}",
+                onclick: move |_| skip_route.toggle(),
+                "{label}"
+            }
+        }
+    }
+}
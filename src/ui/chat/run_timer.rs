@@ -0,0 +1,62 @@
+//! Live timer next to the generating indicator
+//!
+//! Shows how long the agent has been running with staged hints, and a
+//! "wrap up now" button that asks for the final answer instead of stopping.
+
+use crate::app::AppState;
+use dioxus::prelude::*;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+#[component]
+pub fn RunTimer() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut tick = use_signal(|| 0u64);
+
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            tick += 1;
+        }
+    });
+
+    let _ = tick();
+    let Some(progress) = app_state.run_progress.read().clone() else {
+        return rsx! {};
+    };
+    let elapsed = progress.started_at.elapsed();
+    let Some(hint) = progress.hint(elapsed, is_en) else {
+        return rsx! {};
+    };
+    let near_limit = progress.near_limit(elapsed);
+    let button_class = if near_limit {
+        "px-2 py-0.5 rounded-md text-[11px] font-medium text-[var(--accent-primary)] bg-white/[0.08] hover:bg-white/[0.12] transition-colors"
+    } else {
+        "px-2 py-0.5 rounded-md text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06] transition-colors"
+    };
+    let mut app_state = app_state.clone();
+
+    rsx! {
+        div { class: "flex items-center gap-2 text-xs text-[var(--text-tertiary)]",
+            span { class: "tabular-nums", "{hint}" }
+            if !progress.wrapping_up {
+                button {
+                    class: button_class,
+                    title: if is_en {
+                        "Ask the agent to stop calling tools and answer with what it has"
+                    } else {
+                        "Demander a l'agent d'arreter les outils et de repondre avec ce qu'il a"
+                    },
+                    onclick: move |_| {
+                        app_state.wrap_up.store(true, Ordering::Relaxed);
+                        if let Some(progress) = app_state.run_progress.write().as_mut() {
+                            progress.wrapping_up = true;
+                        }
+                    },
+                    if is_en { "Wrap up now" } else { "Conclure maintenant" }
+                }
+            }
+        }
+    }
+}
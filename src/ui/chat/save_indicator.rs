@@ -0,0 +1,48 @@
+//! Subtle "saved" mark shown for a moment after the conversation is written
+
+use crate::app::AppState;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// How long the mark stays visible after a save
+const SHOWN_FOR: Duration = Duration::from_secs(2);
+
+#[component]
+pub fn SaveIndicator() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let autosave_secs = app_state.settings.read().autosave_interval_secs;
+    let last_saved = app_state.last_saved;
+    let mut visible = use_signal(|| false);
+
+    use_effect(move || {
+        if last_saved().is_none() {
+            return;
+        }
+        visible.set(true);
+        spawn(async move {
+            tokio::time::sleep(SHOWN_FOR).await;
+            // A later save keeps it up for its own delay
+            if last_saved.peek().is_some_and(|at| at.elapsed() >= SHOWN_FOR) {
+                visible.set(false);
+            }
+        });
+    });
+
+    let title = match (autosave_secs, is_en) {
+        (0, true) => "Autosave off: saved when the reply ends".to_string(),
+        (0, false) => "Sauvegarde auto desactivee : enregistre a la fin de la reponse".to_string(),
+        (secs, true) => format!("Saved every {}s while a reply streams", secs),
+        (secs, false) => format!("Enregistre toutes les {}s pendant une reponse", secs),
+    };
+
+    rsx! {
+        span {
+            class: "flex items-center gap-1 text-[11px] text-[var(--text-tertiary)] transition-opacity duration-500",
+            style: if visible() { "opacity: 1;" } else { "opacity: 0;" },
+            title: "{title}",
+            "✓ "
+            if is_en { "Saved" } else { "Enregistre" }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Search within the open conversation (Ctrl+F)
+//!
+//! Matches are found on the message text, so collapsed reasoning and tool
+//! results are searched too; going to a match opens its section, then the
+//! webview highlights every hit with the CSS Custom Highlight API and
+//! scrolls to the current one.
+
+use super::message::{is_tool_message, parse_thinking_blocks, ContentPart, Message, MessageRole};
+use crate::app::AppState;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// Where in a message a match is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchSection {
+    Text,
+    /// Inside a collapsed reasoning block
+    Thinking,
+    /// Inside the raw tool result shown under a tool card
+    ToolResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Index of the rendered message holding the match
+    pub message: usize,
+    pub section: MatchSection,
+    /// Position among the matches of that message
+    pub occurrence: usize,
+}
+
+fn count(haystack: &str, needle: &str) -> usize {
+    haystack.to_lowercase().matches(needle).count()
+}
+
+/// Case-insensitive matches of `query`, in display order
+///
+/// System messages aren't shown, except a tool result right after a tool
+/// message, which belongs to that message's card.
+pub fn find_matches(messages: &[Message], query: &str) -> Vec<SearchMatch> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    let mut push = |message: usize, section: MatchSection, n: usize| {
+        let first = matches
+            .iter()
+            .filter(|m: &&SearchMatch| m.message == message)
+            .count();
+        for occurrence in first..first + n {
+            matches.push(SearchMatch {
+                message,
+                section,
+                occurrence,
+            });
+        }
+    };
+    for (idx, message) in messages.iter().enumerate() {
+        match message.role {
+            MessageRole::System => {
+                let after_tool = idx > 0
+                    && messages[idx - 1].role != MessageRole::System
+                    && is_tool_message(&messages[idx - 1].content).is_some();
+                if after_tool {
+                    push(idx - 1, MatchSection::ToolResult, count(&message.content, &needle));
+                }
+            }
+            MessageRole::User => push(idx, MatchSection::Text, count(&message.content, &needle)),
+            MessageRole::Assistant if is_tool_message(&message.content).is_some() => {
+                push(idx, MatchSection::Text, count(&message.content, &needle))
+            }
+            MessageRole::Assistant => {
+                for part in parse_thinking_blocks(&message.content) {
+                    match part {
+                        ContentPart::Text(text) => push(idx, MatchSection::Text, count(&text, &needle)),
+                        ContentPart::Thinking(text) | ContentPart::ThinkingStreaming(text) => {
+                            push(idx, MatchSection::Thinking, count(&text, &needle))
+                        }
+                    }
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// Highlights every hit under `#chat-messages` and scrolls to the
+/// `occurrence`-th one inside `#chat-msg-{message}`
+const HIGHLIGHT_JS: &str = r#"
+window.__clawrsSearch = function (query, message, occurrence) {
+    if (!window.CSS || !CSS.highlights) return;
+    CSS.highlights.delete('chat-search');
+    CSS.highlights.delete('chat-search-current');
+    const root = document.getElementById('chat-messages');
+    if (!query || !root) return;
+    const needle = query.toLowerCase();
+    const target = message === null ? null : document.getElementById('chat-msg-' + message);
+    const walker = document.createTreeWalker(root, NodeFilter.SHOW_TEXT);
+    const ranges = [];
+    let current = null, seen = 0;
+    while (walker.nextNode()) {
+        const node = walker.currentNode;
+        const text = node.textContent.toLowerCase();
+        for (let i = text.indexOf(needle); i !== -1; i = text.indexOf(needle, i + needle.length)) {
+            const range = new Range();
+            range.setStart(node, i);
+            range.setEnd(node, i + needle.length);
+            ranges.push(range);
+            if (target && target.contains(node)) {
+                if (seen === occurrence) current = range;
+                seen++;
+            }
+        }
+    }
+    CSS.highlights.set('chat-search', new Highlight(...ranges));
+    if (current) {
+        CSS.highlights.set('chat-search-current', new Highlight(current));
+        current.startContainer.parentElement.scrollIntoView({ block: 'center' });
+    } else if (target) {
+        target.scrollIntoView({ block: 'center' });
+    }
+};
+"#;
+
+/// Opens the search bar on Ctrl+F / Cmd+F anywhere in the window
+const SHORTCUT_JS: &str = r#"
+if (window.__clawrsSearchKey) document.removeEventListener('keydown', window.__clawrsSearchKey);
+window.__clawrsSearchKey = function (e) {
+    if ((e.ctrlKey || e.metaKey) && e.key.toLowerCase() === 'f') {
+        e.preventDefault();
+        dioxus.send(true);
+    }
+};
+document.addEventListener('keydown', window.__clawrsSearchKey);
+"#;
+
+fn highlight(query: &str, target: Option<SearchMatch>) {
+    let query = serde_json::to_string(query).unwrap_or_default();
+    let (message, occurrence) = match target {
+        Some(m) => (m.message.to_string(), m.occurrence),
+        None => ("null".to_string(), 0),
+    };
+    document::eval(&format!(
+        "window.__clawrsSearch && window.__clawrsSearch({query}, {message}, {occurrence});"
+    ));
+}
+
+#[component]
+pub fn ChatSearchBar() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut open = use_signal(|| false);
+    let mut query = use_signal(String::new);
+    let mut current = use_signal(|| 0usize);
+
+    use_hook(move || {
+        document::eval(HIGHLIGHT_JS);
+        spawn(async move {
+            let mut shortcut = document::eval(SHORTCUT_JS);
+            while shortcut.recv::<bool>().await.is_ok() {
+                open.set(true);
+                document::eval("setTimeout(() => document.getElementById('chat-search-input')?.select(), 0);");
+            }
+        });
+    });
+
+    let matches = find_matches(&app_state.active_messages.read(), &query.read());
+    let total = matches.len();
+
+    // Open the match's section, then highlight once the DOM has caught up
+    let mut app_state_go = app_state.clone();
+    let mut go_to = move |index: usize| {
+        let matches = find_matches(&app_state_go.active_messages.read(), &query.read());
+        let target = matches.get(index).copied();
+        current.set(index);
+        app_state_go.search_focus.set(
+            target
+                .filter(|m| m.section == MatchSection::Thinking)
+                .map(|m| m.message),
+        );
+        if let Some(m) = target.filter(|m| m.section == MatchSection::ToolResult) {
+            app_state_go.focused_tool_call.set(Some(m.message));
+        }
+        let text = query.read().clone();
+        spawn(async move {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            highlight(&text, target);
+        });
+    };
+
+    let mut app_state_close = app_state.clone();
+    let mut close = move || {
+        open.set(false);
+        query.set(String::new());
+        app_state_close.search_focus.set(None);
+        highlight("", None);
+    };
+
+    if !open() {
+        return rsx! {};
+    }
+    let counter = if query.read().trim().is_empty() {
+        String::new()
+    } else if total == 0 {
+        if is_en { "No results".to_string() } else { "Aucun resultat".to_string() }
+    } else {
+        format!("{}/{}", current().min(total - 1) + 1, total)
+    };
+
+    rsx! {
+        div { class: "absolute top-3 left-1/2 -translate-x-1/2 z-20 flex items-center gap-1.5 px-2 py-1.5 rounded-xl glass-md shadow-lg",
+            input {
+                id: "chat-search-input",
+                class: "w-56 px-2 py-1 rounded-md text-sm bg-transparent text-[var(--text-primary)] focus:outline-none",
+                placeholder: if is_en { "Search this conversation" } else { "Rechercher dans la conversation" },
+                autofocus: true,
+                value: "{query}",
+                oninput: move |e| {
+                    query.set(e.value());
+                    go_to(0);
+                },
+                onkeydown: move |e: KeyboardEvent| {
+                    if e.key() == Key::Escape {
+                        close();
+                    } else if e.key() == Key::Enter && total > 0 {
+                        let step = if e.modifiers().contains(Modifiers::SHIFT) { total - 1 } else { 1 };
+                        go_to((current() + step) % total);
+                    }
+                },
+            }
+            span { class: "text-xs text-[var(--text-tertiary)] tabular-nums min-w-[3.5rem] text-right", "{counter}" }
+            button {
+                class: "w-7 h-7 rounded-md text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06] disabled:opacity-40",
+                disabled: total == 0,
+                title: if is_en { "Previous (Shift+Enter)" } else { "Precedent (Maj+Entree)" },
+                onclick: move |_| {
+                    if total > 0 {
+                        go_to((current() + total - 1) % total);
+                    }
+                },
+                "↑"
+            }
+            button {
+                class: "w-7 h-7 rounded-md text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06] disabled:opacity-40",
+                disabled: total == 0,
+                title: if is_en { "Next (Enter)" } else { "Suivant (Entree)" },
+                onclick: move |_| {
+                    if total > 0 {
+                        go_to((current() + 1) % total);
+                    }
+                },
+                "↓"
+            }
+            button {
+                class: "w-7 h-7 rounded-md text-[var(--text-tertiary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06]",
+                title: if is_en { "Close (Esc)" } else { "Fermer (Echap)" },
+                onclick: move |_| close(),
+                "✕"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: MessageRole, content: &str) -> Message {
+        Message::new(role, content)
+    }
+
+    #[test]
+    fn test_find_matches_sections() {
+        let messages = vec![
+            message(MessageRole::User, "Where is the Config?"),
+            message(
+                MessageRole::Assistant,
+                "<think>look for config files</think>The config is in config.toml",
+            ),
+            message(MessageRole::Assistant, "✅ file_read config.toml"),
+            message(MessageRole::System, "<tool_result>[config]\nport = 80</tool_result>"),
+            message(MessageRole::System, "config note the model sees but the user doesn't"),
+        ];
+        let matches = find_matches(&messages, " CONFIG ");
+        let sections: Vec<(usize, MatchSection, usize)> = matches
+            .iter()
+            .map(|m| (m.message, m.section, m.occurrence))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                (0, MatchSection::Text, 0),
+                (1, MatchSection::Thinking, 0),
+                (1, MatchSection::Text, 1),
+                (1, MatchSection::Text, 2),
+                (2, MatchSection::Text, 0),
+                (2, MatchSection::ToolResult, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_matches_empty_query() {
+        let messages = vec![message(MessageRole::User, "hello")];
+        assert!(find_matches(&messages, "  ").is_empty());
+        assert!(find_matches(&messages, "bye").is_empty());
+    }
+}
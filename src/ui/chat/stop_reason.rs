@@ -0,0 +1,118 @@
+//! Badge under a reply that was cut off
+//!
+//! Says whether max_tokens or the context ran out and, on the latest reply,
+//! offers to continue, raise the limit or compact the history first.
+
+use crate::app::AppState;
+use crate::storage::settings::{save_settings, CONTEXT_SIZES};
+use crate::types::message::StopReason;
+use crate::ui::chat::compact_history;
+use dioxus::prelude::*;
+
+fn continue_prompt(is_en: bool) -> String {
+    if is_en { "Continue where you stopped." } else { "Continue là où tu t'es arrêté." }.to_string()
+}
+
+/// Settings change behind "Increase limit": max_tokens doubles (within the
+/// context), the context goes to the next size; `None` when already at the top
+pub fn raised_limit(reason: StopReason, max_tokens: u32, context_size: u32) -> Option<u32> {
+    match reason {
+        StopReason::MaxTokens => Some((max_tokens * 2).min(context_size)).filter(|&raised| raised > max_tokens),
+        StopReason::Context => CONTEXT_SIZES.iter().copied().find(|&size| size > context_size),
+    }
+}
+
+#[component]
+pub fn StopReasonBadge(index: usize, reason: StopReason) -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, max_tokens, context_size) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.max_tokens, settings.context_size)
+    };
+    let is_latest = index + 1 == app_state.active_messages.read().len() && !(app_state.is_generating)();
+    let raise_to = raised_limit(reason, max_tokens, context_size);
+
+    let (label, title) = match (reason, is_en) {
+        (StopReason::MaxTokens, true) => ("✂ Stopped at max tokens", format!("The reply reached the {} token limit", max_tokens)),
+        (StopReason::MaxTokens, false) => ("✂ Arrete a la limite de jetons", format!("La reponse a atteint la limite de {} jetons", max_tokens)),
+        (StopReason::Context, true) => ("✂ Context full", format!("The prompt and the reply filled the {} token context", context_size)),
+        (StopReason::Context, false) => ("✂ Contexte plein", format!("Le prompt et la reponse ont rempli le contexte de {} jetons", context_size)),
+    };
+    let raise_label = match (reason, raise_to, is_en) {
+        (StopReason::MaxTokens, Some(raised), true) => format!("Max tokens → {}", raised),
+        (StopReason::MaxTokens, Some(raised), false) => format!("Jetons max → {}", raised),
+        (StopReason::Context, Some(raised), true) => format!("Context → {}K", raised / 1024),
+        (StopReason::Context, Some(raised), false) => format!("Contexte → {}K", raised / 1024),
+        (_, None, _) => String::new(),
+    };
+    let action_class = "px-1.5 py-0.5 rounded-md text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06]";
+
+    let mut pending_prompt = app_state.pending_prompt;
+    let mut settings = app_state.settings;
+    let mut messages = app_state.active_messages;
+
+    rsx! {
+        span {
+            class: "px-2 py-0.5 rounded-full text-[10px] bg-[var(--warning-bg)] text-[var(--warning)]",
+            title: "{title}",
+            "{label}"
+        }
+        if is_latest {
+            button {
+                class: action_class,
+                onclick: move |_| pending_prompt.set(Some(continue_prompt(is_en))),
+                if is_en { "Continue" } else { "Continuer" }
+            }
+            if let Some(raised) = raise_to {
+                button {
+                    class: action_class,
+                    title: if is_en { "Raise the limit, then continue" } else { "Augmenter la limite, puis continuer" },
+                    onclick: move |_| {
+                        {
+                            let mut settings = settings.write();
+                            match reason {
+                                StopReason::MaxTokens => {
+                                    settings.max_tokens = raised;
+                                    settings.max_tokens_auto = false;
+                                }
+                                StopReason::Context => settings.context_size = raised,
+                            }
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        }
+                        pending_prompt.set(Some(continue_prompt(is_en)));
+                    },
+                    "{raise_label}"
+                }
+            }
+            button {
+                class: action_class,
+                title: if is_en { "Shorten old messages, then continue" } else { "Raccourcir les anciens messages, puis continuer" },
+                onclick: move |_| {
+                    // The cut-off reply stays whole so it can be continued
+                    let mut messages = messages.write();
+                    let reply = messages.pop();
+                    compact_history(&mut messages);
+                    messages.extend(reply);
+                    pending_prompt.set(Some(continue_prompt(is_en)));
+                },
+                if is_en { "Compact" } else { "Compacter" }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raised_limit() {
+        assert_eq!(raised_limit(StopReason::MaxTokens, 1024, 8192), Some(2048));
+        assert_eq!(raised_limit(StopReason::MaxTokens, 6000, 8192), Some(8192));
+        assert_eq!(raised_limit(StopReason::MaxTokens, 8192, 8192), None);
+        assert_eq!(raised_limit(StopReason::Context, 1024, 8192), Some(16384));
+        assert_eq!(raised_limit(StopReason::Context, 1024, 131072), None);
+    }
+}
@@ -0,0 +1,154 @@
+//! Day separators and a jump-to-date menu for long conversations
+//!
+//! A separator is drawn above the first message of each day once the
+//! conversation spans more than one day; the menu scrolls to any of them.
+
+use super::message::{Message, MessageRole};
+use crate::app::AppState;
+use chrono::{Local, NaiveDate, TimeZone};
+use dioxus::prelude::*;
+
+/// First shown message of each day, as (message index, day)
+///
+/// Messages without a timestamp (saved before timestamps were kept) stay
+/// with the day before them.
+pub fn day_breaks<Tz: TimeZone>(messages: &[Message], tz: &Tz) -> Vec<(usize, NaiveDate)> {
+    let mut breaks: Vec<(usize, NaiveDate)> = Vec::new();
+    for (idx, message) in messages.iter().enumerate() {
+        if message.role == MessageRole::System || message.timestamp == 0 {
+            continue;
+        }
+        let Some(time) = tz.timestamp_opt(message.timestamp as i64, 0).single() else {
+            continue;
+        };
+        let day = time.date_naive();
+        if breaks.last().is_none_or(|(_, last)| *last < day) {
+            breaks.push((idx, day));
+        }
+    }
+    breaks
+}
+
+/// "Today", "Yesterday", "Oct 12, 2026" / "Aujourd'hui", "Hier", "12/10/2026"
+pub fn day_label(day: NaiveDate, today: NaiveDate, is_en: bool) -> String {
+    match (today - day).num_days() {
+        0 => if is_en { "Today" } else { "Aujourd'hui" }.to_string(),
+        1 => if is_en { "Yesterday" } else { "Hier" }.to_string(),
+        _ if is_en => day.format("%b %-d, %Y").to_string(),
+        _ => day.format("%d/%m/%Y").to_string(),
+    }
+}
+
+/// Days of the active conversation with their labels, empty for a single day
+pub fn conversation_days(messages: &[Message], is_en: bool) -> Vec<(usize, String)> {
+    let breaks = day_breaks(messages, &Local);
+    if breaks.len() < 2 {
+        return Vec::new();
+    }
+    let today = Local::now().date_naive();
+    breaks
+        .into_iter()
+        .map(|(idx, day)| (idx, day_label(day, today, is_en)))
+        .collect()
+}
+
+#[component]
+pub fn DaySeparator(index: usize, label: String) -> Element {
+    rsx! {
+        div {
+            id: "chat-day-{index}",
+            class: "flex items-center gap-3 my-4 select-none",
+            div { class: "flex-1 h-px bg-[var(--border-subtle)]" }
+            span { class: "text-[11px] font-medium text-[var(--text-tertiary)] uppercase tracking-wide", "{label}" }
+            div { class: "flex-1 h-px bg-[var(--border-subtle)]" }
+        }
+    }
+}
+
+/// Dropdown listing the days of the conversation
+#[component]
+pub fn JumpToDate() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut open = use_signal(|| false);
+
+    let days = conversation_days(&app_state.active_messages.read(), is_en);
+    if days.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "relative",
+            button {
+                class: "flex items-center gap-1.5 px-2.5 py-1 rounded-full text-[11px] font-medium glass-md text-[var(--text-secondary)] hover:text-[var(--text-primary)] transition-colors",
+                title: if is_en { "Jump to a date" } else { "Aller a une date" },
+                onclick: move |_| open.toggle(),
+                "📅 "
+                if is_en { "{days.len()} days" } else { "{days.len()} jours" }
+            }
+            if open() {
+                div { class: "absolute left-0 mt-1.5 min-w-[10rem] max-h-72 overflow-y-auto custom-scrollbar py-1 rounded-xl glass-md shadow-lg",
+                    for (idx, label) in days {
+                        button {
+                            key: "{idx}",
+                            class: "block w-full px-3 py-1.5 text-left text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06]",
+                            onclick: move |_| {
+                                open.set(false);
+                                document::eval(&format!(
+                                    "document.getElementById('chat-day-{idx}')?.scrollIntoView({{ block: 'start' }});"
+                                ));
+                            },
+                            "{label}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(role: MessageRole, timestamp: u64) -> Message {
+        Message {
+            timestamp,
+            ..Message::new(role, "")
+        }
+    }
+
+    #[test]
+    fn test_day_breaks() {
+        let day = 86_400;
+        let monday = 1_760_313_600; // 2025-10-13 00:00 UTC
+        let messages = vec![
+            at(MessageRole::User, monday + 10),
+            at(MessageRole::Assistant, monday + 20),
+            at(MessageRole::System, monday + day + 5),
+            at(MessageRole::Assistant, 0),
+            at(MessageRole::User, monday + 2 * day + 30),
+            at(MessageRole::Assistant, monday + 2 * day + 40),
+        ];
+        let breaks = day_breaks(&messages, &Utc);
+        let days: Vec<(usize, String)> = breaks
+            .iter()
+            .map(|(idx, date)| (*idx, date.to_string()))
+            .collect();
+        assert_eq!(
+            days,
+            vec![(0, "2025-10-13".to_string()), (4, "2025-10-15".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_day_label() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert_eq!(day_label(today, today, true), "Today");
+        assert_eq!(day_label(today.pred_opt().unwrap(), today, false), "Hier");
+        let older = NaiveDate::from_ymd_opt(2026, 10, 2).unwrap();
+        assert_eq!(day_label(older, today, true), "Oct 2, 2026");
+        assert_eq!(day_label(older, today, false), "02/10/2026");
+    }
+}
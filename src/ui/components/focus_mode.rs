@@ -0,0 +1,128 @@
+//! Focus mode button in the header
+//!
+//! Starts focus mode for a picked duration, then shows the time left and
+//! ends it on demand. The registry and notifications enforce it (see
+//! [`crate::agent::focus`]); this only drives it.
+
+use crate::agent::focus::{focus_remaining, FOCUS_DURATIONS};
+use crate::app::AppState;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// "25 min", "1h30", "3h"
+fn duration_label(minutes: u64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m} min"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m:02}"),
+    }
+}
+
+#[component]
+pub fn FocusModeButton() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    // Subscribes to the saved end so starting or ending re-renders
+    let _ = app_state.settings.read().focus_until;
+    let mut open = use_signal(|| false);
+    let mut tick = use_signal(|| 0u64);
+
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(20)).await;
+            tick += 1;
+        }
+    });
+
+    let _ = tick();
+    let remaining_minutes = focus_remaining().map(|secs| secs.div_ceil(60));
+    let app_state_end = app_state.clone();
+
+    rsx! {
+        div { class: "relative",
+            button {
+                r#type: "button",
+                class: if remaining_minutes.is_some() {
+                    "h-8 px-2 rounded-lg bg-white/[0.08] flex items-center gap-1 text-xs text-[var(--accent-primary)] transition-all"
+                } else {
+                    "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all"
+                },
+                title: if is_en {
+                    "Focus mode: no network tools, no notifications"
+                } else {
+                    "Mode concentration : ni outils reseau, ni notifications"
+                },
+                onclick: move |_| open.toggle(),
+                svg {
+                    width: "15", height: "15", view_box: "0 0 24 24", fill: "none",
+                    stroke: "currentColor", stroke_width: "1.5", stroke_linecap: "round", stroke_linejoin: "round",
+                    path { d: "M21 12.79A9 9 0 1 1 11.21 3 7 7 0 0 0 21 12.79z" }
+                }
+                if let Some(minutes) = remaining_minutes {
+                    span { class: "tabular-nums", "{minutes} min" }
+                }
+            }
+
+            if open() {
+                div {
+                    class: "absolute left-1/2 mt-2 p-3 rounded-xl z-50 animate-fade-in space-y-3",
+                    style: "transform: translateX(-50%); width: 260px; background: var(--bg-elevated); border: 1px solid var(--border-medium); box-shadow: 0 12px 32px -4px rgba(30,25,20,0.35);",
+
+                    div { class: "flex items-center justify-between",
+                        span {
+                            class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
+                            if is_en { "Focus mode" } else { "Mode concentration" }
+                        }
+                        button {
+                            class: "text-xs text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                            onclick: move |_| open.set(false),
+                            "✕"
+                        }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)]",
+                        if is_en {
+                            "Web and other network tools are off and desktop notifications are held back until the time is up."
+                        } else {
+                            "Les outils web et reseau sont coupes et les notifications retenues jusqu'a la fin du temps."
+                        }
+                    }
+
+                    if let Some(minutes) = remaining_minutes {
+                        div { class: "flex items-center justify-between gap-2",
+                            span {
+                                class: "text-xs text-[var(--text-secondary)]",
+                                if is_en { "{minutes} min left" } else { "{minutes} min restantes" }
+                            }
+                            button {
+                                class: "px-3 py-1.5 rounded-lg text-xs font-medium bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] hover:bg-white/[0.08]",
+                                onclick: move |_| {
+                                    app_state_end.set_focus(None);
+                                    open.set(false);
+                                },
+                                if is_en { "End now" } else { "Terminer" }
+                            }
+                        }
+                    } else {
+                        div { class: "flex items-center gap-1.5",
+                            for minutes in FOCUS_DURATIONS {
+                                button {
+                                    key: "{minutes}",
+                                    class: "flex-1 px-2 py-1.5 rounded-lg text-xs bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] hover:bg-white/[0.08]",
+                                    onclick: {
+                                        let app_state = app_state.clone();
+                                        move |_| {
+                                            app_state.set_focus(Some(minutes));
+                                            open.set(false);
+                                        }
+                                    },
+                                    {duration_label(minutes)}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -2,7 +2,11 @@
 //!
 //! Reusable components like buttons, inputs, cards, and other primitives.
 
+pub mod focus_mode;
 pub mod loading;
 pub mod monitoring;
 pub mod permission_dialog;
+pub mod quick_settings;
+pub mod restore_session;
 pub mod tool_usage;
+pub mod update_banner;
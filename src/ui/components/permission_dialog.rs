@@ -1,28 +1,239 @@
 //! Permission dialog UI component
 //!
-//! Displays permission requests and allows user approval/denial
+//! Displays permission requests and allows user approval/denial. The full
+//! parameters are shown highlighted, with risky fragments (rm, sudo, force
+//! push, system paths) flagged so an approval is an informed one.
 
-use crate::agent::permissions::PermissionLevel;
+use crate::agent::permissions::{PermissionLevel, PermissionManager, PermissionRequest};
+use crate::agent::skills::skill_call_permissions;
 use crate::app::AppState;
 use dioxus::prelude::*;
+use uuid::Uuid;
 
-/// Permission dialog component
+/// Commands flagged wherever they appear as a word
+const DANGEROUS_WORDS: &[&str] = &["rm", "rmdir", "sudo", "su", "doas", "mkfs", "dd", "shred", "shutdown", "reboot", "chmod", "chown"];
+/// Options and subcommands flagged anywhere
+const DANGEROUS_PHRASES: &[&str] = &["--force", "push -f", "reset --hard", "clean -fd", "rm -rf", ":(){"];
+/// System locations; a path starting with one is flagged whole
+const SYSTEM_PATHS: &[&str] = &[
+    "/etc", "/usr", "/bin", "/sbin", "/boot", "/sys", "/proc", "/dev", "/lib", "/var", "/root", "/system",
+    "c:\\\\windows", "c:\\\\program files", "c:/windows",
+];
+
+/// Piece of the pretty-printed parameters
+#[derive(Debug, Clone, PartialEq)]
+enum ParamSpan {
+    /// Punctuation and whitespace
+    Plain(String),
+    /// Object key, quotes included
+    Key(String),
+    /// String value text
+    Text(String),
+    /// Number, boolean or null
+    Literal(String),
+    /// Risky fragment inside a string value
+    Danger(String),
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Byte ranges of the risky fragments in `text`, sorted and merged
+fn dangerous_ranges(text: &str) -> Vec<(usize, usize)> {
+    let lower = text.to_lowercase();
+    // Lowercasing can change byte lengths outside ASCII; skip those texts
+    if lower.len() != text.len() {
+        return Vec::new();
+    }
+    let boundary_before = |start: usize| !lower[..start].chars().next_back().is_some_and(is_word_char);
+    let mut ranges = Vec::new();
+    for word in DANGEROUS_WORDS {
+        for (start, _) in lower.match_indices(word) {
+            let end = start + word.len();
+            if boundary_before(start) && !lower[end..].chars().next().is_some_and(is_word_char) {
+                ranges.push((start, end));
+            }
+        }
+    }
+    for phrase in DANGEROUS_PHRASES {
+        ranges.extend(lower.match_indices(phrase).map(|(start, _)| (start, start + phrase.len())));
+    }
+    for prefix in SYSTEM_PATHS {
+        for (start, _) in lower.match_indices(prefix) {
+            let after = start + prefix.len();
+            let continues = lower[after..].chars().next();
+            if boundary_before(start) && !continues.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                let end = lower[after..]
+                    .find(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == ';')
+                    .map_or(lower.len(), |i| after + i);
+                ranges.push((start, end));
+            }
+        }
+    }
+    ranges.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Split the text of a string value into plain and risky spans
+fn push_string_value(spans: &mut Vec<ParamSpan>, text: &str) {
+    let mut cursor = 0;
+    for (start, end) in dangerous_ranges(text) {
+        if start > cursor {
+            spans.push(ParamSpan::Text(text[cursor..start].to_string()));
+        }
+        spans.push(ParamSpan::Danger(text[start..end].to_string()));
+        cursor = end;
+    }
+    if cursor < text.len() {
+        spans.push(ParamSpan::Text(text[cursor..].to_string()));
+    }
+}
+
+/// Tokenize pretty-printed JSON for highlighting
+fn highlight_params(json: &str) -> Vec<ParamSpan> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut chars = json.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '"' {
+            let mut end = json.len();
+            let mut escaped = false;
+            for (i, c) in chars.by_ref() {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    end = i + 1;
+                    break;
+                }
+            }
+            if !plain.is_empty() {
+                spans.push(ParamSpan::Plain(std::mem::take(&mut plain)));
+            }
+            let string = &json[start..end];
+            if json[end..].trim_start().starts_with(':') {
+                spans.push(ParamSpan::Key(string.to_string()));
+            } else {
+                push_string_value(&mut spans, string);
+            }
+        } else if c.is_ascii_alphanumeric() || c == '-' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            if !plain.is_empty() {
+                spans.push(ParamSpan::Plain(std::mem::take(&mut plain)));
+            }
+            spans.push(ParamSpan::Literal(json[start..end].to_string()));
+        } else {
+            plain.push(c);
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(ParamSpan::Plain(plain));
+    }
+    spans
+}
+
+/// Covered by "approve all safe": a safe level, nothing risky in the
+/// parameters and not one of the consent-only tools
+fn is_safe_request(request: &PermissionRequest) -> bool {
+    request.level.is_safe()
+        && !matches!(request.tool_name.as_str(), "ai_consult" | "skill_create")
+        && !highlight_params(&request.params.to_string())
+            .iter()
+            .any(|span| matches!(span, ParamSpan::Danger(_)))
+}
+
+/// Seconds left before the request is denied; `None` when the run waits
+/// for the user
+fn seconds_left(manager: &PermissionManager, request: &PermissionRequest, timeout_secs: u32) -> Option<u64> {
+    (timeout_secs > 0).then(|| (timeout_secs as u64).saturating_sub(manager.waited(request.id).as_secs()))
+}
+
+/// Permission dialog component: the selected request in detail and, when
+/// several are waiting, the whole queue with bulk decisions
 #[component]
 pub fn PermissionDialog() -> Element {
     let app_state = use_context::<AppState>();
     let signals = app_state.agent.permission_manager.signals();
-    let requests = signals.pending_requests.read();
+    let pending = signals.pending_requests;
+    let mut selected = use_signal(|| None::<Uuid>);
+    // Ticks the countdowns while requests are waiting
+    let mut tick = use_signal(|| 0u64);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            if !pending.peek().is_empty() {
+                tick += 1;
+            }
+        }
+    });
+    tick.read();
+    let requests = pending.read().clone();
 
     if requests.is_empty() {
         return rsx! { div {} };
     }
 
-    let current_request = &requests[0];
-    let request_id = current_request.id;
     let manager = app_state.agent.permission_manager.clone();
+    let timeout_secs = app_state.settings.read().permission_timeout_secs;
+    let current_request = requests
+        .iter()
+        .find(|request| selected() == Some(request.id))
+        .unwrap_or(&requests[0])
+        .clone();
+    let current_request = &current_request;
+    let request_id = current_request.id;
+    let current_left = seconds_left(&manager, current_request, timeout_secs);
+    let safe_count = requests.iter().filter(|request| is_safe_request(request)).count();
     let manager_deny = manager.clone();
     let manager_approve = manager.clone();
+    let manager_approve_safe = manager.clone();
+    let manager_deny_all = manager.clone();
     let is_en = app_state.settings.read().language == "en";
+    let escalation = app_state
+        .escalation_preview
+        .read()
+        .clone()
+        .filter(|_| current_request.tool_name == "ai_consult");
+    let skill_permissions = skill_call_permissions(
+        &app_state.agent.skill_registry,
+        &current_request.tool_name,
+        &current_request.params,
+    );
+    let is_skill_review = current_request.tool_name == "skill_create";
+    let description = app_state
+        .agent
+        .tool_registry
+        .get(&current_request.tool_name)
+        .map(|tool| match tool.description_fr().filter(|_| !is_en) {
+            Some(french) => french.to_string(),
+            None => tool.description().to_string(),
+        })
+        .filter(|d| !d.trim().is_empty());
+    let params = highlight_params(&serde_json::to_string_pretty(&current_request.params).unwrap_or_default());
+    let dangers = params
+        .iter()
+        .filter_map(|span| match span {
+            ParamSpan::Danger(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
 
     rsx! {
         // Backdrop — heavy blur
@@ -31,7 +242,7 @@ pub fn PermissionDialog() -> Element {
 
             // Dialog — glass-strong with spring animation
             div {
-                class: "w-full max-w-lg glass-strong rounded-2xl overflow-hidden animate-scale-in",
+                class: "w-full max-w-lg max-h-[90vh] flex flex-col glass-strong rounded-2xl overflow-hidden animate-scale-in",
 
                 // Header — with warning icon
                 div {
@@ -67,12 +278,54 @@ pub fn PermissionDialog() -> Element {
                     p {
                         class: "text-sm text-[var(--text-secondary)]",
                         if is_en { "The AI agent is requesting permission to perform an action." } else { "L'agent IA demande la permission d'effectuer une action." }
+                        span { class: "text-[var(--text-tertiary)]",
+                            match (current_left, is_en) {
+                                (Some(left), true) => format!(" Denied automatically in {left}s; the countdown pauses while the window is in the background."),
+                                (Some(left), false) => format!(" Refusee automatiquement dans {left}s ; le decompte s'arrete quand la fenetre est en arriere-plan."),
+                                (None, true) => " The run is paused until you decide.".to_string(),
+                                (None, false) => " L'execution est en pause jusqu'a votre decision.".to_string(),
+                            }
+                        }
+                    }
+                }
+
+                // Queue — every waiting request, the selected one detailed below
+                if requests.len() > 1 {
+                    div { class: "px-6 pt-4 space-y-1.5",
+                        span { class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
+                            if is_en { "{requests.len()} requests waiting" } else { "{requests.len()} demandes en attente" }
+                        }
+                        div { class: "max-h-40 overflow-y-auto custom-scrollbar space-y-1",
+                            for request in requests.iter().cloned() {
+                                {
+                                    let is_current = request.id == request_id;
+                                    let left = seconds_left(&manager, &request, timeout_secs)
+                                        .map(|left| format!("{left}s"))
+                                        .unwrap_or_else(|| "⏸".to_string());
+                                    rsx! {
+                                        button {
+                                            key: "{request.id}",
+                                            class: if is_current {
+                                                "w-full flex items-center gap-2 px-3 py-2 rounded-lg text-left bg-white/[0.08] border border-[var(--accent-primary)]"
+                                            } else {
+                                                "w-full flex items-center gap-2 px-3 py-2 rounded-lg text-left bg-white/[0.03] border border-[var(--border-subtle)] hover:bg-white/[0.06]"
+                                            },
+                                            onclick: move |_| selected.set(Some(request.id)),
+                                            PermissionLevelBadge { level: request.level }
+                                            span { class: "text-sm text-[var(--accent-primary)] font-medium shrink-0", "{request.tool_name}" }
+                                            span { class: "text-xs font-mono text-[var(--text-tertiary)] truncate flex-1", "{request.target}" }
+                                            span { class: "text-[11px] text-[var(--text-tertiary)] tabular-nums shrink-0", "{left}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
 
                 // Content
                 div {
-                    class: "p-6 space-y-3",
+                    class: "p-6 space-y-3 overflow-y-auto custom-scrollbar",
 
                     // Tool info — glass card
                     div {
@@ -101,6 +354,10 @@ pub fn PermissionDialog() -> Element {
                             }
                             PermissionLevelBadge { level: current_request.level }
                         }
+
+                        if let Some(description) = description {
+                            p { class: "mt-3 text-xs text-[var(--text-tertiary)] line-clamp-3", title: "{description}", "{description}" }
+                        }
                     }
 
                     // Target — glass card
@@ -112,19 +369,126 @@ pub fn PermissionDialog() -> Element {
                         p { class: "mt-1 text-sm font-mono text-[var(--text-secondary)] break-all", "{current_request.target}" }
                     }
 
-                    // Parameters
-                    details {
+                    // Permissions declared by the skill being created or run
+                    if let Some(permissions) = skill_permissions {
+                        div {
+                            class: "p-4 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)]",
+                            span { class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
+                                if is_skill_review {
+                                    if is_en { "Permissions the new skill declares" } else { "Permissions declarees par le nouveau skill" }
+                                } else if is_en {
+                                    "Skill permissions"
+                                } else {
+                                    "Permissions du skill"
+                                }
+                            }
+                            div { class: "flex flex-wrap gap-2 mt-2",
+                                for permission in permissions {
+                                    span {
+                                        class: "px-2 py-1 rounded-md text-xs font-medium bg-white/[0.05] text-[var(--text-primary)]",
+                                        "{permission.icon()} {permission.label(is_en)}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Escalation consent: exactly what leaves the machine
+                    if let Some(preview) = escalation {
+                        div {
+                            class: "p-4 rounded-xl border space-y-2",
+                            style: "background: rgba(56,189,248,0.05); border-color: rgba(56,189,248,0.25);",
+                            span { class: "text-[10px] uppercase tracking-widest font-semibold", style: "color: #38bdf8;",
+                                if is_en { "Data leaving this machine" } else { "Donnees envoyees hors de cette machine" }
+                            }
+                            p { class: "text-xs text-[var(--text-secondary)]",
+                                if is_en {
+                                    "Sent to {preview.provider.label()} ({preview.provider.endpoint()}) with model {preview.model}. Reason: {preview.reason.label(true)}."
+                                } else {
+                                    "Envoye a {preview.provider.label()} ({preview.provider.endpoint()}) avec le modele {preview.model}. Raison : {preview.reason.label(false)}."
+                                }
+                            }
+                            pre {
+                                class: "text-xs text-[var(--text-primary)] font-mono whitespace-pre-wrap break-words overflow-y-auto custom-scrollbar p-2 rounded-lg bg-black/20",
+                                style: "max-height: 180px;",
+                                "{preview.outgoing_text}"
+                            }
+                            p { class: "text-xs text-[var(--text-tertiary)]",
+                                {
+                                    let cost = match preview.estimated_cost_usd {
+                                        Some(cost) => format!("~${:.4}", cost),
+                                        None => if is_en { "unknown price".to_string() } else { "prix inconnu".to_string() },
+                                    };
+                                    if is_en {
+                                        format!("~{} tokens sent · max cost {}", preview.estimated_tokens, cost)
+                                    } else {
+                                        format!("~{} tokens envoyes · cout max {}", preview.estimated_tokens, cost)
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Parameters, in full, with risky fragments flagged
+                    div {
                         class: "p-4 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)]",
-                        summary { class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold cursor-pointer",
+                        span { class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
                             if is_en { "Parameters" } else { "Parametres" }
                         }
-                        pre { class: "mt-2 text-xs text-[var(--text-secondary)] overflow-x-auto font-mono", "{serde_json::to_string_pretty(&current_request.params).unwrap_or_default()}" }
+                        if !dangers.is_empty() {
+                            p { class: "mt-1 text-xs", style: "color: #f87171;",
+                                if is_en { "⚠ Risky: {dangers}" } else { "⚠ A risque : {dangers}" }
+                            }
+                        }
+                        pre {
+                            class: "mt-2 text-xs font-mono whitespace-pre-wrap break-all overflow-y-auto custom-scrollbar p-2 rounded-lg bg-black/20",
+                            style: "max-height: 220px;",
+                            for span in params {
+                                match span {
+                                    ParamSpan::Plain(text) => rsx! { span { class: "text-[var(--text-tertiary)]", "{text}" } },
+                                    ParamSpan::Key(text) => rsx! { span { class: "text-[var(--accent-primary)]", "{text}" } },
+                                    ParamSpan::Text(text) => rsx! { span { style: "color: #34d399;", "{text}" } },
+                                    ParamSpan::Literal(text) => rsx! { span { style: "color: #fbbf24;", "{text}" } },
+                                    ParamSpan::Danger(text) => rsx! {
+                                        span {
+                                            class: "font-semibold rounded px-0.5",
+                                            style: "color: #f87171; background: rgba(248,113,113,0.15);",
+                                            "{text}"
+                                        }
+                                    },
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Bulk decisions
+                if requests.len() > 1 {
+                    div { class: "px-6 pt-4 border-t border-[var(--border-subtle)] flex gap-3",
+                        button {
+                            class: "btn-ghost flex-1 text-xs",
+                            title: if is_en { "Read-only requests with nothing risky in their parameters" } else { "Demandes en lecture seule sans rien de risque dans leurs parametres" },
+                            disabled: safe_count == 0,
+                            onclick: move |_| {
+                                manager_approve_safe.approve_where(is_safe_request);
+                                selected.set(None);
+                            },
+                            if is_en { "Approve all safe ({safe_count})" } else { "Approuver les sures ({safe_count})" }
+                        }
+                        button {
+                            class: "btn-ghost flex-1 text-xs",
+                            onclick: move |_| {
+                                manager_deny_all.deny_all_pending();
+                                selected.set(None);
+                            },
+                            if is_en { "Deny all ({requests.len()})" } else { "Tout refuser ({requests.len()})" }
+                        }
                     }
                 }
 
                 // Footer — glass buttons
                 div {
-                    class: "p-6 border-t border-[var(--border-subtle)] flex gap-3",
+                    class: if requests.len() > 1 { "p-6 pt-3 flex gap-3" } else { "p-6 border-t border-[var(--border-subtle)] flex gap-3" },
 
                     button {
                         class: "btn-ghost flex-1",
@@ -193,3 +557,34 @@ fn PermissionLevelBadge(level: PermissionLevel) -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dangers(json: &str) -> Vec<String> {
+        highlight_params(json)
+            .into_iter()
+            .filter_map(|span| match span {
+                ParamSpan::Danger(text) => Some(text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_highlight_params() {
+        let json = serde_json::to_string_pretty(&serde_json::json!({
+            "command": "sudo rm -rf /etc/nginx && git push --force",
+            "timeout": 30,
+        }))
+        .unwrap();
+        assert_eq!(dangers(&json), vec!["sudo", "rm -rf", "/etc/nginx", "--force"]);
+        let spans = highlight_params(&json);
+        assert!(spans.contains(&ParamSpan::Key("\"command\"".to_string())));
+        assert!(spans.contains(&ParamSpan::Literal("30".to_string())));
+        // Words and paths inside other words are left alone
+        assert!(dangers(r#"{"path": "/home/me/form/etcetera", "text": "firmware"}"#).is_empty());
+        assert_eq!(dangers(r#"{"path": "C:\\Windows\\System32"}"#), vec!["C:\\\\Windows\\\\System32"]);
+    }
+}
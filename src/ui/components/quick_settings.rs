@@ -0,0 +1,203 @@
+//! Quick settings popover next to the header model picker
+//!
+//! The knobs changed most often while chatting, without leaving the chat
+//! (and its scroll position) for the full Settings view.
+
+use crate::app::AppState;
+use crate::storage::settings::{save_settings, MAX_TOKENS_LIMIT};
+use crate::ui::chat::personas::set_persona;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-2 py-1 rounded-md text-xs text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+
+#[component]
+pub fn QuickSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let mut open = use_signal(|| false);
+    let (is_en, params, max_tokens_auto, active_preset, tools_enabled, locked, personas) = {
+        let settings = app_state.settings.read();
+        let is_en = settings.language == "en";
+        let personas: Vec<(String, String)> = settings
+            .personas()
+            .into_iter()
+            .map(|p| (p.id.clone(), p.label(is_en)))
+            .collect();
+        (
+            is_en,
+            settings.generation_params(),
+            settings.max_tokens_auto,
+            settings.active_preset.clone(),
+            settings.tools_enabled,
+            settings.lock.enabled,
+            personas,
+        )
+    };
+    let conversation_persona = app_state
+        .current_conversation
+        .read()
+        .as_ref()
+        .map(|c| c.persona.clone().unwrap_or_default());
+    let generating = *app_state.is_generating.read();
+    let temperature_label = format!("{:.1}", params.temperature);
+    let max_tokens_value = if max_tokens_auto { String::new() } else { params.max_tokens.to_string() };
+    let max_tokens_max = MAX_TOKENS_LIMIT.min(params.max_context_size);
+    let preset_note = active_preset.map(|name| {
+        if is_en {
+            format!("Preset \"{}\" is active; changing a value here turns it off.", name)
+        } else {
+            format!("Le preset \"{}\" est actif ; modifier une valeur ici le desactive.", name)
+        }
+    });
+    let mut app_state_temperature = app_state.clone();
+    let mut app_state_max_tokens = app_state.clone();
+    let mut app_state_tools = app_state.clone();
+    let mut app_state_persona = app_state.clone();
+
+    rsx! {
+        div { class: "relative",
+            button {
+                r#type: "button",
+                class: "w-8 h-8 rounded-lg hover:bg-white/[0.06] flex items-center justify-center text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-all",
+                title: if is_en { "Quick settings" } else { "Reglages rapides" },
+                onclick: move |_| open.toggle(),
+                svg {
+                    width: "15", height: "15", view_box: "0 0 24 24", fill: "none",
+                    stroke: "currentColor", stroke_width: "1.5", stroke_linecap: "round", stroke_linejoin: "round",
+                    line { x1: "4", y1: "21", x2: "4", y2: "14" }
+                    line { x1: "4", y1: "10", x2: "4", y2: "3" }
+                    line { x1: "12", y1: "21", x2: "12", y2: "12" }
+                    line { x1: "12", y1: "8", x2: "12", y2: "3" }
+                    line { x1: "20", y1: "21", x2: "20", y2: "16" }
+                    line { x1: "20", y1: "12", x2: "20", y2: "3" }
+                    line { x1: "1", y1: "14", x2: "7", y2: "14" }
+                    line { x1: "9", y1: "8", x2: "15", y2: "8" }
+                    line { x1: "17", y1: "16", x2: "23", y2: "16" }
+                }
+            }
+
+            if open() {
+                div {
+                    class: "absolute left-1/2 mt-2 p-3 rounded-xl z-50 animate-fade-in space-y-3",
+                    style: "transform: translateX(-50%); width: 280px; background: var(--bg-elevated); border: 1px solid var(--border-medium); box-shadow: 0 12px 32px -4px rgba(30,25,20,0.35);",
+
+                    div { class: "flex items-center justify-between",
+                        span {
+                            class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold",
+                            if is_en { "Quick settings" } else { "Reglages rapides" }
+                        }
+                        button {
+                            class: "text-xs text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                            onclick: move |_| open.set(false),
+                            "✕"
+                        }
+                    }
+
+                    // Temperature
+                    div {
+                        div { class: "flex justify-between text-xs mb-1",
+                            span { class: "text-[var(--text-secondary)]", if is_en { "Temperature" } else { "Temperature" } }
+                            span { class: "text-[var(--text-tertiary)] font-mono", "{temperature_label}" }
+                        }
+                        input {
+                            class: "w-full",
+                            r#type: "range",
+                            min: "0",
+                            max: "2",
+                            step: "0.1",
+                            value: "{params.temperature}",
+                            oninput: move |e| {
+                                let Ok(value) = e.value().parse::<f32>() else { return };
+                                let mut settings = app_state_temperature.settings.write();
+                                settings.temperature = value.clamp(0.0, 2.0);
+                                settings.active_preset = None;
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                        }
+                    }
+
+                    // Max tokens
+                    div { class: "flex items-center justify-between gap-2",
+                        span { class: "text-xs text-[var(--text-secondary)]", if is_en { "Max tokens" } else { "Tokens max" } }
+                        input {
+                            class: "{INPUT_CLASS} w-24 text-right",
+                            r#type: "number",
+                            min: "1",
+                            max: "{max_tokens_max}",
+                            step: "256",
+                            disabled: max_tokens_auto,
+                            placeholder: "auto",
+                            title: if max_tokens_auto {
+                                if is_en { "Auto: fits the reply to the remaining context (Settings > Inference)" } else { "Auto : la reponse occupe le contexte restant (Parametres > Inference)" }
+                            } else {
+                                ""
+                            },
+                            value: "{max_tokens_value}",
+                            onchange: move |e| {
+                                let Ok(value) = e.value().parse::<u32>() else { return };
+                                let mut settings = app_state_max_tokens.settings.write();
+                                settings.max_tokens = value.clamp(1, max_tokens_max);
+                                settings.active_preset = None;
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                        }
+                    }
+
+                    if let Some(note) = preset_note {
+                        p { class: "text-[11px] text-[var(--text-tertiary)]", "{note}" }
+                    }
+
+                    // Tools
+                    label { class: "flex items-center justify-between gap-2 cursor-pointer",
+                        span { class: "text-xs text-[var(--text-secondary)]", if is_en { "Agent tools" } else { "Outils de l'agent" } }
+                        input {
+                            r#type: "checkbox",
+                            checked: tools_enabled,
+                            disabled: generating || locked,
+                            onchange: move |e| {
+                                let mut settings = app_state_tools.settings.write();
+                                settings.tools_enabled = e.checked();
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                        }
+                    }
+
+                    // Persona of the open conversation
+                    div { class: "flex items-center justify-between gap-2",
+                        span { class: "text-xs text-[var(--text-secondary)]", "Persona" }
+                        select {
+                            class: "{INPUT_CLASS} max-w-[10rem]",
+                            disabled: generating || conversation_persona.is_none(),
+                            title: if conversation_persona.is_none() {
+                                if is_en { "Open a conversation first" } else { "Ouvrez d'abord une conversation" }
+                            } else {
+                                ""
+                            },
+                            onchange: move |e: Event<FormData>| {
+                                set_persona(&mut app_state_persona, Some(e.value()).filter(|id| !id.is_empty()));
+                            },
+                            option {
+                                value: "",
+                                selected: conversation_persona.as_deref().unwrap_or_default().is_empty(),
+                                if is_en { "Default" } else { "Par defaut" }
+                            }
+                            for (id, label) in personas {
+                                option {
+                                    key: "{id}",
+                                    value: "{id}",
+                                    selected: conversation_persona.as_deref() == Some(id.as_str()),
+                                    "{label}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Offer to restore the session after a crash
+//!
+//! Shown once at launch when the previous run left a crash snapshot (see
+//! [`crate::system::crash`]).
+
+use crate::app::AppState;
+use crate::system::crash::{discard_crash_snapshot, load_crash_snapshot};
+use dioxus::prelude::*;
+
+#[component]
+pub fn RestoreSessionBanner() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut snapshot = use_signal(|| {
+        let snapshot = load_crash_snapshot()?;
+        if snapshot.conversation.is_none() {
+            // Nothing to restore: the crash happened with no conversation open
+            tracing::warn!("Previous session crashed: {}", snapshot.message);
+            let _ = discard_crash_snapshot();
+            return None;
+        }
+        Some(snapshot)
+    });
+
+    let Some(current) = snapshot() else {
+        return rsx! {};
+    };
+    let title = current.conversation.as_ref().map(|c| c.title.clone()).unwrap_or_default();
+    let crashed_at = current.crashed_at.with_timezone(&chrono::Local).format("%H:%M");
+    let details = format!(
+        "{}{}",
+        current.message,
+        current.location.as_deref().map(|l| format!(" ({})", l)).unwrap_or_default()
+    );
+
+    rsx! {
+        div { class: "flex-none mx-4 mt-3 px-4 py-3 rounded-xl glass-md flex items-center gap-3 text-sm",
+            span { class: "text-[var(--warning)]", "⚠️" }
+            div { class: "flex-1 min-w-0",
+                div { class: "text-[var(--text-primary)]",
+                    if is_en { "ClawRS closed unexpectedly at {crashed_at}." } else { "ClawRS s'est ferme de facon inattendue a {crashed_at}." }
+                }
+                div { class: "text-xs text-[var(--text-tertiary)] truncate", title: "{details}",
+                    if is_en { "Conversation in progress: {title}" } else { "Conversation en cours : {title}" }
+                }
+            }
+            button {
+                class: "btn-ghost text-xs",
+                onclick: move |_| {
+                    if let Err(e) = discard_crash_snapshot() {
+                        tracing::warn!("Failed to remove crash snapshot: {}", e);
+                    }
+                    snapshot.set(None);
+                },
+                if is_en { "Dismiss" } else { "Ignorer" }
+            }
+            button {
+                class: "btn-primary text-xs",
+                onclick: move |_| {
+                    if let Some(current) = snapshot.take() {
+                        app_state.restore_session(current);
+                    }
+                },
+                if is_en { "Restore previous session" } else { "Restaurer la session precedente" }
+            }
+        }
+    }
+}
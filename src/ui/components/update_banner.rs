@@ -0,0 +1,129 @@
+//! Banner offering a newer release, with its changelog
+//!
+//! Shown when the update check (see [`crate::system::updates`]) found a
+//! release the user hasn't skipped.
+
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use crate::system::disk::format_size;
+use crate::system::updates::{download_update, open_release_page};
+use crate::ui::chat::message::MarkdownContent;
+use dioxus::prelude::*;
+
+#[component]
+pub fn UpdateBanner() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut available_update = app_state.available_update;
+    let mut show_changelog = use_signal(|| false);
+    // Bytes downloaded and total while the installer downloads
+    let mut download = use_signal(|| None::<(u64, u64)>);
+    let mut error = use_signal(|| None::<String>);
+
+    let Some(release) = available_update() else {
+        return rsx! {};
+    };
+    let date = release
+        .published_at
+        .map(|at| at.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let installable = release.installable();
+    let page_url = release.page_url.clone();
+    let skipped_version = release.version.clone();
+    let mut app_state_skip = app_state.clone();
+
+    rsx! {
+        div { class: "flex-none mx-4 mt-3 px-4 py-3 rounded-xl glass-md text-sm",
+            div { class: "flex items-center gap-3",
+                span { "⬆️" }
+                div { class: "flex-1 min-w-0",
+                    div { class: "text-[var(--text-primary)]",
+                        if is_en { "ClawRS {release.version} is available" } else { "ClawRS {release.version} est disponible" }
+                    }
+                    div { class: "text-xs text-[var(--text-tertiary)] truncate",
+                        "{release.name} · {date}"
+                    }
+                }
+                button {
+                    class: "btn-ghost text-xs",
+                    onclick: move |_| show_changelog.toggle(),
+                    if is_en { "What's new" } else { "Nouveautes" }
+                }
+                button {
+                    class: "btn-ghost text-xs",
+                    title: if is_en { "Don't offer this version again" } else { "Ne plus proposer cette version" },
+                    onclick: move |_| {
+                        let mut settings = app_state_skip.settings.write();
+                        settings.skipped_update = Some(skipped_version.clone());
+                        if let Err(e) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", e);
+                        }
+                        app_state_skip.available_update.set(None);
+                    },
+                    if is_en { "Skip" } else { "Ignorer" }
+                }
+                button {
+                    class: "btn-ghost text-xs",
+                    onclick: move |_| available_update.set(None),
+                    if is_en { "Later" } else { "Plus tard" }
+                }
+                if installable {
+                    button {
+                        class: "btn-primary text-xs",
+                        disabled: download().is_some(),
+                        onclick: {
+                            let app_state = app_state.clone();
+                            let release = release.clone();
+                            move |_| {
+                                let app_state = app_state.clone();
+                                let release = release.clone();
+                                error.set(None);
+                                download.set(Some((0, 0)));
+                                spawn(async move {
+                                    let result = download_update(&release, |done, total| {
+                                        download.set(Some((done, total)));
+                                    })
+                                    .await;
+                                    let installed = result.and_then(|path| app_state.install_update(&path));
+                                    if let Err(e) = installed {
+                                        tracing::error!("Update failed: {}", e);
+                                        error.set(Some(e.to_string()));
+                                        download.set(None);
+                                    }
+                                });
+                            }
+                        },
+                        match download() {
+                            Some((done, total)) if total > 0 => format!("{} / {}", format_size(done), format_size(total)),
+                            Some(_) => if is_en { "Downloading…".to_string() } else { "Telechargement…".to_string() },
+                            None => if is_en { "Download and install".to_string() } else { "Telecharger et installer".to_string() },
+                        }
+                    }
+                } else {
+                    button {
+                        class: "btn-primary text-xs",
+                        title: if is_en { "This build can't verify installers; download it from GitHub" } else { "Cette version ne peut pas verifier les installeurs ; telechargez-la depuis GitHub" },
+                        onclick: move |_| {
+                            if let Err(e) = open_release_page(&page_url) {
+                                tracing::error!("Failed to open release page: {}", e);
+                            }
+                        },
+                        if is_en { "Open release page" } else { "Ouvrir la page" }
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                div { class: "mt-2 text-xs text-[var(--error)]", "{message}" }
+            }
+            if show_changelog() {
+                div { class: "mt-3 max-h-72 overflow-y-auto custom-scrollbar text-[var(--text-secondary)]",
+                    if release.changelog.trim().is_empty() {
+                        if is_en { "No release notes." } else { "Pas de notes de version." }
+                    } else {
+                        MarkdownContent { content: release.changelog.clone() }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -5,16 +5,29 @@
 pub mod chat;
 pub mod components;
 pub mod help;
+pub mod playground;
 pub mod settings;
 pub mod sidebar;
 
 use crate::ui::sidebar::Sidebar;
 use crate::ui::chat::ChatView;
 use crate::ui::help::HelpView;
+use crate::ui::playground::PlaygroundView;
 use crate::ui::settings::Settings as SettingsPanel;
+use crate::ui::components::focus_mode::FocusModeButton;
 use crate::ui::components::permission_dialog::PermissionDialog;
+use crate::ui::components::quick_settings::QuickSettings;
+use crate::ui::components::restore_session::RestoreSessionBanner;
+use crate::ui::components::update_banner::UpdateBanner;
 use crate::app::{AppState, ModelState};
-use crate::storage::models::scan_models_directory;
+use crate::storage::models::{scan_models_for_role, ModelRole};
+use crate::inference::artifacts::OutputCleanup;
+use crate::inference::remote::list_models;
+use crate::inference::InferenceBackend;
+use crate::storage::model_profiles::{arrange_for_picker, is_favorite, picker_name};
+use crate::storage::model_sources::source_badge;
+use crate::storage::settings::save_settings;
+use crate::storage::suggestions::{effective_suggestions, icon_path, WelcomeSuggestion};
 use dioxus::prelude::*;
 
 /// Simple i18n helper — returns FR or EN string based on current language setting
@@ -27,6 +40,7 @@ enum MainView {
     Chat,
     Settings,
     Help,
+    Playground,
 }
 
 /// Compact model picker for the header bar
@@ -34,14 +48,15 @@ enum MainView {
 fn HeaderModelPicker() -> Element {
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
+    let remote = app_state.settings.read().uses_remote_backend();
     let mut dropdown_open = use_signal(|| false);
     let mut models = use_signal(Vec::new);
-    let models_directory = app_state.settings.read().models_directory.clone();
-
+    let mut show_all = use_signal(|| false);
+    let mut editing_alias = use_signal(|| None::<String>);
     // Scan models on mount
-    let models_directory_clone = models_directory.clone();
+    let app_state_for_scan = app_state.clone();
     use_effect(move || {
-        let found = scan_models_directory(&models_directory_clone).unwrap_or_default();
+        let found = scan_models_for_role(&app_state_for_scan.settings.peek(), ModelRole::Chat);
         models.set(found);
     });
 
@@ -50,12 +65,22 @@ fn HeaderModelPicker() -> Element {
     let is_loading = matches!(model_state, ModelState::Loading);
     let is_loaded = matches!(model_state, ModelState::Loaded(_));
 
+    let profiles = app_state.settings.read().model_profiles.clone();
     let display_name = match &model_state {
+        // Remote ids like `qwen2.5:14b` aren't file names
+        ModelState::Loaded(model) if remote => {
+            if model.len() > 20 { format!("{}...", crate::truncate_str(model, 20)) } else { model.clone() }
+        }
         ModelState::Loaded(path) => {
-            std::path::Path::new(path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .map(|s| if s.len() > 20 { format!("{}...", crate::truncate_str(s, 20)) } else { s.to_string() })
+            // Ollama blobs are named by digest, so prefer the scanned name
+            let known = models
+                .read()
+                .iter()
+                .find(|m| m.path.to_string_lossy() == path.as_str())
+                .map(|m| picker_name(m, &profiles));
+            known
+                .or_else(|| std::path::Path::new(path).file_stem().and_then(|s| s.to_str()).map(String::from))
+                .map(|s| if s.len() > 20 { format!("{}...", crate::truncate_str(&s, 20)) } else { s })
                 .unwrap_or_else(|| "Model".to_string())
         }
         ModelState::Loading => if is_en { "Loading..." } else { "Chargement..." }.to_string(),
@@ -66,6 +91,17 @@ fn HeaderModelPicker() -> Element {
         ModelState::NotLoaded => if is_en { "No model" } else { "Aucun modele" }.to_string(),
     };
 
+    let loaded_path = match &model_state {
+        ModelState::Loaded(path) => Some(path.clone()),
+        _ => None,
+    };
+    let (listed_models, hidden_count) = arrange_for_picker(
+        &models.read(),
+        &profiles,
+        loaded_path.as_deref(),
+        show_all(),
+    );
+
     // Dot color class
     let dot_class = match &model_state {
         ModelState::Loaded(_) => "status-dot status-dot-ready",
@@ -77,25 +113,9 @@ fn HeaderModelPicker() -> Element {
     // Handle load
     let app_state_load = app_state.clone();
     let handle_load = move |path: String| {
-        let mut app_state = app_state_load.clone();
         dropdown_open.set(false);
-        app_state.model_state.set(ModelState::Loading);
-        let gpu_layers = app_state.settings.read().gpu_layers;
-        spawn(async move {
-            let result = {
-                let mut engine = app_state.engine.lock().await;
-                if !engine.is_initialized() {
-                    if let Err(e) = engine.init() {
-                        return app_state.model_state.set(ModelState::Error(e.to_string()));
-                    }
-                }
-                engine.load_model_async(&path, gpu_layers).await
-            };
-            match result {
-                Ok(_) => app_state.model_state.set(ModelState::Loaded(path)),
-                Err(e) => app_state.model_state.set(ModelState::Error(e.to_string())),
-            }
-        });
+        let gpu_layers = app_state_load.settings.read().gpu_layers;
+        app_state_load.load_model(path, gpu_layers);
     };
 
     // Handle unload
@@ -168,58 +188,155 @@ fn HeaderModelPicker() -> Element {
                         }
                     }
 
-                    // Models list
-                    div {
-                        class: "max-h-56 overflow-y-auto custom-scrollbar py-1",
+                    // Models list: served by the remote backend, or local GGUF files
+                    if remote {
+                        RemoteModelList {
+                            loaded: loaded_path.clone(),
+                            on_select: {
+                                let mut handle_load = handle_load.clone();
+                                move |model: String| handle_load(model)
+                            },
+                        }
+                    } else {
+                        div {
+                            class: "max-h-56 overflow-y-auto custom-scrollbar py-1",
 
-                        if models.read().is_empty() {
-                            div {
-                                class: "px-3 py-4 text-center",
-                                span { class: "text-xs text-[var(--text-tertiary)]",
-                                    if is_en { "No .gguf models found" } else { "Aucun modele .gguf trouve" }
+                            if models.read().is_empty() {
+                                div {
+                                    class: "px-3 py-4 text-center",
+                                    span { class: "text-xs text-[var(--text-tertiary)]",
+                                        if is_en { "No .gguf models found" } else { "Aucun modele .gguf trouve" }
+                                    }
                                 }
                             }
-                        }
 
-                        for model in models.read().iter() {
-                            {
-                                let path_str = model.path.to_string_lossy().to_string();
-                                let filename = model.filename.clone();
-                                let size = model.size_string();
-                                let is_current = match &model_state {
-                                    ModelState::Loaded(p) => *p == path_str,
-                                    _ => false,
-                                };
-
-                                rsx! {
-                                    button {
-                                        r#type: "button",
-                                        onclick: {
-                                            let path_str = path_str.clone();
-                                            let mut handle_load = handle_load.clone();
-                                            move |_| {
-                                                if !is_current {
-                                                    handle_load(path_str.clone());
+                            for model in listed_models.iter() {
+                                {
+                                    let path_str = model.path.to_string_lossy().to_string();
+                                    let filename = picker_name(model, &profiles);
+                                    let favorite = is_favorite(model, &profiles);
+                                    let is_editing = editing_alias.read().as_deref() == Some(path_str.as_str());
+                                    let alias = profiles.get(&path_str).and_then(|p| p.alias.clone()).unwrap_or_default();
+                                    let output_cleanup = profiles.get(&path_str).map(|p| p.output_cleanup).unwrap_or_default();
+                                    let mut app_state_favorite = app_state.clone();
+                                    let mut app_state_alias = app_state.clone();
+                                    let mut app_state_cleanup = app_state.clone();
+                                    let size = model.size_string();
+                                    let badge = source_badge(&model.path);
+                                    let is_current = match &model_state {
+                                        ModelState::Loaded(p) => *p == path_str,
+                                        _ => false,
+                                    };
+
+                                    rsx! {
+                                        div {
+                                            key: "{path_str}",
+                                            class: "group/row flex items-center pr-2 transition-all hover:bg-white/[0.04]",
+                                            style: if is_current {
+                                                "background: var(--accent-soft); color: var(--accent-primary);"
+                                            } else {
+                                                "color: var(--text-primary);"
+                                            },
+
+                                            if is_editing {
+                                                input {
+                                                    r#type: "text",
+                                                    value: "{alias}",
+                                                    placeholder: "{model.display_name()}",
+                                                    class: "flex-1 min-w-0 mx-2 my-1 px-2 py-1 rounded-md bg-white/[0.04] border border-[var(--border-subtle)] text-xs text-[var(--text-primary)] outline-none focus:border-[var(--accent-primary)]",
+                                                    onchange: {
+                                                        let path_str = path_str.clone();
+                                                        move |e: Event<FormData>| {
+                                                            let value = e.value().trim().to_string();
+                                                            let mut settings = app_state_alias.settings.write();
+                                                            settings.model_profile_mut(&path_str).alias = (!value.is_empty()).then_some(value);
+                                                            if let Err(error) = save_settings(&settings) {
+                                                                tracing::error!("Failed to save settings: {}", error);
+                                                            }
+                                                            editing_alias.set(None);
+                                                        }
+                                                    },
+                                                }
+                                                // Template artifacts stripped from this model's replies
+                                                select {
+                                                    class: "flex-shrink-0 my-1 py-1 px-1 rounded-md bg-white/[0.04] border border-[var(--border-subtle)] text-[11px] text-[var(--text-primary)] outline-none cursor-pointer",
+                                                    title: if is_en { "Chat-template artifacts removed from replies" } else { "Artefacts du modele de chat retires des reponses" },
+                                                    onchange: {
+                                                        let path_str = path_str.clone();
+                                                        move |e: Event<FormData>| {
+                                                            let Some(cleanup) = OutputCleanup::from_id(&e.value()) else { return };
+                                                            let mut settings = app_state_cleanup.settings.write();
+                                                            settings.model_profile_mut(&path_str).output_cleanup = cleanup;
+                                                            if let Err(error) = save_settings(&settings) {
+                                                                tracing::error!("Failed to save settings: {}", error);
+                                                            }
+                                                        }
+                                                    },
+                                                    for cleanup in OutputCleanup::ALL {
+                                                        option { key: "{cleanup.id()}", value: "{cleanup.id()}", selected: cleanup == output_cleanup, "{cleanup.label(is_en)}" }
+                                                    }
+                                                }
+                                            } else {
+                                                button {
+                                                    r#type: "button",
+                                                    onclick: {
+                                                        let path_str = path_str.clone();
+                                                        let mut handle_load = handle_load.clone();
+                                                        move |_| {
+                                                            if !is_current {
+                                                                handle_load(path_str.clone());
+                                                            }
+                                                        }
+                                                    },
+                                                    class: "flex-1 min-w-0 flex items-center justify-between pl-3 py-2 text-left text-sm",
+
+                                                    div {
+                                                        class: "flex items-center gap-2 min-w-0",
+                                                        if is_current {
+                                                            div { class: "w-1.5 h-1.5 rounded-full flex-shrink-0", style: "background: var(--accent-primary);" }
+                                                        }
+                                                        span { class: "truncate font-medium text-xs", title: "{model.filename}", "{filename}" }
+                                                        if let Some(badge) = badge {
+                                                            span { class: "flex-shrink-0 px-1 rounded text-[9px] bg-white/[0.06] text-[var(--text-tertiary)]", "{badge}" }
+                                                        }
+                                                    }
+                                                    span {
+                                                        class: "flex-shrink-0 text-[10px] font-mono text-[var(--text-tertiary)] ml-2",
+                                                        "{size}"
+                                                    }
                                                 }
                                             }
-                                        },
-                                        class: "w-full flex items-center justify-between px-3 py-2 text-left text-sm transition-all hover:bg-white/[0.04]",
-                                        style: if is_current {
-                                            "background: var(--accent-soft); color: var(--accent-primary);"
-                                        } else {
-                                            "color: var(--text-primary);"
-                                        },
-
-                                        div {
-                                            class: "flex items-center gap-2 min-w-0",
-                                            if is_current {
-                                                div { class: "w-1.5 h-1.5 rounded-full flex-shrink-0", style: "background: var(--accent-primary);" }
+                                            button {
+                                                r#type: "button",
+                                                class: "flex-shrink-0 ml-1 text-[11px] text-[var(--text-tertiary)] opacity-0 group-hover/row:opacity-100 hover:text-[var(--text-primary)] transition-opacity",
+                                                title: if is_en { "Rename, output cleanup" } else { "Renommer, nettoyage des reponses" },
+                                                onclick: {
+                                                    let path_str = path_str.clone();
+                                                    move |_| editing_alias.set(if is_editing { None } else { Some(path_str.clone()) })
+                                                },
+                                                "✎"
+                                            }
+                                            button {
+                                                r#type: "button",
+                                                class: if favorite {
+                                                    "flex-shrink-0 ml-1 text-xs text-[#fbbf24]"
+                                                } else {
+                                                    "flex-shrink-0 ml-1 text-xs text-[var(--text-tertiary)] opacity-0 group-hover/row:opacity-100 hover:text-[#fbbf24] transition-opacity"
+                                                },
+                                                title: if is_en { "Favorite" } else { "Favori" },
+                                                onclick: {
+                                                    let path_str = path_str.clone();
+                                                    move |_| {
+                                                        let mut settings = app_state_favorite.settings.write();
+                                                        let profile = settings.model_profile_mut(&path_str);
+                                                        profile.favorite = !profile.favorite;
+                                                        if let Err(error) = save_settings(&settings) {
+                                                            tracing::error!("Failed to save settings: {}", error);
+                                                        }
+                                                    }
+                                                },
+                                                if favorite { "★" } else { "☆" }
                                             }
-                                            span { class: "truncate font-medium text-xs", "{filename}" }
-                                        }
-                                        span {
-                                            class: "flex-shrink-0 text-[10px] font-mono text-[var(--text-tertiary)] ml-2",
-                                            "{size}"
                                         }
                                     }
                                 }
@@ -227,6 +344,21 @@ fn HeaderModelPicker() -> Element {
                         }
                     }
 
+                    if !remote && (hidden_count > 0 || show_all()) {
+                        button {
+                            r#type: "button",
+                            class: "w-full px-3 py-1.5 border-t border-[var(--border-subtle)] text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-colors",
+                            onclick: move |_| show_all.toggle(),
+                            if show_all() {
+                                if is_en { "Hide other quantizations" } else { "Masquer les autres quantifications" }
+                            } else if is_en {
+                                "Show all ({hidden_count} more)"
+                            } else {
+                                "Tout afficher ({hidden_count} de plus)"
+                            }
+                        }
+                    }
+
                     // Footer: Unload if loaded
                     if is_loaded {
                         div {
@@ -255,55 +387,81 @@ fn HeaderModelPicker() -> Element {
     }
 }
 
-/// Prompt suggestion for welcome screen (bilingual)
-struct PromptSuggestion {
-    icon: &'static str,
-    title_fr: &'static str,
-    title_en: &'static str,
-    subtitle_fr: &'static str,
-    subtitle_en: &'static str,
-    prompt_fr: &'static str,
-    prompt_en: &'static str,
-}
+/// Models served by the remote backend, listed each time the picker opens
+#[component]
+fn RemoteModelList(loaded: Option<String>, on_select: EventHandler<String>) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let settings = app_state.settings;
+    let listing = use_resource(move || async move {
+        let (base_url, api_key) = {
+            let settings = settings.peek();
+            (settings.remote_base_url.clone(), settings.remote_api_key.clone())
+        };
+        list_models(&base_url, &api_key).await.map_err(|e| e.to_string())
+    });
 
-const SUGGESTIONS: &[PromptSuggestion] = &[
-    PromptSuggestion {
-        icon: "M9 20l-5.447-2.724A1 1 0 0 1 3 16.382V5.618a1 1 0 0 1 1.447-.894L9 7m0 13l6-3m-6 3V7m6 10l4.553 2.276A1 1 0 0 0 21 18.382V7.618a1 1 0 0 0-.553-.894L15 4m0 13V4m0 0L9 7",
-        title_fr: "Planifier",
-        title_en: "Plan",
-        subtitle_fr: "un voyage, un projet...",
-        subtitle_en: "a trip, a project...",
-        prompt_fr: "Aide-moi a planifier un voyage a Paris. Quels sont les incontournables et les meilleures periodes ?",
-        prompt_en: "Help me plan a trip to Paris. What are the must-sees and best times to visit?",
-    },
-    PromptSuggestion {
-        icon: "M12 6.253v13m0-13C10.832 5.477 9.246 5 7.5 5S4.168 5.477 3 6.253v13C4.168 18.477 5.754 18 7.5 18s3.332.477 4.5 1.253m0-13C13.168 5.477 14.754 5 16.5 5c1.747 0 3.332.477 4.5 1.253v13C19.832 18.477 18.247 18 16.5 18c-1.746 0-3.332.477-4.5 1.253",
-        title_fr: "Expliquer",
-        title_en: "Explain",
-        subtitle_fr: "un concept complexe",
-        subtitle_en: "a complex concept",
-        prompt_fr: "Explique-moi l'informatique quantique en termes simples que n'importe qui peut comprendre.",
-        prompt_en: "Explain quantum computing in simple terms that anyone can understand.",
-    },
-    PromptSuggestion {
-        icon: "M11 5H6a2 2 0 0 0-2 2v11a2 2 0 0 0 2 2h11a2 2 0 0 0 2-2v-5m-1.414-9.414a2 2 0 1 1 2.828 2.828L11.828 15H9v-2.828l8.586-8.586z",
-        title_fr: "Rediger",
-        title_en: "Write",
-        subtitle_fr: "un email, un texte...",
-        subtitle_en: "an email, a document...",
-        prompt_fr: "Aide-moi a ecrire un email professionnel a mon manager pour demander des conges.",
-        prompt_en: "Help me write a professional email to my manager asking for time off.",
-    },
-    PromptSuggestion {
-        icon: "M10 20l4-16m4 4l4 4-4 4M6 16l-4-4 4-4",
-        title_fr: "Coder",
-        title_en: "Code",
-        subtitle_fr: "debugger, expliquer...",
-        subtitle_en: "debug, explain...",
-        prompt_fr: "J'ai un bug dans mon code. Peux-tu m'aider a le debugger ?",
-        prompt_en: "I have a bug in my code. Can you help me debug it?",
-    },
-];
+    rsx! {
+        div {
+            class: "max-h-56 overflow-y-auto custom-scrollbar py-1",
+            match &*listing.read() {
+                None => rsx! {
+                    div {
+                        class: "px-3 py-4 text-center",
+                        span { class: "text-xs text-[var(--text-tertiary)]",
+                            if is_en { "Listing server models..." } else { "Liste des modeles du serveur..." }
+                        }
+                    }
+                },
+                Some(Err(error)) => rsx! {
+                    div {
+                        class: "px-3 py-4 text-center",
+                        span { class: "text-xs text-[var(--text-error)]", "{error}" }
+                    }
+                },
+                Some(Ok(list)) if list.is_empty() => rsx! {
+                    div {
+                        class: "px-3 py-4 text-center",
+                        span { class: "text-xs text-[var(--text-tertiary)]",
+                            if is_en { "The server lists no models" } else { "Le serveur ne liste aucun modele" }
+                        }
+                    }
+                },
+                Some(Ok(list)) => rsx! {
+                    for model in list.iter().cloned() {
+                        {
+                            let is_current = loaded.as_deref() == Some(model.as_str());
+                            rsx! {
+                                button {
+                                    key: "{model}",
+                                    r#type: "button",
+                                    onclick: {
+                                        let model = model.clone();
+                                        move |_| {
+                                            if !is_current {
+                                                on_select.call(model.clone());
+                                            }
+                                        }
+                                    },
+                                    class: "w-full flex items-center gap-2 px-3 py-2 text-left transition-all hover:bg-white/[0.04]",
+                                    style: if is_current {
+                                        "background: var(--accent-soft); color: var(--accent-primary);"
+                                    } else {
+                                        "color: var(--text-primary);"
+                                    },
+                                    if is_current {
+                                        div { class: "w-1.5 h-1.5 rounded-full flex-shrink-0", style: "background: var(--accent-primary);" }
+                                    }
+                                    span { class: "truncate font-medium text-xs", title: "{model}", "{model}" }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
 
 /// Main Application Layout
 #[component]
@@ -338,7 +496,8 @@ pub fn Layout() -> Element {
                 Sidebar {
                     on_settings_click: move |_| current_view.set(MainView::Settings),
                     on_new_chat: move |_| current_view.set(MainView::Chat),
-                    on_help_click: move |_| current_view.set(MainView::Help)
+                    on_help_click: move |_| current_view.set(MainView::Help),
+                    on_playground_click: move |_| current_view.set(MainView::Playground)
                 }
             }
 
@@ -408,8 +567,12 @@ pub fn Layout() -> Element {
                         }
                     }
 
-                    // Center: Model picker dropdown
-                    HeaderModelPicker {}
+                    // Center: Model picker dropdown and quick settings
+                    div { class: "flex items-center gap-1",
+                        HeaderModelPicker {}
+                        QuickSettings {}
+                        FocusModeButton {}
+                    }
 
                     // Right: Settings
                     button {
@@ -431,6 +594,9 @@ pub fn Layout() -> Element {
                     }
                 }
 
+                RestoreSessionBanner {}
+                UpdateBanner {}
+
                 // Main Content
                 if current_view() == MainView::Settings {
                     div {
@@ -480,25 +646,37 @@ pub fn Layout() -> Element {
                         }
                         HelpView {}
                     }
+                } else if current_view() == MainView::Playground {
+                    div {
+                        class: "flex flex-col h-full",
+                        // Back Button Header
+                        div {
+                            class: "flex-none px-6 pt-4 pb-2",
+                            button {
+                                onclick: move |_| current_view.set(MainView::Chat),
+                                class: "flex items-center gap-2 text-[var(--text-secondary)] hover:text-[var(--text-primary)] transition-colors text-sm font-medium group",
+                                svg {
+                                    class: "w-4 h-4 transition-transform group-hover:-translate-x-1",
+                                    view_box: "0 0 24 24",
+                                    fill: "none",
+                                    stroke: "currentColor",
+                                    stroke_width: "2",
+                                    stroke_linecap: "round",
+                                    stroke_linejoin: "round",
+                                    path { d: "M19 12H5M12 19l-7-7 7-7" }
+                                }
+                                "Back to Chat"
+                            }
+                        }
+                        PlaygroundView {}
+                    }
                 } else if app_state.current_conversation.read().is_some() {
                     ChatView {}
                 } else {
                     WelcomeScreen {
                         on_prompt_click: {
-                            let mut current_conversation = app_state.current_conversation.clone();
-                            let mut conversations = app_state.conversations.clone();
-                            move |_prompt: String| {
-                                use crate::storage::conversations::{save_conversation, list_conversations, Conversation};
-                                let conversation = Conversation::new(None);
-                                if let Err(e) = save_conversation(&conversation) {
-                                    tracing::error!("Failed to save conversation: {}", e);
-                                    return;
-                                }
-                                current_conversation.set(Some(conversation));
-                                if let Ok(convs) = list_conversations() {
-                                    conversations.set(convs);
-                                }
-                            }
+                            let app_state = app_state.clone();
+                            move |prompt: String| app_state.start_conversation_with(prompt)
                         }
                     }
                 }
@@ -514,6 +692,7 @@ pub fn Layout() -> Element {
 fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
+    let suggestions = effective_suggestions(app_state.settings.read().welcome_suggestions.as_deref(), is_en);
     rsx! {
         div {
             class: "flex-1 flex flex-col relative overflow-hidden",
@@ -566,15 +745,14 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
                 div {
                     class: "grid grid-cols-2 gap-3 w-full max-w-xl mb-8",
 
-                    for (i, suggestion) in SUGGESTIONS.iter().enumerate() {
+                    for (i, suggestion) in suggestions.into_iter().enumerate() {
                         {
-                            let title = if is_en { suggestion.title_en } else { suggestion.title_fr };
-                            let subtitle = if is_en { suggestion.subtitle_en } else { suggestion.subtitle_fr };
-                            let prompt = if is_en { suggestion.prompt_en } else { suggestion.prompt_fr };
+                            let icon = icon_path(&suggestion.icon);
+                            let WelcomeSuggestion { title, subtitle, prompt, .. } = suggestion;
                             rsx! {
                                 button {
                                     onclick: {
-                                        let prompt = prompt.to_string();
+                                        let prompt = prompt.clone();
                                         let on_prompt_click = on_prompt_click.clone();
                                         move |_| {
                                             on_prompt_click.call(prompt.clone());
@@ -594,7 +772,7 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
                                             stroke_width: "1.5",
                                             stroke_linecap: "round",
                                             stroke_linejoin: "round",
-                                            path { d: "{suggestion.icon}" }
+                                            path { d: "{icon}" }
                                         }
                                     }
 
@@ -0,0 +1,454 @@
+//! Prompt playground
+//!
+//! A scratch view for iterating on a prompt and its sampling parameters
+//! without creating conversations. Every run is kept as an attempt shown
+//! next to the previous ones, with what changed between them. Nothing is
+//! saved: attempts last until the app closes.
+
+use crate::app::{AppState, ModelState};
+use crate::inference::engine::InferenceBackend;
+use crate::inference::StreamToken;
+use crate::types::message::{Message as ChatMessage, Role as ChatRole};
+use dioxus::prelude::*;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors disabled:opacity-50";
+
+/// One run of the playground: its inputs and what the model answered
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaygroundAttempt {
+    pub id: u64,
+    pub system: String,
+    pub prompt: String,
+    pub temperature: f32,
+    pub top_p: f32,
+    pub max_tokens: u32,
+    /// 0 picks a random seed
+    pub seed: u32,
+    pub output: String,
+    pub error: Option<String>,
+    /// Stopped by `max_tokens` or by the user rather than by the model
+    pub truncated: bool,
+    pub running: bool,
+    pub elapsed: Option<Duration>,
+}
+
+impl PlaygroundAttempt {
+    /// Inputs that differ from `previous`, e.g. "temperature 0.7 → 1.0"
+    pub fn changes_from(&self, previous: &PlaygroundAttempt, is_en: bool) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.system != previous.system {
+            changes.push(if is_en { "system prompt" } else { "prompt systeme" }.to_string());
+        }
+        if self.prompt != previous.prompt {
+            changes.push("prompt".to_string());
+        }
+        if (self.temperature - previous.temperature).abs() > f32::EPSILON {
+            changes.push(format!("temperature {:.2} → {:.2}", previous.temperature, self.temperature));
+        }
+        if (self.top_p - previous.top_p).abs() > f32::EPSILON {
+            changes.push(format!("top-p {:.2} → {:.2}", previous.top_p, self.top_p));
+        }
+        if self.max_tokens != previous.max_tokens {
+            changes.push(format!("max tokens {} → {}", previous.max_tokens, self.max_tokens));
+        }
+        if self.seed != previous.seed {
+            changes.push(format!("seed {} → {}", previous.seed, self.seed));
+        }
+        changes
+    }
+}
+
+/// Change one attempt, if it is still listed
+fn update_attempt(attempts: &mut Signal<Vec<PlaygroundAttempt>>, id: u64, change: impl FnOnce(&mut PlaygroundAttempt)) {
+    if let Some(attempt) = attempts.write().iter_mut().find(|a| a.id == id) {
+        change(attempt);
+    }
+}
+
+/// Run `attempt` on the engine, streaming its output into the list
+///
+/// Spawned with `spawn_forever`: it outlives the view, so an attempt left
+/// mid-run still finishes and can be stopped on return.
+async fn run_attempt(app_state: AppState, attempt: PlaygroundAttempt) {
+    let stop = app_state.playground_stop.clone();
+    let mut attempts = app_state.playground_attempts;
+    let id = attempt.id;
+    let mut params = app_state.settings.read().generation_params();
+    params.temperature = attempt.temperature;
+    params.top_p = attempt.top_p;
+    params.max_tokens = attempt.max_tokens;
+    params.seed = attempt.seed;
+
+    let mut messages = Vec::new();
+    if !attempt.system.trim().is_empty() {
+        messages.push(ChatMessage::new(ChatRole::System, attempt.system.clone()));
+    }
+    messages.push(ChatMessage::new(ChatRole::User, attempt.prompt.clone()));
+
+    let started = Instant::now();
+    let result = {
+        let engine = app_state.engine.lock().await;
+        engine.generate_stream_messages(messages, params)
+    };
+    let (rx, stop_signal) = match result {
+        Ok(stream) => stream,
+        Err(e) => {
+            update_attempt(&mut attempts, id, |a| {
+                a.error = Some(e.to_string());
+                a.running = false;
+            });
+            return;
+        }
+    };
+
+    let mut done = false;
+    while !done {
+        if stop.load(Ordering::Relaxed) {
+            stop_signal.store(true, Ordering::Relaxed);
+        }
+        let mut text = String::new();
+        let mut error = None;
+        let mut truncated = false;
+        loop {
+            match rx.try_recv() {
+                Ok(StreamToken::Token(t)) => text.push_str(&t),
+                Ok(StreamToken::Done) => {
+                    done = true;
+                    break;
+                }
+                Ok(StreamToken::Truncated { .. }) => {
+                    truncated = true;
+                    done = true;
+                    break;
+                }
+                Ok(StreamToken::Error(e)) => {
+                    error = Some(e);
+                    done = true;
+                    break;
+                }
+                Ok(StreamToken::Logprob(_)) | Ok(StreamToken::Progress { .. }) => {}
+                Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    done = true;
+                    break;
+                }
+            }
+        }
+        if !text.is_empty() || done {
+            update_attempt(&mut attempts, id, |a| {
+                a.output.push_str(&text);
+                if done {
+                    a.error = error;
+                    a.truncated = truncated || stop.load(Ordering::Relaxed);
+                    a.running = false;
+                    a.elapsed = Some(started.elapsed());
+                }
+            });
+        }
+        if !done && text.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
+/// Scratch view: edit a prompt and its parameters, run it, compare runs
+pub fn PlaygroundView() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, defaults) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.generation_params())
+    };
+    let mut attempts = app_state.playground_attempts;
+    // The editor starts from the last attempt, so leaving the view loses nothing
+    let last = attempts.peek().last().cloned();
+    let mut system = use_signal(|| last.as_ref().map(|a| a.system.clone()).unwrap_or_default());
+    let mut prompt = use_signal(|| last.as_ref().map(|a| a.prompt.clone()).unwrap_or_default());
+    let mut temperature = use_signal(|| last.as_ref().map_or(defaults.temperature, |a| a.temperature));
+    let mut top_p = use_signal(|| last.as_ref().map_or(defaults.top_p, |a| a.top_p));
+    let mut max_tokens = use_signal(|| last.as_ref().map_or(defaults.max_tokens, |a| a.max_tokens));
+    let mut seed = use_signal(|| last.as_ref().map_or(0, |a| a.seed));
+
+    let running = attempts.read().iter().any(|a| a.running);
+    let model_loaded = matches!(*app_state.model_state.read(), ModelState::Loaded(_));
+    let chat_generating = *app_state.is_generating.read();
+    let can_run = !running && model_loaded && !chat_generating && !prompt().trim().is_empty();
+    // Newest first, each with its previous attempt for the changes
+    let listed: Vec<(PlaygroundAttempt, Option<PlaygroundAttempt>)> = {
+        let list = attempts.read();
+        list.iter()
+            .enumerate()
+            .rev()
+            .map(|(i, a)| (a.clone(), i.checked_sub(1).map(|p| list[p].clone())))
+            .collect()
+    };
+    let has_attempts = !listed.is_empty();
+    let app_state_run = app_state.clone();
+
+    rsx! {
+        div {
+            class: "flex-1 overflow-y-auto px-6 pb-6 custom-scrollbar space-y-5",
+
+            div { class: "flex items-start justify-between gap-4",
+                div {
+                    h1 {
+                        class: "text-2xl font-bold text-[var(--text-primary)]",
+                        if is_en { "Playground" } else { "Bac a sable" }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1",
+                        if is_en {
+                            "Try a prompt with different parameters and compare the answers side by side. Nothing here is saved or added to your conversations."
+                        } else {
+                            "Essayez un prompt avec differents parametres et comparez les reponses cote a cote. Rien n'est enregistre ni ajoute a vos conversations."
+                        }
+                    }
+                }
+                if has_attempts {
+                    button {
+                        class: BUTTON_CLASS,
+                        disabled: running,
+                        onclick: move |_| attempts.write().clear(),
+                        if is_en { "Clear attempts" } else { "Effacer les essais" }
+                    }
+                }
+            }
+
+            div { class: "p-5 rounded-2xl glass-md space-y-3",
+                textarea {
+                    class: "{INPUT_CLASS} w-full resize-y",
+                    rows: "2",
+                    placeholder: if is_en { "System prompt (optional)" } else { "Prompt systeme (optionnel)" },
+                    value: "{system}",
+                    oninput: move |e| system.set(e.value()),
+                }
+                textarea {
+                    class: "{INPUT_CLASS} w-full resize-y",
+                    rows: "5",
+                    placeholder: if is_en { "Prompt" } else { "Prompt" },
+                    value: "{prompt}",
+                    oninput: move |e| prompt.set(e.value()),
+                }
+                div { class: "flex flex-wrap items-end gap-3",
+                    label { class: "flex flex-col gap-1 text-xs text-[var(--text-secondary)]",
+                        "Temperature"
+                        input {
+                            class: "{INPUT_CLASS} w-24",
+                            r#type: "number", min: "0", max: "2", step: "0.1",
+                            value: "{temperature}",
+                            oninput: move |e| {
+                                if let Ok(value) = e.value().parse::<f32>() {
+                                    temperature.set(value.clamp(0.0, 2.0));
+                                }
+                            },
+                        }
+                    }
+                    label { class: "flex flex-col gap-1 text-xs text-[var(--text-secondary)]",
+                        "Top-p"
+                        input {
+                            class: "{INPUT_CLASS} w-24",
+                            r#type: "number", min: "0", max: "1", step: "0.05",
+                            value: "{top_p}",
+                            oninput: move |e| {
+                                if let Ok(value) = e.value().parse::<f32>() {
+                                    top_p.set(value.clamp(0.0, 1.0));
+                                }
+                            },
+                        }
+                    }
+                    label { class: "flex flex-col gap-1 text-xs text-[var(--text-secondary)]",
+                        if is_en { "Max tokens" } else { "Tokens max" }
+                        input {
+                            class: "{INPUT_CLASS} w-28",
+                            r#type: "number", min: "1", step: "64",
+                            value: "{max_tokens}",
+                            oninput: move |e| {
+                                if let Ok(value) = e.value().parse::<u32>() {
+                                    max_tokens.set(value.max(1));
+                                }
+                            },
+                        }
+                    }
+                    label { class: "flex flex-col gap-1 text-xs text-[var(--text-secondary)]",
+                        if is_en { "Seed (0 = random)" } else { "Graine (0 = aleatoire)" }
+                        input {
+                            class: "{INPUT_CLASS} w-28",
+                            r#type: "number", min: "0",
+                            value: "{seed}",
+                            oninput: move |e| {
+                                if let Ok(value) = e.value().parse::<u32>() {
+                                    seed.set(value);
+                                }
+                            },
+                        }
+                    }
+                    div { class: "flex-1" }
+                    if running {
+                        button {
+                            class: BUTTON_CLASS,
+                            onclick: {
+                                let stop = app_state.playground_stop.clone();
+                                move |_| stop.store(true, Ordering::Relaxed)
+                            },
+                            if is_en { "Stop" } else { "Arreter" }
+                        }
+                    } else {
+                        button {
+                            class: "{BUTTON_CLASS} text-[var(--accent-primary)]",
+                            disabled: !can_run,
+                            onclick: move |_| {
+                                let id = attempts.peek().last().map_or(1, |a| a.id + 1);
+                                let attempt = PlaygroundAttempt {
+                                    id,
+                                    system: system(),
+                                    prompt: prompt(),
+                                    temperature: temperature(),
+                                    top_p: top_p(),
+                                    max_tokens: max_tokens(),
+                                    seed: seed(),
+                                    output: String::new(),
+                                    error: None,
+                                    truncated: false,
+                                    running: true,
+                                    elapsed: None,
+                                };
+                                attempts.write().push(attempt.clone());
+                                app_state_run.playground_stop.store(false, Ordering::Relaxed);
+                                spawn_forever(run_attempt(app_state_run.clone(), attempt));
+                            },
+                            if is_en { "Run" } else { "Lancer" }
+                        }
+                    }
+                }
+                if !model_loaded {
+                    p { class: "text-xs text-[var(--text-tertiary)]",
+                        if is_en { "Load a model to run prompts." } else { "Chargez un modele pour lancer des prompts." }
+                    }
+                } else if chat_generating {
+                    p { class: "text-xs text-[var(--text-tertiary)]",
+                        if is_en { "Waiting for the chat to finish its answer." } else { "En attente de la fin de la reponse du chat." }
+                    }
+                }
+            }
+
+            div { class: "flex gap-4 overflow-x-auto pb-2 items-start",
+                for (attempt, previous) in listed.into_iter() {
+                    {
+                        let changes = previous
+                            .as_ref()
+                            .map(|p| attempt.changes_from(p, is_en))
+                            .unwrap_or_default()
+                            .join(", ");
+                        let seed_label = if attempt.seed == 0 {
+                            if is_en { "random".to_string() } else { "aleatoire".to_string() }
+                        } else {
+                            attempt.seed.to_string()
+                        };
+                        let params_label = format!(
+                            "T {:.2} · top-p {:.2} · {} tok · seed {}",
+                            attempt.temperature, attempt.top_p, attempt.max_tokens, seed_label
+                        );
+                        let elapsed = attempt.elapsed.map(|d| format!("{:.1}s", d.as_secs_f32()));
+                        let reuse = attempt.clone();
+                        let id = attempt.id;
+                        rsx! {
+                            div {
+                                key: "{attempt.id}",
+                                class: "w-[420px] shrink-0 p-4 rounded-2xl glass-md flex flex-col gap-2",
+                                div { class: "flex items-center justify-between gap-2",
+                                    span { class: "text-sm font-semibold text-[var(--text-primary)]", "#{attempt.id}" }
+                                    span { class: "text-[11px] text-[var(--text-tertiary)] tabular-nums",
+                                        if attempt.running {
+                                            if is_en { "Running..." } else { "En cours..." }
+                                        } else if let Some(elapsed) = elapsed {
+                                            "{elapsed}"
+                                        }
+                                    }
+                                }
+                                p { class: "text-[11px] text-[var(--text-tertiary)] font-mono",
+                                    "{params_label}"
+                                }
+                                if !changes.is_empty() {
+                                    p { class: "text-[11px] text-[var(--accent-primary)]",
+                                        if is_en { "Changed: {changes}" } else { "Modifie : {changes}" }
+                                    }
+                                }
+                                div {
+                                    class: "text-sm text-[var(--text-primary)] whitespace-pre-wrap break-words max-h-[60vh] overflow-y-auto custom-scrollbar",
+                                    "{attempt.output}"
+                                }
+                                if let Some(error) = attempt.error.as_ref() {
+                                    p { class: "text-xs text-[var(--error)]", "{error}" }
+                                } else if attempt.truncated {
+                                    p { class: "text-xs text-[var(--text-tertiary)]",
+                                        if is_en { "Stopped before the end." } else { "Arrete avant la fin." }
+                                    }
+                                }
+                                if !attempt.running {
+                                    div { class: "flex items-center gap-3 pt-1",
+                                        button {
+                                            class: "text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)]",
+                                            title: if is_en { "Put this attempt's prompt and parameters back in the editor" } else { "Remettre le prompt et les parametres de cet essai dans l'editeur" },
+                                            onclick: move |_| {
+                                                system.set(reuse.system.clone());
+                                                prompt.set(reuse.prompt.clone());
+                                                temperature.set(reuse.temperature);
+                                                top_p.set(reuse.top_p);
+                                                max_tokens.set(reuse.max_tokens);
+                                                seed.set(reuse.seed);
+                                            },
+                                            if is_en { "Edit from this" } else { "Repartir de celui-ci" }
+                                        }
+                                        button {
+                                            class: "text-xs text-[var(--text-tertiary)] hover:text-[var(--error)]",
+                                            onclick: move |_| attempts.write().retain(|a| a.id != id),
+                                            if is_en { "Remove" } else { "Retirer" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt() -> PlaygroundAttempt {
+        PlaygroundAttempt {
+            id: 1,
+            system: String::new(),
+            prompt: "Write a haiku".to_string(),
+            temperature: 0.7,
+            top_p: 0.95,
+            max_tokens: 256,
+            seed: 0,
+            output: String::new(),
+            error: None,
+            truncated: false,
+            running: false,
+            elapsed: None,
+        }
+    }
+
+    #[test]
+    fn test_changes_from() {
+        let first = attempt();
+        let mut second = PlaygroundAttempt { id: 2, output: "An answer".to_string(), ..attempt() };
+        assert!(second.changes_from(&first, true).is_empty());
+
+        second.temperature = 1.2;
+        second.prompt = "Write a limerick".to_string();
+        assert_eq!(
+            second.changes_from(&first, true),
+            vec!["prompt".to_string(), "temperature 0.70 → 1.20".to_string()]
+        );
+    }
+}
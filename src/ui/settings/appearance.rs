@@ -1,5 +1,10 @@
 use crate::app::AppState;
-use crate::storage::settings::{default_system_prompt_for_lang, save_settings};
+use crate::storage::settings::save_settings;
+use crate::ui::settings::autosave::AutosaveSettings;
+use crate::ui::settings::updates::UpdateSettings;
+use crate::ui::settings::file_associations::FileAssociationSettings;
+use crate::ui::settings::snippets::SnippetsSettings;
+use crate::ui::settings::suggestions::WelcomeSuggestionsSettings;
 use dioxus::prelude::*;
 
 pub fn AppearanceSettings() -> Element {
@@ -17,6 +22,8 @@ pub fn AppearanceSettings() -> Element {
     let mut app_state_theme = app_state.clone();
     let mut app_state_font_size = app_state.clone();
     let mut app_state_lang = app_state.clone();
+    let mut app_state_tool_lang = app_state.clone();
+    let tool_language = settings.tool_language.clone();
 
     rsx! {
         div {
@@ -49,7 +56,7 @@ pub fn AppearanceSettings() -> Element {
                                     move |_| {
                                         let mut settings = app_state_lang.settings.write();
                                         settings.language = code.clone();
-                                        settings.system_prompt = default_system_prompt_for_lang(&code);
+                                        settings.apply_system_prompt_preset();
                                         if let Err(error) = save_settings(&settings) {
                                             tracing::error!("Failed to save settings: {}", error);
                                         }
@@ -72,6 +79,36 @@ pub fn AppearanceSettings() -> Element {
                         }
                     }
                 }
+
+                div {
+                    class: "flex items-center justify-between gap-4 mt-5",
+                    div {
+                        div { class: "text-sm font-medium text-[var(--text-primary)]",
+                            if is_fr { "Description des outils pour le modele" } else { "Tool descriptions for the model" }
+                        }
+                        div { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if is_fr {
+                                "Langue des descriptions d'outils dans le prompt. La plupart des modeles les suivent mieux en anglais."
+                            } else {
+                                "Language of the tool descriptions in the prompt. Most models follow them best in English."
+                            }
+                        }
+                    }
+                    select {
+                        class: "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        onchange: move |e| {
+                            let mut settings = app_state_tool_lang.settings.write();
+                            settings.tool_language = e.value();
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        option { value: "en", selected: tool_language != "fr",
+                            if is_fr { "Anglais (recommande)" } else { "English (recommended)" }
+                        }
+                        option { value: "fr", selected: tool_language == "fr", "Français" }
+                    }
+                }
             }
 
             // Theme Card — glass
@@ -159,6 +196,16 @@ pub fn AppearanceSettings() -> Element {
                     }
                 }
             }
+
+            WelcomeSuggestionsSettings {}
+
+            SnippetsSettings {}
+
+            AutosaveSettings {}
+
+            FileAssociationSettings {}
+
+            UpdateSettings {}
         }
     }
 }
@@ -0,0 +1,173 @@
+use crate::agent::routing::RequestKind;
+use crate::agent::tagging::{default_rules, AutoTagSettings, TagRule};
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Change the auto-tag settings and save them
+fn update_auto_tags(app_state: &AppState, change: impl FnOnce(&mut AutoTagSettings)) {
+    let mut settings = app_state.settings;
+    let mut settings = settings.write();
+    change(&mut settings.auto_tags);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Change one rule, if it still exists
+fn update_rule(app_state: &AppState, idx: usize, change: impl FnOnce(&mut TagRule)) {
+    update_auto_tags(app_state, |auto_tags| {
+        if let Some(rule) = auto_tags.rules.get_mut(idx) {
+            change(rule);
+        }
+    });
+}
+
+fn kind_value(kind: Option<RequestKind>) -> &'static str {
+    match kind {
+        Some(RequestKind::Chat) => "chat",
+        Some(RequestKind::Code) => "code",
+        Some(RequestKind::General) => "general",
+        None => "",
+    }
+}
+
+/// Auto-tag card: rules tagging conversations from their content
+pub fn AutoTagSettingsCard() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, auto_tags) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.auto_tags.clone())
+    };
+
+    let app_state_toggle = app_state.clone();
+    let app_state_workspace = app_state.clone();
+    let app_state_add = app_state.clone();
+    let app_state_reset = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            div { class: "flex items-center justify-between gap-3 mb-1",
+                h3 {
+                    class: "text-base font-semibold text-[var(--text-primary)]",
+                    if is_en { "🏷️ Automatic tags" } else { "🏷️ Tags automatiques" }
+                }
+                button {
+                    class: if auto_tags.enabled { "toggle-switch active" } else { "toggle-switch" },
+                    onclick: move |_| update_auto_tags(&app_state_toggle, |auto_tags| auto_tags.enabled = !auto_tags.enabled),
+                    div { class: "toggle-switch-knob" }
+                }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "After each reply, a conversation gets the tag of every rule matching one of your messages: by kind of request or by keyword. Tags can be filtered and edited in the sidebar; a tag you remove is not added back."
+                } else {
+                    "Apres chaque reponse, une conversation recoit le tag de chaque regle qui correspond a l'un de vos messages : par type de demande ou par mot-cle. Les tags se filtrent et se modifient dans la barre laterale ; un tag retire n'est pas remis."
+                }
+            }
+
+            div { class: "flex items-center justify-between gap-3 mb-4",
+                span {
+                    class: "text-sm text-[var(--text-secondary)]",
+                    if is_en { "Tag with the workspace folder (workspace:name)" } else { "Tagger avec le dossier de travail (workspace:nom)" }
+                }
+                button {
+                    class: if auto_tags.workspace { "toggle-switch active" } else { "toggle-switch" },
+                    onclick: move |_| update_auto_tags(&app_state_workspace, |auto_tags| auto_tags.workspace = !auto_tags.workspace),
+                    div { class: "toggle-switch-knob" }
+                }
+            }
+
+            div { class: "space-y-3",
+                for (idx, rule) in auto_tags.rules.iter().cloned().enumerate() {
+                    {
+                        let app_state_enabled = app_state.clone();
+                        let app_state_tag = app_state.clone();
+                        let app_state_kind = app_state.clone();
+                        let app_state_keywords = app_state.clone();
+                        let app_state_remove = app_state.clone();
+                        let kind = kind_value(rule.kind);
+                        let keywords = rule.keywords.join(", ");
+                        rsx! {
+                            div {
+                                key: "{idx}-{rule.tag}",
+                                class: "pb-3 border-b border-[var(--border-subtle)] last:border-0 last:pb-0 space-y-2",
+                                div { class: "flex items-center gap-2",
+                                    input {
+                                        class: "{INPUT_CLASS} flex-1",
+                                        placeholder: "tag",
+                                        value: "{rule.tag}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let tag = e.value().trim().to_lowercase();
+                                            update_rule(&app_state_tag, idx, |rule| rule.tag = tag);
+                                        },
+                                    }
+                                    select {
+                                        class: INPUT_CLASS,
+                                        value: "{kind}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let kind = RequestKind::ALL.into_iter().find(|k| kind_value(Some(*k)) == e.value());
+                                            update_rule(&app_state_kind, idx, |rule| rule.kind = kind);
+                                        },
+                                        option { value: "", selected: kind.is_empty(), if is_en { "Keywords only" } else { "Mots-cles seulement" } }
+                                        for k in RequestKind::ALL {
+                                            option { value: kind_value(Some(k)), selected: kind == kind_value(Some(k)), "{k.label(is_en)}" }
+                                        }
+                                    }
+                                    button {
+                                        class: if rule.enabled { "toggle-switch active" } else { "toggle-switch" },
+                                        onclick: move |_| update_rule(&app_state_enabled, idx, |rule| rule.enabled = !rule.enabled),
+                                        div { class: "toggle-switch-knob" }
+                                    }
+                                    button {
+                                        class: "w-7 h-7 rounded-md text-[var(--text-tertiary)] hover:text-[var(--error)] hover:bg-white/[0.06]",
+                                        title: if is_en { "Remove rule" } else { "Supprimer la regle" },
+                                        onclick: move |_| update_auto_tags(&app_state_remove, |auto_tags| {
+                                            if idx < auto_tags.rules.len() {
+                                                auto_tags.rules.remove(idx);
+                                            }
+                                        }),
+                                        "✕"
+                                    }
+                                }
+                                input {
+                                    class: "{INPUT_CLASS} w-full",
+                                    placeholder: if is_en { "Keywords, comma-separated" } else { "Mots-cles separes par des virgules" },
+                                    value: "{keywords}",
+                                    onchange: move |e: Event<FormData>| {
+                                        let keywords = e
+                                            .value()
+                                            .split(',')
+                                            .map(|k| k.trim().to_string())
+                                            .filter(|k| !k.is_empty())
+                                            .collect();
+                                        update_rule(&app_state_keywords, idx, |rule| rule.keywords = keywords);
+                                    },
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex items-center gap-2 mt-4",
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| update_auto_tags(&app_state_add, |auto_tags| {
+                        auto_tags.rules.push(TagRule::new("", None, &[]));
+                    }),
+                    if is_en { "Add a rule" } else { "Ajouter une regle" }
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| update_auto_tags(&app_state_reset, |auto_tags| auto_tags.rules = default_rules()),
+                    if is_en { "Restore defaults" } else { "Retablir les regles par defaut" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::app::AppState;
+use crate::storage::settings::{save_settings, AUTOSAVE_INTERVALS};
+use dioxus::prelude::*;
+
+/// How often a streaming reply is written to disk
+pub fn AutosaveSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, interval) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.autosave_interval_secs)
+    };
+    let mut app_state_interval = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "Autosave" } else { "Sauvegarde automatique" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Conversations are saved when a reply ends and when the window closes. While a reply streams, they can also be saved periodically."
+                } else {
+                    "Les conversations sont enregistrees a la fin d'une reponse et a la fermeture de la fenetre. Pendant une reponse, elles peuvent aussi etre enregistrees periodiquement."
+                }
+            }
+            div { class: "flex items-center justify-between",
+                label { class: "text-sm font-medium text-[var(--text-primary)]",
+                    if is_en { "Save during a reply" } else { "Enregistrer pendant une reponse" }
+                }
+                select {
+                    class: "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                    onchange: move |e| {
+                        let Ok(secs) = e.value().parse::<u32>() else {
+                            return;
+                        };
+                        let mut settings = app_state_interval.settings.write();
+                        settings.autosave_interval_secs = secs;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    },
+                    for secs in AUTOSAVE_INTERVALS {
+                        option {
+                            value: "{secs}",
+                            selected: secs == interval,
+                            if secs == 0 {
+                                if is_en { "Off" } else { "Desactive" }
+                            } else if is_en {
+                                "Every {secs} s"
+                            } else {
+                                "Toutes les {secs} s"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::app::AppState;
+use crate::inference::remote::list_models;
+use crate::storage::settings::{save_settings, BACKEND_LOCAL, BACKEND_OPENAI_COMPAT};
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors disabled:opacity-50";
+
+/// Where generations run: the local llama.cpp engine or a remote
+/// OpenAI-compatible server
+pub fn BackendSettingsCard() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, saved_backend, saved_url, saved_key) = {
+        let settings = app_state.settings.read();
+        (
+            settings.language == "en",
+            settings.backend.clone(),
+            settings.remote_base_url.clone(),
+            settings.remote_api_key.clone(),
+        )
+    };
+    let mut backend = use_signal(|| saved_backend.clone());
+    let mut base_url = use_signal(|| saved_url.clone());
+    let mut api_key = use_signal(|| saved_key.clone());
+    // Outcome of the last connection test: model count or error
+    let mut test_result = use_signal(|| None::<Result<usize, String>>);
+    let mut testing = use_signal(|| false);
+    let remote = backend() == BACKEND_OPENAI_COMPAT;
+    let changed = backend() != saved_backend || base_url() != saved_url || api_key() != saved_key;
+    let mut app_state_save = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "Inference backend" } else { "Moteur d'inference" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Run models on this computer, or send generations to an OpenAI-compatible server (llama-server, Ollama) on your network. With a server, the model picker lists its models and nothing is loaded locally."
+                } else {
+                    "Executer les modeles sur cet ordinateur, ou envoyer les generations a un serveur compatible OpenAI (llama-server, Ollama) du reseau. Avec un serveur, le selecteur de modele liste ses modeles et rien n'est charge localement."
+                }
+            }
+            select {
+                class: "{INPUT_CLASS} w-full mb-3",
+                onchange: move |e: Event<FormData>| {
+                    backend.set(e.value());
+                    test_result.set(None);
+                },
+                option { value: BACKEND_LOCAL, selected: !remote,
+                    "Local (llama.cpp)"
+                }
+                option { value: BACKEND_OPENAI_COMPAT, selected: remote,
+                    if is_en { "OpenAI-compatible server" } else { "Serveur compatible OpenAI" }
+                }
+            }
+            if remote {
+                div { class: "flex flex-col gap-3 mb-4",
+                    input {
+                        class: "{INPUT_CLASS}",
+                        placeholder: "http://192.168.1.20:8080",
+                        value: "{base_url}",
+                        oninput: move |e| {
+                            base_url.set(e.value());
+                            test_result.set(None);
+                        },
+                    }
+                    input {
+                        class: "{INPUT_CLASS}",
+                        r#type: "password",
+                        placeholder: if is_en { "API key (optional)" } else { "Cle d'API (optionnelle)" },
+                        value: "{api_key}",
+                        oninput: move |e| {
+                            api_key.set(e.value());
+                            test_result.set(None);
+                        },
+                    }
+                }
+            }
+            div { class: "flex items-center gap-3",
+                button {
+                    class: BUTTON_CLASS,
+                    disabled: !changed,
+                    onclick: move |_| {
+                        {
+                            let mut settings = app_state_save.settings.write();
+                            settings.backend = backend();
+                            settings.remote_base_url = base_url().trim().to_string();
+                            settings.remote_api_key = api_key().trim().to_string();
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        }
+                        app_state_save.switch_backend();
+                    },
+                    if is_en { "Apply" } else { "Appliquer" }
+                }
+                if remote {
+                    button {
+                        class: BUTTON_CLASS,
+                        disabled: testing() || base_url().trim().is_empty(),
+                        onclick: move |_| {
+                            testing.set(true);
+                            let (url, key) = (base_url(), api_key());
+                            spawn(async move {
+                                let result = list_models(&url, &key).await;
+                                test_result.set(Some(result.map(|models| models.len()).map_err(|e| e.to_string())));
+                                testing.set(false);
+                            });
+                        },
+                        if testing() {
+                            if is_en { "Testing..." } else { "Test..." }
+                        } else if is_en {
+                            "Test connection"
+                        } else {
+                            "Tester la connexion"
+                        }
+                    }
+                }
+                match test_result() {
+                    Some(Ok(count)) => rsx! {
+                        span { class: "text-xs text-[var(--text-success)]",
+                            if is_en { "Connected: {count} model(s)." } else { "Connecte : {count} modele(s)." }
+                        }
+                    },
+                    Some(Err(e)) => rsx! {
+                        span { class: "text-xs text-[var(--error)]", "{e}" }
+                    },
+                    None => rsx! {},
+                }
+            }
+            if changed {
+                p { class: "text-xs text-[var(--text-tertiary)] mt-3",
+                    if is_en {
+                        "Applying unloads the current model."
+                    } else {
+                        "Appliquer decharge le modele actuel."
+                    }
+                }
+            }
+        }
+    }
+}
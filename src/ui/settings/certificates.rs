@@ -0,0 +1,121 @@
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use crate::system::certificates::{import_certificate, set_custom_certificates};
+use dioxus::prelude::*;
+use std::path::Path;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Apply a change to the certificate list, persist it and reload the trust store
+fn update_certificates(app_state: &mut AppState, change: impl FnOnce(&mut Vec<String>)) {
+    let mut settings = app_state.settings.write();
+    change(&mut settings.ca_certificates);
+    set_custom_certificates(&settings.ca_certificates);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Import the PEM file at `path` and trust it
+fn import(app_state: &mut AppState, mut path: Signal<String>, mut error: Signal<Option<String>>) {
+    let source = path.read().trim().to_string();
+    if source.is_empty() {
+        return;
+    }
+    match import_certificate(Path::new(&source)) {
+        Ok(dest) => {
+            let dest = dest.to_string_lossy().to_string();
+            update_certificates(app_state, |list| {
+                if !list.contains(&dest) {
+                    list.push(dest);
+                }
+            });
+            error.set(None);
+            path.set(String::new());
+        }
+        Err(e) => {
+            tracing::warn!("Failed to import certificate: {}", e);
+            error.set(Some(e));
+        }
+    }
+}
+
+/// Custom root CA card, for networks that intercept TLS
+pub fn CertificateSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let certificates = app_state.settings.read().ca_certificates.clone();
+    let mut path = use_signal(String::new);
+    let error = use_signal(|| None::<String>);
+    let mut app_state_enter = app_state.clone();
+    let mut app_state_click = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "🔐 Certificates" } else { "🔐 Certificats" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Root certificates (PEM) trusted on top of the system ones, for corporate networks that inspect TLS traffic. Used by web tools, MCP servers, cloud escalation and model downloads."
+                } else {
+                    "Certificats racines (PEM) approuves en plus de ceux du systeme, pour les reseaux d'entreprise qui inspectent le trafic TLS. Utilises par les outils web, les serveurs MCP, l'escalade cloud et le telechargement de modeles."
+                }
+            }
+
+            if !certificates.is_empty() {
+                div { class: "flex flex-col gap-1 mb-4",
+                    for certificate in certificates {
+                        div {
+                            key: "{certificate}",
+                            class: "flex items-center gap-2 px-2 py-1.5 rounded-lg bg-white/[0.03]",
+                            span {
+                                class: "flex-1 truncate text-sm text-[var(--text-primary)] font-mono",
+                                title: "{certificate}",
+                                "{certificate}"
+                            }
+                            button {
+                                class: "px-1.5 rounded-md text-xs text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
+                                title: if is_en { "Stop trusting this certificate" } else { "Ne plus approuver ce certificat" },
+                                onclick: {
+                                    let mut app_state = app_state.clone();
+                                    let certificate = certificate.clone();
+                                    move |_| {
+                                        update_certificates(&mut app_state, |list| list.retain(|c| *c != certificate));
+                                        let _ = std::fs::remove_file(&certificate);
+                                    }
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex items-center gap-2",
+                input {
+                    class: "{INPUT_CLASS} flex-1",
+                    placeholder: if is_en { "Path to a .pem or .crt file" } else { "Chemin d'un fichier .pem ou .crt" },
+                    value: "{path}",
+                    oninput: move |e| path.set(e.value()),
+                    onkeydown: move |e: KeyboardEvent| {
+                        if e.key() == Key::Enter {
+                            import(&mut app_state_enter, path, error);
+                        }
+                    },
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| import(&mut app_state_click, path, error),
+                    if is_en { "Import" } else { "Importer" }
+                }
+            }
+            if let Some(e) = error() {
+                p { class: "text-xs text-[var(--error)] mt-2", "{e}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::app::AppState;
+use crate::storage::conversations::parse_tags;
+use crate::storage::dataset::{
+    export_dataset, matches_tags, DatasetFormat, DatasetOptions, FeedbackFilter, ScrubOptions,
+};
+use crate::storage::feedback::load_all_feedback;
+use dioxus::prelude::*;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Fine-tuning dataset export: pick conversations by tag, filter by
+/// feedback, scrub personal data and write JSONL
+pub fn DatasetExport() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut format = use_signal(DatasetFormat::default);
+    let mut feedback_filter = use_signal(FeedbackFilter::default);
+    let mut scrub = use_signal(ScrubOptions::default);
+    let mut tag_filter = use_signal(String::new);
+    let mut excluded = use_signal(HashSet::<String>::new);
+    let mut export_result = use_signal(|| None::<Result<(PathBuf, usize), String>>);
+
+    let tags = parse_tags(&tag_filter.read());
+    let candidates: Vec<_> = app_state
+        .conversations
+        .read()
+        .iter()
+        .filter(|c| !c.messages.is_empty() && matches_tags(c, &tags))
+        .cloned()
+        .collect();
+    let selected_count = candidates
+        .iter()
+        .filter(|c| !excluded.read().contains(&c.id))
+        .count();
+
+    let scrub_options = [
+        (
+            if is_en { "Emails" } else { "E-mails" },
+            scrub().emails,
+            (|s: &mut ScrubOptions| s.emails = !s.emails) as fn(&mut ScrubOptions),
+        ),
+        (
+            if is_en { "Phone numbers" } else { "Telephones" },
+            scrub().phone_numbers,
+            |s: &mut ScrubOptions| s.phone_numbers = !s.phone_numbers,
+        ),
+        (
+            if is_en { "IP addresses" } else { "Adresses IP" },
+            scrub().ip_addresses,
+            |s: &mut ScrubOptions| s.ip_addresses = !s.ip_addresses,
+        ),
+        (
+            if is_en {
+                "Keys and passwords"
+            } else {
+                "Cles et mots de passe"
+            },
+            scrub().secrets,
+            |s: &mut ScrubOptions| s.secrets = !s.secrets,
+        ),
+        (
+            if is_en {
+                "Home folder"
+            } else {
+                "Dossier personnel"
+            },
+            scrub().home_dir,
+            |s: &mut ScrubOptions| s.home_dir = !s.home_dir,
+        ),
+    ];
+
+    let export_candidates = candidates.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "Fine-tuning dataset" } else { "Jeu de donnees de fine-tuning" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-5",
+                if is_en {
+                    "Exports the selected conversations as JSONL to train your own adapter. Tool output and thinking blocks are left out; personal data is masked before writing."
+                } else {
+                    "Exporte les conversations selectionnees en JSONL pour entrainer votre propre adaptateur. Les resultats d'outils et les blocs de reflexion sont exclus ; les donnees personnelles sont masquees avant l'ecriture."
+                }
+            }
+
+            div { class: "grid grid-cols-2 gap-3 mb-4",
+                div { class: "flex flex-col gap-1",
+                    label { class: "text-xs text-[var(--text-secondary)]", "Format" }
+                    select {
+                        class: INPUT_CLASS,
+                        onchange: move |e: Event<FormData>| {
+                            if let Some(f) = DatasetFormat::from_id(&e.value()) {
+                                format.set(f);
+                            }
+                        },
+                        for f in DatasetFormat::ALL {
+                            option { value: f.id(), selected: format() == f, "{f.label()}" }
+                        }
+                    }
+                }
+                div { class: "flex flex-col gap-1",
+                    label { class: "text-xs text-[var(--text-secondary)]", if is_en { "Feedback" } else { "Avis" } }
+                    select {
+                        class: INPUT_CLASS,
+                        onchange: move |e: Event<FormData>| feedback_filter.set(match e.value().as_str() {
+                            "not_disliked" => FeedbackFilter::NotDisliked,
+                            "liked" => FeedbackFilter::Liked,
+                            _ => FeedbackFilter::Any,
+                        }),
+                        option { value: "any", selected: feedback_filter() == FeedbackFilter::Any,
+                            if is_en { "Everything" } else { "Tout" }
+                        }
+                        option { value: "not_disliked", selected: feedback_filter() == FeedbackFilter::NotDisliked,
+                            if is_en { "Skip 👎" } else { "Sans les 👎" }
+                        }
+                        option { value: "liked", selected: feedback_filter() == FeedbackFilter::Liked,
+                            if is_en { "Only 👍" } else { "Seulement les 👍" }
+                        }
+                    }
+                }
+            }
+
+            div { class: "mb-4",
+                p { class: "text-xs text-[var(--text-secondary)] mb-2", if is_en { "Mask" } else { "Masquer" } }
+                div { class: "flex flex-wrap gap-x-4 gap-y-2",
+                    for (label, checked, toggle) in scrub_options {
+                        label { class: "flex items-center gap-1.5 text-sm text-[var(--text-primary)] cursor-pointer",
+                            input {
+                                r#type: "checkbox",
+                                class: "accent-[var(--accent-primary)]",
+                                checked,
+                                onchange: move |_| toggle(&mut scrub.write()),
+                            }
+                            "{label}"
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex items-center justify-between gap-3 mb-2",
+                input {
+                    class: "{INPUT_CLASS} flex-1",
+                    placeholder: if is_en { "Filter by tags, comma-separated" } else { "Filtrer par tags, separes par des virgules" },
+                    value: "{tag_filter}",
+                    oninput: move |e| tag_filter.set(e.value()),
+                }
+                span { class: "text-xs text-[var(--text-tertiary)] shrink-0",
+                    if is_en { "{selected_count}/{candidates.len()} selected" } else { "{selected_count}/{candidates.len()} selectionnees" }
+                }
+            }
+            div { class: "max-h-72 overflow-y-auto flex flex-col gap-1 mb-4 scrollbar-thin",
+                for conversation in candidates {
+                    div {
+                        key: "{conversation.id}",
+                        class: "flex items-center gap-2 px-2 py-1.5 rounded-lg hover:bg-white/[0.04]",
+                        input {
+                            r#type: "checkbox",
+                            class: "shrink-0 accent-[var(--accent-primary)]",
+                            checked: !excluded.read().contains(&conversation.id),
+                            onchange: {
+                                let id = conversation.id.clone();
+                                move |_| {
+                                    let mut set = excluded.write();
+                                    if !set.remove(&id) {
+                                        set.insert(id.clone());
+                                    }
+                                }
+                            },
+                        }
+                        span { class: "flex-1 truncate text-sm text-[var(--text-primary)]", title: "{conversation.title}", "{conversation.title}" }
+                        input {
+                            class: "w-40 px-2 py-0.5 rounded-md text-[11px] bg-transparent border border-[var(--border-subtle)] text-[var(--text-secondary)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            placeholder: "tags",
+                            initial_value: conversation.tags.join(", "),
+                            onchange: {
+                                let id = conversation.id.clone();
+                                let app_state = app_state.clone();
+                                move |e: Event<FormData>| app_state.set_conversation_tags(&id, parse_tags(&e.value()))
+                            },
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex items-center gap-2",
+                button {
+                    class: BUTTON_CLASS,
+                    disabled: selected_count == 0,
+                    onclick: move |_| {
+                        let selected: Vec<_> = export_candidates
+                            .iter()
+                            .filter(|c| !excluded.read().contains(&c.id))
+                            .cloned()
+                            .collect();
+                        let options = DatasetOptions {
+                            format: format(),
+                            feedback: feedback_filter(),
+                            scrub: scrub(),
+                        };
+                        let result = export_dataset(&selected, &load_all_feedback(), &options)
+                            .map_err(|e| e.to_string());
+                        if let Err(error) = &result {
+                            tracing::error!("Failed to export dataset: {}", error);
+                        }
+                        export_result.set(Some(result));
+                    },
+                    if is_en { "Export dataset" } else { "Exporter le jeu de donnees" }
+                }
+                if let Some(Ok((path, _))) = export_result.read().clone() {
+                    button {
+                        class: BUTTON_CLASS,
+                        onclick: move |_| {
+                            let Some(folder) = path.parent() else {
+                                return;
+                            };
+                            let result = if cfg!(target_os = "windows") {
+                                Command::new("explorer").arg(folder).spawn()
+                            } else if cfg!(target_os = "macos") {
+                                Command::new("open").arg(folder).spawn()
+                            } else {
+                                Command::new("xdg-open").arg(folder).spawn()
+                            };
+
+                            if let Err(error) = result {
+                                tracing::error!("Failed to open datasets directory: {}", error);
+                            }
+                        },
+                        if is_en { "Open folder" } else { "Ouvrir le dossier" }
+                    }
+                }
+            }
+            match export_result.read().clone() {
+                Some(Ok((path, count))) => rsx! {
+                    p { class: "text-xs text-[var(--text-secondary)] mt-3 break-all",
+                        if is_en { "{count} records written to " } else { "{count} exemples ecrits dans " }
+                        span { class: "font-mono", "{path.display()}" }
+                    }
+                },
+                Some(Err(error)) => rsx! {
+                    p { class: "text-xs text-[var(--error)] mt-3", "{error}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
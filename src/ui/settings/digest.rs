@@ -0,0 +1,182 @@
+use crate::agent::digest::{self, DigestSettings};
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Change the digest settings and save them
+fn update_digest(app_state: &AppState, change: impl FnOnce(&mut DigestSettings)) {
+    let mut settings = app_state.settings;
+    let mut settings = settings.write();
+    change(&mut settings.digest);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Daily digest card: new feed items summarized into a note conversation
+pub fn FeedDigestSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    let personas: Vec<(String, String)> = settings
+        .personas()
+        .into_iter()
+        .map(|p| (p.id.clone(), p.label(is_en)))
+        .collect();
+    let digest_settings = settings.digest.clone();
+    let persona = digest_settings.persona.clone().unwrap_or_default();
+
+    let mut draft_url = use_signal(String::new);
+    let mut error = use_signal(|| false);
+    let mut running = use_signal(digest::is_running);
+    let mut last_run = use_signal(digest::last_run);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            if *running.peek() != digest::is_running() {
+                running.set(digest::is_running());
+                last_run.set(digest::last_run());
+            }
+        }
+    });
+
+    let app_state_toggle = app_state.clone();
+    let app_state_hour = app_state.clone();
+    let app_state_persona = app_state.clone();
+    let app_state_add = app_state.clone();
+    let app_state_run = app_state.clone();
+    let status = match (running(), last_run()) {
+        (true, _) if is_en => "Writing the digest...".to_string(),
+        (true, _) => "Redaction du digest...".to_string(),
+        (false, Some(day)) if is_en => format!("Last digest: {}", day.format("%Y-%m-%d")),
+        (false, Some(day)) => format!("Dernier digest : {}", day.format("%Y-%m-%d")),
+        (false, None) if is_en => "No digest yet".to_string(),
+        (false, None) => "Aucun digest pour l'instant".to_string(),
+    };
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            div { class: "flex items-center justify-between gap-3 mb-1",
+                h3 {
+                    class: "text-base font-semibold text-[var(--text-primary)]",
+                    if is_en { "📰 Daily digest" } else { "📰 Digest quotidien" }
+                }
+                button {
+                    class: if digest_settings.enabled { "toggle-switch active" } else { "toggle-switch" },
+                    onclick: move |_| update_digest(&app_state_toggle, |digest| digest.enabled = !digest.enabled),
+                    div { class: "toggle-switch-knob" }
+                }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Every day from the chosen hour, the new items of these RSS/Atom feeds are summarized with the loaded model into a \"Digest\" conversation, and a notification is shown."
+                } else {
+                    "Chaque jour a partir de l'heure choisie, les nouveaux articles de ces flux RSS/Atom sont resumes avec le modele charge dans une conversation \"Digest\", et une notification s'affiche."
+                }
+            }
+
+            div { class: "space-y-2",
+                for (idx, url) in digest_settings.feeds.iter().cloned().enumerate() {
+                    {
+                        let app_state_remove = app_state.clone();
+                        rsx! {
+                            div {
+                                key: "{idx}-{url}",
+                                class: "flex items-center justify-between gap-3",
+                                span { class: "text-sm font-mono text-[var(--text-primary)] truncate", title: "{url}", "{url}" }
+                                button {
+                                    class: "w-7 h-7 shrink-0 rounded-md text-[var(--text-tertiary)] hover:text-[var(--error)] hover:bg-white/[0.06]",
+                                    title: if is_en { "Remove feed" } else { "Retirer le flux" },
+                                    onclick: move |_| update_digest(&app_state_remove, |digest| {
+                                        if idx < digest.feeds.len() {
+                                            digest.feeds.remove(idx);
+                                        }
+                                    }),
+                                    "✕"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex items-center gap-2 mt-4",
+                input {
+                    class: "{INPUT_CLASS} flex-1 font-mono",
+                    placeholder: "https://example.com/feed.xml",
+                    value: "{draft_url}",
+                    oninput: move |e| {
+                        draft_url.set(e.value());
+                        error.set(false);
+                    },
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| {
+                        let url = draft_url.read().trim().to_string();
+                        if reqwest::Url::parse(&url).map_or(true, |u| !matches!(u.scheme(), "http" | "https")) {
+                            return error.set(true);
+                        }
+                        update_digest(&app_state_add, |digest| {
+                            if !digest.feeds.contains(&url) {
+                                digest.feeds.push(url);
+                            }
+                        });
+                        draft_url.set(String::new());
+                    },
+                    if is_en { "Add" } else { "Ajouter" }
+                }
+            }
+            if error() {
+                p { class: "text-xs text-[var(--error)] mt-2",
+                    if is_en { "Enter an http(s) feed URL." } else { "Saisissez l'URL http(s) d'un flux." }
+                }
+            }
+
+            div { class: "flex items-center gap-2 mt-4",
+                select {
+                    class: INPUT_CLASS,
+                    value: "{digest_settings.hour}",
+                    onchange: move |e: Event<FormData>| {
+                        if let Ok(hour) = e.value().parse::<u32>() {
+                            update_digest(&app_state_hour, |digest| digest.hour = hour.min(23));
+                        }
+                    },
+                    for hour in 0..24u32 {
+                        option { value: "{hour}", selected: hour == digest_settings.hour, "{hour:02}:00" }
+                    }
+                }
+                select {
+                    class: "{INPUT_CLASS} flex-1",
+                    value: "{persona}",
+                    onchange: move |e: Event<FormData>| {
+                        let value = e.value();
+                        update_digest(&app_state_persona, |digest| digest.persona = Some(value).filter(|v| !v.is_empty()));
+                    },
+                    option { value: "", if is_en { "No persona" } else { "Sans persona" } }
+                    for (id, label) in personas.iter() {
+                        option { value: "{id}", selected: *id == persona, "{label}" }
+                    }
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    disabled: running() || digest_settings.feeds.is_empty(),
+                    onclick: move |_| {
+                        let engine = app_state_run.engine.clone();
+                        let settings = app_state_run.settings.peek().digest.clone();
+                        running.set(true);
+                        spawn(async move {
+                            digest::run_now(engine, settings).await;
+                        });
+                    },
+                    if is_en { "Run now" } else { "Lancer" }
+                }
+            }
+            p { class: "text-xs text-[var(--text-tertiary)] mt-2", "{status}" }
+        }
+    }
+}
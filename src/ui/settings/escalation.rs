@@ -0,0 +1,243 @@
+use crate::agent::escalation::{EscalationPolicy, EscalationProvider};
+use crate::app::AppState;
+use crate::storage::escalation_log::current_month_spend;
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+
+/// Apply a change to the escalation policy and persist it
+fn update_policy(app_state: &mut AppState, change: impl FnOnce(&mut EscalationPolicy)) {
+    let mut settings = app_state.settings.write();
+    change(&mut settings.escalation);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Cloud escalation ("phone a friend") settings card
+pub fn EscalationSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    let policy = settings.escalation.clone();
+    let current_model = settings.openrouter_model.clone();
+    let month_spend = use_signal(current_month_spend);
+
+    let provider_value = match policy.provider {
+        EscalationProvider::OpenRouter => "open_router",
+        EscalationProvider::OpenAi => "open_ai",
+    };
+    let models = policy.provider.popular_models();
+    let task_types = policy.task_types.join(", ");
+    let budget = if policy.monthly_budget_usd > 0.0 {
+        format!("{:.2}", policy.monthly_budget_usd)
+    } else {
+        String::new()
+    };
+    let spend_label = if policy.monthly_budget_usd > 0.0 {
+        format!("${:.4} / ${:.2}", month_spend(), policy.monthly_budget_usd)
+    } else {
+        format!("${:.4}", month_spend())
+    };
+
+    let mut app_state_enabled = app_state.clone();
+    let mut app_state_provider = app_state.clone();
+    let mut app_state_model = app_state.clone();
+    let mut app_state_user = app_state.clone();
+    let mut app_state_model_initiated = app_state.clone();
+    let mut app_state_failures = app_state.clone();
+    let mut app_state_tasks = app_state.clone();
+    let mut app_state_budget = app_state.clone();
+
+    rsx! {
+        div {
+            class: "p-5 rounded-2xl glass-md",
+
+            div {
+                class: "flex items-center justify-between mb-1",
+                h3 {
+                    class: "text-base font-semibold text-[var(--text-primary)]",
+                    if is_en { "☁️ Cloud escalation" } else { "☁️ Escalade cloud" }
+                }
+                button {
+                    onclick: move |_| update_policy(&mut app_state_enabled, |p| p.enabled = !p.enabled),
+                    class: if policy.enabled { "toggle-switch active" } else { "toggle-switch" },
+                    div { class: "toggle-switch-knob" }
+                }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-5",
+                if is_en {
+                    "Lets the local agent ask a more powerful remote model (ai_consult). Every call shows exactly what will be sent and needs your approval."
+                } else {
+                    "Permet a l'agent local de consulter un modele distant plus puissant (ai_consult). Chaque appel montre exactement ce qui sera envoye et demande votre accord."
+                }
+            }
+
+            if policy.enabled {
+                div {
+                    class: "flex flex-col gap-4",
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-40",
+                            if is_en { "Provider" } else { "Fournisseur" }
+                        }
+                        select {
+                            class: "flex-1 {INPUT_CLASS}",
+                            value: "{provider_value}",
+                            onchange: move |e: Event<FormData>| {
+                                let provider = match e.value().as_str() {
+                                    "open_ai" => EscalationProvider::OpenAi,
+                                    _ => EscalationProvider::OpenRouter,
+                                };
+                                update_policy(&mut app_state_provider, |p| p.provider = provider);
+                            },
+                            option { value: "open_router", "OpenRouter" }
+                            option { value: "open_ai", "OpenAI" }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-40",
+                            if is_en { "Model" } else { "Modèle" }
+                        }
+                        select {
+                            class: "flex-1 {INPUT_CLASS}",
+                            value: "{current_model}",
+                            onchange: move |e: Event<FormData>| {
+                                let mut settings = app_state_model.settings.write();
+                                settings.openrouter_model = e.value().to_string();
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            for (id, label) in models {
+                                option { value: "{id}", "{label}" }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-2 text-xs text-[var(--text-tertiary)]",
+                        span { "💡" }
+                        if is_en {
+                            "Set the {policy.provider.api_key_env()} environment variable."
+                        } else {
+                            "Definir la variable d'environnement {policy.provider.api_key_env()}."
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center justify-between",
+                        div {
+                            div {
+                                class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "When I ask for it" } else { "Quand je le demande" }
+                            }
+                            div {
+                                class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                                if is_en { "e.g. \"ask a more powerful model\"" } else { "ex. \"demande a un modele plus puissant\"" }
+                            }
+                        }
+                        button {
+                            onclick: move |_| update_policy(&mut app_state_user, |p| p.on_user_request = !p.on_user_request),
+                            class: if policy.on_user_request { "toggle-switch active" } else { "toggle-switch" },
+                            div { class: "toggle-switch-knob" }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center justify-between",
+                        div {
+                            div {
+                                class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "On the local model's initiative" } else { "A l'initiative du modele local" }
+                            }
+                            div {
+                                class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                                if is_en { "Otherwise only the triggers below allow it" } else { "Sinon seuls les declencheurs ci-dessous l'autorisent" }
+                            }
+                        }
+                        button {
+                            onclick: move |_| update_policy(&mut app_state_model_initiated, |p| p.allow_model_initiated = !p.allow_model_initiated),
+                            class: if policy.allow_model_initiated { "toggle-switch active" } else { "toggle-switch" },
+                            div { class: "toggle-switch-knob" }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-40",
+                            if is_en { "After failures (0 = off)" } else { "Apres echecs (0 = jamais)" }
+                        }
+                        input {
+                            r#type: "number",
+                            min: "0",
+                            max: "20",
+                            value: "{policy.after_failed_iterations}",
+                            oninput: move |e| {
+                                if let Ok(value) = e.value().parse::<usize>() {
+                                    update_policy(&mut app_state_failures, |p| p.after_failed_iterations = value.min(20));
+                                }
+                            },
+                            class: "w-24 {INPUT_CLASS}",
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-40",
+                            if is_en { "Task types" } else { "Types de taches" }
+                        }
+                        input {
+                            r#type: "text",
+                            placeholder: if is_en { "proof, legal, architecture" } else { "preuve, juridique, architecture" },
+                            value: "{task_types}",
+                            onchange: move |e: Event<FormData>| {
+                                let types: Vec<String> = e
+                                    .value()
+                                    .split(',')
+                                    .map(|t| t.trim().to_string())
+                                    .filter(|t| !t.is_empty())
+                                    .collect();
+                                update_policy(&mut app_state_tasks, |p| p.task_types = types);
+                            },
+                            class: "flex-1 {INPUT_CLASS}",
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-40",
+                            if is_en { "Monthly budget ($)" } else { "Budget mensuel ($)" }
+                        }
+                        input {
+                            r#type: "number",
+                            min: "0",
+                            step: "0.5",
+                            placeholder: if is_en { "unlimited" } else { "illimite" },
+                            value: "{budget}",
+                            onchange: move |e: Event<FormData>| {
+                                let value = e.value().parse::<f64>().unwrap_or(0.0).max(0.0);
+                                update_policy(&mut app_state_budget, |p| p.monthly_budget_usd = value);
+                            },
+                            class: "w-24 {INPUT_CLASS}",
+                        }
+                        span {
+                            class: "text-xs text-[var(--text-tertiary)]",
+                            if is_en { "This month: {spend_label}" } else { "Ce mois-ci : {spend_label}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::app::AppState;
+use crate::storage::feedback::{load_all_feedback, remove_feedback, summarize, Rating};
+use crate::ui::settings::dataset::DatasetExport;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+
+fn excerpt(text: &str, max: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > max {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// Feedback review: approval per model, every rated message and the
+/// dataset export
+pub fn FeedbackSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut revision = use_signal(|| 0u32);
+    let mut model_filter = use_signal(String::new);
+    let mut rating_filter = use_signal(|| None::<Rating>);
+
+    let entries = use_memo(move || {
+        let _ = revision();
+        load_all_feedback()
+    });
+    let entries = entries.read();
+    let summaries = summarize(&entries);
+    let shown: Vec<_> = entries
+        .iter()
+        .filter(|e| model_filter.read().is_empty() || *model_filter.read() == e.model)
+        .filter(|e| rating_filter().is_none_or(|r| r == e.rating))
+        .map(|e| (e.clone(), e.timestamp.format("%Y-%m-%d %H:%M").to_string()))
+        .collect();
+
+    rsx! {
+        div { class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+            div { class: "p-5 rounded-2xl glass-md",
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    if is_en { "Ratings by model" } else { "Avis par modele" }
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-4",
+                    if is_en {
+                        "👍/👎 given under assistant answers, filed under the model loaded at the time. Stored locally in the feedback folder with the prompt and the answer."
+                    } else {
+                        "👍/👎 donnes sous les reponses de l'assistant, classes sous le modele charge a ce moment. Stockes localement dans le dossier feedback avec la question et la reponse."
+                    }
+                }
+                if summaries.is_empty() {
+                    p {
+                        class: "text-sm text-[var(--text-tertiary)] italic",
+                        if is_en { "No rating yet." } else { "Aucun avis pour l'instant." }
+                    }
+                }
+                div { class: "flex flex-col gap-2",
+                    for summary in summaries.iter() {
+                        div {
+                            key: "{summary.model}",
+                            class: "grid grid-cols-[1fr_3rem_3rem_6rem] items-center gap-2 text-sm",
+                            span { class: "font-mono text-xs truncate text-[var(--text-primary)]", title: "{summary.model}", "{summary.model}" }
+                            span { class: "text-right text-[var(--success)]", "👍 {summary.up}" }
+                            span { class: "text-right text-[var(--error)]", "👎 {summary.down}" }
+                            div { class: "h-1.5 rounded-full overflow-hidden bg-[var(--error)]/40",
+                                div {
+                                    class: "h-full bg-[var(--success)]",
+                                    style: "width: {summary.approval() * 100.0:.0}%;",
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "p-5 rounded-2xl glass-md",
+                div { class: "flex items-center justify-between gap-3 mb-4",
+                    h3 {
+                        class: "text-base font-semibold text-[var(--text-primary)]",
+                        if is_en { "Rated messages" } else { "Messages notes" }
+                    }
+                    div { class: "flex items-center gap-2",
+                        select {
+                            class: INPUT_CLASS,
+                            onchange: move |e: Event<FormData>| model_filter.set(e.value()),
+                            option { value: "", if is_en { "All models" } else { "Tous les modeles" } }
+                            for summary in summaries.iter() {
+                                option { value: "{summary.model}", selected: *model_filter.read() == summary.model, "{summary.model}" }
+                            }
+                        }
+                        select {
+                            class: INPUT_CLASS,
+                            onchange: move |e: Event<FormData>| rating_filter.set(match e.value().as_str() {
+                                "up" => Some(Rating::Up),
+                                "down" => Some(Rating::Down),
+                                _ => None,
+                            }),
+                            option { value: "", if is_en { "All" } else { "Tous" } }
+                            option { value: "up", "👍" }
+                            option { value: "down", "👎" }
+                        }
+                    }
+                }
+                if shown.is_empty() {
+                    p {
+                        class: "text-sm text-[var(--text-tertiary)] italic",
+                        if is_en { "Nothing to show." } else { "Rien a afficher." }
+                    }
+                }
+                div { class: "flex flex-col gap-3",
+                    for (entry, rated_at) in shown {
+                        div {
+                            key: "{entry.conversation_id}-{entry.message_index}",
+                            class: "pb-3 border-b border-[var(--border-subtle)] last:border-0 last:pb-0",
+                            div { class: "flex items-center gap-2 text-xs text-[var(--text-tertiary)]",
+                                span { if entry.rating == Rating::Up { "👍" } else { "👎" } }
+                                span { class: "font-mono truncate", "{entry.model}" }
+                                span { "{rated_at}" }
+                                div { class: "flex-1" }
+                                button {
+                                    class: "px-2 py-0.5 rounded-md hover:text-[var(--error)] hover:bg-white/[0.06] transition-colors",
+                                    onclick: {
+                                        let entry = entry.clone();
+                                        move |_| {
+                                            if let Err(e) = remove_feedback(&entry.model, &entry.conversation_id, entry.message_index) {
+                                                tracing::error!("Failed to remove feedback: {}", e);
+                                            }
+                                            revision += 1;
+                                        }
+                                    },
+                                    if is_en { "Delete" } else { "Supprimer" }
+                                }
+                            }
+                            if !entry.comment.is_empty() {
+                                p { class: "text-sm text-[var(--text-primary)] mt-1", "“{entry.comment}”" }
+                            }
+                            p { class: "text-xs text-[var(--text-secondary)] mt-1",
+                                span { class: "font-medium", if is_en { "Q: " } else { "Q : " } }
+                                "{excerpt(&entry.prompt, 160)}"
+                            }
+                            p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                                span { class: "font-medium", if is_en { "A: " } else { "R : " } }
+                                "{excerpt(&entry.response, 240)}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            DatasetExport {}
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use crate::system::file_associations::{is_supported, register, unregister};
+use dioxus::prelude::*;
+
+/// Opt-in "Open with ClawRS" for models and text files, and `clawrs://` links
+pub fn FileAssociationSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, enabled) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.file_associations)
+    };
+    let supported = is_supported();
+    let mut error = use_signal(|| None::<String>);
+    let mut app_state_toggle = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            div { class: "flex items-center justify-between",
+                div {
+                    h3 { class: "text-base font-semibold text-[var(--text-primary)]",
+                        if is_en { "Open files with ClawRS" } else { "Ouvrir les fichiers avec ClawRS" }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                        if !supported {
+                            if is_en {
+                                "On this system, file types come from the app bundle."
+                            } else {
+                                "Sur ce systeme, les types de fichiers viennent du paquet de l'application."
+                            }
+                        } else if is_en {
+                            "Double-clicking a .gguf loads it; \"Open with ClawRS\" on a .md or .txt file starts a conversation with it attached. clawrs:// links (clawrs://new?prompt=..., clawrs://conversation/<id>) open in this window."
+                        } else {
+                            "Un double-clic sur un .gguf le charge ; \"Ouvrir avec ClawRS\" sur un .md ou .txt demarre une conversation avec le fichier joint. Les liens clawrs:// (clawrs://new?prompt=..., clawrs://conversation/<id>) s'ouvrent dans cette fenetre."
+                        }
+                    }
+                }
+                button {
+                    class: if enabled { "toggle-switch active" } else { "toggle-switch" },
+                    disabled: !supported,
+                    onclick: move |_| {
+                        let enable = !enabled;
+                        let result = if enable { register() } else { unregister() };
+                        match result {
+                            Ok(()) => {
+                                error.set(None);
+                                let mut settings = app_state_toggle.settings.write();
+                                settings.file_associations = enable;
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            }
+                            Err(e) => error.set(Some(e.to_string())),
+                        }
+                    },
+                    div { class: "toggle-switch-knob" }
+                }
+            }
+            if let Some(message) = error() {
+                p { class: "text-xs text-[var(--error)] mt-3", "{message}" }
+            }
+        }
+    }
+}
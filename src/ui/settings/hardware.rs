@@ -1,5 +1,10 @@
-use crate::app::AppState;
+use crate::app::{AppState, ModelState};
+use crate::inference::InferenceBackend;
+use crate::storage::models::{scan_models_for_role, ModelRole};
+use crate::storage::model_sources::ExternalSource;
+use crate::storage::ollama::ollama_models_dir;
 use crate::storage::settings::save_settings;
+use crate::system::diagnostics::create_diagnostic_bundle;
 use crate::system::gpu::{detect_gpu, GpuInfo};
 use crate::system::resources::{get_resource_usage, ResourceUsage};
 use dioxus::prelude::*;
@@ -9,26 +14,78 @@ pub fn HardwareSettings() -> Element {
     let app_state = use_context::<AppState>();
     let settings = app_state.settings.read().clone();
     let gpu_layers = settings.gpu_layers;
+    let gpu_layers_auto = settings.gpu_layers_auto;
     let models_dir = settings.models_directory.to_string_lossy().to_string();
     let models_dir_path = settings.models_directory.clone();
     let auto_load_model = settings.auto_load_model;
     let last_model_path = settings.last_model_path.clone();
+    let last_gpu_layers = settings.last_gpu_layers;
     let mut app_state_gpu_layers = app_state.clone();
+    let mut app_state_gpu_auto = app_state.clone();
+    let app_state_reload = app_state.clone();
+    // A layer change only applies once the model is reloaded
+    let mut pending_reload = use_signal(|| false);
+    let loaded_model = match &*app_state.model_state.read() {
+        ModelState::Loaded(path) => Some(path.clone()),
+        _ => None,
+    };
     let mut app_state_auto_load = app_state.clone();
+    let mut app_state_ollama = app_state.clone();
+    let ollama_import = settings.ollama_import;
+    let ollama_dir = ollama_models_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let is_en = settings.language == "en";
+    // (source, detected folder, enabled)
+    let external_sources: Vec<(ExternalSource, Option<String>, bool)> = ExternalSource::ALL
+        .into_iter()
+        .map(|source| {
+            let detected = source.detect().map(|p| p.to_string_lossy().to_string());
+            (source, detected, settings.external_model_sources.contains(&source))
+        })
+        .collect();
+
+    // (role, configured path, default path, models found)
+    let aux_directories: Vec<(ModelRole, String, String, usize)> = ModelRole::ALL
+        .into_iter()
+        .filter(|role| *role != ModelRole::Chat)
+        .map(|role| {
+            let configured = settings
+                .model_directories
+                .get(role)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let default = settings
+                .models_directory
+                .join(role.subdirectory())
+                .to_string_lossy()
+                .to_string();
+            (role, configured, default, scan_models_for_role(&settings, role).len())
+        })
+        .collect();
 
     let gpu_info = use_signal(GpuInfo::default);
     let ram_usage = use_signal(ResourceUsage::default);
     let info_loaded = use_signal(|| false);
+    // Path of the last bundle written, or the error
+    let mut bundle_result = use_signal(|| None::<Result<std::path::PathBuf, String>>);
+    let app_state_bundle = app_state.clone();
 
+    // Refresh the readouts while the page is open; detection may spawn nvidia-smi
     {
         let mut gpu_info = gpu_info.clone();
         let mut ram_usage = ram_usage.clone();
         let mut info_loaded = info_loaded.clone();
-        use_effect(move || {
-            if !info_loaded() {
-                gpu_info.set(detect_gpu());
-                ram_usage.set(get_resource_usage());
-                info_loaded.set(true);
+        use_future(move || async move {
+            loop {
+                if let Ok((gpu, ram)) =
+                    tokio::task::spawn_blocking(|| (detect_gpu(), get_resource_usage())).await
+                {
+                    gpu_info.set(gpu);
+                    ram_usage.set(ram);
+                    info_loaded.set(true);
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             }
         });
     }
@@ -169,13 +226,19 @@ pub fn HardwareSettings() -> Element {
                 div { class: "mb-6",
                     div { class: "flex items-center justify-between",
                         div {
-                            label { class: "text-sm font-medium text-[var(--text-primary)]", "Charger auto. au demarrage" }
+                            label { class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "Reload last model on launch" } else { "Recharger le dernier modele au lancement" }
+                            }
                             p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
                                 {
-                                    if let Some(ref path) = last_model_path {
-                                        format!("Dernier: {}", std::path::Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default())
-                                    } else {
-                                        "Aucun modele sauvegarde".to_string()
+                                    match (&last_model_path, is_en) {
+                                        (Some(path), _) => {
+                                            let name = std::path::Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                                            let layers = last_gpu_layers.unwrap_or(gpu_layers);
+                                            if is_en { format!("Last: {} ({} GPU layers)", name, layers) } else { format!("Dernier: {} ({} couches GPU)", name, layers) }
+                                        }
+                                        (None, true) => "No model loaded yet".to_string(),
+                                        (None, false) => "Aucun modele sauvegarde".to_string(),
                                     }
                                 }
                             }
@@ -198,9 +261,27 @@ pub fn HardwareSettings() -> Element {
                 div { class: "mb-6",
                     div { class: "flex justify-between items-center mb-2",
                         label { class: "text-sm font-medium text-[var(--text-primary)]", "GPU Layers" }
-                        span {
-                            class: "text-xs font-mono px-2 py-1 rounded-lg bg-white/[0.04] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
-                            "{gpu_layers}"
+                        div { class: "flex items-center gap-3",
+                            label { class: "flex items-center gap-1.5 text-xs text-[var(--text-secondary)] cursor-pointer",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "accent-[var(--accent-primary)]",
+                                    checked: gpu_layers_auto,
+                                    onchange: move |e| {
+                                        let mut settings = app_state_gpu_auto.settings.write();
+                                        settings.gpu_layers_auto = e.checked();
+                                        if let Err(error) = save_settings(&settings) {
+                                            tracing::error!("Failed to save settings: {}", error);
+                                        }
+                                        pending_reload.set(true);
+                                    },
+                                }
+                                "Auto"
+                            }
+                            span {
+                                class: "text-xs font-mono px-2 py-1 rounded-lg bg-white/[0.04] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
+                                if gpu_layers_auto { "auto" } else { "{gpu_layers}" }
+                            }
                         }
                     }
                     input {
@@ -208,6 +289,7 @@ pub fn HardwareSettings() -> Element {
                         min: "0",
                         max: "99",
                         value: "{gpu_layers}",
+                        disabled: gpu_layers_auto,
                         oninput: move |e| {
                             let value = e.value().parse().unwrap_or(0);
                             let mut settings = app_state_gpu_layers.settings.write();
@@ -215,11 +297,47 @@ pub fn HardwareSettings() -> Element {
                             if let Err(error) = save_settings(&settings) {
                                 tracing::error!("Failed to save settings: {}", error);
                             }
+                            pending_reload.set(true);
                         },
-                        class: "w-full",
+                        class: if gpu_layers_auto { "w-full opacity-50" } else { "w-full" },
+                    }
+                    // Live readout to size the offload against
+                    div { class: "flex justify-between text-xs text-[var(--text-secondary)] mt-2",
+                        span { "{gpu_name}" }
+                        if vram_total_mb > 0 {
+                            span { class: "font-mono",
+                                if is_en { "{vram_free_gb:.1} GB free" } else { "{vram_free_gb:.1} GB libres" }
+                            }
+                        }
                     }
                     p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
-                        "Layers to offload to GPU. Higher values need more VRAM."
+                        if gpu_layers_auto {
+                            if is_en {
+                                "Offloads as many layers as fit in free VRAM, checked each time a model loads."
+                            } else {
+                                "Decharge sur le GPU autant de couches que la VRAM libre le permet, calcule a chaque chargement."
+                            }
+                        } else if is_en {
+                            "Layers to offload to GPU. Higher values need more VRAM."
+                        } else {
+                            "Couches dechargees sur le GPU. Plus il y en a, plus il faut de VRAM."
+                        }
+                    }
+                    if let Some(path) = loaded_model.filter(|_| pending_reload()) {
+                        div { class: "flex items-center justify-between gap-3 mt-3 px-3 py-2 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)]",
+                            span { class: "text-xs text-[var(--text-secondary)]",
+                                if is_en { "Applies when the model is reloaded." } else { "S'applique au rechargement du modele." }
+                            }
+                            button {
+                                class: "px-3 py-1.5 rounded-lg bg-white/[0.06] text-[var(--text-primary)] text-xs font-medium hover:bg-white/[0.1] transition-colors",
+                                onclick: move |_| {
+                                    let gpu_layers = app_state_reload.settings.read().gpu_layers;
+                                    app_state_reload.load_model(path.clone(), gpu_layers);
+                                    pending_reload.set(false);
+                                },
+                                if is_en { "Reload model" } else { "Recharger le modele" }
+                            }
+                        }
                     }
                 }
 
@@ -256,6 +374,189 @@ pub fn HardwareSettings() -> Element {
                         "Location where model files (.gguf) are stored."
                     }
                 }
+
+                // Auxiliary model directories
+                div { class: "mt-6 pt-5 border-t border-[var(--border-subtle)]",
+                    label { class: "text-sm font-medium text-[var(--text-primary)] mb-1 block",
+                        if is_en { "Auxiliary model directories" } else { "Dossiers des modeles auxiliaires" }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mb-3",
+                        if is_en {
+                            "Leave empty to use a subfolder of the models directory. Embedding and Whisper files found in the models directory are also detected by name."
+                        } else {
+                            "Laisser vide pour utiliser un sous-dossier du dossier des modeles. Les fichiers Embedding et Whisper du dossier des modeles sont aussi detectes par leur nom."
+                        }
+                    }
+                    div { class: "space-y-2",
+                        for (role, configured, default, count) in aux_directories {
+                            {
+                                let mut app_state_dir = app_state.clone();
+                                rsx! {
+                                    div { class: "flex items-center gap-3",
+                                        span { class: "w-40 text-sm text-[var(--text-secondary)]", "{role.label(is_en)}" }
+                                        input {
+                                            r#type: "text",
+                                            value: "{configured}",
+                                            placeholder: "{default}",
+                                            class: "flex-1 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm focus:outline-none focus:border-[var(--accent-primary)]",
+                                            onchange: move |e: Event<FormData>| {
+                                                let value = e.value().trim().to_string();
+                                                let path = (!value.is_empty()).then(|| std::path::PathBuf::from(value));
+                                                let mut settings = app_state_dir.settings.write();
+                                                settings.model_directories.set(role, path);
+                                                if let Err(error) = save_settings(&settings) {
+                                                    tracing::error!("Failed to save settings: {}", error);
+                                                }
+                                            },
+                                        }
+                                        span { class: "w-20 text-right text-xs font-mono text-[var(--text-tertiary)]",
+                                            if is_en { "{count} found" } else { "{count} trouve(s)" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Ollama store import
+                div { class: "mt-6 pt-5 border-t border-[var(--border-subtle)]",
+                    div { class: "flex items-center justify-between",
+                        div {
+                            label { class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "Import Ollama models" } else { "Importer les modeles Ollama" }
+                            }
+                            p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                                if is_en { "Lists models pulled with Ollama from {ollama_dir}, loaded in place." } else { "Liste les modeles telecharges avec Ollama depuis {ollama_dir}, charges sur place." }
+                            }
+                        }
+                        button {
+                            class: if ollama_import { "toggle-switch active" } else { "toggle-switch" },
+                            onclick: move |_| {
+                                let mut settings = app_state_ollama.settings.write();
+                                settings.ollama_import = !settings.ollama_import;
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                            div { class: "toggle-switch-knob" }
+                        }
+                    }
+                }
+
+                // Other apps' model folders
+                div { class: "mt-6 pt-5 border-t border-[var(--border-subtle)]",
+                    label { class: "text-sm font-medium text-[var(--text-primary)] mb-1 block",
+                        if is_en { "Also scan these locations" } else { "Analyser aussi ces emplacements" }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mb-3",
+                        if is_en { "Models found there are listed with a badge and loaded in place." } else { "Les modeles trouves sont affiches avec un badge et charges sur place." }
+                    }
+                    div { class: "space-y-2",
+                        for (source, detected, enabled) in external_sources {
+                            {
+                                let mut app_state_source = app_state.clone();
+                                let found = detected.is_some();
+                                let location = detected.unwrap_or_else(|| if is_en { "Not found".to_string() } else { "Introuvable".to_string() });
+                                rsx! {
+                                    div { class: "flex items-center justify-between gap-3",
+                                        div { class: "min-w-0",
+                                            span { class: "text-sm text-[var(--text-secondary)]", "{source.label()}" }
+                                            p { class: "text-xs font-mono truncate text-[var(--text-tertiary)]", "{location}" }
+                                        }
+                                        button {
+                                            class: if enabled { "toggle-switch active" } else { "toggle-switch" },
+                                            disabled: !found && !enabled,
+                                            onclick: move |_| {
+                                                let mut settings = app_state_source.settings.write();
+                                                if enabled {
+                                                    settings.external_model_sources.retain(|s| *s != source);
+                                                } else {
+                                                    settings.external_model_sources.push(source);
+                                                }
+                                                if let Err(error) = save_settings(&settings) {
+                                                    tracing::error!("Failed to save settings: {}", error);
+                                                }
+                                            },
+                                            div { class: "toggle-switch-knob" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Diagnostic bundle
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    if is_en { "Diagnostic bundle" } else { "Rapport de diagnostic" }
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    if is_en {
+                        "Zips recent logs, redacted settings, model metadata, the last run's tool calls and system info to attach to a GitHub issue. Nothing is sent: the file stays on this machine until you share it."
+                    } else {
+                        "Regroupe dans un zip les logs recents, les parametres (masques), les metadonnees du modele, les appels d'outils du dernier echange et les infos systeme, a joindre a une issue GitHub. Rien n'est envoye : le fichier reste sur cette machine tant que vous ne le partagez pas."
+                    }
+                }
+
+                div { class: "flex items-center gap-2",
+                    button {
+                        class: "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors",
+                        onclick: move |_| {
+                            let settings = app_state_bundle.settings.read().clone();
+                            // Skip model metadata rather than wait while a generation holds the engine
+                            let model = app_state_bundle
+                                .engine
+                                .try_lock()
+                                .ok()
+                                .and_then(|engine| engine.model_info().cloned());
+                            let result = create_diagnostic_bundle(&settings, model.as_ref())
+                                .map_err(|e| e.to_string());
+                            if let Err(error) = &result {
+                                tracing::error!("Failed to create diagnostic bundle: {}", error);
+                            }
+                            bundle_result.set(Some(result));
+                        },
+                        if is_en { "Create diagnostic bundle" } else { "Creer le rapport de diagnostic" }
+                    }
+                    if let Some(Ok(path)) = bundle_result.read().clone() {
+                        button {
+                            class: "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors",
+                            onclick: move |_| {
+                                let Some(folder) = path.parent() else {
+                                    return;
+                                };
+                                let result = if cfg!(target_os = "windows") {
+                                    Command::new("explorer").arg(folder).spawn()
+                                } else if cfg!(target_os = "macos") {
+                                    Command::new("open").arg(folder).spawn()
+                                } else {
+                                    Command::new("xdg-open").arg(folder).spawn()
+                                };
+
+                                if let Err(error) = result {
+                                    tracing::error!("Failed to open diagnostics directory: {}", error);
+                                }
+                            },
+                            if is_en { "Open folder" } else { "Ouvrir le dossier" }
+                        }
+                    }
+                }
+                match bundle_result.read().clone() {
+                    Some(Ok(path)) => rsx! {
+                        p { class: "text-xs font-mono text-[var(--text-secondary)] mt-3 break-all", "{path.display()}" }
+                    },
+                    Some(Err(error)) => rsx! {
+                        p { class: "text-xs text-[var(--error)] mt-3", "{error}" }
+                    },
+                    None => rsx! {},
+                }
             }
         }
     }
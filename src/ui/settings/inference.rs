@@ -1,31 +1,188 @@
 use crate::agent::{ExaSearchConfig, ExaSearchTool};
 use crate::app::AppState;
-use crate::storage::settings::save_settings;
+use crate::inference::{InferenceBackend, TruncationStrategy};
+use crate::storage::settings::{
+    default_system_prompt_for_lang, parse_max_tokens, save_settings, GenerationPreset, MaxTokensError, CONTEXT_SIZES, MAX_PENALTY_LAST_N, MAX_TOKENS_LIMIT,
+};
+use crate::storage::system_prompts::{diff_lines, DiffLine, SystemPromptPreset};
+use crate::system::gpu::get_total_vram_gb;
+use crate::ui::settings::backend::BackendSettingsCard;
 use dioxus::prelude::*;
 use std::sync::Arc;
 
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+fn context_tier(size: u32, is_en: bool) -> &'static str {
+    match (size, is_en) {
+        (..=2048, true) => "Ultra fast",
+        (..=2048, false) => "Ultra rapide",
+        (..=4096, true) => "Fast",
+        (..=4096, false) => "Rapide",
+        (..=8192, true) => "Recommended",
+        (..=8192, false) => "Recommande",
+        (..=16384, true) => "Balanced",
+        (..=16384, false) => "Equilibre",
+        (..=32768, true) => "Long context",
+        (..=32768, false) => "Long contexte",
+        (..=65536, true) => "Very long",
+        (..=65536, false) => "Tres long",
+        _ => "Maximum",
+    }
+}
+
 pub fn InferenceSettings() -> Element {
     let app_state = use_context::<AppState>();
     let settings = app_state.settings.read().clone();
     let temperature = settings.temperature;
     let top_p = settings.top_p;
+    let min_p = settings.min_p;
+    let typical_p = settings.typical_p;
+    let presence_penalty = settings.presence_penalty;
+    let frequency_penalty = settings.frequency_penalty;
+    let penalty_last_n = settings.penalty_last_n;
     let top_k = settings.top_k;
     let max_tokens = settings.max_tokens;
+    let max_tokens_auto = settings.max_tokens_auto;
     let context_size = settings.context_size;
+    let truncation = settings.prompt_truncation;
+    let stop_sequences = settings
+        .stop_sequences
+        .iter()
+        .map(|stop| stop.replace('\n', "\\n"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let debug_logprobs = settings.debug_logprobs;
+    let kv_cache_reuse = settings.kv_cache_reuse;
+    let context_over_vram = settings.context_over_vram;
+    let constrained_tool_calls = settings.constrained_tool_calls;
     let system_prompt = settings.system_prompt.clone();
+    let system_prompt_preset = settings.system_prompt_preset;
     let exa_mcp_url = settings.exa_mcp_url.clone();
     let mut app_state_temperature = app_state.clone();
     let mut app_state_top_p = app_state.clone();
+    let mut app_state_min_p = app_state.clone();
+    let mut app_state_typical_p = app_state.clone();
+    let mut app_state_presence = app_state.clone();
+    let mut app_state_frequency = app_state.clone();
+    let mut app_state_penalty_last_n = app_state.clone();
     let mut app_state_top_k = app_state.clone();
     let mut app_state_max_tokens = app_state.clone();
+    let mut app_state_max_tokens_auto = app_state.clone();
     let mut app_state_context_size = app_state.clone();
+    let mut app_state_truncation = app_state.clone();
+    let mut app_state_stops = app_state.clone();
+    let mut app_state_logprobs = app_state.clone();
+    let mut app_state_kv_cache = app_state.clone();
+    let mut app_state_context_over_vram = app_state.clone();
+    let mut app_state_grammar = app_state.clone();
     let mut app_state_system_prompt = app_state.clone();
+    let mut app_state_prompt_preset = app_state.clone();
+    let mut app_state_prompt_reset = app_state.clone();
+    let mut show_prompt_diff = use_signal(|| false);
+    let prompt_diff = if show_prompt_diff() {
+        changed_lines(&diff_lines(
+            &default_system_prompt_for_lang(&settings.language),
+            &system_prompt,
+        ))
+    } else {
+        Vec::new()
+    };
     let mut app_state_exa_mcp_url = app_state.clone();
+    let mut app_state_save_preset = app_state.clone();
+    let is_en = settings.language == "en";
+    let active_preset = settings.active_preset.clone();
+    let presets = settings.presets();
+    let mut preset_name = use_signal(String::new);
+    let mut max_tokens_draft = use_signal(|| max_tokens.to_string());
+    let mut max_tokens_error = use_signal(|| None::<MaxTokensError>);
+    let max_tokens_error_text = max_tokens_error().map(|error| match (error, is_en) {
+        (MaxTokensError::NotANumber, true) => "Enter a whole number.".to_string(),
+        (MaxTokensError::NotANumber, false) => "Entrez un nombre entier.".to_string(),
+        (MaxTokensError::OutOfRange, true) => format!("Between 1 and {}.", MAX_TOKENS_LIMIT),
+        (MaxTokensError::OutOfRange, false) => format!("Entre 1 et {}.", MAX_TOKENS_LIMIT),
+        (MaxTokensError::ExceedsContext, true) => {
+            format!("At most the context window ({} tokens).", context_size)
+        }
+        (MaxTokensError::ExceedsContext, false) => {
+            format!("Au plus la fenetre de contexte ({} tokens).", context_size)
+        }
+    });
+
+    // KV cache estimates need the loaded model; skip them while a generation holds the engine
+    let model_info = {
+        let _ = (app_state.model_state)();
+        app_state
+            .engine
+            .try_lock()
+            .ok()
+            .and_then(|engine| engine.model_info().cloned())
+    };
+    let vram_bytes = use_hook(|| get_total_vram_gb().map(|gb| (gb * GIB) as u64));
+    let context_options: Vec<(u32, String)> = CONTEXT_SIZES
+        .iter()
+        .map(|&size| {
+            let mut label = format!("{}K - {}", size / 1024, context_tier(size, is_en));
+            if let Some(info) = &model_info {
+                let kv = info.kv_cache_bytes(size);
+                label.push_str(&format!(" · KV ~{:.1} {}", kv as f64 / GIB, if is_en { "GB" } else { "Go" }));
+                if vram_bytes.is_some_and(|vram| info.size_bytes + kv > vram) {
+                    label.push_str(" ⚠");
+                }
+            }
+            (size, label)
+        })
+        .collect();
+    // Model and KV cache against the VRAM, when the context doesn't fit
+    let over_vram = model_info.as_ref().and_then(|info| {
+        let needed = info.size_bytes + info.kv_cache_bytes(context_size);
+        vram_bytes
+            .filter(|&vram| needed > vram)
+            .map(|vram| (needed, vram, info.max_context_in_vram(vram)))
+    });
+    let context_warning = model_info.as_ref().and_then(|info| {
+        let unit = if is_en { "GB" } else { "Go" };
+        if let Some((needed, vram, cap)) = over_vram {
+            Some(match (context_over_vram, is_en) {
+                (true, true) => format!(
+                    "The model and its KV cache need about {:.1} {unit}, more than the {:.1} {unit} of detected VRAM: part of it will spill into RAM and generation will slow down a lot.",
+                    needed as f64 / GIB,
+                    vram as f64 / GIB
+                ),
+                (true, false) => format!(
+                    "Le modele et son cache KV demandent environ {:.1} {unit}, plus que les {:.1} {unit} de VRAM detectes : une partie debordera en RAM et la generation sera bien plus lente.",
+                    needed as f64 / GIB,
+                    vram as f64 / GIB
+                ),
+                (false, true) => format!(
+                    "The model and its KV cache need about {:.1} {unit}, more than the {:.1} {unit} of detected VRAM: generations will use a {}K context.",
+                    needed as f64 / GIB,
+                    vram as f64 / GIB,
+                    cap / 1024
+                ),
+                (false, false) => format!(
+                    "Le modele et son cache KV demandent environ {:.1} {unit}, plus que les {:.1} {unit} de VRAM detectes : les generations utiliseront un contexte de {}K.",
+                    needed as f64 / GIB,
+                    vram as f64 / GIB,
+                    cap / 1024
+                ),
+            })
+        } else if context_size > info.context_length && info.context_length > 0 {
+            Some(if is_en {
+                format!("The model was trained on {}K tokens of context; answers may degrade beyond that.", info.context_length / 1024)
+            } else {
+                format!("Le modele a ete entraine sur {}K tokens de contexte ; les reponses peuvent se degrader au-dela.", info.context_length / 1024)
+            })
+        } else {
+            None
+        }
+    });
 
     rsx! {
         div {
             class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
 
+            BackendSettingsCard {}
+
             // Section: Generation Parameters — glass
             SettingsCard { title: "Generation Parameters",
                 SettingsSlider {
@@ -60,6 +217,38 @@ pub fn InferenceSettings() -> Element {
                     }
                 }
 
+                SettingsSlider {
+                    label: "Min P",
+                    value: min_p,
+                    min: 0.0,
+                    max: 0.5,
+                    step: 0.01,
+                    description: "Drops tokens less likely than this fraction of the top token. Keeps higher temperatures coherent; 0 disables it.",
+                    on_change: move |value| {
+                        let mut settings = app_state_min_p.settings.write();
+                        settings.min_p = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsSlider {
+                    label: "Typical P",
+                    value: typical_p,
+                    min: 0.0,
+                    max: 1.0,
+                    step: 0.05,
+                    description: "Locally typical sampling: keeps tokens close to the expected surprise. 0 disables it.",
+                    on_change: move |value| {
+                        let mut settings = app_state_typical_p.settings.write();
+                        settings.typical_p = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
                 SettingsNumber {
                     label: "Top K",
                     value: top_k as f64,
@@ -75,24 +264,202 @@ pub fn InferenceSettings() -> Element {
                         }
                     }
                 }
-            }
 
-            // Section: Model Configuration — glass
-            SettingsCard { title: "Model Configuration",
                 SettingsNumber {
-                    label: "Max Tokens (Output)",
-                    value: max_tokens as f64,
-                    min: 256.0,
-                    max: 16384.0,
-                    description: "Tokens a generer. Plus petit = plus rapide. (Defaut: 4096)",
+                    label: "Presence Penalty",
+                    value: presence_penalty as f64,
+                    min: -2.0,
+                    max: 2.0,
+                    step: 0.1,
+                    description: "Penalizes any token already used recently, pushing toward new topics. 0 disables it.",
+                    on_change: move |value: f64| {
+                        let mut settings = app_state_presence.settings.write();
+                        settings.presence_penalty = (value as f32).clamp(-2.0, 2.0);
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsNumber {
+                    label: "Frequency Penalty",
+                    value: frequency_penalty as f64,
+                    min: -2.0,
+                    max: 2.0,
+                    step: 0.1,
+                    description: "Penalizes tokens in proportion to how often they were repeated. Breaks phrase loops in long texts. 0 disables it.",
+                    on_change: move |value: f64| {
+                        let mut settings = app_state_frequency.settings.write();
+                        settings.frequency_penalty = (value as f32).clamp(-2.0, 2.0);
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsNumber {
+                    label: "Penalty Window",
+                    value: penalty_last_n as f64,
+                    min: -1.0,
+                    max: MAX_PENALTY_LAST_N as f64,
+                    description: "Recent tokens the presence and frequency penalties look at. -1 = whole context. (Default: 64)",
                     on_change: move |value: f64| {
-                        let mut settings = app_state_max_tokens.settings.write();
-                        settings.max_tokens = (value as u32).clamp(256, 16384);
+                        let mut settings = app_state_penalty_last_n.settings.write();
+                        settings.penalty_last_n = (value.round() as i32).clamp(-1, MAX_PENALTY_LAST_N);
                         if let Err(error) = save_settings(&settings) {
                             tracing::error!("Failed to save settings: {}", error);
                         }
                     }
                 }
+            }
+
+            // Section: Generation Presets — glass
+            SettingsCard { title: "Presets",
+                p { class: "text-xs text-[var(--text-tertiary)] mb-3",
+                    if is_en {
+                        "The active preset overrides the parameters above. Pick one here or above the chat input."
+                    } else {
+                        "Le preset actif remplace les parametres ci-dessus. Choisissez-le ici ou au-dessus de la zone de saisie."
+                    }
+                }
+                div { class: "space-y-1.5 mb-4",
+                    for preset in presets {
+                        {
+                            let is_active = active_preset.as_deref() == Some(preset.name.as_str());
+                            let is_custom = !GenerationPreset::is_builtin(&preset.name);
+                            let label = preset.label(is_en);
+                            let name = preset.name.clone();
+                            let delete_name = preset.name.clone();
+                            let mut app_state_activate = app_state.clone();
+                            let mut app_state_delete = app_state.clone();
+                            rsx! {
+                                div {
+                                    key: "{preset.name}",
+                                    class: if is_active {
+                                        "flex items-center gap-3 px-3 py-2 rounded-xl border border-[var(--accent-primary)] bg-[var(--accent-soft)]"
+                                    } else {
+                                        "flex items-center gap-3 px-3 py-2 rounded-xl border border-[var(--border-subtle)] bg-white/[0.02]"
+                                    },
+                                    button {
+                                        class: "flex-1 text-left text-sm text-[var(--text-primary)]",
+                                        onclick: {
+                                            let name = name.clone();
+                                            move |_| {
+                                                let mut settings = app_state_activate.settings.write();
+                                                settings.active_preset = if is_active { None } else { Some(name.clone()) };
+                                                if let Err(error) = save_settings(&settings) {
+                                                    tracing::error!("Failed to save settings: {}", error);
+                                                }
+                                            }
+                                        },
+                                        "{label}"
+                                    }
+                                    span { class: "text-[11px] font-mono text-[var(--text-tertiary)]",
+                                        "T {preset.temperature:.1} · k {preset.top_k} · p {preset.top_p:.2} · {preset.max_tokens}"
+                                    }
+                                    if is_custom {
+                                        button {
+                                            class: "text-xs text-[var(--text-tertiary)] hover:text-[var(--error)] transition-colors",
+                                            title: if is_en { "Delete preset" } else { "Supprimer le preset" },
+                                            onclick: move |_| {
+                                                let mut settings = app_state_delete.settings.write();
+                                                settings.delete_custom_preset(&delete_name);
+                                                if let Err(error) = save_settings(&settings) {
+                                                    tracing::error!("Failed to save settings: {}", error);
+                                                }
+                                            },
+                                            "✕"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "flex gap-2",
+                    input {
+                        r#type: "text",
+                        value: "{preset_name}",
+                        placeholder: if is_en { "Preset name" } else { "Nom du preset" },
+                        oninput: move |e| preset_name.set(e.value()),
+                        class: "flex-1 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
+                    }
+                    button {
+                        class: "px-4 py-2 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors",
+                        title: if is_en { "Save the parameters above as a new preset" } else { "Enregistrer les parametres ci-dessus comme nouveau preset" },
+                        onclick: move |_| {
+                            let mut settings = app_state_save_preset.settings.write();
+                            if settings.save_custom_preset(&preset_name()) {
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                                preset_name.set(String::new());
+                            }
+                        },
+                        if is_en { "Save current" } else { "Enregistrer" }
+                    }
+                }
+            }
+
+            // Section: Model Configuration — glass
+            SettingsCard { title: "Model Configuration",
+                // Max Tokens: typed values are checked against the limits validation enforces
+                div { class: "mb-6",
+                    div { class: "flex justify-between items-center mb-2",
+                        label { class: "text-sm font-medium text-[var(--text-primary)]", "Max Tokens (Output)" }
+                        label { class: "flex items-center gap-1.5 text-xs text-[var(--text-secondary)] cursor-pointer",
+                            input {
+                                r#type: "checkbox",
+                                class: "accent-[var(--accent-primary)]",
+                                checked: max_tokens_auto,
+                                onchange: move |e| {
+                                    let mut settings = app_state_max_tokens_auto.settings.write();
+                                    settings.max_tokens_auto = e.checked();
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                            }
+                            if is_en { "Auto (fit to context)" } else { "Auto (selon le contexte)" }
+                        }
+                    }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        max: "{MAX_TOKENS_LIMIT.min(context_size)}",
+                        disabled: max_tokens_auto,
+                        value: "{max_tokens_draft}",
+                        oninput: move |e| {
+                            let value = e.value();
+                            match parse_max_tokens(&value, context_size) {
+                                Ok(parsed) => {
+                                    max_tokens_error.set(None);
+                                    let mut settings = app_state_max_tokens.settings.write();
+                                    settings.max_tokens = parsed;
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                }
+                                Err(error) => max_tokens_error.set(Some(error)),
+                            }
+                            max_tokens_draft.set(value);
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm disabled:opacity-50",
+                    }
+                    if let Some(error) = max_tokens_error_text.filter(|_| !max_tokens_auto) {
+                        p { class: "text-xs text-[var(--text-error)] mt-1.5", "{error}" }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if max_tokens_auto {
+                            if is_en { "Each reply can use whatever context the prompt leaves free." } else { "Chaque reponse peut occuper tout le contexte laisse libre par le prompt." }
+                        } else if is_en {
+                            "Tokens to generate. Smaller = faster. (Default: 4096)"
+                        } else {
+                            "Tokens a generer. Plus petit = plus rapide. (Defaut: 4096)"
+                        }
+                    }
+                }
 
                 // Context Size
                 div { class: "mb-6",
@@ -114,23 +481,246 @@ pub fn InferenceSettings() -> Element {
                             }
                         },
                         class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm appearance-none cursor-pointer",
-                        option { value: "2048", "2K - Ultra rapide" }
-                        option { value: "4096", "4K - Rapide" }
-                        option { value: "8192", "8K - Recommande" }
-                        option { value: "16384", "16K - Equilibre" }
-                        option { value: "32768", "32K - Long contexte" }
+                        for (size, label) in context_options {
+                            option { key: "{size}", value: "{size}", selected: size == context_size, "{label}" }
+                        }
+                    }
+                    if let Some(warning) = context_warning {
+                        p { class: "text-xs text-[var(--text-error)] mt-1.5", "⚠ {warning}" }
+                    }
+                    if over_vram.is_some() {
+                        label { class: "flex items-center gap-2 text-xs text-[var(--text-secondary)] mt-1.5 cursor-pointer",
+                            input {
+                                r#type: "checkbox",
+                                class: "accent-[var(--accent-primary)]",
+                                checked: context_over_vram,
+                                onchange: move |e| {
+                                    let mut settings = app_state_context_over_vram.settings.write();
+                                    settings.context_over_vram = e.checked();
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                            }
+                            if is_en { "Keep this context anyway" } else { "Garder ce contexte quand meme" }
+                        }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if model_info.is_some() {
+                            if is_en { "Context size. Smaller = much faster. KV estimates are for the loaded model." } else { "Taille du contexte. Plus petit = beaucoup plus rapide. Estimations KV pour le modele charge." }
+                        } else if is_en {
+                            "Context size. Smaller = much faster. Load a model to see KV memory estimates."
+                        } else {
+                            "Taille du contexte. Plus petit = beaucoup plus rapide. Chargez un modele pour voir les estimations memoire KV."
+                        }
+                    }
+                }
+
+                // What happens to a prompt longer than the context
+                div { class: "mb-6",
+                    label { class: "block text-sm font-medium text-[var(--text-primary)] mb-2",
+                        if is_en { "When the prompt is too long" } else { "Quand le prompt est trop long" }
+                    }
+                    select {
+                        value: "{truncation.id()}",
+                        onchange: move |e| {
+                            let Some(strategy) = TruncationStrategy::ALL.into_iter().find(|s| s.id() == e.value()) else {
+                                return;
+                            };
+                            let mut settings = app_state_truncation.settings.write();
+                            settings.prompt_truncation = strategy;
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm appearance-none cursor-pointer",
+                        for strategy in TruncationStrategy::ALL {
+                            option { key: "{strategy.id()}", value: "{strategy.id()}", selected: strategy == truncation, "{strategy.label(is_en)}" }
+                        }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if is_en {
+                            "Whole messages are dropped before the prompt is sent, so the system prompt and your question are never cut."
+                        } else {
+                            "Des messages entiers sont retires avant l'envoi du prompt, pour ne jamais couper le prompt systeme ni votre question."
+                        }
+                    }
+                }
+
+                // Model-specific stop strings
+                div { class: "mb-6",
+                    label { class: "block text-sm font-medium text-[var(--text-primary)] mb-2",
+                        if is_en { "Stop sequences" } else { "Sequences d'arret" }
+                    }
+                    textarea {
+                        class: "w-full h-20 py-2 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm font-mono resize-none custom-scrollbar",
+                        placeholder: "</s>\n\\nUser:",
+                        value: "{stop_sequences}",
+                        onchange: move |e| {
+                            let mut settings = app_state_stops.settings.write();
+                            settings.stop_sequences = e
+                                .value()
+                                .lines()
+                                .filter(|line| !line.is_empty())
+                                .map(|line| line.replace("\\n", "\n"))
+                                .collect();
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if is_en {
+                            "One per line; \\n stands for a line break. The reply ends at the first one, which is not shown."
+                        } else {
+                            "Une par ligne ; \\n represente un retour a la ligne. La reponse s'arrete a la premiere, qui n'est pas affichee."
+                        }
+                    }
+                }
+
+                // Debugging: per-token confidence, shown with each reply
+                div { class: "mb-6",
+                    label { class: "flex items-center gap-2 text-sm font-medium text-[var(--text-primary)] cursor-pointer",
+                        input {
+                            r#type: "checkbox",
+                            class: "accent-[var(--accent-primary)]",
+                            checked: debug_logprobs,
+                            onchange: move |e| {
+                                let mut settings = app_state_logprobs.settings.write();
+                                settings.debug_logprobs = e.checked();
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                        }
+                        if is_en { "Record token probabilities" } else { "Enregistrer les probabilites des tokens" }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if is_en {
+                            "For debugging: each reply keeps every token's probability and the likeliest alternatives, shown colored by confidence. Makes conversations larger."
+                        } else {
+                            "Pour le debogage : chaque reponse garde la probabilite de chaque token et les alternatives les plus probables, affichees en couleur selon la confiance. Alourdit les conversations."
+                        }
+                    }
+                }
+
+                // Debugging: prompt prefix reuse between generations
+                div { class: "mb-6",
+                    label { class: "flex items-center gap-2 text-sm font-medium text-[var(--text-primary)] cursor-pointer",
+                        input {
+                            r#type: "checkbox",
+                            class: "accent-[var(--accent-primary)]",
+                            checked: kv_cache_reuse,
+                            onchange: move |e| {
+                                let mut settings = app_state_kv_cache.settings.write();
+                                settings.kv_cache_reuse = e.checked();
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                        }
+                        if is_en { "Reuse the prompt cache" } else { "Reutiliser le cache du prompt" }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if is_en {
+                            "The start a prompt shares with the previous one (system prompt, history) is not evaluated again, so each agent step answers sooner. Turn off only to debug."
+                        } else {
+                            "Le debut qu'un prompt partage avec le precedent (prompt systeme, historique) n'est pas reevalue : chaque etape de l'agent repond plus vite. A desactiver seulement pour deboguer."
+                        }
+                    }
+                }
+
+                // Grammar-constrained tool calls
+                div { class: "mb-6",
+                    label { class: "flex items-center gap-2 text-sm font-medium text-[var(--text-primary)] cursor-pointer",
+                        input {
+                            r#type: "checkbox",
+                            class: "accent-[var(--accent-primary)]",
+                            checked: constrained_tool_calls,
+                            onchange: move |e| {
+                                let mut settings = app_state_grammar.settings.write();
+                                settings.constrained_tool_calls = e.checked();
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                        }
+                        if is_en { "Constrain tool calls with a grammar" } else { "Contraindre les appels d'outils par une grammaire" }
+                    }
+                    p {
+                        class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if is_en {
+                            "When the agent is expected to call a tool (after a reflection step or a malformed call), the reply can only be text or a valid call to an existing tool, instead of retrying."
+                        } else {
+                            "Quand l'agent doit appeler un outil (apres une etape de reflexion ou un appel mal forme), la reponse ne peut etre que du texte ou un appel valide a un outil existant, au lieu de reessayer."
+                        }
                     }
-                    p { class: "text-xs text-[var(--text-tertiary)] mt-1.5", "Taille du contexte. Plus petit = beaucoup plus rapide." }
                 }
 
-                // System Prompt Textarea
+                // System Prompt: presets, free editing (saved as the custom preset) and a diff
                 div { class: "space-y-2",
-                    label { class: "text-sm font-medium text-[var(--text-primary)]", "System Prompt" }
+                    div { class: "flex items-center justify-between gap-2",
+                        label { class: "text-sm font-medium text-[var(--text-primary)]", "System Prompt" }
+                        div { class: "flex items-center gap-2",
+                            select {
+                                class: "py-1 px-2 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] text-xs outline-none cursor-pointer",
+                                value: "{system_prompt_preset.id()}",
+                                onchange: move |e| {
+                                    let Some(preset) = SystemPromptPreset::from_id(&e.value()) else { return };
+                                    let mut settings = app_state_prompt_preset.settings.write();
+                                    // Start a custom prompt from the one on screen
+                                    if preset == SystemPromptPreset::Custom && settings.custom_system_prompt.trim().is_empty() {
+                                        settings.custom_system_prompt = settings.system_prompt.clone();
+                                    }
+                                    settings.system_prompt_preset = preset;
+                                    settings.apply_system_prompt_preset();
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                                for preset in SystemPromptPreset::ALL {
+                                    option { value: "{preset.id()}", selected: preset == system_prompt_preset, "{preset.label(is_en)}" }
+                                }
+                            }
+                            button {
+                                class: "px-2 py-1 rounded-lg text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06] transition-colors",
+                                onclick: move |_| show_prompt_diff.toggle(),
+                                if show_prompt_diff() {
+                                    if is_en { "Hide diff" } else { "Masquer le diff" }
+                                } else if is_en {
+                                    "Diff vs default"
+                                } else {
+                                    "Diff avec le defaut"
+                                }
+                            }
+                            button {
+                                class: "px-2 py-1 rounded-lg text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06] transition-colors disabled:opacity-40",
+                                disabled: system_prompt_preset == SystemPromptPreset::Default,
+                                title: if is_en { "Back to the default agent prompt; the custom text is kept in its preset" } else { "Revenir au prompt agent par defaut ; le texte personnalise reste dans son preset" },
+                                onclick: move |_| {
+                                    let mut settings = app_state_prompt_reset.settings.write();
+                                    settings.system_prompt_preset = SystemPromptPreset::Default;
+                                    settings.apply_system_prompt_preset();
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                                if is_en { "Reset" } else { "Reinitialiser" }
+                            }
+                        }
+                    }
                     textarea {
                         value: "{system_prompt}",
                         oninput: move |e| {
                             let value = e.value();
                             let mut settings = app_state_system_prompt.settings.write();
+                            settings.system_prompt_preset = SystemPromptPreset::Custom;
+                            settings.custom_system_prompt = value.clone();
                             settings.system_prompt = value;
                             if let Err(error) = save_settings(&settings) {
                                 tracing::error!("Failed to save settings: {}", error);
@@ -139,6 +729,28 @@ pub fn InferenceSettings() -> Element {
                         class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm h-28 resize-y",
                         placeholder: "Enter system prompt..."
                     }
+                    if show_prompt_diff() {
+                        div { class: "max-h-64 overflow-y-auto custom-scrollbar rounded-xl bg-white/[0.02] border border-[var(--border-subtle)] p-2 font-mono text-[11px] leading-relaxed",
+                            if prompt_diff.is_empty() {
+                                p { class: "text-[var(--text-tertiary)]",
+                                    if is_en { "Same as the default prompt." } else { "Identique au prompt par defaut." }
+                                }
+                            }
+                            for (idx, line) in prompt_diff.into_iter().enumerate() {
+                                {
+                                    let (prefix, text, style) = match line {
+                                        Some(DiffLine::Added(text)) => ("+", text, "color: #34d399;"),
+                                        Some(DiffLine::Removed(text)) => ("-", text, "color: #f87171;"),
+                                        Some(DiffLine::Same(text)) => (" ", text, "color: var(--text-tertiary);"),
+                                        None => ("", "…".to_string(), "color: var(--text-tertiary);"),
+                                    };
+                                    rsx! {
+                                        div { key: "{idx}", class: "whitespace-pre-wrap break-words", style, "{prefix} {text}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
                     p { class: "text-xs text-[var(--text-tertiary)]", "Initial instructions for the model's behavior." }
                 }
             }
@@ -164,13 +776,17 @@ pub fn InferenceSettings() -> Element {
                             }
 
                             let registry = app_state_exa_mcp_url.agent.tool_registry.clone();
-                            let tool = ExaSearchTool::new(ExaSearchConfig {
+                            match ExaSearchTool::new(ExaSearchConfig {
                                 mcp_url: value,
                                 ..Default::default()
-                            });
-                            spawn(async move {
-                                registry.register(Arc::new(tool)).await;
-                            });
+                            }) {
+                                Ok(tool) => {
+                                    spawn(async move {
+                                        registry.register(Arc::new(tool)).await;
+                                    });
+                                }
+                                Err(error) => tracing::error!("Failed to create the Exa client: {}", error),
+                            }
                         },
                         placeholder: "https://mcp.exa.ai/mcp",
                         class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm",
@@ -184,6 +800,25 @@ pub fn InferenceSettings() -> Element {
     }
 }
 
+/// Changed lines with one line of context; `None` marks skipped lines
+fn changed_lines(diff: &[DiffLine]) -> Vec<Option<DiffLine>> {
+    let changed = |idx: usize| diff.get(idx).is_some_and(|line| !matches!(line, DiffLine::Same(_)));
+    let mut lines = Vec::new();
+    let mut skipped = false;
+    for (idx, line) in diff.iter().enumerate() {
+        if changed(idx) || changed(idx + 1) || (idx > 0 && changed(idx - 1)) {
+            if skipped && !lines.is_empty() {
+                lines.push(None);
+            }
+            lines.push(Some(line.clone()));
+            skipped = false;
+        } else {
+            skipped = true;
+        }
+    }
+    lines
+}
+
 #[component]
 fn SettingsCard(title: &'static str, children: Element) -> Element {
     rsx! {
@@ -242,6 +877,7 @@ fn SettingsNumber(
     value: f64,
     min: f64,
     max: f64,
+    #[props(default = 1.0)] step: f64,
     description: &'static str,
     on_change: EventHandler<f64>,
 ) -> Element {
@@ -252,6 +888,7 @@ fn SettingsNumber(
                 r#type: "number",
                 min: "{min}",
                 max: "{max}",
+                step: "{step}",
                 value: "{value}",
                 oninput: move |e| {
                     let val = e.value().parse().unwrap_or(value);
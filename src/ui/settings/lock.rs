@@ -0,0 +1,144 @@
+use crate::app::AppState;
+use crate::storage::conversations::{list_conversations, set_ephemeral};
+use crate::storage::lock::LockSettings;
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Save the new lock state and switch the conversation store and watched
+/// folders to match; the open conversation is closed either way
+fn apply_lock(app_state: &mut AppState, lock: LockSettings) {
+    let enabled = lock.enabled;
+    {
+        let mut settings = app_state.settings.write();
+        settings.lock = lock;
+        if let Err(e) = save_settings(&settings) {
+            tracing::error!("Failed to save settings: {}", e);
+        }
+    }
+    set_ephemeral(enabled);
+    // Folder workflows stop while locked and resume on unlock
+    app_state.watch_folders();
+    app_state.current_conversation.set(None);
+    match list_conversations() {
+        Ok(conversations) => app_state.conversations.set(conversations),
+        Err(e) => tracing::error!("Failed to list conversations: {}", e),
+    }
+    tracing::info!(
+        "Locked mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+}
+
+/// Unlock if the PIN matches, otherwise flag it as wrong
+fn try_unlock(app_state: &mut AppState, mut pin: Signal<String>, mut wrong_pin: Signal<bool>) {
+    let mut lock = app_state.settings.read().lock.clone();
+    if lock.unlock(&pin.read()) {
+        wrong_pin.set(false);
+        apply_lock(app_state, lock);
+    } else {
+        tracing::warn!("Wrong PIN for locked mode");
+        wrong_pin.set(true);
+    }
+    pin.set(String::new());
+}
+
+/// Card to turn locked mode on, with an optional PIN
+pub fn LockModeSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut pin = use_signal(String::new);
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "Locked mode" } else { "Mode verrouille" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-5",
+                if is_en {
+                    "For shared or demo machines. Settings are frozen, file writing, shell and network tools are disabled, and conversations are kept in memory only: they disappear when the app closes or is unlocked. Existing conversations are hidden meanwhile."
+                } else {
+                    "Pour les postes partages ou de demonstration. Les parametres sont figes, l'ecriture de fichiers, le shell et les outils reseau sont desactives, et les conversations restent en memoire : elles disparaissent a la fermeture ou au deverrouillage. Les conversations existantes sont masquees pendant ce temps."
+                }
+            }
+            div { class: "flex items-center gap-2",
+                input {
+                    class: "{INPUT_CLASS} w-40",
+                    r#type: "password",
+                    inputmode: "numeric",
+                    placeholder: if is_en { "PIN (optional)" } else { "PIN (optionnel)" },
+                    value: "{pin}",
+                    oninput: move |e| pin.set(e.value()),
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| {
+                        let mut lock = LockSettings::default();
+                        lock.lock(&pin.read());
+                        pin.set(String::new());
+                        apply_lock(&mut app_state.clone(), lock);
+                    },
+                    if is_en { "Lock" } else { "Verrouiller" }
+                }
+            }
+        }
+    }
+}
+
+/// Shown instead of the settings while locked
+pub fn UnlockPanel() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let has_pin = app_state.settings.read().lock.has_pin();
+    let mut pin = use_signal(String::new);
+    let wrong_pin = use_signal(|| false);
+    let mut app_state_enter = app_state.clone();
+    let mut app_state_click = app_state.clone();
+
+    rsx! {
+        div { class: "max-w-md mx-auto mt-16 p-6 rounded-2xl glass-md text-center animate-fade-in-up",
+            div { class: "text-3xl mb-3", "🔒" }
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "Settings are locked" } else { "Les parametres sont verrouilles" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-5",
+                if is_en {
+                    "Read-only tools only; conversations are not saved."
+                } else {
+                    "Outils en lecture seule uniquement ; les conversations ne sont pas enregistrees."
+                }
+            }
+            div { class: "flex items-center justify-center gap-2",
+                if has_pin {
+                    input {
+                        class: "{INPUT_CLASS} w-40",
+                        r#type: "password",
+                        inputmode: "numeric",
+                        placeholder: "PIN",
+                        value: "{pin}",
+                        oninput: move |e| pin.set(e.value()),
+                        onkeydown: move |e: KeyboardEvent| {
+                            if e.key() == Key::Enter {
+                                try_unlock(&mut app_state_enter, pin, wrong_pin);
+                            }
+                        },
+                    }
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| try_unlock(&mut app_state_click, pin, wrong_pin),
+                    if is_en { "Unlock" } else { "Deverrouiller" }
+                }
+            }
+            if wrong_pin() {
+                p { class: "text-xs text-[var(--error)] mt-3", if is_en { "Wrong PIN." } else { "PIN incorrect." } }
+            }
+        }
+    }
+}
@@ -1,16 +1,39 @@
 #![allow(non_snake_case)]
 
 pub mod appearance;
+pub mod auto_tags;
+pub mod autosave;
+pub mod backend;
+pub mod certificates;
+pub mod dataset;
+pub mod digest;
+pub mod escalation;
+pub mod feedback;
+pub mod file_associations;
 pub mod hardware;
 pub mod inference;
+pub mod lock;
 pub mod tools;
+pub mod tool_health;
+pub mod watch_folders;
+pub mod workspace;
 pub mod skills;
 pub mod mcp;
+pub mod personas;
+pub mod proxy;
+pub mod routing;
+pub mod snippets;
+pub mod suggestions;
+pub mod updates;
 
 use crate::app::AppState;
+use crate::storage::get_data_dir;
+use crate::system::disk::{available_space, format_size, LOW_SPACE_BYTES};
 use crate::ui::settings::appearance::AppearanceSettings;
+use crate::ui::settings::feedback::FeedbackSettings;
 use crate::ui::settings::hardware::HardwareSettings;
 use crate::ui::settings::inference::InferenceSettings;
+use crate::ui::settings::lock::UnlockPanel;
 use crate::ui::settings::tools::ToolsSettings;
 use crate::ui::settings::skills::SkillsSettings;
 use crate::ui::settings::mcp::McpSettings;
@@ -23,6 +46,7 @@ enum SettingsTab {
     Tools,
     Skills,
     Mcp,
+    Feedback,
     Appearance,
 }
 
@@ -30,6 +54,15 @@ pub fn Settings() -> Element {
     let mut active_tab = use_signal(|| SettingsTab::Inference);
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
+    // Checked once per visit to Settings
+    let free_space = use_hook(|| get_data_dir().ok().and_then(|dir| available_space(&dir)));
+    let low_space = free_space.filter(|free| *free < LOW_SPACE_BYTES);
+
+    if app_state.settings.read().lock.enabled {
+        return rsx! {
+            div { class: "flex-1 overflow-y-auto p-6", UnlockPanel {} }
+        };
+    }
 
     rsx! {
         div {
@@ -72,6 +105,11 @@ pub fn Settings() -> Element {
                             onclick: move |_| active_tab.set(SettingsTab::Mcp),
                             label: "MCP",
                         }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Feedback,
+                            onclick: move |_| active_tab.set(SettingsTab::Feedback),
+                            label: if is_en { "Feedback" } else { "Avis" },
+                        }
                         TabButton {
                             active: active_tab() == SettingsTab::Appearance,
                             onclick: move |_| active_tab.set(SettingsTab::Appearance),
@@ -84,12 +122,24 @@ pub fn Settings() -> Element {
             // Content Area
             div {
                 class: "flex-1 overflow-y-auto p-6 scrollbar-thin",
+                if let Some(free) = low_space {
+                    div {
+                        class: "max-w-3xl mx-auto mb-4 px-4 py-3 rounded-xl text-sm",
+                        style: "background: rgba(251,191,36,0.10); color: #fbbf24; border: 1px solid rgba(251,191,36,0.25);",
+                        if is_en {
+                            "The data directory is nearly full ({format_size(free)} free). Model downloads and generated files may fail."
+                        } else {
+                            "Le dossier de donnees est presque plein ({format_size(free)} libres). Les telechargements de modeles et les fichiers generes peuvent echouer."
+                        }
+                    }
+                }
                 match active_tab() {
                     SettingsTab::Inference => rsx! { InferenceSettings {} },
                     SettingsTab::Hardware => rsx! { HardwareSettings {} },
                     SettingsTab::Tools => rsx! { ToolsSettings {} },
                     SettingsTab::Skills => rsx! { SkillsSettings {} },
                     SettingsTab::Mcp => rsx! { McpSettings {} },
+                    SettingsTab::Feedback => rsx! { FeedbackSettings {} },
                     SettingsTab::Appearance => rsx! { AppearanceSettings {} },
                 }
             }
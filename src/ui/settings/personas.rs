@@ -0,0 +1,116 @@
+use crate::app::AppState;
+use crate::storage::models::{scan_models_for_role, ModelRole};
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+
+/// Personas card: tool subsets and the model each persona loads
+pub fn PersonaSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    // (path, name)
+    let models: Vec<(String, String)> = scan_models_for_role(&settings, ModelRole::Chat)
+        .iter()
+        .map(|m| {
+            (
+                m.path.to_string_lossy().to_string(),
+                m.display_name().to_string(),
+            )
+        })
+        .collect();
+    let all_tools = if is_en {
+        "All tools"
+    } else {
+        "Tous les outils"
+    };
+    // (id, label, tools, excluded, max iterations, preferred model)
+    let personas: Vec<(String, String, String, String, String, String)> = settings
+        .personas()
+        .into_iter()
+        .map(|p| {
+            let tools = if p.allow_tools.is_empty() {
+                all_tools.to_string()
+            } else {
+                p.allow_tools.join(", ")
+            };
+            let iterations = p.max_iterations.map(|n| n.to_string()).unwrap_or_default();
+            (
+                p.id.clone(),
+                p.label(is_en),
+                tools,
+                p.deny_tools.join(", "),
+                iterations,
+                p.preferred_model.unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    rsx! {
+        div {
+            class: "p-5 rounded-2xl glass-md",
+
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                "Personas"
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-5",
+                if is_en {
+                    "Chosen per conversation above the input. Each persona only sees its own tools, which keeps the prompt short and risky tools out of reach. Custom personas can be added to custom_personas in settings.json."
+                } else {
+                    "A choisir par conversation au-dessus de la saisie. Chaque persona ne voit que ses outils, ce qui raccourcit le prompt et ecarte les outils risques. Des personas personnalises peuvent etre ajoutes dans custom_personas de settings.json."
+                }
+            }
+
+            div { class: "space-y-4",
+                for (id, label, tools, excluded, iterations, preferred) in personas {
+                    {
+                        let mut app_state_model = app_state.clone();
+                        rsx! {
+                            div {
+                                key: "{id}",
+                                class: "pb-4 border-b border-[var(--border-subtle)] last:border-0 last:pb-0",
+                                div { class: "flex items-center justify-between gap-3",
+                                    span { class: "text-sm font-medium text-[var(--text-primary)]", "{label}" }
+                                    select {
+                                        class: "{INPUT_CLASS} max-w-[50%]",
+                                        value: "{preferred}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let value = e.value();
+                                            let mut settings = app_state_model.settings.write();
+                                            if value.is_empty() {
+                                                settings.persona_models.remove(&id);
+                                            } else {
+                                                settings.persona_models.insert(id.clone(), value);
+                                            }
+                                            if let Err(e) = save_settings(&settings) {
+                                                tracing::error!("Failed to save settings: {}", e);
+                                            }
+                                        },
+                                        option { value: "", if is_en { "Current model" } else { "Modele actuel" } }
+                                        for (path, name) in models.iter() {
+                                            option { value: "{path}", selected: *path == preferred, "{name}" }
+                                        }
+                                    }
+                                }
+                                p { class: "text-xs font-mono text-[var(--text-secondary)] mt-1.5 break-words", "{tools}" }
+                                if !excluded.is_empty() {
+                                    p { class: "text-xs font-mono text-[var(--text-tertiary)] mt-0.5 break-words",
+                                        if is_en { "Excluded: {excluded}" } else { "Exclus : {excluded}" }
+                                    }
+                                }
+                                if !iterations.is_empty() {
+                                    p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                                        if is_en { "Up to {iterations} steps per request" } else { "Jusqu'a {iterations} etapes par demande" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
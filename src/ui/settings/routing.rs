@@ -0,0 +1,197 @@
+use crate::agent::routing::{RequestKind, RouteRule, RoutingSettings};
+use crate::app::AppState;
+use crate::storage::models::{scan_models_for_role, ModelRole};
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Change the routing settings and save them
+fn update_routing(app_state: &AppState, change: impl FnOnce(&mut RoutingSettings)) {
+    let mut settings = app_state.settings;
+    let mut settings = settings.write();
+    change(&mut settings.routing);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Change one rule, if it still exists
+fn update_rule(app_state: &AppState, idx: usize, change: impl FnOnce(&mut RouteRule)) {
+    update_routing(app_state, |routing| {
+        if let Some(rule) = routing.rules.get_mut(idx) {
+            change(rule);
+        }
+    });
+}
+
+fn kind_value(kind: Option<RequestKind>) -> &'static str {
+    match kind {
+        Some(RequestKind::Chat) => "chat",
+        Some(RequestKind::Code) => "code",
+        Some(RequestKind::General) => "general",
+        None => "",
+    }
+}
+
+/// Routing card: rules picking the model and persona per message
+pub fn ModelRoutingSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    // (path, name)
+    let models: Vec<(String, String)> = scan_models_for_role(&settings, ModelRole::Chat)
+        .iter()
+        .map(|m| (m.path.to_string_lossy().to_string(), m.display_name().to_string()))
+        .collect();
+    let personas: Vec<(String, String)> = settings
+        .personas()
+        .into_iter()
+        .map(|p| (p.id.clone(), p.label(is_en)))
+        .collect();
+    let routing = settings.routing.clone();
+
+    let app_state_toggle = app_state.clone();
+    let app_state_add = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            div { class: "flex items-center justify-between gap-3 mb-1",
+                h3 {
+                    class: "text-base font-semibold text-[var(--text-primary)]",
+                    if is_en { "🔀 Model routing" } else { "🔀 Routage des modeles" }
+                }
+                button {
+                    class: if routing.enabled { "toggle-switch active" } else { "toggle-switch" },
+                    onclick: move |_| update_routing(&app_state_toggle, |routing| routing.enabled = !routing.enabled),
+                    div { class: "toggle-switch-knob" }
+                }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Each message is classified (chit-chat, code, other) and the first matching rule picks the model and persona that answer it, e.g. a small model for chit-chat and a big one for code. Switching models reloads them. The chip above the input shows the route and can skip it for the next message."
+                } else {
+                    "Chaque message est classe (discussion, code, autre) et la premiere regle qui correspond choisit le modele et le persona qui repondent, par exemple un petit modele pour discuter et un gros pour le code. Changer de modele le recharge. La pastille au-dessus de la saisie montre la route et permet de l'ignorer pour le prochain message."
+                }
+            }
+
+            div { class: "space-y-4",
+                for (idx, rule) in routing.rules.iter().cloned().enumerate() {
+                    {
+                        let app_state_enabled = app_state.clone();
+                        let app_state_name = app_state.clone();
+                        let app_state_kind = app_state.clone();
+                        let app_state_keywords = app_state.clone();
+                        let app_state_model = app_state.clone();
+                        let app_state_persona = app_state.clone();
+                        let app_state_remove = app_state.clone();
+                        let kind = kind_value(rule.kind);
+                        let keywords = rule.keywords.join(", ");
+                        let model = rule.model.clone().unwrap_or_default();
+                        let persona = rule.persona.clone().unwrap_or_default();
+                        rsx! {
+                            div {
+                                key: "{idx}-{rule.name}",
+                                class: "pb-4 border-b border-[var(--border-subtle)] last:border-0 last:pb-0 space-y-2",
+                                div { class: "flex items-center gap-2",
+                                    input {
+                                        class: "{INPUT_CLASS} flex-1",
+                                        value: "{rule.name}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let name = e.value();
+                                            update_rule(&app_state_name, idx, |rule| rule.name = name);
+                                        },
+                                    }
+                                    button {
+                                        class: if rule.enabled { "toggle-switch active" } else { "toggle-switch" },
+                                        onclick: move |_| update_rule(&app_state_enabled, idx, |rule| rule.enabled = !rule.enabled),
+                                        div { class: "toggle-switch-knob" }
+                                    }
+                                    button {
+                                        class: "w-7 h-7 rounded-md text-[var(--text-tertiary)] hover:text-[var(--error)] hover:bg-white/[0.06]",
+                                        title: if is_en { "Remove rule" } else { "Supprimer la regle" },
+                                        onclick: move |_| update_routing(&app_state_remove, |routing| {
+                                            if idx < routing.rules.len() {
+                                                routing.rules.remove(idx);
+                                            }
+                                        }),
+                                        "✕"
+                                    }
+                                }
+                                div { class: "flex items-center gap-2",
+                                    select {
+                                        class: INPUT_CLASS,
+                                        value: "{kind}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let kind = RequestKind::ALL.into_iter().find(|k| kind_value(Some(*k)) == e.value());
+                                            update_rule(&app_state_kind, idx, |rule| rule.kind = kind);
+                                        },
+                                        option { value: "", selected: kind.is_empty(), if is_en { "Any message" } else { "Tout message" } }
+                                        for k in RequestKind::ALL {
+                                            option { value: kind_value(Some(k)), selected: kind == kind_value(Some(k)), "{k.label(is_en)}" }
+                                        }
+                                    }
+                                    input {
+                                        class: "{INPUT_CLASS} flex-1",
+                                        placeholder: if is_en { "Keywords, comma-separated (optional)" } else { "Mots-cles separes par des virgules (optionnel)" },
+                                        value: "{keywords}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let keywords = e
+                                                .value()
+                                                .split(',')
+                                                .map(|k| k.trim().to_string())
+                                                .filter(|k| !k.is_empty())
+                                                .collect();
+                                            update_rule(&app_state_keywords, idx, |rule| rule.keywords = keywords);
+                                        },
+                                    }
+                                }
+                                div { class: "flex items-center gap-2",
+                                    select {
+                                        class: "{INPUT_CLASS} flex-1",
+                                        value: "{model}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let value = e.value();
+                                            update_rule(&app_state_model, idx, |rule| rule.model = Some(value).filter(|v| !v.is_empty()));
+                                        },
+                                        option { value: "", if is_en { "Current model" } else { "Modele actuel" } }
+                                        for (path, name) in models.iter() {
+                                            option { value: "{path}", selected: *path == model, "{name}" }
+                                        }
+                                    }
+                                    select {
+                                        class: "{INPUT_CLASS} flex-1",
+                                        value: "{persona}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let value = e.value();
+                                            update_rule(&app_state_persona, idx, |rule| rule.persona = Some(value).filter(|v| !v.is_empty()));
+                                        },
+                                        option { value: "", if is_en { "Conversation persona" } else { "Persona de la conversation" } }
+                                        for (id, label) in personas.iter() {
+                                            option { value: "{id}", selected: *id == persona, "{label}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            button {
+                class: "{BUTTON_CLASS} mt-4",
+                onclick: move |_| update_routing(&app_state_add, |routing| {
+                    let name = if is_en {
+                        format!("Rule {}", routing.rules.len() + 1)
+                    } else {
+                        format!("Regle {}", routing.rules.len() + 1)
+                    };
+                    routing.rules.push(RouteRule::new(name, Some(RequestKind::Chat)));
+                }),
+                if is_en { "Add a rule" } else { "Ajouter une regle" }
+            }
+        }
+    }
+}
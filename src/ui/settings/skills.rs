@@ -4,6 +4,7 @@ use dioxus::prelude::*;
 
 pub fn SkillsSettings() -> Element {
     let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
     
     // Use resource to load skills async
     let mut skills_resource = use_resource(move || async move {
@@ -56,6 +57,33 @@ pub fn SkillsSettings() -> Element {
                                         div {
                                             h3 { class: "font-mono text-sm font-semibold text-[var(--text-primary)]", "{skill.name}" }
                                             p { class: "text-sm text-[var(--text-secondary)] mt-1", "{skill.description}" }
+                                            {
+                                                let permissions = skill.effective_permissions();
+                                                rsx! {
+                                                    div {
+                                                        class: "flex flex-wrap items-center gap-1.5 mt-2",
+                                                        if permissions.is_empty() {
+                                                            span {
+                                                                class: "px-2 py-0.5 rounded-md text-[11px] text-[var(--text-tertiary)] bg-white/[0.03]",
+                                                                if is_en { "No permissions" } else { "Aucune permission" }
+                                                            }
+                                                        }
+                                                        for permission in permissions {
+                                                            span {
+                                                                class: "px-2 py-0.5 rounded-md text-[11px] text-[var(--text-secondary)] bg-white/[0.05]",
+                                                                title: if skill.permissions.contains(&permission) {
+                                                                    if is_en { "Declared" } else { "Declaree" }
+                                                                } else if is_en {
+                                                                    "Not declared: required because the skill runs a script"
+                                                                } else {
+                                                                    "Non declaree : requise car le skill execute un script"
+                                                                },
+                                                                "{permission.icon()} {permission.label(is_en)}"
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
                                             div {
                                                 class: "flex items-center gap-2 mt-3 text-xs text-[var(--text-tertiary)]",
                                                 span { "📂" }
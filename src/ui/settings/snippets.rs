@@ -0,0 +1,142 @@
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use crate::storage::snippets::{variables, Snippet, SNIPPET_COMMAND};
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors disabled:opacity-50";
+
+fn update_snippets(app_state: &mut AppState, f: impl FnOnce(&mut Vec<Snippet>)) {
+    let mut settings = app_state.settings.write();
+    f(&mut settings.snippets);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Editor for the snippets expanded with `/snippet name` in the chat input
+pub fn SnippetsSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, snippets) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.snippets.clone())
+    };
+    let mut app_state_add = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                "Snippets"
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Type {SNIPPET_COMMAND} name in the chat to fill the input with a snippet. {{{{variable}}}} placeholders take the arguments that follow, in order or as key=value; {{{{date}}}} and {{{{time}}}} default to now."
+                } else {
+                    "Tapez {SNIPPET_COMMAND} nom dans le chat pour remplir la saisie avec un snippet. Les variables {{{{variable}}}} prennent les arguments qui suivent, dans l'ordre ou en cle=valeur ; {{{{date}}}} et {{{{time}}}} valent maintenant par defaut."
+                }
+            }
+            div { class: "space-y-3",
+                for (i, snippet) in snippets.into_iter().enumerate() {
+                    div {
+                        key: "{i}",
+                        class: "p-3 rounded-xl border border-[var(--border-subtle)] bg-white/[0.02] space-y-2",
+                        div { class: "flex items-center gap-2",
+                            input {
+                                class: "{INPUT_CLASS} w-40",
+                                placeholder: if is_en { "Name" } else { "Nom" },
+                                value: "{snippet.name}",
+                                oninput: {
+                                    let mut app_state = app_state.clone();
+                                    move |e: Event<FormData>| update_snippets(&mut app_state, |list| {
+                                        if let Some(s) = list.get_mut(i) {
+                                            s.name = e.value().trim().to_string();
+                                        }
+                                    })
+                                },
+                            }
+                            input {
+                                class: "{INPUT_CLASS} flex-1 min-w-0",
+                                placeholder: "Description",
+                                value: "{snippet.description}",
+                                oninput: {
+                                    let mut app_state = app_state.clone();
+                                    move |e: Event<FormData>| update_snippets(&mut app_state, |list| {
+                                        if let Some(s) = list.get_mut(i) {
+                                            s.description = e.value();
+                                        }
+                                    })
+                                },
+                            }
+                            button {
+                                class: "px-2 py-1 rounded-md text-xs text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
+                                title: if is_en { "Remove" } else { "Supprimer" },
+                                onclick: {
+                                    let mut app_state = app_state.clone();
+                                    move |_| update_snippets(&mut app_state, |list| {
+                                        if i < list.len() {
+                                            list.remove(i);
+                                        }
+                                    })
+                                },
+                                "✕"
+                            }
+                        }
+                        if !snippet.name.is_empty() && !snippet.has_valid_name() {
+                            p {
+                                class: "text-xs text-[var(--error)]",
+                                if is_en {
+                                    "Use only letters, digits, - and _ in the name."
+                                } else {
+                                    "Utilisez seulement des lettres, chiffres, - et _ dans le nom."
+                                }
+                            }
+                        }
+                        textarea {
+                            class: "{INPUT_CLASS} w-full resize-y",
+                            rows: "3",
+                            placeholder: if is_en { "Hello {{{{name}}}}, ..." } else { "Bonjour {{{{name}}}}, ..." },
+                            value: "{snippet.body}",
+                            oninput: {
+                                let mut app_state = app_state.clone();
+                                move |e: Event<FormData>| update_snippets(&mut app_state, |list| {
+                                    if let Some(s) = list.get_mut(i) {
+                                        s.body = e.value();
+                                    }
+                                })
+                            },
+                        }
+                        {
+                            let names = variables(&snippet.body);
+                            if names.is_empty() {
+                                rsx! {}
+                            } else {
+                                let names = names.join(", ");
+                                rsx! {
+                                    p {
+                                        class: "text-xs text-[var(--text-tertiary)]",
+                                        if is_en { "Variables: {names}" } else { "Variables : {names}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "flex items-center gap-2 mt-4",
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| update_snippets(&mut app_state_add, |list| {
+                        list.push(Snippet {
+                            name: String::new(),
+                            description: String::new(),
+                            body: String::new(),
+                        })
+                    }),
+                    if is_en { "Add a snippet" } else { "Ajouter un snippet" }
+                }
+            }
+        }
+    }
+}
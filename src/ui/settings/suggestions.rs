@@ -0,0 +1,164 @@
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use crate::storage::suggestions::{
+    default_suggestions, WelcomeSuggestion, MAX_SUGGESTIONS, SUGGESTION_ICONS,
+};
+use dioxus::prelude::*;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors disabled:opacity-50";
+
+/// Edit the saved suggestions, starting from the built-in ones on the first edit
+fn update_suggestions(app_state: &mut AppState, is_en: bool, f: impl FnOnce(&mut Vec<WelcomeSuggestion>)) {
+    let mut settings = app_state.settings.write();
+    let suggestions = settings
+        .welcome_suggestions
+        .get_or_insert_with(|| default_suggestions(is_en));
+    f(suggestions);
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// Editor for the prompt cards of the welcome screen
+pub fn WelcomeSuggestionsSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, customized, suggestions) = {
+        let settings = app_state.settings.read();
+        let is_en = settings.language == "en";
+        (
+            is_en,
+            settings.welcome_suggestions.is_some(),
+            settings
+                .welcome_suggestions
+                .clone()
+                .unwrap_or_else(|| default_suggestions(is_en)),
+        )
+    };
+    let full = suggestions.len() >= MAX_SUGGESTIONS;
+    let mut app_state_add = app_state.clone();
+    let mut app_state_reset = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "Welcome suggestions" } else { "Suggestions d'accueil" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Cards shown on the welcome screen. Clicking one starts a conversation with its prompt. Cards without a title or prompt are hidden."
+                } else {
+                    "Cartes affichees sur l'ecran d'accueil. Un clic demarre une conversation avec leur prompt. Les cartes sans titre ni prompt sont masquees."
+                }
+            }
+            div { class: "space-y-3",
+                for (i, suggestion) in suggestions.into_iter().enumerate() {
+                    div {
+                        key: "{i}",
+                        class: "p-3 rounded-xl border border-[var(--border-subtle)] bg-white/[0.02] space-y-2",
+                        div { class: "flex items-center gap-2",
+                            select {
+                                class: INPUT_CLASS,
+                                title: if is_en { "Icon" } else { "Icone" },
+                                onchange: {
+                                    let mut app_state = app_state.clone();
+                                    move |e: Event<FormData>| update_suggestions(&mut app_state, is_en, |list| {
+                                        if let Some(s) = list.get_mut(i) {
+                                            s.icon = e.value();
+                                        }
+                                    })
+                                },
+                                for (icon, _) in SUGGESTION_ICONS.iter() {
+                                    option { value: "{icon}", selected: suggestion.icon == *icon, "{icon}" }
+                                }
+                            }
+                            input {
+                                class: "{INPUT_CLASS} w-40",
+                                placeholder: if is_en { "Title" } else { "Titre" },
+                                value: "{suggestion.title}",
+                                oninput: {
+                                    let mut app_state = app_state.clone();
+                                    move |e: Event<FormData>| update_suggestions(&mut app_state, is_en, |list| {
+                                        if let Some(s) = list.get_mut(i) {
+                                            s.title = e.value();
+                                        }
+                                    })
+                                },
+                            }
+                            input {
+                                class: "{INPUT_CLASS} flex-1 min-w-0",
+                                placeholder: if is_en { "Subtitle" } else { "Sous-titre" },
+                                value: "{suggestion.subtitle}",
+                                oninput: {
+                                    let mut app_state = app_state.clone();
+                                    move |e: Event<FormData>| update_suggestions(&mut app_state, is_en, |list| {
+                                        if let Some(s) = list.get_mut(i) {
+                                            s.subtitle = e.value();
+                                        }
+                                    })
+                                },
+                            }
+                            button {
+                                class: "px-2 py-1 rounded-md text-xs text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
+                                title: if is_en { "Remove" } else { "Supprimer" },
+                                onclick: {
+                                    let mut app_state = app_state.clone();
+                                    move |_| update_suggestions(&mut app_state, is_en, |list| {
+                                        if i < list.len() {
+                                            list.remove(i);
+                                        }
+                                    })
+                                },
+                                "✕"
+                            }
+                        }
+                        textarea {
+                            class: "{INPUT_CLASS} w-full resize-y",
+                            rows: "2",
+                            placeholder: if is_en { "Prompt sent to the chat" } else { "Prompt envoye dans le chat" },
+                            value: "{suggestion.prompt}",
+                            oninput: {
+                                let mut app_state = app_state.clone();
+                                move |e: Event<FormData>| update_suggestions(&mut app_state, is_en, |list| {
+                                    if let Some(s) = list.get_mut(i) {
+                                        s.prompt = e.value();
+                                    }
+                                })
+                            },
+                        }
+                    }
+                }
+            }
+            div { class: "flex items-center gap-2 mt-4",
+                button {
+                    class: BUTTON_CLASS,
+                    disabled: full,
+                    onclick: move |_| update_suggestions(&mut app_state_add, is_en, |list| {
+                        list.push(WelcomeSuggestion {
+                            icon: "chat".to_string(),
+                            title: String::new(),
+                            subtitle: String::new(),
+                            prompt: String::new(),
+                        })
+                    }),
+                    if is_en { "Add a suggestion" } else { "Ajouter une suggestion" }
+                }
+                if customized {
+                    button {
+                        class: BUTTON_CLASS,
+                        onclick: move |_| {
+                            let mut settings = app_state_reset.settings.write();
+                            settings.welcome_suggestions = None;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        if is_en { "Restore defaults" } else { "Restaurer les suggestions par defaut" }
+                    }
+                }
+            }
+        }
+    }
+}
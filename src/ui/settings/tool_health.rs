@@ -0,0 +1,157 @@
+use crate::app::AppState;
+use crate::storage::audit_log::{load_all_audit_logs, tool_stats, ToolStats};
+use dioxus::prelude::*;
+
+/// Below this success rate a tool is flagged as unreliable
+const UNRELIABLE_RATE: f64 = 0.8;
+
+fn format_duration(ms: u64) -> String {
+    if ms >= 1000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+/// Tools health card: per-tool calls, success rate, median duration and the
+/// most common errors, aggregated from the audit logs of all conversations
+pub fn ToolHealthSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let audit_revision = app_state.audit_revision;
+
+    let stats = use_memo(move || {
+        let _ = audit_revision();
+        tool_stats(&load_all_audit_logs())
+    });
+    let stats = stats.read();
+    let total_calls: usize = stats.iter().map(|s| s.calls).sum();
+    let mut expanded = use_signal(|| None::<String>);
+
+    rsx! {
+        div {
+            class: "p-5 rounded-2xl glass-md",
+
+            div {
+                class: "flex items-center justify-between mb-1",
+                h3 {
+                    class: "text-base font-semibold text-[var(--text-primary)]",
+                    if is_en { "🩺 Tools health" } else { "🩺 Sante des outils" }
+                }
+                span {
+                    class: "text-xs text-[var(--text-tertiary)]",
+                    if is_en { "{total_calls} calls" } else { "{total_calls} appels" }
+                }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Usage and failure rate of each tool across all conversations. Click a tool to see its common errors and which models call it."
+                } else {
+                    "Utilisation et taux d'echec de chaque outil sur toutes les conversations. Cliquez sur un outil pour voir ses erreurs frequentes et les modeles qui l'appellent."
+                }
+            }
+
+            if stats.is_empty() {
+                p {
+                    class: "text-sm text-[var(--text-tertiary)] italic",
+                    if is_en { "No tool call recorded yet." } else { "Aucun appel d'outil enregistre." }
+                }
+            } else {
+                div {
+                    class: "flex flex-col gap-1",
+                    div {
+                        class: "grid grid-cols-[1fr_4rem_5rem_5rem] gap-2 px-2 text-[10px] uppercase tracking-wide text-[var(--text-tertiary)]",
+                        span { if is_en { "Tool" } else { "Outil" } }
+                        span { class: "text-right", if is_en { "Calls" } else { "Appels" } }
+                        span { class: "text-right", if is_en { "Success" } else { "Succes" } }
+                        span { class: "text-right", if is_en { "Median" } else { "Mediane" } }
+                    }
+                    for tool in stats.iter().cloned() {
+                        ToolHealthRow {
+                            key: "{tool.tool}",
+                            is_open: expanded().as_deref() == Some(tool.tool.as_str()),
+                            on_toggle: {
+                                let name = tool.tool.clone();
+                                move |_| {
+                                    let open = expanded().as_deref() == Some(name.as_str());
+                                    expanded.set(if open { None } else { Some(name.clone()) });
+                                }
+                            },
+                            is_en,
+                            tool,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ToolHealthRow(
+    tool: ToolStats,
+    is_open: bool,
+    on_toggle: EventHandler<()>,
+    is_en: bool,
+) -> Element {
+    let rate = tool.success_rate();
+    let rate_class = if rate < UNRELIABLE_RATE {
+        "text-right text-[#f87171]"
+    } else {
+        "text-right text-[var(--text-secondary)]"
+    };
+    let median = tool
+        .median_ms
+        .map(format_duration)
+        .unwrap_or_else(|| "—".to_string());
+
+    rsx! {
+        div {
+            class: "rounded-lg hover:bg-white/[0.03]",
+            button {
+                class: "grid grid-cols-[1fr_4rem_5rem_5rem] gap-2 w-full px-2 py-1.5 text-sm text-left",
+                onclick: move |_| on_toggle.call(()),
+                span { class: "font-mono text-[var(--text-primary)] truncate", "{tool.tool}" }
+                span { class: "text-right text-[var(--text-secondary)]", "{tool.calls}" }
+                span { class: "{rate_class}", "{rate * 100.0:.0}%" }
+                span { class: "text-right text-[var(--text-secondary)]", "{median}" }
+            }
+            if is_open {
+                div {
+                    class: "px-3 pb-2 flex flex-col gap-2 text-xs",
+                    if !tool.top_errors.is_empty() {
+                        div {
+                            div {
+                                class: "text-[var(--text-tertiary)] mb-0.5",
+                                if is_en { "Common errors" } else { "Erreurs frequentes" }
+                            }
+                            for (error, count) in tool.top_errors.iter() {
+                                div {
+                                    class: "flex gap-2 text-[var(--text-secondary)]",
+                                    span { class: "text-[#f87171] w-8 shrink-0", "×{count}" }
+                                    span { class: "font-mono break-all", "{error}" }
+                                }
+                            }
+                        }
+                    }
+                    if !tool.models.is_empty() {
+                        div {
+                            div {
+                                class: "text-[var(--text-tertiary)] mb-0.5",
+                                if is_en { "Called by" } else { "Appele par" }
+                            }
+                            for (model, count) in tool.models.iter() {
+                                div {
+                                    class: "flex gap-2 text-[var(--text-secondary)]",
+                                    span { class: "w-8 shrink-0", "×{count}" }
+                                    span { class: "truncate", "{model}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
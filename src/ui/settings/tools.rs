@@ -1,6 +1,21 @@
-use crate::agent::get_tool_permission;
+use crate::agent::{get_tool_permission, ReflectionTrigger};
 use crate::app::AppState;
-use crate::storage::settings::save_settings;
+use crate::ui::settings::escalation::EscalationSettings;
+use crate::ui::settings::certificates::CertificateSettings;
+use crate::ui::settings::lock::LockModeSettings;
+use crate::ui::settings::proxy::ProxySettingsCard;
+use crate::ui::settings::personas::PersonaSettings;
+use crate::ui::settings::routing::ModelRoutingSettings;
+use crate::ui::settings::auto_tags::AutoTagSettingsCard;
+use crate::ui::settings::tool_health::ToolHealthSettings;
+use crate::ui::settings::digest::FeedDigestSettings;
+use crate::ui::settings::watch_folders::WatchFolderSettings;
+use crate::ui::settings::workspace::WorkspaceSettings;
+use crate::storage::settings::{save_settings, PERMISSION_TIMEOUTS};
+use crate::storage::tool_cache::clear_tool_cache;
+use crate::storage::tool_recordings::{
+    delete_recording, list_recordings, sanitize_recording_name, ToolReplayMode,
+};
 use dioxus::prelude::*;
 
 /// Known tool groups for the allowlist UI
@@ -61,6 +76,7 @@ const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
             "web_fetch",
             "web_download",
             "web_crawl",
+            "feed_read",
             "ai_consult",
         ],
         "🌐",
@@ -102,8 +118,37 @@ pub fn ToolsSettings() -> Element {
     let is_en = settings.language == "en";
     let auto_approve = settings.auto_approve_all_tools;
     let allowlist = settings.tool_allowlist.clone();
+    let permission_timeout = settings.permission_timeout_secs;
+
+    let reflection = settings.reflection_policy.clone();
+    let trigger_value = match reflection.trigger {
+        ReflectionTrigger::Never => "never",
+        ReflectionTrigger::OnFailure => "on_failure",
+        ReflectionTrigger::Always => "always",
+    };
+
+    let persistent_cache = settings.persistent_tool_cache;
+    let injection_defense = settings.prompt_injection_defense;
+    let replay_mode = settings.tool_replay.clone();
+    let replay_value = match &replay_mode {
+        ToolReplayMode::Off => "off",
+        ToolReplayMode::Record(_) => "record",
+        ToolReplayMode::Replay(_) => "replay",
+    };
+    let mut recordings = use_signal(list_recordings);
+    let mut cache_status = use_signal(|| None::<String>);
 
     let mut app_state_toggle = app_state.clone();
+    let mut app_state_timeout = app_state.clone();
+    let mut app_state_cache = app_state.clone();
+    let mut app_state_injection = app_state.clone();
+    let mut app_state_replay = app_state.clone();
+    let mut app_state_replay_name = app_state.clone();
+    let mut app_state_replay_delete = app_state.clone();
+    let mut app_state_trigger = app_state.clone();
+    let mut app_state_recoveries = app_state.clone();
+    let mut app_state_long_toggle = app_state.clone();
+    let mut app_state_long_iterations = app_state.clone();
     let mut app_state_group = app_state.clone();
     let mut app_state_tool = app_state.clone();
 
@@ -111,88 +156,280 @@ pub fn ToolsSettings() -> Element {
         div {
             class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
 
-            // OpenRouter model selector
+            // Project folder mapped into the prompt
+            WorkspaceSettings {}
+
+            // Workflows run on files dropped in folders
+            WatchFolderSettings {}
+
+            // Daily digest of RSS/Atom feeds
+            FeedDigestSettings {}
+
+            // Personas and their tool subsets
+            PersonaSettings {}
+
+            // Model and persona picked per message
+            ModelRoutingSettings {}
+            AutoTagSettingsCard {}
+
+            // Cloud escalation (ai_consult)
+            EscalationSettings {}
+
+            // Proxy for network tools and downloads
+            ProxySettingsCard {}
+
+            // Custom root CAs for TLS interception
+            CertificateSettings {}
+
+            // Per-tool usage and failure rate
+            ToolHealthSettings {}
+
+            // Read-only kiosk mode
+            LockModeSettings {}
+
+            // Auto-approve ALL toggle
             div {
                 class: "p-5 rounded-2xl glass-md",
 
                 h3 {
                     class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
-                    "🤖 OpenRouter AI"
+                    if is_en { "Auto-approve Mode" } else { "Mode tout accepter" }
                 }
                 p {
                     class: "text-xs text-[var(--text-tertiary)] mb-5",
                     if is_en {
-                        "Model to use when the AI consults a more powerful external model via the ai_consult tool."
+                        "When enabled, ALL tool calls are automatically approved without asking. Use with caution."
                     } else {
-                        "Modèle à utiliser lorsque l'IA consulte un modèle externe plus puissant via l'outil ai_consult."
+                        "Quand active, TOUS les appels d'outils sont approuves automatiquement. A utiliser avec precaution."
                     }
                 }
 
                 div {
-                    class: "flex flex-col gap-3",
+                    class: "flex items-center justify-between",
 
-                    // Model dropdown
                     div {
-                        class: "flex items-center gap-4",
-                        label {
-                            class: "text-sm text-[var(--text-secondary)] w-32",
-                            if is_en { "Model" } else { "Modèle" }
+                        div {
+                            class: "text-sm font-medium text-[var(--text-primary)] flex items-center gap-2",
+                            if is_en { "Accept all tools" } else { "Tout accepter" }
+                            if auto_approve {
+                                span {
+                                    class: "px-1.5 py-0.5 rounded text-[10px] font-semibold uppercase",
+                                    style: "background: rgba(196,69,69,0.12); color: #C45B5B;",
+                                    if is_en { "DANGEROUS" } else { "DANGEREUX" }
+                                }
+                            }
                         }
-                        {
-                            let current_model = settings.openrouter_model.clone();
-                            let mut app_state_model = app_state.clone();
-                            rsx! {
-                                select {
-                                    class: "flex-1 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
-                                    value: "{current_model}",
-                                    onchange: move |e: Event<FormData>| {
-                                        let mut settings = app_state_model.settings.write();
-                                        settings.openrouter_model = e.value().to_string();
-                                        if let Err(e) = save_settings(&settings) {
-                                            tracing::error!("Failed to save settings: {}", e);
-                                        }
-                                    },
-                                    option { value: "openrouter/pony-alpha", "Pony Alpha (OpenRouter)" }
-                                    option { value: "nousresearch/deephermes-3-llama-3-8b-preview:free", "DeepHermes 3 8B (Free)" }
-                                    option { value: "google/gemma-2-9b-it:free", "Gemma 2 9B (Free)" }
-                                    option { value: "meta-llama/llama-3.2-3b-instruct:free", "Llama 3.2 3B (Free)" }
-                                    option { value: "google/gemini-2.0-flash-exp:free", "Gemini 2.0 Flash (Free)" }
-                                    option { value: "anthropic/claude-3.5-sonnet", "Claude 3.5 Sonnet ($)" }
-                                    option { value: "openai/gpt-4o", "GPT-4o ($)" }
-                                    option { value: "openai/gpt-4o-mini", "GPT-4o Mini ($)" }
-                                    option { value: "deepseek/deepseek-chat", "DeepSeek V3 ($)" }
+                        div {
+                            class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if is_en { "Skip permission dialogs for all tools" } else { "Ignorer les dialogues de permission pour tous les outils" }
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_toggle.settings.write();
+                            settings.auto_approve_all_tools = !settings.auto_approve_all_tools;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        class: if auto_approve { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+
+                div {
+                    class: "flex items-center justify-between mt-4",
+                    div {
+                        div {
+                            class: "text-sm font-medium text-[var(--text-primary)]",
+                            if is_en { "Permission timeout" } else { "Delai des permissions" }
+                        }
+                        div {
+                            class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if is_en {
+                                "Unanswered requests are denied after this; the countdown stops while the window is in the background"
+                            } else {
+                                "Les demandes sans reponse sont refusees apres ce delai ; le decompte s'arrete quand la fenetre est en arriere-plan"
+                            }
+                        }
+                    }
+                    select {
+                        class: "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        onchange: move |e| {
+                            let Ok(secs) = e.value().parse::<u32>() else {
+                                return;
+                            };
+                            let mut settings = app_state_timeout.settings.write();
+                            settings.permission_timeout_secs = secs;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        for secs in PERMISSION_TIMEOUTS {
+                            option {
+                                value: "{secs}",
+                                selected: secs == permission_timeout,
+                                if secs == 0 {
+                                    if is_en { "Wait, pause the run" } else { "Attendre, mettre en pause" }
+                                } else if secs >= 60 {
+                                    "{secs / 60} min"
+                                } else {
+                                    "{secs} s"
                                 }
                             }
                         }
                     }
+                }
+            }
+
+            // Prompt-injection defense
+            div {
+                class: "p-5 rounded-2xl glass-md",
 
-                    // API key info
+                div {
+                    class: "flex items-center justify-between",
                     div {
-                        class: "flex items-center gap-2 text-xs text-[var(--text-tertiary)]",
-                        span { "💡" }
-                        if is_en {
-                            "Set OPENROUTER_API_KEY environment variable. Get a free key at openrouter.ai/keys"
-                        } else {
-                            "Définir la variable d'environnement OPENROUTER_API_KEY. Clé gratuite sur openrouter.ai/keys"
+                        h3 {
+                            class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                            if is_en { "Prompt Injection Defense" } else { "Protection contre l'injection de prompt" }
                         }
+                        p {
+                            class: "text-xs text-[var(--text-tertiary)]",
+                            if is_en {
+                                "Web pages, files and MCP results are passed to the model as delimited data, with tool calls stripped out. Suspected injection attempts are flagged in the chat."
+                            } else {
+                                "Les pages web, fichiers et resultats MCP sont transmis au modele comme donnees delimitees, sans les appels d'outils qu'ils contiennent. Les tentatives d'injection suspectees sont signalees dans le chat."
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_injection.settings.write();
+                            settings.prompt_injection_defense = !settings.prompt_injection_defense;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        class: if injection_defense { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
                     }
                 }
             }
 
-            // Auto-approve ALL toggle
+            // Record / replay tool results
             div {
                 class: "p-5 rounded-2xl glass-md",
 
                 h3 {
                     class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
-                    if is_en { "Auto-approve Mode" } else { "Mode tout accepter" }
+                    if is_en { "Record / Replay" } else { "Enregistrer / Rejouer" }
                 }
                 p {
                     class: "text-xs text-[var(--text-tertiary)] mb-5",
                     if is_en {
-                        "When enabled, ALL tool calls are automatically approved without asking. Use with caution."
+                        "Record tool results during a session, then replay them instead of running the tools: reproducible runs and offline demos."
                     } else {
-                        "Quand active, TOUS les appels d'outils sont approuves automatiquement. A utiliser avec precaution."
+                        "Enregistre les resultats d'outils pendant une session, puis les rejoue au lieu d'executer les outils : executions reproductibles et demos hors ligne."
+                    }
+                }
+
+                div {
+                    class: "flex items-center gap-3",
+                    select {
+                        class: "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        value: "{replay_value}",
+                        onchange: move |e: Event<FormData>| {
+                            let mode = match e.value().as_str() {
+                                "record" => ToolReplayMode::Record("demo".to_string()),
+                                "replay" => match recordings.read().first() {
+                                    Some(name) => ToolReplayMode::Replay(name.clone()),
+                                    None => ToolReplayMode::Off,
+                                },
+                                _ => ToolReplayMode::Off,
+                            };
+                            let mut settings = app_state_replay.settings.write();
+                            settings.tool_replay = mode;
+                            if let Err(e) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", e);
+                            }
+                        },
+                        option { value: "off", if is_en { "Off" } else { "Desactive" } }
+                        option { value: "record", if is_en { "Record" } else { "Enregistrer" } }
+                        option {
+                            value: "replay",
+                            disabled: recordings.read().is_empty(),
+                            if is_en { "Replay" } else { "Rejouer" }
+                        }
+                    }
+                    match replay_mode.clone() {
+                        ToolReplayMode::Record(name) => rsx! {
+                            input {
+                                class: "flex-1 px-3 py-2 rounded-lg text-sm font-mono text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                                value: "{name}",
+                                placeholder: if is_en { "Recording name" } else { "Nom de l'enregistrement" },
+                                onchange: move |e: Event<FormData>| {
+                                    let name = sanitize_recording_name(&e.value());
+                                    if name.is_empty() {
+                                        return;
+                                    }
+                                    let mut settings = app_state_replay_name.settings.write();
+                                    settings.tool_replay = ToolReplayMode::Record(name);
+                                    if let Err(e) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", e);
+                                    }
+                                },
+                            }
+                        },
+                        ToolReplayMode::Replay(name) => rsx! {
+                            select {
+                                class: "flex-1 px-3 py-2 rounded-lg text-sm font-mono text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                                value: "{name}",
+                                onchange: move |e: Event<FormData>| {
+                                    let mut settings = app_state_replay_name.settings.write();
+                                    settings.tool_replay = ToolReplayMode::Replay(e.value());
+                                    if let Err(e) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", e);
+                                    }
+                                },
+                                for recording in recordings.read().iter() {
+                                    option { value: "{recording}", "{recording}" }
+                                }
+                            }
+                            button {
+                                class: "px-3 py-2 rounded-lg text-sm text-[var(--text-secondary)] hover:text-[var(--error)] hover:bg-[var(--bg-hover)] transition-colors",
+                                onclick: move |_| {
+                                    if let Err(e) = delete_recording(&name) {
+                                        tracing::error!("Failed to delete recording: {}", e);
+                                    }
+                                    recordings.set(list_recordings());
+                                    let mut settings = app_state_replay_delete.settings.write();
+                                    settings.tool_replay = ToolReplayMode::Off;
+                                    if let Err(e) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", e);
+                                    }
+                                },
+                                if is_en { "Delete" } else { "Supprimer" }
+                            }
+                        },
+                        ToolReplayMode::Off => rsx! {},
+                    }
+                }
+            }
+
+            // Persistent tool cache
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    if is_en { "Tool Result Cache" } else { "Cache des resultats d'outils" }
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    if is_en {
+                        "Reuse results of web_search (1h), web_fetch (24h) and pdf_read (7 days) across conversations."
+                    } else {
+                        "Reutilise les resultats de web_search (1h), web_fetch (24h) et pdf_read (7 jours) entre les conversations."
                     }
                 }
 
@@ -200,36 +437,165 @@ pub fn ToolsSettings() -> Element {
                     class: "flex items-center justify-between",
 
                     div {
-                        div {
-                            class: "text-sm font-medium text-[var(--text-primary)] flex items-center gap-2",
-                            if is_en { "Accept all tools" } else { "Tout accepter" }
-                            if auto_approve {
-                                span {
-                                    class: "px-1.5 py-0.5 rounded text-[10px] font-semibold uppercase",
-                                    style: "background: rgba(196,69,69,0.12); color: #C45B5B;",
-                                    if is_en { "DANGEROUS" } else { "DANGEREUX" }
+                        class: "flex items-center gap-3",
+                        button {
+                            class: "px-3 py-1.5 rounded-lg text-xs text-[var(--text-secondary)] border border-[var(--border-subtle)] hover:border-[var(--accent-primary)] transition-colors",
+                            onclick: move |_| {
+                                match clear_tool_cache() {
+                                    Ok(count) => cache_status.set(Some(if is_en {
+                                        format!("{} entries removed", count)
+                                    } else {
+                                        format!("{} entrees supprimees", count)
+                                    })),
+                                    Err(e) => {
+                                        tracing::error!("Failed to clear tool cache: {}", e);
+                                        cache_status.set(Some(e.to_string()));
+                                    }
                                 }
-                            }
+                            },
+                            if is_en { "Clear cache" } else { "Vider le cache" }
                         }
-                        div {
-                            class: "text-xs text-[var(--text-tertiary)] mt-0.5",
-                            if is_en { "Skip permission dialogs for all tools" } else { "Ignorer les dialogues de permission pour tous les outils" }
+                        if let Some(status) = cache_status() {
+                            span { class: "text-xs text-[var(--text-tertiary)]", "{status}" }
                         }
                     }
                     button {
                         onclick: move |_| {
-                            let mut settings = app_state_toggle.settings.write();
-                            settings.auto_approve_all_tools = !settings.auto_approve_all_tools;
+                            let mut settings = app_state_cache.settings.write();
+                            settings.persistent_tool_cache = !settings.persistent_tool_cache;
                             if let Err(e) = save_settings(&settings) {
                                 tracing::error!("Failed to save settings: {}", e);
                             }
                         },
-                        class: if auto_approve { "toggle-switch active" } else { "toggle-switch" },
+                        class: if persistent_cache { "toggle-switch active" } else { "toggle-switch" },
                         div { class: "toggle-switch-knob" }
                     }
                 }
             }
 
+            // Reflection policy
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    if is_en { "Reflection" } else { "Reflexion" }
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    if is_en {
+                        "When the agent is asked to step back and rethink its strategy after a tool call."
+                    } else {
+                        "Quand l'agent doit prendre du recul et revoir sa strategie apres un appel d'outil."
+                    }
+                }
+
+                div {
+                    class: "flex flex-col gap-4",
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-40",
+                            if is_en { "Reflect" } else { "Reflechir" }
+                        }
+                        select {
+                            class: "flex-1 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            value: "{trigger_value}",
+                            onchange: move |e: Event<FormData>| {
+                                let mut settings = app_state_trigger.settings.write();
+                                settings.reflection_policy.trigger = match e.value().as_str() {
+                                    "never" => ReflectionTrigger::Never,
+                                    "always" => ReflectionTrigger::Always,
+                                    _ => ReflectionTrigger::OnFailure,
+                                };
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            option { value: "never", if is_en { "Never" } else { "Jamais" } }
+                            option { value: "on_failure", if is_en { "After failures" } else { "Apres un echec" } }
+                            option { value: "always", if is_en { "After every tool call" } else { "Apres chaque outil" } }
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center gap-4",
+                        label {
+                            class: "text-sm text-[var(--text-secondary)] w-40",
+                            if is_en { "Max recoveries" } else { "Reprises max" }
+                        }
+                        input {
+                            r#type: "number",
+                            min: "0",
+                            max: "10",
+                            value: "{reflection.max_recoveries}",
+                            oninput: move |e| {
+                                if let Ok(value) = e.value().parse::<usize>() {
+                                    let mut settings = app_state_recoveries.settings.write();
+                                    settings.reflection_policy.max_recoveries = value.min(10);
+                                    if let Err(e) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", e);
+                                    }
+                                }
+                            },
+                            class: "w-24 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        }
+                    }
+
+                    div {
+                        class: "flex items-center justify-between",
+                        div {
+                            div {
+                                class: "text-sm font-medium text-[var(--text-primary)]",
+                                if is_en { "Reflect on long tasks" } else { "Reflechir sur les taches longues" }
+                            }
+                            div {
+                                class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                                if is_en { "Also reflect after successful calls past the iteration below" } else { "Reflechir aussi apres les succes au-dela de l'iteration ci-dessous" }
+                            }
+                        }
+                        button {
+                            onclick: move |_| {
+                                let mut settings = app_state_long_toggle.settings.write();
+                                settings.reflection_policy.reflect_on_long_tasks = !settings.reflection_policy.reflect_on_long_tasks;
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            },
+                            class: if reflection.reflect_on_long_tasks { "toggle-switch active" } else { "toggle-switch" },
+                            div { class: "toggle-switch-knob" }
+                        }
+                    }
+
+                    if reflection.reflect_on_long_tasks {
+                        div {
+                            class: "flex items-center gap-4",
+                            label {
+                                class: "text-sm text-[var(--text-secondary)] w-40",
+                                if is_en { "Long task from iteration" } else { "Tache longue des l'iteration" }
+                            }
+                            input {
+                                r#type: "number",
+                                min: "1",
+                                max: "50",
+                                value: "{reflection.long_task_iterations}",
+                                oninput: move |e| {
+                                    if let Ok(value) = e.value().parse::<usize>() {
+                                        let mut settings = app_state_long_iterations.settings.write();
+                                        settings.reflection_policy.long_task_iterations = value.clamp(1, 50);
+                                        if let Err(e) = save_settings(&settings) {
+                                            tracing::error!("Failed to save settings: {}", e);
+                                        }
+                                    }
+                                },
+                                class: "w-24 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            }
+                        }
+                    }
+                }
+            }
+
             // Allowlist — per-group and per-tool toggles
             if !auto_approve {
                 div {
@@ -0,0 +1,75 @@
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+
+/// Opt-in check for new releases on GitHub
+pub fn UpdateSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, enabled) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.check_for_updates)
+    };
+    // Result of the last "Check now"
+    let mut status = use_signal(|| None::<String>);
+    let mut checking = use_signal(|| false);
+    let mut app_state_toggle = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            div { class: "flex items-center justify-between",
+                div {
+                    h3 { class: "text-base font-semibold text-[var(--text-primary)]",
+                        if is_en { "Updates" } else { "Mises a jour" }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                        if is_en {
+                            "Check GitHub for a new release at launch and show its changelog. Only the release list is fetched; nothing about you or your conversations is sent. Version {env!(\"CARGO_PKG_VERSION\")}."
+                        } else {
+                            "Verifier au lancement si une nouvelle version est sortie sur GitHub et afficher ses nouveautes. Seule la liste des versions est recuperee ; rien sur vous ou vos conversations n'est envoye. Version {env!(\"CARGO_PKG_VERSION\")}."
+                        }
+                    }
+                }
+                button {
+                    class: if enabled { "toggle-switch active" } else { "toggle-switch" },
+                    onclick: move |_| {
+                        let mut settings = app_state_toggle.settings.write();
+                        settings.check_for_updates = !enabled;
+                        if let Err(e) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", e);
+                        }
+                    },
+                    div { class: "toggle-switch-knob" }
+                }
+            }
+            div { class: "flex items-center gap-3 mt-4",
+                button {
+                    class: "btn-ghost text-xs",
+                    disabled: checking(),
+                    onclick: move |_| {
+                        let app_state = app_state.clone();
+                        checking.set(true);
+                        spawn(async move {
+                            let message = match app_state.check_for_update().await {
+                                Ok(true) => if is_en { "A new version is available." } else { "Une nouvelle version est disponible." }.to_string(),
+                                Ok(false) => if is_en { "ClawRS is up to date." } else { "ClawRS est a jour." }.to_string(),
+                                Err(e) => e.to_string(),
+                            };
+                            status.set(Some(message));
+                            checking.set(false);
+                        });
+                    },
+                    if checking() {
+                        if is_en { "Checking…" } else { "Verification…" }
+                    } else if is_en {
+                        "Check now"
+                    } else {
+                        "Verifier maintenant"
+                    }
+                }
+                if let Some(message) = status() {
+                    span { class: "text-xs text-[var(--text-secondary)]", "{message}" }
+                }
+            }
+        }
+    }
+}
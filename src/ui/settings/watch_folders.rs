@@ -0,0 +1,228 @@
+use crate::agent::watch_folders::{self, WatchFolder, WatchOutput, WatchRun, OUTPUT_SUFFIX, WORKFLOW_TEMPLATES};
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Change the watched folders, save them and restart the watch
+fn update_folders(app_state: &mut AppState, change: impl FnOnce(&mut Vec<WatchFolder>)) {
+    {
+        let mut settings = app_state.settings.write();
+        change(&mut settings.watch_folders);
+        if let Err(e) = save_settings(&settings) {
+            tracing::error!("Failed to save settings: {}", e);
+        }
+    }
+    app_state.watch_folders();
+}
+
+fn run_line(run: &WatchRun, is_en: bool) -> String {
+    let name = run.file.file_name().unwrap_or_default().to_string_lossy();
+    let time = run.finished_at.with_timezone(&chrono::Local).format("%H:%M");
+    match &run.result {
+        Ok(output) => format!("{} {} → {}", time, name, output),
+        Err(e) if is_en => format!("{} {} failed: {}", time, name, e),
+        Err(e) => format!("{} {} en echec : {}", time, name, e),
+    }
+}
+
+/// Watched folders card: a workflow run on each file dropped in a folder
+pub fn WatchFolderSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    let personas: Vec<(String, String)> = settings
+        .personas()
+        .into_iter()
+        .map(|p| (p.id.clone(), p.label(is_en)))
+        .collect();
+    let folders = settings.watch_folders.clone();
+
+    let mut draft_path = use_signal(String::new);
+    let mut draft_template = use_signal(|| WORKFLOW_TEMPLATES[0].0.to_string());
+    let mut error = use_signal(|| false);
+    let mut runs = use_signal(watch_folders::recent_runs);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            let latest = watch_folders::recent_runs();
+            if *runs.peek() != latest {
+                runs.set(latest);
+            }
+        }
+    });
+    let mut app_state_add = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "📥 Watched folders" } else { "📥 Dossiers surveilles" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Each new file dropped in a watched folder (text or PDF) is processed with the loaded model: the answer is written next to it as {OUTPUT_SUFFIX} or saved as a conversation, and a notification is shown."
+                } else {
+                    "Chaque nouveau fichier depose dans un dossier surveille (texte ou PDF) est traite avec le modele charge : la reponse est ecrite a cote en {OUTPUT_SUFFIX} ou enregistree comme conversation, et une notification s'affiche."
+                }
+            }
+
+            div { class: "space-y-4",
+                for (idx, folder) in folders.into_iter().enumerate() {
+                    {
+                        let mut app_state_toggle = app_state.clone();
+                        let mut app_state_output = app_state.clone();
+                        let mut app_state_persona = app_state.clone();
+                        let mut app_state_prompt = app_state.clone();
+                        let mut app_state_remove = app_state.clone();
+                        let path = folder.path.display().to_string();
+                        let persona = folder.persona.clone().unwrap_or_default();
+                        let output = match folder.output {
+                            WatchOutput::NextToFile => "next_to_file",
+                            WatchOutput::Conversation => "conversation",
+                        };
+                        rsx! {
+                            div {
+                                key: "{idx}-{path}",
+                                class: "pb-4 border-b border-[var(--border-subtle)] last:border-0 last:pb-0 space-y-2",
+                                div { class: "flex items-center justify-between gap-3",
+                                    span { class: "text-sm font-mono text-[var(--text-primary)] truncate", title: "{path}", "{path}" }
+                                    div { class: "flex items-center gap-2 shrink-0",
+                                        button {
+                                            class: if folder.enabled { "toggle-switch active" } else { "toggle-switch" },
+                                            onclick: move |_| update_folders(&mut app_state_toggle, |folders| {
+                                                if let Some(folder) = folders.get_mut(idx) {
+                                                    folder.enabled = !folder.enabled;
+                                                }
+                                            }),
+                                            div { class: "toggle-switch-knob" }
+                                        }
+                                        button {
+                                            class: "w-7 h-7 rounded-md text-[var(--text-tertiary)] hover:text-[var(--error)] hover:bg-white/[0.06]",
+                                            title: if is_en { "Stop watching" } else { "Ne plus surveiller" },
+                                            onclick: move |_| update_folders(&mut app_state_remove, |folders| {
+                                                if idx < folders.len() {
+                                                    folders.remove(idx);
+                                                }
+                                            }),
+                                            "✕"
+                                        }
+                                    }
+                                }
+                                textarea {
+                                    class: "{INPUT_CLASS} w-full min-h-[4rem] resize-y",
+                                    value: "{folder.prompt}",
+                                    onchange: move |e: Event<FormData>| {
+                                        let prompt = e.value();
+                                        update_folders(&mut app_state_prompt, |folders| {
+                                            if let Some(folder) = folders.get_mut(idx) {
+                                                folder.prompt = prompt;
+                                            }
+                                        });
+                                    },
+                                }
+                                div { class: "flex items-center gap-2",
+                                    select {
+                                        class: "{INPUT_CLASS} flex-1",
+                                        value: "{persona}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let value = e.value();
+                                            update_folders(&mut app_state_persona, |folders| {
+                                                if let Some(folder) = folders.get_mut(idx) {
+                                                    folder.persona = Some(value).filter(|v| !v.is_empty());
+                                                }
+                                            });
+                                        },
+                                        option { value: "", if is_en { "No persona" } else { "Sans persona" } }
+                                        for (id, label) in personas.iter() {
+                                            option { value: "{id}", selected: *id == persona, "{label}" }
+                                        }
+                                    }
+                                    select {
+                                        class: "{INPUT_CLASS} flex-1",
+                                        value: "{output}",
+                                        onchange: move |e: Event<FormData>| {
+                                            let output = if e.value() == "conversation" {
+                                                WatchOutput::Conversation
+                                            } else {
+                                                WatchOutput::NextToFile
+                                            };
+                                            update_folders(&mut app_state_output, |folders| {
+                                                if let Some(folder) = folders.get_mut(idx) {
+                                                    folder.output = output;
+                                                }
+                                            });
+                                        },
+                                        option { value: "next_to_file", selected: output == "next_to_file",
+                                            if is_en { "Write next to the file" } else { "Ecrire a cote du fichier" }
+                                        }
+                                        option { value: "conversation", selected: output == "conversation",
+                                            if is_en { "Save as a conversation" } else { "Enregistrer comme conversation" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex items-center gap-2 mt-4",
+                input {
+                    class: "{INPUT_CLASS} flex-1 font-mono",
+                    placeholder: if is_en { "/path/to/inbox" } else { "/chemin/du/dossier" },
+                    value: "{draft_path}",
+                    oninput: move |e| {
+                        draft_path.set(e.value());
+                        error.set(false);
+                    },
+                }
+                select {
+                    class: INPUT_CLASS,
+                    value: "{draft_template}",
+                    onchange: move |e: Event<FormData>| draft_template.set(e.value()),
+                    for (id, label_en, label_fr, _) in WORKFLOW_TEMPLATES.iter() {
+                        option { value: "{id}", selected: *id == draft_template(), if is_en { "{label_en}" } else { "{label_fr}" } }
+                    }
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| {
+                        let path = PathBuf::from(draft_path.read().trim());
+                        if !path.is_dir() {
+                            return error.set(true);
+                        }
+                        let prompt = WORKFLOW_TEMPLATES
+                            .iter()
+                            .find(|(id, ..)| *id == draft_template())
+                            .map(|(.., prompt)| prompt.to_string())
+                            .unwrap_or_default();
+                        update_folders(&mut app_state_add, |folders| folders.push(WatchFolder::new(path, prompt)));
+                        draft_path.set(String::new());
+                    },
+                    if is_en { "Watch" } else { "Surveiller" }
+                }
+            }
+            if error() {
+                p { class: "text-xs text-[var(--error)] mt-2",
+                    if is_en { "This folder doesn't exist." } else { "Ce dossier n'existe pas." }
+                }
+            }
+
+            if !runs.read().is_empty() {
+                div { class: "mt-4 space-y-1",
+                    for run in runs.read().iter() {
+                        p {
+                            class: if run.result.is_ok() { "text-xs text-[var(--text-tertiary)] truncate" } else { "text-xs text-[var(--error)] truncate" },
+                            "{run_line(run, is_en)}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
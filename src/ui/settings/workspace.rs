@@ -0,0 +1,133 @@
+use crate::agent::skills::runtime::set_skill_workspace;
+use crate::agent::workspace_index::{self, IndexStatus};
+use crate::app::AppState;
+use crate::storage::settings::save_settings;
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+const INPUT_CLASS: &str = "px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]";
+const BUTTON_CLASS: &str = "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors";
+
+/// Persist the workspace folder, `None` to clear it
+fn set_workspace(app_state: &mut AppState, workspace: Option<PathBuf>) {
+    {
+        let mut settings = app_state.settings.write();
+        set_skill_workspace(workspace.clone());
+        settings.workspace = workspace;
+        if let Err(e) = save_settings(&settings) {
+            tracing::error!("Failed to save settings: {}", e);
+        }
+    }
+    app_state.index_workspace();
+}
+
+/// One-line summary of the index
+fn status_line(status: &IndexStatus, is_en: bool) -> String {
+    let mut line = if is_en {
+        format!("{} files, {} symbols indexed", status.files, status.symbols)
+    } else {
+        format!("{} fichiers, {} symboles indexes", status.files, status.symbols)
+    };
+    if status.has_embedding_model {
+        line += &if is_en {
+            format!(", {}/{} passages embedded", status.embedded, status.chunks)
+        } else {
+            format!(", {}/{} passages vectorises", status.embedded, status.chunks)
+        };
+    } else {
+        line += if is_en {
+            " (no embedding model: symbol search only)"
+        } else {
+            " (aucun modele d'embedding : recherche par symboles uniquement)"
+        };
+    }
+    if status.busy {
+        line += if is_en { " - indexing..." } else { " - indexation..." };
+    }
+    line
+}
+
+/// Workspace card: the project folder mapped into the agent's prompt
+pub fn WorkspaceSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let current = app_state.settings.read().workspace.clone();
+    let mut draft = use_signal(|| {
+        current
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default()
+    });
+    let mut error = use_signal(|| false);
+    let mut index_status = use_signal(workspace_index::status);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            let status = workspace_index::status();
+            if *index_status.peek() != status {
+                index_status.set(status);
+            }
+        }
+    });
+    let status_text = index_status.read().as_ref().map(|s| status_line(s, is_en));
+    let mut app_state_save = app_state.clone();
+    let mut app_state_clear = app_state.clone();
+
+    rsx! {
+        div { class: "p-5 rounded-2xl glass-md",
+            h3 {
+                class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                if is_en { "📁 Workspace" } else { "📁 Espace de travail" }
+            }
+            p {
+                class: "text-xs text-[var(--text-tertiary)] mb-4",
+                if is_en {
+                    "Project folder the agent works in. A condensed map of it (languages, key files, entry points, tree) is added to the system prompt of each run. It is indexed in the background and kept up to date as files change, for the repo_map and workspace_search tools."
+                } else {
+                    "Dossier du projet sur lequel travaille l'agent. Une carte condensee (langages, fichiers cles, points d'entree, arborescence) est ajoutee au prompt systeme de chaque execution. Il est indexe en arriere-plan et tenu a jour quand les fichiers changent, pour les outils repo_map et workspace_search."
+                }
+            }
+            div { class: "flex items-center gap-2",
+                input {
+                    class: "{INPUT_CLASS} flex-1 font-mono",
+                    placeholder: if is_en { "/path/to/project" } else { "/chemin/du/projet" },
+                    value: "{draft}",
+                    oninput: move |e| {
+                        draft.set(e.value());
+                        error.set(false);
+                    },
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| {
+                        let path = PathBuf::from(draft.read().trim());
+                        if path.is_dir() {
+                            set_workspace(&mut app_state_save, Some(path));
+                        } else {
+                            error.set(true);
+                        }
+                    },
+                    if is_en { "Save" } else { "Enregistrer" }
+                }
+                if current.is_some() {
+                    button {
+                        class: BUTTON_CLASS,
+                        onclick: move |_| {
+                            draft.set(String::new());
+                            set_workspace(&mut app_state_clear, None);
+                        },
+                        if is_en { "Clear" } else { "Retirer" }
+                    }
+                }
+            }
+            if let Some(text) = status_text {
+                p { class: "text-xs text-[var(--text-tertiary)] mt-2", "{text}" }
+            }
+            if error() {
+                p { class: "text-xs text-[var(--error)] mt-2",
+                    if is_en { "This folder doesn't exist." } else { "Ce dossier n'existe pas." }
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,108 @@
+use std::collections::{HashMap, HashSet};
+
 use dioxus::prelude::*;
 
 use crate::app::AppState;
 use crate::storage::conversations::{
-    delete_conversation, list_conversations, save_conversation, Conversation,
+    clear_conversation_messages, duplicate_conversation, empty_trash, list_conversations,
+    list_trash, parse_tags, purge_expired_trash, purge_trashed, restore_conversation,
+    save_conversation, trash_conversations, Conversation, TrashedConversation, TRASH_RETENTION_DAYS,
 };
 
+/// A destructive action waiting for confirmation
+#[derive(Clone, PartialEq)]
+enum PendingAction {
+    /// Move these conversations to the trash
+    Trash(Vec<String>),
+    /// Delete the messages of a conversation, keep the conversation
+    ClearMessages(String),
+    /// Permanently delete a trashed conversation
+    Purge(String),
+    EmptyTrash,
+}
+
+/// Reload the conversation list and the trash from disk
+fn refresh(app_state: &mut AppState, trash: &mut Signal<Vec<TrashedConversation>>) {
+    match list_conversations() {
+        Ok(conversations) => app_state.conversations.set(conversations),
+        Err(e) => tracing::error!("Failed to load conversations: {}", e),
+    }
+    match list_trash() {
+        Ok(items) => trash.set(items),
+        Err(e) => tracing::error!("Failed to load trash: {}", e),
+    }
+}
+
+/// Tags used by the conversations, most used first
+fn tags_by_use(conversations: &[Conversation]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for tag in conversations.iter().flat_map(|c| &c.tags) {
+        *counts.entry(tag.as_str()).or_default() += 1;
+    }
+    let mut tags: Vec<(String, usize)> = counts.into_iter().map(|(tag, n)| (tag.to_string(), n)).collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    tags
+}
+
+/// Run a confirmed action
+fn apply(action: PendingAction, app_state: &mut AppState) {
+    let current_id = app_state
+        .current_conversation
+        .read()
+        .as_ref()
+        .map(|conv| conv.id.clone());
+
+    match action {
+        PendingAction::Trash(ids) => {
+            if let Err(e) = trash_conversations(&ids) {
+                tracing::error!("Failed to move conversations to trash: {}", e);
+            }
+            if current_id.is_some_and(|id| ids.contains(&id)) {
+                app_state.current_conversation.set(None);
+            }
+        }
+        PendingAction::ClearMessages(id) => match clear_conversation_messages(&id) {
+            Ok(conversation) => {
+                if current_id.as_deref() == Some(id.as_str()) {
+                    app_state.current_conversation.set(Some(conversation));
+                }
+            }
+            Err(e) => tracing::error!("Failed to clear conversation messages: {}", e),
+        },
+        PendingAction::Purge(id) => {
+            if let Err(e) = purge_trashed(&id) {
+                tracing::error!("Failed to delete conversation: {}", e);
+            }
+        }
+        PendingAction::EmptyTrash => {
+            if let Err(e) = empty_trash() {
+                tracing::error!("Failed to empty trash: {}", e);
+            }
+        }
+    }
+}
+
 #[component]
 pub fn ConversationList() -> Element {
     let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut select_mode = use_signal(|| false);
+    let mut selected = use_signal(HashSet::<String>::new);
+    let mut show_trash = use_signal(|| false);
+    let trash = use_signal(Vec::<TrashedConversation>::new);
+    let mut pending = use_signal(|| None::<PendingAction>);
+    let mut tag_filter = use_signal(|| None::<String>);
+    // Conversation whose tags are being edited
+    let mut editing_tags = use_signal(|| None::<String>);
 
     {
         let mut app_state = app_state.clone();
-        use_effect(move || match list_conversations() {
-            Ok(conversations) => app_state.conversations.set(conversations),
-            Err(e) => tracing::error!("Failed to load conversations: {}", e),
+        let mut trash = trash;
+        use_effect(move || {
+            if let Err(e) = purge_expired_trash() {
+                tracing::warn!("Failed to purge expired trash: {}", e);
+            }
+            refresh(&mut app_state, &mut trash);
         });
     }
 
@@ -34,114 +123,495 @@ pub fn ConversationList() -> Element {
     };
 
     let conversations = app_state.conversations.read().clone();
+    let all_tags = tags_by_use(&conversations);
+    // A filter on a tag no conversation has anymore shows everything
+    let active_tag = tag_filter().filter(|tag| all_tags.iter().any(|(t, _)| t == tag));
+    let conversations: Vec<Conversation> = conversations
+        .into_iter()
+        .filter(|c| active_tag.as_ref().map_or(true, |tag| c.tags.contains(tag)))
+        .collect();
+    let visible_ids: Vec<String> = conversations.iter().map(|c| c.id.clone()).collect();
+    let trashed = trash.read().clone();
+    let trash_count = trashed.len();
+    let selected_count = selected.read().len();
     let selected_id = app_state
         .current_conversation
         .read()
         .as_ref()
         .map(|conv| conv.id.clone());
 
+    let icon_button = "p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-colors";
+
     rsx! {
         div {
-            class: "flex-1 overflow-y-auto p-2 space-y-1 scrollbar-thin",
+            class: "flex-1 flex flex-col min-h-0",
 
-            if conversations.is_empty() {
+            // Section header: title, select mode and trash toggles
+            div {
+                class: "flex items-center justify-between px-4 pt-3 pb-1 select-none",
+                span {
+                    class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold opacity-60",
+                    if show_trash() {
+                        if is_en { "Trash" } else { "Corbeille" }
+                    } else if is_en {
+                        "Recent"
+                    } else {
+                        "Recents"
+                    }
+                }
                 div {
-                    class: "flex flex-col items-center justify-center py-10 text-[var(--text-tertiary)] gap-2 opacity-50",
-                    svg { width: "24", height: "24", view_box: "0 0 24 24", fill: "none", stroke: "currentColor", stroke_width: "1.5", stroke_dasharray: "4 4", circle { cx: "12", cy: "12", r: "10" } }
-                    span { class: "text-xs font-medium", "No recent chats" }
+                    class: "flex items-center gap-1",
+                    if !show_trash() && !conversations.is_empty() {
+                        button {
+                            class: if select_mode() { "p-1 rounded-md bg-white/[0.08] text-[var(--accent-primary)]" } else { icon_button },
+                            title: if is_en { "Select" } else { "Selectionner" },
+                            onclick: move |_| {
+                                select_mode.toggle();
+                                selected.write().clear();
+                            },
+                            svg {
+                                width: "13", height: "13", view_box: "0 0 24 24", fill: "none",
+                                stroke: "currentColor", stroke_width: "2", stroke_linecap: "round", stroke_linejoin: "round",
+                                polyline { points: "9 11 12 14 22 4" }
+                                path { d: "M21 12v7a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V5a2 2 0 0 1 2-2h11" }
+                            }
+                        }
+                    }
+                    button {
+                        class: if show_trash() { "flex items-center gap-1 p-1 rounded-md bg-white/[0.08] text-[var(--accent-primary)]" } else { "flex items-center gap-1 {icon_button}" },
+                        title: if is_en { "Trash" } else { "Corbeille" },
+                        onclick: move |_| {
+                            show_trash.toggle();
+                            select_mode.set(false);
+                            selected.write().clear();
+                        },
+                        svg {
+                            width: "13", height: "13", view_box: "0 0 24 24", fill: "none",
+                            stroke: "currentColor", stroke_width: "2", stroke_linecap: "round", stroke_linejoin: "round",
+                            polyline { points: "3 6 5 6 21 6" }
+                            path { d: "M19 6l-1 14a2 2 0 0 1-2 2H8a2 2 0 0 1-2-2L5 6m3 0V4a2 2 0 0 1 2-2h4a2 2 0 0 1 2 2v2" }
+                        }
+                        if trash_count > 0 {
+                            span { class: "text-[10px]", "{trash_count}" }
+                        }
+                    }
                 }
-            } else {
+            }
+
+            // Tag filters
+            if !show_trash() && !all_tags.is_empty() {
                 div {
-                    class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold px-3 py-2 select-none opacity-60",
-                    "Recent"
+                    class: "flex items-center gap-1 px-3 pb-1 overflow-x-auto scrollbar-thin",
+                    for (tag, count) in all_tags.into_iter() {
+                        {
+                            let active = active_tag.as_deref() == Some(tag.as_str());
+                            let tag_for_filter = tag.clone();
+                            rsx! {
+                                button {
+                                    key: "{tag}",
+                                    class: if active { "shrink-0 px-2 py-0.5 rounded-full text-[10px] bg-[var(--accent-primary)]/20 text-[var(--accent-primary)]" } else { "shrink-0 px-2 py-0.5 rounded-full text-[10px] bg-white/[0.04] text-[var(--text-tertiary)] hover:text-[var(--text-primary)]" },
+                                    title: "{count} conversation(s)",
+                                    onclick: move |_| tag_filter.set(if active { None } else { Some(tag_for_filter.clone()) }),
+                                    "#{tag}"
+                                }
+                            }
+                        }
+                    }
                 }
+            }
 
-                {conversations.into_iter().map(|conversation| {
-                    let is_selected = selected_id
-                        .as_ref()
-                        .map(|id| id == &conversation.id)
-                        .unwrap_or(false);
+            div {
+                class: "flex-1 overflow-y-auto p-2 space-y-1 scrollbar-thin",
 
-                    let row_class = if is_selected {
-                        "group flex items-center gap-2.5 px-3 py-2 rounded-lg bg-white/[0.08] border-l-2 border-[var(--accent-primary)] text-[var(--text-primary)] cursor-pointer transition-all"
+                if show_trash() {
+                    if trashed.is_empty() {
+                        div {
+                            class: "flex flex-col items-center justify-center py-10 text-[var(--text-tertiary)] gap-2 opacity-50",
+                            span { class: "text-xs font-medium", if is_en { "Trash is empty" } else { "La corbeille est vide" } }
+                        }
                     } else {
-                        "group flex items-center gap-2.5 px-3 py-2 rounded-lg hover:bg-white/[0.05] border-l-2 border-transparent text-[var(--text-secondary)] hover:text-[var(--text-primary)] cursor-pointer transition-all"
-                    };
+                        p {
+                            class: "px-3 pb-1 text-[11px] text-[var(--text-tertiary)]",
+                            if is_en {
+                                "Deleted conversations are kept for {TRASH_RETENTION_DAYS} days."
+                            } else {
+                                "Les conversations supprimees sont conservees {TRASH_RETENTION_DAYS} jours."
+                            }
+                        }
+                        for item in trashed.into_iter() {
+                            TrashRow { key: "{item.conversation.id}", item: item.clone(), trash, pending }
+                        }
+                        div {
+                            class: "px-1 pt-2",
+                            button {
+                                class: "w-full px-3 py-2 text-xs rounded-lg text-[var(--text-error)] hover:bg-white/[0.05] transition-colors",
+                                onclick: move |_| pending.set(Some(PendingAction::EmptyTrash)),
+                                if is_en { "Empty trash" } else { "Vider la corbeille" }
+                            }
+                        }
+                    }
+                } else if conversations.is_empty() {
+                    div {
+                        class: "flex flex-col items-center justify-center py-10 text-[var(--text-tertiary)] gap-2 opacity-50",
+                        svg { width: "24", height: "24", view_box: "0 0 24 24", fill: "none", stroke: "currentColor", stroke_width: "1.5", stroke_dasharray: "4 4", circle { cx: "12", cy: "12", r: "10" } }
+                        span { class: "text-xs font-medium", "No recent chats" }
+                    }
+                } else {
+                    {conversations.into_iter().map(|conversation| {
+                        let is_selected = selected_id
+                            .as_ref()
+                            .map(|id| id == &conversation.id)
+                            .unwrap_or(false);
+                        let is_checked = selected.read().contains(&conversation.id);
 
-                    let conversation_for_select = conversation.clone();
-                    let conversation_id = conversation.id.clone();
-                    let mut current_conversation_signal = app_state.current_conversation.clone();
-                    let mut conversations_signal = app_state.conversations.clone();
+                        let row_class = if is_selected {
+                            "group flex items-center gap-2.5 px-3 py-2 rounded-lg bg-white/[0.08] border-l-2 border-[var(--accent-primary)] text-[var(--text-primary)] cursor-pointer transition-all"
+                        } else {
+                            "group flex items-center gap-2.5 px-3 py-2 rounded-lg hover:bg-white/[0.05] border-l-2 border-transparent text-[var(--text-secondary)] hover:text-[var(--text-primary)] cursor-pointer transition-all"
+                        };
 
-                    rsx! {
-                        div {
-                            key: "{conversation.id}",
-                            class: "px-1",
-                            onclick: move |_| {
-                                current_conversation_signal.set(Some(conversation_for_select.clone()));
-                            },
+                        let conversation_for_select = conversation.clone();
+                        let conversation_id = conversation.id.clone();
+                        let id_for_toggle = conversation.id.clone();
+                        let id_for_clear = conversation.id.clone();
+                        let conversation_for_duplicate = conversation.clone();
+                        let id_for_tags = conversation.id.clone();
+                        let id_for_tag_input = conversation.id.clone();
+                        let editing = editing_tags().as_deref() == Some(conversation.id.as_str());
+                        let tags_label = conversation.tags.iter().map(|t| format!("#{t}")).collect::<Vec<_>>().join(" ");
+                        let tags_value = conversation.tags.join(", ");
+                        let app_state_tags = app_state.clone();
+                        let mut app_state_duplicate = app_state.clone();
+                        let mut trash_duplicate = trash;
+                        let mut current_conversation_signal = app_state.current_conversation.clone();
 
+                        rsx! {
                             div {
-                                class: row_class,
-                                // Icon
-                                div {
-                                    class: "shrink-0 " .to_string() + if is_selected { "text-[var(--accent-primary)]" } else { "text-[var(--text-tertiary)] group-hover:text-[var(--text-secondary)]" },
-                                    svg {
-                                        width: "14",
-                                        height: "14",
-                                        view_box: "0 0 24 24",
-                                        fill: "none",
-                                        stroke: "currentColor",
-                                        stroke_width: "2",
-                                        stroke_linecap: "round",
-                                        stroke_linejoin: "round",
-                                        path { d: "M21 15a2 2 0 0 1-2 2H7l-4 4V5a2 2 0 0 1 2-2h14a2 2 0 0 1 2 2z" }
+                                key: "{conversation.id}",
+                                class: "px-1",
+                                onclick: move |_| {
+                                    if select_mode() {
+                                        let mut set = selected.write();
+                                        if !set.remove(&id_for_toggle) {
+                                            set.insert(id_for_toggle.clone());
+                                        }
+                                    } else {
+                                        current_conversation_signal.set(Some(conversation_for_select.clone()));
                                     }
-                                }
+                                },
 
-                                // Title
                                 div {
-                                    class: "truncate flex-1 text-sm",
-                                    "{conversation.title}"
-                                }
+                                    class: row_class,
+                                    if select_mode() {
+                                        input {
+                                            r#type: "checkbox",
+                                            class: "shrink-0 accent-[var(--accent-primary)] pointer-events-none",
+                                            checked: is_checked,
+                                        }
+                                    } else {
+                                        // Icon
+                                        div {
+                                            class: "shrink-0 " .to_string() + if is_selected { "text-[var(--accent-primary)]" } else { "text-[var(--text-tertiary)] group-hover:text-[var(--text-secondary)]" },
+                                            svg {
+                                                width: "14",
+                                                height: "14",
+                                                view_box: "0 0 24 24",
+                                                fill: "none",
+                                                stroke: "currentColor",
+                                                stroke_width: "2",
+                                                stroke_linecap: "round",
+                                                stroke_linejoin: "round",
+                                                path { d: "M21 15a2 2 0 0 1-2 2H7l-4 4V5a2 2 0 0 1 2-2h14a2 2 0 0 1 2 2z" }
+                                            }
+                                        }
+                                    }
 
-                                button {
-                                    class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
-                                    title: if app_state.settings.read().language == "en" { "Delete conversation" } else { "Supprimer la conversation" },
-                                    onclick: move |evt| {
-                                        evt.stop_propagation();
-                                        if let Err(e) = delete_conversation(&conversation_id) {
-                                            tracing::error!("Failed to delete conversation: {}", e);
+                                    // Title and tags
+                                    div {
+                                        class: "flex-1 min-w-0",
+                                        div { class: "truncate text-sm", "{conversation.title}" }
+                                        if !tags_label.is_empty() {
+                                            div { class: "truncate text-[10px] text-[var(--text-tertiary)]", "{tags_label}" }
+                                        }
+                                    }
+
+                                    if !select_mode() {
+                                        button {
+                                            class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] text-[11px] leading-none",
+                                            title: if is_en { "Edit tags" } else { "Modifier les tags" },
+                                            onclick: move |evt| {
+                                                evt.stop_propagation();
+                                                editing_tags.set(if editing { None } else { Some(id_for_tags.clone()) });
+                                            },
+                                            "#"
                                         }
-                                        let should_clear = current_conversation_signal
-                                            .read()
-                                            .as_ref()
-                                            .map(|conv| conv.id == conversation_id)
-                                            .unwrap_or(false);
-                                        if should_clear {
-                                            current_conversation_signal.set(None);
+                                        button {
+                                            class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                            title: if is_en { "Duplicate" } else { "Dupliquer" },
+                                            onclick: move |evt| {
+                                                evt.stop_propagation();
+                                                match duplicate_conversation(&conversation_for_duplicate, None) {
+                                                    Ok(copy) => {
+                                                        app_state_duplicate.current_conversation.set(Some(copy));
+                                                        refresh(&mut app_state_duplicate, &mut trash_duplicate);
+                                                    }
+                                                    Err(e) => tracing::error!("Failed to duplicate conversation: {}", e),
+                                                }
+                                            },
+                                            svg {
+                                                width: "12", height: "12", view_box: "0 0 24 24", fill: "none",
+                                                stroke: "currentColor", stroke_width: "2", stroke_linecap: "round", stroke_linejoin: "round",
+                                                rect { x: "9", y: "9", width: "13", height: "13", rx: "2", ry: "2" }
+                                                path { d: "M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1" }
+                                            }
                                         }
-                                        if let Ok(conversations) = list_conversations() {
-                                            conversations_signal.set(conversations);
+                                        button {
+                                            class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                            title: if is_en { "Delete all messages" } else { "Effacer les messages" },
+                                            onclick: move |evt| {
+                                                evt.stop_propagation();
+                                                pending.set(Some(PendingAction::ClearMessages(id_for_clear.clone())));
+                                            },
+                                            svg {
+                                                width: "12", height: "12", view_box: "0 0 24 24", fill: "none",
+                                                stroke: "currentColor", stroke_width: "2", stroke_linecap: "round", stroke_linejoin: "round",
+                                                path { d: "M20 20H7L3 16l10-10 7 7-3.5 3.5" }
+                                                line { x1: "9", y1: "10", x2: "14", y2: "15" }
+                                            }
                                         }
-                                    },
-                                    svg {
-                                        width: "12",
-                                        height: "12",
-                                        view_box: "0 0 24 24",
-                                        fill: "none",
-                                        stroke: "currentColor",
-                                        stroke_width: "2",
-                                        stroke_linecap: "round",
-                                        stroke_linejoin: "round",
-                                        line { x1: "18", y1: "6", x2: "6", y2: "18" }
-                                        line { x1: "6", y1: "6", x2: "18", y2: "18" }
+                                        button {
+                                            class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
+                                            title: if is_en { "Delete conversation" } else { "Supprimer la conversation" },
+                                            onclick: move |evt| {
+                                                evt.stop_propagation();
+                                                pending.set(Some(PendingAction::Trash(vec![conversation_id.clone()])));
+                                            },
+                                            svg {
+                                                width: "12",
+                                                height: "12",
+                                                view_box: "0 0 24 24",
+                                                fill: "none",
+                                                stroke: "currentColor",
+                                                stroke_width: "2",
+                                                stroke_linecap: "round",
+                                                stroke_linejoin: "round",
+                                                line { x1: "18", y1: "6", x2: "6", y2: "18" }
+                                                line { x1: "6", y1: "6", x2: "18", y2: "18" }
+                                            }
+                                        }
+                                    }
+                                }
+
+                                if editing {
+                                    input {
+                                        class: "w-full mt-1 px-2 py-1 rounded-md text-[11px] bg-transparent border border-[var(--border-subtle)] text-[var(--text-secondary)] focus:outline-none focus:border-[var(--accent-primary)]",
+                                        placeholder: if is_en { "tags, comma-separated" } else { "tags separes par des virgules" },
+                                        initial_value: tags_value,
+                                        autofocus: true,
+                                        onclick: move |evt| evt.stop_propagation(),
+                                        onkeydown: move |evt: KeyboardEvent| {
+                                            if evt.key() == Key::Escape {
+                                                editing_tags.set(None);
+                                            }
+                                        },
+                                        onchange: move |e: Event<FormData>| {
+                                            app_state_tags.set_conversation_tags(&id_for_tag_input, parse_tags(&e.value()));
+                                            editing_tags.set(None);
+                                        },
                                     }
                                 }
                             }
                         }
+                    })}
+                }
+            }
+
+            // Bulk actions for the selection
+            if select_mode() && !show_trash() {
+                {
+                    let all_ids = visible_ids.clone();
+                    rsx! {
+                        div {
+                            class: "flex items-center gap-2 px-3 py-2 border-t border-[var(--border-subtle)]",
+                            button {
+                                class: "text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)]",
+                                onclick: move |_| {
+                                    let mut set = selected.write();
+                                    if set.len() == all_ids.len() {
+                                        set.clear();
+                                    } else {
+                                        set.extend(all_ids.iter().cloned());
+                                    }
+                                },
+                                if is_en { "All" } else { "Tout" }
+                            }
+                            div { class: "flex-1" }
+                            button {
+                                class: "px-3 py-1.5 text-xs font-medium rounded-lg text-[var(--text-error)] hover:bg-white/[0.06] disabled:opacity-40",
+                                disabled: selected_count == 0,
+                                onclick: move |_| {
+                                    let ids: Vec<String> = selected.read().iter().cloned().collect();
+                                    pending.set(Some(PendingAction::Trash(ids)));
+                                },
+                                if is_en { "Delete ({selected_count})" } else { "Supprimer ({selected_count})" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(action) = pending() {
+                ConfirmDialog {
+                    action,
+                    trash_count,
+                    on_cancel: move |_| pending.set(None),
+                    on_confirm: {
+                        let app_state = app_state.clone();
+                        let mut trash = trash;
+                        move |action: PendingAction| {
+                            let mut app_state = app_state.clone();
+                            apply(action, &mut app_state);
+                            refresh(&mut app_state, &mut trash);
+                            pending.set(None);
+                            selected.write().clear();
+                            select_mode.set(false);
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// A conversation in the trash, with restore and delete-forever actions
+#[component]
+fn TrashRow(
+    item: TrashedConversation,
+    trash: Signal<Vec<TrashedConversation>>,
+    pending: Signal<Option<PendingAction>>,
+) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let days = item.days_remaining(chrono::Utc::now());
+    let id_restore = item.conversation.id.clone();
+    let id_purge = item.conversation.id.clone();
+
+    rsx! {
+        div {
+            class: "group flex items-center gap-2 px-3 py-2 mx-1 rounded-lg hover:bg-white/[0.05] text-[var(--text-secondary)]",
+            div {
+                class: "flex-1 min-w-0",
+                div { class: "truncate text-sm", "{item.conversation.title}" }
+                div {
+                    class: "text-[10px] text-[var(--text-tertiary)]",
+                    if is_en { "{days} days left" } else { "{days} jours restants" }
+                }
+            }
+            button {
+                class: "opacity-0 group-hover:opacity-100 transition-opacity px-2 py-1 text-[11px] rounded-md hover:bg-white/[0.08] text-[var(--text-secondary)] hover:text-[var(--text-primary)]",
+                onclick: move |_| {
+                    if let Err(e) = restore_conversation(&id_restore) {
+                        tracing::error!("Failed to restore conversation: {}", e);
                     }
-                })}
+                    let mut app_state = app_state.clone();
+                    refresh(&mut app_state, &mut trash);
+                },
+                if is_en { "Restore" } else { "Restaurer" }
+            }
+            button {
+                class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
+                title: if is_en { "Delete permanently" } else { "Supprimer definitivement" },
+                onclick: move |_| pending.set(Some(PendingAction::Purge(id_purge.clone()))),
+                svg {
+                    width: "12", height: "12", view_box: "0 0 24 24", fill: "none",
+                    stroke: "currentColor", stroke_width: "2", stroke_linecap: "round", stroke_linejoin: "round",
+                    line { x1: "18", y1: "6", x2: "6", y2: "18" }
+                    line { x1: "6", y1: "6", x2: "18", y2: "18" }
+                }
+            }
+        }
+    }
+}
+
+/// Confirmation modal for destructive conversation actions
+#[component]
+fn ConfirmDialog(
+    action: PendingAction,
+    trash_count: usize,
+    on_cancel: EventHandler<()>,
+    on_confirm: EventHandler<PendingAction>,
+) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+
+    let (title, body, confirm_label) = match &action {
+        PendingAction::Trash(ids) if is_en => (
+            "Move to trash?".to_string(),
+            format!("{} conversation(s) will be kept in the trash for {} days before being deleted.", ids.len(), TRASH_RETENTION_DAYS),
+            "Move to trash",
+        ),
+        PendingAction::Trash(ids) => (
+            "Mettre a la corbeille ?".to_string(),
+            format!("{} conversation(s) seront conservees {} jours dans la corbeille avant suppression.", ids.len(), TRASH_RETENTION_DAYS),
+            "Mettre a la corbeille",
+        ),
+        PendingAction::ClearMessages(_) if is_en => (
+            "Delete all messages?".to_string(),
+            "The conversation is kept but its messages are permanently deleted.".to_string(),
+            "Delete messages",
+        ),
+        PendingAction::ClearMessages(_) => (
+            "Effacer tous les messages ?".to_string(),
+            "La conversation est conservee mais ses messages sont supprimes definitivement.".to_string(),
+            "Effacer",
+        ),
+        PendingAction::Purge(_) if is_en => (
+            "Delete permanently?".to_string(),
+            "This conversation cannot be recovered afterwards.".to_string(),
+            "Delete",
+        ),
+        PendingAction::Purge(_) => (
+            "Supprimer definitivement ?".to_string(),
+            "Cette conversation ne pourra plus etre recuperee.".to_string(),
+            "Supprimer",
+        ),
+        PendingAction::EmptyTrash if is_en => (
+            "Empty the trash?".to_string(),
+            format!("{} conversation(s) will be permanently deleted.", trash_count),
+            "Empty trash",
+        ),
+        PendingAction::EmptyTrash => (
+            "Vider la corbeille ?".to_string(),
+            format!("{} conversation(s) seront supprimees definitivement.", trash_count),
+            "Vider",
+        ),
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/60 backdrop-blur-2xl z-50 flex items-center justify-center p-4",
+            onclick: move |_| on_cancel.call(()),
+            div {
+                class: "w-full max-w-sm glass-strong rounded-2xl overflow-hidden animate-scale-in",
+                onclick: move |evt| evt.stop_propagation(),
+                div {
+                    class: "p-6 space-y-2",
+                    h3 { class: "text-lg font-semibold text-[var(--text-primary)]", "{title}" }
+                    p { class: "text-sm text-[var(--text-secondary)]", "{body}" }
+                }
+                div {
+                    class: "p-6 pt-0 flex gap-3",
+                    button {
+                        class: "btn-ghost flex-1",
+                        onclick: move |_| on_cancel.call(()),
+                        if is_en { "Cancel" } else { "Annuler" }
+                    }
+                    button {
+                        class: "btn-primary flex-1",
+                        onclick: move |_| on_confirm.call(action.clone()),
+                        "{confirm_label}"
+                    }
+                }
             }
         }
     }
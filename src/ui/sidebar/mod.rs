@@ -1,14 +1,16 @@
 pub mod conversation_list;
 pub mod model_picker;
+pub mod profile_switcher;
 
 use crate::app::AppState;
 use crate::storage::conversations::{list_conversations, save_conversation, Conversation};
 use crate::ui::sidebar::conversation_list::ConversationList;
 use crate::ui::sidebar::model_picker::ModelPicker;
+use crate::ui::sidebar::profile_switcher::ProfileSwitcher;
 use dioxus::prelude::*;
 
 #[component]
-pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHandler<()>, on_help_click: EventHandler<MouseEvent>) -> Element {
+pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHandler<()>, on_help_click: EventHandler<MouseEvent>, on_playground_click: EventHandler<MouseEvent>) -> Element {
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
     tracing::debug!("Sidebar rendered");
@@ -67,9 +69,11 @@ pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHa
             // Conversation List
             ConversationList {}
             
-            // Footer: Settings + Help
+            // Footer: Profile + Settings + Help
             div {
                 class: "p-3 border-t border-[var(--border-subtle)]",
+
+                ProfileSwitcher {}
                 
                 // Settings button
                 button {
@@ -101,6 +105,35 @@ pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHa
                     }
                 }
 
+                // Playground button
+                button {
+                    onclick: on_playground_click,
+                    class: "w-full flex items-center gap-3 px-3 py-2.5 text-sm text-[var(--text-secondary)] hover:text-[var(--text-primary)] rounded-xl hover:bg-white/[0.06] transition-all group",
+
+                    div {
+                        class: "p-1.5 rounded-lg bg-white/[0.04] text-[var(--text-tertiary)] group-hover:text-[var(--text-primary)] transition-colors",
+                        svg {
+                            class: "w-4 h-4",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "1.5",
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            path { d: "M9 3h6M10 3v6L4.5 18.5A1.7 1.7 0 0 0 6 21h12a1.7 1.7 0 0 0 1.5-2.5L14 9V3" }
+                        }
+                    }
+                    div {
+                        class: "flex flex-col items-start",
+                        span { class: "font-medium text-[var(--text-primary)] text-sm",
+                            if is_en { "Playground" } else { "Bac a sable" }
+                        }
+                        span { class: "text-[11px] text-[var(--text-tertiary)]",
+                            if is_en { "Try prompts" } else { "Essayer des prompts" }
+                        }
+                    }
+                }
+
                 // Help button
                 button {
                     onclick: on_help_click,
@@ -1,18 +1,21 @@
 use dioxus::prelude::*;
 use crate::app::{AppState, ModelState};
+use crate::inference::InferenceBackend;
 use crate::storage::huggingface::download_model;
-use crate::storage::models::scan_models_directory;
+use crate::storage::models::{scan_models_for_role, ModelRole};
+use crate::storage::model_profiles::{arrange_for_picker, is_favorite, picker_name};
+use crate::storage::model_sources::source_badge;
 use crate::ui::components::loading::Spinner;
 
 
 #[component]
 pub fn ModelPicker() -> Element {
     let app_state = use_context::<AppState>();
-    let models_directory = app_state.settings.read().models_directory.clone();
     
     let mut models = use_signal(Vec::new);
     let mut selected_model_path = use_signal(|| None::<String>);
     let mut dropdown_open = use_signal(|| false);
+    let mut show_all = use_signal(|| false);
     
     // Download dialog state
     let mut show_download_dialog = use_signal(|| false);
@@ -21,11 +24,14 @@ pub fn ModelPicker() -> Element {
     let mut download_error = use_signal(|| None::<String>);
     let mut download_success = use_signal(|| false);
     
-    let models_directory_clone = models_directory.clone();
+    let app_state_for_scan = app_state.clone();
     use_effect(move || {
-        let found_models = scan_models_directory(&models_directory_clone).unwrap_or_default();
+        let settings = app_state_for_scan.settings.peek().clone();
+        let found_models = scan_models_for_role(&settings, ModelRole::Chat);
         if selected_model_path.read().is_none() {
-            if let Some(first_model) = found_models.first() {
+            // Favorites come first
+            let (arranged, _) = arrange_for_picker(&found_models, &settings.model_profiles, None, false);
+            if let Some(first_model) = arranged.first() {
                 let path_str = first_model.path.to_string_lossy().to_string();
                 tracing::debug!("Pre-selecting first model: {}", path_str);
                 selected_model_path.set(Some(path_str));
@@ -38,28 +44,12 @@ pub fn ModelPicker() -> Element {
     let app_state_for_load = app_state.clone();
     let selected_model_path_for_load = selected_model_path.clone();
     let handle_load = move |_| {
-        let mut app_state = app_state_for_load.clone();
-        app_state.model_state.set(ModelState::Loading);
         let path = selected_model_path_for_load
             .read()
             .clone()
             .unwrap_or_default();
-        let gpu_layers = app_state.settings.read().gpu_layers;
-        spawn(async move {
-            let result = {
-                let mut engine = app_state.engine.lock().await;
-                if !engine.is_initialized() {
-                    if let Err(e) = engine.init() {
-                        return app_state.model_state.set(ModelState::Error(e.to_string()));
-                    }
-                }
-                engine.load_model_async(&path, gpu_layers).await
-            };
-            match result {
-                Ok(_info) => app_state.model_state.set(ModelState::Loaded(path)),
-                Err(e) => app_state.model_state.set(ModelState::Error(e.to_string())),
-            }
-        });
+        let gpu_layers = app_state_for_load.settings.read().gpu_layers;
+        app_state_for_load.load_model(path, gpu_layers);
     };
 
     let app_state_for_unload = app_state.clone();
@@ -75,15 +65,12 @@ pub fn ModelPicker() -> Element {
     let app_state_for_refresh = app_state.clone();
     let mut models_for_refresh = models.clone();
     let handle_refresh = move |_| {
-        let models_directory = app_state_for_refresh
-            .settings
-            .read()
-            .models_directory
-            .clone();
-        models_for_refresh.set(scan_models_directory(&models_directory).unwrap_or_default());
+        let settings = app_state_for_refresh.settings.read().clone();
+        models_for_refresh.set(scan_models_for_role(&settings, ModelRole::Chat));
     };
 
     // Download handler
+    let app_state_for_download = app_state.clone();
     let handle_download = move |_| {
         let url = download_url.read().clone();
         if url.is_empty() {
@@ -99,7 +86,7 @@ pub fn ModelPicker() -> Element {
         let mut download_error_inner = download_error.clone();
         let mut download_success_inner = download_success.clone();
         let mut models_inner = models.clone();
-        let models_directory_inner = models_directory.clone();
+        let app_state_inner = app_state_for_download.clone();
         let mut download_url_inner = download_url.clone();
         
         spawn(async move {
@@ -112,7 +99,8 @@ pub fn ModelPicker() -> Element {
                 Ok(path) => {
                     tracing::info!("Downloaded model to: {:?}", path);
                     download_success_inner.set(true);
-                    let found_models = scan_models_directory(&models_directory_inner).unwrap_or_default();
+                    let settings = app_state_inner.settings.read().clone();
+                    let found_models = scan_models_for_role(&settings, ModelRole::Chat);
                     models_inner.set(found_models);
                     download_url_inner.set(String::new());
                 }
@@ -175,8 +163,17 @@ pub fn ModelPicker() -> Element {
                             let sel = selected_model_path.read();
                             let mods = models.read();
                             let fallback = if app_state.settings.read().language == "en" { "Select a model" } else { "Choisir un modele" };
-                            sel.as_ref().and_then(|p| mods.iter().find(|m| m.path.to_string_lossy() == *p).map(|m| m.filename.clone())).unwrap_or_else(|| fallback.to_string())
+                            let profiles = &app_state.settings.read().model_profiles;
+                            sel.as_ref().and_then(|p| mods.iter().find(|m| m.path.to_string_lossy() == *p).map(|m| picker_name(m, profiles))).unwrap_or_else(|| fallback.to_string())
                         };
+                        let is_en = app_state.settings.read().language == "en";
+                        let profiles = app_state.settings.read().model_profiles.clone();
+                        let (listed_models, hidden_count) = arrange_for_picker(
+                            &models.read(),
+                            &profiles,
+                            selected_model_path.read().as_deref(),
+                            show_all(),
+                        );
 
                         rsx! {
                             div {
@@ -216,12 +213,14 @@ pub fn ModelPicker() -> Element {
                                         div {
                                             class: "max-h-48 overflow-y-auto custom-scrollbar py-1",
 
-                                            for model in models.read().iter() {
+                                            for model in listed_models.iter() {
                                                 {
                                                     let path_str = model.path.to_string_lossy().to_string();
                                                     let is_selected = selected_model_path.read().as_ref().map_or(false, |p| *p == path_str);
-                                                    let filename = model.filename.clone();
+                                                    let filename = picker_name(model, &profiles);
+                                                    let favorite = is_favorite(model, &profiles);
                                                     let size = model.size_string();
+                                                    let badge = source_badge(&model.path);
 
                                                     rsx! {
                                                         button {
@@ -244,7 +243,13 @@ pub fn ModelPicker() -> Element {
                                                                 "color: var(--text-primary);"
                                                             },
 
-                                                            span { class: "truncate font-medium", "{filename}" }
+                                                            if favorite {
+                                                                span { class: "flex-shrink-0 mr-1.5 text-xs text-[#fbbf24]", "★" }
+                                                            }
+                                                            span { class: "truncate font-medium", title: "{model.filename}", "{filename}" }
+                                                            if let Some(badge) = badge {
+                                                                span { class: "flex-shrink-0 ml-auto px-1 rounded text-[9px] bg-white/[0.06] text-[var(--text-tertiary)]", "{badge}" }
+                                                            }
                                                             span {
                                                                 class: "flex-shrink-0 text-[10px] font-mono text-[var(--text-tertiary)] ml-2",
                                                                 "{size}"
@@ -254,6 +259,20 @@ pub fn ModelPicker() -> Element {
                                                 }
                                             }
                                         }
+                                        if hidden_count > 0 || show_all() {
+                                            button {
+                                                r#type: "button",
+                                                class: "w-full px-3 py-1.5 border-t border-[var(--border-subtle)] text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-primary)] transition-colors",
+                                                onclick: move |_| show_all.toggle(),
+                                                if show_all() {
+                                                    if is_en { "Hide other quantizations" } else { "Masquer les autres quantifications" }
+                                                } else if is_en {
+                                                    "Show all ({hidden_count} more)"
+                                                } else {
+                                                    "Tout afficher ({hidden_count} de plus)"
+                                                }
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -0,0 +1,201 @@
+use crate::app::AppState;
+use crate::storage::conversations::{list_conversations, set_ephemeral};
+use crate::storage::profiles::{
+    active_profile, create_profile, delete_profile, list_profiles, switch_profile,
+};
+use crate::storage::settings::{load_settings, save_settings, AppSettings};
+use crate::system::certificates::set_custom_certificates;
+use crate::system::proxy::set_proxy_settings;
+use dioxus::prelude::*;
+
+/// Switch profile and reload everything read from its data directory
+fn switch_to(app_state: &mut AppState, id: &str) -> bool {
+    if let Err(e) = switch_profile(id) {
+        tracing::error!("Failed to switch profile: {}", e);
+        return false;
+    }
+    let settings = load_settings();
+    set_ephemeral(settings.lock.enabled);
+    set_proxy_settings(&settings.proxy);
+    set_custom_certificates(&settings.ca_certificates);
+    app_state.settings.set(settings);
+    app_state.current_conversation.set(None);
+    match list_conversations() {
+        Ok(conversations) => app_state.conversations.set(conversations),
+        Err(e) => tracing::error!("Failed to list conversations: {}", e),
+    }
+    app_state.audit_revision += 1;
+    app_state.index_workspace();
+    app_state.watch_folders();
+    true
+}
+
+/// Create a profile and switch to it, carrying over the language, look and
+/// model folders so it starts out usable
+fn create_and_switch(app_state: &mut AppState, name: &str) {
+    let current = app_state.settings.read().clone();
+    let profile = match create_profile(name) {
+        Ok(profile) => profile,
+        Err(e) => return tracing::error!("Failed to create profile: {}", e),
+    };
+    if !switch_to(app_state, &profile.id) {
+        return;
+    }
+    let settings = AppSettings {
+        language: current.language,
+        theme: current.theme,
+        font_size: current.font_size,
+        models_directory: current.models_directory,
+        model_directories: current.model_directories,
+        ..Default::default()
+    };
+    if let Err(e) = save_settings(&settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+    app_state.settings.set(settings);
+}
+
+/// Profile picker in the sidebar footer
+#[component]
+pub fn ProfileSwitcher() -> Element {
+    let app_state = use_context::<AppState>();
+    let (is_en, locked) = {
+        let settings = app_state.settings.read();
+        (settings.language == "en", settings.lock.enabled)
+    };
+    let mut open = use_signal(|| false);
+    let mut new_name = use_signal(String::new);
+    let mut pending_delete = use_signal(|| None::<String>);
+    let mut revision = use_signal(|| 0u32);
+
+    let profiles = use_memo(move || {
+        let _ = revision();
+        list_profiles()
+    });
+    let active = active_profile();
+    let active_label = profiles
+        .read()
+        .iter()
+        .find(|p| p.id == active)
+        .map(|p| p.label(is_en))
+        .unwrap_or_else(|| active.clone());
+    // Switching would leave locked mode; a generation writes to the current profile
+    let disabled = locked || (app_state.is_generating)();
+
+    let mut app_state_create = app_state.clone();
+    let mut create = move || {
+        let name = new_name.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        create_and_switch(&mut app_state_create, &name);
+        new_name.set(String::new());
+        revision += 1;
+        open.set(false);
+    };
+    let mut create_on_enter = create.clone();
+
+    rsx! {
+        div { class: "relative mb-1",
+            button {
+                class: "w-full flex items-center gap-3 px-3 py-2 text-sm text-[var(--text-secondary)] hover:text-[var(--text-primary)] rounded-xl hover:bg-white/[0.06] transition-all disabled:opacity-50 disabled:hover:bg-transparent",
+                disabled,
+                title: if locked {
+                    if is_en { "Unavailable in locked mode" } else { "Indisponible en mode verrouille" }
+                } else if is_en {
+                    "Switch profile"
+                } else {
+                    "Changer de profil"
+                },
+                onclick: move |_| open.toggle(),
+                div { class: "p-1.5 rounded-lg bg-white/[0.04] text-[var(--text-tertiary)]",
+                    svg {
+                        class: "w-4 h-4", view_box: "0 0 24 24", fill: "none",
+                        stroke: "currentColor", stroke_width: "1.5", stroke_linecap: "round", stroke_linejoin: "round",
+                        path { d: "M20 21v-2a4 4 0 0 0-4-4H8a4 4 0 0 0-4 4v2" }
+                        circle { cx: "12", cy: "7", r: "4" }
+                    }
+                }
+                div { class: "flex flex-col items-start min-w-0",
+                    span { class: "font-medium text-[var(--text-primary)] text-sm truncate max-w-[10rem]", "{active_label}" }
+                    span { class: "text-[11px] text-[var(--text-tertiary)]", if is_en { "Profile" } else { "Profil" } }
+                }
+            }
+
+            if open() && !disabled {
+                div { class: "absolute bottom-full left-0 right-0 mb-1 p-1.5 rounded-xl glass-md z-30 space-y-0.5",
+                    for profile in profiles.read().iter().cloned() {
+                        div {
+                            key: "{profile.id}",
+                            class: if profile.id == active {
+                                "group flex items-center gap-2 px-2.5 py-1.5 rounded-lg bg-white/[0.08] text-[var(--text-primary)] text-sm"
+                            } else {
+                                "group flex items-center gap-2 px-2.5 py-1.5 rounded-lg hover:bg-white/[0.05] text-[var(--text-secondary)] hover:text-[var(--text-primary)] text-sm cursor-pointer"
+                            },
+                            onclick: {
+                                let id = profile.id.clone();
+                                let mut app_state = app_state.clone();
+                                move |_| {
+                                    if id != active_profile() && switch_to(&mut app_state, &id) {
+                                        pending_delete.set(None);
+                                        open.set(false);
+                                    }
+                                }
+                            },
+                            span { class: "flex-1 truncate", "{profile.label(is_en)}" }
+                            if !profile.is_default() && profile.id != active {
+                                button {
+                                    class: if pending_delete.read().as_deref() == Some(profile.id.as_str()) {
+                                        "px-1.5 rounded-md text-[11px] text-[var(--text-error)] bg-white/[0.06]"
+                                    } else {
+                                        "opacity-0 group-hover:opacity-100 px-1.5 rounded-md text-[11px] text-[var(--text-tertiary)] hover:text-[var(--text-error)]"
+                                    },
+                                    title: if is_en { "Delete the profile and its data" } else { "Supprimer le profil et ses donnees" },
+                                    onclick: {
+                                        let id = profile.id.clone();
+                                        move |evt: MouseEvent| {
+                                            evt.stop_propagation();
+                                            // Second click confirms
+                                            if pending_delete.read().as_deref() == Some(id.as_str()) {
+                                                if let Err(e) = delete_profile(&id) {
+                                                    tracing::error!("Failed to delete profile: {}", e);
+                                                }
+                                                pending_delete.set(None);
+                                                revision += 1;
+                                            } else {
+                                                pending_delete.set(Some(id.clone()));
+                                            }
+                                        }
+                                    },
+                                    if pending_delete.read().as_deref() == Some(profile.id.as_str()) {
+                                        if is_en { "Delete?" } else { "Supprimer ?" }
+                                    } else {
+                                        "✕"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div { class: "flex items-center gap-1 pt-1 mt-1 border-t border-[var(--border-subtle)]",
+                        input {
+                            class: "flex-1 min-w-0 px-2 py-1 rounded-md text-xs bg-transparent border border-[var(--border-subtle)] text-[var(--text-primary)] focus:outline-none focus:border-[var(--accent-primary)]",
+                            placeholder: if is_en { "New profile" } else { "Nouveau profil" },
+                            value: "{new_name}",
+                            oninput: move |e| new_name.set(e.value()),
+                            onkeydown: move |e: KeyboardEvent| {
+                                if e.key() == Key::Enter {
+                                    create_on_enter();
+                                }
+                            },
+                        }
+                        button {
+                            class: "px-2 py-1 rounded-md text-xs text-[var(--text-secondary)] hover:text-[var(--text-primary)] hover:bg-white/[0.06]",
+                            onclick: move |_| create(),
+                            "+"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}